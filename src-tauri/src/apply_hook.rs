@@ -0,0 +1,161 @@
+//! 壁纸变化钩子
+//!
+//! 设置 `apply_hook` 后，每次桌面壁纸变化都会运行该程序，参数依次为图片路径和标题（自定义图片或
+//! 没有元数据时为空字符串），可用于同步终端配色、更新直播叠加层等。程序在后台运行，超过
+//! [`HOOK_TIMEOUT`] 仍未退出时被终止；失败只记录日志，不影响壁纸设置。
+//!
+//! 运行任意程序有风险，而 settings.json 可能被同步工具或其他程序改写，因此设置界面选择程序时需要
+//! 用户确认，确认结果通过 [`confirm_apply_hook`] 记录在运行时状态中（不随设置同步）。设置中的程序
+//! 与确认过的不一致时不会运行。
+
+use anyhow::{Context, Result};
+use log::{info, warn};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+use crate::{AppState, runtime_state};
+
+/// 钩子程序的最长运行时间
+const HOOK_TIMEOUT: Duration = Duration::from_secs(30);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 运行钩子程序并等待退出，超时后终止
+fn run_hook(program: &Path, image: &Path, title: &str, timeout: Duration) -> Result<ExitStatus> {
+    let mut child = Command::new(program)
+        .arg(image)
+        .arg(title)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to run {}", program.display()))?;
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait().context("Failed to wait for hook")? {
+            return Ok(status);
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            anyhow::bail!("Hook timed out after {}s", timeout.as_secs());
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// 设置中的钩子程序（未设置或未经确认时返回 None）
+async fn confirmed_hook(app: &AppHandle) -> Option<PathBuf> {
+    let hook = app
+        .state::<AppState>()
+        .settings
+        .read()
+        .await
+        .apply_hook
+        .clone()?;
+    let confirmed = runtime_state::load_runtime_state(app)
+        .ok()
+        .and_then(|state| state.apply_hook_confirmed);
+    if confirmed.as_deref() != Some(hook.as_str()) {
+        warn!(target: "apply_hook", "钩子程序未经确认，跳过: {}", hook);
+        return None;
+    }
+    Some(PathBuf::from(hook))
+}
+
+/// 壁纸变化后在后台运行钩子程序（未设置时不做任何事）
+pub(crate) async fn spawn(app: &AppHandle, image: &Path, title: Option<&str>) {
+    let Some(program) = confirmed_hook(app).await else {
+        return;
+    };
+    let image = image.to_path_buf();
+    let title = title.unwrap_or_default().to_string();
+    tauri::async_runtime::spawn_blocking(move || {
+        match run_hook(&program, &image, &title, HOOK_TIMEOUT) {
+            Ok(status) if status.success() => {
+                info!(target: "apply_hook", "钩子程序已运行: {}", program.display());
+            }
+            Ok(status) => {
+                warn!(target: "apply_hook", "钩子程序退出码异常 ({}): {}", status, program.display());
+            }
+            Err(e) => warn!(target: "apply_hook", "运行钩子程序失败: {:#}", e),
+        }
+    });
+}
+
+/// 记录用户确认过的钩子程序（设置界面选择程序并确认后调用）
+///
+/// 路径不是绝对路径或不是文件时返回 "INVALID_PATH"。
+#[tauri::command]
+pub(crate) async fn confirm_apply_hook(path: String, app: AppHandle) -> Result<(), String> {
+    let program = Path::new(&path);
+    if !program.is_absolute() || !program.is_file() {
+        return Err("INVALID_PATH".to_string());
+    }
+    runtime_state::modify_runtime_state(&app, |state| {
+        state.apply_hook_confirmed = Some(path.clone());
+        true
+    })
+    .map_err(|e| e.to_string())?;
+    info!(target: "apply_hook", "用户已确认钩子程序: {}", path);
+    Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use std::time::SystemTime;
+
+    fn write_script(name: &str, body: &str) -> PathBuf {
+        let unique = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let path = std::env::temp_dir().join(format!("bw_hook_{name}_{unique}.sh"));
+        std::fs::write(&path, format!("#!/bin/sh\n{body}\n")).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_run_hook_passes_path_and_title() {
+        let script = write_script(
+            "args",
+            r#"[ "$1" = "/tmp/20240101.jpg" ] && [ "$2" = "Snow Peak" ] && exit 0; exit 3"#,
+        );
+        let status = run_hook(
+            &script,
+            Path::new("/tmp/20240101.jpg"),
+            "Snow Peak",
+            Duration::from_secs(10),
+        )
+        .unwrap();
+        assert!(status.success());
+        let status = run_hook(
+            &script,
+            Path::new("/tmp/other.jpg"),
+            "",
+            Duration::from_secs(10),
+        )
+        .unwrap();
+        assert_eq!(status.code(), Some(3));
+        std::fs::remove_file(&script).unwrap();
+    }
+
+    #[test]
+    fn test_run_hook_kills_after_timeout() {
+        let script = write_script("slow", "sleep 5");
+        let started = Instant::now();
+        let result = run_hook(
+            &script,
+            Path::new("/tmp/x.jpg"),
+            "",
+            Duration::from_millis(200),
+        );
+        assert!(result.is_err());
+        assert!(started.elapsed() < Duration::from_secs(4));
+        std::fs::remove_file(&script).unwrap();
+    }
+}
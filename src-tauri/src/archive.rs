@@ -0,0 +1,337 @@
+//! 第三方 Bing 历史归档回填
+//!
+//! Bing 官方 API 只能获取最近 8 天左右的壁纸。归档镜像按"国家/语言/年份"
+//! 提供完整的历史 JSON，可用于按需回填更早日期的元数据；图片仍沿用按需下载逻辑。
+//!
+//! 由于依赖第三方服务，回填受 `AppSettings::archive_backfill_enabled` 控制，默认关闭。
+
+use anyhow::{Context, Result};
+use chrono::{Datelike, NaiveDate};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::models::LocalWallpaper;
+use crate::{
+    AppState, command_guard, directory_permission, events, get_effective_mkt, http_client,
+    network_gate, storage, utils,
+};
+
+/// 归档镜像地址
+const ARCHIVE_BASE_URL: &str = "https://bing.npanuhin.me";
+
+/// 单次回填允许的最大天数，避免一次性拉取过多年份数据
+const MAX_BACKFILL_DAYS: i64 = 366;
+
+/// 归档 JSON 中的单条记录（只解析需要的字段）
+#[derive(Debug, Deserialize)]
+struct ArchiveEntry {
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    copyright: Option<String>,
+    /// 格式：YYYY-MM-DD
+    date: String,
+    /// 原始 Bing 图片地址，如 `https://www.bing.com/th?id=OHR.Name_EN-US123_UHD.jpg`
+    #[serde(default)]
+    bing_url: Option<String>,
+}
+
+/// 回填结果统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct BackfillResult {
+    /// 归档中落在日期范围内的条目数
+    fetched: usize,
+    /// 实际新增到索引的条目数
+    added: usize,
+}
+
+/// 将 mkt 转换为归档的目录结构：`en-US` -> (`US`, `en`)
+fn archive_market_path(mkt: &str) -> Option<(String, String)> {
+    let (lang, country) = mkt.split_once('-')?;
+    if lang.is_empty() || country.is_empty() {
+        return None;
+    }
+    Some((country.to_uppercase(), lang.to_lowercase()))
+}
+
+/// 某个 mkt 某一年的归档 JSON 地址
+fn archive_year_url(mkt: &str, year: i32) -> Option<String> {
+    let (country, lang) = archive_market_path(mkt)?;
+    Some(format!("{ARCHIVE_BASE_URL}/{country}/{lang}.{year}.json"))
+}
+
+/// 从完整的 Bing 图片地址中还原 urlbase
+///
+/// `https://www.bing.com/th?id=OHR.Name_EN-US123_UHD.jpg` -> `/th?id=OHR.Name_EN-US123`
+fn urlbase_from_bing_url(bing_url: &str) -> Option<String> {
+    let start = bing_url.find("/th?id=")?;
+    let path = &bing_url[start..];
+    let path = path.split('&').next().unwrap_or(path);
+    let stem = path.strip_suffix(".jpg")?;
+    let (urlbase, _resolution) = stem.rsplit_once('_')?;
+    // 去掉分辨率后仍需保留 `OHR.Name_MKT...` 结构
+    urlbase.contains('_').then(|| urlbase.to_string())
+}
+
+/// 解析 YYYYMMDD 格式的日期
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.trim(), "%Y%m%d").ok()
+}
+
+/// 校验并解析回填日期范围
+fn parse_date_range(from_date: &str, to_date: &str) -> Result<(NaiveDate, NaiveDate), String> {
+    let from = parse_date(from_date).ok_or_else(|| "INVALID_DATE_RANGE".to_string())?;
+    let to = parse_date(to_date).ok_or_else(|| "INVALID_DATE_RANGE".to_string())?;
+    if from > to || (to - from).num_days() >= MAX_BACKFILL_DAYS {
+        return Err("INVALID_DATE_RANGE".to_string());
+    }
+    Ok((from, to))
+}
+
+/// 将归档条目转换为本地壁纸元数据
+///
+/// 缺少 bing_url 的条目无法按需下载图片，直接跳过。
+fn entry_to_wallpaper(entry: ArchiveEntry) -> Option<LocalWallpaper> {
+    let date = NaiveDate::parse_from_str(&entry.date, "%Y-%m-%d").ok()?;
+    let urlbase = urlbase_from_bing_url(entry.bing_url.as_deref()?)?;
+    Some(LocalWallpaper {
+        title: entry.title.unwrap_or_default(),
+        copyright: entry.copyright.unwrap_or_default(),
+        copyright_link: String::new(),
+        end_date: date.format("%Y%m%d").to_string(),
+        urlbase,
+        resolution: None,
+        portrait_available: None,
+        watermark_free: None,
+        recompression: None,
+    })
+}
+
+/// 从归档获取指定日期范围内的壁纸元数据
+/// 只保留索引中还没有的日期
+///
+/// 归档镜像的标题、版权文本与 Bing 不完全一致且缺少 copyright_link，
+/// 不能覆盖更新循环已经保存的官方元数据。
+fn missing_from_index(
+    wallpapers: Vec<LocalWallpaper>,
+    existing: &[LocalWallpaper],
+) -> Vec<LocalWallpaper> {
+    let known: std::collections::HashSet<&str> =
+        existing.iter().map(|w| w.end_date.as_str()).collect();
+    wallpapers
+        .into_iter()
+        .filter(|w| !known.contains(w.end_date.as_str()))
+        .collect()
+}
+
+async fn fetch_archive_wallpapers(
+    mkt: &str,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<LocalWallpaper>> {
+    let mut wallpapers = Vec::new();
+
+    for year in from.year()..=to.year() {
+        let url = archive_year_url(mkt, year)
+            .with_context(|| format!("Unsupported mkt for archive: {mkt}"))?;
+        info!(target: "archive", "请求历史归档: mkt={}, year={}, url={}", mkt, year, url);
+
+        let response = http_client::send(http_client::builder().build()?.get(&url))
+            .await
+            .context("Failed to fetch archive")?;
+        if !response.status().is_success() {
+            anyhow::bail!("Archive returned HTTP {} for {}", response.status(), url);
+        }
+        let entries: Vec<ArchiveEntry> = response
+            .json()
+            .await
+            .context("Failed to parse archive response")?;
+
+        wallpapers.extend(
+            entries
+                .into_iter()
+                .filter_map(entry_to_wallpaper)
+                .filter(|w| parse_date(&w.end_date).is_some_and(|date| date >= from && date <= to)),
+        );
+    }
+
+    Ok(wallpapers)
+}
+
+/// 从第三方归档回填历史壁纸元数据
+///
+/// 日期格式为 YYYYMMDD（含首尾），单次最多回填一年。
+/// 只写入元数据，图片在用户浏览或设置时按需下载。执行中或调用过于频繁时返回 "BUSY"。
+#[tauri::command]
+pub(crate) async fn backfill_archive(
+    from_date: String,
+    to_date: String,
+    state: tauri::State<'_, AppState>,
+    app: tauri::AppHandle,
+) -> Result<BackfillResult, String> {
+    command_guard::guarded(
+        command_guard::ARCHIVE_BACKFILL,
+        run_backfill(&from_date, &to_date, &state, &app),
+    )
+    .await
+}
+
+async fn run_backfill(
+    from_date: &str,
+    to_date: &str,
+    state: &AppState,
+    app: &tauri::AppHandle,
+) -> Result<BackfillResult, String> {
+    if !state.settings.read().await.archive_backfill_enabled {
+        return Err("ARCHIVE_DISABLED".to_string());
+    }
+    let (from, to) = parse_date_range(from_date, to_date)?;
+    if directory_permission::archive_read_only(app).await {
+        return Err(directory_permission::READ_ONLY_DIRECTORY.to_string());
+    }
+
+    let mkt = get_effective_mkt(state).await;
+    if !utils::is_valid_mkt(&mkt) {
+        return Err("UNSUPPORTED_MKT".to_string());
+    }
+
+    let wallpapers = fetch_archive_wallpapers(&mkt, from, to)
+        .await
+        .map_err(|e| {
+            if http_client::is_restricted(&e) {
+                return network_gate::NETWORK_RESTRICTED.to_string();
+            }
+            warn!(target: "archive", "获取历史归档失败: {}", e);
+            format!("Failed to fetch archive: {}", e)
+        })?;
+    let fetched = wallpapers.len();
+
+    let wallpaper_dir = state.wallpaper_directory.lock().await.clone();
+    let existing = storage::get_local_wallpapers(&wallpaper_dir, &mkt)
+        .await
+        .map_err(|e| format!("Failed to load index: {}", e))?;
+    let wallpapers = missing_from_index(wallpapers, &existing);
+    let saved = storage::save_wallpapers_metadata(wallpapers, &wallpaper_dir, &mkt)
+        .await
+        .map_err(|e| format!("Failed to save archive metadata: {}", e))?;
+
+    info!(
+        target: "archive",
+        "历史归档回填完成: mkt={}, 范围={}~{}, 获取 {} 条, 新增 {} 条",
+        mkt, from_date, to_date, fetched, saved.new_count
+    );
+
+    if saved.new_count > 0
+        && let Err(e) = events::WALLPAPER_UPDATED.emit(app, &())
+    {
+        warn!(target: "archive", "通知前端失败: {}", e);
+    }
+
+    Ok(BackfillResult {
+        fetched,
+        added: saved.new_count,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_archive_year_url() {
+        assert_eq!(
+            archive_year_url("en-US", 2023).as_deref(),
+            Some("https://bing.npanuhin.me/US/en.2023.json")
+        );
+        assert_eq!(
+            archive_year_url("zh-CN", 2020).as_deref(),
+            Some("https://bing.npanuhin.me/CN/zh.2020.json")
+        );
+        assert_eq!(archive_year_url("invalid", 2020), None);
+    }
+
+    #[test]
+    fn test_urlbase_from_bing_url() {
+        assert_eq!(
+            urlbase_from_bing_url(
+                "https://www.bing.com/th?id=OHR.GreatWall_ZH-CN1234567890_UHD.jpg"
+            )
+            .as_deref(),
+            Some("/th?id=OHR.GreatWall_ZH-CN1234567890")
+        );
+        assert_eq!(
+            urlbase_from_bing_url(
+                "https://www.bing.com/th?id=OHR.Some_Name_EN-US123_1920x1080.jpg&rf=x"
+            )
+            .as_deref(),
+            Some("/th?id=OHR.Some_Name_EN-US123")
+        );
+        assert_eq!(urlbase_from_bing_url("https://example.com/image.jpg"), None);
+        assert_eq!(
+            urlbase_from_bing_url("https://www.bing.com/th?id=OHR.NoRes.jpg"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_date_range() {
+        let (from, to) = parse_date_range("20200101", "20200131").unwrap();
+        assert_eq!(from, NaiveDate::from_ymd_opt(2020, 1, 1).unwrap());
+        assert_eq!(to, NaiveDate::from_ymd_opt(2020, 1, 31).unwrap());
+
+        assert!(parse_date_range("20200201", "20200101").is_err());
+        assert!(parse_date_range("2020-01-01", "20200131").is_err());
+        assert!(parse_date_range("20180101", "20200101").is_err());
+    }
+
+    #[test]
+    fn test_entry_to_wallpaper() {
+        let entry: ArchiveEntry = serde_json::from_str(
+            r#"{
+                "title": "Great Wall",
+                "caption": "ignored",
+                "copyright": "© Someone",
+                "date": "2020-01-02",
+                "bing_url": "https://www.bing.com/th?id=OHR.GreatWall_EN-US123_UHD.jpg",
+                "url": "https://mirror.example/image.jpg"
+            }"#,
+        )
+        .unwrap();
+
+        let wallpaper = entry_to_wallpaper(entry).unwrap();
+        assert_eq!(wallpaper.end_date, "20200102");
+        assert_eq!(wallpaper.title, "Great Wall");
+        assert_eq!(wallpaper.urlbase, "/th?id=OHR.GreatWall_EN-US123");
+    }
+
+    #[test]
+    fn test_missing_from_index_keeps_existing_metadata() {
+        let wallpaper = |end_date: &str, title: &str| LocalWallpaper {
+            title: title.to_string(),
+            copyright: String::new(),
+            copyright_link: String::new(),
+            end_date: end_date.to_string(),
+            urlbase: String::new(),
+            resolution: None,
+            portrait_available: None,
+            watermark_free: None,
+            recompression: None,
+        };
+        let existing = vec![wallpaper("20200102", "Bing title")];
+        let fetched = vec![
+            wallpaper("20200102", "Mirror title"),
+            wallpaper("20200101", "Mirror title"),
+        ];
+
+        let missing = missing_from_index(fetched, &existing);
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].end_date, "20200101");
+    }
+
+    #[test]
+    fn test_entry_without_bing_url_is_skipped() {
+        let entry: ArchiveEntry =
+            serde_json::from_str(r#"{"title": "No URL", "date": "2020-01-02"}"#).unwrap();
+        assert!(entry_to_wallpaper(entry).is_none());
+    }
+}
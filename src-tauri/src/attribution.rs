@@ -0,0 +1,176 @@
+//! "这是哪里？"壁纸信息浮窗
+//!
+//! 在托盘附近弹出一个无边框小窗口，显示当前壁纸的标题、从版权信息中解析出的地点和摄影师，
+//! 以及打开详情链接的按钮。窗口由后端直接创建（页面为静态的 `attribution.html`，
+//! 数据通过初始化脚本注入），主窗口关闭时同样可用；失去焦点或超时后自动关闭。
+
+use log::{info, warn};
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder, WindowEvent};
+use tauri_plugin_opener::OpenerExt;
+
+use crate::{AppState, get_effective_mkt, storage};
+
+const OVERLAY_LABEL: &str = "attribution";
+const OVERLAY_PAGE: &str = "attribution.html";
+const OVERLAY_SIZE: (f64, f64) = (360.0, 128.0);
+/// 浮窗与屏幕工作区边缘的间距（逻辑像素）
+const OVERLAY_MARGIN: f64 = 12.0;
+/// 未交互时自动关闭的时间
+const AUTO_HIDE_AFTER: Duration = Duration::from_secs(10);
+
+/// 每次打开浮窗递增，避免旧的自动关闭计时器关掉新打开的浮窗
+static OVERLAY_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// 注入浮窗页面的数据（`window.__ATTRIBUTION__`）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Attribution {
+    lang: String,
+    title: String,
+    location: String,
+    credit: Option<String>,
+    link: Option<String>,
+    link_text: String,
+}
+
+fn link_text(language: &str) -> &'static str {
+    if language == "zh-CN" {
+        "了解更多"
+    } else {
+        "Learn more"
+    }
+}
+
+/// 读取当前壁纸的信息（优先使用当前已应用的壁纸，否则使用最新一张）
+async fn load_current_attribution(app: &AppHandle) -> Option<Attribution> {
+    let state = app.state::<AppState>();
+    let wallpaper_dir = state.wallpaper_directory.lock().await.clone();
+    let language = state.settings.read().await.resolved_language;
+    let current_end_date = state
+        .current_wallpaper_path
+        .lock()
+        .await
+        .as_ref()
+        .and_then(|path| path.file_stem())
+        .and_then(|stem| stem.to_str())
+        .map(str::to_string);
+    let mkt = get_effective_mkt(&state).await;
+
+    let wallpapers = match storage::get_local_wallpapers(&wallpaper_dir, &mkt).await {
+        Ok(wallpapers) => wallpapers,
+        Err(e) => {
+            warn!(target: "attribution", "读取壁纸元数据失败: {}", e);
+            return None;
+        }
+    };
+    let wallpaper = current_end_date
+        .and_then(|end_date| wallpapers.iter().find(|w| w.end_date == end_date))
+        .or_else(|| wallpapers.first())?;
+
+    let info = wallpaper.copyright_info();
+    let link = wallpaper.copyright_link.trim();
+    let link =
+        (link.starts_with("https://") || link.starts_with("http://")).then(|| link.to_string());
+
+    Some(Attribution {
+        link_text: link_text(&language).to_string(),
+        lang: language,
+        title: wallpaper.title.clone(),
+        credit: info.credit(),
+        location: info.location,
+        link,
+    })
+}
+
+/// 计算浮窗位置：macOS 在菜单栏下方右上角，其他平台在任务栏上方右下角
+fn overlay_position(app: &AppHandle) -> Option<(f64, f64)> {
+    let monitor = app.primary_monitor().ok().flatten()?;
+    let scale = monitor.scale_factor();
+    let area = monitor.work_area();
+    let (x, y) = (
+        f64::from(area.position.x) / scale,
+        f64::from(area.position.y) / scale,
+    );
+    let (width, height) = (
+        f64::from(area.size.width) / scale,
+        f64::from(area.size.height) / scale,
+    );
+
+    let left = x + width - OVERLAY_SIZE.0 - OVERLAY_MARGIN;
+    let top = if cfg!(target_os = "macos") {
+        y + OVERLAY_MARGIN
+    } else {
+        y + height - OVERLAY_SIZE.1 - OVERLAY_MARGIN
+    };
+    Some((left, top))
+}
+
+fn close_overlay(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(OVERLAY_LABEL) {
+        let _ = window.close();
+    }
+}
+
+/// 打开壁纸信息浮窗（已打开时先关闭旧窗口）
+pub(crate) async fn show_overlay(app: &AppHandle) -> Result<(), String> {
+    let attribution = load_current_attribution(app)
+        .await
+        .ok_or_else(|| "NO_CURRENT_WALLPAPER".to_string())?;
+    close_overlay(app);
+
+    let data = serde_json::to_string(&attribution).map_err(|e| e.to_string())?;
+    let opener_app = app.clone();
+    let mut builder =
+        WebviewWindowBuilder::new(app, OVERLAY_LABEL, WebviewUrl::App(OVERLAY_PAGE.into()))
+            .title(attribution.title.as_str())
+            .inner_size(OVERLAY_SIZE.0, OVERLAY_SIZE.1)
+            .decorations(false)
+            .resizable(false)
+            .always_on_top(true)
+            .skip_taskbar(true)
+            .focused(true)
+            .initialization_script(format!("window.__ATTRIBUTION__ = {data};"))
+            .on_navigation(move |url| {
+                // 浮窗内只加载自身页面，外部链接交给系统浏览器打开
+                if !matches!(url.scheme(), "http" | "https") || url.path().ends_with(OVERLAY_PAGE) {
+                    return true;
+                }
+                if let Err(e) = opener_app.opener().open_url(url.as_str(), None::<&str>) {
+                    warn!(target: "attribution", "打开详情链接失败: {}", e);
+                }
+                close_overlay(&opener_app);
+                false
+            });
+    if let Some((x, y)) = overlay_position(app) {
+        builder = builder.position(x, y);
+    }
+
+    let window = builder.build().map_err(|e| e.to_string())?;
+    let close_handle = app.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::Focused(false) = event {
+            close_overlay(&close_handle);
+        }
+    });
+
+    let generation = OVERLAY_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let timer_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(AUTO_HIDE_AFTER).await;
+        if OVERLAY_GENERATION.load(Ordering::SeqCst) == generation {
+            close_overlay(&timer_handle);
+        }
+    });
+
+    info!(target: "attribution", "显示壁纸信息浮窗: {}", attribution.title);
+    Ok(())
+}
+
+/// 显示当前壁纸的"这是哪里？"浮窗
+#[tauri::command]
+pub(crate) async fn show_attribution_overlay(app: AppHandle) -> Result<(), String> {
+    show_overlay(&app).await
+}
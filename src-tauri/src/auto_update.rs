@@ -1,12 +1,22 @@
-use crate::AppState;
-use crate::update_cycle;
-use chrono::{Duration as ChronoDuration, Local, TimeZone, Timelike};
+use crate::publish_schedule::{self, PublishWindow};
+use crate::{AppState, get_effective_mkt, update_cycle};
+use chrono::{
+    DateTime, Duration as ChronoDuration, Local, LocalResult, NaiveDateTime, NaiveTime, TimeZone,
+    Utc,
+};
 use log::{error, info, warn};
 use std::time::Duration;
 use tauri::{AppHandle, Manager};
 
+/// 调度器中自动更新任务的名称
+const AUTO_UPDATE_JOB: &str = "auto_update";
+
 /// 一小时（秒）。
 const HOUR_SECS: u64 = 3600;
+/// 发布窗口内的检查间隔（秒）
+const WINDOW_POLL_SECS: u64 = 15 * 60;
+/// 发布窗口外的最长检查间隔（秒）
+const SPARSE_POLL_SECS: u64 = 3 * HOUR_SECS;
 
 /// 计算下一次自动更新循环之前的睡眠时长。
 ///
@@ -18,11 +28,15 @@ const HOUR_SECS: u64 = 3600;
 /// - 第 4-6 次失败：每 30 分钟一次；
 /// - 之后：回退到 60 分钟，与普通整点巡询一致，避免无限制写日志。
 ///
+/// 已学到当前 mkt 的典型发布时间时（见 `publish_schedule`），追赶模式改为只在发布窗口内
+/// 每 15 分钟检查，窗口外最多每 3 小时检查一次，且在窗口开始时醒来。
+///
 /// 抽出为纯函数以便单元测试覆盖各档位逻辑。
 fn compute_sleep_duration(
     until_midnight: ChronoDuration,
     needs_catchup: bool,
     consecutive_today_failures: u32,
+    publish_window: PublishWindow,
 ) -> Duration {
     let normal = if let Ok(rem) = until_midnight.to_std() {
         if rem <= Duration::from_secs(HOUR_SECS) {
@@ -38,220 +52,410 @@ fn compute_sleep_duration(
         return normal;
     }
 
-    let catchup_secs: u64 = match consecutive_today_failures {
-        0..=2 => 15 * 60,
-        3..=5 => 30 * 60,
-        _ => HOUR_SECS,
+    let catchup_secs: u64 = match publish_window {
+        PublishWindow::Inside => WINDOW_POLL_SECS,
+        // 窗口外不受整点巡询限制，只对齐零点
+        PublishWindow::Outside(until_window) => {
+            let sparse = until_window.min(Duration::from_secs(SPARSE_POLL_SECS));
+            return until_midnight
+                .to_std()
+                .map_or(normal, |until_midnight| sparse.min(until_midnight));
+        }
+        PublishWindow::Unknown => match consecutive_today_failures {
+            0..=2 => 15 * 60,
+            3..=5 => 30 * 60,
+            _ => HOUR_SECS,
+        },
     };
     normal.min(Duration::from_secs(catchup_secs))
 }
 
+/// 零点重试的最大次数
+const MAX_MIDNIGHT_RETRIES: u32 = 10;
+/// 零点重试的最大退避（秒）
+const MAX_BACKOFF_SECS: u64 = 60;
+/// 每日对齐更新的本地时刻（零点后 5 分钟）
+const MIDNIGHT_WAKEUP_TIME: NaiveTime = match NaiveTime::from_hms_opt(0, 5, 0) {
+    Some(time) => time,
+    None => panic!("invalid wakeup time"),
+};
+
+/// 夏令时切换可能跳过的最长本地时间（个别时区曾整天跳过），超过后放弃查找
+const MAX_LOCAL_GAP_MINUTES: i64 = 25 * 60;
+
+/// 本地时间 `naive` 处（或其后）第一个实际存在的本地时刻
+///
+/// 夏令时开始（时钟拨快）时 `naive` 可能落在被跳过的区间内，此时按分钟向后查找，
+/// 得到切换后的第一个有效时刻；夏令时结束（时钟拨回）时保留两个候选，由调用方选择。
+fn first_valid_local<Tz: TimeZone>(tz: &Tz, naive: NaiveDateTime) -> LocalResult<DateTime<Tz>> {
+    for minutes in 0..=MAX_LOCAL_GAP_MINUTES {
+        match tz.from_local_datetime(&(naive + ChronoDuration::minutes(minutes))) {
+            LocalResult::None => continue,
+            valid => return valid,
+        }
+    }
+    LocalResult::None
+}
+
+/// 计算下一次零点对齐更新的时刻（次日 00:05，留 5 分钟缓冲等待 Bing 发布）
+///
+/// 00:05 因夏令时不存在时取其后最早的有效时刻；出现两次时取晚于 `now` 的较早一次。
+fn next_midnight_wakeup<Tz: TimeZone>(now: DateTime<Tz>) -> DateTime<Tz> {
+    let today = now.date_naive();
+    // 安全处理日期计算，提供 fallback 避免 panic
+    let tomorrow = today.succ_opt().unwrap_or_else(|| {
+        warn!(target: "auto_update", "日期计算失败，使用默认值（明天）");
+        today + ChronoDuration::days(1)
+    });
+    let naive_next = tomorrow.and_time(MIDNIGHT_WAKEUP_TIME);
+    match first_valid_local(&now.timezone(), naive_next) {
+        LocalResult::Single(wakeup) => wakeup,
+        LocalResult::Ambiguous(earliest, latest) => {
+            if earliest > now {
+                earliest
+            } else {
+                latest
+            }
+        }
+        LocalResult::None => {
+            warn!(target: "auto_update", "无法创建本地时间，使用当前时间 + 1小时");
+            now + ChronoDuration::hours(1)
+        }
+    }
+}
+
+/// 是否处于零点窗口（00:00~00:05），该窗口内执行每日对齐更新并在失败时快速重试
+///
+/// 窗口的起止按当日实际存在的本地时刻计算：零点因夏令时被跳过时，窗口从切换后的
+/// 第一个时刻开始；时刻出现两次时以第一次为准。
+fn is_in_midnight_window<Tz: TimeZone>(now: DateTime<Tz>) -> bool {
+    let tz = now.timezone();
+    let today = now.date_naive();
+    let (Some(start), Some(end)) = (
+        first_valid_local(&tz, today.and_time(NaiveTime::MIN)).earliest(),
+        first_valid_local(&tz, today.and_time(MIDNIGHT_WAKEUP_TIME)).earliest(),
+    ) else {
+        return false;
+    };
+    // 对齐到分钟：00:05:59 仍属于窗口
+    start <= now && now < end + ChronoDuration::minutes(1)
+}
+
+/// 零点重试第 `attempt` 次（从 0 开始）前的退避时长：1, 2, 4, ... 秒，最多 60 秒
+fn midnight_retry_backoff(attempt: u32) -> Duration {
+    let base_backoff = 1u64.checked_shl(attempt).unwrap_or(u64::MAX);
+    Duration::from_secs(base_backoff.min(MAX_BACKOFF_SECS))
+}
+
+/// 当前 mkt 相对其典型发布时间窗口的位置
+async fn current_publish_window(app: &AppHandle, now: DateTime<Local>) -> PublishWindow {
+    let mkt = get_effective_mkt(&app.state::<AppState>()).await;
+    publish_schedule::window(
+        now.with_timezone(&Utc),
+        publish_schedule::typical_minute_for(app, &mkt),
+    )
+}
+
 /// 启动自动更新任务（响应设置变更，可取消）
 pub(crate) fn start_auto_update_task(app: AppHandle) {
     let state = app.state::<AppState>();
-    let mut rx = state.settings_rx.clone();
-
-    // 如已有旧任务，先取消（不需要获取 runtime handle）
-    tauri::async_runtime::block_on(async {
-        let mut h = state.auto_update_handle.lock().await;
-        h.abort();
-        let app_clone = app.clone();
-        let new_handle = tauri::async_runtime::spawn(async move {
-            // 初始立即执行一次更新（强制更新，确保首次启动时能获取数据）
-            // 检查索引是否为空，如果为空则强制更新
-            update_cycle::check_and_trigger_update_if_needed(&app_clone).await;
-
-            // 标记是否是第一次收到设置变更（启动时的初始化不算）
-            let mut is_first_change = true;
-            // 当日壁纸尚未获取成功时的连续失败次数（追赶模式退避档位用）
-            let mut consecutive_today_failures: u32 = 0;
-
-            // 小时循环 + 零点对齐 + 失败追赶
-            loop {
-                // 计算距下一次本地零点（含 5 分钟缓冲）剩余时间
-                let now = Local::now();
-                let today = now.date_naive();
-                // 安全处理日期计算，提供 fallback 避免 panic
-                let tomorrow = today.succ_opt().unwrap_or_else(|| {
-                    warn!(target: "auto_update", "日期计算失败，使用默认值（明天）");
-                    today + ChronoDuration::days(1)
-                });
-                let naive_next = tomorrow.and_hms_opt(0, 5, 0).unwrap_or_else(|| {
-                    warn!(target: "auto_update", "时间创建失败，使用默认值（00:00:00）");
-                    tomorrow.and_hms_opt(0, 0, 0).unwrap_or_else(|| {
-                        warn!(target: "auto_update", "无法创建默认时间，使用当前日期时间");
-                        now.naive_local()
-                    })
-                });
-                let next_midnight = Local
-                    .from_local_datetime(&naive_next)
-                    .single()
-                    .unwrap_or_else(|| {
-                        warn!(target: "auto_update", "时区转换失败，使用首个匹配时间");
-                        Local
-                            .from_local_datetime(&naive_next)
-                            .earliest()
-                            .unwrap_or_else(|| {
-                                warn!(target: "auto_update", "无法创建本地时间，使用当前时间 + 1小时");
-                                now + ChronoDuration::hours(1)
-                            })
-                    });
-                let until_midnight = next_midnight - now;
-
-                // 检查"今日壁纸是否已成功获取"
-                let needs_catchup = {
-                    let state_ref = app_clone.state::<AppState>();
-                    let guard = state_ref.last_update_time.lock().await;
-                    guard.map(|dt| dt.date_naive()) != Some(today)
-                };
-                if !needs_catchup {
-                    consecutive_today_failures = 0;
-                }
+    let mut rx = state.settings.subscribe();
+    let clock = state.clock.clone();
+
+    // 同名任务重新注册时，调度器会先取消旧任务
+    let app_clone = app.clone();
+    state.scheduler.spawn(AUTO_UPDATE_JOB, async move {
+        // 初始立即执行一次更新（强制更新，确保首次启动时能获取数据）
+        // 检查索引是否为空，如果为空则强制更新
+        update_cycle::check_and_trigger_update_if_needed(&app_clone).await;
+
+        // 标记是否是第一次收到设置变更（启动时的初始化不算）
+        let mut is_first_change = true;
+        // 当日壁纸尚未获取成功时的连续失败次数（追赶模式退避档位用）
+        let mut consecutive_today_failures: u32 = 0;
 
-                let sleep_dur = compute_sleep_duration(
-                    until_midnight,
-                    needs_catchup,
+        // 小时循环 + 零点对齐 + 失败追赶
+        loop {
+            // 计算距下一次本地零点（含 5 分钟缓冲）剩余时间
+            let now = clock.now();
+            let today = now.date_naive();
+            let next_midnight = next_midnight_wakeup(now);
+            let until_midnight = next_midnight - now;
+
+            // 检查"今日壁纸是否已成功获取"
+            let needs_catchup = {
+                let state_ref = app_clone.state::<AppState>();
+                let guard = state_ref.last_update_time.lock().await;
+                guard.map(|dt| dt.date_naive()) != Some(today)
+            };
+            if !needs_catchup {
+                consecutive_today_failures = 0;
+            }
+            let publish_window = if needs_catchup {
+                current_publish_window(&app_clone, now).await
+            } else {
+                PublishWindow::Unknown
+            };
+
+            let sleep_dur = compute_sleep_duration(
+                until_midnight,
+                needs_catchup,
+                consecutive_today_failures,
+                publish_window,
+            );
+
+            if needs_catchup {
+                info!(
+                    target: "auto_update",
+                    "今日壁纸尚未获取成功（连续失败 {} 次，发布窗口 {:?}），追赶模式：{}s 后重试",
                     consecutive_today_failures,
+                    publish_window,
+                    sleep_dur.as_secs()
                 );
+            }
 
-                if needs_catchup {
-                    info!(
-                        target: "auto_update",
-                        "今日壁纸尚未获取成功（连续失败 {} 次），追赶模式：{}s 后重试",
-                        consecutive_today_failures,
-                        sleep_dur.as_secs()
-                    );
-                }
+            tokio::select! {
+                _ = tokio::time::sleep(sleep_dur) => {
+                    let after_sleep_now = clock.now();
+                    // 零点窗口（00:00~00:05）内执行每日对齐更新，并在失败时快速重试
+                    if is_in_midnight_window(after_sleep_now) {
+                        // 记录更新前的日期
+                        update_cycle::run_update_cycle(&app_clone).await;
+                        let today = after_sleep_now.date_naive();
+                        // 判断是否成功（last_update_time 是否被更新为今日）
+                        let mut need_retry = {
+                            let state_ref = app_clone.state::<AppState>();
+                            let guard = state_ref.last_update_time.lock().await;
+                            guard.map(|dt| dt.date_naive()) != Some(today)
+                        };
+                        // 已知当前 mkt 不在零点前后发布时，快速重试只会浪费请求
+                        if need_retry
+                            && matches!(
+                                current_publish_window(&app_clone, after_sleep_now).await,
+                                PublishWindow::Outside(_)
+                            )
+                        {
+                            info!(target:"auto_update","当前不在壁纸发布窗口内，跳过零点重试");
+                            need_retry = false;
+                        }
+                        if need_retry {
+                            warn!(target:"auto_update","零点窗口初次更新可能失败，开始指数退避重试");
+                            for attempt in 0..MAX_MIDNIGHT_RETRIES {
+                                let backoff = midnight_retry_backoff(attempt);
+                                warn!(target:"auto_update","零点重试第 {} 次，{}s 后执行", attempt + 1, backoff.as_secs());
+                                tokio::time::sleep(backoff).await;
 
-                tokio::select! {
-                    _ = tokio::time::sleep(sleep_dur) => {
-                        let after_sleep_now = Local::now();
-                        // 零点窗口（00:00~00:05）内执行每日对齐更新，并在失败时快速重试
-                        if after_sleep_now.hour() == 0 && after_sleep_now.minute() <= 5 {
-                            // 记录更新前的日期
-                            update_cycle::run_update_cycle(&app_clone).await;
-                            let today = after_sleep_now.date_naive();
-                            // 判断是否成功（last_update_time 是否被更新为今日）
-                            let mut need_retry = {
-                                let state_ref = app_clone.state::<AppState>();
-                                let guard = state_ref.last_update_time.lock().await;
-                                guard.map(|dt| dt.date_naive()) != Some(today)
-                            };
-                            if need_retry {
-                                warn!(target:"auto_update","零点窗口初次更新可能失败，开始指数退避重试");
-                                // 优化：改进的指数退避重试策略，限制最大延迟
-                                const MAX_MIDNIGHT_RETRIES: u32 = 10;
-                                const MAX_BACKOFF_SECS: u64 = 60; // 最大延迟 60 秒
-                                for attempt in 0..MAX_MIDNIGHT_RETRIES {
-                                    // 优化：限制最大延迟时间，避免等待时间过长
-                                    let base_backoff = 1 << attempt; // 指数退避：1, 2, 4, 8, 16, 32, 64, 128, 256, 512
-                                    let backoff = base_backoff.min(MAX_BACKOFF_SECS); // 限制最大 60 秒
-                                    warn!(target:"auto_update","零点重试第 {} 次，{}s 后执行", attempt + 1, backoff);
-                                    tokio::time::sleep(Duration::from_secs(backoff)).await;
-
-                                    update_cycle::run_update_cycle(&app_clone).await;
-                                    let now_retry = Local::now();
-                                    let after_cycle_success = {
-                                        let state_ref = app_clone.state::<AppState>();
-                                        let guard = state_ref.last_update_time.lock().await;
-                                        guard.map(|dt| dt.date_naive()) == Some(now_retry.date_naive())
-                                    };
-                                    if after_cycle_success {
-                                        info!(target:"auto_update","零点重试第 {} 次成功", attempt + 1);
-                                        need_retry = false;
-                                        break;
-                                    } else {
-                                        warn!(target:"auto_update","零点重试第 {} 次仍未获取到当日壁纸", attempt + 1);
-                                    }
-                                }
-                                if need_retry {
-                                    warn!(target:"auto_update","零点重试结束，仍未成功获取当日壁纸，进入追赶模式等待下一轮重试");
+                                update_cycle::run_update_cycle(&app_clone).await;
+                                let now_retry = clock.now();
+                                let after_cycle_success = {
+                                    let state_ref = app_clone.state::<AppState>();
+                                    let guard = state_ref.last_update_time.lock().await;
+                                    guard.map(|dt| dt.date_naive()) == Some(now_retry.date_naive())
+                                };
+                                if after_cycle_success {
+                                    info!(target:"auto_update","零点重试第 {} 次成功", attempt + 1);
+                                    need_retry = false;
+                                    break;
+                                } else {
+                                    warn!(target:"auto_update","零点重试第 {} 次仍未获取到当日壁纸", attempt + 1);
                                 }
                             }
-                        } else {
-                            // 普通每小时轮询 / 追赶模式重试
-                            update_cycle::run_update_cycle(&app_clone).await;
+                            if need_retry {
+                                warn!(target:"auto_update","零点重试结束，仍未成功获取当日壁纸，进入追赶模式等待下一轮重试");
+                            }
                         }
+                    } else {
+                        // 普通每小时轮询 / 追赶模式重试
+                        update_cycle::run_update_cycle(&app_clone).await;
+                    }
 
-                        // 统一更新追赶计数：cycle 完成后检查今日是否成功
-                        let cycle_today = Local::now().date_naive();
-                        let success_today = {
-                            let state_ref = app_clone.state::<AppState>();
-                            let guard = state_ref.last_update_time.lock().await;
-                            guard.map(|dt| dt.date_naive()) == Some(cycle_today)
-                        };
-                        if success_today {
-                            consecutive_today_failures = 0;
-                        } else {
-                            consecutive_today_failures =
-                                consecutive_today_failures.saturating_add(1);
-                        }
+                    // 统一更新追赶计数：cycle 完成后检查今日是否成功
+                    let cycle_today = clock.now().date_naive();
+                    let success_today = {
+                        let state_ref = app_clone.state::<AppState>();
+                        let guard = state_ref.last_update_time.lock().await;
+                        guard.map(|dt| dt.date_naive()) == Some(cycle_today)
+                    };
+                    if success_today {
+                        consecutive_today_failures = 0;
+                    } else {
+                        consecutive_today_failures =
+                            consecutive_today_failures.saturating_add(1);
+                    }
+                }
+                changed = rx.changed() => {
+                    if changed.is_err() {
+                        error!(target: "update", "settings watch channel closed");
+                        break;
                     }
-                    changed = rx.changed() => {
-                        if changed.is_err() {
-                            error!(target: "update", "settings watch channel closed");
-                            break;
-                        }
 
-                        // 跳过第一次设置变更（启动时的初始化）
-                        if is_first_change {
-                            is_first_change = false;
-                            continue;
-                        }
+                    // 跳过第一次设置变更（启动时的初始化）
+                    if is_first_change {
+                        is_first_change = false;
+                        continue;
+                    }
 
-                        let latest = rx.borrow().clone();
-                        if !latest.auto_update {
-                            info!(target: "update", "自动应用已关闭（仍会获取新壁纸），等待重新开启...");
-                            loop {
-                                if rx.changed().await.is_err() { break; }
-                                let s = rx.borrow().clone();
-                                if s.auto_update {
-                                    info!(target: "update", "自动应用重新开启，立即执行一次");
-                                    update_cycle::run_update_cycle(&app_clone).await;
-                                    break;
-                                }
+                    let latest = rx.borrow().clone();
+                    if !latest.auto_update {
+                        info!(target: "update", "自动应用已关闭（仍会获取新壁纸），等待重新开启...");
+                        loop {
+                            if rx.changed().await.is_err() { break; }
+                            let s = rx.borrow().clone();
+                            if s.auto_update {
+                                info!(target: "update", "自动应用重新开启，立即执行一次");
+                                update_cycle::run_update_cycle(&app_clone).await;
+                                break;
                             }
-                        } else {
-                            info!(target: "update", "设置改变，立即执行更新");
-                            update_cycle::run_update_cycle(&app_clone).await;
                         }
+                    } else {
+                        info!(target: "update", "设置改变，立即执行更新");
+                        update_cycle::run_update_cycle(&app_clone).await;
                     }
                 }
             }
-        });
-        *h = new_handle;
+        }
     });
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::clock::{Clock, MockClock};
+    use chrono::{FixedOffset, NaiveDate, Offset};
+
+    /// 测试用时区：在 UTC 时刻 `transition` 从 `before` 偏移切换为 `after` 偏移
+    #[derive(Debug, Clone, Copy)]
+    struct DstZone {
+        transition: NaiveDateTime,
+        before: FixedOffset,
+        after: FixedOffset,
+    }
+
+    #[derive(Debug, Clone, Copy)]
+    struct DstOffset {
+        zone: DstZone,
+        offset: FixedOffset,
+    }
+
+    impl Offset for DstOffset {
+        fn fix(&self) -> FixedOffset {
+            self.offset
+        }
+    }
+
+    impl DstZone {
+        fn new(transition_utc: NaiveDateTime, before_hours: i32, after_hours: i32) -> Self {
+            Self {
+                transition: transition_utc,
+                before: FixedOffset::east_opt(before_hours * 3600).unwrap(),
+                after: FixedOffset::east_opt(after_hours * 3600).unwrap(),
+            }
+        }
+
+        fn with(&self, offset: FixedOffset) -> DstOffset {
+            DstOffset {
+                zone: *self,
+                offset,
+            }
+        }
+
+        fn at(&self, year: i32, month: u32, day: u32, hour: u32, min: u32) -> DateTime<Self> {
+            self.from_local_datetime(&naive(year, month, day, hour, min))
+                .earliest()
+                .expect("local time should exist")
+        }
+    }
+
+    impl TimeZone for DstZone {
+        type Offset = DstOffset;
+
+        fn from_offset(offset: &DstOffset) -> Self {
+            offset.zone
+        }
+
+        fn offset_from_local_date(&self, local: &NaiveDate) -> LocalResult<DstOffset> {
+            self.offset_from_local_datetime(&local.and_time(NaiveTime::MIN))
+        }
+
+        fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<DstOffset> {
+            let mut valid: Vec<_> = [self.before, self.after]
+                .into_iter()
+                .filter(|offset| {
+                    let utc = *local - ChronoDuration::seconds(offset.local_minus_utc().into());
+                    (utc < self.transition) == (*offset == self.before)
+                })
+                .collect();
+            valid.sort_by_key(|offset| std::cmp::Reverse(offset.local_minus_utc()));
+            match valid.as_slice() {
+                [] => LocalResult::None,
+                [offset] => LocalResult::Single(self.with(*offset)),
+                [earliest, latest, ..] => {
+                    LocalResult::Ambiguous(self.with(*earliest), self.with(*latest))
+                }
+            }
+        }
+
+        fn offset_from_utc_date(&self, utc: &NaiveDate) -> DstOffset {
+            self.offset_from_utc_datetime(&utc.and_time(NaiveTime::MIN))
+        }
+
+        fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> DstOffset {
+            self.with(if *utc < self.transition {
+                self.before
+            } else {
+                self.after
+            })
+        }
+    }
+
+    fn naive(year: i32, month: u32, day: u32, hour: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .unwrap()
+            .and_hms_opt(hour, min, 0)
+            .unwrap()
+    }
 
     #[test]
     fn normal_mode_uses_full_hour_when_far_from_midnight() {
         // 距零点 5 小时，正常模式应当 sleep 1 小时
-        let dur = compute_sleep_duration(ChronoDuration::hours(5), false, 0);
+        let dur =
+            compute_sleep_duration(ChronoDuration::hours(5), false, 0, PublishWindow::Unknown);
         assert_eq!(dur, Duration::from_secs(HOUR_SECS));
     }
 
     #[test]
     fn normal_mode_aligns_to_midnight_when_close() {
         // 距零点 30 分钟，正常模式应当 sleep 30 分钟（对齐零点）
-        let dur = compute_sleep_duration(ChronoDuration::minutes(30), false, 0);
+        let dur = compute_sleep_duration(
+            ChronoDuration::minutes(30),
+            false,
+            0,
+            PublishWindow::Unknown,
+        );
         assert_eq!(dur, Duration::from_secs(30 * 60));
     }
 
     #[test]
     fn normal_mode_handles_negative_duration() {
         // 时钟回拨等异常：fallback 到 1 小时
-        let dur = compute_sleep_duration(ChronoDuration::seconds(-100), false, 0);
+        let dur = compute_sleep_duration(
+            ChronoDuration::seconds(-100),
+            false,
+            0,
+            PublishWindow::Unknown,
+        );
         assert_eq!(dur, Duration::from_secs(HOUR_SECS));
     }
 
     #[test]
     fn catchup_first_three_failures_use_15_minutes() {
         for failures in [0u32, 1, 2] {
-            let dur = compute_sleep_duration(ChronoDuration::hours(5), true, failures);
+            let dur = compute_sleep_duration(
+                ChronoDuration::hours(5),
+                true,
+                failures,
+                PublishWindow::Unknown,
+            );
             assert_eq!(
                 dur,
                 Duration::from_secs(15 * 60),
@@ -263,7 +467,12 @@ mod tests {
     #[test]
     fn catchup_mid_failures_use_30_minutes() {
         for failures in [3u32, 4, 5] {
-            let dur = compute_sleep_duration(ChronoDuration::hours(5), true, failures);
+            let dur = compute_sleep_duration(
+                ChronoDuration::hours(5),
+                true,
+                failures,
+                PublishWindow::Unknown,
+            );
             assert_eq!(
                 dur,
                 Duration::from_secs(30 * 60),
@@ -275,7 +484,12 @@ mod tests {
     #[test]
     fn catchup_long_failures_fall_back_to_hour() {
         for failures in [6u32, 7, 100, u32::MAX] {
-            let dur = compute_sleep_duration(ChronoDuration::hours(5), true, failures);
+            let dur = compute_sleep_duration(
+                ChronoDuration::hours(5),
+                true,
+                failures,
+                PublishWindow::Unknown,
+            );
             assert_eq!(
                 dur,
                 Duration::from_secs(HOUR_SECS),
@@ -287,7 +501,155 @@ mod tests {
     #[test]
     fn catchup_never_exceeds_until_midnight() {
         // 距零点仅 5 分钟，即使追赶模式想 sleep 15 分钟，也应缩短到 5 分钟以对齐零点
-        let dur = compute_sleep_duration(ChronoDuration::minutes(5), true, 0);
+        let dur =
+            compute_sleep_duration(ChronoDuration::minutes(5), true, 0, PublishWindow::Unknown);
         assert_eq!(dur, Duration::from_secs(5 * 60));
     }
+
+    #[test]
+    fn catchup_follows_learned_publish_window() {
+        // 窗口内不论失败多少次都每 15 分钟检查
+        let dur = compute_sleep_duration(ChronoDuration::hours(20), true, 8, PublishWindow::Inside);
+        assert_eq!(dur, Duration::from_secs(WINDOW_POLL_SECS));
+
+        // 窗口外：在窗口开始时醒来，但最多间隔 3 小时
+        let soon = PublishWindow::Outside(Duration::from_secs(40 * 60));
+        let dur = compute_sleep_duration(ChronoDuration::hours(20), true, 0, soon);
+        assert_eq!(dur, Duration::from_secs(40 * 60));
+        let far = PublishWindow::Outside(Duration::from_secs(10 * HOUR_SECS));
+        let dur = compute_sleep_duration(ChronoDuration::hours(20), true, 0, far);
+        assert_eq!(dur, Duration::from_secs(SPARSE_POLL_SECS));
+
+        // 仍然对齐零点
+        let dur = compute_sleep_duration(ChronoDuration::minutes(50), true, 0, far);
+        assert_eq!(dur, Duration::from_secs(50 * 60));
+    }
+
+    #[test]
+    fn next_wakeup_is_five_minutes_past_next_midnight() {
+        let clock = MockClock::at(2024, 3, 15, 23, 30, 0);
+        let now = clock.now();
+        let next = next_midnight_wakeup(now);
+        assert_eq!(
+            next.naive_local(),
+            chrono::NaiveDate::from_ymd_opt(2024, 3, 16)
+                .unwrap()
+                .and_hms_opt(0, 5, 0)
+                .unwrap()
+        );
+
+        // 23:30 时正常模式应缩短到 35 分钟以对齐零点更新
+        let dur = compute_sleep_duration(next - now, false, 0, PublishWindow::Unknown);
+        assert_eq!(dur, Duration::from_secs(35 * 60));
+    }
+
+    #[test]
+    fn next_wakeup_just_after_midnight_targets_following_day() {
+        // 00:01 已经处于当日，下一次对齐应是次日 00:05，本轮按整点轮询
+        let clock = MockClock::at(2024, 3, 16, 0, 1, 0);
+        let now = clock.now();
+        let next = next_midnight_wakeup(now);
+        assert_eq!(
+            next.date_naive(),
+            chrono::NaiveDate::from_ymd_opt(2024, 3, 17).unwrap()
+        );
+        assert_eq!(
+            compute_sleep_duration(next - now, false, 0, PublishWindow::Unknown),
+            Duration::from_secs(HOUR_SECS)
+        );
+    }
+
+    #[test]
+    fn midnight_window_boundaries() {
+        let clock = MockClock::at(2024, 3, 15, 23, 59, 59);
+        assert!(!is_in_midnight_window(clock.now()));
+
+        clock.advance(ChronoDuration::seconds(1));
+        assert!(is_in_midnight_window(clock.now()));
+
+        clock.advance(ChronoDuration::minutes(5));
+        assert!(is_in_midnight_window(clock.now()));
+
+        clock.advance(ChronoDuration::minutes(1));
+        assert!(!is_in_midnight_window(clock.now()));
+    }
+
+    #[test]
+    fn spring_forward_over_midnight_wakes_at_first_valid_instant() {
+        // 当地 00:00 拨快到 01:00（UTC-3 -> UTC-2），00:00~00:59 不存在
+        let zone = DstZone::new(naive(2024, 11, 3, 3, 0), -3, -2);
+        let now = zone.at(2024, 11, 2, 23, 30);
+        let next = next_midnight_wakeup(now);
+        assert_eq!(next.naive_local(), naive(2024, 11, 3, 1, 0));
+        assert_eq!(next - now, ChronoDuration::minutes(30));
+
+        // 零点窗口从切换后的第一个时刻开始
+        assert!(is_in_midnight_window(next));
+        assert!(!is_in_midnight_window(zone.at(2024, 11, 3, 1, 1)));
+        assert!(!is_in_midnight_window(zone.at(2024, 11, 2, 23, 59)));
+    }
+
+    #[test]
+    fn spring_forward_outside_window_keeps_regular_wakeup() {
+        // 常见的 02:00 拨快到 03:00 不影响 00:05 的对齐
+        let zone = DstZone::new(naive(2024, 3, 31, 1, 0), 1, 2);
+        let now = zone.at(2024, 3, 30, 23, 0);
+        let next = next_midnight_wakeup(now);
+        assert_eq!(next.naive_local(), naive(2024, 3, 31, 0, 5));
+        assert_eq!(next - now, ChronoDuration::minutes(65));
+        assert!(is_in_midnight_window(zone.at(2024, 3, 31, 0, 0)));
+        assert!(is_in_midnight_window(zone.at(2024, 3, 31, 0, 5)));
+        assert!(!is_in_midnight_window(zone.at(2024, 3, 31, 0, 6)));
+    }
+
+    #[test]
+    fn fall_back_over_midnight_uses_first_occurrence() {
+        // 当地 01:00 拨回到 00:00（UTC+1 -> UTC+0），00:00~00:59 出现两次
+        let zone = DstZone::new(naive(2024, 10, 27, 0, 0), 1, 0);
+        let now = zone.at(2024, 10, 26, 23, 30);
+        let next = next_midnight_wakeup(now);
+        assert_eq!(next.naive_local(), naive(2024, 10, 27, 0, 5));
+        assert_eq!(next.offset().fix(), zone.before);
+        assert_eq!(next - now, ChronoDuration::minutes(35));
+        assert!(is_in_midnight_window(next));
+
+        // 第二次经过 00:05 时不再视为零点窗口，下一次对齐是次日
+        let second = zone
+            .from_local_datetime(&naive(2024, 10, 27, 0, 5))
+            .latest()
+            .unwrap();
+        assert_eq!(second - next, ChronoDuration::hours(1));
+        assert!(!is_in_midnight_window(second));
+        assert_eq!(
+            next_midnight_wakeup(second).naive_local(),
+            naive(2024, 10, 28, 0, 5)
+        );
+    }
+
+    #[test]
+    fn fall_back_before_midnight_targets_next_day_after_now() {
+        // 当地 00:00 拨回到前一日 23:00（UTC-2 -> UTC-3），23:00~23:59 出现两次
+        let zone = DstZone::new(naive(2024, 2, 18, 2, 0), -2, -3);
+        for now in [
+            zone.from_local_datetime(&naive(2024, 2, 17, 23, 30))
+                .earliest()
+                .unwrap(),
+            zone.from_local_datetime(&naive(2024, 2, 17, 23, 30))
+                .latest()
+                .unwrap(),
+        ] {
+            let next = next_midnight_wakeup(now);
+            assert!(next > now);
+            assert_eq!(next.naive_local(), naive(2024, 2, 18, 0, 5));
+        }
+    }
+
+    #[test]
+    fn midnight_retry_backoff_is_exponential_and_capped() {
+        let secs: Vec<u64> = (0..MAX_MIDNIGHT_RETRIES)
+            .map(|attempt| midnight_retry_backoff(attempt).as_secs())
+            .collect();
+        assert_eq!(secs, vec![1, 2, 4, 8, 16, 32, 60, 60, 60, 60]);
+        assert_eq!(midnight_retry_backoff(u32::MAX), Duration::from_secs(60));
+    }
 }
@@ -0,0 +1,272 @@
+//! macOS 自动化（AppleScript / 快捷指令）
+//!
+//! 通过 Apple Events 暴露常用操作，脚本词典见 `BingWallpaperNow.sdef`（打包到 Resources）：
+//! - `refresh wallpaper`：立即强制更新，与托盘"刷新"相同
+//! - `set latest wallpaper`：应用最新一张本地壁纸
+//! - `today wallpaper title`：返回最新一张壁纸的标题
+//!
+//! 快捷指令可通过"运行 AppleScript"操作调用，例如
+//! `tell application "Bing Wallpaper Now" to today wallpaper title`。
+
+use log::{info, warn};
+use objc2::encode::{Encoding, RefEncode};
+use objc2::rc::Retained;
+use objc2::runtime::AnyObject;
+use objc2::{ClassType, class, define_class, msg_send, sel};
+use objc2_foundation::{NSObject, NSString};
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager};
+
+use crate::models::LocalWallpaper;
+use crate::{AppState, get_effective_mkt, storage};
+
+/// 事件类（与 sdef 中 suite 的 code 一致）
+const EVENT_CLASS: u32 = u32::from_be_bytes(*b"BWNw");
+/// keyDirectObject：命令返回值
+const KEY_DIRECT_OBJECT: u32 = u32::from_be_bytes(*b"----");
+/// keyErrorNumber / keyErrorString：命令失败时返回给脚本的错误
+const KEY_ERROR_NUMBER: u32 = u32::from_be_bytes(*b"errn");
+const KEY_ERROR_STRING: u32 = u32::from_be_bytes(*b"errs");
+/// 通用失败错误码（errAEEventFailed）
+const ERR_EVENT_FAILED: i32 = -10000;
+
+static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AutomationAction {
+    Refresh,
+    SetLatest,
+    TodayTitle,
+}
+
+impl AutomationAction {
+    const ALL: [Self; 3] = [Self::Refresh, Self::SetLatest, Self::TodayTitle];
+
+    /// 事件 ID（与 sdef 中 command 的 code 后四位一致）
+    fn event_id(self) -> u32 {
+        match self {
+            Self::Refresh => u32::from_be_bytes(*b"rfsh"),
+            Self::SetLatest => u32::from_be_bytes(*b"stlt"),
+            Self::TodayTitle => u32::from_be_bytes(*b"titl"),
+        }
+    }
+
+    fn from_event_id(event_id: u32) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|action| action.event_id() == event_id)
+    }
+}
+
+/// 当前市场最新的一张本地壁纸
+async fn latest_wallpaper(app: &AppHandle) -> Result<LocalWallpaper, String> {
+    let state = app.state::<AppState>();
+    let wallpaper_dir = state.wallpaper_directory.lock().await.clone();
+    let mkt = get_effective_mkt(&state).await;
+    storage::get_local_wallpapers(&wallpaper_dir, &mkt)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "NO_WALLPAPER".to_string())
+}
+
+async fn set_latest_wallpaper(app: AppHandle) -> Result<(), String> {
+    let latest = latest_wallpaper(&app).await?;
+    let state = app.state::<AppState>();
+    let wallpaper_dir = state.wallpaper_directory.lock().await.clone();
+    let path = storage::get_wallpaper_path(&wallpaper_dir, &latest.end_date);
+    crate::commands::wallpaper::set_desktop_wallpaper(
+        path.to_string_lossy().to_string(),
+        state,
+        app.clone(),
+    )
+    .await
+}
+
+/// NSAppleEventManagerSuspensionID 指向的不透明结构
+#[repr(C)]
+struct SuspensionOpaque {
+    _private: [u8; 0],
+}
+
+unsafe impl RefEncode for SuspensionOpaque {
+    const ENCODING_REF: Encoding =
+        Encoding::Pointer(&Encoding::Struct("__NSAppleEventManagerSuspension", &[]));
+}
+
+/// 被挂起的 Apple Event，在后台任务完成后回到主线程回复并恢复
+struct SuspendedEvent(usize);
+
+impl SuspendedEvent {
+    /// 挂起当前正在处理的 Apple Event（只能在事件处理器中调用）
+    fn suspend_current() -> Self {
+        let id: *mut SuspensionOpaque =
+            unsafe { msg_send![&*event_manager(), suspendCurrentAppleEvent] };
+        Self(id as usize)
+    }
+
+    /// 填写回复并恢复事件（必须在主线程调用）
+    fn resume(self, result: &Result<String, String>) {
+        let manager = event_manager();
+        let id = self.0 as *mut SuspensionOpaque;
+        unsafe {
+            let reply: Option<Retained<AnyObject>> =
+                msg_send![&*manager, replyAppleEventForSuspensionID: id];
+            if let Some(reply) = reply {
+                write_reply(&reply, result);
+            }
+            let _: () = msg_send![&*manager, resumeWithSuspensionID: id];
+        }
+    }
+}
+
+fn event_manager() -> Retained<AnyObject> {
+    unsafe { msg_send![class!(NSAppleEventManager), sharedAppleEventManager] }
+}
+
+/// 执行脚本命令
+///
+/// 事件处理器运行在主线程，不能同步等待异步任务，否则会与需要主线程的任务互相等待：
+/// 刷新和设置壁纸在后台执行并立即返回；获取标题先挂起事件，读取完成后再回复。
+fn run_action(app: &AppHandle, action: AutomationAction) {
+    info!(target: "automation", "收到脚本命令: {:?}", action);
+    let app = app.clone();
+    match action {
+        AutomationAction::Refresh => {
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = crate::update_cycle::force_update(app).await {
+                    warn!(target: "automation", "脚本刷新壁纸失败: {}", e);
+                }
+            });
+        }
+        AutomationAction::SetLatest => {
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = set_latest_wallpaper(app).await {
+                    warn!(target: "automation", "脚本设置最新壁纸失败: {}", e);
+                }
+            });
+        }
+        AutomationAction::TodayTitle => {
+            let suspended = SuspendedEvent::suspend_current();
+            tauri::async_runtime::spawn(async move {
+                let result = latest_wallpaper(&app).await.map(|w| w.title);
+                if let Err(e) = &result {
+                    warn!(target: "automation", "脚本获取壁纸标题失败: {}", e);
+                }
+                if let Err(e) = app.run_on_main_thread(move || suspended.resume(&result)) {
+                    warn!(target: "automation", "回复脚本命令失败: {}", e);
+                }
+            });
+        }
+    }
+}
+
+fn string_descriptor(text: &str) -> Retained<AnyObject> {
+    let text = NSString::from_str(text);
+    unsafe { msg_send![class!(NSAppleEventDescriptor), descriptorWithString: &*text] }
+}
+
+/// 把命令结果写入回复事件：成功时为返回值，失败时为错误码和错误信息
+fn write_reply(reply: &AnyObject, result: &Result<String, String>) {
+    match result {
+        Ok(text) => unsafe {
+            let _: () = msg_send![
+                reply,
+                setParamDescriptor: &*string_descriptor(text),
+                forKeyword: KEY_DIRECT_OBJECT
+            ];
+        },
+        Err(e) => unsafe {
+            let number: Retained<AnyObject> = msg_send![
+                class!(NSAppleEventDescriptor),
+                descriptorWithInt32: ERR_EVENT_FAILED
+            ];
+            let _: () = msg_send![
+                reply,
+                setParamDescriptor: &*number,
+                forKeyword: KEY_ERROR_NUMBER
+            ];
+            let _: () = msg_send![
+                reply,
+                setParamDescriptor: &*string_descriptor(e),
+                forKeyword: KEY_ERROR_STRING
+            ];
+        },
+    }
+}
+
+// 声明 AutomationHandler 类，作为 NSAppleEventManager 的事件处理对象
+define_class!(
+    #[unsafe(super(NSObject))]
+    #[name = "BingWallpaperAutomationHandler"]
+    struct AutomationHandler;
+
+    impl AutomationHandler {
+        #[unsafe(method(handleAppleEvent:withReplyEvent:))]
+        fn handle_apple_event(&self, event: &AnyObject, _reply: &AnyObject) {
+            let event_id: u32 = unsafe { msg_send![event, eventID] };
+            if let (Some(app), Some(action)) =
+                (APP_HANDLE.get(), AutomationAction::from_event_id(event_id))
+            {
+                run_action(app, action);
+            }
+        }
+    }
+);
+
+/// 注册 Apple Events 处理器
+/// 必须在主线程调用一次（应用 setup 中）
+pub(crate) fn initialize(app: &AppHandle) {
+    if APP_HANDLE.set(app.clone()).is_err() {
+        return;
+    }
+
+    let handler: Retained<AutomationHandler> =
+        unsafe { msg_send![AutomationHandler::class(), new] };
+    let handler_ref: &AnyObject = &handler;
+    unsafe {
+        let manager = event_manager();
+        for action in AutomationAction::ALL {
+            let _: () = msg_send![
+                &*manager,
+                setEventHandler: handler_ref,
+                andSelector: sel!(handleAppleEvent:withReplyEvent:),
+                forEventClass: EVENT_CLASS,
+                andEventID: action.event_id()
+            ];
+        }
+    }
+
+    // 与 WallpaperObserver 相同：处理器需要一直存活到程序退出
+    std::mem::forget(handler);
+    info!(target: "automation", "已注册 AppleScript 自动化命令");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_ids_round_trip() {
+        for action in AutomationAction::ALL {
+            assert_eq!(
+                AutomationAction::from_event_id(action.event_id()),
+                Some(action)
+            );
+        }
+        assert_eq!(
+            AutomationAction::from_event_id(u32::from_be_bytes(*b"oapp")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_sdef_codes_match_event_ids() {
+        let sdef = include_str!("../BingWallpaperNow.sdef");
+        assert!(sdef.contains(r#"code="BWNw""#));
+        for code in ["BWNwrfsh", "BWNwstlt", "BWNwtitl"] {
+            assert!(sdef.contains(&format!(r#"code="{code}""#)), "{code}");
+        }
+    }
+}
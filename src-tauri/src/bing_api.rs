@@ -1,10 +1,16 @@
+use crate::http_client::{self, SharedClient};
 use crate::models::{BingImageArchive, BingImageEntry};
 use crate::utils;
 use anyhow::{Context, Result};
 use log::{error, info, warn};
+use std::future::Future;
+use std::pin::Pin;
 
 const BING_API_URL: &str = "https://www.bing.com/HPImageArchive.aspx";
 const BING_BASE_URL: &str = "https://www.bing.com";
+const BING_DEFAULT_HOST: &str = "www.bing.com";
+
+static HTTP_CLIENT: SharedClient = SharedClient::new(|builder| builder);
 
 /// Bing API 获取结果
 ///
@@ -18,6 +24,81 @@ pub struct BingFetchResult {
     /// 从 copyrightlink 检测到的实际 mkt（标准化后）
     /// None 表示无法从响应中检测
     pub actual_mkt: Option<String>,
+    /// 发生 HTTP 重定向时实际响应的主机（如 `cn.bing.com`）
+    /// None 表示请求由 www.bing.com 直接响应
+    pub served_host: Option<String>,
+}
+
+/// `ImageSource::fetch` 返回的 future
+pub(crate) type FetchFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<BingFetchResult>> + Send + 'a>>;
+
+/// 壁纸元数据来源
+///
+/// 更新循环和市场探测通过 `AppState` 中注入的实现获取图片列表：生产环境使用
+/// `BingImageSource` 请求 Bing API，测试中使用 `MockImageSource` 按预设返回结果，
+/// 不依赖网络即可覆盖重试、mkt 不一致等分支。
+pub(crate) trait ImageSource: Send + Sync {
+    /// 获取壁纸列表，参数含义同 `fetch_bing_images`
+    fn fetch<'a>(&'a self, count: u8, idx: u8, mkt: &'a str) -> FetchFuture<'a>;
+}
+
+/// 通过 HTTP 请求 Bing API 的图片来源
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct BingImageSource;
+
+impl ImageSource for BingImageSource {
+    fn fetch<'a>(&'a self, count: u8, idx: u8, mkt: &'a str) -> FetchFuture<'a> {
+        Box::pin(fetch_bing_images(count, idx, mkt))
+    }
+}
+
+/// 测试用的图片来源：按顺序返回预设结果，并记录每次请求的 mkt
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub(crate) struct MockImageSource {
+    responses: std::sync::Mutex<std::collections::VecDeque<Result<BingFetchResult, String>>>,
+    requests: std::sync::Mutex<Vec<String>>,
+}
+
+#[cfg(test)]
+impl MockImageSource {
+    pub(crate) fn new(responses: Vec<Result<BingFetchResult, String>>) -> Self {
+        Self {
+            responses: std::sync::Mutex::new(responses.into()),
+            requests: std::sync::Mutex::new(Vec::new()),
+        }
+    }
+
+    /// 已收到的请求（按顺序记录 mkt）
+    pub(crate) fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+impl ImageSource for MockImageSource {
+    fn fetch<'a>(&'a self, _count: u8, _idx: u8, mkt: &'a str) -> FetchFuture<'a> {
+        self.requests.lock().unwrap().push(mkt.to_string());
+        let response = self
+            .responses
+            .lock()
+            .unwrap()
+            .pop_front()
+            .unwrap_or_else(|| Err("no more mock responses".to_string()));
+        Box::pin(async move { response.map_err(anyhow::Error::msg) })
+    }
+}
+
+/// 从最终响应 URL 中提取重定向后的主机
+///
+/// 部分地区访问 www.bing.com 会被 302 到 `cn.bing.com` 等区域站点，
+/// 此时无论请求哪个 mkt，返回的都是该区域的壁纸。
+fn detect_served_host(final_url: &reqwest::Url) -> Option<String> {
+    final_url
+        .host_str()
+        .filter(|host| !host.eq_ignore_ascii_case(BING_DEFAULT_HOST))
+        .map(|host| host.to_lowercase())
 }
 
 /// 从 Bing API 获取壁纸列表
@@ -41,7 +122,7 @@ pub async fn fetch_bing_images(count: u8, idx: u8, mkt: &str) -> Result<BingFetc
 
     let start_time = std::time::Instant::now();
 
-    let response = match reqwest::get(&url).await {
+    let response = match http_client::send(HTTP_CLIENT.client().get(&url)).await {
         Ok(resp) => {
             let elapsed = start_time.elapsed();
             let status = resp.status();
@@ -51,6 +132,10 @@ pub async fn fetch_bing_images(count: u8, idx: u8, mkt: &str) -> Result<BingFetc
                 warn!(target: "bing_api", "Bing API 返回非成功状态: status={}", status);
             }
 
+            if let Some(host) = detect_served_host(resp.url()) {
+                warn!(target: "bing_api", "Bing API 请求被重定向: mkt={}, 实际主机={}", mkt, host);
+            }
+
             resp
         }
         Err(e) => {
@@ -60,6 +145,8 @@ pub async fn fetch_bing_images(count: u8, idx: u8, mkt: &str) -> Result<BingFetc
         }
     };
 
+    let served_host = detect_served_host(response.url());
+
     let parse_start = std::time::Instant::now();
     let archive: BingImageArchive = match response.json().await {
         Ok(archive) => {
@@ -74,11 +161,11 @@ pub async fn fetch_bing_images(count: u8, idx: u8, mkt: &str) -> Result<BingFetc
         }
     };
 
-    // 从第一个图片的 copyrightlink 检测实际 mkt
-    let actual_mkt = archive
-        .images
-        .first()
-        .and_then(|img| utils::detect_actual_mkt(&img.copyrightlink));
+    // 从第一个图片的 copyrightlink 检测实际 mkt，缺失时回退到 urlbase 中的市场后缀
+    let actual_mkt = archive.images.first().and_then(|img| {
+        utils::detect_actual_mkt(&img.copyrightlink)
+            .or_else(|| utils::detect_mkt_from_urlbase(&img.urlbase))
+    });
 
     if let Some(ref detected) = actual_mkt
         && detected != mkt
@@ -132,7 +219,11 @@ pub async fn fetch_bing_images(count: u8, idx: u8, mkt: &str) -> Result<BingFetc
         total_elapsed.as_secs_f64() * 1000.0
     );
 
-    Ok(BingFetchResult { images, actual_mkt })
+    Ok(BingFetchResult {
+        images,
+        actual_mkt,
+        served_host,
+    })
 }
 
 /// 将日期字符串减一天（YYYYMMDD 格式）
@@ -171,6 +262,26 @@ pub fn get_wallpaper_url(urlbase: &str, resolution: &str) -> String {
     format!("{}{}_{}.jpg", BING_BASE_URL, urlbase, resolution)
 }
 
+/// zh-CN 市场 urlbase 中的市场标记
+const ZH_CN_MARKER: &str = "_ZH-CN";
+/// 国际版（rest of world）素材的市场标记
+const ROW_MARKER: &str = "_ROW";
+
+/// 获取 zh-CN 壁纸的无水印版本 urlbase
+///
+/// 中国市场的部分素材在图片中内嵌了 Bing 标志，而同一素材的国际版（`_ROW`）不带水印。
+/// 仅对带 `_ZH-CN` 标记的 urlbase 返回替换后的地址，其他市场返回 None。
+/// 国际版不一定存在，调用方需在 404 时回退到原始 urlbase。
+pub fn watermark_free_urlbase(urlbase: &str) -> Option<String> {
+    let position = urlbase.rfind(ZH_CN_MARKER)?;
+    Some(format!(
+        "{}{}{}",
+        &urlbase[..position],
+        ROW_MARKER,
+        &urlbase[position + ZH_CN_MARKER.len()..]
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -331,6 +442,19 @@ mod tests {
         assert!(url.ends_with("_verylongresolutionstring.jpg"));
     }
 
+    #[test]
+    fn test_watermark_free_urlbase_only_for_zh_cn() {
+        assert_eq!(
+            watermark_free_urlbase("/th?id=OHR.GreatWall_ZH-CN1234567890").as_deref(),
+            Some("/th?id=OHR.GreatWall_ROW1234567890")
+        );
+        assert_eq!(
+            watermark_free_urlbase("/th?id=OHR.GreatWall_EN-US1234567890"),
+            None
+        );
+        assert_eq!(watermark_free_urlbase(""), None);
+    }
+
     #[test]
     fn test_get_wallpaper_url_consistency() {
         // Test that calling the same function with the same inputs produces consistent results
@@ -382,6 +506,21 @@ mod tests {
         assert_eq!(subtract_one_day("20240630"), "20240629");
     }
 
+    // ─── detect_served_host 测试 ───
+
+    #[test]
+    fn test_detect_served_host_default_host() {
+        let url =
+            reqwest::Url::parse("https://www.bing.com/HPImageArchive.aspx?mkt=en-US").unwrap();
+        assert_eq!(detect_served_host(&url), None);
+    }
+
+    #[test]
+    fn test_detect_served_host_redirected() {
+        let url = reqwest::Url::parse("https://cn.bing.com/HPImageArchive.aspx?mkt=en-US").unwrap();
+        assert_eq!(detect_served_host(&url), Some("cn.bing.com".to_string()));
+    }
+
     // ─── BingFetchResult 结构测试 ───
 
     #[test]
@@ -397,6 +536,7 @@ mod tests {
                 enddate: "20240102".to_string(),
             }],
             actual_mkt: Some("zh-CN".to_string()),
+            served_host: None,
         };
 
         assert_eq!(result.images.len(), 1);
@@ -409,6 +549,7 @@ mod tests {
         let result = BingFetchResult {
             images: vec![],
             actual_mkt: None,
+            served_host: None,
         };
 
         assert!(result.images.is_empty());
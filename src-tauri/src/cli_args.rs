@@ -0,0 +1,113 @@
+//! 第二个实例的命令行参数转发
+//!
+//! 应用只允许单实例运行。再次启动时，single-instance 插件会把新进程的参数和工作目录
+//! 交给已运行的实例处理，便于脚本或文件管理器的"打开方式"集成：
+//! - `--update-now`：立即强制更新，与托盘"刷新"相同
+//! - 目录路径（或其中的 `index.json`）：把该壁纸目录导入当前目录
+//!
+//! 自启动使用的 `--hidden` 等其他参数会被忽略。
+
+use log::{info, warn};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use crate::{AppState, commands, transfer, update_cycle};
+
+/// 第二个实例请求执行的操作
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CliAction {
+    UpdateNow,
+    Import(PathBuf),
+}
+
+/// 解析命令行参数（`args[0]` 为程序路径），相对路径按第二个实例的工作目录解析
+fn parse_args(args: &[String], cwd: &Path) -> Vec<CliAction> {
+    args.iter()
+        .skip(1)
+        .filter_map(|arg| {
+            if arg == "--update-now" {
+                return Some(CliAction::UpdateNow);
+            }
+            if arg.starts_with('-') {
+                return None;
+            }
+            let path = cwd.join(arg);
+            if path.file_name().is_some_and(|name| name == "index.json") {
+                return path
+                    .parent()
+                    .map(|dir| CliAction::Import(dir.to_path_buf()));
+            }
+            Some(CliAction::Import(path))
+        })
+        .collect()
+}
+
+/// 处理第二个实例转发来的参数
+///
+/// 没有可执行的操作（普通地再次启动）或需要导入时显示主窗口；
+/// 只请求 `--update-now` 时在后台更新，不打扰用户。
+pub(crate) fn handle_second_instance(app: &AppHandle, args: Vec<String>, cwd: String) {
+    let actions = parse_args(&args, Path::new(&cwd));
+    info!(target: "single_instance", "收到第二个实例的参数: {:?}", actions);
+
+    let background_only =
+        !actions.is_empty() && actions.iter().all(|action| *action == CliAction::UpdateNow);
+    if !background_only
+        && let Err(e) = commands::window::show_main_window_with_watchdog(app, "single_instance")
+    {
+        warn!(target: "frontend", "通过 single_instance 显示主窗口失败: {}", e);
+    }
+
+    for action in actions {
+        let app = app.clone();
+        match action {
+            CliAction::UpdateNow => {
+                tauri::async_runtime::spawn(async move {
+                    update_cycle::run_update_cycle_internal(&app, true).await;
+                });
+            }
+            CliAction::Import(dir) => {
+                tauri::async_runtime::spawn(async move {
+                    let source = dir.to_string_lossy().to_string();
+                    match transfer::import_wallpapers(source, app.state::<AppState>(), app.clone())
+                        .await
+                    {
+                        Ok(_) => {
+                            info!(target: "single_instance", "已导入壁纸目录: {}", dir.display())
+                        }
+                        Err(e) => {
+                            warn!(target: "single_instance", "导入 {} 失败: {}", dir.display(), e)
+                        }
+                    }
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let cwd = Path::new("/home/user");
+        assert!(parse_args(&args(&["app"]), cwd).is_empty());
+        assert!(parse_args(&args(&["app", "--hidden"]), cwd).is_empty());
+        assert_eq!(
+            parse_args(&args(&["app", "--update-now", "Pictures/Bing"]), cwd),
+            vec![
+                CliAction::UpdateNow,
+                CliAction::Import(PathBuf::from("/home/user/Pictures/Bing")),
+            ]
+        );
+        assert_eq!(
+            parse_args(&args(&["app", "/mnt/backup/index.json"]), cwd),
+            vec![CliAction::Import(PathBuf::from("/mnt/backup"))]
+        );
+    }
+}
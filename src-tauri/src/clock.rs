@@ -0,0 +1,83 @@
+//! 时间源抽象
+//!
+//! 自动更新的零点对齐、跨天缓存失效和追赶退避都依赖"当前时间"。
+//! 业务代码通过 `Clock` 获取时间，生产环境使用系统时钟，测试中使用 `MockClock`
+//! 固定或推进时间，从而可以确定性地覆盖 23:59 → 00:00 等边界场景。
+
+use chrono::{DateTime, Local};
+
+/// 当前时间的提供者
+pub(crate) trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Local>;
+}
+
+/// 系统时钟
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Local> {
+        Local::now()
+    }
+}
+
+/// 测试用的可控时钟
+#[cfg(test)]
+#[derive(Debug)]
+pub(crate) struct MockClock {
+    now: std::sync::Mutex<DateTime<Local>>,
+}
+
+#[cfg(test)]
+impl MockClock {
+    pub(crate) fn new(now: DateTime<Local>) -> Self {
+        Self {
+            now: std::sync::Mutex::new(now),
+        }
+    }
+
+    /// 以本地时间构造（测试中的固定时刻）
+    pub(crate) fn at(year: i32, month: u32, day: u32, hour: u32, min: u32, sec: u32) -> Self {
+        use chrono::TimeZone;
+        let now = Local
+            .with_ymd_and_hms(year, month, day, hour, min, sec)
+            .earliest()
+            .expect("invalid local time for MockClock");
+        Self::new(now)
+    }
+
+    pub(crate) fn set(&self, now: DateTime<Local>) {
+        *self.now.lock().unwrap() = now;
+    }
+
+    pub(crate) fn advance(&self, duration: chrono::Duration) {
+        let mut now = self.now.lock().unwrap();
+        *now += duration;
+    }
+}
+
+#[cfg(test)]
+impl Clock for MockClock {
+    fn now(&self) -> DateTime<Local> {
+        *self.now.lock().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_clock_set_and_advance() {
+        let clock = MockClock::at(2024, 1, 1, 23, 59, 0);
+        clock.advance(chrono::Duration::minutes(2));
+        assert_eq!(
+            clock.now().date_naive(),
+            chrono::NaiveDate::from_ymd_opt(2024, 1, 2).unwrap()
+        );
+
+        let earlier = MockClock::at(2023, 6, 1, 12, 0, 0).now();
+        clock.set(earlier);
+        assert_eq!(clock.now(), earlier);
+    }
+}
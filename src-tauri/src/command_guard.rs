@@ -0,0 +1,164 @@
+//! 耗时命令的防重入与限频
+//!
+//! 强制更新、导入导出、备份等命令会占用网络或大量磁盘 I/O。前端异常（重复点击、
+//! 事件循环重复触发）时，同一命令不应排队执行多次：同组命令正在执行，或距上次开始
+//! 不足最小间隔时，直接返回 "BUSY"，由前端提示稍后重试。
+
+use log::warn;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// 命令被拒绝时返回的错误码
+pub(crate) const BUSY: &str = "BUSY";
+
+/// 命令的防护策略
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct GuardPolicy {
+    /// 互斥分组：同组命令不能同时执行
+    pub group: &'static str,
+    /// 两次开始执行之间的最小间隔
+    pub min_interval: Duration,
+}
+
+pub(crate) const FORCE_UPDATE: GuardPolicy = GuardPolicy {
+    group: "force_update",
+    min_interval: Duration::from_secs(5),
+};
+/// 导入与导出都会批量复制图片并写索引，共用一个分组
+pub(crate) const TRANSFER: GuardPolicy = GuardPolicy {
+    group: "transfer",
+    min_interval: Duration::from_secs(2),
+};
+pub(crate) const PREVIEW_IMPORT: GuardPolicy = GuardPolicy {
+    group: "preview_import",
+    min_interval: Duration::from_secs(1),
+};
+pub(crate) const BACKUP_NOW: GuardPolicy = GuardPolicy {
+    group: "backup_now",
+    min_interval: Duration::from_secs(10),
+};
+pub(crate) const EXPORT_CROPS: GuardPolicy = GuardPolicy {
+    group: "export_crops",
+    min_interval: Duration::from_secs(2),
+};
+/// 历史归档回填会请求第三方服务并写索引
+pub(crate) const ARCHIVE_BACKFILL: GuardPolicy = GuardPolicy {
+    group: "archive_backfill",
+    min_interval: Duration::from_secs(5),
+};
+
+#[derive(Default)]
+struct GuardRegistry {
+    running: HashSet<&'static str>,
+    last_started: HashMap<&'static str, Instant>,
+}
+
+impl GuardRegistry {
+    /// 登记一次执行，被拒绝时返回 false
+    fn try_begin(&mut self, policy: GuardPolicy, now: Instant) -> bool {
+        if self.running.contains(policy.group) {
+            return false;
+        }
+        if self
+            .last_started
+            .get(policy.group)
+            .is_some_and(|last| now.duration_since(*last) < policy.min_interval)
+        {
+            return false;
+        }
+        self.running.insert(policy.group);
+        self.last_started.insert(policy.group, now);
+        true
+    }
+
+    fn finish(&mut self, group: &'static str) {
+        self.running.remove(group);
+    }
+}
+
+static REGISTRY: LazyLock<Mutex<GuardRegistry>> = LazyLock::new(Default::default);
+
+fn registry() -> std::sync::MutexGuard<'static, GuardRegistry> {
+    REGISTRY.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// 执行期间持有，释放时（包括 future 被取消）解除占用
+struct Running(&'static str);
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        registry().finish(self.0);
+    }
+}
+
+/// 按策略执行命令：同组命令执行中或调用过于频繁时返回 "BUSY"
+pub(crate) async fn guarded<T, F>(policy: GuardPolicy, command: F) -> Result<T, String>
+where
+    F: Future<Output = Result<T, String>>,
+{
+    if !registry().try_begin(policy, Instant::now()) {
+        warn!(target: "commands", "命令 {} 正在执行或调用过于频繁，已拒绝", policy.group);
+        return Err(BUSY.to_string());
+    }
+    let _running = Running(policy.group);
+    command.await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_POLICY: GuardPolicy = GuardPolicy {
+        group: "test",
+        min_interval: Duration::from_secs(5),
+    };
+
+    #[test]
+    fn test_rejects_reentry_until_finished() {
+        let mut registry = GuardRegistry::default();
+        let now = Instant::now();
+        let policy = GuardPolicy {
+            min_interval: Duration::ZERO,
+            ..TEST_POLICY
+        };
+
+        assert!(registry.try_begin(policy, now));
+        assert!(!registry.try_begin(policy, now));
+        registry.finish(policy.group);
+        assert!(registry.try_begin(policy, now));
+    }
+
+    #[test]
+    fn test_enforces_min_interval_between_starts() {
+        let mut registry = GuardRegistry::default();
+        let now = Instant::now();
+
+        assert!(registry.try_begin(TEST_POLICY, now));
+        registry.finish(TEST_POLICY.group);
+        assert!(!registry.try_begin(TEST_POLICY, now + Duration::from_secs(1)));
+        assert!(registry.try_begin(TEST_POLICY, now + Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_groups_are_independent() {
+        let mut registry = GuardRegistry::default();
+        let now = Instant::now();
+
+        assert!(registry.try_begin(TEST_POLICY, now));
+        assert!(registry.try_begin(PREVIEW_IMPORT, now));
+    }
+
+    #[tokio::test]
+    async fn test_guarded_releases_after_completion() {
+        let policy = GuardPolicy {
+            group: "test_guarded",
+            min_interval: Duration::ZERO,
+        };
+
+        let inner = guarded(policy, async { guarded(policy, async { Ok(()) }).await }).await;
+        assert_eq!(inner, Err(BUSY.to_string()));
+        assert_eq!(guarded(policy, async { Ok(1) }).await, Ok(1));
+    }
+}
@@ -1,6 +1,8 @@
 use crate::AppState;
-use crate::models::MarketStatus;
-use crate::utils;
+use crate::bing_api::BingFetchResult;
+use crate::models::{MarketProbeResult, MarketStatus};
+use crate::{runtime_state, utils};
+use log::{info, warn};
 
 /// 获取按区域分组的市场列表（前端动态渲染下拉选项）
 #[tauri::command]
@@ -14,13 +16,90 @@ pub(crate) fn get_supported_mkts() -> Vec<utils::MarketGroup> {
 /// `effective_mkt` 与 `get_effective_mkt()` 返回值完全一致，确保单一 truth source。
 #[tauri::command]
 pub(crate) async fn get_market_status(
+    app: tauri::AppHandle,
     state: tauri::State<'_, AppState>,
 ) -> Result<MarketStatus, String> {
-    let requested = state.settings.lock().await.mkt.clone();
+    let requested = state.settings.read().await.mkt;
     let effective = crate::get_effective_mkt(&state).await;
-    Ok(MarketStatus {
-        is_mismatch: requested != effective,
-        requested_mkt: requested,
-        effective_mkt: effective,
-    })
+    let is_degraded = runtime_state::market_health(&app, &requested).is_degraded();
+    Ok(MarketStatus::new(requested, effective).with_degraded(is_degraded))
+}
+
+/// 根据 Bing 响应构造探测结果
+///
+/// 只有检测到的实际 mkt 与请求不同时才视为不可用；无法检测时按可用处理，
+/// 避免因响应缺少字段而误报。
+fn build_probe_result(requested_mkt: &str, result: &BingFetchResult) -> MarketProbeResult {
+    let is_available = result
+        .actual_mkt
+        .as_deref()
+        .is_none_or(|served| served.eq_ignore_ascii_case(requested_mkt));
+    MarketProbeResult {
+        requested_mkt: requested_mkt.to_string(),
+        served_mkt: result.actual_mkt.clone(),
+        served_host: result.served_host.clone(),
+        is_available,
+    }
+}
+
+/// 探测指定市场在当前网络环境下是否真正可用
+///
+/// 请求一张该 mkt 的壁纸并检查 Bing 是否将其重定向到其他市场，
+/// 供设置界面在用户选择市场时提前给出提示（不写入索引，不影响 last_actual_mkt）。
+#[tauri::command]
+pub(crate) async fn probe_market_availability(
+    mkt: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<MarketProbeResult, String> {
+    let mkt = utils::normalize_mkt_case(mkt.trim());
+    if !utils::is_valid_mkt(&mkt) && !utils::is_well_formed_mkt(&mkt) {
+        return Err(format!("不支持的 mkt: {mkt}"));
+    }
+
+    let result = state.image_source.fetch(1, 0, &mkt).await.map_err(|e| {
+        warn!(target: "commands", "探测市场可用性失败: mkt={}, 错误={}", mkt, e);
+        e.to_string()
+    })?;
+
+    let probe = build_probe_result(&mkt, &result);
+    info!(
+        target: "commands",
+        "市场可用性探测: requested={}, served={:?}, host={:?}, available={}",
+        probe.requested_mkt, probe.served_mkt, probe.served_host, probe.is_available
+    );
+    Ok(probe)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fetch_result(actual_mkt: Option<&str>, served_host: Option<&str>) -> BingFetchResult {
+        BingFetchResult {
+            images: vec![],
+            actual_mkt: actual_mkt.map(str::to_string),
+            served_host: served_host.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_probe_available_when_served_mkt_matches() {
+        let probe = build_probe_result("en-US", &fetch_result(Some("en-US"), None));
+        assert!(probe.is_available);
+        assert_eq!(probe.served_mkt.as_deref(), Some("en-US"));
+    }
+
+    #[test]
+    fn test_probe_unavailable_when_redirected() {
+        let probe = build_probe_result("en-US", &fetch_result(Some("zh-CN"), Some("cn.bing.com")));
+        assert!(!probe.is_available);
+        assert_eq!(probe.served_host.as_deref(), Some("cn.bing.com"));
+    }
+
+    #[test]
+    fn test_probe_treats_undetected_mkt_as_available() {
+        let probe = build_probe_result("ja-JP", &fetch_result(None, None));
+        assert!(probe.is_available);
+        assert!(probe.served_mkt.is_none());
+    }
 }
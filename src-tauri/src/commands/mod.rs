@@ -9,5 +9,6 @@
 pub(crate) mod mkt;
 pub(crate) mod settings;
 pub(crate) mod storage;
+pub(crate) mod tags;
 pub(crate) mod wallpaper;
 pub(crate) mod window;
@@ -1,6 +1,8 @@
 use crate::models::AppSettings;
-use crate::{AppState, runtime_state, settings_store, storage, tray};
+use crate::{AppState, events, runtime_state, settings_store, storage, tray, wallpaper_theme};
 use log::{error, info, warn};
+use serde::Serialize;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use tauri::AppHandle;
 use tauri_plugin_autostart::ManagerExt;
@@ -34,23 +36,16 @@ pub(crate) fn can_enable_autostart_for_current_build() -> bool {
 ///
 /// # 测试覆盖
 /// 此函数依赖于 Tauri AppHandle，难以直接进行单元测试。
-/// 但底层逻辑（`runtime_state::load_runtime_state` 和 `runtime_state::save_runtime_state`）
+/// 但底层逻辑（`runtime_state::modify_runtime_state`）
 /// 已在 `runtime_state.rs` 模块中有完整的测试覆盖。
 pub(crate) fn set_autostart_notification_flag_if_needed(app: &AppHandle, log_target: &str) {
-    match runtime_state::load_runtime_state(app) {
-        Ok(mut runtime_state) => {
-            if !runtime_state.autostart_notification_shown {
-                runtime_state.autostart_notification_shown = true;
-                if let Err(e) = runtime_state::save_runtime_state(app, &runtime_state) {
-                    warn!(target: log_target, "保存自启动通知标志失败: {}", e);
-                } else {
-                    info!(target: log_target, "已记录自启动通知已显示标志");
-                }
-            }
-        }
-        Err(e) => {
-            warn!(target: log_target, "加载运行时状态失败，无法记录通知标志: {}", e);
-        }
+    let result = runtime_state::modify_runtime_state(app, |runtime_state| {
+        !std::mem::replace(&mut runtime_state.autostart_notification_shown, true)
+    });
+    match result {
+        Ok(true) => info!(target: log_target, "已记录自启动通知已显示标志"),
+        Ok(false) => {}
+        Err(e) => warn!(target: log_target, "保存自启动通知标志失败: {}", e),
     }
 }
 
@@ -60,15 +55,14 @@ pub(crate) async fn get_settings(
     state: tauri::State<'_, AppState>,
     app: tauri::AppHandle,
 ) -> Result<AppSettings, String> {
-    let stored_settings = settings_store::load_settings(&app).unwrap_or_else(|e| {
-        warn!(target: "settings", "从 store 加载设置失败: {}，使用内存中的设置", e);
-        tauri::async_runtime::block_on(async { state.settings.lock().await.clone() })
-    });
-
-    {
-        let mut settings = state.settings.lock().await;
-        *settings = stored_settings.clone();
-    }
+    let revision = state.settings.revision();
+    let stored_settings = match settings_store::load_settings_async(&app).await {
+        Ok(settings) => settings,
+        Err(e) => {
+            warn!(target: "settings", "从 store 加载设置失败: {}，使用内存中的设置", e);
+            state.settings.read().await
+        }
+    };
 
     let mut settings = stored_settings;
 
@@ -88,89 +82,198 @@ pub(crate) async fn get_settings(
 
     settings.compute_resolved_language();
     settings.normalize_mkt();
-
+    if !state
+        .settings
+        .refresh_cache(settings.clone(), revision)
+        .await
     {
-        let mut app_settings = state.settings.lock().await;
-        *app_settings = settings.clone();
+        // 读取期间设置已被修改，返回最新设置而不是过期快照
+        return Ok(state.settings.read().await);
     }
 
     Ok(settings)
 }
 
+/// `update_settings` 的错误
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(crate) enum UpdateSettingsError {
+    /// 字段校验失败：字段名 -> 错误码，前端据此标出有问题的输入
+    Validation { fields: BTreeMap<String, String> },
+    /// 其他失败（启用自启动、保存设置等）
+    Failed { message: String },
+}
+
+impl std::fmt::Display for UpdateSettingsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Validation { fields } => {
+                let fields: Vec<_> = fields
+                    .iter()
+                    .map(|(field, code)| format!("{field}: {code}"))
+                    .collect();
+                write!(f, "设置校验失败 ({})", fields.join(", "))
+            }
+            Self::Failed { message } => f.write_str(message),
+        }
+    }
+}
+
+impl From<String> for UpdateSettingsError {
+    fn from(message: String) -> Self {
+        Self::Failed { message }
+    }
+}
+
+/// 保存设置，返回自启动设置的应用结果
+///
+/// 自启动无法修改（MDM 策略、缺少权限、debug 构建）时其余设置照常保存，
+/// 由前端根据返回的状态提示用户。
 #[tauri::command]
 pub(crate) async fn update_settings(
     new_settings: AppSettings,
     state: tauri::State<'_, AppState>,
     app: tauri::AppHandle,
-) -> Result<(), String> {
-    let mut settings = state.settings.lock().await;
-
-    let mut new_settings = new_settings;
-    new_settings.normalize_language();
-    new_settings.compute_resolved_language();
-    new_settings.normalize_mkt();
+) -> Result<AutostartStatus, UpdateSettingsError> {
+    // 同步配置方案也在写入锁内，避免并发修改时方案与设置不一致
+    let _writes = state.settings.lock_writes().await;
+    let autostart = apply_settings(new_settings.clone(), &state, &app).await?;
+    crate::profiles::sync_active_profile(&app, &new_settings).await;
+    Ok(autostart)
+}
 
-    let old_language = settings.language.clone();
-    let old_mkt = settings.mkt.clone();
+/// 自启动设置的应用结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub(crate) enum AutostartStatus {
+    /// 系统状态与设置一致
+    Ok,
+    /// 当前构建不支持启用自启动
+    Unsupported { reason: String },
+    /// 系统拒绝了修改（MDM 策略、缺少权限等）
+    Failed { reason: String },
+}
 
+/// 按新设置启用或禁用系统自启动（与系统当前状态一致时不操作）
+fn sync_autostart(app: &AppHandle, launch_at_startup: bool) -> AutostartStatus {
     let autostart_manager = app.autolaunch();
     let current_autostart_enabled = autostart_manager.is_enabled().unwrap_or_else(|e| {
         warn!(target: "settings", "读取当前自启动状态失败: {}，假设为未启用", e);
         false
     });
+    if launch_at_startup == current_autostart_enabled {
+        return AutostartStatus::Ok;
+    }
 
-    if new_settings.launch_at_startup != current_autostart_enabled {
-        if new_settings.launch_at_startup {
-            if !can_enable_autostart_for_current_build() {
-                return Err("Debug 构建禁止启用开机自启动，请使用正式版启用该功能".to_string());
+    let result = if launch_at_startup {
+        if !can_enable_autostart_for_current_build() {
+            return AutostartStatus::Unsupported {
+                reason: "Debug 构建禁止启用开机自启动，请使用正式版启用该功能".to_string(),
+            };
+        }
+        autostart_manager.enable().inspect(|_| {
+            set_autostart_notification_flag_if_needed(app, "settings");
+        })
+    } else {
+        autostart_manager.disable()
+    };
+    match result {
+        Ok(()) => AutostartStatus::Ok,
+        Err(e) => {
+            warn!(target: "settings", "修改开机自启动失败: {}，其余设置照常保存", e);
+            AutostartStatus::Failed {
+                reason: e.to_string(),
             }
-
-            autostart_manager
-                .enable()
-                .map_err(|e| format!("启用开机自启动失败: {}", e))?;
-
-            set_autostart_notification_flag_if_needed(&app, "settings");
-        } else {
-            autostart_manager
-                .disable()
-                .map_err(|e| format!("禁用开机自启动失败: {}", e))?;
         }
     }
+}
 
-    *settings = new_settings.clone();
-    drop(settings);
-
-    {
-        let mut wallpaper_dir = state.wallpaper_directory.lock().await;
-        if let Some(ref new_dir) = new_settings.save_directory {
-            *wallpaper_dir = PathBuf::from(new_dir);
-        } else {
-            *wallpaper_dir =
-                storage::get_default_wallpaper_directory().map_err(|e| e.to_string())?;
-        }
+/// 校验并应用新设置：处理自启动、壁纸目录与 IndexManager 切换、持久化和广播
+///
+/// 自启动修改失败不会中断保存：保存的 `launch_at_startup` 保持为系统实际状态，结果通过返回值告知调用方。
+/// `update_settings` 与切换配置方案共用此流程。调用方需持有设置写入锁
+/// （`SettingsService::lock_writes`），并发调用因此按顺序执行，提交后的副作用（托盘、主题等）不会交错。
+pub(crate) async fn apply_settings(
+    new_settings: AppSettings,
+    state: &AppState,
+    app: &tauri::AppHandle,
+) -> Result<AutostartStatus, UpdateSettingsError> {
+    // 管理员锁定的设置不允许修改，直接覆盖为策略值
+    let new_settings = crate::policy::current()
+        .enforce(&new_settings)
+        .unwrap_or_else(|e| {
+            warn!(target: "settings", "应用设置策略失败: {}，忽略策略", e);
+            new_settings
+        });
+    let fields = new_settings.validate();
+    if !fields.is_empty() {
+        warn!(target: "settings", "设置校验失败: {:?}", fields);
+        return Err(UpdateSettingsError::Validation { fields });
     }
 
-    settings_store::save_settings(&app, &new_settings)
-        .map_err(|e| format!("保存设置到 store 失败: {}", e))?;
+    let new_dir = match new_settings.save_directory {
+        Some(ref new_dir) => PathBuf::from(new_dir),
+        None => storage::get_default_wallpaper_directory().map_err(|e| e.to_string())?,
+    };
+
+    // 持有目录锁直到切换完成，设置广播触发的更新循环不会读到旧目录
+    let mut wallpaper_dir = state.wallpaper_directory.lock().await;
+    let mut autostart = AutostartStatus::Ok;
+    let change = state
+        .settings
+        .update(app, |settings| {
+            autostart = sync_autostart(app, new_settings.launch_at_startup);
+            let mut new_settings = new_settings;
+            if autostart != AutostartStatus::Ok {
+                // 系统状态没有改变，仍是修改前的值
+                new_settings.launch_at_startup = !new_settings.launch_at_startup;
+            }
+            *settings = new_settings;
+            Ok::<_, UpdateSettingsError>(())
+        })
+        .await?;
+    let old_wallpaper_dir = std::mem::replace(&mut *wallpaper_dir, new_dir);
+    let dir_changed = old_wallpaper_dir != *wallpaper_dir;
+    drop(wallpaper_dir);
+    let old_settings = change.previous;
+    let new_settings = change.current;
+
+    // 保存目录变更后释放旧目录的 IndexManager 缓存
+    if dir_changed {
+        info!(target: "settings", "壁纸目录已变更，释放旧目录索引缓存: {}", old_wallpaper_dir.display());
+        storage::remove_index_manager(&old_wallpaper_dir);
+    }
 
-    state
-        .settings_tx
-        .send(new_settings.clone())
-        .map_err(|e| format!("广播设置失败: {e}"))?;
+    // 通知前端各视图具体变化了哪些设置，避免重新拉取全部设置后再自行比较
+    let changes = old_settings.diff(&new_settings);
+    if !changes.is_empty()
+        && let Err(e) = events::SETTINGS_CHANGED.emit(app, &changes)
+    {
+        warn!(target: "settings", "发送 settings-changed 事件失败: {}", e);
+    }
 
-    if new_settings.mkt != old_mkt {
-        info!(target: "settings", "mkt 从 {} 切换到 {}，清空 last_actual_mkt", old_mkt, new_settings.mkt);
+    if new_settings.mkt != old_settings.mkt {
+        info!(target: "settings", "mkt 从 {} 切换到 {}，清空 last_actual_mkt", old_settings.mkt, new_settings.mkt);
         *state.last_actual_mkt.lock().await = None;
-        if let Ok(mut runtime_state) = runtime_state::load_runtime_state(&app) {
+        if let Err(e) = runtime_state::modify_runtime_state(app, |runtime_state| {
             runtime_state.last_actual_mkt = None;
-            if let Err(e) = runtime_state::save_runtime_state(&app, &runtime_state) {
-                warn!(target: "settings", "持久化清空 last_actual_mkt 失败: {}", e);
-            }
+            true
+        }) {
+            warn!(target: "settings", "持久化清空 last_actual_mkt 失败: {}", e);
         }
     }
 
-    if new_settings.language != old_language {
-        info!(target: "settings", "语言从 {} 切换到 {}，更新托盘菜单", old_language, new_settings.language);
+    if new_settings.tray_left_click != old_settings.tray_left_click {
+        info!(target: "settings", "托盘左键行为切换为 {}", new_settings.tray_left_click);
+        tray::apply_left_click_behavior(app, &new_settings.tray_left_click).await;
+    }
+
+    if new_settings.theme != old_settings.theme {
+        wallpaper_theme::on_theme_changed(app, &new_settings.theme).await;
+    }
+
+    if new_settings.language != old_settings.language {
+        info!(target: "settings", "语言从 {} 切换到 {}，更新托盘菜单", old_settings.language, new_settings.language);
         let app_clone = app.clone();
         tauri::async_runtime::spawn(async move {
             if let Err(e) = tray::update_tray_menu(&app_clone).await {
@@ -182,5 +285,5 @@ pub(crate) async fn update_settings(
         });
     }
 
-    Ok(())
+    Ok(autostart)
 }
@@ -1,4 +1,8 @@
-use crate::{AppState, index_manager, models::WallpaperIndex, storage};
+use crate::models::{
+    BandwidthRange, BandwidthStats, ExpandedWallpaperIndex, UpdateState, WallpaperIndex,
+};
+use crate::utils::{self, TimestampFormat};
+use crate::{AppState, directory_permission, events, index_manager, runtime_state, storage};
 use chrono::Local;
 use serde::Serialize;
 
@@ -33,16 +37,36 @@ pub(crate) async fn get_default_wallpaper_directory() -> Result<String, String>
         .map(|p| p.to_string_lossy().to_string())
 }
 
+/// 获取指定时间范围内的图片下载流量统计
+#[tauri::command]
+pub(crate) async fn get_bandwidth_stats(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    range: BandwidthRange,
+) -> Result<BandwidthStats, String> {
+    let runtime_state = runtime_state::load_runtime_state(&app).map_err(|e| e.to_string())?;
+    Ok(runtime_state.bandwidth_stats(range, state.clock.now().date_naive()))
+}
+
 /// 获取最后一次成功更新时间（本地时区）
 /// 优先从内存状态读取，如果为空则从索引文件读取
+///
+/// `format` 缺省为按界面语言格式化的展示文本；传 `"rfc3339"` 获取可解析的原始时间。
 #[tauri::command]
 pub(crate) async fn get_last_update_time(
     state: tauri::State<'_, AppState>,
+    format: Option<TimestampFormat>,
 ) -> Result<Option<String>, String> {
+    let format = format.unwrap_or_default();
+    let resolved_language = state.settings.read().await.resolved_language;
     {
         let guard = state.last_update_time.lock().await;
         if let Some(dt) = *guard {
-            return Ok(Some(dt.format("%Y-%m-%d %H:%M:%S").to_string()));
+            return Ok(Some(utils::format_timestamp(
+                &dt,
+                format,
+                &resolved_language,
+            )));
         }
     }
 
@@ -55,7 +79,11 @@ pub(crate) async fn get_last_update_time(
     match index_manager.load_index().await {
         Ok(index) => {
             let local_time = index.last_updated.with_timezone(&Local);
-            Ok(Some(local_time.format("%Y-%m-%d %H:%M:%S").to_string()))
+            Ok(Some(utils::format_timestamp(
+                &local_time,
+                format,
+                &resolved_language,
+            )))
         }
         Err(_) => Ok(None),
     }
@@ -65,14 +93,22 @@ pub(crate) async fn get_last_update_time(
 pub(crate) async fn get_update_in_progress(
     state: tauri::State<'_, AppState>,
 ) -> Result<bool, String> {
-    let flag = state.update_in_progress.lock().await;
-    Ok(*flag)
+    Ok(state.update_state.lock().await.is_busy())
+}
+
+/// 获取更新循环当前所处的阶段及本轮的阶段记录
+#[tauri::command]
+pub(crate) async fn get_update_state(
+    state: tauri::State<'_, AppState>,
+) -> Result<UpdateState, String> {
+    Ok(state.update_state.lock().await.clone())
 }
 
 /// 确保壁纸目录存在
 #[tauri::command]
 pub(crate) async fn ensure_wallpaper_directory_exists(
     state: tauri::State<'_, AppState>,
+    app: tauri::AppHandle,
 ) -> Result<(), String> {
     let wallpaper_dir = {
         let dir = state.wallpaper_directory.lock().await;
@@ -81,7 +117,12 @@ pub(crate) async fn ensure_wallpaper_directory_exists(
 
     storage::ensure_wallpaper_directory(&wallpaper_dir)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| {
+            if directory_permission::is_permission_error(&e) {
+                directory_permission::report(&app, &wallpaper_dir, &e);
+            }
+            e.to_string()
+        })
 }
 
 /// 获取当前壁纸目录（用户自定义或默认）
@@ -110,6 +151,90 @@ pub(crate) async fn get_wallpaper_data_stats(
     Ok(build_wallpaper_data_stats(&index))
 }
 
+/// 获取当前 index.json 的结构版本号
+#[tauri::command]
+pub(crate) fn get_index_schema_version() -> u32 {
+    WallpaperIndex::VERSION
+}
+
+/// 把索引序列化为导出用的 JSON
+///
+/// `long_field_names` 为 true 时使用完整字段名（title/copyright/...），否则与 index.json 格式一致。
+fn serialize_index_export(
+    index: &WallpaperIndex,
+    pretty: bool,
+    long_field_names: bool,
+) -> serde_json::Result<Vec<u8>> {
+    match (long_field_names, pretty) {
+        (true, true) => serde_json::to_vec_pretty(&ExpandedWallpaperIndex::from(index)),
+        (true, false) => serde_json::to_vec(&ExpandedWallpaperIndex::from(index)),
+        (false, true) => serde_json::to_vec_pretty(index),
+        (false, false) => serde_json::to_vec(index),
+    }
+}
+
+/// 导出当前壁纸目录的索引到指定文件
+#[tauri::command]
+pub(crate) async fn export_index_json(
+    path: String,
+    pretty: bool,
+    long_field_names: Option<bool>,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let target = std::path::PathBuf::from(path);
+    if !target.parent().is_some_and(|parent| parent.is_dir()) {
+        return Err("NOT_DIRECTORY".to_string());
+    }
+
+    let wallpaper_dir = state.wallpaper_directory.lock().await.clone();
+    let index = storage::get_index_snapshot(&wallpaper_dir)
+        .await
+        .map_err(|e| e.to_string())?;
+    let bytes = serialize_index_export(&index, pretty, long_field_names.unwrap_or(false))
+        .map_err(|e| e.to_string())?;
+    tokio::fs::write(&target, bytes)
+        .await
+        .map_err(|e| format!("Failed to write index export: {e}"))
+}
+
+/// 获取当前壁纸目录中已有的索引备份日期（YYYYMMDD，按日期降序）
+#[tauri::command]
+pub(crate) async fn get_index_backups(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    let wallpaper_dir = state.wallpaper_directory.lock().await.clone();
+    storage::list_index_backups(&wallpaper_dir)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 用 `backups/index-backup-<date>.json` 替换当前索引，返回恢复的壁纸条目数
+///
+/// 错误码：INVALID_DATE、BACKUP_NOT_FOUND、READ_ONLY_DIRECTORY。
+#[tauri::command]
+pub(crate) async fn restore_index_backup(
+    date: String,
+    state: tauri::State<'_, AppState>,
+    app: tauri::AppHandle,
+) -> Result<usize, String> {
+    // date 会拼接为文件名，必须是 YYYYMMDD
+    if date.len() != 8 || !date.bytes().all(|b| b.is_ascii_digit()) {
+        return Err("INVALID_DATE".to_string());
+    }
+
+    let wallpaper_dir = state.wallpaper_directory.lock().await.clone();
+    if directory_permission::is_read_only_archive(&wallpaper_dir).await {
+        return Err(directory_permission::READ_ONLY_DIRECTORY.to_string());
+    }
+    let restored = storage::restore_index_backup(&wallpaper_dir, &date)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "BACKUP_NOT_FOUND".to_string())?;
+
+    let _ = events::WALLPAPER_UPDATED.emit(&app, &());
+    Ok(restored)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -122,6 +247,10 @@ mod tests {
             copyright_link: "https://example.com".to_string(),
             end_date: end_date.to_string(),
             urlbase: format!("/th?id=OHR.{}", title),
+            resolution: None,
+            portrait_available: None,
+            watermark_free: None,
+            recompression: None,
         }
     }
 
@@ -159,4 +288,20 @@ mod tests {
         assert_eq!(stats.earliest_end_date, None);
         assert_eq!(stats.latest_end_date, None);
     }
+
+    #[test]
+    fn test_serialize_index_export_variants() {
+        let mut index = WallpaperIndex::new();
+        index.upsert_wallpapers_for_mkt("zh-CN", vec![make_wallpaper("20240101", "First")]);
+
+        let compact = serialize_index_export(&index, false, false).unwrap();
+        let compact: serde_json::Value = serde_json::from_slice(&compact).unwrap();
+        assert_eq!(compact["mkt"]["zh-CN"]["20240101"]["t"], "First");
+
+        let expanded = serialize_index_export(&index, true, true).unwrap();
+        assert!(String::from_utf8_lossy(&expanded).contains('\n'));
+        let expanded: serde_json::Value = serde_json::from_slice(&expanded).unwrap();
+        assert_eq!(expanded["version"], WallpaperIndex::VERSION);
+        assert_eq!(expanded["mkt"]["zh-CN"]["20240101"]["title"], "First");
+    }
 }
@@ -0,0 +1,186 @@
+//! 壁纸标签
+//!
+//! 标签按 end_date 记录在索引的 `tags` 字段中（聚焦图片使用其索引键），所有 mkt 共享。
+//! 标签统一归一化为小写，按标签查询时优先返回当前 mkt 的元数据，缺失时回退到其他 mkt。
+
+use serde::Serialize;
+
+use crate::models::{LocalWallpaper, WallpaperIndex, normalize_tag};
+use crate::{AppState, directory_permission, get_effective_mkt, storage};
+
+/// 标签及其使用次数
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct TagCount {
+    pub tag: String,
+    pub count: usize,
+}
+
+/// 校验壁纸键：日期（YYYYMMDD）或聚焦图片的十六进制哈希
+fn validate_key(end_date: &str) -> Result<(), String> {
+    let valid = !end_date.is_empty()
+        && end_date.len() <= 64
+        && end_date.bytes().all(|b| b.is_ascii_hexdigit());
+    valid
+        .then_some(())
+        .ok_or_else(|| "INVALID_END_DATE".to_string())
+}
+
+fn parse_tag(tag: &str) -> Result<String, String> {
+    normalize_tag(tag).ok_or_else(|| "INVALID_TAG".to_string())
+}
+
+/// 带有指定标签的壁纸（按日期降序），每个日期优先取 `preferred_mkt` 的元数据
+fn wallpapers_with_tag(
+    index: &WallpaperIndex,
+    preferred_mkt: &str,
+    tag: &str,
+) -> Vec<LocalWallpaper> {
+    index
+        .keys_with_tag(tag)
+        .iter()
+        .filter_map(|key| {
+            index
+                .mkt
+                .get(preferred_mkt)
+                .and_then(|wallpapers| wallpapers.get(key))
+                .or_else(|| {
+                    index
+                        .mkt
+                        .values()
+                        .find_map(|wallpapers| wallpapers.get(key))
+                })
+                .cloned()
+        })
+        .collect()
+}
+
+/// 为壁纸添加标签，返回该壁纸当前的全部标签
+///
+/// 标签为空或超过 32 个字符时返回 "INVALID_TAG"，壁纸不在索引中时返回 "WALLPAPER_NOT_FOUND"。
+#[tauri::command]
+pub(crate) async fn add_tag(
+    end_date: String,
+    tag: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    validate_key(&end_date)?;
+    let tag = parse_tag(&tag)?;
+    let wallpaper_dir = state.wallpaper_directory.lock().await.clone();
+    if directory_permission::is_read_only_archive(&wallpaper_dir).await {
+        return Err(directory_permission::READ_ONLY_DIRECTORY.to_string());
+    }
+    let index = storage::get_index_snapshot(&wallpaper_dir)
+        .await
+        .map_err(|e| format!("Failed to load index: {}", e))?;
+    if !index
+        .mkt
+        .values()
+        .any(|wallpapers| wallpapers.contains_key(&end_date))
+    {
+        return Err("WALLPAPER_NOT_FOUND".to_string());
+    }
+    storage::add_tag(&wallpaper_dir, &end_date, &tag)
+        .await
+        .map_err(|e| format!("Failed to add tag: {}", e))
+}
+
+/// 移除壁纸的标签，返回该壁纸剩余的标签
+#[tauri::command]
+pub(crate) async fn remove_tag(
+    end_date: String,
+    tag: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    validate_key(&end_date)?;
+    let tag = parse_tag(&tag)?;
+    let wallpaper_dir = state.wallpaper_directory.lock().await.clone();
+    if directory_permission::is_read_only_archive(&wallpaper_dir).await {
+        return Err(directory_permission::READ_ONLY_DIRECTORY.to_string());
+    }
+    storage::remove_tag(&wallpaper_dir, &end_date, &tag)
+        .await
+        .map_err(|e| format!("Failed to remove tag: {}", e))
+}
+
+/// 获取带有指定标签的壁纸（按日期降序）
+#[tauri::command]
+pub(crate) async fn get_wallpapers_by_tag(
+    tag: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<LocalWallpaper>, String> {
+    let tag = parse_tag(&tag)?;
+    let wallpaper_dir = state.wallpaper_directory.lock().await.clone();
+    let mkt = get_effective_mkt(&state).await;
+    let index = storage::get_index_snapshot(&wallpaper_dir)
+        .await
+        .map_err(|e| format!("Failed to load index: {}", e))?;
+    Ok(wallpapers_with_tag(&index, &mkt, &tag))
+}
+
+/// 获取所有标签及使用次数（按标签名排序）
+#[tauri::command]
+pub(crate) async fn get_all_tags(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<TagCount>, String> {
+    let wallpaper_dir = state.wallpaper_directory.lock().await.clone();
+    let index = storage::get_index_snapshot(&wallpaper_dir)
+        .await
+        .map_err(|e| format!("Failed to load index: {}", e))?;
+    Ok(index
+        .tag_counts()
+        .into_iter()
+        .map(|(tag, count)| TagCount { tag, count })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_wallpaper(end_date: &str, title: &str) -> LocalWallpaper {
+        LocalWallpaper {
+            title: title.to_string(),
+            copyright: String::new(),
+            copyright_link: String::new(),
+            end_date: end_date.to_string(),
+            urlbase: String::new(),
+            resolution: None,
+            portrait_available: None,
+            watermark_free: None,
+            recompression: None,
+        }
+    }
+
+    #[test]
+    fn test_wallpapers_with_tag_prefers_current_mkt() {
+        let mut index = WallpaperIndex::new();
+        index.upsert_wallpapers_for_mkt(
+            "en-US",
+            vec![
+                make_wallpaper("20240101", "English 1"),
+                make_wallpaper("20240102", "English 2"),
+            ],
+        );
+        index.upsert_wallpapers_for_mkt("zh-CN", vec![make_wallpaper("20240102", "中文 2")]);
+        index.add_tag("20240101", "nature");
+        index.add_tag("20240102", "nature");
+        index.add_tag("20240102", "city");
+
+        let titles: Vec<_> = wallpapers_with_tag(&index, "zh-CN", "nature")
+            .into_iter()
+            .map(|wallpaper| wallpaper.title)
+            .collect();
+        assert_eq!(titles, ["中文 2", "English 1"]);
+        assert!(wallpapers_with_tag(&index, "zh-CN", "animals").is_empty());
+    }
+
+    #[test]
+    fn test_validate_key_and_tag() {
+        assert!(validate_key("20240102").is_ok());
+        assert!(validate_key("0123456789abcdef").is_ok());
+        assert!(validate_key("../index").is_err());
+        assert!(validate_key("").is_err());
+        assert_eq!(parse_tag("  City ").unwrap(), "city");
+        assert_eq!(parse_tag(" ").unwrap_err(), "INVALID_TAG");
+    }
+}
@@ -1,12 +1,17 @@
-use crate::models::{LocalWallpaper, MarketStatus};
+use crate::models::{
+    AppliedWallpaper, CurrentWallpaper, LocalWallpaper, LocalWallpaperPage, MarketStatus,
+    WallpaperDetails, WallpaperIndex, WallpaperMetadataRefreshed, WallpaperStatus,
+};
 use crate::{
-    AppState, download_manager, get_effective_mkt, runtime_state, storage, update_cycle,
-    wallpaper_manager,
+    AppState, bing_api, command_guard, directory_permission, download_manager, events,
+    extension_events, get_effective_mkt, runtime_state, safe_path, smart_crop, spotlight, storage,
+    update_cycle, utils, wallpaper_apply, wallpaper_manager, wallpaper_theme, wallpaper_transition,
 };
 use log::{error, info, warn};
 use std::path::Path;
 use std::path::PathBuf;
-use tauri::{Emitter, Manager};
+use tauri::Manager;
+use tokio::io::AsyncReadExt;
 
 /// 设置桌面壁纸（异步非阻塞）
 #[tauri::command]
@@ -21,34 +26,30 @@ pub(crate) async fn set_desktop_wallpaper(
         let dir = state.wallpaper_directory.lock().await;
         dir.clone()
     };
-    let base_dir_can = base_dir
-        .canonicalize()
-        .map_err(|e| format!("无法解析壁纸目录: {e}"))?;
+    let target_can = safe_path::resolve_in_dir(&base_dir, &path)
+        .map_err(|e| format!("目标文件不在壁纸目录下，拒绝设置: {e}"))?;
 
-    if !path.exists() {
+    if !target_can.exists() {
         info!(
             target: "wallpaper",
             "壁纸文件不存在，尝试按需下载: {}",
-            path.display()
+            target_can.display()
         );
         if let Err(e) =
-            download_manager::download_wallpaper_if_needed(&path, &base_dir_can, &app).await
+            download_manager::download_wallpaper_if_needed(&target_can, &base_dir, &app).await
         {
             return Err(format!("文件不存在且下载失败: {}", e));
         }
     }
-
-    let target_can = path
-        .canonicalize()
-        .map_err(|e| format!("无法解析目标路径: {e}"))?;
-
-    if !target_can.starts_with(&base_dir_can) {
-        return Err("目标文件不在壁纸目录下，拒绝设置".into());
-    }
     if !target_can.is_file() {
         return Err("目标文件不存在或不是普通文件".into());
     }
 
+    // 校验和按需下载成功后才登记请求，使之前尚未完成的点击全部失效（latest-wins）；
+    // 被拒绝的请求不会取代之前的有效请求
+    let apply_queue = state.wallpaper_apply_queue.clone();
+    let ticket = apply_queue.enqueue();
+
     let target_for_spawn = target_can.clone();
     let app_clone = app.clone();
     let (mkt_code, wallpaper_dir_for_record) = {
@@ -63,8 +64,20 @@ pub(crate) async fn set_desktop_wallpaper(
         .map(|s| s.to_string());
 
     tauri::async_runtime::spawn(async move {
+        let Some(_apply_guard) = apply_queue
+            .acquire(ticket, wallpaper_apply::APPLY_DEBOUNCE)
+            .await
+        else {
+            info!(
+                target: "wallpaper",
+                "壁纸设置请求已被更新的请求取代，跳过: {}",
+                target_for_spawn.display()
+            );
+            return;
+        };
+
         let screen_orientations = wallpaper_manager::get_screen_orientations();
-        let has_portrait_screen = screen_orientations.iter().any(|s| s.is_portrait);
+        let has_portrait_screen = wallpaper_manager::portrait_variant_wanted(&screen_orientations);
 
         let base_dir = target_for_spawn.parent().unwrap_or(Path::new(""));
         let portrait_file = target_for_spawn
@@ -117,8 +130,23 @@ pub(crate) async fn set_desktop_wallpaper(
             }
         }
 
-        if let Err(e) =
-            wallpaper_manager::set_wallpaper(&target_for_spawn, portrait_path.as_deref())
+        // 按需下载竖屏壁纸期间可能出现新的点击
+        if !apply_queue.is_latest(ticket) {
+            info!(
+                target: "wallpaper",
+                "壁纸设置请求已被更新的请求取代，跳过: {}",
+                target_for_spawn.display()
+            );
+            return;
+        }
+
+        let landscape_path = smart_crop::prepare_for_display(&target_for_spawn).await;
+        if let Err(e) = wallpaper_transition::set_wallpaper(
+            &app_clone,
+            &landscape_path,
+            portrait_path.as_deref(),
+        )
+        .await
         {
             error!(target: "wallpaper", "设置壁纸失败: {e}");
         } else {
@@ -127,22 +155,35 @@ pub(crate) async fn set_desktop_wallpaper(
             *current_path = Some(target_for_spawn.clone());
             drop(current_path);
 
-            let _ = app_clone.emit(
-                "current-wallpaper-changed",
-                target_for_spawn.to_string_lossy().to_string(),
-            );
+            let _ = events::CURRENT_WALLPAPER_CHANGED
+                .emit(&app_clone, &target_for_spawn.to_string_lossy());
+            wallpaper_theme::on_wallpaper_applied(&app_clone, &target_for_spawn);
+            extension_events::publish_wallpaper_changed(
+                &app_clone,
+                &target_for_spawn,
+                &mkt_code,
+                set_end_date.as_deref(),
+            )
+            .await;
+
+            if let Some(ref set_end_date) = set_end_date
+                && let Err(e) =
+                    runtime_state::record_applied_wallpaper(&app_clone, &mkt_code, set_end_date)
+            {
+                warn!(target: "wallpaper", "保存当前壁纸记录失败: {e}");
+            }
 
             if let Some(set_end_date) = set_end_date
                 && let Ok(latest_wallpapers) =
                     storage::get_local_wallpapers(&wallpaper_dir_for_record, &mkt_code).await
                 && let Some(latest) = latest_wallpapers.first()
             {
-                let mut runtime_state =
-                    runtime_state::load_runtime_state(&app_clone).unwrap_or_default();
-                runtime_state
-                    .manually_set_latest_wallpapers
-                    .insert(mkt_code.clone(), latest.end_date.clone());
-                if let Err(e) = runtime_state::save_runtime_state(&app_clone, &runtime_state) {
+                if let Err(e) = runtime_state::modify_runtime_state(&app_clone, |runtime_state| {
+                    runtime_state
+                        .manually_set_latest_wallpapers
+                        .insert(mkt_code.clone(), latest.end_date.clone());
+                    true
+                }) {
                     warn!(target: "wallpaper", "保存手动设置记录失败: {e}");
                 } else {
                     info!(target: "wallpaper",
@@ -185,17 +226,195 @@ pub(crate) async fn get_current_wallpaper_path(
     }
 }
 
+/// 获取当前 mkt 下最近一次由本应用设置的壁纸
+///
+/// 记录在运行时状态中持久化，重启后前端可据此高亮当前壁纸；本地文件已被删除时返回 `None`。
+#[tauri::command]
+pub(crate) async fn get_current_wallpaper(
+    state: tauri::State<'_, AppState>,
+    app: tauri::AppHandle,
+) -> Result<Option<CurrentWallpaper>, String> {
+    let mkt = get_effective_mkt(&state).await;
+    let wallpaper_dir = state.wallpaper_directory.lock().await.clone();
+    let runtime = runtime_state::load_runtime_state(&app)
+        .map_err(|e| format!("Failed to load runtime state: {}", e))?;
+
+    Ok(runtime.applied_wallpapers.get(&mkt).and_then(|end_date| {
+        let path = storage::get_wallpaper_path(&wallpaper_dir, end_date);
+        path.exists().then(|| CurrentWallpaper {
+            mkt: mkt.clone(),
+            end_date: end_date.clone(),
+            file_path: path.to_string_lossy().to_string(),
+        })
+    }))
+}
+
+/// 壁纸文件对应的存档键
+///
+/// 识别 `<日期>.jpg`、竖屏 `<日期>r.jpg`、超宽屏裁剪 `.derived/<日期>_<宽>x<高>.jpg` 和
+/// 聚焦图片 `spotlight/<ID>.jpg`；不在壁纸目录中的文件（包括过渡帧）返回 None。
+fn archive_key(wallpaper_dir: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(wallpaper_dir).ok()?;
+    let stem = relative.file_stem()?.to_str()?;
+    let parent = relative.parent()?;
+    let key = if parent.as_os_str().is_empty() {
+        stem.strip_suffix('r')
+            .filter(|date| date.len() == 8)
+            .unwrap_or(stem)
+    } else if parent == Path::new(smart_crop::DERIVED_DIR) {
+        stem.split_once('_')?.0
+    } else if parent == Path::new(spotlight::SPOTLIGHT_DIR) {
+        stem
+    } else {
+        return None;
+    };
+    Some(key.to_string())
+}
+
+/// 获取各显示器实际显示的壁纸，并标出与本应用最近设置的壁纸不一致的显示器
+///
+/// 系统无法提供按显示器的查询时返回空列表。
+#[tauri::command]
+pub(crate) async fn get_applied_wallpapers(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<AppliedWallpaper>, String> {
+    let screens = tokio::task::spawn_blocking(wallpaper_manager::get_applied_wallpapers)
+        .await
+        .map_err(|e| format!("Failed to query wallpapers: {}", e))?;
+    let wallpaper_dir = state.wallpaper_directory.lock().await.clone();
+    // 系统返回的路径已展开符号链接，壁纸目录也需规范化后才能比较
+    let wallpaper_dir = wallpaper_dir.canonicalize().unwrap_or(wallpaper_dir);
+    let current = state
+        .current_wallpaper_path
+        .lock()
+        .await
+        .as_deref()
+        .and_then(|path| archive_key(&wallpaper_dir, &path.canonicalize().ok()?));
+
+    Ok(screens
+        .into_iter()
+        .enumerate()
+        .map(|(screen_index, path)| {
+            let end_date = path
+                .as_deref()
+                .and_then(|path| archive_key(&wallpaper_dir, path));
+            AppliedWallpaper {
+                screen_index,
+                file_path: path.map(|path| path.to_string_lossy().to_string()),
+                matches_current: end_date.is_some() && end_date == current,
+                end_date,
+            }
+        })
+        .collect())
+}
+
 /// 获取已下载的壁纸列表
 #[tauri::command]
 pub(crate) async fn get_local_wallpapers(
     state: tauri::State<'_, AppState>,
     app: tauri::AppHandle,
 ) -> Result<Vec<LocalWallpaper>, String> {
+    load_local_wallpapers(&state, &app, 0, None, None)
+        .await
+        .map(|page| page.wallpapers)
+}
+
+/// 分页获取已下载的壁纸列表（按日期降序，与 `get_local_wallpapers` 顺序一致）
+///
+/// `mkt` 为空时读取当前生效的 mkt（无数据时回退到索引中可用的 mkt），
+/// 指定 `mkt` 时只读取该 mkt，不回退也不更新 mkt 状态。
+/// 只检查当前页的图片文件是否缺失。
+#[tauri::command]
+pub(crate) async fn get_local_wallpapers_page(
+    offset: usize,
+    limit: usize,
+    mkt: Option<String>,
+    state: tauri::State<'_, AppState>,
+    app: tauri::AppHandle,
+) -> Result<LocalWallpaperPage, String> {
+    let mkt = mkt
+        .map(|mkt| utils::normalize_mkt_case(mkt.trim()))
+        .filter(|mkt| !mkt.is_empty());
+    load_local_wallpapers(&state, &app, offset, Some(limit), mkt).await
+}
+
+/// 读取一页本地壁纸，并为缺失的图片触发重新下载
+async fn load_local_wallpapers(
+    state: &AppState,
+    app: &tauri::AppHandle,
+    offset: usize,
+    limit: Option<usize>,
+    requested_mkt: Option<String>,
+) -> Result<LocalWallpaperPage, String> {
     let wallpaper_dir = state.wallpaper_directory.lock().await.clone();
 
-    let mkt = get_effective_mkt(&state).await;
+    let page = match requested_mkt {
+        Some(mkt) => read_wallpaper_page(&wallpaper_dir, mkt, offset, limit).await?,
+        None => read_effective_wallpaper_page(state, app, &wallpaper_dir, offset, limit).await?,
+    };
+
+    let mut missing_wallpapers = Vec::new();
+    for wallpaper in &page.wallpapers {
+        let path = storage::get_wallpaper_path(&wallpaper_dir, &wallpaper.end_date);
+        if !path.exists() {
+            warn!(target: "commands", "壁纸文件不存在，将触发重新下载: {}", path.display());
+            missing_wallpapers.push(wallpaper.clone());
+        }
+    }
+
+    if !missing_wallpapers.is_empty() {
+        warn!(
+            target: "commands",
+            "发现 {} 个缺失的壁纸文件，将触发重新下载",
+            missing_wallpapers.len()
+        );
+        let wallpaper_dir_clone = wallpaper_dir.clone();
+        let app_clone = app.clone();
+        tauri::async_runtime::spawn(async move {
+            update_cycle::redownload_missing_wallpapers(
+                missing_wallpapers,
+                wallpaper_dir_clone,
+                app_clone,
+            )
+            .await;
+        });
+    }
+
+    Ok(page)
+}
+
+async fn read_wallpaper_page(
+    wallpaper_dir: &Path,
+    mkt: String,
+    offset: usize,
+    limit: Option<usize>,
+) -> Result<LocalWallpaperPage, String> {
+    let (wallpapers, total) =
+        storage::get_local_wallpapers_page(wallpaper_dir, &mkt, offset, limit)
+            .await
+            .map_err(|e| {
+                error!(target: "commands", "获取本地壁纸列表失败: {}", e);
+                e.to_string()
+            })?;
+    Ok(LocalWallpaperPage {
+        wallpapers,
+        total,
+        offset,
+        mkt,
+    })
+}
+
+/// 读取当前生效 mkt 的壁纸（无数据时回退到索引中可用的 mkt），并同步 mkt 状态
+async fn read_effective_wallpaper_page(
+    state: &AppState,
+    app: &tauri::AppHandle,
+    wallpaper_dir: &Path,
+    offset: usize,
+    limit: Option<usize>,
+) -> Result<LocalWallpaperPage, String> {
+    let mkt = get_effective_mkt(state).await;
     let (settings_mkt, resolved_language) = {
-        let settings = state.settings.lock().await;
+        let settings = state.settings.read().await;
         (settings.mkt.clone(), settings.resolved_language.clone())
     };
 
@@ -206,17 +425,10 @@ pub(crate) async fn get_local_wallpapers(
         wallpaper_dir.display()
     );
 
-    let mut wallpapers = storage::get_local_wallpapers(&wallpaper_dir, &mkt)
-        .await
-        .map_err(|e| {
-            error!(target: "commands", "获取本地壁纸列表失败: {}", e);
-            e.to_string()
-        })?;
-
-    let mut actual_read_mkt = mkt.clone();
+    let mut page = read_wallpaper_page(wallpaper_dir, mkt.clone(), offset, limit).await?;
 
-    if wallpapers.is_empty()
-        && let Ok(available_keys) = storage::get_available_mkt_keys(&wallpaper_dir).await
+    if page.total == 0
+        && let Ok(available_keys) = storage::get_available_mkt_keys(wallpaper_dir).await
         && !available_keys.is_empty()
     {
         let fallback_mkt = if available_keys.contains(&settings_mkt) {
@@ -233,16 +445,11 @@ pub(crate) async fn get_local_wallpapers(
                 "mkt fallback: effective_mkt={} 无数据，回退到 index 中可用的 mkt={}（可用 keys: {:?}）",
                 mkt, fallback_mkt, available_keys
             );
-            wallpapers = storage::get_local_wallpapers(&wallpaper_dir, &fallback_mkt)
-                .await
-                .map_err(|e| {
-                    error!(target: "commands", "fallback 获取本地壁纸列表失败: {}", e);
-                    e.to_string()
-                })?;
-            actual_read_mkt = fallback_mkt;
+            page = read_wallpaper_page(wallpaper_dir, fallback_mkt, offset, limit).await?;
         }
     }
 
+    let actual_read_mkt = page.mkt.clone();
     let old_effective = {
         let guard = state.last_actual_mkt.lock().await;
         guard.clone().unwrap_or_else(|| settings_mkt.clone())
@@ -258,33 +465,32 @@ pub(crate) async fn get_local_wallpapers(
     if old_effective != actual_read_mkt {
         *state.last_actual_mkt.lock().await = new_actual_mkt.clone();
 
-        if let Ok(mut runtime_state) = runtime_state::load_runtime_state(&app) {
+        if let Err(e) = runtime_state::modify_runtime_state(app, |runtime_state| {
             runtime_state.last_actual_mkt = new_actual_mkt;
-            if let Err(e) = runtime_state::save_runtime_state(&app, &runtime_state) {
-                warn!(target: "commands", "持久化同步 last_actual_mkt 失败: {}", e);
-            }
+            true
+        }) {
+            warn!(target: "commands", "持久化同步 last_actual_mkt 失败: {}", e);
         }
     }
 
     if new_mismatch != old_mismatch {
-        let status = MarketStatus {
-            requested_mkt: settings_mkt.clone(),
-            effective_mkt: actual_read_mkt.clone(),
-            is_mismatch: new_mismatch,
-        };
-        if let Err(e) = app.emit("mkt-status-changed", &status) {
+        let is_degraded = runtime_state::market_health(app, &settings_mkt).is_degraded();
+        let status = MarketStatus::new(settings_mkt.clone(), actual_read_mkt.clone())
+            .with_degraded(is_degraded);
+        if let Err(e) = events::MKT_STATUS_CHANGED.emit(app, &status) {
             warn!(target: "commands", "发送 mkt-status-changed 事件失败: {}", e);
         }
     }
 
     info!(
         target: "commands",
-        "成功获取 {} 张本地壁纸（mkt: {}）",
-        wallpapers.len(),
+        "成功获取 {} 张本地壁纸（共 {} 张，mkt: {}）",
+        page.wallpapers.len(),
+        page.total,
         actual_read_mkt
     );
 
-    if wallpapers.is_empty() {
+    if page.total == 0 {
         warn!(
             target: "commands",
             "当前 mkt ({}) 的壁纸列表为空（fallback 后仍无数据），将触发异步更新",
@@ -296,32 +502,380 @@ pub(crate) async fn get_local_wallpapers(
         });
     }
 
-    let mut missing_wallpapers = Vec::new();
-    for wallpaper in &wallpapers {
-        let path = storage::get_wallpaper_path(&wallpaper_dir, &wallpaper.end_date);
-        if !path.exists() {
-            warn!(target: "commands", "壁纸文件不存在，将触发重新下载: {}", path.display());
-            missing_wallpapers.push(wallpaper.clone());
+    Ok(page)
+}
+
+/// 解析图片尺寸时最多读取的文件头字节数（SOF 通常位于 EXIF 等 APP 段之后）
+const IMAGE_HEADER_READ_LIMIT: u64 = 256 * 1024;
+
+/// 在索引中查找指定日期的壁纸，优先使用当前 mkt，找不到时再查找其他 mkt
+fn find_wallpaper_in_index(
+    index: &WallpaperIndex,
+    preferred_mkt: &str,
+    end_date: &str,
+) -> Option<(String, LocalWallpaper)> {
+    index
+        .mkt
+        .get(preferred_mkt)
+        .and_then(|wallpapers| wallpapers.get(end_date))
+        .map(|wallpaper| (preferred_mkt.to_string(), wallpaper.clone()))
+        .or_else(|| {
+            index.mkt.iter().find_map(|(mkt, wallpapers)| {
+                wallpapers
+                    .get(end_date)
+                    .map(|wallpaper| (mkt.clone(), wallpaper.clone()))
+            })
+        })
+}
+
+/// 读取 JPEG 文件头并解析尺寸
+async fn read_image_dimensions(path: &Path) -> Option<(u32, u32)> {
+    let file = tokio::fs::File::open(path).await.ok()?;
+    let mut header = Vec::new();
+    file.take(IMAGE_HEADER_READ_LIMIT)
+        .read_to_end(&mut header)
+        .await
+        .ok()?;
+    crate::utils::jpeg_dimensions(&header)
+}
+
+/// 获取单张壁纸的详情
+///
+/// 汇总索引元数据与本地文件信息（大小、尺寸、下载时间、竖屏版本），
+/// 前端详情面板只需一次调用。
+#[tauri::command]
+pub(crate) async fn get_wallpaper_details(
+    end_date: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<WallpaperDetails, String> {
+    // end_date 会拼接为文件名，必须是 YYYYMMDD
+    if utils::parse_end_date(&end_date).is_none() {
+        return Err("INVALID_END_DATE".to_string());
+    }
+
+    let wallpaper_dir = state.wallpaper_directory.lock().await.clone();
+    let mkt = get_effective_mkt(&state).await;
+
+    let index = storage::get_index_snapshot(&wallpaper_dir)
+        .await
+        .map_err(|e| format!("Failed to load index: {}", e))?;
+    let (mkt, wallpaper) = find_wallpaper_in_index(&index, &mkt, &end_date)
+        .ok_or_else(|| "WALLPAPER_NOT_FOUND".to_string())?;
+
+    let path = storage::get_wallpaper_path(&wallpaper_dir, &end_date);
+    let metadata = tokio::fs::metadata(&path).await.ok();
+    let dimensions = match metadata {
+        Some(_) => read_image_dimensions(&path).await,
+        None => None,
+    };
+    let downloaded_at = metadata
+        .as_ref()
+        .and_then(|m| m.modified().ok())
+        .map(|time| {
+            utils::format_rfc3339_timestamp(&chrono::DateTime::<chrono::Local>::from(time))
+        });
+
+    let source_url = (!wallpaper.urlbase.is_empty()).then(|| {
+        let resolution = wallpaper
+            .resolution
+            .as_deref()
+            .unwrap_or(download_manager::LANDSCAPE_RESOLUTION_LADDER[0]);
+        bing_api::get_wallpaper_url(&wallpaper.urlbase, resolution)
+    });
+
+    let has_portrait = wallpaper_dir.join(format!("{}r.jpg", end_date)).exists();
+    let tags = index.tags_for(&end_date);
+    let copyright_info = wallpaper.copyright_info();
+
+    Ok(WallpaperDetails {
+        end_date,
+        title: wallpaper.title,
+        copyright: wallpaper.copyright,
+        mkt,
+        file_path: path.to_string_lossy().to_string(),
+        file_size: metadata.as_ref().map(|m| m.len()),
+        original_file_size: wallpaper.recompression.map(|r| r.original_size),
+        width: dimensions.map(|(width, _)| width),
+        height: dimensions.map(|(_, height)| height),
+        resolution: wallpaper.resolution,
+        downloaded_at,
+        source_url,
+        has_portrait,
+        tags,
+        copyright_info,
+    })
+}
+
+/// 索引中包含指定日期的 mkt（按名称排序）
+fn mkts_containing(index: &WallpaperIndex, end_date: &str) -> Vec<String> {
+    let mut mkts: Vec<String> = index
+        .mkt
+        .iter()
+        .filter(|(_, wallpapers)| wallpapers.contains_key(end_date))
+        .map(|(mkt, _)| mkt.clone())
+        .collect();
+    mkts.sort();
+    mkts
+}
+
+/// 查询某一日期的壁纸在本地的状态
+///
+/// 一次返回索引记录、横屏/竖屏文件是否存在、文件大小及所在 mkt，不存在时也正常返回。
+#[tauri::command]
+pub(crate) async fn get_wallpaper_status(
+    end_date: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<WallpaperStatus, String> {
+    // end_date 会拼接为文件名，必须是 YYYYMMDD
+    if utils::parse_end_date(&end_date).is_none() {
+        return Err("INVALID_END_DATE".to_string());
+    }
+
+    let wallpaper_dir = state.wallpaper_directory.lock().await.clone();
+    let index = storage::get_index_snapshot(&wallpaper_dir)
+        .await
+        .map_err(|e| format!("Failed to load index: {}", e))?;
+    let mkt_list = mkts_containing(&index, &end_date);
+
+    let path = storage::get_wallpaper_path(&wallpaper_dir, &end_date);
+    let file_size = tokio::fs::metadata(&path)
+        .await
+        .ok()
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len());
+    let portrait_exists = tokio::fs::try_exists(wallpaper_dir.join(format!("{}r.jpg", end_date)))
+        .await
+        .unwrap_or(false);
+
+    Ok(WallpaperStatus {
+        in_index: !mkt_list.is_empty(),
+        file_exists: file_size.is_some(),
+        portrait_exists,
+        file_size,
+        mkt_list,
+    })
+}
+
+/// Bing API 可回溯的最大 idx（idx 为距今天数，超过后返回的始终是最旧一张）
+const MAX_BING_IDX: i64 = 7;
+
+/// 计算重新获取指定日期所需的请求窗口 (count, idx)
+///
+/// API 返回的日期已按本地时区调整，与本地日期相差 1 天以内时仍可能错位，
+/// 因此请求前后各多取一天，再按 end_date 精确匹配。超出 API 回溯范围时返回 None。
+fn metadata_refresh_window(end_date: &str, today: chrono::NaiveDate) -> Option<(u8, u8)> {
+    let date = utils::parse_end_date(end_date)?;
+    let days_ago = (today - date).num_days();
+    if !(0..=MAX_BING_IDX).contains(&days_ago) {
+        return None;
+    }
+    let idx = (days_ago - 1).clamp(0, MAX_BING_IDX);
+    Some((3, idx as u8))
+}
+
+/// 重新获取指定日期壁纸的标题和版权信息
+///
+/// Bing 有时会在发布后修正标题，只请求该日期附近的窗口并更新索引中的这一条，
+/// 已记录的分辨率、竖屏和无水印信息保持不变。
+/// 错误码：INVALID_END_DATE、INVALID_MKT、OUT_OF_RANGE、NOT_FOUND、MKT_MISMATCH、READ_ONLY_DIRECTORY。
+#[tauri::command]
+pub(crate) async fn refresh_metadata(
+    app: tauri::AppHandle,
+    end_date: String,
+    mkt: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<LocalWallpaper, String> {
+    if utils::parse_end_date(&end_date).is_none() {
+        return Err("INVALID_END_DATE".to_string());
+    }
+    let mkt = utils::normalize_mkt_case(mkt.trim());
+    if !utils::is_valid_mkt(&mkt) && !utils::is_well_formed_mkt(&mkt) {
+        return Err("INVALID_MKT".to_string());
+    }
+    let (count, idx) = metadata_refresh_window(&end_date, state.clock.now().date_naive())
+        .ok_or_else(|| "OUT_OF_RANGE".to_string())?;
+    if directory_permission::archive_read_only(&app).await {
+        return Err(directory_permission::READ_ONLY_DIRECTORY.to_string());
+    }
+
+    let result = state
+        .image_source
+        .fetch(count, idx, &mkt)
+        .await
+        .map_err(|e| {
+            warn!(target: "commands", "刷新壁纸元数据失败: end_date={}, mkt={}, 错误={}", end_date, mkt, e);
+            e.to_string()
+        })?;
+    let wallpaper = result
+        .images
+        .into_iter()
+        .find(|image| image.enddate == end_date)
+        .map(LocalWallpaper::from)
+        .ok_or_else(|| "NOT_FOUND".to_string())?;
+
+    let wallpaper_dir = state.wallpaper_directory.lock().await.clone();
+    let saved = storage::save_wallpapers_metadata(vec![wallpaper.clone()], &wallpaper_dir, &mkt)
+        .await
+        .map_err(|e| format!("保存元数据失败: {e}"))?;
+    if saved.validated == 0 {
+        return Err("MKT_MISMATCH".to_string());
+    }
+    info!(target: "commands", "已刷新 {} ({}) 的元数据: {}", end_date, mkt, wallpaper.title);
+
+    let refreshed = WallpaperMetadataRefreshed {
+        mkt,
+        wallpaper: wallpaper.clone(),
+    };
+    if let Err(e) = events::WALLPAPER_METADATA_REFRESHED.emit(&app, &refreshed) {
+        warn!(target: "commands", "通知前端失败: {e}");
+    }
+    Ok(wallpaper)
+}
+
+/// 将壁纸导出为常见比例（16:9、16:10、4:3、21:9、手机竖屏）的裁剪版本
+///
+/// 从壁纸目录中的原图裁剪，输出到用户选择的目录，返回生成的文件路径。
+/// 错误码：INVALID_END_DATE、NOT_DIRECTORY、SAME_DIRECTORY、WALLPAPER_NOT_FOUND、BUSY。
+#[tauri::command]
+pub(crate) async fn export_crops(
+    end_date: String,
+    dir: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    command_guard::guarded(
+        command_guard::EXPORT_CROPS,
+        export_crops_to(end_date, dir, &state),
+    )
+    .await
+}
+
+async fn export_crops_to(
+    end_date: String,
+    dir: String,
+    state: &AppState,
+) -> Result<Vec<String>, String> {
+    if utils::parse_end_date(&end_date).is_none() {
+        return Err("INVALID_END_DATE".to_string());
+    }
+    let output_dir = PathBuf::from(&dir);
+    if !output_dir.is_dir() {
+        return Err("NOT_DIRECTORY".to_string());
+    }
+
+    let wallpaper_dir = state.wallpaper_directory.lock().await.clone();
+    if safe_path::is_within(&wallpaper_dir, &output_dir) {
+        return Err("SAME_DIRECTORY".to_string());
+    }
+    let source = storage::get_wallpaper_path(&wallpaper_dir, &end_date);
+    if !source.exists() {
+        return Err("WALLPAPER_NOT_FOUND".to_string());
+    }
+
+    let outputs =
+        tokio::task::spawn_blocking(move || smart_crop::export_crops(&source, &output_dir))
+            .await
+            .map_err(|e| format!("导出裁剪任务异常: {e}"))?
+            .map_err(|e| format!("导出裁剪版本失败: {e}"))?;
+    info!(target: "wallpaper", "已导出 {} 的 {} 个裁剪版本到 {}", end_date, outputs.len(), dir);
+    Ok(outputs
+        .into_iter()
+        .map(|path| path.to_string_lossy().to_string())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_wallpaper(end_date: &str, title: &str) -> LocalWallpaper {
+        LocalWallpaper {
+            title: title.to_string(),
+            copyright: String::new(),
+            copyright_link: String::new(),
+            end_date: end_date.to_string(),
+            urlbase: String::new(),
+            resolution: None,
+            portrait_available: None,
+            watermark_free: None,
+            recompression: None,
         }
     }
 
-    if !missing_wallpapers.is_empty() {
-        warn!(
-            target: "commands",
-            "发现 {} 个缺失的壁纸文件，将触发重新下载",
-            missing_wallpapers.len()
+    fn make_index(entries: &[(&str, &str, &str)]) -> WallpaperIndex {
+        let mut index = WallpaperIndex::new();
+        for (mkt, end_date, title) in entries {
+            index
+                .mkt
+                .entry(mkt.to_string())
+                .or_default()
+                .insert(end_date.to_string(), make_wallpaper(end_date, title));
+        }
+        index
+    }
+
+    #[test]
+    fn test_archive_key_recognizes_wallpaper_variants() {
+        let dir = Path::new("/data/wallpapers");
+        let key = |relative: &str| archive_key(dir, &dir.join(relative));
+        assert_eq!(key("20240101.jpg").as_deref(), Some("20240101"));
+        assert_eq!(key("20240101r.jpg").as_deref(), Some("20240101"));
+        assert_eq!(
+            key(".derived/20240101_5120x1440.jpg").as_deref(),
+            Some("20240101")
         );
-        let wallpaper_dir_clone = wallpaper_dir.clone();
-        let app_clone = app.clone();
-        tauri::async_runtime::spawn(async move {
-            update_cycle::redownload_missing_wallpapers(
-                missing_wallpapers,
-                wallpaper_dir_clone,
-                app_clone,
-            )
-            .await;
-        });
+        assert_eq!(key("spotlight/abc123.jpg").as_deref(), Some("abc123"));
+        assert_eq!(key(".derived/transition/fade_1.jpg"), None);
+        assert_eq!(
+            archive_key(dir, Path::new("/usr/share/backgrounds/default.jpg")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_metadata_refresh_window() {
+        let today = chrono::NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        assert_eq!(metadata_refresh_window("20240310", today), Some((3, 0)));
+        assert_eq!(metadata_refresh_window("20240309", today), Some((3, 0)));
+        assert_eq!(metadata_refresh_window("20240305", today), Some((3, 4)));
+        assert_eq!(metadata_refresh_window("20240303", today), Some((3, 6)));
+        // 8 天前已超出 API 回溯范围
+        assert_eq!(metadata_refresh_window("20240302", today), None);
+        assert_eq!(metadata_refresh_window("20240311", today), None);
+        assert_eq!(metadata_refresh_window("2024031x", today), None);
+    }
+
+    #[test]
+    fn test_find_wallpaper_prefers_current_mkt() {
+        let index = make_index(&[
+            ("en-US", "20240101", "English"),
+            ("zh-CN", "20240101", "中文"),
+        ]);
+
+        let (mkt, wallpaper) = find_wallpaper_in_index(&index, "zh-CN", "20240101").unwrap();
+        assert_eq!(mkt, "zh-CN");
+        assert_eq!(wallpaper.title, "中文");
+    }
+
+    #[test]
+    fn test_find_wallpaper_falls_back_to_other_mkt() {
+        let index = make_index(&[("en-US", "20240101", "English")]);
+
+        let (mkt, wallpaper) = find_wallpaper_in_index(&index, "zh-CN", "20240101").unwrap();
+        assert_eq!(mkt, "en-US");
+        assert_eq!(wallpaper.title, "English");
+        assert!(find_wallpaper_in_index(&index, "zh-CN", "20240102").is_none());
     }
 
-    Ok(wallpapers)
+    #[test]
+    fn test_mkts_containing_lists_sorted_markets() {
+        let index = make_index(&[
+            ("zh-CN", "20240101", "中文"),
+            ("en-US", "20240101", "English"),
+            ("ja-JP", "20240102", "日本語"),
+        ]);
+
+        assert_eq!(mkts_containing(&index, "20240101"), ["en-US", "zh-CN"]);
+        assert_eq!(mkts_containing(&index, "20240102"), ["ja-JP"]);
+        assert!(mkts_containing(&index, "20240103").is_empty());
+    }
 }
@@ -0,0 +1,234 @@
+//! 壁纸目录权限错误的恢复
+//!
+//! 壁纸目录不可写（权限被收回、只读挂载等）时，更新循环在下载前探测到错误即停止，
+//! 并向前端发送 `directory-permission-error` 事件（同一目录只提示一次），避免反复失败重试。
+//! 用户确认后通过 `fallback_to_default_directory` 切回默认目录。
+//!
+//! 已有 index.json 但不可写的目录（如只读挂载的 NAS 共享存档）视为只读存档，进入浏览模式：
+//! 暂停下载和清理、不再提示回退，仍可浏览索引和设置壁纸；写入目录的命令返回 "READ_ONLY_DIRECTORY"。
+
+use anyhow::Context;
+use log::{info, warn};
+use serde::Serialize;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Manager};
+
+use crate::index_manager::INDEX_FILE;
+use crate::models::AppSettings;
+use crate::{AppState, commands, events, get_effective_mkt, policy, storage, tray, update_cycle};
+
+/// 在只读存档中执行写入操作时返回的错误码
+pub(crate) const READ_ONLY_DIRECTORY: &str = "READ_ONLY_DIRECTORY";
+
+/// 写入探测使用的临时文件名
+const PROBE_FILE: &str = ".write-probe";
+
+/// 已提示过权限错误的目录，避免每次更新循环重复弹出提示
+static REPORTED_DIRECTORY: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// 上一次检查时壁纸目录是否为只读存档（用于边沿触发日志）
+static READ_ONLY: AtomicBool = AtomicBool::new(false);
+
+/// `directory-permission-error` 事件内容
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct DirectoryPermissionError {
+    /// 无法写入的壁纸目录
+    pub directory: String,
+    /// 可以回退到的默认目录，None 表示无法回退（已是默认目录或被管理员锁定）
+    pub default_directory: Option<String>,
+    pub message: String,
+}
+
+/// 错误链中是否包含权限类 IO 错误
+pub(crate) fn is_permission_error(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        cause.downcast_ref::<std::io::Error>().is_some_and(|e| {
+            matches!(
+                e.kind(),
+                ErrorKind::PermissionDenied | ErrorKind::ReadOnlyFilesystem
+            )
+        })
+    })
+}
+
+/// 确保目录存在并可写（写入后立即删除一个探测文件）
+pub(crate) async fn ensure_writable(directory: &Path) -> anyhow::Result<()> {
+    storage::ensure_wallpaper_directory(directory).await?;
+    let probe = directory.join(PROBE_FILE);
+    tokio::fs::write(&probe, b"")
+        .await
+        .context("Failed to write to wallpaper directory")?;
+    let _ = tokio::fs::remove_file(&probe).await;
+    Ok(())
+}
+
+/// 目录是否为只读存档：已有索引，但因权限或只读文件系统无法写入
+pub(crate) async fn is_read_only_archive(directory: &Path) -> bool {
+    directory.join(INDEX_FILE).is_file()
+        && ensure_writable(directory)
+            .await
+            .is_err_and(|e| is_permission_error(&e))
+}
+
+/// 当前壁纸目录是否为只读存档（进入或离开浏览模式时记录日志）
+pub(crate) async fn archive_read_only(app: &AppHandle) -> bool {
+    let directory = app
+        .state::<AppState>()
+        .wallpaper_directory
+        .lock()
+        .await
+        .clone();
+    let read_only = is_read_only_archive(&directory).await;
+    if READ_ONLY.swap(read_only, Ordering::Relaxed) != read_only {
+        if read_only {
+            info!(
+                target: "storage",
+                "壁纸目录 {} 为只读存档，进入浏览模式（暂停下载和清理）",
+                directory.display()
+            );
+        } else {
+            info!(target: "storage", "壁纸目录已可写入，退出浏览模式");
+        }
+    }
+    read_only
+}
+
+/// 当前壁纸目录是否为只读存档（设置界面据此显示浏览模式提示）
+#[tauri::command]
+pub(crate) async fn get_archive_read_only(app: AppHandle) -> Result<bool, String> {
+    Ok(archive_read_only(&app).await)
+}
+
+/// 默认目录；当前已是默认目录或壁纸目录被管理员锁定时返回 None
+fn fallback_directory(current: &Path) -> Option<PathBuf> {
+    if policy::current().locked.contains_key("save_directory") {
+        return None;
+    }
+    storage::get_default_wallpaper_directory()
+        .ok()
+        .filter(|default| default != current)
+}
+
+/// 记录并通知目录权限错误（同一目录只通知一次）
+pub(crate) fn report(app: &AppHandle, directory: &Path, error: &anyhow::Error) {
+    {
+        let mut reported = REPORTED_DIRECTORY.lock().unwrap_or_else(|e| e.into_inner());
+        if reported.as_deref() == Some(directory) {
+            return;
+        }
+        *reported = Some(directory.to_path_buf());
+    }
+
+    warn!(target: "storage", "壁纸目录无写入权限 {}: {:#}", directory.display(), error);
+    let payload = DirectoryPermissionError {
+        directory: directory.to_string_lossy().to_string(),
+        default_directory: fallback_directory(directory)
+            .map(|dir| dir.to_string_lossy().to_string()),
+        message: format!("{error:#}"),
+    };
+    if let Err(e) = events::DIRECTORY_PERMISSION_ERROR.emit(app, &payload) {
+        warn!(target: "storage", "发送目录权限错误事件失败: {}", e);
+    }
+}
+
+/// 把壁纸目录切回默认目录（用户确认目录权限错误提示后调用）
+///
+/// 更新进行中返回 "UPDATE_IN_PROGRESS"；已是默认目录或目录被管理员锁定时返回 "NO_FALLBACK"。
+#[tauri::command]
+pub(crate) async fn fallback_to_default_directory(app: AppHandle) -> Result<String, String> {
+    let state = app.state::<AppState>();
+    if state.update_state.lock().await.is_busy() {
+        return Err("UPDATE_IN_PROGRESS".to_string());
+    }
+    let current_dir = state.wallpaper_directory.lock().await.clone();
+    let default_dir = fallback_directory(&current_dir).ok_or_else(|| "NO_FALLBACK".to_string())?;
+    ensure_writable(&default_dir)
+        .await
+        .map_err(|e| format!("默认目录不可用: {e:#}"))?;
+
+    let writes = state.settings.lock_writes().await;
+    let new_settings = AppSettings {
+        save_directory: None,
+        ..state.settings.read().await
+    };
+    commands::settings::apply_settings(new_settings, &state, &app)
+        .await
+        .map_err(|e| e.to_string())?;
+    drop(writes);
+    *REPORTED_DIRECTORY.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    info!(
+        target: "storage",
+        "壁纸目录已从 {} 切回默认目录 {}",
+        current_dir.display(),
+        default_dir.display()
+    );
+
+    let _ = events::WALLPAPER_UPDATED.emit(&app, &());
+    if let Err(e) = tray::update_tray_menu(&app).await {
+        warn!(target: "storage", "更新托盘菜单失败: {}", e);
+    }
+    let mkt = get_effective_mkt(&state).await;
+    update_cycle::try_trigger_update_if_empty(&app, &mkt).await;
+    Ok(default_dir.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_permission_error_walks_context_chain() {
+        let denied = anyhow::Error::new(std::io::Error::from(ErrorKind::PermissionDenied))
+            .context("Failed to create wallpaper directory");
+        assert!(is_permission_error(&denied));
+
+        let missing = anyhow::Error::new(std::io::Error::from(ErrorKind::NotFound))
+            .context("Failed to create wallpaper directory");
+        assert!(!is_permission_error(&missing));
+        assert!(!is_permission_error(&anyhow::anyhow!("network error")));
+    }
+
+    #[tokio::test]
+    async fn test_ensure_writable_creates_directory_without_leaving_probe() {
+        let unique = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("bw_permission_{unique}/wallpapers"));
+
+        ensure_writable(&dir).await.unwrap();
+        assert!(dir.is_dir());
+        assert!(!dir.join(PROBE_FILE).exists());
+
+        let _ = std::fs::remove_dir_all(dir.parent().unwrap());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_is_read_only_archive_requires_index_and_no_write_access() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let unique = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("bw_read_only_{unique}"));
+        std::fs::create_dir_all(&dir).unwrap();
+        assert!(!is_read_only_archive(&dir).await);
+
+        std::fs::write(dir.join(INDEX_FILE), b"{}").unwrap();
+        assert!(!is_read_only_archive(&dir).await);
+
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o555)).unwrap();
+        // root 不受目录权限限制，此时无法模拟只读目录
+        let probe_denied = std::fs::write(dir.join(PROBE_FILE), b"").is_err();
+        let _ = std::fs::remove_file(dir.join(PROBE_FILE));
+        assert_eq!(is_read_only_archive(&dir).await, probe_denied);
+
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,139 @@
+//! 壁纸目录磁盘空间检查
+//!
+//! UHD 壁纸单张可达数 MB，磁盘将满时继续下载会留下截断的图片并触发系统的磁盘空间警告。
+//! 每批后台下载前检查壁纸目录所在磁盘的可用空间，低于 `low_disk_space_threshold_mb` 时跳过下载，
+//! 向前端发送 `low-disk-space` 事件（进入空间不足状态时发送一次），下一次更新循环再重试。
+//!
+//! 阈值为 0 表示不检查；无法获取可用空间时视为空间充足，不影响下载。
+
+use log::{info, warn};
+use serde::Serialize;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Manager};
+
+use crate::{AppState, events};
+
+/// 上一次检查是否处于空间不足状态（用于边沿触发事件）
+static SPACE_LOW: AtomicBool = AtomicBool::new(false);
+
+/// `low-disk-space` 事件内容
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct LowDiskSpace {
+    /// 壁纸目录
+    pub directory: String,
+    /// 可用空间（字节）
+    pub available_bytes: u64,
+    /// 设置的阈值（字节）
+    pub threshold_bytes: u64,
+}
+
+/// 目录所在磁盘的可用空间（字节）；目录尚不存在时检查最近的已存在上级目录
+#[cfg(unix)]
+fn available_space(directory: &Path) -> Option<u64> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let existing = directory.ancestors().find(|p| p.exists())?;
+    let path = std::ffi::CString::new(existing.as_os_str().as_bytes()).ok()?;
+    // SAFETY: 结构体为纯数据，全零是合法的初始值
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: path 是以 NUL 结尾的有效字符串，stat 指向有效的可写内存
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    #[allow(clippy::unnecessary_cast)]
+    Some((stat.f_bavail as u64).saturating_mul(stat.f_frsize as u64))
+}
+
+#[cfg(windows)]
+fn available_space(directory: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let existing = directory.ancestors().find(|p| p.exists())?;
+    let wide: Vec<u16> = existing
+        .as_os_str()
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+    let mut available = 0u64;
+    // SAFETY: wide 以 NUL 结尾，available 指向有效的可写内存，其余输出参数允许为空
+    let ok = unsafe {
+        GetDiskFreeSpaceExW(
+            wide.as_ptr(),
+            &mut available,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    };
+    (ok != 0).then_some(available)
+}
+
+/// 阈值（MB）换算为字节，0 表示不检查
+fn threshold_bytes(threshold_mb: u64) -> Option<u64> {
+    (threshold_mb > 0).then(|| threshold_mb.saturating_mul(1024 * 1024))
+}
+
+/// 当前壁纸目录的可用空间是否低于阈值（进入空间不足状态时通知前端）
+pub(crate) async fn space_low(app: &AppHandle) -> bool {
+    let state = app.state::<AppState>();
+    let Some(threshold) = threshold_bytes(state.settings.read().await.low_disk_space_threshold_mb)
+    else {
+        return false;
+    };
+    let directory = state.wallpaper_directory.lock().await.clone();
+
+    let dir = directory.clone();
+    let available = match tokio::task::spawn_blocking(move || available_space(&dir)).await {
+        Ok(Some(available)) => available,
+        _ => return false,
+    };
+
+    if available >= threshold {
+        if SPACE_LOW.swap(false, Ordering::Relaxed) {
+            info!(target: "storage", "壁纸目录磁盘空间已恢复，继续下载");
+        }
+        return false;
+    }
+
+    if !SPACE_LOW.swap(true, Ordering::Relaxed) {
+        warn!(
+            target: "storage",
+            "壁纸目录 {} 磁盘可用空间不足（{} MB < {} MB），暂停下载",
+            directory.display(),
+            available / 1024 / 1024,
+            threshold / 1024 / 1024
+        );
+        let payload = LowDiskSpace {
+            directory: directory.to_string_lossy().to_string(),
+            available_bytes: available,
+            threshold_bytes: threshold,
+        };
+        if let Err(e) = events::LOW_DISK_SPACE.emit(app, &payload) {
+            warn!(target: "storage", "发送磁盘空间不足事件失败: {}", e);
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_threshold_zero_disables_check() {
+        assert_eq!(threshold_bytes(0), None);
+        assert_eq!(threshold_bytes(500), Some(500 * 1024 * 1024));
+    }
+
+    #[test]
+    fn test_available_space_uses_existing_ancestor() {
+        let temp = std::env::temp_dir();
+        let space = available_space(&temp);
+        assert!(space.is_some());
+        assert_eq!(
+            available_space(&temp.join("bw_disk_space_missing/nested")).is_some(),
+            space.is_some()
+        );
+    }
+}
@@ -1,23 +1,714 @@
 use anyhow::{Context, Result};
-use log::{error, info};
-use reqwest::Client;
+use log::{error, info, warn};
+use std::collections::HashMap;
 use std::path::Path;
-use std::sync::LazyLock;
-use std::time::Duration;
-use tauri::{AppHandle, Emitter, Manager};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
 
+use crate::events::{self, Event};
+use crate::http_client::{self, SharedClient};
+use crate::models::{ActiveDownload, DownloadFailure, LocalWallpaper, Recompression};
+
 /// 全局 HTTP 客户端，复用连接池
-static HTTP_CLIENT: LazyLock<Client> = LazyLock::new(|| {
-    Client::builder()
+static HTTP_CLIENT: SharedClient = SharedClient::new(|builder| {
+    builder
         .pool_max_idle_per_host(4)
         .tcp_nodelay(true)
         .user_agent("BingWallpaperNow/0.3.1")
-        .build()
-        .expect("Failed to create HTTP client")
 });
 
+/// 正在进行的下载（key 为下载编号），供 `get_active_downloads` 查询
+static ACTIVE_DOWNLOADS: LazyLock<Mutex<HashMap<u64, ActiveDownload>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+static NEXT_DOWNLOAD_ID: AtomicU64 = AtomicU64::new(1);
+/// 用于发送下载事件的 AppHandle（应用启动时设置；未设置时只记录状态不发事件）
+static EVENT_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+
+/// 设置下载事件的发送端（在应用 setup 中调用一次）
+pub(crate) fn attach_event_handle(app: &AppHandle) {
+    let _ = EVENT_HANDLE.set(app.clone());
+}
+
+/// 记录一次下载尝试的流量（未设置 AppHandle 时忽略）
+fn record_bandwidth(bytes: u64, elapsed: Duration) {
+    if let Some(app) = EVENT_HANDLE.get() {
+        let today = chrono::Local::now().date_naive();
+        if let Err(e) =
+            crate::runtime_state::record_bandwidth(app, today, bytes, elapsed.as_millis() as u64)
+        {
+            warn!(target: "download", "记录下载流量失败: {}", e);
+        }
+    }
+}
+
+fn emit_download_event<P: serde::Serialize>(event: &Event<P>, payload: P) {
+    if let Some(app) = EVENT_HANDLE.get() {
+        let _ = event.emit(app, &payload);
+    }
+}
+
+/// 从保存路径解析壁纸日期与横竖屏（`20240101.jpg` / `20240101r.jpg`）
+fn describe_save_path(save_path: &Path) -> (String, bool) {
+    let stem = save_path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default();
+    match stem.strip_suffix('r') {
+        Some(end_date) => (end_date.to_string(), true),
+        None => (stem.to_string(), false),
+    }
+}
+
+/// 单次下载的状态登记，析构时从活动列表中移除
+struct DownloadTracker {
+    id: u64,
+}
+
+impl DownloadTracker {
+    fn start(url: &str, save_path: &Path, max_attempts: usize) -> Self {
+        let (end_date, portrait) = describe_save_path(save_path);
+        let id = NEXT_DOWNLOAD_ID.fetch_add(1, Ordering::Relaxed);
+        let download = ActiveDownload {
+            end_date,
+            portrait,
+            url: url.to_string(),
+            downloaded_bytes: 0,
+            total_bytes: None,
+            progress: None,
+            attempt: 0,
+            max_attempts,
+        };
+        ACTIVE_DOWNLOADS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(id, download);
+        Self { id }
+    }
+
+    /// 修改登记的状态并返回修改后的快照
+    fn update(&self, f: impl FnOnce(&mut ActiveDownload)) -> Option<ActiveDownload> {
+        let mut downloads = ACTIVE_DOWNLOADS.lock().unwrap_or_else(|e| e.into_inner());
+        let download = downloads.get_mut(&self.id)?;
+        f(download);
+        Some(download.clone())
+    }
+
+    fn begin_attempt(&self, attempt: usize) {
+        if let Some(download) = self.update(|d| {
+            d.attempt = attempt;
+            d.downloaded_bytes = 0;
+            d.total_bytes = None;
+            d.progress = None;
+        }) {
+            emit_download_event(&events::DOWNLOAD_STARTED, download);
+        }
+    }
+
+    fn set_progress(&self, downloaded_bytes: u64, total_bytes: Option<u64>) {
+        self.update(|d| {
+            d.downloaded_bytes = downloaded_bytes;
+            d.total_bytes = total_bytes;
+            d.progress = total_bytes
+                .filter(|total| *total > 0)
+                .map(|total| (downloaded_bytes as f64 / total as f64).min(1.0));
+        });
+    }
+
+    fn finish(&self) {
+        if let Some(download) = self.update(|d| {
+            if let Some(total) = d.total_bytes {
+                d.downloaded_bytes = total;
+            }
+            d.progress = Some(1.0);
+        }) {
+            emit_download_event(&events::DOWNLOAD_FINISHED, download);
+        }
+    }
+
+    fn fail(&self, error: &anyhow::Error, will_retry: bool) {
+        if let Some(download) = self.update(|_| {}) {
+            emit_download_event(
+                &events::DOWNLOAD_FAILED,
+                DownloadFailure {
+                    download,
+                    error: format!("{:#}", error),
+                    will_retry,
+                },
+            );
+        }
+    }
+}
+
+impl Drop for DownloadTracker {
+    fn drop(&mut self) {
+        ACTIVE_DOWNLOADS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&self.id);
+    }
+}
+
+/// 当前正在进行的下载快照（按开始顺序）
+pub(crate) fn active_downloads() -> Vec<ActiveDownload> {
+    let downloads = ACTIVE_DOWNLOADS.lock().unwrap_or_else(|e| e.into_inner());
+    let mut entries: Vec<_> = downloads.iter().collect();
+    entries.sort_by_key(|(id, _)| **id);
+    entries
+        .into_iter()
+        .map(|(_, download)| download.clone())
+        .collect()
+}
+
+/// 获取正在进行的图片下载（前端下载面板使用）
+#[tauri::command]
+pub(crate) fn get_active_downloads() -> Vec<ActiveDownload> {
+    active_downloads()
+}
+
+/// 横屏壁纸的分辨率阶梯（按优先级从高到低）
+///
+/// 部分市场/日期的 UHD 资源会返回 404，此时依次降级，而不是整体下载失败。
+pub(crate) const LANDSCAPE_RESOLUTION_LADDER: &[&str] = &["UHD", "1920x1200", "1920x1080"];
+
+/// 1080p 及以下屏幕使用的横屏分辨率阶梯
+///
+/// 这类屏幕显示 UHD 并无收益，只会多占用带宽和磁盘。
+pub(crate) const FHD_RESOLUTION_LADDER: &[&str] = &["1920x1080"];
+
+/// 竖屏壁纸分辨率
+pub(crate) const PORTRAIT_RESOLUTION: &str = "1080x1920";
+
+/// 竖屏版本 HEAD 探测超时
+/// 索引中没有该日期的元数据（切换市场或重置后），重试也无法下载
+pub(crate) const METADATA_NOT_FOUND: &str = "METADATA_NOT_FOUND";
+
+const PORTRAIT_PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 服务器返回非成功状态码
+///
+/// 单独建模以便区分 404 等"资源不存在"的永久性错误与网络波动等可重试错误。
+#[derive(Debug)]
+pub struct HttpStatusError(pub reqwest::StatusCode);
+
+impl std::fmt::Display for HttpStatusError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Failed to download image: HTTP {}", self.0)
+    }
+}
+
+impl std::error::Error for HttpStatusError {}
+
+/// 单个图片文件大小上限（UHD 壁纸通常在 10 MB 以内，留足余量）
+const MAX_IMAGE_BYTES: u64 = 60 * 1024 * 1024;
+
+/// JPEG 文件头（SOI 标记 + 下一个标记的前缀）
+const JPEG_MAGIC: [u8; 3] = [0xFF, 0xD8, 0xFF];
+
+/// JPEG 文件尾（EOI 标记），CDN 截断的响应缺少该标记
+const JPEG_EOI: [u8; 2] = [0xFF, 0xD9];
+
+/// 在文件末尾多少字节内查找 EOI 标记
+///
+/// 部分编码器会在 EOI 之后追加填充字节或附加数据，不能只看最后两个字节；
+/// 熵编码数据中的 0xFF 之后只会是 0x00 或 RST 标记，因此这一范围内的 FF D9 只能是 EOI。
+const JPEG_TRAILER_WINDOW: usize = 512;
+
+/// 服务器返回的内容不是可接受的壁纸图片
+///
+/// 常见于强制门户（酒店/机场 Wi-Fi 登录页）劫持请求、返回 200 的 HTML 页面。
+/// 这类错误重试无意义，下载会立即中止且不留下任何文件。
+#[derive(Debug, PartialEq, Eq)]
+pub enum InvalidContentError {
+    /// Content-Type 不是图片（如 text/html）
+    ContentType(String),
+    /// 超过大小上限（声明的 Content-Length 或实际已接收的字节数）
+    TooLarge(u64),
+    /// 文件头不是 JPEG
+    NotJpeg,
+}
+
+impl std::fmt::Display for InvalidContentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ContentType(content_type) => {
+                write!(f, "Unexpected content type: {content_type}")
+            }
+            Self::TooLarge(size) => {
+                write!(
+                    f,
+                    "Image exceeds size limit: {size} > {MAX_IMAGE_BYTES} bytes"
+                )
+            }
+            Self::NotJpeg => f.write_str("Response is not a JPEG image"),
+        }
+    }
+}
+
+impl std::error::Error for InvalidContentError {}
+
+/// 下载的图片不完整或尺寸异常
+///
+/// 与 [`InvalidContentError`] 不同，这类错误通常是 CDN 传输中断或边缘节点缓存了异常文件，
+/// 会删除临时文件并重试。
+///
+/// Bing API 返回的 `hsh` 只是图片的标识，与实际下发的文件内容（随分辨率和 CDN 重新编码而变化）
+/// 没有对应关系，无法用来校验摘要，因此只能校验文件结构与尺寸。
+#[derive(Debug, PartialEq, Eq)]
+pub enum CorruptImageError {
+    /// 文件尾缺少 JPEG EOI 标记
+    Truncated,
+    /// 实际尺寸与请求的分辨率明显不符
+    UnexpectedDimensions {
+        actual: (u32, u32),
+        requested: (u32, u32),
+    },
+}
+
+impl std::fmt::Display for CorruptImageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Truncated => f.write_str("Image is truncated (missing JPEG EOI marker)"),
+            Self::UnexpectedDimensions { actual, requested } => write!(
+                f,
+                "Image is {}x{}, expected about {}x{}",
+                actual.0, actual.1, requested.0, requested.1
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CorruptImageError {}
+
+/// 校验 Content-Type：缺失或为 image/* / application/octet-stream 时放行
+fn check_content_type(content_type: Option<&str>) -> Result<(), InvalidContentError> {
+    let Some(content_type) = content_type else {
+        return Ok(());
+    };
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    if mime.starts_with("image/") || mime == "application/octet-stream" {
+        Ok(())
+    } else {
+        Err(InvalidContentError::ContentType(mime))
+    }
+}
+
+fn check_size(size: u64) -> Result<(), InvalidContentError> {
+    if size > MAX_IMAGE_BYTES {
+        Err(InvalidContentError::TooLarge(size))
+    } else {
+        Ok(())
+    }
+}
+
+fn check_jpeg_magic(head: &[u8]) -> Result<(), InvalidContentError> {
+    if head.starts_with(&JPEG_MAGIC) {
+        Ok(())
+    } else {
+        Err(InvalidContentError::NotJpeg)
+    }
+}
+
+/// 记录已接收数据的最后 [`JPEG_TRAILER_WINDOW`] 个字节（用于检查 JPEG 文件尾）
+fn update_tail(tail: &mut Vec<u8>, chunk: &[u8]) {
+    let keep = chunk.len().min(JPEG_TRAILER_WINDOW);
+    tail.extend_from_slice(&chunk[chunk.len() - keep..]);
+    let excess = tail.len().saturating_sub(JPEG_TRAILER_WINDOW);
+    tail.drain(..excess);
+}
+
+fn check_jpeg_trailer(tail: &[u8]) -> Result<(), CorruptImageError> {
+    if tail
+        .windows(JPEG_EOI.len())
+        .any(|window| window == JPEG_EOI)
+    {
+        Ok(())
+    } else {
+        Err(CorruptImageError::Truncated)
+    }
+}
+
+/// 从图片 URL 中解析请求的分辨率（`..._1920x1080.jpg`），UHD 等无固定尺寸时返回 None
+fn requested_dimensions(url: &str) -> Option<(u32, u32)> {
+    let (stem, _) = url.split_once(".jpg")?;
+    let (_, resolution) = stem.rsplit_once('_')?;
+    let (width, height) = resolution.split_once('x')?;
+    Some((width.parse().ok()?, height.parse().ok()?))
+}
+
+/// 检查实际尺寸是否与请求的分辨率相符
+///
+/// Bing 偶尔会对个别旧图返回略有差异的尺寸，因此只拒绝方向相反或任一边不足一半的图片
+/// （如 CDN 错误返回的缩略图或占位图）。
+fn check_dimensions(
+    actual: (u32, u32),
+    requested: Option<(u32, u32)>,
+) -> Result<(), CorruptImageError> {
+    let Some(requested) = requested else {
+        return Ok(());
+    };
+    let orientation_matches = (actual.0 >= actual.1) == (requested.0 >= requested.1);
+    if orientation_matches && actual.0 * 2 >= requested.0 && actual.1 * 2 >= requested.1 {
+        Ok(())
+    } else {
+        Err(CorruptImageError::UnexpectedDimensions { actual, requested })
+    }
+}
+
+/// 错误链中是否包含内容校验失败
+fn is_invalid_content(error: &anyhow::Error) -> bool {
+    error
+        .chain()
+        .any(|cause| cause.downcast_ref::<InvalidContentError>().is_some())
+}
+
+/// 错误链中是否包含 HTTP 404
+fn is_not_found(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        cause
+            .downcast_ref::<HttpStatusError>()
+            .is_some_and(|e| e.0 == reqwest::StatusCode::NOT_FOUND)
+    })
+}
+
+/// 根据下载分辨率设置和屏幕物理像素选择横屏分辨率阶梯
+///
+/// `preference` 为 "auto" 时，最大屏幕不超过 1920x1080（按长边/短边比较，兼容竖放屏幕）
+/// 则使用 1080p 阶梯；屏幕信息不可用时保守地使用完整阶梯。
+pub(crate) fn select_landscape_ladder(
+    preference: &str,
+    screens: &[(u32, u32)],
+) -> &'static [&'static str] {
+    match preference {
+        "1920x1080" => FHD_RESOLUTION_LADDER,
+        "UHD" => LANDSCAPE_RESOLUTION_LADDER,
+        _ => {
+            let largest = screens
+                .iter()
+                .map(|&(w, h)| (w.max(h), w.min(h)))
+                .max_by_key(|&(long, short)| u64::from(long) * u64::from(short));
+            match largest {
+                Some((long, short)) if long <= 1920 && short <= 1080 => FHD_RESOLUTION_LADDER,
+                _ => LANDSCAPE_RESOLUTION_LADDER,
+            }
+        }
+    }
+}
+
+/// 读取当前设置与显示器信息，决定本次下载使用的横屏分辨率阶梯
+pub(crate) async fn landscape_ladder_for(app: &AppHandle) -> &'static [&'static str] {
+    let preference = {
+        let state = app.state::<crate::AppState>();
+        state.settings.read().await.download_resolution
+    };
+    if preference != "auto" {
+        return select_landscape_ladder(&preference, &[]);
+    }
+
+    let screens = tokio::task::spawn_blocking(crate::wallpaper_manager::get_screen_pixel_sizes)
+        .await
+        .unwrap_or_default();
+    let ladder = select_landscape_ladder(&preference, &screens);
+    log::debug!(
+        target: "download",
+        "显示器物理分辨率 {:?}，选择分辨率阶梯 {:?}",
+        screens,
+        ladder
+    );
+    ladder
+}
+
+/// 读取设置中的重新压缩质量（0 表示保留原图）
+pub(crate) async fn jpeg_quality_for(app: &AppHandle) -> u8 {
+    let state = app.state::<crate::AppState>();
+    state.settings.read().await.jpeg_quality
+}
+
+/// 按分辨率阶梯下载横屏壁纸，并在索引中记录实际使用的分辨率
+///
+/// 只有 404 会触发降级；网络错误等其他失败直接返回，避免对每一档都重复重试。
+/// zh-CN 壁纸会优先尝试无水印的国际版，不存在时回退到原始地址，并在索引中记录磁盘上是哪个版本。
+///
+/// # Arguments
+/// * `ladder` - 分辨率阶梯，通常来自 [`landscape_ladder_for`]
+/// * `jpeg_quality` - 下载后重新压缩的质量，0 表示保留原图，通常来自 [`jpeg_quality_for`]
+///
+/// # Returns
+/// 实际下载成功的分辨率
+pub(crate) async fn download_landscape_wallpaper(
+    urlbase: &str,
+    end_date: &str,
+    wallpaper_dir: &Path,
+    ladder: &[&'static str],
+    jpeg_quality: u8,
+) -> Result<&'static str> {
+    let save_path = crate::storage::get_wallpaper_path(wallpaper_dir, end_date);
+    // 国际版 404 后同一素材的其他分辨率也不会存在，不再重复尝试
+    let mut watermark_free = crate::bing_api::watermark_free_urlbase(urlbase);
+    let tried_watermark_free = watermark_free.is_some();
+
+    for &resolution in ladder {
+        if let Some(clean) = watermark_free.as_deref() {
+            let url = crate::bing_api::get_wallpaper_url(clean, resolution);
+            match download_image(&url, &save_path).await {
+                Ok(()) => {
+                    record_landscape_download(
+                        wallpaper_dir,
+                        end_date,
+                        ladder,
+                        resolution,
+                        Some(true),
+                        jpeg_quality,
+                    )
+                    .await;
+                    return Ok(resolution);
+                }
+                Err(e) if is_not_found(&e) => {
+                    info!(
+                        target: "download",
+                        "壁纸 {} 没有无水印版本，使用原始地址",
+                        end_date
+                    );
+                    watermark_free = None;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        let url = crate::bing_api::get_wallpaper_url(urlbase, resolution);
+        match download_image(&url, &save_path).await {
+            Ok(()) => {
+                let variant = tried_watermark_free.then_some(false);
+                record_landscape_download(
+                    wallpaper_dir,
+                    end_date,
+                    ladder,
+                    resolution,
+                    variant,
+                    jpeg_quality,
+                )
+                .await;
+                return Ok(resolution);
+            }
+            Err(e) if is_not_found(&e) => {
+                log::warn!(
+                    target: "download",
+                    "壁纸 {} 的 {} 资源不存在，尝试下一档分辨率",
+                    end_date,
+                    resolution
+                );
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    anyhow::bail!("壁纸 {} 的所有分辨率均不可用: {:?}", end_date, ladder)
+}
+
+/// 在索引中记录横屏壁纸实际下载的分辨率和版本（`watermark_free` 为 None 表示未尝试无水印版本）
+///
+/// 设置了重新压缩时先压缩新下载的原图，再记录压缩结果。
+async fn record_landscape_download(
+    wallpaper_dir: &Path,
+    end_date: &str,
+    ladder: &[&'static str],
+    resolution: &'static str,
+    watermark_free: Option<bool>,
+    jpeg_quality: u8,
+) {
+    if Some(&resolution) != ladder.first() {
+        info!(
+            target: "download",
+            "壁纸 {} 已降级下载: {}",
+            end_date,
+            resolution
+        );
+    }
+    if let Err(e) =
+        crate::storage::record_wallpaper_resolution(wallpaper_dir, end_date, resolution).await
+    {
+        log::warn!(target: "download", "记录壁纸分辨率失败 {}: {}", end_date, e);
+    }
+    if let Some(watermark_free) = watermark_free
+        && let Err(e) =
+            crate::storage::record_watermark_free(wallpaper_dir, end_date, watermark_free).await
+    {
+        log::warn!(target: "download", "记录壁纸版本失败 {}: {}", end_date, e);
+    }
+
+    let recompression = match jpeg_quality {
+        0 => None,
+        quality => {
+            let save_path = crate::storage::get_wallpaper_path(wallpaper_dir, end_date);
+            recompress_landscape(&save_path, end_date, quality).await
+        }
+    };
+    if let Err(e) =
+        crate::storage::record_recompression(wallpaper_dir, end_date, recompression).await
+    {
+        log::warn!(target: "download", "记录壁纸压缩结果失败 {}: {}", end_date, e);
+    }
+}
+
+/// 在阻塞线程上重新压缩横屏原图，失败或没有变小时保留原图
+async fn recompress_landscape(path: &Path, end_date: &str, quality: u8) -> Option<Recompression> {
+    let path = path.to_path_buf();
+    match tokio::task::spawn_blocking(move || recompress_jpeg(&path, quality)).await {
+        Ok(Ok(Some(recompression))) => {
+            info!(
+                target: "download",
+                "壁纸 {} 已按质量 {} 重新压缩: {} -> {} 字节",
+                end_date,
+                quality,
+                recompression.original_size,
+                recompression.stored_size
+            );
+            Some(recompression)
+        }
+        Ok(Ok(None)) => {
+            info!(target: "download", "壁纸 {} 重新压缩后没有变小，保留原图", end_date);
+            None
+        }
+        Ok(Err(e)) => {
+            warn!(target: "download", "壁纸 {} 重新压缩失败，保留原图: {}", end_date, e);
+            None
+        }
+        Err(e) => {
+            warn!(target: "download", "壁纸 {} 重新压缩任务执行失败: {}", end_date, e);
+            None
+        }
+    }
+}
+
+/// 以指定质量重新编码 JPEG，仅在结果更小时（经临时文件原子地）替换原文件
+fn recompress_jpeg(path: &Path, quality: u8) -> Result<Option<Recompression>> {
+    let original_size = std::fs::metadata(path)
+        .context("Failed to read image metadata")?
+        .len();
+    let image = image::open(path).context("Failed to decode image")?;
+    let mut encoded = Vec::new();
+    image::codecs::jpeg::JpegEncoder::new_with_quality(&mut encoded, quality)
+        .encode_image(&image)
+        .context("Failed to encode image")?;
+
+    let stored_size = encoded.len() as u64;
+    if stored_size >= original_size {
+        return Ok(None);
+    }
+    let temp_path = path.with_extension("jpg.recompress");
+    let replaced =
+        std::fs::write(&temp_path, &encoded).and_then(|()| std::fs::rename(&temp_path, path));
+    if let Err(e) = replaced {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(e).context("Failed to replace image");
+    }
+    Ok(Some(Recompression {
+        quality,
+        original_size,
+        stored_size,
+    }))
+}
+
+/// 用 HEAD 请求探测竖屏版本是否存在
+///
+/// 404 视为不存在；其他非成功状态码和网络错误返回 `Err`，不写入索引，下次重新探测。
+async fn portrait_variant_exists(url: &str) -> Result<bool> {
+    let request = HTTP_CLIENT
+        .client()
+        .head(url)
+        .timeout(PORTRAIT_PROBE_TIMEOUT);
+    let response = http_client::send(request)
+        .await
+        .context("Failed to probe portrait variant")?;
+    match response.status() {
+        status if status.is_success() => Ok(true),
+        reqwest::StatusCode::NOT_FOUND => Ok(false),
+        status => Err(HttpStatusError(status).into()),
+    }
+}
+
+/// 确保竖屏壁纸 `YYYYMMDDr.jpg` 存在
+///
+/// 优先使用索引中缓存的探测结果，未探测过时先发 HEAD 请求并写回索引。
+/// 竖屏版本存在则直接下载；不存在时从横屏原图本地裁剪生成（横屏原图缺失时先下载），
+/// 保证竖屏显示器总能拿到方向正确的壁纸。
+pub(crate) async fn ensure_portrait_wallpaper(
+    app: &AppHandle,
+    wallpaper: &LocalWallpaper,
+    mkt: &str,
+    wallpaper_dir: &Path,
+) -> Result<()> {
+    use crate::{bing_api, smart_crop, storage};
+
+    let end_date = wallpaper.end_date.as_str();
+    let portrait_path = wallpaper_dir.join(format!("{end_date}r.jpg"));
+    if portrait_path.exists() {
+        return Ok(());
+    }
+
+    let url = bing_api::get_wallpaper_url(&wallpaper.urlbase, PORTRAIT_RESOLUTION);
+    let available = match wallpaper.portrait_available {
+        Some(available) => available,
+        None => {
+            let available = portrait_variant_exists(&url).await?;
+            if let Err(e) =
+                storage::record_portrait_availability(wallpaper_dir, mkt, end_date, available).await
+            {
+                warn!(target: "download", "记录竖屏版本探测结果失败 {}: {}", end_date, e);
+            }
+            available
+        }
+    };
+
+    if available {
+        match download_image(&url, &portrait_path).await {
+            Ok(()) => return Ok(()),
+            // 探测结果已过期（资源被下线），更新缓存后改为本地生成
+            Err(e) if is_not_found(&e) => {
+                if let Err(e) =
+                    storage::record_portrait_availability(wallpaper_dir, mkt, end_date, false).await
+                {
+                    warn!(target: "download", "记录竖屏版本探测结果失败 {}: {}", end_date, e);
+                }
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    info!(
+        target: "download",
+        "{} 在 {} 没有竖屏版本，从横屏原图生成",
+        end_date,
+        mkt
+    );
+    let landscape_path = storage::get_wallpaper_path(wallpaper_dir, end_date);
+    if !landscape_path.exists() {
+        let ladder = landscape_ladder_for(app).await;
+        let jpeg_quality = jpeg_quality_for(app).await;
+        download_landscape_wallpaper(
+            &wallpaper.urlbase,
+            end_date,
+            wallpaper_dir,
+            ladder,
+            jpeg_quality,
+        )
+        .await?;
+    }
+    tokio::task::spawn_blocking(move || {
+        smart_crop::generate_portrait(&landscape_path, &portrait_path)
+    })
+    .await
+    .context("Portrait crop task failed")?
+}
+
 /// 按需下载单个壁纸
 ///
 /// 从文件路径中提取 end_date，查找对应的元数据并下载图片。
@@ -35,34 +726,16 @@ pub(crate) async fn download_wallpaper_if_needed(
     wallpaper_dir: &Path,
     app: &AppHandle,
 ) -> std::result::Result<(), String> {
-    use crate::{AppState, bing_api, storage};
+    use crate::{AppState, safe_path, storage};
+
+    // 验证文件路径是否在壁纸目录下（安全性检查）
+    let file_path = safe_path::resolve_in_dir(wallpaper_dir, file_path)
+        .map_err(|e| format!("文件路径不在壁纸目录下: {e}"))?;
 
     if file_path.exists() {
         return Ok(());
     }
 
-    // 验证文件路径是否在壁纸目录下（安全性检查）
-    if let Some(parent) = file_path.parent() {
-        if let (Ok(parent_can), Ok(dir_can)) = (parent.canonicalize(), wallpaper_dir.canonicalize())
-        {
-            if !parent_can.starts_with(&dir_can) {
-                return Err(format!(
-                    "文件路径不在壁纸目录下: {} (期望在: {})",
-                    file_path.display(),
-                    wallpaper_dir.display()
-                ));
-            }
-        } else if parent != wallpaper_dir {
-            return Err(format!(
-                "文件路径的父目录不匹配: {} (期望: {})",
-                parent.display(),
-                wallpaper_dir.display()
-            ));
-        }
-    } else {
-        return Err(format!("无法确定文件路径的父目录: {}", file_path.display()));
-    }
-
     let filename = file_path
         .file_name()
         .and_then(|n| n.to_str())
@@ -89,7 +762,10 @@ pub(crate) async fn download_wallpaper_if_needed(
     let wallpaper = wallpapers
         .iter()
         .find(|w| w.end_date == end_date)
-        .ok_or_else(|| format!("未找到 end_date 为 {} 的壁纸元数据", end_date))?;
+        .ok_or_else(|| {
+            warn!(target: "commands", "未找到 end_date 为 {} 的壁纸元数据", end_date);
+            METADATA_NOT_FOUND.to_string()
+        })?;
 
     if wallpaper.urlbase.is_empty() {
         info!(
@@ -102,9 +778,6 @@ pub(crate) async fn download_wallpaper_if_needed(
         );
     }
 
-    let resolution = if is_portrait { "1080x1920" } else { "UHD" };
-    let image_url = bing_api::get_wallpaper_url(&wallpaper.urlbase, resolution);
-
     info!(
         target: "commands",
         "开始按需下载壁纸: {} -> {}",
@@ -112,10 +785,33 @@ pub(crate) async fn download_wallpaper_if_needed(
         file_path.display()
     );
 
-    match download_image(&image_url, file_path).await {
+    let result = if is_portrait {
+        ensure_portrait_wallpaper(app, wallpaper, &mkt, wallpaper_dir).await
+    } else {
+        let ladder = landscape_ladder_for(app).await;
+        let jpeg_quality = jpeg_quality_for(app).await;
+        download_landscape_wallpaper(
+            &wallpaper.urlbase,
+            end_date,
+            wallpaper_dir,
+            ladder,
+            jpeg_quality,
+        )
+        .await
+        .map(|_| ())
+    };
+
+    // 竖屏下载失败时记入待重试队列，后续更新周期会再次尝试
+    if is_portrait
+        && let Err(e) = crate::runtime_state::set_portrait_pending(app, end_date, result.is_err())
+    {
+        warn!(target: "commands", "更新待重试竖屏下载失败 {}: {}", end_date, e);
+    }
+
+    match result {
         Ok(()) => {
             info!(target: "commands", "成功按需下载壁纸: {}", file_path.display());
-            let _ = app.emit("image-downloaded", end_date);
+            let _ = events::IMAGE_DOWNLOADED.emit(app, end_date);
             Ok(())
         }
         Err(e) => {
@@ -136,6 +832,10 @@ pub(crate) async fn download_wallpaper_if_needed(
 /// * `url` - 图片 URL
 /// * `save_path` - 保存路径
 pub async fn download_image(url: &str, save_path: &Path) -> Result<()> {
+    if save_path.exists() {
+        log::debug!("文件已存在，跳过下载: {}", save_path.display());
+        return Ok(());
+    }
     download_image_with_retry(url, save_path, 3).await
 }
 
@@ -146,14 +846,25 @@ pub async fn download_image(url: &str, save_path: &Path) -> Result<()> {
 /// * `save_path` - 保存路径
 /// * `max_retries` - 最大重试次数
 async fn download_image_with_retry(url: &str, save_path: &Path, max_retries: usize) -> Result<()> {
+    let tracker = DownloadTracker::start(url, save_path, max_retries);
     let mut attempts = 0;
     let mut last_error = None;
 
     while attempts < max_retries {
-        match download_image_internal(url, save_path).await {
-            Ok(_) => return Ok(()),
+        tracker.begin_attempt(attempts + 1);
+        match download_image_internal(url, save_path, &tracker).await {
+            Ok(_) => {
+                tracker.finish();
+                return Ok(());
+            }
+            // 资源不存在、返回的不是图片都是永久性错误，重试没有意义
+            Err(e) if is_not_found(&e) || is_invalid_content(&e) => {
+                tracker.fail(&e, false);
+                return Err(e);
+            }
             Err(e) => {
                 attempts += 1;
+                tracker.fail(&e, attempts < max_retries);
                 last_error = Some(e);
                 if attempts < max_retries {
                     // 改进的重试延迟策略：
@@ -197,7 +908,12 @@ async fn download_image_with_retry(url: &str, save_path: &Path, max_retries: usi
 /// # Arguments
 /// * `url` - 图片 URL
 /// * `save_path` - 保存路径
-async fn download_image_internal(url: &str, save_path: &Path) -> Result<()> {
+/// * `tracker` - 下载状态登记，用于更新进度
+async fn download_image_internal(
+    url: &str,
+    save_path: &Path,
+    tracker: &DownloadTracker,
+) -> Result<()> {
     // 检查文件是否已存在
     if save_path.exists() {
         log::debug!("文件已存在，跳过下载: {}", save_path.display());
@@ -212,7 +928,13 @@ async fn download_image_internal(url: &str, save_path: &Path) -> Result<()> {
     }
 
     // 使用全局客户端发起请求，提供更详细的错误信息
-    let mut response = HTTP_CLIENT.get(url).send().await.map_err(|e| {
+    let started_at = Instant::now();
+    let request = HTTP_CLIENT.client().get(url);
+    let mut response = http_client::send(request).await.map_err(|e| {
+        let e = match e {
+            http_client::SendError::Http(e) => e,
+            restricted => return anyhow::Error::new(restricted),
+        };
         // 提供更详细的错误信息，帮助诊断问题
         let error_msg = if e.is_connect() {
             format!("Connection failed: {}", e)
@@ -229,10 +951,19 @@ async fn download_image_internal(url: &str, save_path: &Path) -> Result<()> {
     })?;
 
     if !response.status().is_success() {
-        anyhow::bail!("Failed to download image: HTTP {}", response.status());
+        return Err(HttpStatusError(response.status()).into());
     }
 
+    // 写盘前先检查响应头，HTML 错误页和超大响应直接中止
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok());
+    check_content_type(content_type)?;
     let content_length = response.content_length();
+    if let Some(length) = content_length {
+        check_size(length)?;
+    }
 
     // 流式下载：边下载边写入磁盘，减少内存占用
     let temp_path = save_path.with_extension("tmp");
@@ -240,10 +971,40 @@ async fn download_image_internal(url: &str, save_path: &Path) -> Result<()> {
         .await
         .context("Failed to create temporary file")?;
 
-    while let Some(chunk) = response.chunk().await.context("Failed to read chunk")? {
-        file.write_all(&chunk)
-            .await
-            .context("Failed to write chunk")?;
+    let mut downloaded_bytes = 0u64;
+    let mut head = Vec::with_capacity(JPEG_MAGIC.len());
+    let mut tail = Vec::with_capacity(JPEG_TRAILER_WINDOW);
+    let streamed: Result<()> = async {
+        while let Some(chunk) = response.chunk().await.context("Failed to read chunk")? {
+            // 凑够文件头后立即校验，不是 JPEG 时不必等待整个响应
+            if head.len() < JPEG_MAGIC.len() {
+                let needed = JPEG_MAGIC.len() - head.len();
+                head.extend_from_slice(&chunk[..needed.min(chunk.len())]);
+                if head.len() == JPEG_MAGIC.len() {
+                    check_jpeg_magic(&head)?;
+                }
+            }
+            update_tail(&mut tail, &chunk);
+            downloaded_bytes += chunk.len() as u64;
+            check_size(downloaded_bytes)?;
+            file.write_all(&chunk)
+                .await
+                .context("Failed to write chunk")?;
+            tracker.set_progress(downloaded_bytes, content_length);
+        }
+        check_jpeg_magic(&head)?;
+        Ok(())
+    }
+    .await;
+    // 无论成功与否，已接收的数据都计入流量统计
+    record_bandwidth(downloaded_bytes, started_at.elapsed());
+    if let Err(e) = streamed {
+        if is_invalid_content(&e) {
+            drop(file);
+            let _ = fs::remove_file(&temp_path).await;
+            log::warn!(target: "download", "响应内容校验失败，已中止下载 {}: {}", url, e);
+        }
+        return Err(e);
     }
 
     // 确保数据写入磁盘
@@ -263,15 +1024,23 @@ async fn download_image_internal(url: &str, save_path: &Path) -> Result<()> {
         }
     }
 
-    // 校验 2: 图片格式有效性 (尝试解析图片头)
+    // 校验 2: 文件尾完整（分块传输时没有 Content-Length，截断只能从文件结构判断）
+    if let Err(e) = check_jpeg_trailer(&tail) {
+        log::warn!(target: "download", "图片不完整，将删除临时文件并重试 {}: {}", url, e);
+        let _ = fs::remove_file(&temp_path).await;
+        return Err(e.into());
+    }
+
+    // 校验 3: 图片格式有效性与尺寸 (解析图片头)
     // 使用 spawn_blocking 因为 image crate 操作是阻塞的
     let temp_path_clone = temp_path.clone();
+    let requested = requested_dimensions(url);
     let validation_result = tokio::task::spawn_blocking(move || {
         // 使用 image crate 尝试读取图片头信息
         match image::ImageReader::open(&temp_path_clone) {
             Ok(reader) => match reader.with_guessed_format() {
                 Ok(reader) => match reader.into_dimensions() {
-                    Ok(_) => Ok(()),
+                    Ok(dimensions) => Ok(check_dimensions(dimensions, requested)?),
                     Err(e) => Err(anyhow::anyhow!("无效的图片文件(无法获取尺寸): {}", e)),
                 },
                 Err(e) => Err(anyhow::anyhow!("无法识别图片格式: {}", e)),
@@ -310,9 +1079,50 @@ async fn download_image_internal(url: &str, save_path: &Path) -> Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use reqwest::Client;
     use std::path::PathBuf;
     use std::time::SystemTime;
 
+    #[test]
+    fn test_describe_save_path() {
+        assert_eq!(
+            describe_save_path(Path::new("/w/20240101.jpg")),
+            ("20240101".to_string(), false)
+        );
+        assert_eq!(
+            describe_save_path(Path::new("/w/20240101r.jpg")),
+            ("20240101".to_string(), true)
+        );
+    }
+
+    #[test]
+    fn test_download_tracker_reports_progress_until_dropped() {
+        let url = "https://example.invalid/tracker_progress.jpg";
+        let find = || active_downloads().into_iter().find(|d| d.url == url);
+
+        let tracker = DownloadTracker::start(url, Path::new("/w/20240102r.jpg"), 3);
+        tracker.begin_attempt(2);
+        tracker.set_progress(250, Some(1000));
+        let download = find().unwrap();
+        assert_eq!(download.end_date, "20240102");
+        assert!(download.portrait);
+        assert_eq!(download.attempt, 2);
+        assert_eq!(download.max_attempts, 3);
+        assert_eq!(download.downloaded_bytes, 250);
+        assert_eq!(download.progress, Some(0.25));
+
+        // 未知总大小时没有进度比例
+        tracker.begin_attempt(3);
+        tracker.set_progress(100, None);
+        assert_eq!(find().unwrap().progress, None);
+
+        tracker.finish();
+        assert_eq!(find().unwrap().progress, Some(1.0));
+
+        drop(tracker);
+        assert!(find().is_none());
+    }
+
     /// 用于测试的下载函数，使用更短的超时时间（1秒）
     async fn download_image_fast_timeout(url: &str, save_path: &Path) -> Result<()> {
         let client = Client::builder()
@@ -349,6 +1159,42 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_recompress_jpeg_replaces_only_when_smaller() {
+        let unique = SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let temp_dir = std::env::temp_dir().join(format!("bw_recompress_{unique}"));
+        std::fs::create_dir_all(&temp_dir).unwrap();
+        let path = temp_dir.join("20240101.jpg");
+        let image = image::RgbImage::from_fn(256, 256, |x, y| {
+            image::Rgb([(x * 7 + y * 13) as u8, (x ^ y) as u8, (x * y) as u8])
+        });
+        let mut original = Vec::new();
+        image::codecs::jpeg::JpegEncoder::new_with_quality(&mut original, 100)
+            .encode_image(&image)
+            .unwrap();
+        std::fs::write(&path, &original).unwrap();
+
+        let recompression = recompress_jpeg(&path, 60).unwrap().unwrap();
+        assert_eq!(recompression.quality, 60);
+        assert_eq!(recompression.original_size, original.len() as u64);
+        assert!(recompression.stored_size < recompression.original_size);
+        assert_eq!(
+            std::fs::metadata(&path).unwrap().len(),
+            recompression.stored_size
+        );
+        assert!(!temp_dir.join("20240101.jpg.recompress").exists());
+
+        // 已经压缩过的图片以更高质量重新编码不会变小，保留原文件
+        let stored = std::fs::read(&path).unwrap();
+        assert_eq!(recompress_jpeg(&path, 95).unwrap(), None);
+        assert_eq!(std::fs::read(&path).unwrap(), stored);
+
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
+
     #[tokio::test]
     async fn test_download_image_creates_file() {
         let unique = SystemTime::now()
@@ -547,4 +1393,164 @@ mod tests {
         // 清理
         let _ = fs::remove_dir_all(&temp_dir).await;
     }
+
+    #[test]
+    fn test_is_not_found_detects_404_in_error_chain() {
+        let err: anyhow::Error = HttpStatusError(reqwest::StatusCode::NOT_FOUND).into();
+        assert!(is_not_found(&err));
+
+        let wrapped = err.context("Failed to download after 3 attempts");
+        assert!(is_not_found(&wrapped));
+    }
+
+    #[test]
+    fn test_is_not_found_ignores_other_errors() {
+        let server_error: anyhow::Error =
+            HttpStatusError(reqwest::StatusCode::INTERNAL_SERVER_ERROR).into();
+        assert!(!is_not_found(&server_error));
+        assert!(!is_not_found(&anyhow::anyhow!("Connection failed")));
+    }
+
+    #[test]
+    fn test_check_content_type_rejects_html() {
+        assert!(check_content_type(None).is_ok());
+        assert!(check_content_type(Some("image/jpeg")).is_ok());
+        assert!(check_content_type(Some("application/octet-stream")).is_ok());
+        assert_eq!(
+            check_content_type(Some("Text/HTML; charset=utf-8")),
+            Err(InvalidContentError::ContentType("text/html".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_check_size_and_jpeg_magic() {
+        assert!(check_size(MAX_IMAGE_BYTES).is_ok());
+        assert_eq!(
+            check_size(MAX_IMAGE_BYTES + 1),
+            Err(InvalidContentError::TooLarge(MAX_IMAGE_BYTES + 1))
+        );
+        assert!(check_jpeg_magic(&[0xFF, 0xD8, 0xFF, 0xE0]).is_ok());
+        assert_eq!(
+            check_jpeg_magic(b"<!DOCTYPE html>"),
+            Err(InvalidContentError::NotJpeg)
+        );
+        assert_eq!(check_jpeg_magic(&[0xFF]), Err(InvalidContentError::NotJpeg));
+    }
+
+    #[test]
+    fn test_jpeg_trailer_detects_truncation_across_chunks() {
+        let mut tail = Vec::new();
+        update_tail(&mut tail, &[0xFF, 0xD8, 0xFF, 0xE0, 0x00]);
+        assert_eq!(check_jpeg_trailer(&tail), Err(CorruptImageError::Truncated));
+        // EOI 标记被拆在两个分块中
+        update_tail(&mut tail, &[0x12, 0xFF]);
+        update_tail(&mut tail, &[]);
+        update_tail(&mut tail, &[0xD9]);
+        assert!(check_jpeg_trailer(&tail).is_ok());
+    }
+
+    #[test]
+    fn test_jpeg_trailer_allows_padding_after_eoi() {
+        let mut tail = Vec::new();
+        update_tail(&mut tail, &[0x00; 4096]);
+        update_tail(&mut tail, &[0x12, 0xFF, 0xD9]);
+        // EOI 之后的填充字节
+        update_tail(&mut tail, &[0x00; 100]);
+        assert_eq!(tail.len(), JPEG_TRAILER_WINDOW);
+        assert!(check_jpeg_trailer(&tail).is_ok());
+
+        // EOI 已移出窗口时视为截断
+        update_tail(&mut tail, &[0x00; JPEG_TRAILER_WINDOW]);
+        assert_eq!(check_jpeg_trailer(&tail), Err(CorruptImageError::Truncated));
+    }
+
+    #[test]
+    fn test_requested_dimensions_and_sanity_check() {
+        assert_eq!(
+            requested_dimensions("https://www.bing.com/th?id=OHR.Name_EN-US123_1920x1080.jpg"),
+            Some((1920, 1080))
+        );
+        assert_eq!(
+            requested_dimensions("https://www.bing.com/th?id=OHR.Name_EN-US123_1080x1920.jpg&rf=x"),
+            Some((1080, 1920))
+        );
+        assert_eq!(
+            requested_dimensions("https://www.bing.com/th?id=OHR.Name_EN-US123_UHD.jpg"),
+            None
+        );
+
+        assert!(check_dimensions((3840, 2160), None).is_ok());
+        assert!(check_dimensions((1920, 1080), Some((1920, 1080))).is_ok());
+        assert!(check_dimensions((1920, 1200), Some((1920, 1080))).is_ok());
+        assert!(check_dimensions((320, 180), Some((1920, 1080))).is_err());
+        assert_eq!(
+            check_dimensions((1920, 1080), Some((1080, 1920))),
+            Err(CorruptImageError::UnexpectedDimensions {
+                actual: (1920, 1080),
+                requested: (1080, 1920),
+            })
+        );
+        // 尺寸异常可以重试，不属于内容类型错误
+        let err: anyhow::Error = CorruptImageError::Truncated.into();
+        assert!(!is_invalid_content(&err));
+    }
+
+    #[test]
+    fn test_is_invalid_content_detects_wrapped_error() {
+        let err: anyhow::Error = InvalidContentError::NotJpeg.into();
+        assert!(is_invalid_content(&err.context("Failed to download")));
+        assert!(!is_invalid_content(&anyhow::anyhow!("Connection failed")));
+    }
+
+    #[test]
+    fn test_landscape_resolution_ladder_starts_with_uhd() {
+        assert_eq!(LANDSCAPE_RESOLUTION_LADDER[0], "UHD");
+        assert_eq!(
+            LANDSCAPE_RESOLUTION_LADDER.last().copied(),
+            Some("1920x1080")
+        );
+    }
+
+    #[test]
+    fn test_select_landscape_ladder_explicit_preference() {
+        let uhd_screen = [(3840, 2160)];
+        assert_eq!(
+            select_landscape_ladder("1920x1080", &uhd_screen),
+            FHD_RESOLUTION_LADDER
+        );
+        assert_eq!(
+            select_landscape_ladder("UHD", &[(1920, 1080)]),
+            LANDSCAPE_RESOLUTION_LADDER
+        );
+    }
+
+    #[test]
+    fn test_select_landscape_ladder_auto_uses_largest_screen() {
+        assert_eq!(
+            select_landscape_ladder("auto", &[(1920, 1080), (1366, 768)]),
+            FHD_RESOLUTION_LADDER
+        );
+        // 竖放的 1080p 屏幕同样视为 1080p
+        assert_eq!(
+            select_landscape_ladder("auto", &[(1080, 1920)]),
+            FHD_RESOLUTION_LADDER
+        );
+        // 任一屏幕超过 1080p（如 Retina 物理像素）即使用 UHD
+        assert_eq!(
+            select_landscape_ladder("auto", &[(1920, 1080), (2880, 1800)]),
+            LANDSCAPE_RESOLUTION_LADDER
+        );
+        assert_eq!(
+            select_landscape_ladder("auto", &[(1920, 1200)]),
+            LANDSCAPE_RESOLUTION_LADDER
+        );
+    }
+
+    #[test]
+    fn test_select_landscape_ladder_auto_without_screen_info() {
+        assert_eq!(
+            select_landscape_ladder("auto", &[]),
+            LANDSCAPE_RESOLUTION_LADDER
+        );
+    }
 }
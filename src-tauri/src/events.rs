@@ -0,0 +1,174 @@
+//! 后端发送给前端的事件
+//!
+//! 所有事件名与 payload 类型集中在 [`events!`] 表中定义，发送时通过 [`Event::emit`]
+//! 进行类型检查，避免各模块手写字符串导致事件名或 payload 与前端不一致。
+//!
+//! 同一张表会生成前端使用的 `src/config/events.generated.ts`（事件名常量 + payload 类型映射）。
+//! 打包时前端先于 Rust 构建，因此生成文件随代码提交，由测试校验是否与此表一致：
+//! 修改事件后运行 `UPDATE_EVENT_TYPES=1 cargo test events` 重新生成。
+
+use serde::Serialize;
+use std::marker::PhantomData;
+use tauri::{AppHandle, Emitter};
+
+use crate::directory_permission::DirectoryPermissionError;
+use crate::disk_space::LowDiskSpace;
+use crate::models::{
+    ActiveDownload, DownloadFailure, MarketStatus, MktSuggestion, SettingChange, UpdateState,
+    WallpaperMetadataRefreshed,
+};
+
+/// 一个带 payload 类型的事件
+pub(crate) struct Event<P: ?Sized> {
+    name: &'static str,
+    payload: PhantomData<fn(&P)>,
+}
+
+impl<P: Serialize + ?Sized> Event<P> {
+    const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            payload: PhantomData,
+        }
+    }
+
+    /// 向所有窗口广播事件
+    pub(crate) fn emit(&self, app: &AppHandle, payload: &P) -> tauri::Result<()> {
+        app.emit(self.name, payload)
+    }
+}
+
+/// 定义事件常量，同时生成前端类型定义所需的目录
+///
+/// 每项格式：`/// 说明` `常量名: Rust payload 类型 = "事件名" => "TypeScript payload 类型";`
+macro_rules! events {
+    ($(
+        #[doc = $doc:literal]
+        $ident:ident: $payload:ty = $name:literal => $ts:literal;
+    )*) => {
+        $(
+            #[doc = $doc]
+            pub(crate) const $ident: Event<$payload> = Event::new($name);
+        )*
+
+        /// (常量名, 事件名, TypeScript payload 类型, 说明)
+        #[cfg(test)]
+        const CATALOG: &[(&str, &str, &str, &str)] = &[
+            $((stringify!($ident), $name, $ts, $doc),)*
+        ];
+    };
+}
+
+events! {
+    /// 壁纸列表或元数据已更新，需要重新加载
+    WALLPAPER_UPDATED: () = "wallpaper-updated" => "null";
+    /// 单张壁纸的标题和版权信息已重新获取（只需更新这一条）
+    WALLPAPER_METADATA_REFRESHED: WallpaperMetadataRefreshed = "wallpaper-metadata-refreshed" => "WallpaperMetadataRefreshed";
+    /// 单张壁纸图片下载完成（payload 为 end_date）
+    IMAGE_DOWNLOADED: str = "image-downloaded" => "string";
+    /// 当前桌面壁纸已变化（payload 为图片路径）
+    CURRENT_WALLPAPER_CHANGED: str = "current-wallpaper-changed" => "string";
+    /// 用户取消了正在进行的更新
+    UPDATE_CANCELLED: () = "update-cancelled" => "null";
+    /// 更新循环阶段变化
+    UPDATE_STATE_CHANGED: UpdateState = "update-state-changed" => "UpdateState";
+    /// 图片下载开始（含每次重试）
+    DOWNLOAD_STARTED: ActiveDownload = "download-started" => "ActiveDownload";
+    /// 图片下载完成
+    DOWNLOAD_FINISHED: ActiveDownload = "download-finished" => "ActiveDownload";
+    /// 图片下载失败（will_retry 表示是否还会自动重试）
+    DOWNLOAD_FAILED: DownloadFailure = "download-failed" => "DownloadFailure";
+    /// 打开设置
+    OPEN_SETTINGS: () = "open-settings" => "null";
+    /// 打开关于
+    OPEN_ABOUT: () = "open-about" => "null";
+    /// 打开文件夹
+    OPEN_FOLDER: () = "open-folder" => "null";
+    /// 托盘手动检查更新
+    TRAY_CHECK_UPDATES: () = "tray-check-updates" => "null";
+    /// mkt 状态变化（mismatch 边沿触发：false→true / true→false）
+    MKT_STATUS_CHANGED: MarketStatus = "mkt-status-changed" => "MarketStatus";
+    /// 首次启动的市场建议已生成，等待用户确认
+    MKT_SUGGESTION_READY: MktSuggestion = "mkt-suggestion-ready" => "MktSuggestion";
+    /// 设置已修改（保存设置、切换配置方案等），payload 为变化的设置项列表
+    SETTINGS_CHANGED: [SettingChange] = "settings-changed" => "SettingChange[]";
+    /// 启动延后阶段（状态修复、索引预加载、后台任务启动）已完成
+    STARTUP_COMPLETE: () = "startup-complete" => "null";
+    /// 壁纸目录无写入权限，可回退到默认目录
+    DIRECTORY_PERMISSION_ERROR: DirectoryPermissionError = "directory-permission-error" => "DirectoryPermissionError";
+    /// 壁纸目录磁盘空间不足，已暂停下载（进入空间不足状态时发送一次）
+    LOW_DISK_SPACE: LowDiskSpace = "low-disk-space" => "LowDiskSpace";
+    /// 主题跟随壁纸时，新壁纸的明暗分析结果
+    SUGGESTED_THEME: str = "suggested-theme" => "\"light\" | \"dark\"";
+}
+
+/// 生成前端的事件定义文件内容
+#[cfg(test)]
+fn typescript_definitions() -> String {
+    let mut imports: Vec<&str> = CATALOG
+        .iter()
+        .map(|&(_, _, ts, _)| ts.trim_end_matches("[]"))
+        .filter(|ts| ts.starts_with(|c: char| c.is_ascii_uppercase()))
+        .collect();
+    imports.sort_unstable();
+    imports.dedup();
+
+    let mut out = String::from(
+        "// 此文件由 src-tauri/src/events.rs 生成，请勿手动修改。\n\
+         // 修改事件后在 src-tauri 目录运行 `UPDATE_EVENT_TYPES=1 cargo test events` 重新生成。\n\n",
+    );
+    out.push_str("import type {\n");
+    for name in &imports {
+        out.push_str(&format!("  {name},\n"));
+    }
+    out.push_str("} from \"../types\";\n\n");
+
+    out.push_str("/**\n * 后端事件名\n */\nexport const EVENTS = {\n");
+    for (ident, name, _, doc) in CATALOG {
+        out.push_str(&format!(
+            "  /** {} */\n  {ident}: \"{name}\",\n",
+            doc.trim()
+        ));
+    }
+    out.push_str("} as const;\n\n");
+
+    out.push_str("/**\n * 各事件的 payload 类型\n */\nexport interface EventPayloads {\n");
+    for (_, name, ts, _) in CATALOG {
+        out.push_str(&format!("  \"{name}\": {ts};\n"));
+    }
+    out.push_str("}\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use std::path::Path;
+
+    #[test]
+    fn test_event_names_are_unique_kebab_case() {
+        let mut seen = HashSet::new();
+        for (ident, name, _, _) in CATALOG {
+            assert!(seen.insert(*name), "duplicate event name: {name}");
+            assert_eq!(ident.to_ascii_lowercase().replace('_', "-"), *name);
+        }
+        assert_eq!(WALLPAPER_UPDATED.name, "wallpaper-updated");
+    }
+
+    #[test]
+    fn test_events_typescript_definitions_up_to_date() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../src/config/events.generated.ts");
+        let expected = typescript_definitions();
+        if std::env::var_os("UPDATE_EVENT_TYPES").is_some() {
+            std::fs::write(&path, &expected).unwrap();
+            return;
+        }
+        let actual = std::fs::read_to_string(&path).unwrap_or_default();
+        assert!(
+            actual == expected,
+            "{} is out of date, run `UPDATE_EVENT_TYPES=1 cargo test events`",
+            path.display()
+        );
+    }
+}
@@ -0,0 +1,356 @@
+//! 面向第三方扩展的本地事件端点
+//!
+//! 开启设置 `extension_events` 后，应用在本地 IPC 端点上发布 JSON 事件，conky、Rainmeter、
+//! OBS 脚本等外部工具连接后即可在壁纸变化时做出响应：
+//! - macOS / Linux：应用数据目录下的 Unix socket `events.sock`（权限 0600）
+//! - Windows：命名管道 `\\.\pipe\BingWallpaperNow-events-<会话 ID>`（拒绝远程连接）。
+//!   管道名在整台机器上共享，因此带上登录会话 ID，快速切换用户时各用户的实例互不冲突
+//!
+//! 协议为 JSON Lines，每行一个事件，`event` 字段区分类型；客户端只读，发送的数据会被忽略：
+//! - `hello`：连接后立即发送，`protocol` 为协议版本（当前为 1）
+//! - `wallpaper-changed`：桌面壁纸已变化。连接时若已有壁纸记录，会在 `hello` 之后补发一次当前壁纸
+//!
+//! 同一协议版本内只会新增事件类型或字段，不会删除或改变已有字段的含义，客户端应忽略不认识的内容。
+
+use log::{info, warn};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, Mutex};
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
+
+use crate::local_folder::LOCAL_MKT;
+use crate::{AppState, apply_hook, storage};
+
+/// 当前协议版本
+const PROTOCOL_VERSION: u32 = 1;
+const EXTENSION_EVENTS_JOB: &str = "extension_events";
+#[cfg(unix)]
+const SOCKET_FILE: &str = "events.sock";
+#[cfg(windows)]
+const PIPE_PREFIX: &str = r"\\.\pipe\BingWallpaperNow-events-";
+
+/// 已序列化的事件行（不含换行），所有客户端共享
+static EVENTS: LazyLock<broadcast::Sender<Arc<str>>> = LazyLock::new(|| broadcast::channel(16).0);
+/// 最近一次 `wallpaper-changed`，新客户端连接时补发
+static LAST_WALLPAPER: Mutex<Option<Arc<str>>> = Mutex::new(None);
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+enum ExtensionEvent<'a> {
+    Hello { protocol: u32, app_version: &'a str },
+    WallpaperChanged(&'a WallpaperChanged),
+}
+
+/// `wallpaper-changed` 事件内容
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct WallpaperChanged {
+    /// 设置到桌面的图片路径
+    path: String,
+    /// "bing"（Bing 每日壁纸）或 "local"（自定义文件夹中的图片）
+    source: &'static str,
+    /// 以下字段仅 Bing 壁纸有值
+    mkt: Option<String>,
+    end_date: Option<String>,
+    title: Option<String>,
+    copyright: Option<String>,
+    copyright_link: Option<String>,
+    /// 变化时间（RFC 3339）
+    changed_at: String,
+}
+
+fn to_line(event: &ExtensionEvent) -> Arc<str> {
+    serde_json::to_string(event)
+        .expect("extension events are always serializable")
+        .into()
+}
+
+/// 本机的事件端点（Unix socket 路径或命名管道名）
+fn endpoint(app: &AppHandle) -> Option<PathBuf> {
+    #[cfg(unix)]
+    {
+        app.path()
+            .app_data_dir()
+            .ok()
+            .map(|dir| dir.join(SOCKET_FILE))
+    }
+    #[cfg(windows)]
+    {
+        use windows_sys::Win32::System::RemoteDesktop::ProcessIdToSessionId;
+
+        let _ = app;
+        let mut session_id = 0u32;
+        // SAFETY: `session_id` 是有效的可写 u32
+        if unsafe { ProcessIdToSessionId(std::process::id(), &mut session_id) } == 0 {
+            warn!(
+                target: "extension",
+                "获取当前会话 ID 失败: {}",
+                std::io::Error::last_os_error()
+            );
+            return None;
+        }
+        Some(PathBuf::from(format!("{PIPE_PREFIX}{session_id}")))
+    }
+}
+
+/// 获取事件端点地址（供设置界面展示）
+#[tauri::command]
+pub(crate) fn get_extension_endpoint(app: AppHandle) -> Option<String> {
+    endpoint(&app).map(|path| path.to_string_lossy().into_owned())
+}
+
+/// 发布壁纸变化事件，并运行设置的钩子程序（见 `apply_hook` 模块）
+///
+/// 未开启 `extension_events` 且没有设置钩子时不做任何事。
+/// `end_date` 为 Bing 壁纸的日期，自定义文件夹图片传 `mkt = LOCAL_MKT`。
+pub(crate) async fn publish_wallpaper_changed(
+    app: &AppHandle,
+    path: &Path,
+    mkt: &str,
+    end_date: Option<&str>,
+) {
+    let state = app.state::<AppState>();
+    let (publish, has_hook) = {
+        let settings = state.settings.read().await;
+        (settings.extension_events, settings.apply_hook.is_some())
+    };
+    if !publish && !has_hook {
+        return;
+    }
+
+    let mut event = WallpaperChanged {
+        path: path.to_string_lossy().into_owned(),
+        source: if mkt == LOCAL_MKT { "local" } else { "bing" },
+        mkt: None,
+        end_date: None,
+        title: None,
+        copyright: None,
+        copyright_link: None,
+        changed_at: state.clock.now().to_rfc3339(),
+    };
+    if mkt != LOCAL_MKT {
+        event.mkt = Some(mkt.to_string());
+        event.end_date = end_date.map(str::to_string);
+        let wallpaper_dir = state.wallpaper_directory.lock().await.clone();
+        if let Some(end_date) = end_date
+            && let Ok(index) = storage::get_index_snapshot(&wallpaper_dir).await
+            && let Some(wallpaper) = index.mkt.get(mkt).and_then(|m| m.get(end_date))
+        {
+            event.title = Some(wallpaper.title.clone());
+            event.copyright = Some(wallpaper.copyright.clone());
+            event.copyright_link = Some(wallpaper.copyright_link.clone());
+        }
+    }
+
+    if has_hook {
+        apply_hook::spawn(app, path, event.title.as_deref()).await;
+    }
+    if !publish {
+        return;
+    }
+    let line = to_line(&ExtensionEvent::WallpaperChanged(&event));
+    *LAST_WALLPAPER.lock().unwrap_or_else(|e| e.into_inner()) = Some(line.clone());
+    // 没有客户端连接时发送失败，忽略即可
+    let _ = EVENTS.send(line);
+}
+
+/// 启动事件端点任务：随设置 `extension_events` 开启或关闭端点
+pub(crate) fn start_extension_events_task(app: AppHandle) {
+    let state = app.state::<AppState>();
+    let mut rx = state.settings.subscribe();
+    let Some(endpoint) = endpoint(&app) else {
+        warn!(target: "extension", "无法确定事件端点路径，扩展事件不可用");
+        return;
+    };
+
+    state.scheduler.spawn(EXTENSION_EVENTS_JOB, async move {
+        loop {
+            if !rx.borrow_and_update().extension_events {
+                if rx.changed().await.is_err() {
+                    break;
+                }
+                continue;
+            }
+
+            let failed = tokio::select! {
+                result = serve(&endpoint) => result.err(),
+                _ = rx.wait_for(|settings| !settings.extension_events) => None,
+            };
+            cleanup(&endpoint);
+            match failed {
+                // 端点创建失败（如被其他实例占用）时等待下一次设置变化再重试
+                Some(e) => {
+                    warn!(target: "extension", "扩展事件端点出错: {}", e);
+                    if rx.changed().await.is_err() {
+                        break;
+                    }
+                }
+                None => info!(target: "extension", "扩展事件端点已关闭"),
+            }
+        }
+    });
+}
+
+#[cfg(unix)]
+async fn serve(endpoint: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    if let Some(parent) = endpoint.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    // 上次异常退出残留的 socket 文件会导致 bind 失败
+    let _ = std::fs::remove_file(endpoint);
+    let listener = tokio::net::UnixListener::bind(endpoint)?;
+    std::fs::set_permissions(endpoint, std::fs::Permissions::from_mode(0o600))?;
+    info!(target: "extension", "扩展事件端点已开启: {}", endpoint.display());
+
+    // 端点关闭时（future 被丢弃）断开所有客户端
+    let closed = CancellationToken::new();
+    let _close_clients = closed.clone().drop_guard();
+    loop {
+        let (stream, _) = listener.accept().await?;
+        tauri::async_runtime::spawn(serve_client(stream, closed.clone()));
+    }
+}
+
+#[cfg(windows)]
+async fn serve(endpoint: &Path) -> std::io::Result<()> {
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    let mut server = ServerOptions::new()
+        .first_pipe_instance(true)
+        .reject_remote_clients(true)
+        .create(endpoint)?;
+    info!(target: "extension", "扩展事件端点已开启: {}", endpoint.display());
+
+    let closed = CancellationToken::new();
+    let _close_clients = closed.clone().drop_guard();
+    loop {
+        server.connect().await?;
+        // 先创建下一个管道实例再交出已连接的实例，避免客户端在间隙中连接失败
+        let client = std::mem::replace(
+            &mut server,
+            ServerOptions::new()
+                .reject_remote_clients(true)
+                .create(endpoint)?,
+        );
+        tauri::async_runtime::spawn(serve_client(client, closed.clone()));
+    }
+}
+
+fn cleanup(endpoint: &Path) {
+    #[cfg(unix)]
+    let _ = std::fs::remove_file(endpoint);
+    #[cfg(windows)]
+    let _ = endpoint;
+}
+
+/// 向单个客户端推送事件，直到客户端断开或端点关闭
+async fn serve_client<S: AsyncWrite + Unpin>(mut stream: S, closed: CancellationToken) {
+    // 先订阅再读取最近的壁纸，避免两者之间发布的事件丢失
+    let mut rx = EVENTS.subscribe();
+    let last_wallpaper = LAST_WALLPAPER
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone();
+    let hello = to_line(&ExtensionEvent::Hello {
+        protocol: PROTOCOL_VERSION,
+        app_version: env!("CARGO_PKG_VERSION"),
+    });
+
+    for line in std::iter::once(hello).chain(last_wallpaper) {
+        if write_line(&mut stream, &line).await.is_err() {
+            return;
+        }
+    }
+    loop {
+        let line = tokio::select! {
+            _ = closed.cancelled() => return,
+            received = rx.recv() => match received {
+                Ok(line) => line,
+                // 客户端读取过慢时跳过积压的事件
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return,
+            },
+        };
+        if write_line(&mut stream, &line).await.is_err() {
+            return;
+        }
+    }
+}
+
+async fn write_line<S: AsyncWrite + Unpin>(stream: &mut S, line: &str) -> std::io::Result<()> {
+    stream.write_all(line.as_bytes()).await?;
+    stream.write_all(b"\n").await?;
+    stream.flush().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_event() -> WallpaperChanged {
+        WallpaperChanged {
+            path: "/wallpapers/20240101.jpg".to_string(),
+            source: "bing",
+            mkt: Some("zh-CN".to_string()),
+            end_date: Some("20240101".to_string()),
+            title: Some("长城".to_string()),
+            copyright: Some("Copyright".to_string()),
+            copyright_link: Some("https://example.com".to_string()),
+            changed_at: "2024-01-01T08:00:00+08:00".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_event_lines_are_stable() {
+        let hello = to_line(&ExtensionEvent::Hello {
+            protocol: 1,
+            app_version: "1.0.0",
+        });
+        assert_eq!(
+            &*hello,
+            r#"{"event":"hello","protocol":1,"app_version":"1.0.0"}"#
+        );
+
+        let changed = to_line(&ExtensionEvent::WallpaperChanged(&sample_event()));
+        let value: serde_json::Value = serde_json::from_str(&changed).unwrap();
+        assert_eq!(value["event"], "wallpaper-changed");
+        assert_eq!(value["path"], "/wallpapers/20240101.jpg");
+        assert_eq!(value["source"], "bing");
+        assert_eq!(value["end_date"], "20240101");
+        assert_eq!(value["title"], "长城");
+        assert_eq!(value["changed_at"], "2024-01-01T08:00:00+08:00");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_socket_sends_hello_and_events() {
+        use tokio::io::{AsyncBufReadExt, BufReader};
+
+        let endpoint =
+            std::env::temp_dir().join(format!("bw_extension_{}.sock", std::process::id()));
+        let server = tokio::spawn({
+            let endpoint = endpoint.clone();
+            async move { serve(&endpoint).await }
+        });
+        while !endpoint.exists() {
+            tokio::task::yield_now().await;
+        }
+
+        let stream = tokio::net::UnixStream::connect(&endpoint).await.unwrap();
+        let mut lines = BufReader::new(stream).lines();
+        let hello = lines.next_line().await.unwrap().unwrap();
+        assert!(hello.starts_with(r#"{"event":"hello","protocol":1,"#));
+
+        // 收到 hello 时客户端已经订阅，之后发布的事件会被推送
+        let line = to_line(&ExtensionEvent::WallpaperChanged(&sample_event()));
+        EVENTS.send(line.clone()).unwrap();
+        assert_eq!(lines.next_line().await.unwrap().unwrap(), *line);
+
+        server.abort();
+        cleanup(&endpoint);
+    }
+}
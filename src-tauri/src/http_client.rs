@@ -0,0 +1,250 @@
+//! HTTP 客户端的 TLS 根证书配置
+//!
+//! 默认通过 rustls-platform-verifier 使用操作系统证书库校验证书（企业通过组策略/MDM
+//! 下发到系统中的根证书会被信任）。在 SSL 检查代理环境中，如果代理的根证书没有进入系统证书库，
+//! 可以在设置中指定额外的 PEM 证书文件：默认与系统证书库合并，也可以只信任该文件中的证书。
+//!
+//! 所有 HTTP 客户端都应通过 [`builder`] 或 [`SharedClient`] 创建，证书配置变化后共享客户端会在
+//! 下次使用时重建。空闲连接的保留时间同样由设置控制；网络环境变化（见 `network_watch` 模块）或
+//! 用户手动重置网络时调用 [`reset_clients`]，丢弃连接池中可能已失效的连接。
+//!
+//! 除用户配置的备份目标（WebDAV/S3）外，所有请求都应通过 [`send`] 发送，由它执行
+//! `network_gate` 模块的出站限制。
+
+use anyhow::{Context, Result};
+use log::{info, warn};
+use reqwest::{Certificate, Client, ClientBuilder, RequestBuilder, Response};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, RwLock};
+use std::time::Duration;
+
+use crate::network_gate;
+
+/// 当前生效的额外根证书
+struct TlsRoots {
+    /// 证书来源 (PEM 路径, 是否只信任该证书)，用于判断设置是否变化
+    source: Option<(String, bool)>,
+    certs: Vec<Certificate>,
+}
+
+static TLS_ROOTS: RwLock<TlsRoots> = RwLock::new(TlsRoots {
+    source: None,
+    certs: Vec::new(),
+});
+/// 证书配置、连接保留时间变化或重置网络时递增，共享客户端据此判断是否需要重建
+static CLIENT_GENERATION: AtomicU64 = AtomicU64::new(0);
+/// 空闲连接在连接池中保留的秒数，0 表示不复用连接
+static KEEP_ALIVE_SECS: AtomicU64 = AtomicU64::new(90);
+
+/// 读取 PEM 证书文件（可包含多个证书）
+pub(crate) fn read_ca_bundle(path: &Path) -> Result<Vec<Certificate>> {
+    let pem = std::fs::read(path)
+        .with_context(|| format!("Failed to read CA bundle: {}", path.display()))?;
+    let certs = Certificate::from_pem_bundle(&pem).context("Failed to parse CA bundle")?;
+    if certs.is_empty() {
+        anyhow::bail!("No certificate found in {}", path.display());
+    }
+    Ok(certs)
+}
+
+/// 应用设置中的证书配置（未变化时不做任何事）
+///
+/// 证书文件读取失败时记录警告并回退到只使用系统证书库。
+pub(crate) fn configure_tls(custom_ca_path: Option<&str>, custom_ca_only: bool) {
+    let source = custom_ca_path.map(|path| (path.to_string(), custom_ca_only));
+    let mut roots = TLS_ROOTS.write().unwrap_or_else(|e| e.into_inner());
+    if roots.source == source {
+        return;
+    }
+    roots.certs = match &source {
+        Some((path, only)) => match read_ca_bundle(Path::new(path)) {
+            Ok(certs) => {
+                info!(
+                    target: "http",
+                    "加载自定义根证书: {} 个（{}），来源 {}",
+                    certs.len(),
+                    if *only { "仅信任自定义证书" } else { "与系统证书库合并" },
+                    path
+                );
+                certs
+            }
+            Err(e) => {
+                warn!(target: "http", "加载自定义根证书失败，仅使用系统证书库: {:#}", e);
+                Vec::new()
+            }
+        },
+        None => Vec::new(),
+    };
+    roots.source = source;
+    CLIENT_GENERATION.fetch_add(1, Ordering::SeqCst);
+}
+
+/// 应用设置中的空闲连接保留时间（未变化时不做任何事）
+pub(crate) fn configure_keep_alive(secs: u32) {
+    if KEEP_ALIVE_SECS.swap(u64::from(secs), Ordering::SeqCst) != u64::from(secs) {
+        CLIENT_GENERATION.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// 丢弃所有共享客户端（及其连接池），下次使用时重建
+pub(crate) fn reset_clients(reason: &str) {
+    CLIENT_GENERATION.fetch_add(1, Ordering::SeqCst);
+    info!(target: "http", "重建 HTTP 客户端: {}", reason);
+}
+
+/// 应用了当前根证书配置和空闲连接保留时间的客户端构建器
+pub(crate) fn builder() -> ClientBuilder {
+    let roots = TLS_ROOTS.read().unwrap_or_else(|e| e.into_inner());
+    let builder = match KEEP_ALIVE_SECS.load(Ordering::SeqCst) {
+        0 => Client::builder().pool_max_idle_per_host(0),
+        secs => Client::builder().pool_idle_timeout(Duration::from_secs(secs)),
+    };
+    match &roots.source {
+        Some((_, true)) if !roots.certs.is_empty() => builder.tls_certs_only(roots.certs.clone()),
+        _ => builder.tls_certs_merge(roots.certs.clone()),
+    }
+}
+
+/// 复用连接池的全局客户端，证书配置变化或重置网络后在下次使用时重建
+pub(crate) struct SharedClient {
+    configure: fn(ClientBuilder) -> ClientBuilder,
+    cached: Mutex<Option<(u64, Client)>>,
+}
+
+impl SharedClient {
+    /// `configure` 用于设置超时、User-Agent 等与证书无关的选项
+    pub(crate) const fn new(configure: fn(ClientBuilder) -> ClientBuilder) -> Self {
+        Self {
+            configure,
+            cached: Mutex::new(None),
+        }
+    }
+
+    pub(crate) fn client(&self) -> Client {
+        let generation = CLIENT_GENERATION.load(Ordering::SeqCst);
+        let mut cached = self.cached.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((built_at, client)) = cached.as_ref()
+            && *built_at == generation
+        {
+            return client.clone();
+        }
+        let client = match (self.configure)(builder()).build() {
+            Ok(client) => client,
+            Err(e) => {
+                warn!(target: "http", "使用自定义根证书创建 HTTP 客户端失败，回退到系统证书库: {}", e);
+                (self.configure)(Client::builder())
+                    .build()
+                    .expect("Failed to create HTTP client")
+            }
+        };
+        *cached = Some((generation, client.clone()));
+        client
+    }
+}
+
+/// 通过 [`send`] 发送请求的错误
+#[derive(Debug)]
+pub(crate) enum SendError {
+    /// 出站限制不允许访问该地址
+    Restricted,
+    Http(reqwest::Error),
+}
+
+impl std::fmt::Display for SendError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Restricted => f.write_str(network_gate::NETWORK_RESTRICTED),
+            Self::Http(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for SendError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Restricted => None,
+            Self::Http(e) => Some(e),
+        }
+    }
+}
+
+impl From<reqwest::Error> for SendError {
+    fn from(e: reqwest::Error) -> Self {
+        Self::Http(e)
+    }
+}
+
+/// 错误链中是否包含出站限制的拦截
+pub(crate) fn is_restricted(error: &anyhow::Error) -> bool {
+    error
+        .chain()
+        .any(|e| matches!(e.downcast_ref::<SendError>(), Some(SendError::Restricted)))
+}
+
+/// 检查出站限制后发送请求，不允许访问的地址直接返回 [`SendError::Restricted`]
+pub(crate) async fn send(request: RequestBuilder) -> Result<Response, SendError> {
+    let (client, request) = request.build_split();
+    let request = request?;
+    if !network_gate::is_allowed(request.url().as_str()) {
+        return Err(SendError::Restricted);
+    }
+    Ok(client.execute(request).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_ca_bundle_rejects_missing_and_empty_files() {
+        let dir = std::env::temp_dir();
+        assert!(read_ca_bundle(&dir.join("bw_missing_ca.pem")).is_err());
+
+        let empty = dir.join(format!("bw_empty_ca_{}.pem", std::process::id()));
+        std::fs::write(&empty, "not a certificate\n").unwrap();
+        assert!(read_ca_bundle(&empty).is_err());
+        let _ = std::fs::remove_file(&empty);
+    }
+
+    #[test]
+    fn test_shared_client_rebuilds_after_tls_change() {
+        static CLIENT: SharedClient = SharedClient::new(|builder| builder);
+        CLIENT.client();
+        let before = CLIENT.cached.lock().unwrap().as_ref().unwrap().0;
+
+        // 无法读取的证书文件回退到系统证书库，但仍视为一次配置变化
+        configure_tls(Some("/nonexistent/ca.pem"), false);
+        CLIENT.client();
+        let after = CLIENT.cached.lock().unwrap().as_ref().unwrap().0;
+        assert_ne!(before, after);
+
+        configure_tls(None, false);
+    }
+
+    #[tokio::test]
+    async fn test_send_rejects_non_bing_url_when_restricted() {
+        network_gate::configure(true);
+        let client = Client::new();
+        let error = send(client.get("https://api.github.com/"))
+            .await
+            .expect_err("非 Bing 地址应被拦截");
+        network_gate::configure(false);
+
+        assert!(matches!(error, SendError::Restricted));
+        assert!(is_restricted(&anyhow::Error::new(error).context("Failed")));
+    }
+
+    #[test]
+    fn test_reset_and_keep_alive_change_rebuild_clients() {
+        let before = CLIENT_GENERATION.load(Ordering::SeqCst);
+        reset_clients("test");
+        assert!(CLIENT_GENERATION.load(Ordering::SeqCst) > before);
+
+        let keep_alive = KEEP_ALIVE_SECS.load(Ordering::SeqCst) as u32;
+        let before = CLIENT_GENERATION.load(Ordering::SeqCst);
+        configure_keep_alive(keep_alive + 1);
+        assert!(CLIENT_GENERATION.load(Ordering::SeqCst) > before);
+        configure_keep_alive(keep_alive);
+    }
+}
@@ -0,0 +1,296 @@
+//! 空闲时预取缺失的壁纸图片
+//!
+//! 历史归档回填等途径只写入元数据，图片在首次查看时才按需下载。开启 `idle_prefetch` 后，
+//! 系统空闲（一段时间没有键盘鼠标输入）且网络不按流量计费时，以低并发依次补齐这些图片，
+//! 让画廊逐步可以离线浏览。检测到用户重新活动时立即中止本轮预取，下次空闲再继续。
+//!
+//! - 空闲时长：Windows 使用 `GetLastInputInfo`，macOS 使用 `CGEventSourceSecondsSinceLastEventType`；
+//!   其他平台无法检测，不会预取。
+//! - 计量网络：Windows 使用 `GetNetworkConnectivityHint`；其他平台无法检测，视为不计量。
+
+use log::{debug, info, warn};
+use std::path::Path;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::task::JoinSet;
+
+use crate::models::LocalWallpaper;
+use crate::{AppState, download_manager, events, get_effective_mkt, storage, update_cycle};
+
+/// 调度器中空闲预取任务的名称
+pub(crate) const IDLE_PREFETCH_JOB: &str = "idle_prefetch";
+
+/// 无输入超过该时长视为空闲
+const IDLE_THRESHOLD: Duration = Duration::from_secs(5 * 60);
+/// 未在预取时检查空闲状态的间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// 预取期间检查用户是否恢复活动的间隔
+const ACTIVITY_POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// 同时进行的下载数
+const PREFETCH_CONCURRENCY: usize = 2;
+
+/// 距离最近一次用户输入的时长（平台不支持时返回 None）
+#[cfg(target_os = "windows")]
+fn system_idle_time() -> Option<Duration> {
+    use windows_sys::Win32::System::SystemInformation::GetTickCount;
+    use windows_sys::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+    let mut info = LASTINPUTINFO {
+        cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+        dwTime: 0,
+    };
+    // SAFETY: info 为有效的 LASTINPUTINFO，cbSize 已正确设置
+    if unsafe { GetLastInputInfo(&mut info) } == 0 {
+        return None;
+    }
+    // GetTickCount 约 49.7 天回绕一次，使用 wrapping_sub 得到正确的差值
+    let elapsed_ms = unsafe { GetTickCount() }.wrapping_sub(info.dwTime);
+    Some(Duration::from_millis(u64::from(elapsed_ms)))
+}
+
+#[cfg(target_os = "macos")]
+fn system_idle_time() -> Option<Duration> {
+    /// kCGEventSourceStateCombinedSessionState
+    const COMBINED_SESSION_STATE: i32 = 0;
+    /// kCGAnyInputEventType
+    const ANY_INPUT_EVENT_TYPE: u32 = u32::MAX;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    unsafe extern "C" {
+        fn CGEventSourceSecondsSinceLastEventType(state_id: i32, event_type: u32) -> f64;
+    }
+
+    // SAFETY: 纯查询函数，参数为文档定义的常量
+    let seconds = unsafe {
+        CGEventSourceSecondsSinceLastEventType(COMBINED_SESSION_STATE, ANY_INPUT_EVENT_TYPE)
+    };
+    (seconds.is_finite() && seconds >= 0.0).then(|| Duration::from_secs_f64(seconds))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn system_idle_time() -> Option<Duration> {
+    None
+}
+
+/// 当前网络是否按流量计费（平台不支持检测或检测失败时返回 None）
+#[cfg(target_os = "windows")]
+fn is_metered_network() -> Option<bool> {
+    use windows_sys::Win32::NetworkManagement::IpHelper::GetNetworkConnectivityHint;
+    use windows_sys::Win32::Networking::WinSock::{
+        NL_NETWORK_CONNECTIVITY_HINT, NetworkConnectivityCostHintFixed,
+        NetworkConnectivityCostHintUnrestricted, NetworkConnectivityCostHintVariable,
+    };
+
+    // SAFETY: 结构体为纯数据，全零是合法的初始值
+    let mut hint: NL_NETWORK_CONNECTIVITY_HINT = unsafe { std::mem::zeroed() };
+    // SAFETY: hint 指向有效的可写内存
+    if unsafe { GetNetworkConnectivityHint(&mut hint) } != 0 {
+        return None;
+    }
+    if hint.Roaming || hint.OverDataLimit || hint.ApproachingDataLimit {
+        return Some(true);
+    }
+    match hint.ConnectivityCost {
+        NetworkConnectivityCostHintUnrestricted => Some(false),
+        NetworkConnectivityCostHintFixed | NetworkConnectivityCostHintVariable => Some(true),
+        _ => None,
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn is_metered_network() -> Option<bool> {
+    None
+}
+
+fn is_idle(idle_time: Option<Duration>) -> bool {
+    idle_time.is_some_and(|idle| idle >= IDLE_THRESHOLD)
+}
+
+/// 挑出只有元数据、图片尚未下载的壁纸（保持索引顺序，即从新到旧）
+fn select_prefetch_targets(
+    wallpapers: Vec<LocalWallpaper>,
+    wallpaper_dir: &Path,
+) -> Vec<LocalWallpaper> {
+    wallpapers
+        .into_iter()
+        .filter(|wallpaper| {
+            !wallpaper.urlbase.is_empty()
+                && !storage::get_wallpaper_path(wallpaper_dir, &wallpaper.end_date).exists()
+        })
+        .collect()
+}
+
+/// 当前是否满足预取条件（设置已开启、空闲、非计量网络、后台下载未暂停、没有正在进行的更新）
+async fn should_prefetch(app: &AppHandle) -> bool {
+    let state = app.state::<AppState>();
+    if !state.settings.read().await.idle_prefetch || state.update_state.lock().await.is_busy() {
+        return false;
+    }
+    // 无法判断是否计量时视为不计量
+    is_idle(system_idle_time())
+        && is_metered_network() != Some(true)
+        && !update_cycle::downloads_paused(app).await
+}
+
+/// 等待用户恢复活动
+async fn wait_for_activity() {
+    while is_idle(system_idle_time()) {
+        tokio::time::sleep(ACTIVITY_POLL_INTERVAL).await;
+    }
+}
+
+/// 以低并发下载全部缺失图片，返回成功数量
+async fn prefetch_missing(app: &AppHandle, targets: Vec<LocalWallpaper>) -> usize {
+    let wallpaper_dir = app
+        .state::<AppState>()
+        .wallpaper_directory
+        .lock()
+        .await
+        .clone();
+    let ladder = download_manager::landscape_ladder_for(app).await;
+    let jpeg_quality = download_manager::jpeg_quality_for(app).await;
+
+    let mut targets = targets.into_iter();
+    let mut tasks = JoinSet::new();
+    let mut downloaded = 0;
+    loop {
+        while tasks.len() < PREFETCH_CONCURRENCY
+            && let Some(wallpaper) = targets.next()
+        {
+            let wallpaper_dir = wallpaper_dir.clone();
+            tasks.spawn(async move {
+                let result = download_manager::download_landscape_wallpaper(
+                    &wallpaper.urlbase,
+                    &wallpaper.end_date,
+                    &wallpaper_dir,
+                    ladder,
+                    jpeg_quality,
+                )
+                .await;
+                (wallpaper.end_date, result)
+            });
+        }
+        let Some(joined) = tasks.join_next().await else {
+            break;
+        };
+        match joined {
+            Ok((end_date, Ok(_))) => {
+                downloaded += 1;
+                let _ = events::IMAGE_DOWNLOADED.emit(app, &end_date);
+            }
+            Ok((end_date, Err(e))) => {
+                warn!(target: "idle_prefetch", "预取壁纸失败 {}: {}", end_date, e);
+            }
+            Err(e) => warn!(target: "idle_prefetch", "预取任务异常: {}", e),
+        }
+    }
+    downloaded
+}
+
+/// 执行一轮预取（用户恢复活动时中止）
+async fn run_prefetch_round(app: &AppHandle) {
+    let (wallpaper_dir, mkt) = {
+        let state = app.state::<AppState>();
+        let dir = state.wallpaper_directory.lock().await.clone();
+        (dir, get_effective_mkt(&state).await)
+    };
+    let wallpapers = match storage::get_local_wallpapers(&wallpaper_dir, &mkt).await {
+        Ok(wallpapers) => wallpapers,
+        Err(e) => {
+            warn!(target: "idle_prefetch", "读取壁纸元数据失败: {}", e);
+            return;
+        }
+    };
+    let targets = match tokio::task::spawn_blocking(move || {
+        select_prefetch_targets(wallpapers, &wallpaper_dir)
+    })
+    .await
+    {
+        Ok(targets) => targets,
+        Err(e) => {
+            warn!(target: "idle_prefetch", "扫描缺失图片任务异常: {}", e);
+            return;
+        }
+    };
+    if targets.is_empty() {
+        debug!(target: "idle_prefetch", "没有需要预取的图片");
+        return;
+    }
+
+    let total = targets.len();
+    info!(target: "idle_prefetch", "系统空闲，开始预取 {} 张缺失图片", total);
+    // 丢弃预取 future 时 JoinSet 会中止所有进行中的下载
+    tokio::select! {
+        downloaded = prefetch_missing(app, targets) => {
+            info!(target: "idle_prefetch", "预取完成: {}/{}", downloaded, total);
+        }
+        _ = wait_for_activity() => {
+            info!(target: "idle_prefetch", "检测到用户活动，暂停预取");
+        }
+    }
+}
+
+/// 启动空闲预取后台任务
+pub(crate) fn start_idle_prefetch_task(app: AppHandle) {
+    let scheduler = app.state::<AppState>().scheduler.clone();
+    scheduler.spawn(IDLE_PREFETCH_JOB, async move {
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+            if should_prefetch(&app).await {
+                run_prefetch_round(&app).await;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wallpaper(end_date: &str, urlbase: &str) -> LocalWallpaper {
+        LocalWallpaper {
+            title: String::new(),
+            copyright: String::new(),
+            copyright_link: String::new(),
+            end_date: end_date.to_string(),
+            urlbase: urlbase.to_string(),
+            resolution: None,
+            portrait_available: None,
+            watermark_free: None,
+            recompression: None,
+        }
+    }
+
+    #[test]
+    fn test_idle_requires_known_idle_time_over_threshold() {
+        assert!(!is_idle(None));
+        assert!(!is_idle(Some(Duration::from_secs(60))));
+        assert!(is_idle(Some(IDLE_THRESHOLD)));
+        assert!(is_idle(Some(Duration::from_secs(3600))));
+    }
+
+    #[test]
+    fn test_select_prefetch_targets_skips_downloaded_and_unfetchable() {
+        let unique = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("bw_idle_prefetch_{unique}"));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(storage::get_wallpaper_path(&dir, "20240102"), b"jpeg").unwrap();
+
+        let targets = select_prefetch_targets(
+            vec![
+                wallpaper("20240103", "/th?id=OHR.C"),
+                wallpaper("20240102", "/th?id=OHR.B"),
+                wallpaper("20240101", ""),
+                wallpaper("20231231", "/th?id=OHR.A"),
+            ],
+            &dir,
+        );
+        let dates: Vec<_> = targets.iter().map(|w| w.end_date.as_str()).collect();
+        assert_eq!(dates, vec!["20240103", "20231231"]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
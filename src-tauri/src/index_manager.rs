@@ -1,12 +1,37 @@
-use crate::models::{LocalWallpaper, WallpaperIndex};
+use crate::models::{LocalWallpaper, Recompression, SPOTLIGHT_MKT, WallpaperIndex};
 use anyhow::{Context, Result};
+use chrono::NaiveDate;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 use tokio::fs;
 use tokio::sync::Mutex;
 
 /// 索引文件名
-const INDEX_FILE: &str = "index.json";
+pub(crate) const INDEX_FILE: &str = "index.json";
+
+/// 跨进程写锁文件名
+///
+/// 多台机器共享同一网络壁纸目录时，通过对该文件加建议锁串行化索引写入。
+const LOCK_FILE: &str = "index.lock";
+
+/// 索引定期备份所在的子目录
+const BACKUP_DIR: &str = "backups";
+
+/// 索引备份文件名前缀，完整文件名为 `index-backup-YYYYMMDD.json`
+const BACKUP_PREFIX: &str = "index-backup-";
+
+/// 最多保留的索引备份数量，超出后删除最旧的备份
+const BACKUP_RETENTION: usize = 8;
+
+/// 等待其他实例释放写锁的最长时间
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 轮询写锁的间隔
+const LOCK_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 磁盘上 index.json 的版本标识（修改时间, 文件大小），用于发现其他实例的写入
+type DiskStamp = (SystemTime, u64);
 
 /// 索引最大条目数限制（基于唯一日期数）
 ///
@@ -30,6 +55,15 @@ const MAX_INDEX_COUNT: usize = 2000;
 pub struct IndexManager {
     directory: PathBuf,
     cache: Arc<Mutex<Option<WallpaperIndex>>>,
+    /// 最近一次由本实例读取或写入时的磁盘版本标识
+    disk_stamp: Arc<Mutex<Option<DiskStamp>>>,
+    /// 进程内写入串行化（跨进程由锁文件负责）
+    write_lock: Arc<Mutex<()>>,
+}
+
+/// 索引写锁，drop 时释放
+struct IndexFileLock {
+    _file: Option<std::fs::File>,
 }
 
 impl IndexManager {
@@ -41,6 +75,8 @@ impl IndexManager {
         Self {
             directory,
             cache: Arc::new(Mutex::new(None)),
+            disk_stamp: Arc::new(Mutex::new(None)),
+            write_lock: Arc::new(Mutex::new(())),
         }
     }
 
@@ -49,52 +85,109 @@ impl IndexManager {
         self.directory.join(INDEX_FILE)
     }
 
-    /// 从任意路径加载 index.json（只读，不走缓存，不回写迁移）
-    ///
-    /// 用于导入场景：读取外部壁纸目录的 index.json 并解析为 WallpaperIndex。
-    /// 支持 v4 和 v5 格式（通过 serde alias 自动兼容）。
-    /// 不支持的版本返回错误（与 `load_from_disk` 的静默降级不同，导入需要明确失败）。
-    pub async fn load_external_index(path: &Path) -> Result<WallpaperIndex> {
-        let index_path = path.join(INDEX_FILE);
+    /// 读取磁盘上索引文件的版本标识，文件不存在时返回 `None`
+    async fn read_disk_stamp(&self) -> Option<DiskStamp> {
+        let metadata = fs::metadata(self.index_path()).await.ok()?;
+        Some((metadata.modified().ok()?, metadata.len()))
+    }
 
-        let contents = match fs::read_to_string(&index_path).await {
-            Ok(c) => c,
-            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
-                anyhow::bail!("Index file not found: {}", index_path.display());
-            }
-            Err(e) => {
-                return Err(anyhow::Error::new(e).context(format!(
-                    "Failed to read index file: {}",
-                    index_path.display()
-                )));
+    /// 获取跨进程写锁
+    ///
+    /// 文件系统不支持加锁（部分网络共享）时退化为无锁写入，仅记录警告。
+    async fn acquire_file_lock(&self) -> Result<IndexFileLock> {
+        fs::create_dir_all(&self.directory)
+            .await
+            .context("Failed to create directory")?;
+        let lock_path = self.directory.join(LOCK_FILE);
+        let deadline = tokio::time::Instant::now() + LOCK_TIMEOUT;
+
+        loop {
+            let path = lock_path.clone();
+            let attempt = tokio::task::spawn_blocking(move || {
+                let file = std::fs::OpenOptions::new()
+                    .create(true)
+                    .truncate(false)
+                    .write(true)
+                    .open(&path)?;
+                match file.try_lock() {
+                    Ok(()) => Ok(Some(file)),
+                    Err(std::fs::TryLockError::WouldBlock) => Ok(None),
+                    Err(std::fs::TryLockError::Error(e)) => Err(e),
+                }
+            })
+            .await
+            .context("Index lock task panicked")?;
+
+            match attempt {
+                Ok(Some(file)) => return Ok(IndexFileLock { _file: Some(file) }),
+                Ok(None) if tokio::time::Instant::now() < deadline => {
+                    tokio::time::sleep(LOCK_POLL_INTERVAL).await;
+                }
+                Ok(None) => {
+                    anyhow::bail!("Timed out waiting for index lock: {}", lock_path.display());
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::Unsupported => {
+                    log::warn!("当前文件系统不支持文件锁，索引写入将不加锁: {}", e);
+                    return Ok(IndexFileLock { _file: None });
+                }
+                Err(e) => {
+                    return Err(anyhow::Error::new(e)
+                        .context(format!("Failed to lock index: {}", lock_path.display())));
+                }
             }
-        };
+        }
+    }
 
-        let json_value: serde_json::Value = serde_json::from_str(&contents)
-            .with_context(|| format!("Failed to parse JSON: {}", index_path.display()))?;
+    /// 在写锁保护下读取-修改-写回索引
+    ///
+    /// 若磁盘上的索引在本实例上次读写之后被其他实例修改过，先重新加载最新内容，
+    /// 再在其上重新应用本次修改，避免覆盖对方的写入。
+    /// `apply` 返回 `(结果, 是否需要写盘)`。
+    async fn modify_index<R>(
+        &self,
+        apply: impl FnOnce(&mut WallpaperIndex) -> (R, bool),
+    ) -> Result<R> {
+        let _local_guard = self.write_lock.lock().await;
+        let _file_lock = self.acquire_file_lock().await?;
 
-        let file_version = json_value
-            .get("version")
-            .and_then(|v| v.as_u64())
-            .unwrap_or(0) as u32;
+        let has_cache = self.cache.lock().await.is_some();
+        let known_stamp = *self.disk_stamp.lock().await;
+        let changed_on_disk = has_cache && self.read_disk_stamp().await != known_stamp;
 
-        if file_version != WallpaperIndex::VERSION
-            && file_version != WallpaperIndex::MIGRATE_FROM_VERSION
-        {
-            anyhow::bail!(
-                "Unsupported index version: v{} (supported: v{}, v{})",
-                file_version,
-                WallpaperIndex::MIGRATE_FROM_VERSION,
-                WallpaperIndex::VERSION
+        let (mut index, reconciled) = if changed_on_disk {
+            log::info!(
+                "检测到其他实例修改了索引，重新加载后合并本次修改，路径: {}",
+                self.index_path().display()
             );
-        }
+            match self.load_from_disk().await {
+                Ok((index, reconciled)) => {
+                    *self.cache.lock().await = Some(index.clone());
+                    (index, reconciled)
+                }
+                Err(e) => {
+                    log::warn!("重新加载索引失败 ({})，基于缓存写入", e);
+                    self.load_cached().await?
+                }
+            }
+        } else {
+            self.load_cached().await?
+        };
 
-        let mut index: WallpaperIndex = serde_json::from_value(json_value).with_context(|| {
-            format!("Failed to deserialize index file: {}", index_path.display())
-        })?;
+        let (result, needs_save) = apply(&mut index);
+        if needs_save || reconciled {
+            self.write_index(&index).await?;
+        }
+        Ok(result)
+    }
 
-        index.version = WallpaperIndex::VERSION;
-        index.sort_all();
+    /// 从任意路径加载 index.json（只读，不走缓存，不回写迁移）
+    ///
+    /// 用于导入场景：读取外部壁纸目录的 index.json 并解析为 WallpaperIndex。
+    /// 支持 v4 和 v5 格式（通过 serde alias 自动兼容）。
+    /// 不支持的版本返回错误（与 `load_from_disk` 的静默降级不同，导入需要明确失败）。
+    pub async fn load_external_index(path: &Path) -> Result<WallpaperIndex> {
+        let index_path = path.join(INDEX_FILE);
+        let index = read_index_file(&index_path).await?;
 
         log::info!(
             "成功加载外部索引文件，包含 {} 个 mkt，共 {} 张壁纸，路径: {}",
@@ -111,6 +204,28 @@ impl IndexManager {
     /// 如果缓存中有数据，直接返回缓存；否则从磁盘加载。
     /// 如果磁盘上没有索引文件，返回空索引。
     pub async fn load_index(&self) -> Result<WallpaperIndex> {
+        let (index, reconciled) = self.load_cached().await?;
+        // 回写失败（如目录只读）不影响使用，下次加载时再合并
+        if reconciled && let Err(e) = self.write_reconciled(&index).await {
+            log::warn!("回写合并后的索引失败: {:#}", e);
+        }
+        Ok(index)
+    }
+
+    /// 在写锁内回写刚合并过重复条目的索引
+    ///
+    /// 加载之后磁盘上的索引已被其他实例修改时不回写，下次写入时重新加载并合并。
+    async fn write_reconciled(&self, index: &WallpaperIndex) -> Result<()> {
+        let _local_guard = self.write_lock.lock().await;
+        let _file_lock = self.acquire_file_lock().await?;
+        if self.read_disk_stamp().await != *self.disk_stamp.lock().await {
+            return Ok(());
+        }
+        self.write_index(index).await
+    }
+
+    /// 加载索引（优先使用缓存），第二个返回值含义同 [`Self::load_from_disk`]
+    async fn load_cached(&self) -> Result<(WallpaperIndex, bool)> {
         let index_path = self.index_path();
 
         // 检查缓存
@@ -122,14 +237,14 @@ impl IndexManager {
                     index.mkt.len(),
                     index_path.display()
                 );
-                return Ok(index.clone());
+                return Ok((index.clone(), false));
             }
         }
 
         // 从磁盘加载
         log::debug!("从磁盘加载索引，路径: {}", index_path.display());
-        let index = match self.load_from_disk().await {
-            Ok(index) => {
+        let (index, reconciled) = match self.load_from_disk().await {
+            Ok((index, reconciled)) => {
                 let mkt_count = index.mkt.len();
                 let total_wallpapers: usize = index.mkt.values().map(|m| m.len()).sum();
                 log::info!(
@@ -138,7 +253,7 @@ impl IndexManager {
                     total_wallpapers,
                     index_path.display()
                 );
-                index
+                (index, reconciled)
             }
             Err(e) => {
                 log::warn!(
@@ -146,7 +261,7 @@ impl IndexManager {
                     e,
                     index_path.display()
                 );
-                WallpaperIndex::default()
+                (WallpaperIndex::default(), false)
             }
         };
 
@@ -156,18 +271,23 @@ impl IndexManager {
             *cache = Some(index.clone());
         }
 
-        Ok(index)
+        Ok((index, reconciled))
     }
 
     /// 从磁盘加载索引
-    async fn load_from_disk(&self) -> Result<WallpaperIndex> {
+    ///
+    /// 第二个返回值表示是否刚在内存中合并了重复条目（见 `WallpaperIndex::reconcile_once`），
+    /// 由调用方在持有写锁时回写。
+    async fn load_from_disk(&self) -> Result<(WallpaperIndex, bool)> {
         let path = self.index_path();
         if !path.exists() {
             log::debug!("索引文件不存在，返回空索引，路径: {}", path.display());
-            return Ok(WallpaperIndex::default());
+            return Ok((WallpaperIndex::default(), false));
         }
 
         log::debug!("读取索引文件，路径: {}", path.display());
+        // 先取版本标识再读内容：读取期间若有其他实例写入，下次写入前仍能发现
+        *self.disk_stamp.lock().await = self.read_disk_stamp().await;
         let contents = fs::read_to_string(&path)
             .await
             .with_context(|| format!("Failed to read index file: {}", path.display()))?;
@@ -190,7 +310,8 @@ impl IndexManager {
                 .with_context(|| format!("Failed to deserialize index file: {}", path.display()))?;
             index.sort_all();
             log::debug!("索引文件加载成功，版本: v{}", index.version);
-            return Ok(index);
+            let reconciled = index.reconcile_once();
+            return Ok((index, reconciled));
         }
 
         if file_version == WallpaperIndex::MIGRATE_FROM_VERSION {
@@ -202,16 +323,31 @@ impl IndexManager {
                 path.display()
             );
 
-            // 1. 备份旧文件
+            // 1. 备份旧文件（只读存档无法备份和回写，只在内存中迁移）
             let backup_path = path.with_extension(format!("json.v{file_version}.bak"));
-            fs::copy(&path, &backup_path).await.with_context(|| {
-                format!(
-                    "Failed to backup index file: {} → {}",
-                    path.display(),
-                    backup_path.display()
-                )
-            })?;
-            log::info!("已备份旧索引文件: {}", backup_path.display());
+            let writable = match fs::copy(&path, &backup_path).await {
+                Ok(_) => {
+                    log::info!("已备份旧索引文件: {}", backup_path.display());
+                    true
+                }
+                Err(e)
+                    if matches!(
+                        e.kind(),
+                        std::io::ErrorKind::PermissionDenied
+                            | std::io::ErrorKind::ReadOnlyFilesystem
+                    ) =>
+                {
+                    log::warn!("索引目录不可写，仅在内存中迁移: {}", e);
+                    false
+                }
+                Err(e) => {
+                    return Err(anyhow::Error::new(e).context(format!(
+                        "Failed to backup index file: {} → {}",
+                        path.display(),
+                        backup_path.display()
+                    )));
+                }
+            };
 
             // 2. 反序列化（serde alias 自动兼容 wallpapers_by_language → mkt）
             let mut index: WallpaperIndex =
@@ -222,19 +358,23 @@ impl IndexManager {
                     )
                 })?;
 
-            // 3. 升级版本号
+            // 3. 升级版本号，合并语言键与市场键下的重复条目
             index.version = WallpaperIndex::VERSION;
             index.sort_all();
+            index.reconcile_once();
+            if !writable {
+                return Ok((index, false));
+            }
 
-            // 4. 回写新格式
-            self.save_index(&index).await?;
+            // 4. 回写新格式（可能发生在 modify_index 持锁期间，因此不再加锁）
+            self.write_index(&index).await?;
             log::info!(
                 "索引迁移完成 v{} → v{}，路径: {}",
                 file_version,
                 WallpaperIndex::VERSION,
                 path.display()
             );
-            return Ok(index);
+            return Ok((index, false));
         }
 
         // 不支持的旧版本，返回空索引
@@ -244,15 +384,15 @@ impl IndexManager {
             file_version,
             path.display()
         );
-        Ok(WallpaperIndex::default())
+        Ok((WallpaperIndex::default(), false))
     }
 
-    /// 保存索引到磁盘
+    /// 保存索引到磁盘（调用方负责持有写锁）
     ///
     /// 使用原子写入（临时文件 + 重命名）确保数据完整性。
     /// 直接序列化 WallpaperIndex，支持多语言。
     /// 使用紧凑格式以节省存储空间。
-    pub async fn save_index(&self, index: &WallpaperIndex) -> Result<()> {
+    async fn write_index(&self, index: &WallpaperIndex) -> Result<()> {
         // 序列化为 JSON（紧凑格式，节省存储空间）
         let json = serde_json::to_string(index).context("Failed to serialize index")?;
 
@@ -270,6 +410,7 @@ impl IndexManager {
         fs::rename(&temp_path, self.index_path())
             .await
             .context("Failed to rename index file")?;
+        *self.disk_stamp.lock().await = self.read_disk_stamp().await;
 
         // 更新缓存
         {
@@ -284,6 +425,8 @@ impl IndexManager {
     ///
     /// 一次性写入多个壁纸，比多次调用 `upsert_wallpaper` 效率高。
     /// 如果索引数据超过最大限制（默认 2000 个唯一日期），会自动清理最旧的条目。
+    /// 与索引中已有内容完全相同时不写盘：更新循环每天多次拿到同样的元数据，
+    /// 大型存档每次重写整个 index.json 会造成不必要的写入（SSD 磨损、NAS 目录同步）。
     /// 返回实际新增的条目数（不含覆盖已存在的条目）。
     ///
     /// # Arguments
@@ -298,14 +441,100 @@ impl IndexManager {
             return Ok(0);
         }
 
-        let mut index = self.load_index().await?;
-        let new_count = index.upsert_wallpapers_for_mkt(language, wallpapers);
+        self.modify_index(|index| {
+            let (new_count, changed) = index.upsert_wallpapers_tracked(language, wallpapers);
 
-        // 限制索引数量，防止 JSON 文件过大
-        index.limit_index_size(MAX_INDEX_COUNT);
+            // 限制索引数量，防止 JSON 文件过大
+            let trimmed = index.limit_index_size(MAX_INDEX_COUNT);
+            (new_count, changed || trimmed)
+        })
+        .await
+    }
+
+    /// 记录指定日期壁纸实际下载的分辨率
+    ///
+    /// 仅在有条目变化时写盘。返回是否发生了变化。
+    pub async fn set_resolution(&self, end_date: &str, resolution: &str) -> Result<bool> {
+        self.modify_index(|index| {
+            let changed = index.set_resolution(end_date, resolution);
+            (changed, changed)
+        })
+        .await
+    }
+
+    /// 记录指定日期横屏图片的重新压缩结果
+    ///
+    /// 仅在有条目变化时写盘。返回是否发生了变化。
+    pub async fn set_recompression(
+        &self,
+        end_date: &str,
+        recompression: Option<Recompression>,
+    ) -> Result<bool> {
+        self.modify_index(|index| {
+            let changed = index.set_recompression(end_date, recompression);
+            (changed, changed)
+        })
+        .await
+    }
+
+    /// 从所有 mkt 中删除指定键的条目
+    ///
+    /// 仅在有条目变化时写盘。返回是否发生了变化。
+    pub async fn remove_entries(&self, keys: &[String]) -> Result<bool> {
+        self.modify_index(|index| {
+            let changed = index.remove_entries(keys);
+            (changed, changed)
+        })
+        .await
+    }
 
-        self.save_index(&index).await?;
-        Ok(new_count)
+    /// 为壁纸添加标签（`tag` 需已归一化），返回添加后的标签
+    ///
+    /// 仅在有变化时写盘。
+    pub async fn add_tag(&self, end_date: &str, tag: &str) -> Result<Vec<String>> {
+        self.modify_index(|index| {
+            let changed = index.add_tag(end_date, tag);
+            (index.tags_for(end_date), changed)
+        })
+        .await
+    }
+
+    /// 移除壁纸的标签，返回移除后的标签
+    ///
+    /// 仅在有变化时写盘。
+    pub async fn remove_tag(&self, end_date: &str, tag: &str) -> Result<Vec<String>> {
+        self.modify_index(|index| {
+            let changed = index.remove_tag(end_date, tag);
+            (index.tags_for(end_date), changed)
+        })
+        .await
+    }
+
+    /// 记录指定日期壁纸磁盘上的横屏图片是否为无水印版本
+    ///
+    /// 仅在有条目变化时写盘。返回是否发生了变化。
+    pub async fn set_watermark_free(&self, end_date: &str, watermark_free: bool) -> Result<bool> {
+        self.modify_index(|index| {
+            let changed = index.set_watermark_free(end_date, watermark_free);
+            (changed, changed)
+        })
+        .await
+    }
+
+    /// 记录指定 mkt 某日竖屏版本是否可用
+    ///
+    /// 仅在有条目变化时写盘。返回是否发生了变化。
+    pub async fn set_portrait_available(
+        &self,
+        mkt: &str,
+        end_date: &str,
+        available: bool,
+    ) -> Result<bool> {
+        self.modify_index(|index| {
+            let changed = index.set_portrait_available(mkt, end_date, available);
+            (changed, changed)
+        })
+        .await
     }
 
     /// 获取所有壁纸（排序）
@@ -329,18 +558,198 @@ impl IndexManager {
         Ok(wallpapers)
     }
 
-    /// 获取 index.json 中所有可用的 mkt key
+    /// 分页获取壁纸列表，返回当前页和该 mkt 的壁纸总数
+    pub async fn get_wallpapers_page(
+        &self,
+        mkt: &str,
+        offset: usize,
+        limit: Option<usize>,
+    ) -> Result<(Vec<LocalWallpaper>, usize)> {
+        let index = self.load_index().await?;
+        Ok(index.get_wallpapers_page_for_mkt(mkt, offset, limit))
+    }
+
+    /// 获取 index.json 中所有可用的 mkt key（不含 Windows 聚焦图片）
     ///
     /// 用于 fallback 场景：当 effective_mkt 对应的壁纸列表为空时，
     /// 可从可用 key 中选择最匹配的 mkt。
     pub async fn get_available_mkt_keys(&self) -> Result<Vec<String>> {
         let index = self.load_index().await?;
-        let mut keys: Vec<String> = index.mkt.keys().cloned().collect();
+        let mut keys: Vec<String> = index
+            .mkt
+            .keys()
+            .filter(|mkt| mkt.as_str() != SPOTLIGHT_MKT)
+            .cloned()
+            .collect();
         // 排序确保返回顺序稳定，避免上层 fallback 使用时出现随机行为。
         keys.sort();
         log::debug!("index.json 可用 mkt keys: {:?}", keys);
         Ok(keys)
     }
+
+    /// 索引备份目录
+    fn backup_dir(&self) -> PathBuf {
+        self.directory.join(BACKUP_DIR)
+    }
+
+    fn backup_path(&self, date: &str) -> PathBuf {
+        self.backup_dir()
+            .join(format!("{BACKUP_PREFIX}{date}.json"))
+    }
+
+    /// 列出已有的索引备份日期（YYYYMMDD，按日期降序）
+    pub async fn list_backups(&self) -> Result<Vec<String>> {
+        let mut entries = match fs::read_dir(self.backup_dir()).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(anyhow::Error::new(e).context("Failed to read backup directory")),
+        };
+
+        let mut dates = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            if let Some(date) = entry.file_name().to_str().and_then(backup_date) {
+                dates.push(date.to_string());
+            }
+        }
+        dates.sort_unstable_by(|a, b| b.cmp(a));
+        Ok(dates)
+    }
+
+    /// 距上次备份已满 `interval_days` 天时，把 index.json 备份到 `backups/` 子目录
+    ///
+    /// 只备份能正常解析且非空的索引，避免用已损坏的索引挤掉可用的备份；
+    /// 超出保留数量的旧备份会被删除。`interval_days` 为 0 时不备份。
+    /// 返回新写入的备份文件路径，未到备份时间时返回 `None`。
+    pub async fn backup_if_due(
+        &self,
+        today: NaiveDate,
+        interval_days: u32,
+    ) -> Result<Option<PathBuf>> {
+        if interval_days == 0 {
+            return Ok(None);
+        }
+
+        let backups = self.list_backups().await?;
+        let latest = backups
+            .first()
+            .and_then(|date| NaiveDate::parse_from_str(date, "%Y%m%d").ok());
+        if latest.is_some_and(|latest| (today - latest).num_days() < i64::from(interval_days)) {
+            return Ok(None);
+        }
+
+        let index_path = self.index_path();
+        if !index_path.exists() {
+            return Ok(None);
+        }
+        let index = match read_index_file(&index_path).await {
+            Ok(index) => index,
+            Err(e) => {
+                log::warn!("索引文件无法解析，跳过本次备份: {}", e);
+                return Ok(None);
+            }
+        };
+        if index.mkt.values().all(|m| m.is_empty()) {
+            return Ok(None);
+        }
+
+        let json = serde_json::to_string(&index).context("Failed to serialize index")?;
+        fs::create_dir_all(self.backup_dir())
+            .await
+            .context("Failed to create backup directory")?;
+        let backup_path = self.backup_path(&today.format("%Y%m%d").to_string());
+        let temp_path = backup_path.with_extension("tmp");
+        fs::write(&temp_path, json)
+            .await
+            .context("Failed to write temporary backup file")?;
+        fs::rename(&temp_path, &backup_path)
+            .await
+            .context("Failed to rename backup file")?;
+        log::info!("已备份索引文件: {}", backup_path.display());
+
+        for date in self.list_backups().await?.iter().skip(BACKUP_RETENTION) {
+            let expired = self.backup_path(date);
+            if let Err(e) = fs::remove_file(&expired).await {
+                log::warn!("删除过期索引备份失败: {}, {}", expired.display(), e);
+            }
+        }
+
+        Ok(Some(backup_path))
+    }
+
+    /// 用指定日期的备份替换当前索引，返回恢复后的壁纸条目总数
+    ///
+    /// 备份不存在时返回 `Ok(None)`；备份无法解析时返回错误，当前索引保持不变。
+    pub async fn restore_backup(&self, date: &str) -> Result<Option<usize>> {
+        let backup_path = self.backup_path(date);
+        if !backup_path.exists() {
+            return Ok(None);
+        }
+        let index = read_index_file(&backup_path).await?;
+
+        let _local_guard = self.write_lock.lock().await;
+        let _file_lock = self.acquire_file_lock().await?;
+        self.write_index(&index).await?;
+
+        let total = index.mkt.values().map(|m| m.len()).sum();
+        log::info!(
+            "已从备份恢复索引，共 {} 条壁纸，备份: {}",
+            total,
+            backup_path.display()
+        );
+        Ok(Some(total))
+    }
+}
+
+/// 读取并校验 index.json 格式的文件（v4 或 v5），不支持的版本返回错误
+async fn read_index_file(index_path: &Path) -> Result<WallpaperIndex> {
+    let contents = match fs::read_to_string(index_path).await {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            anyhow::bail!("Index file not found: {}", index_path.display());
+        }
+        Err(e) => {
+            return Err(anyhow::Error::new(e).context(format!(
+                "Failed to read index file: {}",
+                index_path.display()
+            )));
+        }
+    };
+
+    let json_value: serde_json::Value = serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to parse JSON: {}", index_path.display()))?;
+
+    let file_version = json_value
+        .get("version")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0) as u32;
+
+    if file_version != WallpaperIndex::VERSION
+        && file_version != WallpaperIndex::MIGRATE_FROM_VERSION
+    {
+        anyhow::bail!(
+            "Unsupported index version: v{} (supported: v{}, v{})",
+            file_version,
+            WallpaperIndex::MIGRATE_FROM_VERSION,
+            WallpaperIndex::VERSION
+        );
+    }
+
+    let mut index: WallpaperIndex = serde_json::from_value(json_value)
+        .with_context(|| format!("Failed to deserialize index file: {}", index_path.display()))?;
+
+    index.version = WallpaperIndex::VERSION;
+    index.sort_all();
+    index.reconcile_once();
+
+    Ok(index)
+}
+
+/// 从备份文件名中解析日期（`index-backup-YYYYMMDD.json` → `YYYYMMDD`）
+fn backup_date(file_name: &str) -> Option<&str> {
+    let date = file_name
+        .strip_prefix(BACKUP_PREFIX)?
+        .strip_suffix(".json")?;
+    (date.len() == 8 && date.bytes().all(|b| b.is_ascii_digit())).then_some(date)
 }
 
 #[cfg(test)]
@@ -383,6 +792,10 @@ mod tests {
             copyright_link: "https://example.com".to_string(),
             end_date: "20240102".to_string(),
             urlbase: "/th?id=OHR.TestWallpaper".to_string(),
+            resolution: None,
+            portrait_available: None,
+            watermark_free: None,
+            recompression: None,
         };
 
         manager
@@ -417,6 +830,10 @@ mod tests {
                 copyright_link: "https://example.com/1".to_string(),
                 end_date: "20240102".to_string(),
                 urlbase: "/th?id=OHR.Wallpaper1".to_string(),
+                resolution: None,
+                portrait_available: None,
+                watermark_free: None,
+                recompression: None,
             },
             LocalWallpaper {
                 title: "Wallpaper 2".to_string(),
@@ -424,6 +841,10 @@ mod tests {
                 copyright_link: "https://example.com/2".to_string(),
                 end_date: "20240103".to_string(),
                 urlbase: "/th?id=OHR.Wallpaper2".to_string(),
+                resolution: None,
+                portrait_available: None,
+                watermark_free: None,
+                recompression: None,
             },
         ];
 
@@ -454,6 +875,10 @@ mod tests {
             copyright_link: "https://example.com".to_string(),
             end_date: "20240102".to_string(),
             urlbase: "/th?id=OHR.PersistTest".to_string(),
+            resolution: None,
+            portrait_available: None,
+            watermark_free: None,
+            recompression: None,
         };
 
         // 第一个管理器实例
@@ -478,6 +903,108 @@ mod tests {
         let _ = fs::remove_dir_all(&temp_dir).await;
     }
 
+    #[tokio::test]
+    async fn test_index_manager_set_resolution_persists() {
+        let unique = SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let temp_dir = std::env::temp_dir().join(format!("bw_index_resolution_{unique}"));
+        fs::create_dir_all(&temp_dir).await.unwrap();
+
+        let wallpaper = LocalWallpaper {
+            title: "Resolution Test".to_string(),
+            copyright: "Test".to_string(),
+            copyright_link: "https://example.com".to_string(),
+            end_date: "20240102".to_string(),
+            urlbase: "/th?id=OHR.ResolutionTest".to_string(),
+            resolution: None,
+            portrait_available: None,
+            watermark_free: None,
+            recompression: None,
+        };
+
+        {
+            let manager = IndexManager::new(temp_dir.clone());
+            manager
+                .upsert_wallpapers(vec![wallpaper], "zh-CN")
+                .await
+                .unwrap();
+            assert!(
+                manager
+                    .set_resolution("20240102", "1920x1200")
+                    .await
+                    .unwrap()
+            );
+            assert!(
+                !manager
+                    .set_resolution("20240102", "1920x1200")
+                    .await
+                    .unwrap()
+            );
+        }
+
+        // 重新加载后分辨率仍然存在
+        {
+            let manager = IndexManager::new(temp_dir.clone());
+            let all = manager.get_all_wallpapers("zh-CN").await.unwrap();
+            assert_eq!(all[0].resolution.as_deref(), Some("1920x1200"));
+        }
+
+        let _ = fs::remove_dir_all(&temp_dir).await;
+    }
+
+    #[tokio::test]
+    async fn test_index_manager_tags_persist_across_upserts() {
+        let unique = SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let temp_dir = std::env::temp_dir().join(format!("bw_index_tags_{unique}"));
+        fs::create_dir_all(&temp_dir).await.unwrap();
+
+        let wallpaper = LocalWallpaper {
+            title: "Tag Test".to_string(),
+            copyright: "Test".to_string(),
+            copyright_link: "https://example.com".to_string(),
+            end_date: "20240102".to_string(),
+            urlbase: "/th?id=OHR.TagTest".to_string(),
+            resolution: None,
+            portrait_available: None,
+            watermark_free: None,
+            recompression: None,
+        };
+
+        {
+            let manager = IndexManager::new(temp_dir.clone());
+            manager
+                .upsert_wallpapers(vec![wallpaper.clone()], "zh-CN")
+                .await
+                .unwrap();
+            manager.add_tag("20240102", "nature").await.unwrap();
+            let tags = manager.add_tag("20240102", "animals").await.unwrap();
+            assert_eq!(tags, ["animals", "nature"]);
+            // 元数据刷新不影响标签
+            manager
+                .upsert_wallpapers(vec![wallpaper], "zh-CN")
+                .await
+                .unwrap();
+        }
+
+        {
+            let manager = IndexManager::new(temp_dir.clone());
+            let tags = manager.remove_tag("20240102", "animals").await.unwrap();
+            assert_eq!(tags, ["nature"]);
+        }
+        {
+            let manager = IndexManager::new(temp_dir.clone());
+            let index = manager.load_index().await.unwrap();
+            assert_eq!(index.tags_for("20240102"), ["nature"]);
+        }
+
+        let _ = fs::remove_dir_all(&temp_dir).await;
+    }
+
     #[tokio::test]
     async fn test_index_manager_end_date_as_key() {
         let unique = SystemTime::now()
@@ -497,6 +1024,10 @@ mod tests {
                 copyright_link: "https://example.com/1".to_string(),
                 end_date: "20240102".to_string(),
                 urlbase: "/th?id=OHR.Wallpaper1".to_string(),
+                resolution: None,
+                portrait_available: None,
+                watermark_free: None,
+                recompression: None,
             },
             LocalWallpaper {
                 title: "Wallpaper 2".to_string(),
@@ -504,6 +1035,10 @@ mod tests {
                 copyright_link: "https://example.com/2".to_string(),
                 end_date: "20240103".to_string(),
                 urlbase: "/th?id=OHR.Wallpaper2".to_string(),
+                resolution: None,
+                portrait_available: None,
+                watermark_free: None,
+                recompression: None,
             },
         ];
 
@@ -555,6 +1090,10 @@ mod tests {
             copyright_link: "https://example.com/zh".to_string(),
             end_date: "20240102".to_string(),
             urlbase: "/th?id=OHR.Wallpaper_ZH-CN".to_string(),
+            resolution: None,
+            portrait_available: None,
+            watermark_free: None,
+            recompression: None,
         };
 
         // 添加英文壁纸
@@ -564,6 +1103,10 @@ mod tests {
             copyright_link: "https://example.com/en".to_string(),
             end_date: "20240102".to_string(),
             urlbase: "/th?id=OHR.Wallpaper_EN-US".to_string(),
+            resolution: None,
+            portrait_available: None,
+            watermark_free: None,
+            recompression: None,
         };
 
         manager
@@ -612,6 +1155,10 @@ mod tests {
             copyright_link: "https://example.com".to_string(),
             end_date: "20240102".to_string(),
             urlbase: "/th?id=OHR.CacheTest".to_string(),
+            resolution: None,
+            portrait_available: None,
+            watermark_free: None,
+            recompression: None,
         };
 
         // 第一次加载（应该从磁盘）
@@ -649,6 +1196,10 @@ mod tests {
             copyright_link: "https://example.com".to_string(),
             end_date: "20240102".to_string(),
             urlbase: "/th?id=OHR.Test".to_string(),
+            resolution: None,
+            portrait_available: None,
+            watermark_free: None,
+            recompression: None,
         };
 
         manager
@@ -663,6 +1214,10 @@ mod tests {
             copyright_link: "https://example.com/updated".to_string(),
             end_date: "20240102".to_string(), // 相同的 end_date
             urlbase: "/th?id=OHR.TestUpdated".to_string(),
+            resolution: None,
+            portrait_available: None,
+            watermark_free: None,
+            recompression: None,
         };
 
         manager
@@ -680,6 +1235,65 @@ mod tests {
         let _ = fs::remove_dir_all(&temp_dir).await;
     }
 
+    #[tokio::test]
+    async fn test_index_manager_skips_write_when_unchanged() {
+        let unique = SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let temp_dir = std::env::temp_dir().join(format!("bw_index_unchanged_{unique}"));
+        fs::create_dir_all(&temp_dir).await.unwrap();
+
+        let manager = IndexManager::new(temp_dir.clone());
+        let wallpaper = LocalWallpaper {
+            title: "Same".to_string(),
+            copyright: "Copyright".to_string(),
+            copyright_link: "https://example.com".to_string(),
+            end_date: "20240102".to_string(),
+            urlbase: "/th?id=OHR.Same".to_string(),
+            resolution: None,
+            portrait_available: None,
+            watermark_free: None,
+            recompression: None,
+        };
+        manager
+            .upsert_wallpapers(vec![wallpaper.clone()], "zh-CN")
+            .await
+            .unwrap();
+
+        // 把文件时间设为固定值，之后没有写盘时应保持不变
+        let index_path = temp_dir.join(INDEX_FILE);
+        let marker = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+        std::fs::File::options()
+            .write(true)
+            .open(&index_path)
+            .unwrap()
+            .set_modified(marker)
+            .unwrap();
+
+        let new_count = manager
+            .upsert_wallpapers(vec![wallpaper.clone()], "zh-CN")
+            .await
+            .unwrap();
+        assert_eq!(new_count, 0);
+        let modified = || std::fs::metadata(&index_path).unwrap().modified().unwrap();
+        assert_eq!(modified(), marker);
+
+        manager
+            .upsert_wallpapers(
+                vec![LocalWallpaper {
+                    title: "Changed".to_string(),
+                    ..wallpaper
+                }],
+                "zh-CN",
+            )
+            .await
+            .unwrap();
+        assert_ne!(modified(), marker);
+
+        let _ = fs::remove_dir_all(&temp_dir).await;
+    }
+
     #[tokio::test]
     async fn test_index_manager_empty_operations() {
         let unique = SystemTime::now()
@@ -720,6 +1334,10 @@ mod tests {
             copyright_link: "https://example.com".to_string(),
             end_date: "20240102".to_string(),
             urlbase: "/th?id=OHR.AtomicTest".to_string(),
+            resolution: None,
+            portrait_available: None,
+            watermark_free: None,
+            recompression: None,
         };
 
         // 保存索引
@@ -762,6 +1380,10 @@ mod tests {
             copyright_link: "https://example.com".to_string(),
             end_date: "20240102".to_string(),
             urlbase: "/th?id=OHR.JsonTest".to_string(),
+            resolution: None,
+            portrait_available: None,
+            watermark_free: None,
+            recompression: None,
         };
 
         manager
@@ -822,6 +1444,35 @@ mod tests {
         let _ = fs::remove_dir_all(&temp_dir).await;
     }
 
+    #[tokio::test]
+    async fn test_index_manager_reconciles_language_keys_once() {
+        let unique = SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let temp_dir = std::env::temp_dir().join(format!("bw_index_reconcile_{unique}"));
+        fs::create_dir_all(&temp_dir).await.unwrap();
+        let index_path = temp_dir.join("index.json");
+
+        // 旧版本迁移后的 v5 文件：zh-CN（界面语言）与 en-GB（实际市场）下是同一条数据
+        let v5_json = r#"{"version":5,"last_updated":"2025-02-14T00:00:00Z","mkt":{"en-GB":{"20250214":{"t":"Peak","c":"c","l":"l","d":"20250214","u":"/th?id=OHR.Peak_EN-GB1"}},"zh-CN":{"20250214":{"t":"Peak","c":"c","l":"l","d":"20250214","u":"/th?id=OHR.Peak_EN-GB1","r":"UHD"}}}}"#;
+        fs::write(&index_path, v5_json).await.unwrap();
+
+        let manager = IndexManager::new(temp_dir.clone());
+        let index = manager.load_index().await.unwrap();
+        assert_eq!(index.mkt.len(), 1);
+        assert_eq!(
+            index.mkt["en-GB"]["20250214"].resolution.as_deref(),
+            Some("UHD")
+        );
+
+        let written = fs::read_to_string(&index_path).await.unwrap();
+        assert!(written.contains("\"mkt_reconciled\":true"), "{written}");
+        assert!(!written.contains("zh-CN"), "{written}");
+
+        let _ = fs::remove_dir_all(&temp_dir).await;
+    }
+
     #[tokio::test]
     async fn test_index_manager_migrate_v4_to_v5() {
         let unique = SystemTime::now()
@@ -943,6 +1594,10 @@ mod tests {
                 copyright_link: format!("https://example.com/{}", i),
                 end_date: format!("202401{:02}", i + 1),
                 urlbase: format!("/th?id=OHR.Wallpaper{}", i),
+                resolution: None,
+                portrait_available: None,
+                watermark_free: None,
+                recompression: None,
             })
             .collect();
 
@@ -1000,6 +1655,10 @@ mod tests {
             copyright_link: "https://example.com".to_string(),
             end_date: "20240102".to_string(),
             urlbase: "/th?id=OHR.KeyOrder".to_string(),
+            resolution: None,
+            portrait_available: None,
+            watermark_free: None,
+            recompression: None,
         };
 
         // 有意按非字典序写入语言 key，验证返回顺序稳定。
@@ -1166,4 +1825,154 @@ mod tests {
 
         let _ = fs::remove_dir_all(&temp_dir).await;
     }
+
+    fn shared_dir_wallpaper(end_date: &str, title: &str) -> LocalWallpaper {
+        LocalWallpaper {
+            title: title.to_string(),
+            copyright: "Test".to_string(),
+            copyright_link: "https://example.com".to_string(),
+            end_date: end_date.to_string(),
+            urlbase: format!("/th?id=OHR.{title}"),
+            resolution: None,
+            portrait_available: None,
+            watermark_free: None,
+            recompression: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_index_manager_merges_writes_from_other_instance() {
+        let unique = SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let temp_dir = std::env::temp_dir().join(format!("bw_index_shared_{unique}"));
+        fs::create_dir_all(&temp_dir).await.unwrap();
+
+        // 两个实例模拟两台机器共享同一目录，各自持有缓存
+        let machine_a = IndexManager::new(temp_dir.clone());
+        let machine_b = IndexManager::new(temp_dir.clone());
+
+        machine_a
+            .upsert_wallpapers(vec![shared_dir_wallpaper("20240101", "A")], "zh-CN")
+            .await
+            .unwrap();
+        machine_b
+            .upsert_wallpapers(vec![shared_dir_wallpaper("20240102", "B")], "zh-CN")
+            .await
+            .unwrap();
+        // A 的缓存已过期，写入前应重新加载并保留 B 的条目
+        machine_a
+            .upsert_wallpapers(vec![shared_dir_wallpaper("20240103", "C")], "en-US")
+            .await
+            .unwrap();
+        assert!(
+            machine_a
+                .set_resolution("20240102", "1920x1080")
+                .await
+                .unwrap()
+        );
+
+        let on_disk = IndexManager::new(temp_dir.clone());
+        let zh = on_disk.get_all_wallpapers("zh-CN").await.unwrap();
+        let zh_dates: Vec<&str> = zh.iter().map(|w| w.end_date.as_str()).collect();
+        assert_eq!(zh_dates, vec!["20240102", "20240101"]);
+        assert_eq!(zh[0].resolution.as_deref(), Some("1920x1080"));
+        assert_eq!(on_disk.get_all_wallpapers("en-US").await.unwrap().len(), 1);
+
+        let _ = fs::remove_dir_all(&temp_dir).await;
+    }
+
+    #[tokio::test]
+    async fn test_index_manager_waits_for_file_lock() {
+        let unique = SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let temp_dir = std::env::temp_dir().join(format!("bw_index_lock_{unique}"));
+        fs::create_dir_all(&temp_dir).await.unwrap();
+
+        // 模拟另一个实例持有写锁
+        let holder = std::fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(temp_dir.join(LOCK_FILE))
+            .unwrap();
+        holder.lock().unwrap();
+        let release = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            drop(holder);
+        });
+
+        let manager = IndexManager::new(temp_dir.clone());
+        let started = std::time::Instant::now();
+        manager
+            .upsert_wallpapers(vec![shared_dir_wallpaper("20240101", "A")], "zh-CN")
+            .await
+            .unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(300));
+        release.await.unwrap();
+
+        let _ = fs::remove_dir_all(&temp_dir).await;
+    }
+
+    #[test]
+    fn test_backup_date_parses_only_backup_files() {
+        assert_eq!(backup_date("index-backup-20240101.json"), Some("20240101"));
+        assert_eq!(backup_date("index-backup-2024010.json"), None);
+        assert_eq!(backup_date("index-backup-20240101.tmp"), None);
+        assert_eq!(backup_date("index.json"), None);
+    }
+
+    #[tokio::test]
+    async fn test_index_backup_interval_retention_and_restore() {
+        let unique = SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let temp_dir = std::env::temp_dir().join(format!("bw_index_backup_{unique}"));
+        fs::create_dir_all(&temp_dir).await.unwrap();
+        let manager = IndexManager::new(temp_dir.clone());
+        let day = |d: u32| NaiveDate::from_ymd_opt(2024, 1, d).unwrap();
+
+        // 空索引不备份
+        assert!(manager.backup_if_due(day(1), 7).await.unwrap().is_none());
+
+        manager
+            .upsert_wallpapers(vec![shared_dir_wallpaper("20240101", "First")], "zh-CN")
+            .await
+            .unwrap();
+        assert!(manager.backup_if_due(day(1), 0).await.unwrap().is_none());
+        let path = manager.backup_if_due(day(1), 7).await.unwrap().unwrap();
+        assert!(path.ends_with("backups/index-backup-20240101.json"));
+        // 未满间隔不重复备份
+        assert!(manager.backup_if_due(day(7), 7).await.unwrap().is_none());
+
+        manager
+            .upsert_wallpapers(vec![shared_dir_wallpaper("20240102", "Second")], "zh-CN")
+            .await
+            .unwrap();
+        for d in 2..=(BACKUP_RETENTION as u32 + 2) {
+            manager.backup_if_due(day(d), 1).await.unwrap().unwrap();
+        }
+        let backups = manager.list_backups().await.unwrap();
+        assert_eq!(backups.len(), BACKUP_RETENTION);
+        assert_eq!(backups.first().map(String::as_str), Some("20240110"));
+        assert!(!backups.contains(&"20240101".to_string()));
+
+        // 损坏的主索引可以从备份恢复
+        fs::write(temp_dir.join(INDEX_FILE), "{ broken")
+            .await
+            .unwrap();
+        assert_eq!(manager.restore_backup("20240110").await.unwrap(), Some(2));
+        assert_eq!(manager.restore_backup("20230101").await.unwrap(), None);
+        let restored = IndexManager::new(temp_dir.clone())
+            .get_all_wallpapers("zh-CN")
+            .await
+            .unwrap();
+        assert_eq!(restored.len(), 2);
+
+        let _ = fs::remove_dir_all(&temp_dir).await;
+    }
 }
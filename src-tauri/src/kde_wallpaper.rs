@@ -0,0 +1,287 @@
+//! KDE Plasma 壁纸设置
+//!
+//! 通用的设置方式（修改单个 containment 的配置）在 Plasma 下往往只影响当前显示器的
+//! 当前 activity。这里通过 `org.kde.plasmashell` 的 `evaluateScript` 接口执行
+//! Plasma 脚本，遍历所有 activity 的所有桌面 containment 统一设置壁纸。
+
+use anyhow::{Context, Result};
+use log::{info, warn};
+use reqwest::Url;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Plasma 6 与 Plasma 5 发行版中 qdbus 的常见命名，按顺序尝试
+const QDBUS_CANDIDATES: [&str; 3] = ["qdbus6", "qdbus-qt6", "qdbus"];
+
+const PLASMASHELL_SERVICE: &str = "org.kde.plasmashell";
+const PLASMASHELL_PATH: &str = "/PlasmaShell";
+const EVALUATE_SCRIPT_METHOD: &str = "org.kde.PlasmaShell.evaluateScript";
+
+/// 壁纸应用范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ActivityScope {
+    /// 所有 activity 的所有桌面
+    AllActivities,
+    /// 仅当前 activity 的桌面
+    CurrentActivity,
+}
+
+/// 根据桌面环境变量判断是否为 KDE 会话
+///
+/// `XDG_CURRENT_DESKTOP` 可能是冒号分隔的列表（如 `KDE:GNOME`），
+/// 旧版 Plasma 仅设置 `KDE_FULL_SESSION=true`。
+fn is_kde_desktop(xdg_current_desktop: Option<&str>, kde_full_session: Option<&str>) -> bool {
+    let from_xdg = xdg_current_desktop.is_some_and(|value| {
+        value
+            .split(':')
+            .any(|desktop| desktop.trim().eq_ignore_ascii_case("KDE"))
+    });
+    from_xdg || kde_full_session.is_some_and(|value| value.trim().eq_ignore_ascii_case("true"))
+}
+
+/// 当前是否运行在 KDE Plasma 会话中
+pub(crate) fn is_kde_session() -> bool {
+    is_kde_desktop(
+        std::env::var("XDG_CURRENT_DESKTOP").ok().as_deref(),
+        std::env::var("KDE_FULL_SESSION").ok().as_deref(),
+    )
+}
+
+/// 转义为 JavaScript 双引号字符串字面量的内容
+fn escape_js_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// 把壁纸路径转换为 KDE 配置使用的 `file://` URL（空格、`#` 等字符按 URL 规则编码）
+pub(crate) fn file_url(path: &Path) -> String {
+    Url::from_file_path(path)
+        .map(String::from)
+        .unwrap_or_else(|()| format!("file://{}", path.display()))
+}
+
+/// 把 KDE 配置中的壁纸地址转换为本地路径，兼容直接保存的路径；非本地 URL 返回 None
+fn path_from_config(value: &str) -> Option<PathBuf> {
+    if value.is_empty() {
+        return None;
+    }
+    match Url::parse(value) {
+        Ok(url) => url.to_file_path().ok(),
+        Err(_) => Some(PathBuf::from(value)),
+    }
+}
+
+/// 生成设置壁纸的 Plasma 脚本
+fn build_plasma_script(image_path: &Path, scope: ActivityScope) -> String {
+    let image_url = escape_js_string(&file_url(image_path));
+
+    // desktops() 在部分 Plasma 版本中只返回当前 activity 的桌面，
+    // 因此显式按 activity 遍历
+    let activities = match scope {
+        ActivityScope::AllActivities => "activities()",
+        ActivityScope::CurrentActivity => "[currentActivity()]",
+    };
+
+    format!(
+        r#"var activityIds = {activities};
+for (var a = 0; a < activityIds.length; a++) {{
+    var allDesktops = desktopsForActivity(activityIds[a]);
+    for (var i = 0; i < allDesktops.length; i++) {{
+        var d = allDesktops[i];
+        d.wallpaperPlugin = "org.kde.image";
+        d.currentConfigGroup = Array("Wallpaper", "org.kde.image", "General");
+        d.writeConfig("Image", "{image_url}");
+    }}
+}}"#
+    )
+}
+
+/// 读取当前 activity 各桌面壁纸的 Plasma 脚本，每行输出 `<屏幕索引>\t<图片地址>`
+const QUERY_SCRIPT: &str = r#"var allDesktops = desktops();
+for (var i = 0; i < allDesktops.length; i++) {
+    var d = allDesktops[i];
+    if (d.screen < 0) continue;
+    d.currentConfigGroup = Array("Wallpaper", "org.kde.image", "General");
+    print(d.screen + "\t" + d.readConfig("Image") + "\n");
+}"#;
+
+/// 通过 qdbus 执行 Plasma 脚本，返回脚本 `print` 的输出
+fn evaluate_script(script: &str) -> Result<String> {
+    for program in QDBUS_CANDIDATES {
+        let output = match Command::new(program)
+            .args([
+                PLASMASHELL_SERVICE,
+                PLASMASHELL_PATH,
+                EVALUATE_SCRIPT_METHOD,
+                script,
+            ])
+            .output()
+        {
+            Ok(output) => output,
+            Err(e) if e.kind() == ErrorKind::NotFound => continue,
+            Err(e) => return Err(e).with_context(|| format!("Failed to run {program}")),
+        };
+
+        if !output.status.success() {
+            anyhow::bail!(
+                "{} evaluateScript failed ({}): {}",
+                program,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+    }
+
+    anyhow::bail!("qdbus not found (tried: {})", QDBUS_CANDIDATES.join(", "))
+}
+
+/// 为 KDE Plasma 设置壁纸
+///
+/// 默认应用到所有 activity；若当前 Plasma 不支持按 activity 遍历（脚本执行失败），
+/// 退回到只设置当前 activity。
+pub(crate) fn set_wallpaper_kde(image_path: &Path) -> Result<()> {
+    let image_path = image_path
+        .canonicalize()
+        .unwrap_or_else(|_| image_path.to_path_buf());
+
+    match evaluate_script(&build_plasma_script(
+        &image_path,
+        ActivityScope::AllActivities,
+    )) {
+        Ok(_) => {
+            info!(target: "wallpaper", "KDE 壁纸已设置到所有 activity: {}", image_path.display());
+            Ok(())
+        }
+        Err(e) => {
+            warn!(target: "wallpaper", "按所有 activity 设置 KDE 壁纸失败，尝试仅设置当前 activity: {}", e);
+            evaluate_script(&build_plasma_script(
+                &image_path,
+                ActivityScope::CurrentActivity,
+            ))?;
+            info!(target: "wallpaper", "KDE 壁纸已设置到当前 activity: {}", image_path.display());
+            Ok(())
+        }
+    }
+}
+
+/// 解析 [`QUERY_SCRIPT`] 的输出，按屏幕索引排列（没有输出的屏幕为 None）
+fn parse_screen_wallpapers(output: &str) -> Vec<Option<PathBuf>> {
+    let mut wallpapers: Vec<Option<PathBuf>> = Vec::new();
+    for line in output.lines() {
+        let Some((screen, image)) = line.split_once('\t') else {
+            continue;
+        };
+        let Ok(screen) = screen.trim().parse::<usize>() else {
+            continue;
+        };
+        if wallpapers.len() <= screen {
+            wallpapers.resize(screen + 1, None);
+        }
+        wallpapers[screen] = path_from_config(image.trim());
+    }
+    wallpapers
+}
+
+/// 读取 KDE Plasma 当前 activity 各显示器的壁纸
+pub(crate) fn get_screen_wallpapers_kde() -> Result<Vec<Option<PathBuf>>> {
+    evaluate_script(QUERY_SCRIPT).map(|output| parse_screen_wallpapers(&output))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_kde_desktop() {
+        assert!(is_kde_desktop(Some("KDE"), None));
+        assert!(is_kde_desktop(Some("ubuntu:KDE"), None));
+        assert!(is_kde_desktop(None, Some("true")));
+        assert!(!is_kde_desktop(Some("GNOME"), None));
+        assert!(!is_kde_desktop(Some("ubuntu:GNOME"), Some("false")));
+        assert!(!is_kde_desktop(None, None));
+    }
+
+    #[test]
+    fn test_escape_js_string() {
+        assert_eq!(escape_js_string(r#"a"b\c"#), r#"a\"b\\c"#);
+        assert_eq!(escape_js_string("line\nbreak"), "line\\nbreak");
+    }
+
+    #[test]
+    fn test_script_targets_all_activities() {
+        let script = build_plasma_script(
+            Path::new("/home/user/壁纸/20240101.jpg"),
+            ActivityScope::AllActivities,
+        );
+        assert!(script.starts_with("var activityIds = activities();"));
+        assert!(script.contains("desktopsForActivity(activityIds[a])"));
+        assert!(script.contains(
+            r#"d.writeConfig("Image", "file:///home/user/%E5%A3%81%E7%BA%B8/20240101.jpg");"#
+        ));
+    }
+
+    #[test]
+    fn test_script_current_activity_only() {
+        let script = build_plasma_script(
+            Path::new("/tmp/wallpaper.jpg"),
+            ActivityScope::CurrentActivity,
+        );
+        assert!(script.starts_with("var activityIds = [currentActivity()];"));
+    }
+
+    #[test]
+    fn test_script_escapes_path() {
+        let script = build_plasma_script(
+            Path::new(r#"/tmp/we"ird.jpg"#),
+            ActivityScope::AllActivities,
+        );
+        assert!(script.contains(r#""file:///tmp/we%22ird.jpg""#));
+    }
+
+    #[test]
+    fn test_file_url_encodes_path() {
+        assert_eq!(
+            file_url(Path::new("/home/u/My Pictures/#1/20240101.jpg")),
+            "file:///home/u/My%20Pictures/%231/20240101.jpg"
+        );
+    }
+
+    #[test]
+    fn test_path_from_config_decodes_file_url() {
+        let path = Path::new("/home/u/My Pictures/#1/20240101.jpg");
+        assert_eq!(path_from_config(&file_url(path)), Some(path.to_path_buf()));
+        assert_eq!(path_from_config("https://example.com/a.jpg"), None);
+        assert_eq!(path_from_config(""), None);
+    }
+
+    #[test]
+    fn test_parse_screen_wallpapers() {
+        let output = "1\tfile:///home/u/Pictures/20240102.jpg\n\
+                      0\tfile:///home/u/My%20Pictures/20240101.jpg\n\
+                      2\t/home/u/Pictures/20240103.jpg\n\
+                      4\t\n\
+                      garbage\n";
+        assert_eq!(
+            parse_screen_wallpapers(output),
+            vec![
+                Some(PathBuf::from("/home/u/My Pictures/20240101.jpg")),
+                Some(PathBuf::from("/home/u/Pictures/20240102.jpg")),
+                Some(PathBuf::from("/home/u/Pictures/20240103.jpg")),
+                None,
+                None,
+            ]
+        );
+        assert!(parse_screen_wallpapers("").is_empty());
+    }
+}
@@ -1,43 +1,100 @@
+mod apply_hook;
+mod archive;
+mod attribution;
 mod auto_update;
+#[cfg(target_os = "macos")]
+mod automation;
+mod backup;
 mod bing_api;
+mod cli_args;
+mod clock;
+mod command_guard;
 mod commands;
+mod directory_permission;
+mod disk_space;
 mod download_manager;
+mod events;
+mod extension_events;
+mod http_client;
+mod idle_prefetch;
 mod index_manager;
+#[cfg(target_os = "linux")]
+mod kde_wallpaper;
+#[cfg(target_os = "linux")]
+mod linux_greeter;
+mod local_folder;
+mod log_filter;
+mod mini_window;
+mod mkt_suggestion;
 mod models;
+mod network_gate;
+mod network_watch;
 mod notification;
+mod placeholder;
+mod policy;
+mod power;
+mod profiles;
+mod publish_schedule;
+mod quit;
+mod random_wallpaper;
+mod recovery;
+mod reset;
 mod runtime_state;
+mod safe_path;
+mod scheduler;
+mod settings_service;
 mod settings_store;
+mod settings_watch;
+mod slideshow;
+mod smart_crop;
+mod solar_schedule;
+mod spotlight;
+mod startup;
 mod storage;
+mod test_wallpapers;
+mod thumbnail_cache;
 mod transfer;
+mod trash;
 mod tray;
 mod update_cycle;
 mod utils;
 mod version_check;
+mod wallpaper_apply;
 mod wallpaper_manager;
+mod wallpaper_osd;
+mod wallpaper_stats;
+mod wallpaper_theme;
+mod wallpaper_transition;
 
 use chrono::{DateTime, Local};
 use log::{info, warn};
 
-use models::{AppRuntimeState, AppSettings};
+use models::{AppRuntimeState, AppSettings, ProfilesConfig, UpdateState};
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tauri::{Manager, tray::TrayIcon, webview::PageLoadEvent};
-use tauri_plugin_autostart::ManagerExt;
-use tokio::sync::{Mutex, watch};
+use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
 
 /// 全局状态管理
 struct AppState {
-    settings: Arc<Mutex<AppSettings>>,
+    /// 应用设置（读取、修改、持久化与广播统一经由 `SettingsService`）
+    settings: Arc<settings_service::SettingsService>,
+    /// 壁纸配置方案（持久化在 `profiles.json`），切换方案时覆盖到 `settings` 上
+    profiles: Arc<Mutex<ProfilesConfig>>,
     wallpaper_directory: Arc<Mutex<PathBuf>>,
-    last_tray_click: Arc<Mutex<Option<Instant>>>,
+    /// 托盘左键单击/双击判别状态
+    tray_clicks: Arc<Mutex<tray::TrayClickTracker>>,
     current_wallpaper_path: Arc<Mutex<Option<PathBuf>>>,
     last_update_time: Arc<Mutex<Option<DateTime<Local>>>>,
-    settings_tx: watch::Sender<AppSettings>,
-    settings_rx: watch::Receiver<AppSettings>,
-    auto_update_handle: Arc<Mutex<tauri::async_runtime::JoinHandle<()>>>,
-    update_in_progress: Arc<Mutex<bool>>,
+    /// 后台任务调度器（自动更新循环、定时备份等）
+    scheduler: Arc<scheduler::Scheduler>,
+    /// 更新循环状态机，`is_busy()` 时不允许启动新的更新或重置
+    update_state: Arc<Mutex<UpdateState>>,
+    /// 当前更新循环的取消令牌，仅在更新进行中时为 `Some`
+    update_cancel_token: Arc<Mutex<Option<CancellationToken>>>,
     tray_icon: Arc<Mutex<Option<TrayIcon>>>,
     frontend_ready: Arc<AtomicBool>,
     frontend_reload_attempted: Arc<AtomicBool>,
@@ -47,6 +104,47 @@ struct AppState {
     /// 确保后续读取壁纸时使用与写入一致的 mkt key。
     /// 用户更改 mkt 设置时应清空此字段。
     last_actual_mkt: Arc<Mutex<Option<String>>>,
+    /// 壁纸应用队列：串行化设置壁纸的系统调用，快速连续点击时只保留最后一次
+    wallpaper_apply_queue: Arc<wallpaper_apply::WallpaperApplyQueue>,
+    /// 时间源：自动更新与缓存判断统一从这里取当前时间，便于测试替换
+    clock: Arc<dyn clock::Clock>,
+    /// 壁纸元数据来源：默认请求 Bing API，测试中可替换为不访问网络的实现
+    image_source: Arc<dyn bing_api::ImageSource>,
+}
+
+/// setup 阶段初始化共享状态
+///
+/// setup 是同步回调，且此时后台任务尚未启动、锁不会被占用，
+/// 因此使用 `try_lock` 而不是 `block_on`，避免在运行时线程上阻塞等待。
+fn init_setup_state<T>(mutex: &Mutex<T>, name: &str, init: impl FnOnce(&mut T)) {
+    match mutex.try_lock() {
+        Ok(mut guard) => init(&mut guard),
+        Err(_) => warn!(target: "startup", "状态 {} 被占用，跳过初始化", name),
+    }
+}
+
+/// 启动后台任务（自动更新循环、空闲预取、每日备份）
+///
+/// 任务均登记在调度器中，重复调用时同名旧任务会先被取消（重置应用后据此重新启动）。
+pub(crate) fn start_background_tasks(app: &tauri::AppHandle) {
+    idle_prefetch::start_idle_prefetch_task(app.clone());
+    auto_update::start_auto_update_task(app.clone());
+    power::start_power_watch_task(app.clone());
+    network_watch::start_network_watch_task(app.clone());
+    settings_watch::start_settings_watch_task(app.clone());
+    extension_events::start_extension_events_task(app.clone());
+    solar_schedule::start_solar_schedule_task(app.clone());
+
+    // 每日定时备份：补传更新循环中失败的文件，随机抖动避免同时请求备份服务
+    let state = app.state::<AppState>();
+    state.scheduler.schedule(
+        app,
+        "daily_backup",
+        scheduler::Schedule::Daily { hour: 3, minute: 0 },
+        Duration::from_secs(30 * 60),
+        state.clock.clone(),
+        Arc::new(|app| Box::pin(backup::run_backup_if_enabled(app))),
+    );
 }
 
 // (removed) fetch_bing_images command; image retrieval now handled by background auto-update logic.
@@ -56,7 +154,7 @@ struct AppState {
 /// 委托给 `utils::effective_mkt`，从 AppState 中提取所需参数。
 pub(crate) async fn get_effective_mkt(state: &AppState) -> String {
     let last_actual = state.last_actual_mkt.lock().await.clone();
-    let settings_mkt = state.settings.lock().await.mkt.clone();
+    let settings_mkt = state.settings.read().await.mkt;
     utils::effective_mkt(last_actual.as_deref(), &settings_mkt)
 }
 
@@ -65,33 +163,31 @@ pub fn run() {
     let default_dir =
         storage::get_default_wallpaper_directory().unwrap_or_else(|_| PathBuf::from("."));
 
-    // 初始设置
-    let initial_settings = AppSettings::default();
-    let (tx, rx) = watch::channel(initial_settings.clone());
-
     let app_state = AppState {
-        settings: Arc::new(Mutex::new(initial_settings)),
+        settings: Arc::new(settings_service::SettingsService::new(
+            AppSettings::default(),
+        )),
+        profiles: Arc::new(Mutex::new(ProfilesConfig::default())),
         wallpaper_directory: Arc::new(Mutex::new(default_dir)),
-        last_tray_click: Arc::new(Mutex::new(None)),
+        tray_clicks: Arc::new(Mutex::new(tray::TrayClickTracker::default())),
         current_wallpaper_path: Arc::new(Mutex::new(None)),
         last_update_time: Arc::new(Mutex::new(None)),
-        settings_tx: tx,
-        settings_rx: rx,
-        auto_update_handle: Arc::new(Mutex::new(tauri::async_runtime::spawn(async {}))),
-        update_in_progress: Arc::new(Mutex::new(false)),
+        scheduler: Arc::new(scheduler::Scheduler::new()),
+        update_state: Arc::new(Mutex::new(UpdateState::default())),
+        update_cancel_token: Arc::new(Mutex::new(None)),
         tray_icon: Arc::new(Mutex::new(None)),
         frontend_ready: Arc::new(AtomicBool::new(false)),
         frontend_reload_attempted: Arc::new(AtomicBool::new(false)),
         last_actual_mkt: Arc::new(Mutex::new(None)),
+        wallpaper_apply_queue: Arc::new(wallpaper_apply::WallpaperApplyQueue::new()),
+        clock: Arc::new(clock::SystemClock),
+        image_source: Arc::new(bing_api::BingImageSource),
     };
 
     tauri::Builder::default()
-        .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
-            // 当检测到第二个实例启动时，将第一个实例的窗口显示出来
-            if let Err(e) = commands::window::show_main_window_with_watchdog(app, "single_instance")
-            {
-                warn!(target: "frontend", "通过 single_instance 显示主窗口失败: {}", e);
-            }
+        .plugin(tauri_plugin_single_instance::init(|app, args, cwd| {
+            // 当检测到第二个实例启动时，由第一个实例处理其参数并显示窗口
+            cli_args::handle_second_instance(app, args, cwd);
         }))
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_store::Builder::default().build())
@@ -123,44 +219,104 @@ pub fn run() {
         })
         .plugin(
             tauri_plugin_log::Builder::default()
-                .level(log::LevelFilter::Info)
+                // 插件以最低级别注册，实际级别由 log_filter 在运行时决定
+                .level(log::LevelFilter::Trace)
+                .filter(log_filter::is_enabled)
                 .timezone_strategy(tauri_plugin_log::TimezoneStrategy::UseLocal)
                 .max_file_size(10_000_000) // 10MB
                 .rotation_strategy(tauri_plugin_log::RotationStrategy::KeepOne)
                 .build(),
         )
         .manage(app_state)
+        .register_asynchronous_uri_scheme_protocol(
+            thumbnail_cache::PROTOCOL,
+            thumbnail_cache::handle_request,
+        )
         .invoke_handler(tauri::generate_handler![
             commands::wallpaper::set_desktop_wallpaper,
             commands::wallpaper::get_current_wallpaper_path,
+            commands::wallpaper::get_current_wallpaper,
+            commands::wallpaper::get_applied_wallpapers,
             commands::wallpaper::get_local_wallpapers,
+            commands::wallpaper::get_local_wallpapers_page,
+            commands::wallpaper::get_wallpaper_details,
+            commands::wallpaper::get_wallpaper_status,
+            commands::tags::add_tag,
+            commands::tags::remove_tag,
+            commands::tags::get_wallpapers_by_tag,
+            commands::tags::get_all_tags,
+            commands::wallpaper::refresh_metadata,
+            commands::wallpaper::export_crops,
+            attribution::show_attribution_overlay,
+            slideshow::start_slideshow,
+            random_wallpaper::apply_random_wallpaper,
+            mini_window::toggle_mini_window,
+            extension_events::get_extension_endpoint,
+            apply_hook::confirm_apply_hook,
+            local_folder::count_local_folder_images,
+            spotlight::import_spotlight_images,
+            download_manager::get_active_downloads,
+            backup::get_backup_config,
+            backup::set_backup_config,
+            backup::backup_now,
+            recovery::get_recovery_report,
+            reset::reset_application,
+            profiles::get_profiles,
+            profiles::save_profile,
+            profiles::delete_profile,
+            profiles::switch_profile,
             commands::settings::get_settings,
             commands::settings::update_settings,
+            policy::get_settings_policy,
+            wallpaper_theme::get_wallpaper_theme,
             commands::storage::get_wallpaper_directory,
             commands::storage::get_wallpaper_data_stats,
+            commands::storage::get_index_schema_version,
+            commands::storage::export_index_json,
+            commands::storage::get_index_backups,
+            commands::storage::restore_index_backup,
+            wallpaper_stats::get_archive_statistics,
             commands::storage::get_default_wallpaper_directory,
             commands::storage::get_last_update_time,
+            commands::storage::get_bandwidth_stats,
             commands::storage::get_update_in_progress,
+            commands::storage::get_update_state,
             commands::storage::ensure_wallpaper_directory_exists,
+            directory_permission::fallback_to_default_directory,
+            directory_permission::get_archive_read_only,
             commands::window::show_main_window,
             commands::window::mark_frontend_ready,
             commands::window::report_frontend_error,
             update_cycle::force_update,
+            network_watch::reset_network,
             update_cycle::send_test_wallpaper_notification,
+            test_wallpapers::inject_test_wallpapers,
             version_check::add_ignored_update_version,
+            version_check::check_for_updates,
             version_check::is_version_ignored,
             commands::window::get_screen_orientations,
             commands::mkt::get_market_status,
             commands::mkt::get_supported_mkts,
+            commands::mkt::probe_market_availability,
             notification::show_system_notification,
             transfer::import_wallpapers,
+            transfer::preview_import,
+            mkt_suggestion::get_mkt_suggestion,
+            mkt_suggestion::respond_mkt_suggestion,
             transfer::export_wallpapers,
+            log_filter::set_log_level,
+            archive::backfill_archive,
         ])
         .setup(|app| {
+            let setup_started = Instant::now();
+
             #[cfg(target_os = "macos")]
             notification::initialize_notification_center();
 
             wallpaper_manager::initialize_observer();
+            download_manager::attach_event_handle(app.handle());
+            #[cfg(target_os = "macos")]
+            automation::initialize(app.handle());
 
             // macOS: Info.plist 的 LSUIElement=true 不足以在所有场景下阻止
             // Dock 运行状态点出现，运行时补充设置 Accessory 模式作为双重保障。
@@ -182,18 +338,9 @@ pub fn run() {
                 AppSettings::default()
             });
 
-            // 更新 AppState 中的设置
+            // 更新 AppState 中的设置并广播，auto_update_task 等监听者能获取到正确的初始设置
             let state = app.state::<AppState>();
-            tauri::async_runtime::block_on(async {
-                let mut settings = state.settings.lock().await;
-                *settings = loaded_settings.clone();
-            });
-
-            // 同步持久化设置到 settings_tx watch channel
-            // 这样 auto_update_task 等监听者能获取到正确的初始设置
-            if let Err(e) = state.settings_tx.send(loaded_settings.clone()) {
-                warn!(target: "settings", "发送持久化设置到 watch channel 失败: {}", e);
-            }
+            state.settings.init(loaded_settings.clone());
 
             // 更新壁纸目录
             let wallpaper_dir = if let Some(ref dir) = loaded_settings.save_directory {
@@ -201,77 +348,32 @@ pub fn run() {
             } else {
                 storage::get_default_wallpaper_directory().unwrap_or_else(|_| PathBuf::from("."))
             };
-            tauri::async_runtime::block_on(async {
-                let mut dir = state.wallpaper_directory.lock().await;
-                *dir = wallpaper_dir;
+            init_setup_state(&state.wallpaper_directory, "wallpaper_directory", |dir| {
+                *dir = wallpaper_dir.clone();
             });
 
             info!(target: "settings", "成功加载持久化设置");
 
-            // 从操作系统读取真实的自启动状态，并更新应用设置
-            // 这样即使用户手动在系统设置中修改了自启动状态，应用也能获取到准确的值
-            // 同时一次性加载 runtime_state，避免重复加载
-            {
-                let autostart_manager = app.handle().autolaunch();
-                let system_autostart_enabled = autostart_manager.is_enabled().unwrap_or_else(|e| {
-                    warn!(target: "startup", "读取系统自启动状态失败: {}，假设为未启用", e);
-                    false
-                });
-                let settings_autostart_enabled = loaded_settings.launch_at_startup;
-
-                // 一次性加载 runtime_state，后续复用
-                let mut runtime_state = match runtime_state::load_runtime_state(app.handle()) {
-                    Ok(state) => state,
-                    Err(e) => {
-                        warn!(target: "startup", "加载运行时状态失败: {}，使用默认值", e);
-                        AppRuntimeState::default()
-                    }
-                };
-
-                // 如果系统实际状态与设置不一致，更新设置以匹配系统状态
-                // 这可能是因为用户手动在系统设置中修改了自启动状态
-                if system_autostart_enabled != settings_autostart_enabled {
-                    info!(target: "startup",
-                        "检测到自启动状态不一致（设置: {}，系统: {}），更新设置为系统实际状态",
-                        settings_autostart_enabled, system_autostart_enabled);
-
-                    // 更新内存中的设置
-                    tauri::async_runtime::block_on(async {
-                        let mut settings = state.settings.lock().await;
-                        settings.launch_at_startup = system_autostart_enabled;
-                    });
-
-                    // 更新持久化设置
-                    let mut updated_settings = loaded_settings.clone();
-                    updated_settings.launch_at_startup = system_autostart_enabled;
-                    if let Err(e) = settings_store::save_settings(app.handle(), &updated_settings) {
-                        warn!(target: "startup", "保存同步后的设置失败: {}", e);
-                    } else {
-                        // 同步更新后的设置到 watch channel
-                        // 这样 auto_update_task 等监听者能获取到正确的自启动状态
-                        if let Err(e) = state.settings_tx.send(updated_settings.clone()) {
-                            warn!(target: "startup", "发送同步后的设置到 watch channel 失败: {}", e);
-                        }
-                    }
-                }
-
-                // 如果自启动已启用，但通知标志未设置，则自动设置标志
-                // 这适用于在更新到 0.4.10 之前就已经启用自启动的用户
-                if system_autostart_enabled && !runtime_state.autostart_notification_shown {
-                    runtime_state.autostart_notification_shown = true;
-                    if let Err(e) = runtime_state::save_runtime_state(app.handle(), &runtime_state) {
-                        warn!(target: "startup", "保存自启动通知标志失败: {}", e);
-                    } else {
-                        info!(target: "startup", "检测到自启动已启用但通知标志未设置，已自动设置标志");
-                    }
-                }
+            let loaded_profiles = profiles::load_profiles(app.handle()).unwrap_or_else(|e| {
+                warn!(target: "profiles", "加载配置方案失败: {}，使用空配置", e);
+                ProfilesConfig::default()
+            });
+            init_setup_state(&state.profiles, "profiles", |profiles| {
+                *profiles = loaded_profiles;
+            });
 
+            // 一次性加载 runtime_state，后续复用（自启动同步在延后阶段进行）
+            let runtime_state = runtime_state::load_runtime_state(app.handle())
+                .unwrap_or_else(|e| {
+                    warn!(target: "startup", "加载运行时状态失败: {}，使用默认值", e);
+                    AppRuntimeState::default()
+                });
+            {
                 // 使用已加载的 runtime_state 恢复上次更新时间
                 if let Some(ref last_update_str) = runtime_state.last_successful_update
                     && let Ok(dt) = chrono::DateTime::parse_from_rfc3339(last_update_str)
                 {
-                    tauri::async_runtime::block_on(async {
-                        let mut last_update = state.last_update_time.lock().await;
+                    init_setup_state(&state.last_update_time, "last_update_time", |last_update| {
                         *last_update = Some(dt.with_timezone(&Local));
                     });
                     info!(target: "startup", "从持久化状态恢复上次更新时间: {}", last_update_str);
@@ -280,14 +382,60 @@ pub fn run() {
                 // 从持久化 runtime_state 恢复 last_actual_mkt
                 // 解决重启后因 settings.mkt 与 index.json 中实际 key 不一致导致的短暂空白
                 if let Some(ref actual_mkt) = runtime_state.last_actual_mkt {
-                    tauri::async_runtime::block_on(async {
-                        *state.last_actual_mkt.lock().await = Some(actual_mkt.clone());
+                    init_setup_state(&state.last_actual_mkt, "last_actual_mkt", |mkt| {
+                        *mkt = Some(actual_mkt.clone());
                     });
                     info!(target: "startup", "从持久化状态恢复 last_actual_mkt: {}", actual_mkt);
                 }
+
+                // 恢复上次应用的壁纸，避免自动应用重复设置同一张图片
+                let mkt = utils::effective_mkt(
+                    runtime_state.last_actual_mkt.as_deref(),
+                    &loaded_settings.mkt,
+                );
+                if let Some(end_date) = runtime_state.applied_wallpapers.get(&mkt) {
+                    let path = storage::get_wallpaper_path(&wallpaper_dir, end_date);
+                    if path.exists() {
+                        info!(target: "startup", "从持久化状态恢复当前壁纸: {}", path.display());
+                        // Windows 登录/解锁后可能还原壁纸，需要知道应有的壁纸（超宽屏时为裁剪后的派生图）
+                        #[cfg(target_os = "windows")]
+                        {
+                            let source = path.clone();
+                            tauri::async_runtime::spawn(async move {
+                                let display = smart_crop::prepare_for_display(&source).await;
+                                wallpaper_manager::restore_expected_wallpaper(&display);
+                            });
+                        }
+                        init_setup_state(&state.current_wallpaper_path, "current_wallpaper_path", |current| {
+                            *current = Some(path);
+                        });
+                    }
+                }
             }
 
+            // 上次未正常退出时，延后阶段需要在自动更新开始前修复残留状态
+            let previous_session_started_at = match recovery::begin_session(app.handle()) {
+                Ok(previous) => previous,
+                Err(e) => {
+                    warn!(target: "recovery", "写入会话标记失败: {}", e);
+                    None
+                }
+            };
+
+            mkt_suggestion::spawn_first_launch_suggestion(app.handle());
+
             tray::setup_tray(app.handle())?;
+
+            // macOS：Cmd+Q 与托盘"退出"一样经过退出确认
+            #[cfg(target_os = "macos")]
+            {
+                app.set_menu(quit::app_menu(app.handle())?)?;
+                app.on_menu_event(|app, event| {
+                    if event.id() == quit::APP_QUIT_MENU_ID {
+                        quit::request_quit(app);
+                    }
+                });
+            }
             commands::window::schedule_frontend_ready_watchdog(
                 app.handle().clone(),
                 "startup",
@@ -305,9 +453,19 @@ pub fn run() {
                 warn!(target: "frontend", "启动时显示主窗口失败: {}", e);
             }
 
-            // 使用 tauri-plugin-log 进行标准化日志输出（已在 Builder 中初始化）
-            // 日志文件超过 10MB 时自动轮转，保留所有历史日志文件
-            auto_update::start_auto_update_task(app.handle().clone());
+            let elapsed = setup_started.elapsed();
+            if elapsed.as_millis() > startup::CRITICAL_PATH_BUDGET_MS {
+                warn!(target: "startup", "启动关键路径耗时 {:?}，超出预算", elapsed);
+            } else {
+                info!(target: "startup", "启动关键路径耗时 {:?}", elapsed);
+            }
+
+            tauri::async_runtime::spawn(startup::run_deferred_startup(
+                app.handle().clone(),
+                runtime_state,
+                wallpaper_dir,
+                previous_session_started_at,
+            ));
             Ok(())
         })
         .on_page_load(|webview, payload| {
@@ -366,6 +524,12 @@ pub fn run() {
                 let _ = window.hide();
             }
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                app.state::<AppState>().scheduler.shutdown();
+                recovery::end_session(app);
+            }
+        });
 }
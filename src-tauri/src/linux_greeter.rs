@@ -0,0 +1,315 @@
+//! Linux 锁屏与登录界面背景
+//!
+//! 设置桌面壁纸后按设置同步到：
+//! - KDE 锁屏（`lock_screen_wallpaper`）：通过 kwriteconfig 修改当前用户的 `kscreenlockerrc`，无需额外权限；
+//! - SDDM 登录界面（`greeter_background = "sddm"`）：SDDM 以独立用户运行，读不到用户主目录中的
+//!   图片，普通用户也不能修改主题配置。应用把壁纸复制到 [`GREETER_DIR`]，再在当前主题的
+//!   `theme.conf.user` 中写入 `background`（Breeze 等主流主题都读取该键）。需要管理员事先授权一次：
+//!
+//!   ```sh
+//!   sudo install -d -m 0775 -g "$(id -gn)" /var/lib/bing-wallpaper-now
+//!   sudo touch <主题目录>/theme.conf.user
+//!   sudo chgrp "$(id -gn)" <主题目录>/theme.conf.user
+//!   sudo chmod 0664 <主题目录>/theme.conf.user
+//!   ```
+//!
+//!   没有写入权限时只记录警告，不影响桌面壁纸。
+//!
+//! GDM 的背景编译在 gnome-shell 的 gresource 中，没有受支持的修改方式，因此不支持。
+
+use anyhow::{Context, Result};
+use log::{info, warn};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tauri::{AppHandle, Manager};
+
+use crate::{AppState, kde_wallpaper};
+
+/// 登录界面可读取的共享目录
+const GREETER_DIR: &str = "/var/lib/bing-wallpaper-now";
+const GREETER_IMAGE: &str = "greeter.jpg";
+
+const KWRITECONFIG_CANDIDATES: [&str; 2] = ["kwriteconfig6", "kwriteconfig5"];
+
+/// SDDM 配置文件，后读取的覆盖先读取的
+const SDDM_CONFIG_DIRS: [&str; 2] = ["/usr/lib/sddm/sddm.conf.d", "/etc/sddm.conf.d"];
+const SDDM_CONFIG_FILE: &str = "/etc/sddm.conf";
+const SDDM_DEFAULT_THEME_DIR: &str = "/usr/share/sddm/themes";
+const THEME_CONF_USER: &str = "theme.conf.user";
+
+/// kwriteconfig 设置锁屏壁纸的参数
+fn lock_screen_args(image_path: &Path) -> Vec<String> {
+    let mut args: Vec<String> = ["--file", "kscreenlockerrc"]
+        .into_iter()
+        .map(String::from)
+        .collect();
+    for group in ["Greeter", "Wallpaper", "org.kde.image", "General"] {
+        args.push("--group".to_string());
+        args.push(group.to_string());
+    }
+    args.push("--key".to_string());
+    args.push("Image".to_string());
+    args.push(kde_wallpaper::file_url(image_path));
+    args
+}
+
+fn set_lock_screen(image_path: &Path) -> Result<()> {
+    let args = lock_screen_args(image_path);
+    for program in KWRITECONFIG_CANDIDATES {
+        let output = match Command::new(program).args(&args).output() {
+            Ok(output) => output,
+            Err(e) if e.kind() == ErrorKind::NotFound => continue,
+            Err(e) => return Err(e).with_context(|| format!("Failed to run {program}")),
+        };
+        if !output.status.success() {
+            anyhow::bail!(
+                "{} failed ({}): {}",
+                program,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        return Ok(());
+    }
+    anyhow::bail!(
+        "kwriteconfig not found (tried: {})",
+        KWRITECONFIG_CANDIDATES.join(", ")
+    )
+}
+
+/// 从按顺序排列的 SDDM 配置内容中读取 `[Theme]` 的 `ThemeDir` 与 `Current`
+fn parse_sddm_theme(configs: &[String]) -> (Option<String>, Option<String>) {
+    let mut theme_dir = None;
+    let mut current = None;
+    for config in configs {
+        let mut in_theme = false;
+        for line in config.lines().map(str::trim) {
+            if line.starts_with('[') {
+                in_theme = line == "[Theme]";
+                continue;
+            }
+            if !in_theme {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = value.trim();
+            let target = match key.trim() {
+                "ThemeDir" => &mut theme_dir,
+                "Current" => &mut current,
+                _ => continue,
+            };
+            *target = (!value.is_empty()).then(|| value.to_string());
+        }
+    }
+    (theme_dir, current)
+}
+
+fn read_sddm_configs() -> Vec<String> {
+    let mut paths = Vec::new();
+    for dir in SDDM_CONFIG_DIRS {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            continue;
+        };
+        let mut files: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "conf"))
+            .collect();
+        files.sort();
+        paths.extend(files);
+    }
+    paths.push(PathBuf::from(SDDM_CONFIG_FILE));
+    paths
+        .iter()
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .collect()
+}
+
+/// 当前 SDDM 主题的目录，`override_dir` 为用户在设置中指定的目录
+fn sddm_theme_path(override_dir: Option<&str>) -> Result<PathBuf> {
+    if let Some(dir) = override_dir {
+        return Ok(PathBuf::from(dir));
+    }
+    let (theme_dir, current) = parse_sddm_theme(&read_sddm_configs());
+    let current = current.context("No SDDM theme configured")?;
+    Ok(Path::new(theme_dir.as_deref().unwrap_or(SDDM_DEFAULT_THEME_DIR)).join(current))
+}
+
+/// 在 `theme.conf.user` 内容中设置 `[General] background`，保留其他键
+fn update_theme_conf(existing: &str, background: &str) -> String {
+    let entry = format!("background={background}");
+    let mut lines: Vec<String> = existing.lines().map(String::from).collect();
+    let mut in_general = false;
+    let mut general_end = None;
+    let mut replaced = false;
+    for (index, line) in lines.iter_mut().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            in_general = trimmed == "[General]";
+            if in_general {
+                general_end = Some(index + 1);
+            }
+            continue;
+        }
+        if !in_general {
+            continue;
+        }
+        if trimmed
+            .split_once('=')
+            .is_some_and(|(key, _)| key.trim() == "background")
+        {
+            *line = entry.clone();
+            replaced = true;
+        } else if !trimmed.is_empty() {
+            general_end = Some(index + 1);
+        }
+    }
+    if !replaced {
+        match general_end {
+            Some(index) => lines.insert(index, entry),
+            None => {
+                if lines.last().is_some_and(|line| !line.trim().is_empty()) {
+                    lines.push(String::new());
+                }
+                lines.push("[General]".to_string());
+                lines.push(entry);
+            }
+        }
+    }
+    let mut content = lines.join("\n");
+    content.push('\n');
+    content
+}
+
+fn set_sddm_background(image_path: &Path, override_dir: Option<&str>) -> Result<()> {
+    let theme_path = sddm_theme_path(override_dir)?;
+    let target = Path::new(GREETER_DIR).join(GREETER_IMAGE);
+    std::fs::copy(image_path, &target)
+        .with_context(|| format!("Failed to copy wallpaper to {}", target.display()))?;
+
+    let conf_path = theme_path.join(THEME_CONF_USER);
+    let existing = match std::fs::read_to_string(&conf_path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+        Err(e) => {
+            return Err(e).with_context(|| format!("Failed to read {}", conf_path.display()));
+        }
+    };
+    let updated = update_theme_conf(&existing, &target.to_string_lossy());
+    if updated != existing {
+        std::fs::write(&conf_path, updated)
+            .with_context(|| format!("Failed to write {}", conf_path.display()))?;
+    }
+    info!(target: "wallpaper", "SDDM 登录界面背景已更新: {}", conf_path.display());
+    Ok(())
+}
+
+/// 按设置把壁纸同步到锁屏和登录界面，失败只记录警告
+pub(crate) async fn apply(app: &AppHandle, image_path: &Path) {
+    let (lock_screen, greeter, theme_dir) = {
+        let settings = app.state::<AppState>().settings.read().await;
+        (
+            settings.lock_screen_wallpaper,
+            settings.greeter_background.clone(),
+            settings.sddm_theme_dir.clone(),
+        )
+    };
+    if !lock_screen && greeter == "off" {
+        return;
+    }
+    let image_path = image_path
+        .canonicalize()
+        .unwrap_or_else(|_| image_path.to_path_buf());
+
+    let result = tokio::task::spawn_blocking(move || {
+        if lock_screen {
+            match set_lock_screen(&image_path) {
+                Ok(()) => {
+                    info!(target: "wallpaper", "KDE 锁屏壁纸已设置: {}", image_path.display())
+                }
+                Err(e) => warn!(target: "wallpaper", "设置 KDE 锁屏壁纸失败: {:#}", e),
+            }
+        }
+        if greeter == "sddm"
+            && let Err(e) = set_sddm_background(&image_path, theme_dir.as_deref())
+        {
+            warn!(
+                target: "wallpaper",
+                "设置 SDDM 登录界面背景失败（需要管理员授予写入权限）: {:#}",
+                e
+            );
+        }
+    })
+    .await;
+    if let Err(e) = result {
+        warn!(target: "wallpaper", "锁屏/登录界面背景任务失败: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock_screen_args_target_greeter_wallpaper() {
+        let args = lock_screen_args(Path::new("/home/u/Pictures/20240101.jpg"));
+        assert_eq!(args[..2], ["--file", "kscreenlockerrc"]);
+        assert_eq!(
+            args.iter().filter(|arg| *arg == "--group").count(),
+            4,
+            "{args:?}"
+        );
+        assert_eq!(args.last().unwrap(), "file:///home/u/Pictures/20240101.jpg");
+
+        let args = lock_screen_args(Path::new("/home/u/My Pictures/20240101.jpg"));
+        assert_eq!(
+            args.last().unwrap(),
+            "file:///home/u/My%20Pictures/20240101.jpg"
+        );
+    }
+
+    #[test]
+    fn test_parse_sddm_theme_later_files_override() {
+        let configs = vec![
+            "[Theme]\nCurrent=breeze\n".to_string(),
+            "[General]\nCurrent=ignored\n\n[Theme]\nThemeDir=/opt/themes\n".to_string(),
+            "[Theme]\nCurrent = sugar-candy\n".to_string(),
+        ];
+        assert_eq!(
+            parse_sddm_theme(&configs),
+            (
+                Some("/opt/themes".to_string()),
+                Some("sugar-candy".to_string())
+            )
+        );
+        assert_eq!(parse_sddm_theme(&[]), (None, None));
+    }
+
+    #[test]
+    fn test_update_theme_conf_preserves_other_keys() {
+        assert_eq!(
+            update_theme_conf("", "/var/lib/x.jpg"),
+            "[General]\nbackground=/var/lib/x.jpg\n"
+        );
+        assert_eq!(
+            update_theme_conf(
+                "[General]\nbackground=old.png\ntype=image\n",
+                "/var/lib/x.jpg"
+            ),
+            "[General]\nbackground=/var/lib/x.jpg\ntype=image\n"
+        );
+        assert_eq!(
+            update_theme_conf(
+                "[General]\ntype=image\n\n[Other]\nbackground=keep\n",
+                "/x.jpg"
+            ),
+            "[General]\ntype=image\nbackground=/x.jpg\n\n[Other]\nbackground=keep\n"
+        );
+        assert_eq!(
+            update_theme_conf("[Other]\nkey=1", "/x.jpg"),
+            "[Other]\nkey=1\n\n[General]\nbackground=/x.jpg\n"
+        );
+    }
+}
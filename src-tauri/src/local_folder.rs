@@ -0,0 +1,255 @@
+//! 自定义图片文件夹壁纸来源
+//!
+//! 用户可以在设置中指定一个本地图片文件夹，按 `local_folder_schedule` 与 Bing 壁纸
+//! 按天交替或混合轮换。自定义图片不复制到壁纸目录、也不写入 Bing 元数据索引，
+//! 已应用记录保存在运行时状态的 `"local"` mkt 下（键为文件名）。
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, Local};
+use log::{error, info, warn};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tauri::AppHandle;
+
+use crate::{
+    AppState, events, extension_events, runtime_state, wallpaper_theme, wallpaper_transition,
+};
+
+/// 已应用记录中自定义文件夹使用的 mkt 键
+pub(crate) const LOCAL_MKT: &str = "local";
+
+const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png"];
+
+/// 自定义文件夹中的一张图片
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FolderImage {
+    pub path: PathBuf,
+    pub modified: SystemTime,
+}
+
+impl FolderImage {
+    fn file_name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+}
+
+fn is_image_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+/// 按设置排序：`"name"` 按文件名升序，其余按修改时间从新到旧
+fn sort_images(images: &mut [FolderImage], order: &str) {
+    if order == "name" {
+        images.sort_by_key(|image| image.file_name().to_lowercase());
+    } else {
+        images.sort_by(|a, b| {
+            b.modified
+                .cmp(&a.modified)
+                .then_with(|| a.file_name().cmp(&b.file_name()))
+        });
+    }
+}
+
+/// 扫描文件夹顶层的图片文件（忽略隐藏文件和子目录）
+pub(crate) fn scan_folder(folder: &Path, order: &str) -> Result<Vec<FolderImage>> {
+    let mut images = Vec::new();
+    for entry in std::fs::read_dir(folder).context("Failed to read local folder")? {
+        let entry = entry?;
+        let path = entry.path();
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        if hidden || !is_image_file(&path) {
+            continue;
+        }
+        let metadata = entry.metadata()?;
+        if !metadata.is_file() || metadata.len() == 0 {
+            continue;
+        }
+        images.push(FolderImage {
+            path,
+            modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+        });
+    }
+    sort_images(&mut images, order);
+    Ok(images)
+}
+
+/// 决定某一天使用的来源：`Some(i)` 为自定义文件夹中第 `i` 张图片，`None` 为 Bing 壁纸
+///
+/// - `"alternate"`：奇数天使用自定义图片（依次轮换），偶数天使用 Bing；
+/// - `"mix"`：Bing 最新壁纸与全部自定义图片组成一个轮换队列，每天前进一格；
+/// - 其他值：始终使用 Bing。
+pub(crate) fn pick_local_index(
+    schedule: &str,
+    day_number: i64,
+    image_count: usize,
+) -> Option<usize> {
+    if image_count == 0 {
+        return None;
+    }
+    let count = image_count as i64;
+    match schedule {
+        "alternate" => (day_number.rem_euclid(2) == 1)
+            .then(|| (day_number.div_euclid(2).rem_euclid(count)) as usize),
+        "mix" => match day_number.rem_euclid(count + 1) {
+            0 => None,
+            slot => Some((slot - 1) as usize),
+        },
+        _ => None,
+    }
+}
+
+/// 今天应应用的自定义图片（按排期不使用自定义文件夹时返回 `None`）
+async fn scheduled_image(state: &AppState, now: DateTime<Local>) -> Option<FolderImage> {
+    let (folder, order, schedule) = {
+        let settings = state.settings.read().await;
+        (
+            settings.local_folder.clone()?,
+            settings.local_folder_order.clone(),
+            settings.local_folder_schedule.clone(),
+        )
+    };
+    if schedule == "off" {
+        return None;
+    }
+
+    let images =
+        match tokio::task::spawn_blocking(move || scan_folder(Path::new(&folder), &order)).await {
+            Ok(Ok(images)) => images,
+            Ok(Err(e)) => {
+                warn!(target: "local_folder", "扫描自定义文件夹失败: {}", e);
+                return None;
+            }
+            Err(e) => {
+                warn!(target: "local_folder", "扫描自定义文件夹任务异常: {}", e);
+                return None;
+            }
+        };
+
+    let day_number = i64::from(now.date_naive().num_days_from_ce());
+    let index = pick_local_index(&schedule, day_number, images.len())?;
+    images.into_iter().nth(index)
+}
+
+/// 按排期应用自定义文件夹中的图片
+///
+/// 返回 `true` 表示今天由自定义文件夹接管（已应用或已是当前壁纸），调用方不再应用 Bing 壁纸。
+pub(crate) async fn apply_scheduled_image(app: &AppHandle, state: &AppState) -> bool {
+    let Some(image) = scheduled_image(state, state.clock.now()).await else {
+        return false;
+    };
+
+    apply_image(app, state, &image.path, LOCAL_MKT, &image.file_name()).await
+}
+
+/// 应用非 Bing 来源的图片（自定义文件夹、Windows 聚焦），已应用记录保存在 `mkt` 下
+///
+/// 返回 `true` 表示已应用或已是当前壁纸；设置失败时返回 `false`，由调用方回退到 Bing 壁纸。
+pub(crate) async fn apply_image(
+    app: &AppHandle,
+    state: &AppState,
+    path: &Path,
+    mkt: &str,
+    key: &str,
+) -> bool {
+    if state.current_wallpaper_path.lock().await.as_deref() == Some(path) {
+        return true;
+    }
+
+    let _apply_guard = state.wallpaper_apply_queue.lock().await;
+    if let Err(e) = wallpaper_transition::set_wallpaper(app, path, None).await {
+        error!(target: "local_folder", "设置 {mkt} 图片失败: {e}，回退到 Bing 壁纸");
+        return false;
+    }
+
+    info!(target: "local_folder", "已应用 {} 图片: {}", mkt, path.display());
+    *state.current_wallpaper_path.lock().await = Some(path.to_path_buf());
+    let _ = events::CURRENT_WALLPAPER_CHANGED.emit(app, &path.to_string_lossy());
+    wallpaper_theme::on_wallpaper_applied(app, path);
+    extension_events::publish_wallpaper_changed(app, path, mkt, None).await;
+    if let Err(e) = runtime_state::record_applied_wallpaper(app, mkt, key) {
+        warn!(target: "local_folder", "保存当前壁纸记录失败: {e}");
+    }
+    true
+}
+
+/// 统计自定义文件夹中可用的图片数量（供设置界面校验所选文件夹）
+#[tauri::command]
+pub(crate) async fn count_local_folder_images(folder: String) -> Result<usize, String> {
+    let path = PathBuf::from(&folder);
+    if !path.is_dir() {
+        return Err("NOT_DIRECTORY".to_string());
+    }
+    tokio::task::spawn_blocking(move || scan_folder(&path, "name").map(|images| images.len()))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("Failed to scan folder: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn image(name: &str, modified_secs: u64) -> FolderImage {
+        FolderImage {
+            path: PathBuf::from(format!("/photos/{name}")),
+            modified: SystemTime::UNIX_EPOCH + Duration::from_secs(modified_secs),
+        }
+    }
+
+    #[test]
+    fn test_sort_images_by_mtime_and_name() {
+        let mut images = vec![image("b.jpg", 10), image("A.png", 30), image("c.jpeg", 20)];
+
+        sort_images(&mut images, "mtime");
+        let names: Vec<_> = images.iter().map(FolderImage::file_name).collect();
+        assert_eq!(names, vec!["A.png", "c.jpeg", "b.jpg"]);
+
+        sort_images(&mut images, "name");
+        let names: Vec<_> = images.iter().map(FolderImage::file_name).collect();
+        assert_eq!(names, vec!["A.png", "b.jpg", "c.jpeg"]);
+    }
+
+    #[test]
+    fn test_pick_local_index_alternate() {
+        let picks: Vec<_> = (0..6)
+            .map(|day| pick_local_index("alternate", day, 2))
+            .collect();
+        assert_eq!(picks, vec![None, Some(0), None, Some(1), None, Some(0)]);
+    }
+
+    #[test]
+    fn test_pick_local_index_mix_and_off() {
+        let picks: Vec<_> = (0..4).map(|day| pick_local_index("mix", day, 2)).collect();
+        assert_eq!(picks, vec![None, Some(0), Some(1), None]);
+
+        assert_eq!(pick_local_index("off", 1, 3), None);
+        assert_eq!(pick_local_index("mix", 1, 0), None);
+    }
+
+    #[test]
+    fn test_scan_folder_skips_non_images() {
+        let unique = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("bw_local_folder_{unique}"));
+        std::fs::create_dir_all(dir.join("nested.jpg")).unwrap();
+        std::fs::write(dir.join("b.JPG"), b"jpeg").unwrap();
+        std::fs::write(dir.join("a.png"), b"png").unwrap();
+        std::fs::write(dir.join("empty.jpg"), b"").unwrap();
+        std::fs::write(dir.join(".hidden.jpg"), b"jpeg").unwrap();
+        std::fs::write(dir.join("notes.txt"), b"text").unwrap();
+
+        let images = scan_folder(&dir, "name").unwrap();
+        let names: Vec<_> = images.iter().map(FolderImage::file_name).collect();
+        assert_eq!(names, vec!["a.png", "b.JPG"]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,147 @@
+//! 运行时可调的日志级别过滤
+//!
+//! tauri-plugin-log 的级别在 Builder 中固定，无法在运行时修改。
+//! 插件本身以 `Trace` 级别注册，实际过滤交给这里的全局配置：
+//! 默认级别 + 按 target 覆盖的级别，可通过 `set_log_level` 命令随时调整，
+//! 便于用户复现问题时只为 `bing_api`、`update` 等模块打开 debug 日志，而无需重启。
+
+use log::{LevelFilter, Metadata, info};
+use std::collections::HashMap;
+use std::sync::{LazyLock, RwLock};
+
+/// 未做任何配置时的默认级别（与此前 Builder 中的固定级别一致）
+pub(crate) const DEFAULT_LOG_LEVEL: LevelFilter = LevelFilter::Info;
+
+#[derive(Debug)]
+struct LogFilterConfig {
+    default_level: LevelFilter,
+    target_levels: HashMap<String, LevelFilter>,
+}
+
+impl LogFilterConfig {
+    fn new() -> Self {
+        Self {
+            default_level: DEFAULT_LOG_LEVEL,
+            target_levels: HashMap::new(),
+        }
+    }
+
+    /// 查找记录 target 对应的级别
+    ///
+    /// 未显式指定 target 的日志使用模块路径（如 `bing_wallpaper_now_lib::download_manager`），
+    /// 因此除精确匹配外，也匹配模块路径的前缀或最后一段。
+    fn level_for(&self, target: &str) -> LevelFilter {
+        if let Some(level) = self.target_levels.get(target) {
+            return *level;
+        }
+        self.target_levels
+            .iter()
+            .find(|(name, _)| {
+                target.starts_with(&format!("{name}::")) || target.ends_with(&format!("::{name}"))
+            })
+            .map(|(_, level)| *level)
+            .unwrap_or(self.default_level)
+    }
+}
+
+static LOG_FILTER: LazyLock<RwLock<LogFilterConfig>> =
+    LazyLock::new(|| RwLock::new(LogFilterConfig::new()));
+
+/// tauri-plugin-log 的过滤回调
+pub(crate) fn is_enabled(metadata: &Metadata) -> bool {
+    let config = LOG_FILTER.read().unwrap_or_else(|e| e.into_inner());
+    metadata.level() <= config.level_for(metadata.target())
+}
+
+/// 解析前端传入的级别字符串（不区分大小写）
+fn parse_level(level: &str) -> Result<LevelFilter, String> {
+    level
+        .trim()
+        .parse::<LevelFilter>()
+        .map_err(|_| format!("无效的日志级别: {level}（可选: off/error/warn/info/debug/trace）"))
+}
+
+/// 应用新的级别配置
+///
+/// - `targets` 为空：修改默认级别，并清除所有 target 覆盖；
+/// - 否则：仅为指定 target 设置级别，其余保持不变。
+fn apply_level(config: &mut LogFilterConfig, level: LevelFilter, targets: &[String]) {
+    let targets: Vec<&str> = targets
+        .iter()
+        .map(|t| t.trim())
+        .filter(|t| !t.is_empty())
+        .collect();
+
+    if targets.is_empty() {
+        config.default_level = level;
+        config.target_levels.clear();
+    } else {
+        for target in targets {
+            config.target_levels.insert(target.to_string(), level);
+        }
+    }
+}
+
+/// 运行时调整日志级别
+///
+/// 示例：`set_log_level("debug", ["bing_api", "download_manager"])` 仅为这两个模块打开 debug 日志；
+/// `set_log_level("info", [])` 恢复默认配置。
+#[tauri::command]
+pub(crate) fn set_log_level(level: String, targets: Option<Vec<String>>) -> Result<(), String> {
+    let level = parse_level(&level)?;
+    let targets = targets.unwrap_or_default();
+    {
+        let mut config = LOG_FILTER.write().unwrap_or_else(|e| e.into_inner());
+        apply_level(&mut config, level, &targets);
+    }
+    // 先更新配置再输出，确保这条日志本身不会被旧配置过滤掉
+    info!(target: "settings", "日志级别已更新: level={}, targets={:?}", level, targets);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_level_is_case_insensitive() {
+        assert_eq!(parse_level("DEBUG").unwrap(), LevelFilter::Debug);
+        assert_eq!(parse_level(" trace ").unwrap(), LevelFilter::Trace);
+        assert!(parse_level("verbose").is_err());
+    }
+
+    #[test]
+    fn test_default_level_applies_to_unknown_targets() {
+        let config = LogFilterConfig::new();
+        assert_eq!(config.level_for("update"), DEFAULT_LOG_LEVEL);
+    }
+
+    #[test]
+    fn test_target_override_matches_exact_and_module_path() {
+        let mut config = LogFilterConfig::new();
+        apply_level(
+            &mut config,
+            LevelFilter::Debug,
+            &["bing_api".to_string(), "download_manager".to_string()],
+        );
+
+        assert_eq!(config.level_for("bing_api"), LevelFilter::Debug);
+        assert_eq!(
+            config.level_for("bing_wallpaper_now_lib::download_manager"),
+            LevelFilter::Debug
+        );
+        assert_eq!(config.level_for("bing_api::retry"), LevelFilter::Debug);
+        assert_eq!(config.level_for("update"), DEFAULT_LOG_LEVEL);
+    }
+
+    #[test]
+    fn test_empty_targets_reset_overrides() {
+        let mut config = LogFilterConfig::new();
+        apply_level(&mut config, LevelFilter::Trace, &["update".to_string()]);
+        apply_level(&mut config, LevelFilter::Warn, &[]);
+
+        assert_eq!(config.default_level, LevelFilter::Warn);
+        assert!(config.target_levels.is_empty());
+        assert_eq!(config.level_for("update"), LevelFilter::Warn);
+    }
+}
@@ -0,0 +1,327 @@
+//! "今日壁纸"迷你窗口
+//!
+//! 一个固定在屏幕角落、始终置顶的无边框小窗口，显示今日壁纸的缩略图和标题，并提供刷新按钮，
+//! 方便只想瞄一眼壁纸信息、不想打开主窗口的用户。窗口完全由后端管理：页面为静态的 `mini.html`，
+//! 数据通过初始化脚本注入，之后由后端调用页面的 `__showWallpaper` 更新；
+//! 页面中的按钮导航到约定地址，由后端拦截后执行对应操作。
+//!
+//! 拖动后的位置保存在运行时状态中，下次打开时恢复；位置已不在任何显示器上时回到右上角。
+
+use log::{info, warn};
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, PhysicalPosition, WebviewUrl, WebviewWindowBuilder, WindowEvent};
+
+use crate::models::LocalWallpaper;
+use crate::{AppState, get_effective_mkt, runtime_state, storage, tray};
+
+const MINI_LABEL: &str = "mini";
+const MINI_PAGE: &str = "mini.html";
+/// 页面按钮导航到的地址（被 `on_navigation` 拦截，不会真正加载）
+const ACTION_URL_HOST: &str = "action.mini";
+const MINI_SIZE: (f64, f64) = (320.0, 96.0);
+/// 默认位置与屏幕工作区边缘的间距（逻辑像素）
+const MINI_MARGIN: f64 = 16.0;
+/// 拖动结束后等待多久再保存位置，避免拖动过程中反复写盘
+const SAVE_POSITION_DELAY: Duration = Duration::from_millis(500);
+
+/// 每次移动窗口递增，只有最后一次移动会保存位置
+static MOVE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// 页面显示的壁纸
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MiniWallpaper {
+    title: String,
+    end_date: String,
+}
+
+/// 注入页面的初始数据（`window.__MINI__`）
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MiniInit<'a> {
+    lang: &'a str,
+    refresh_label: &'static str,
+    empty_text: &'static str,
+    action_url: String,
+    wallpaper: Option<MiniWallpaper>,
+}
+
+fn refresh_label(language: &str) -> &'static str {
+    if language == "zh-CN" {
+        "刷新"
+    } else {
+        "Refresh"
+    }
+}
+
+fn empty_text(language: &str) -> &'static str {
+    if language == "zh-CN" {
+        "暂无壁纸"
+    } else {
+        "No wallpaper yet"
+    }
+}
+
+/// 迷你窗口当前是否显示（托盘菜单据此显示勾选状态）
+pub(crate) fn is_visible(app: &AppHandle) -> bool {
+    app.get_webview_window(MINI_LABEL)
+        .is_some_and(|window| window.is_visible().unwrap_or(false))
+}
+
+fn to_mini_wallpaper(wallpaper: &LocalWallpaper) -> MiniWallpaper {
+    MiniWallpaper {
+        title: wallpaper.title.clone(),
+        end_date: wallpaper.end_date.clone(),
+    }
+}
+
+/// 读取最新一张壁纸（本地列表按日期降序）
+async fn load_today_wallpaper(app: &AppHandle) -> Option<MiniWallpaper> {
+    let state = app.state::<AppState>();
+    let wallpaper_dir = state.wallpaper_directory.lock().await.clone();
+    let mkt = get_effective_mkt(&state).await;
+    match storage::get_local_wallpapers(&wallpaper_dir, &mkt).await {
+        Ok(wallpapers) => wallpapers.first().map(to_mini_wallpaper),
+        Err(e) => {
+            warn!(target: "mini_window", "读取壁纸元数据失败: {}", e);
+            None
+        }
+    }
+}
+
+/// 窗口在某个显示器上是否至少有一部分可见
+///
+/// `monitors` 为各显示器的 (x, y, 宽, 高)，均为物理像素。
+fn position_on_screen(
+    position: (i32, i32),
+    size: (u32, u32),
+    monitors: &[(i32, i32, u32, u32)],
+) -> bool {
+    let (left, top) = (i64::from(position.0), i64::from(position.1));
+    let (right, bottom) = (left + i64::from(size.0), top + i64::from(size.1));
+    monitors.iter().any(|&(x, y, width, height)| {
+        let (x, y) = (i64::from(x), i64::from(y));
+        left < x + i64::from(width) && right > x && top < y + i64::from(height) && bottom > y
+    })
+}
+
+/// 默认位置：主显示器工作区右上角（逻辑像素）
+fn default_position(app: &AppHandle) -> Option<(f64, f64)> {
+    let monitor = app.primary_monitor().ok().flatten()?;
+    let scale = monitor.scale_factor();
+    let area = monitor.work_area();
+    let x = f64::from(area.position.x) / scale;
+    let y = f64::from(area.position.y) / scale;
+    let width = f64::from(area.size.width) / scale;
+    Some((x + width - MINI_SIZE.0 - MINI_MARGIN, y + MINI_MARGIN))
+}
+
+/// 读取上次保存的位置，已不在任何显示器上时返回 None
+fn saved_position(app: &AppHandle) -> Option<(i32, i32)> {
+    let position = runtime_state::load_runtime_state(app)
+        .ok()?
+        .mini_window_position?;
+    let scale = app
+        .primary_monitor()
+        .ok()
+        .flatten()
+        .map_or(1.0, |monitor| monitor.scale_factor());
+    let size = (
+        (MINI_SIZE.0 * scale).round() as u32,
+        (MINI_SIZE.1 * scale).round() as u32,
+    );
+    let monitors: Vec<_> = app
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(|monitor| {
+            let (position, size) = (monitor.position(), monitor.size());
+            (position.x, position.y, size.width, size.height)
+        })
+        .collect();
+    position_on_screen(position, size, &monitors).then_some(position)
+}
+
+/// 拖动停止后保存窗口位置
+fn schedule_save_position(app: &AppHandle, position: PhysicalPosition<i32>) {
+    let generation = MOVE_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(SAVE_POSITION_DELAY).await;
+        if MOVE_GENERATION.load(Ordering::SeqCst) != generation {
+            return;
+        }
+        if let Err(e) = runtime_state::modify_runtime_state(&app, |state| {
+            state.mini_window_position = Some((position.x, position.y));
+            true
+        }) {
+            warn!(target: "mini_window", "保存迷你窗口位置失败: {}", e);
+        }
+    });
+}
+
+/// 处理页面按钮：`refresh` 强制更新壁纸，`open` 显示主窗口
+fn handle_action(app: &AppHandle, action: &str) {
+    match action {
+        "refresh" => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                crate::update_cycle::run_update_cycle_internal(&app, true).await;
+                // 更新循环结束时会刷新迷你窗口；已有更新在进行时这里兜底恢复按钮状态
+                refresh(&app).await;
+            });
+        }
+        "open" => {
+            if let Err(e) =
+                crate::commands::window::show_main_window_with_watchdog(app, "mini_window")
+            {
+                warn!(target: "mini_window", "从迷你窗口显示主窗口失败: {}", e);
+            }
+        }
+        other => warn!(target: "mini_window", "未知的迷你窗口操作: {}", other),
+    }
+}
+
+/// 用最新壁纸刷新迷你窗口（未打开时不做任何事）
+pub(crate) async fn refresh(app: &AppHandle) {
+    let Some(window) = app.get_webview_window(MINI_LABEL) else {
+        return;
+    };
+    let wallpaper = load_today_wallpaper(app).await;
+    let Ok(data) = serde_json::to_string(&wallpaper) else {
+        return;
+    };
+    if let Err(e) = window.eval(format!("window.__showWallpaper({data});")) {
+        warn!(target: "mini_window", "刷新迷你窗口失败: {}", e);
+    }
+}
+
+async fn open(app: &AppHandle) -> Result<(), String> {
+    let language = app
+        .state::<AppState>()
+        .settings
+        .read()
+        .await
+        .resolved_language;
+    let init = MiniInit {
+        lang: &language,
+        refresh_label: refresh_label(&language),
+        empty_text: empty_text(&language),
+        action_url: format!("https://{ACTION_URL_HOST}/"),
+        wallpaper: load_today_wallpaper(app).await,
+    };
+    let data = serde_json::to_string(&init).map_err(|e| e.to_string())?;
+
+    let action_handle = app.clone();
+    let window = WebviewWindowBuilder::new(app, MINI_LABEL, WebviewUrl::App(MINI_PAGE.into()))
+        .title("Bing Wallpaper Now")
+        .inner_size(MINI_SIZE.0, MINI_SIZE.1)
+        .decorations(false)
+        .resizable(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .focused(false)
+        .visible(false)
+        .initialization_script(format!("window.__MINI__ = {data};"))
+        .on_navigation(move |url| {
+            if url.host_str() != Some(ACTION_URL_HOST) {
+                return url.path().ends_with(MINI_PAGE);
+            }
+            let action = url.path().trim_matches('/').to_string();
+            let app = action_handle.clone();
+            // 不在导航回调中直接操作窗口
+            tauri::async_runtime::spawn(async move { handle_action(&app, &action) });
+            false
+        })
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    match saved_position(app) {
+        Some((x, y)) => {
+            let _ = window.set_position(PhysicalPosition::new(x, y));
+        }
+        None => {
+            if let Some((x, y)) = default_position(app) {
+                let _ = window.set_position(tauri::LogicalPosition::new(x, y));
+            }
+        }
+    }
+    window.show().map_err(|e| e.to_string())?;
+
+    let event_handle = app.clone();
+    window.on_window_event(move |event| match event {
+        WindowEvent::Moved(position) => schedule_save_position(&event_handle, *position),
+        // 无论从托盘还是系统快捷键关闭，都同步托盘菜单的勾选状态
+        WindowEvent::Destroyed => {
+            let app = event_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = tray::update_tray_menu(&app).await {
+                    warn!(target: "mini_window", "刷新托盘菜单失败: {}", e);
+                }
+            });
+        }
+        _ => {}
+    });
+    info!(target: "mini_window", "打开迷你窗口");
+    Ok(())
+}
+
+/// 切换迷你窗口，返回切换后是否显示
+pub(crate) async fn toggle(app: &AppHandle) -> Result<bool, String> {
+    if let Some(window) = app.get_webview_window(MINI_LABEL) {
+        // 托盘菜单在窗口销毁后刷新
+        window.close().map_err(|e| e.to_string())?;
+        info!(target: "mini_window", "关闭迷你窗口");
+        return Ok(false);
+    }
+    open(app).await?;
+    if let Err(e) = tray::update_tray_menu(app).await {
+        warn!(target: "mini_window", "刷新托盘菜单失败: {}", e);
+    }
+    Ok(true)
+}
+
+/// 显示或关闭"今日壁纸"迷你窗口，返回切换后是否显示
+#[tauri::command]
+pub(crate) async fn toggle_mini_window(app: AppHandle) -> Result<bool, String> {
+    toggle(&app).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position_on_screen_requires_overlap_with_a_monitor() {
+        let monitors = [(0, 0, 1920, 1080), (1920, 0, 2560, 1440)];
+        assert!(position_on_screen((100, 100), (320, 96), &monitors));
+        // 跨越两块显示器
+        assert!(position_on_screen((1800, 0), (320, 96), &monitors));
+        // 刚好贴在右侧显示器之外
+        assert!(!position_on_screen((4480, 0), (320, 96), &monitors));
+        // 副屏拔掉后停留在原副屏位置
+        assert!(!position_on_screen((2400, 200), (320, 96), &monitors[..1]));
+        assert!(!position_on_screen((0, 0), (320, 96), &[]));
+    }
+
+    #[test]
+    fn test_init_data_serializes_camel_case() {
+        let init = MiniInit {
+            lang: "en-US",
+            refresh_label: refresh_label("en-US"),
+            empty_text: empty_text("en-US"),
+            action_url: format!("https://{ACTION_URL_HOST}/"),
+            wallpaper: Some(MiniWallpaper {
+                title: "Moraine Lake".to_string(),
+                end_date: "20240101".to_string(),
+            }),
+        };
+        let json = serde_json::to_value(&init).unwrap();
+        assert_eq!(json["refreshLabel"], "Refresh");
+        assert_eq!(json["actionUrl"], "https://action.mini/");
+        assert_eq!(json["wallpaper"]["endDate"], "20240101");
+        assert_eq!(refresh_label("zh-CN"), "刷新");
+    }
+}
@@ -0,0 +1,229 @@
+//! 首次启动的市场（mkt）建议
+//!
+//! 默认 mkt 跟随界面语言，但界面语言并不代表所在地区（例如在日本使用英文系统的用户）。
+//! 首次启动时根据 IP 所在国家（失败时退回系统 locale 的地区部分）推算最相关的 Bing 市场，
+//! 作为待确认的建议保存到运行时状态；只有用户在设置中确认后才会真正切换 mkt。
+
+use log::{info, warn};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::models::{MktSuggestion, MktSuggestionSource, MktSuggestionStatus};
+use crate::{AppState, commands, events, http_client, runtime_state, utils};
+
+/// 轻量地理位置接口，返回 `key=value` 文本，其中 `loc=XX` 为国家代码
+const GEO_TRACE_URL: &str = "https://www.cloudflare.com/cdn-cgi/trace";
+
+const GEO_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 解析 locale 字符串，返回 (语言, 地区)
+///
+/// 兼容 `en-US`、`en_JP.UTF-8`、`zh-Hans-CN` 等格式，地区统一为大写。
+fn parse_locale(locale: &str) -> (String, Option<String>) {
+    let locale = locale.split(['.', '@']).next().unwrap_or_default();
+    let mut parts = locale.split(['-', '_']);
+    let language = parts.next().unwrap_or_default().to_ascii_lowercase();
+    let region = parts
+        .rfind(|part| part.len() == 2 && part.chars().all(|c| c.is_ascii_alphabetic()))
+        .map(|part| part.to_ascii_uppercase());
+    (language, region)
+}
+
+/// 根据国家代码选择市场，同一国家有多个市场时优先匹配语言（如 fr-CA / en-CA）
+fn mkt_for_country(country: &str, language: &str) -> Option<&'static str> {
+    let country = country.to_ascii_uppercase();
+    let candidates: Vec<&'static str> = utils::SUPPORTED_MKTS
+        .iter()
+        .copied()
+        .filter(|mkt| mkt.split('-').nth(1) == Some(country.as_str()))
+        .collect();
+
+    candidates
+        .iter()
+        .copied()
+        .find(|mkt| mkt.split('-').next() == Some(language))
+        .or_else(|| candidates.first().copied())
+}
+
+/// 从地理位置接口的响应中提取国家代码
+fn parse_geo_trace(body: &str) -> Option<String> {
+    body.lines()
+        .find_map(|line| line.strip_prefix("loc="))
+        .map(str::trim)
+        .filter(|loc| loc.len() == 2 && loc.chars().all(|c| c.is_ascii_alphabetic()))
+        .map(str::to_ascii_uppercase)
+}
+
+async fn fetch_geo_country() -> Option<String> {
+    let client = http_client::builder().timeout(GEO_TIMEOUT).build().ok()?;
+    let response = match http_client::send(client.get(GEO_TRACE_URL)).await {
+        Ok(response) if response.status().is_success() => response,
+        Ok(response) => {
+            warn!(target: "startup", "地理位置查询失败: HTTP {}", response.status());
+            return None;
+        }
+        Err(http_client::SendError::Restricted) => return None,
+        Err(e) => {
+            warn!(target: "startup", "地理位置查询失败: {}", e);
+            return None;
+        }
+    };
+    parse_geo_trace(&response.text().await.ok()?)
+}
+
+/// 计算市场建议：优先使用 IP 所在国家，失败时使用系统 locale 的地区
+async fn compute_suggestion(current_mkt: &str) -> Option<MktSuggestion> {
+    let (language, locale_region) = parse_locale(&sys_locale::get_locale().unwrap_or_default());
+
+    let geo = fetch_geo_country().await.and_then(|country| {
+        let mkt = mkt_for_country(&country, &language)?;
+        Some((mkt, country, MktSuggestionSource::Geo))
+    });
+    let (mkt, region, source) = match geo {
+        Some(geo) => geo,
+        None => {
+            let region = locale_region?;
+            let mkt = mkt_for_country(&region, &language)?;
+            (mkt, region, MktSuggestionSource::Locale)
+        }
+    };
+
+    // 与当前设置一致时无需询问用户
+    let status = if mkt == current_mkt {
+        MktSuggestionStatus::Accepted
+    } else {
+        MktSuggestionStatus::Pending
+    };
+
+    Some(MktSuggestion {
+        mkt: mkt.to_string(),
+        region,
+        source,
+        status,
+    })
+}
+
+/// 首次启动时生成市场建议（后台执行，每个安装只计算一次）
+pub(crate) fn spawn_first_launch_suggestion(app: &AppHandle) {
+    let runtime = runtime_state::load_runtime_state(app).unwrap_or_default();
+    if runtime.mkt_suggestion.is_some() || runtime.last_successful_update.is_some() {
+        return;
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let current_mkt = app.state::<AppState>().settings.read().await.mkt;
+        let Some(suggestion) = compute_suggestion(&current_mkt).await else {
+            info!(target: "startup", "无法推算所在地区，跳过市场建议");
+            return;
+        };
+
+        info!(
+            target: "startup",
+            "首次启动市场建议: {} (地区 {}, 来源 {:?}, 当前 {})",
+            suggestion.mkt, suggestion.region, suggestion.source, current_mkt
+        );
+
+        let pending = suggestion.status == MktSuggestionStatus::Pending;
+        if let Err(e) = runtime_state::modify_runtime_state(&app, |runtime| {
+            runtime.mkt_suggestion = Some(suggestion.clone());
+            true
+        }) {
+            warn!(target: "startup", "保存市场建议失败: {}", e);
+            return;
+        }
+        if pending {
+            let _ = events::MKT_SUGGESTION_READY.emit(&app, &suggestion);
+        }
+    });
+}
+
+/// 获取待确认的市场建议（已接受或已忽略时返回 `None`）
+#[tauri::command]
+pub(crate) async fn get_mkt_suggestion(app: AppHandle) -> Result<Option<MktSuggestion>, String> {
+    let runtime = runtime_state::load_runtime_state(&app)
+        .map_err(|e| format!("Failed to load runtime state: {}", e))?;
+    Ok(runtime
+        .mkt_suggestion
+        .filter(|s| s.status == MktSuggestionStatus::Pending))
+}
+
+/// 响应市场建议：接受时切换 mkt，否则仅标记为已忽略
+#[tauri::command]
+pub(crate) async fn respond_mkt_suggestion(
+    accept: bool,
+    state: tauri::State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    let mut runtime = runtime_state::load_runtime_state(&app)
+        .map_err(|e| format!("Failed to load runtime state: {}", e))?;
+    let Some(mut suggestion) = runtime
+        .mkt_suggestion
+        .take()
+        .filter(|s| s.status == MktSuggestionStatus::Pending)
+    else {
+        return Err("NO_PENDING_SUGGESTION".to_string());
+    };
+
+    if accept {
+        let mut settings = state.settings.read().await;
+        settings.mkt = suggestion.mkt.clone();
+        commands::settings::update_settings(settings, state, app.clone())
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    suggestion.status = if accept {
+        MktSuggestionStatus::Accepted
+    } else {
+        MktSuggestionStatus::Dismissed
+    };
+    info!(target: "settings", "市场建议 {} 已{}", suggestion.mkt, if accept { "接受" } else { "忽略" });
+
+    // update_settings 可能改写了运行时状态（清空 last_actual_mkt），只更新建议字段
+    runtime_state::modify_runtime_state(&app, |runtime| {
+        runtime.mkt_suggestion = Some(suggestion);
+        true
+    })
+    .map(|_| ())
+    .map_err(|e| format!("Failed to save runtime state: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_locale_formats() {
+        assert_eq!(
+            parse_locale("en-US"),
+            ("en".to_string(), Some("US".to_string()))
+        );
+        assert_eq!(
+            parse_locale("en_JP.UTF-8"),
+            ("en".to_string(), Some("JP".to_string()))
+        );
+        assert_eq!(
+            parse_locale("zh-Hans-CN"),
+            ("zh".to_string(), Some("CN".to_string()))
+        );
+        assert_eq!(parse_locale("fr"), ("fr".to_string(), None));
+    }
+
+    #[test]
+    fn test_mkt_for_country_prefers_matching_language() {
+        assert_eq!(mkt_for_country("JP", "en"), Some("ja-JP"));
+        assert_eq!(mkt_for_country("ca", "fr"), Some("fr-CA"));
+        assert_eq!(mkt_for_country("CA", "en"), Some("en-CA"));
+        assert_eq!(mkt_for_country("US", "es"), Some("es-US"));
+        assert_eq!(mkt_for_country("XX", "en"), None);
+    }
+
+    #[test]
+    fn test_parse_geo_trace() {
+        let body = "fl=123\nh=www.cloudflare.com\nip=203.0.113.1\nloc=JP\ntls=TLSv1.3\n";
+        assert_eq!(parse_geo_trace(body), Some("JP".to_string()));
+        assert_eq!(parse_geo_trace("loc=\n"), None);
+        assert_eq!(parse_geo_trace("ip=1.1.1.1"), None);
+    }
+}
@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+
+/// 备份目标
+///
+/// 凭据（WebDAV 密码 / S3 Secret Key）不在此结构中，统一存放在系统钥匙串。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BackupTarget {
+    /// 未配置
+    #[default]
+    None,
+    /// WebDAV 目录，如 `https://dav.example.com/remote.php/dav/files/me/bing`
+    WebDav { url: String, username: String },
+    /// S3 兼容对象存储（AWS S3、MinIO、R2 等）
+    S3 {
+        /// 服务地址，如 `https://s3.us-east-1.amazonaws.com`
+        endpoint: String,
+        bucket: String,
+        region: String,
+        access_key_id: String,
+        /// 对象 key 前缀，如 `bing-wallpapers/`
+        #[serde(default)]
+        prefix: String,
+        /// 使用 path-style 地址（`endpoint/bucket/key`），多数自建服务需要开启
+        #[serde(default = "default_path_style")]
+        path_style: bool,
+    },
+}
+
+fn default_path_style() -> bool {
+    true
+}
+
+/// 备份配置（存储在独立的 `backup.json` 中，与用户设置分离）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BackupConfig {
+    /// 每次更新循环结束后是否自动备份
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub target: BackupTarget,
+}
+
+/// 一次备份的结果统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BackupReport {
+    /// 本次上传成功的文件数（含 index.json）
+    pub uploaded: usize,
+    /// 已在之前备份过而跳过的文件数
+    pub skipped: usize,
+    /// 上传失败的文件名
+    pub failed: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backup_target_serialization() {
+        let target = BackupTarget::WebDav {
+            url: "https://dav.example.com/bing".to_string(),
+            username: "me".to_string(),
+        };
+        let json = serde_json::to_value(&target).unwrap();
+        assert_eq!(json["kind"], "web_dav");
+        assert_eq!(json["username"], "me");
+
+        let s3: BackupTarget = serde_json::from_str(
+            r#"{"kind":"s3","endpoint":"https://s3.example.com","bucket":"b","region":"us-east-1","access_key_id":"AK"}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            s3,
+            BackupTarget::S3 {
+                path_style: true,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_backup_config_default_is_disabled() {
+        let config: BackupConfig = serde_json::from_str("{}").unwrap();
+        assert!(!config.enabled);
+        assert_eq!(config.target, BackupTarget::None);
+    }
+}
@@ -0,0 +1,36 @@
+use serde::Serialize;
+
+/// 正在进行的图片下载
+///
+/// 由 `get_active_downloads` 命令返回，同时作为 `download-started` /
+/// `download-finished` 事件的负载。
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ActiveDownload {
+    /// 壁纸日期（YYYYMMDD）
+    pub end_date: String,
+    /// 是否为竖屏壁纸
+    pub portrait: bool,
+    /// 下载地址
+    pub url: String,
+    /// 已下载字节数
+    pub downloaded_bytes: u64,
+    /// 总字节数（服务器未返回 Content-Length 时为 None）
+    pub total_bytes: Option<u64>,
+    /// 下载进度（0.0 ~ 1.0，总大小未知时为 None）
+    pub progress: Option<f64>,
+    /// 当前是第几次尝试（从 1 开始）
+    pub attempt: usize,
+    /// 最大尝试次数
+    pub max_attempts: usize,
+}
+
+/// `download-failed` 事件负载
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadFailure {
+    #[serde(flatten)]
+    pub download: ActiveDownload,
+    /// 失败原因
+    pub error: String,
+    /// 是否还会自动重试
+    pub will_retry: bool,
+}
@@ -2,7 +2,12 @@ use chrono::{DateTime, Utc};
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
-use super::wallpaper::LocalWallpaper;
+use super::wallpaper::{LocalWallpaper, Recompression};
+
+/// Windows 聚焦图片在索引中使用的 mkt 键
+///
+/// 该键下的条目以图片内容哈希（而不是日期）为键，不参与按日期的去重和清理。
+pub const SPOTLIGHT_MKT: &str = "spotlight";
 
 /// 壁纸元数据索引（单一文件存储）
 ///
@@ -13,6 +18,7 @@ use super::wallpaper::LocalWallpaper;
 /// 迁移说明：
 /// - v4 → v5：自动备份旧文件为 `index.json.v4.bak`，将 `wallpapers_by_language` 迁移为 `mkt`
 /// - 通过 `#[serde(alias = "wallpapers_by_language")]` 保证反序列化兼容
+/// - 加载时一次性合并 v4 语言键与实际市场键下的重复条目（见 `reconcile_duplicate_mkts`）
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WallpaperIndex {
     /// 版本号（用于兼容性检查）
@@ -25,6 +31,44 @@ pub struct WallpaperIndex {
     /// 使用 IndexMap 以保持插入顺序，确保 JSON 序列化时按日期排序
     #[serde(alias = "wallpapers_by_language")]
     pub mkt: IndexMap<String, IndexMap<String, LocalWallpaper>>,
+    /// 用户为壁纸添加的标签：key = end_date（聚焦图片为其索引键），value = 排序后的标签
+    ///
+    /// 图片文件在所有 mkt 间共享，标签也按日期记录，不随某个 mkt 的元数据更新而丢失。
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub tags: IndexMap<String, Vec<String>>,
+    /// 是否已合并过旧版本留下的重复条目（旧文件缺少该字段时为 false，加载时合并一次）
+    #[serde(default)]
+    pub mkt_reconciled: bool,
+}
+
+/// v4 索引按界面语言分组时使用的键（迁移到 v5 后被当作 mkt）
+const LEGACY_LANGUAGE_KEYS: [&str; 2] = ["zh-CN", "en-US"];
+
+/// 用其他副本补全壁纸缺失的元数据，已有的值保持不变
+fn fill_missing_metadata(target: &mut LocalWallpaper, other: LocalWallpaper) {
+    for (field, value) in [
+        (&mut target.title, other.title),
+        (&mut target.copyright, other.copyright),
+        (&mut target.copyright_link, other.copyright_link),
+    ] {
+        if field.is_empty() {
+            *field = value;
+        }
+    }
+    target.resolution = target.resolution.take().or(other.resolution);
+    target.portrait_available = target.portrait_available.or(other.portrait_available);
+    target.watermark_free = target.watermark_free.or(other.watermark_free);
+    target.recompression = target.recompression.or(other.recompression);
+}
+
+/// 标签的最大长度（字符数）
+const MAX_TAG_CHARS: usize = 32;
+
+/// 归一化标签：去除首尾空白、合并连续空白并转为小写，空标签或过长时返回 None
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.split_whitespace().collect::<Vec<_>>().join(" ");
+    let tag = tag.to_lowercase();
+    (!tag.is_empty() && tag.chars().count() <= MAX_TAG_CHARS).then_some(tag)
 }
 
 impl Default for WallpaperIndex {
@@ -49,6 +93,8 @@ impl WallpaperIndex {
             version: Self::VERSION,
             last_updated: Utc::now(),
             mkt: IndexMap::new(),
+            tags: IndexMap::new(),
+            mkt_reconciled: true,
         }
     }
 
@@ -64,6 +110,29 @@ impl WallpaperIndex {
             .unwrap_or_default()
     }
 
+    /// 分页获取指定 mkt 的壁纸（与 `get_wallpapers_for_mkt` 顺序一致），返回当前页和总数
+    ///
+    /// 只克隆当前页的条目，`limit` 为 None 时返回 offset 之后的全部壁纸。
+    pub fn get_wallpapers_page_for_mkt(
+        &self,
+        mkt: &str,
+        offset: usize,
+        limit: Option<usize>,
+    ) -> (Vec<LocalWallpaper>, usize) {
+        let Some(wp_map) = self.mkt.get(mkt) else {
+            return (Vec::new(), 0);
+        };
+        let mut wallpapers: Vec<_> = wp_map.values().collect();
+        wallpapers.sort_by(|a, b| b.end_date.cmp(&a.end_date));
+        let page = wallpapers
+            .into_iter()
+            .skip(offset)
+            .take(limit.unwrap_or(usize::MAX))
+            .cloned()
+            .collect();
+        (page, wp_map.len())
+    }
+
     /// 批量添加或更新指定 mkt 的壁纸
     ///
     /// 插入时会按日期降序排序，确保 JSON 序列化时保持顺序。
@@ -73,18 +142,54 @@ impl WallpaperIndex {
         mkt: &str,
         wallpapers: Vec<LocalWallpaper>,
     ) -> usize {
+        self.upsert_wallpapers_tracked(mkt, wallpapers).0
+    }
+
+    /// 同 [`upsert_wallpapers_for_mkt`](Self::upsert_wallpapers_for_mkt)，额外返回索引是否发生变化
+    ///
+    /// 与已有条目完全相同的壁纸不计为变化，也不更新 `last_updated`；调用方据此跳过写盘。
+    pub fn upsert_wallpapers_tracked(
+        &mut self,
+        mkt: &str,
+        wallpapers: Vec<LocalWallpaper>,
+    ) -> (usize, bool) {
         if wallpapers.is_empty() {
-            return 0;
+            return (0, false);
         }
         let mkt_map = self.mkt.entry(mkt.to_string()).or_default();
 
         let mut new_count = 0;
-        for wallpaper in wallpapers {
+        let mut changed = false;
+        for mut wallpaper in wallpapers {
             let key = wallpaper.end_date.clone();
-            if !mkt_map.contains_key(&key) {
-                new_count += 1;
+            match mkt_map.get(&key) {
+                // API 返回的元数据不含分辨率和竖屏探测结果，保留已记录的值
+                Some(existing) => {
+                    if wallpaper.resolution.is_none() {
+                        wallpaper.resolution = existing.resolution.clone();
+                    }
+                    if wallpaper.watermark_free.is_none() {
+                        wallpaper.watermark_free = existing.watermark_free;
+                    }
+                    if wallpaper.recompression.is_none() {
+                        wallpaper.recompression = existing.recompression;
+                    }
+                    // urlbase 变化后竖屏地址也随之变化，需要重新探测
+                    if wallpaper.portrait_available.is_none()
+                        && wallpaper.urlbase == existing.urlbase
+                    {
+                        wallpaper.portrait_available = existing.portrait_available;
+                    }
+                }
+                None => new_count += 1,
             }
-            mkt_map.insert(key, wallpaper);
+            if mkt_map.get(&key) != Some(&wallpaper) {
+                mkt_map.insert(key, wallpaper);
+                changed = true;
+            }
+        }
+        if !changed {
+            return (0, false);
         }
 
         // 按日期降序排序（最新的在前）
@@ -94,7 +199,143 @@ impl WallpaperIndex {
         self.mkt.sort_keys();
 
         self.last_updated = Utc::now();
-        new_count
+        (new_count, true)
+    }
+
+    /// 记录指定日期壁纸实际下载的分辨率
+    ///
+    /// 图片文件按 end_date 命名、在所有 mkt 间共享，因此会更新所有 mkt 下的同日期条目。
+    /// 返回是否有条目发生变化。
+    pub fn set_resolution(&mut self, end_date: &str, resolution: &str) -> bool {
+        let mut changed = false;
+        for mkt_wallpapers in self.mkt.values_mut() {
+            if let Some(wallpaper) = mkt_wallpapers.get_mut(end_date)
+                && wallpaper.resolution.as_deref() != Some(resolution)
+            {
+                wallpaper.resolution = Some(resolution.to_string());
+                changed = true;
+            }
+        }
+        if changed {
+            self.last_updated = Utc::now();
+        }
+        changed
+    }
+
+    /// 记录指定日期壁纸磁盘上的横屏图片是否为无水印版本
+    ///
+    /// 与分辨率相同，图片文件在所有 mkt 间共享，因此更新所有 mkt 下的同日期条目。
+    /// 返回是否有条目发生变化。
+    pub fn set_watermark_free(&mut self, end_date: &str, watermark_free: bool) -> bool {
+        let mut changed = false;
+        for mkt_wallpapers in self.mkt.values_mut() {
+            if let Some(wallpaper) = mkt_wallpapers.get_mut(end_date)
+                && wallpaper.watermark_free != Some(watermark_free)
+            {
+                wallpaper.watermark_free = Some(watermark_free);
+                changed = true;
+            }
+        }
+        if changed {
+            self.last_updated = Utc::now();
+        }
+        changed
+    }
+
+    /// 记录指定日期横屏图片的重新压缩结果（None 表示磁盘上是原图）
+    ///
+    /// 图片文件在所有 mkt 间共享，因此更新所有 mkt 下的同日期条目。返回是否有条目发生变化。
+    pub fn set_recompression(
+        &mut self,
+        end_date: &str,
+        recompression: Option<Recompression>,
+    ) -> bool {
+        let mut changed = false;
+        for mkt_wallpapers in self.mkt.values_mut() {
+            if let Some(wallpaper) = mkt_wallpapers.get_mut(end_date)
+                && wallpaper.recompression != recompression
+            {
+                wallpaper.recompression = recompression;
+                changed = true;
+            }
+        }
+        if changed {
+            self.last_updated = Utc::now();
+        }
+        changed
+    }
+
+    /// 记录指定 mkt 某日竖屏版本是否可用
+    ///
+    /// 竖屏图片地址由各 mkt 的 urlbase 决定，可用性按 mkt 分别记录。返回是否有条目发生变化。
+    pub fn set_portrait_available(&mut self, mkt: &str, end_date: &str, available: bool) -> bool {
+        let Some(wallpaper) = self
+            .mkt
+            .get_mut(mkt)
+            .and_then(|wallpapers| wallpapers.get_mut(end_date))
+        else {
+            return false;
+        };
+        if wallpaper.portrait_available == Some(available) {
+            return false;
+        }
+        wallpaper.portrait_available = Some(available);
+        self.last_updated = Utc::now();
+        true
+    }
+
+    /// 为壁纸添加标签（`tag` 需已归一化），返回是否发生了变化
+    pub fn add_tag(&mut self, end_date: &str, tag: &str) -> bool {
+        let tags = self.tags.entry(end_date.to_string()).or_default();
+        let Err(position) = tags.binary_search_by(|t| t.as_str().cmp(tag)) else {
+            return false;
+        };
+        tags.insert(position, tag.to_string());
+        self.tags.sort_keys();
+        self.last_updated = Utc::now();
+        true
+    }
+
+    /// 移除壁纸的标签，返回是否发生了变化
+    pub fn remove_tag(&mut self, end_date: &str, tag: &str) -> bool {
+        let Some(tags) = self.tags.get_mut(end_date) else {
+            return false;
+        };
+        let Ok(position) = tags.binary_search_by(|t| t.as_str().cmp(tag)) else {
+            return false;
+        };
+        tags.remove(position);
+        if tags.is_empty() {
+            self.tags.shift_remove(end_date);
+        }
+        self.last_updated = Utc::now();
+        true
+    }
+
+    /// 壁纸的标签（未添加时为空）
+    pub fn tags_for(&self, end_date: &str) -> Vec<String> {
+        self.tags.get(end_date).cloned().unwrap_or_default()
+    }
+
+    /// 带有指定标签的壁纸键（end_date），按日期降序
+    pub fn keys_with_tag(&self, tag: &str) -> Vec<String> {
+        let mut keys: Vec<String> = self
+            .tags
+            .iter()
+            .filter(|(_, tags)| tags.iter().any(|t| t == tag))
+            .map(|(key, _)| key.clone())
+            .collect();
+        keys.sort_by(|a, b| b.cmp(a));
+        keys
+    }
+
+    /// 所有标签及使用次数，按标签名排序
+    pub fn tag_counts(&self) -> Vec<(String, usize)> {
+        let mut counts = std::collections::BTreeMap::new();
+        for tag in self.tags.values().flatten() {
+            *counts.entry(tag.clone()).or_insert(0) += 1;
+        }
+        counts.into_iter().collect()
     }
 
     /// 对所有 mkt 和日期进行排序，确保 JSON 序列化时保持顺序
@@ -107,6 +348,83 @@ impl WallpaperIndex {
         self.mkt.sort_keys();
     }
 
+    /// 合并旧版本语言键下的重复条目，返回删除的副本数
+    ///
+    /// v4 及更早版本按界面语言（"zh-CN"/"en-US"）而不是实际请求的市场分组，迁移到 v5 后语言键被当作
+    /// mkt，与之后按实际市场写入的数据重复，存档中同一张壁纸出现两次。Bing 为不同市场的同一张图片
+    /// 生成带各自市场后缀的 urlbase，因此同一天 urlbase 和标题都相同、且 urlbase 后缀与某个键一致时，
+    /// 可以确定其他键下的副本来自该市场的响应。只删除存放在语言键下、后缀与该键不一致的副本，
+    /// 其中有而保留条目缺失的元数据（分辨率、竖屏探测结果等）合并到保留的条目。
+    ///
+    /// 不同的真实市场可能拿到同一张 `_ROW` 等通用图片，这些条目都不属于语言键，全部保留；
+    /// 无法确定来源市场（没有键与后缀一致）的分组同样保持不变。
+    pub fn reconcile_duplicate_mkts(&mut self) -> usize {
+        use std::collections::BTreeMap;
+
+        let mut groups: BTreeMap<(String, String, String), Vec<String>> = BTreeMap::new();
+        for (mkt, wallpapers) in self.mkt.iter().filter(|(mkt, _)| *mkt != SPOTLIGHT_MKT) {
+            for wallpaper in wallpapers.values().filter(|w| !w.urlbase.is_empty()) {
+                groups
+                    .entry((
+                        wallpaper.end_date.clone(),
+                        wallpaper.urlbase.clone(),
+                        wallpaper.title.clone(),
+                    ))
+                    .or_default()
+                    .push(mkt.clone());
+            }
+        }
+
+        let mut removed = 0;
+        for ((end_date, urlbase, _), mkts) in groups {
+            if mkts.len() < 2 {
+                continue;
+            }
+            let urlbase_upper = urlbase.to_ascii_uppercase();
+            let matches_suffix =
+                |mkt: &str| urlbase_upper.contains(&format!("_{}", mkt.to_ascii_uppercase()));
+            let Some(source) = mkts.iter().find(|mkt| matches_suffix(mkt)) else {
+                continue;
+            };
+            let duplicates: Vec<LocalWallpaper> = mkts
+                .iter()
+                .filter(|mkt| LEGACY_LANGUAGE_KEYS.contains(&mkt.as_str()) && !matches_suffix(mkt))
+                .filter_map(|mkt| self.mkt.get_mut(mkt)?.shift_remove(&end_date))
+                .collect();
+            removed += duplicates.len();
+            if let Some(kept) = self
+                .mkt
+                .get_mut(source)
+                .and_then(|wallpapers| wallpapers.get_mut(&end_date))
+            {
+                for duplicate in duplicates {
+                    fill_missing_metadata(kept, duplicate);
+                }
+            }
+        }
+
+        if removed > 0 {
+            self.mkt.retain(|_, wallpapers| !wallpapers.is_empty());
+            self.last_updated = Utc::now();
+        }
+        removed
+    }
+
+    /// 尚未合并过重复条目时执行一次 [`reconcile_duplicate_mkts`](Self::reconcile_duplicate_mkts)
+    ///
+    /// 返回索引是否发生变化（包括记录已合并的标记），调用方据此回写。
+    pub fn reconcile_once(&mut self) -> bool {
+        if self.mkt_reconciled {
+            return false;
+        }
+        let removed = self.reconcile_duplicate_mkts();
+        if removed > 0 {
+            log::info!("已合并旧版本按语言分组留下的 {} 条重复壁纸", removed);
+        }
+        self.mkt_reconciled = true;
+        true
+    }
+
     /// 获取所有语言的壁纸（用于清理操作）
     /// 返回所有语言中唯一的 end_date 对应的壁纸列表
     /// 如果有多个语言存在相同 end_date，优先选择字典序靠前的语言
@@ -119,7 +437,10 @@ impl WallpaperIndex {
         let lang_order: BTreeMap<_, _> = self.mkt.iter().collect();
 
         // 按语言代码顺序遍历，优先选择字典序靠前的语言
-        for (_, lang_wallpapers) in lang_order {
+        for (_, lang_wallpapers) in lang_order
+            .into_iter()
+            .filter(|(mkt, _)| mkt.as_str() != SPOTLIGHT_MKT)
+        {
             for wallpaper in lang_wallpapers.values() {
                 if seen.insert(wallpaper.end_date.clone()) {
                     result.push(wallpaper.clone());
@@ -139,13 +460,15 @@ impl WallpaperIndex {
     ///
     /// # Arguments
     /// * `max_count` - 最大索引数量
-    pub fn limit_index_size(&mut self, max_count: usize) {
+    ///
+    /// 返回是否删除了条目。
+    pub fn limit_index_size(&mut self, max_count: usize) -> bool {
         // 获取所有唯一的 end_date，按降序排序（最新的在前）
         let all_unique = self.get_all_wallpapers_unique();
 
         // 如果总数不超过限制，不需要清理
         if all_unique.len() <= max_count {
-            return;
+            return false;
         }
 
         // 需要删除的 end_date 列表（最旧的）
@@ -162,18 +485,97 @@ impl WallpaperIndex {
             to_remove.len()
         );
 
-        // 从所有语言中删除这些 end_date
-        for lang_wallpapers in self.mkt.values_mut() {
-            for end_date in &to_remove {
-                lang_wallpapers.shift_remove(end_date);
+        self.remove_entries(&to_remove)
+    }
+
+    /// 从所有 mkt 中删除指定键的条目及其标签，并移除空的 mkt 分组
+    ///
+    /// 返回是否有条目被删除。
+    pub fn remove_entries(&mut self, keys: &[String]) -> bool {
+        let mut changed = false;
+        for wallpapers in self.mkt.values_mut() {
+            for key in keys {
+                changed |= wallpapers.shift_remove(key).is_some();
             }
         }
+        self.mkt.retain(|_, wallpapers| !wallpapers.is_empty());
+        for key in keys {
+            changed |= self.tags.shift_remove(key).is_some();
+        }
+        if changed {
+            self.last_updated = Utc::now();
+        }
+        changed
+    }
+}
 
-        // 移除空的语言分组
-        self.mkt
-            .retain(|_, lang_wallpapers| !lang_wallpapers.is_empty());
+/// 使用完整字段名的索引导出格式
+///
+/// 结构与 `WallpaperIndex` 一致，但壁纸字段不使用 `t`/`c`/`l`/`d`/`u`/`r` 短名，
+/// 供外部工具直接读取归档。
+#[derive(Debug, Clone, Serialize)]
+pub struct ExpandedWallpaperIndex {
+    pub version: u32,
+    pub last_updated: DateTime<Utc>,
+    /// 外层 key = mkt，内层 key = end_date
+    pub mkt: IndexMap<String, IndexMap<String, ExpandedWallpaper>>,
+    /// key = end_date，value = 用户添加的标签
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub tags: IndexMap<String, Vec<String>>,
+}
 
-        self.last_updated = Utc::now();
+/// `LocalWallpaper` 的完整字段名版本
+#[derive(Debug, Clone, Serialize)]
+pub struct ExpandedWallpaper {
+    pub title: String,
+    pub copyright: String,
+    pub copyright_link: String,
+    pub end_date: String,
+    pub urlbase: String,
+    pub resolution: Option<String>,
+    pub portrait_available: Option<bool>,
+    pub watermark_free: Option<bool>,
+    pub jpeg_quality: Option<u8>,
+    pub original_size: Option<u64>,
+    pub stored_size: Option<u64>,
+}
+
+impl From<&LocalWallpaper> for ExpandedWallpaper {
+    fn from(wallpaper: &LocalWallpaper) -> Self {
+        Self {
+            title: wallpaper.title.clone(),
+            copyright: wallpaper.copyright.clone(),
+            copyright_link: wallpaper.copyright_link.clone(),
+            end_date: wallpaper.end_date.clone(),
+            urlbase: wallpaper.urlbase.clone(),
+            resolution: wallpaper.resolution.clone(),
+            portrait_available: wallpaper.portrait_available,
+            watermark_free: wallpaper.watermark_free,
+            jpeg_quality: wallpaper.recompression.map(|r| r.quality),
+            original_size: wallpaper.recompression.map(|r| r.original_size),
+            stored_size: wallpaper.recompression.map(|r| r.stored_size),
+        }
+    }
+}
+
+impl From<&WallpaperIndex> for ExpandedWallpaperIndex {
+    fn from(index: &WallpaperIndex) -> Self {
+        Self {
+            version: index.version,
+            last_updated: index.last_updated,
+            mkt: index
+                .mkt
+                .iter()
+                .map(|(mkt, wallpapers)| {
+                    let wallpapers = wallpapers
+                        .iter()
+                        .map(|(end_date, wallpaper)| (end_date.clone(), wallpaper.into()))
+                        .collect();
+                    (mkt.clone(), wallpapers)
+                })
+                .collect(),
+            tags: index.tags.clone(),
+        }
     }
 }
 
@@ -189,9 +591,83 @@ mod tests {
             copyright_link: "https://example.com".to_string(),
             end_date: end_date.to_string(),
             urlbase: format!("/th?id=OHR.{}", title),
+            resolution: None,
+            portrait_available: None,
+            watermark_free: None,
+            recompression: None,
         }
     }
 
+    #[test]
+    fn test_reconcile_duplicate_mkts_merges_language_key_copies() {
+        let mut index = WallpaperIndex::new();
+        let mut legacy = make_wallpaper("20250214", "Peak");
+        legacy.urlbase = "/th?id=OHR.Peak_EN-GB123".to_string();
+        legacy.resolution = Some("UHD".to_string());
+        legacy.watermark_free = Some(false);
+        let mut market = legacy.clone();
+        market.resolution = None;
+        market.portrait_available = Some(true);
+        // 同一天但 urlbase 不同：真正的 zh-CN 市场数据，不是重复
+        let mut native = make_wallpaper("20250213", "Lake");
+        native.urlbase = "/th?id=OHR.Lake_ZH-CN456".to_string();
+        let mut native_gb = native.clone();
+        native_gb.urlbase = "/th?id=OHR.Lake_EN-GB456".to_string();
+        index.upsert_wallpapers_for_mkt("zh-CN", vec![legacy, native]);
+        index.upsert_wallpapers_for_mkt("en-GB", vec![market, native_gb]);
+
+        assert_eq!(index.reconcile_duplicate_mkts(), 1);
+        assert_eq!(index.get_wallpapers_for_mkt("zh-CN").len(), 1);
+        let kept = &index.mkt["en-GB"]["20250214"];
+        assert_eq!(kept.resolution.as_deref(), Some("UHD"));
+        assert_eq!(kept.portrait_available, Some(true));
+        assert_eq!(kept.watermark_free, Some(false));
+        assert_eq!(index.reconcile_duplicate_mkts(), 0);
+    }
+
+    #[test]
+    fn test_reconcile_duplicate_mkts_removes_only_legacy_copy() {
+        let mut index = WallpaperIndex::new();
+        let mut wallpaper = make_wallpaper("20250214", "Peak");
+        wallpaper.urlbase = "/th?id=OHR.Peak_JA-JP123".to_string();
+        index.upsert_wallpapers_for_mkt("en-US", vec![wallpaper.clone()]);
+        index.upsert_wallpapers_for_mkt("ja-JP", vec![wallpaper.clone()]);
+        index.upsert_wallpapers_for_mkt("de-DE", vec![wallpaper]);
+
+        assert_eq!(index.reconcile_duplicate_mkts(), 1);
+        assert!(!index.mkt.contains_key("en-US"));
+        assert!(index.mkt["ja-JP"].contains_key("20250214"));
+        assert!(index.mkt["de-DE"].contains_key("20250214"));
+    }
+
+    #[test]
+    fn test_reconcile_duplicate_mkts_keeps_shared_images_of_real_markets() {
+        let mut index = WallpaperIndex::new();
+        let mut wallpaper = make_wallpaper("20250214", "Peak");
+        wallpaper.urlbase = "/th?id=OHR.Peak_ROW123".to_string();
+        index.upsert_wallpapers_for_mkt("de-DE", vec![wallpaper.clone()]);
+        index.upsert_wallpapers_for_mkt("fr-FR", vec![wallpaper.clone()]);
+        index.upsert_wallpapers_for_mkt("en-US", vec![wallpaper]);
+
+        assert_eq!(index.reconcile_duplicate_mkts(), 0);
+        for mkt in ["de-DE", "fr-FR", "en-US"] {
+            assert!(index.mkt[mkt].contains_key("20250214"), "{mkt}");
+        }
+    }
+
+    #[test]
+    fn test_reconcile_once_runs_only_for_old_files() {
+        let mut index = WallpaperIndex::new();
+        assert!(!index.reconcile_once());
+
+        let json = r#"{"version":5,"last_updated":"2025-02-14T00:00:00Z","mkt":{}}"#;
+        let mut index: WallpaperIndex = serde_json::from_str(json).unwrap();
+        assert!(!index.mkt_reconciled);
+        assert!(index.reconcile_once());
+        assert!(index.mkt_reconciled);
+        assert!(!index.reconcile_once());
+    }
+
     #[test]
     fn test_wallpaper_index_new() {
         let index = WallpaperIndex::new();
@@ -241,6 +717,36 @@ mod tests {
         assert_eq!(wallpapers[2].end_date, "20240101");
     }
 
+    #[test]
+    fn test_get_wallpapers_page_for_mkt() {
+        let mut index = WallpaperIndex::new();
+        index.upsert_wallpapers_for_mkt(
+            "zh-CN",
+            vec![
+                make_wallpaper("20240101", "Old"),
+                make_wallpaper("20240103", "New"),
+                make_wallpaper("20240102", "Mid"),
+            ],
+        );
+
+        let (page, total) = index.get_wallpapers_page_for_mkt("zh-CN", 1, Some(1));
+        assert_eq!(total, 3);
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].end_date, "20240102");
+
+        let (rest, _) = index.get_wallpapers_page_for_mkt("zh-CN", 1, None);
+        let dates: Vec<_> = rest.iter().map(|w| w.end_date.as_str()).collect();
+        assert_eq!(dates, ["20240102", "20240101"]);
+
+        assert!(
+            index
+                .get_wallpapers_page_for_mkt("zh-CN", 5, Some(2))
+                .0
+                .is_empty()
+        );
+        assert_eq!(index.get_wallpapers_page_for_mkt("en-US", 0, Some(2)).1, 0);
+    }
+
     #[test]
     fn test_upsert_wallpapers_for_mkt_empty_vec() {
         let mut index = WallpaperIndex::new();
@@ -379,6 +885,30 @@ mod tests {
         assert!(unique.is_empty());
     }
 
+    #[test]
+    fn test_upsert_tracked_reports_unchanged_entries() {
+        let mut index = WallpaperIndex::new();
+        let wallpaper = make_wallpaper("20240101", "Day1");
+        assert_eq!(
+            index.upsert_wallpapers_tracked("zh-CN", vec![wallpaper.clone()]),
+            (1, true)
+        );
+        let last_updated = index.last_updated;
+
+        // 同样的元数据再次写入：不算变化，也不更新时间戳
+        assert_eq!(
+            index.upsert_wallpapers_tracked("zh-CN", vec![wallpaper.clone()]),
+            (0, false)
+        );
+        assert_eq!(index.last_updated, last_updated);
+
+        let renamed = make_wallpaper("20240101", "Day1 renamed");
+        assert_eq!(
+            index.upsert_wallpapers_tracked("zh-CN", vec![renamed]),
+            (0, true)
+        );
+    }
+
     #[test]
     fn test_limit_index_size_no_op_when_under_limit() {
         let mut index = WallpaperIndex::new();
@@ -390,7 +920,7 @@ mod tests {
             ],
         );
 
-        index.limit_index_size(10);
+        assert!(!index.limit_index_size(10));
 
         // 不超过限制，应保持不变
         let wallpapers = index.get_wallpapers_for_mkt("zh-CN");
@@ -493,6 +1023,88 @@ mod tests {
         assert!(index.mkt.is_empty());
     }
 
+    #[test]
+    fn test_limit_index_size_drops_tags_of_removed_dates() {
+        let mut index = WallpaperIndex::new();
+        index.upsert_wallpapers_for_mkt(
+            "zh-CN",
+            vec![
+                make_wallpaper("20240101", "Day1"),
+                make_wallpaper("20240102", "Day2"),
+            ],
+        );
+        index.add_tag("20240101", "nature");
+        index.add_tag("20240102", "city");
+
+        index.limit_index_size(1);
+
+        assert!(index.tags_for("20240101").is_empty());
+        assert_eq!(index.tags_for("20240102"), ["city"]);
+    }
+
+    #[test]
+    fn test_remove_entries_across_mkts() {
+        let mut index = WallpaperIndex::new();
+        index.upsert_wallpapers_for_mkt("zh-CN", vec![make_wallpaper("20240101", "Day1")]);
+        index.upsert_wallpapers_for_mkt(
+            "en-US",
+            vec![
+                make_wallpaper("20240101", "Day1"),
+                make_wallpaper("20240102", "Day2"),
+            ],
+        );
+
+        assert!(index.remove_entries(&["20240101".to_string()]));
+        assert!(!index.mkt.contains_key("zh-CN"));
+        assert_eq!(index.get_wallpapers_for_mkt("en-US").len(), 1);
+        assert!(!index.remove_entries(&["20240101".to_string()]));
+    }
+
+    #[test]
+    fn test_normalize_tag() {
+        assert_eq!(normalize_tag("  Nature "), Some("nature".to_string()));
+        assert_eq!(
+            normalize_tag("Snowy   Mountains"),
+            Some("snowy mountains".to_string())
+        );
+        assert_eq!(normalize_tag("动物"), Some("动物".to_string()));
+        assert_eq!(normalize_tag("   "), None);
+        assert_eq!(normalize_tag(&"a".repeat(MAX_TAG_CHARS + 1)), None);
+    }
+
+    #[test]
+    fn test_add_and_remove_tags() {
+        let mut index = WallpaperIndex::new();
+        assert!(index.add_tag("20240102", "nature"));
+        assert!(index.add_tag("20240102", "animals"));
+        assert!(!index.add_tag("20240102", "nature"));
+        assert!(index.add_tag("20240101", "nature"));
+
+        assert_eq!(index.tags_for("20240102"), ["animals", "nature"]);
+        assert_eq!(index.keys_with_tag("nature"), ["20240102", "20240101"]);
+        assert_eq!(
+            index.tag_counts(),
+            [("animals".to_string(), 1), ("nature".to_string(), 2)]
+        );
+
+        assert!(index.remove_tag("20240101", "nature"));
+        assert!(!index.remove_tag("20240101", "nature"));
+        assert!(!index.tags.contains_key("20240101"));
+        assert_eq!(index.keys_with_tag("nature"), ["20240102"]);
+    }
+
+    #[test]
+    fn test_tags_are_optional_in_serialized_index() {
+        let mut index = WallpaperIndex::new();
+        let json = serde_json::to_value(&index).unwrap();
+        assert!(json.get("tags").is_none());
+
+        index.add_tag("20240102", "city");
+        let json = serde_json::to_string(&index).unwrap();
+        let deserialized: WallpaperIndex = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.tags_for("20240102"), ["city"]);
+    }
+
     #[test]
     fn test_wallpaper_index_serialization_roundtrip() {
         let mut index = WallpaperIndex::new();
@@ -507,4 +1119,157 @@ mod tests {
         assert_eq!(wallpapers.len(), 1);
         assert_eq!(wallpapers[0].title, "Test");
     }
+
+    #[test]
+    fn test_set_resolution_updates_all_mkts() {
+        let mut index = WallpaperIndex::new();
+        index.upsert_wallpapers_for_mkt("zh-CN", vec![make_wallpaper("20240102", "A")]);
+        index.upsert_wallpapers_for_mkt("en-US", vec![make_wallpaper("20240102", "B")]);
+
+        assert!(index.set_resolution("20240102", "1920x1200"));
+        for mkt in ["zh-CN", "en-US"] {
+            let wallpapers = index.get_wallpapers_for_mkt(mkt);
+            assert_eq!(wallpapers[0].resolution.as_deref(), Some("1920x1200"));
+        }
+
+        // 重复设置相同分辨率不视为变化
+        assert!(!index.set_resolution("20240102", "1920x1200"));
+        // 不存在的日期不做任何修改
+        assert!(!index.set_resolution("20990101", "UHD"));
+    }
+
+    #[test]
+    fn test_upsert_preserves_recorded_resolution() {
+        let mut index = WallpaperIndex::new();
+        index.upsert_wallpapers_for_mkt("zh-CN", vec![make_wallpaper("20240102", "A")]);
+        index.set_resolution("20240102", "1920x1080");
+
+        // 重新从 API 获取的元数据不带分辨率，不应覆盖已记录的值
+        index.upsert_wallpapers_for_mkt("zh-CN", vec![make_wallpaper("20240102", "A")]);
+        let wallpapers = index.get_wallpapers_for_mkt("zh-CN");
+        assert_eq!(wallpapers[0].resolution.as_deref(), Some("1920x1080"));
+    }
+
+    #[test]
+    fn test_unique_wallpapers_skip_spotlight() {
+        let mut index = WallpaperIndex::new();
+        index.upsert_wallpapers_for_mkt("zh-CN", vec![make_wallpaper("20240102", "A")]);
+        index.upsert_wallpapers_for_mkt(
+            SPOTLIGHT_MKT,
+            vec![make_wallpaper("9f3a5c0d1e2b4a67", "S")],
+        );
+
+        let unique = index.get_all_wallpapers_unique();
+        assert_eq!(unique.len(), 1);
+        assert_eq!(unique[0].end_date, "20240102");
+
+        // 清理旧条目时不会删除聚焦图片
+        index.limit_index_size(0);
+        assert!(index.get_wallpapers_for_mkt("zh-CN").is_empty());
+        assert_eq!(index.get_wallpapers_for_mkt(SPOTLIGHT_MKT).len(), 1);
+    }
+
+    #[test]
+    fn test_set_recompression_applies_to_all_mkts_and_survives_upsert() {
+        let mut index = WallpaperIndex::new();
+        index.upsert_wallpapers_for_mkt("zh-CN", vec![make_wallpaper("20240102", "A")]);
+        index.upsert_wallpapers_for_mkt("en-US", vec![make_wallpaper("20240102", "B")]);
+
+        let recompression = Recompression {
+            quality: 85,
+            original_size: 4_000_000,
+            stored_size: 1_500_000,
+        };
+        assert!(index.set_recompression("20240102", Some(recompression)));
+        assert!(!index.set_recompression("20240102", Some(recompression)));
+        index.upsert_wallpapers_for_mkt("zh-CN", vec![make_wallpaper("20240102", "A")]);
+        for mkt in ["zh-CN", "en-US"] {
+            assert_eq!(
+                index.get_wallpapers_for_mkt(mkt)[0].recompression,
+                Some(recompression)
+            );
+        }
+
+        // 重新下载原图后清除记录
+        assert!(index.set_recompression("20240102", None));
+        assert_eq!(index.get_wallpapers_for_mkt("en-US")[0].recompression, None);
+    }
+
+    #[test]
+    fn test_set_watermark_free_applies_to_all_mkts_and_survives_upsert() {
+        let mut index = WallpaperIndex::new();
+        index.upsert_wallpapers_for_mkt("zh-CN", vec![make_wallpaper("20240102", "A")]);
+        index.upsert_wallpapers_for_mkt("en-US", vec![make_wallpaper("20240102", "B")]);
+
+        assert!(index.set_watermark_free("20240102", true));
+        assert!(!index.set_watermark_free("20240102", true));
+        for mkt in ["zh-CN", "en-US"] {
+            assert_eq!(
+                index.get_wallpapers_for_mkt(mkt)[0].watermark_free,
+                Some(true)
+            );
+        }
+
+        index.upsert_wallpapers_for_mkt("zh-CN", vec![make_wallpaper("20240102", "A")]);
+        assert_eq!(
+            index.get_wallpapers_for_mkt("zh-CN")[0].watermark_free,
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn test_set_portrait_available_is_per_mkt() {
+        let mut index = WallpaperIndex::new();
+        index.upsert_wallpapers_for_mkt("zh-CN", vec![make_wallpaper("20240102", "A")]);
+        index.upsert_wallpapers_for_mkt("en-US", vec![make_wallpaper("20240102", "B")]);
+
+        assert!(index.set_portrait_available("zh-CN", "20240102", false));
+        assert_eq!(
+            index.get_wallpapers_for_mkt("zh-CN")[0].portrait_available,
+            Some(false)
+        );
+        assert_eq!(
+            index.get_wallpapers_for_mkt("en-US")[0].portrait_available,
+            None
+        );
+
+        assert!(!index.set_portrait_available("zh-CN", "20240102", false));
+        assert!(!index.set_portrait_available("ja-JP", "20240102", true));
+    }
+
+    #[test]
+    fn test_upsert_preserves_portrait_availability_for_same_urlbase() {
+        let mut index = WallpaperIndex::new();
+        index.upsert_wallpapers_for_mkt("zh-CN", vec![make_wallpaper("20240102", "A")]);
+        index.set_portrait_available("zh-CN", "20240102", true);
+
+        index.upsert_wallpapers_for_mkt("zh-CN", vec![make_wallpaper("20240102", "A")]);
+        assert_eq!(
+            index.get_wallpapers_for_mkt("zh-CN")[0].portrait_available,
+            Some(true)
+        );
+
+        let mut changed = make_wallpaper("20240102", "A");
+        changed.urlbase = "/th?id=OHR.Other_ZH-CN1".to_string();
+        index.upsert_wallpapers_for_mkt("zh-CN", vec![changed]);
+        assert_eq!(
+            index.get_wallpapers_for_mkt("zh-CN")[0].portrait_available,
+            None
+        );
+    }
+
+    #[test]
+    fn test_expanded_index_uses_long_field_names() {
+        let mut index = WallpaperIndex::new();
+        index.upsert_wallpapers_for_mkt("zh-CN", vec![make_wallpaper("20240101", "Test")]);
+
+        let expanded = ExpandedWallpaperIndex::from(&index);
+        let json = serde_json::to_value(&expanded).unwrap();
+        let entry = &json["mkt"]["zh-CN"]["20240101"];
+        assert_eq!(json["version"], WallpaperIndex::VERSION);
+        assert_eq!(entry["end_date"], "20240101");
+        assert!(entry.get("title").is_some());
+        assert!(entry.get("copyright_link").is_some());
+        assert!(entry.get("t").is_none());
+    }
 }
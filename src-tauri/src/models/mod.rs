@@ -1,11 +1,21 @@
+mod backup;
 mod bing;
+mod download;
 mod index;
+mod policy;
+mod profile;
 mod runtime;
 mod settings;
+mod update;
 mod wallpaper;
 
+pub use backup::*;
 pub use bing::*;
+pub use download::*;
 pub use index::*;
+pub use policy::*;
+pub use profile::*;
 pub use runtime::*;
 pub use settings::*;
+pub use update::*;
 pub use wallpaper::*;
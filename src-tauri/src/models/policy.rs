@@ -0,0 +1,138 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::AppSettings;
+
+/// 管理员下发的设置策略（企业部署）
+///
+/// 键与 `AppSettings` 的字段名一致。合并优先级从高到低：
+/// `locked` > 用户已保存的设置 > `defaults` > 内置默认值。
+///
+/// ```json
+/// {
+///   "defaults": { "mkt": "en-US" },
+///   "locked": { "save_directory": "D:\\Wallpapers", "auto_update": false }
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct SettingsPolicy {
+    /// 用户尚未设置时使用的默认值，用户之后可以修改
+    #[serde(default)]
+    pub defaults: Map<String, Value>,
+    /// 强制值，用户无法修改
+    #[serde(default)]
+    pub locked: Map<String, Value>,
+}
+
+/// 前端展示用的策略状态
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SettingsPolicyStatus {
+    /// 已加载的策略文件路径，None 表示没有策略
+    pub path: Option<String>,
+    /// 被锁定（只读）的设置字段
+    pub locked: Vec<String>,
+}
+
+fn overlay(target: &mut Map<String, Value>, layer: &Map<String, Value>) {
+    for (key, value) in layer {
+        target.insert(key.clone(), value.clone());
+    }
+}
+
+impl SettingsPolicy {
+    /// 策略中不属于 `AppSettings` 的键（拼写错误或不支持的设置）
+    pub fn unknown_keys(&self) -> Vec<&str> {
+        let Ok(Value::Object(known)) = serde_json::to_value(AppSettings::default()) else {
+            return Vec::new();
+        };
+        self.defaults
+            .keys()
+            .chain(self.locked.keys())
+            .filter(|key| !known.contains_key(key.as_str()))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// 按优先级合并出最终设置，`stored` 为用户已保存的设置（不存在时为 None）
+    pub fn merge(&self, stored: Option<&Value>) -> Result<AppSettings, serde_json::Error> {
+        let mut merged = match serde_json::to_value(AppSettings::default())? {
+            Value::Object(builtin) => builtin,
+            _ => Map::new(),
+        };
+        overlay(&mut merged, &self.defaults);
+        if let Some(Value::Object(stored)) = stored {
+            overlay(&mut merged, stored);
+        }
+        overlay(&mut merged, &self.locked);
+        serde_json::from_value(Value::Object(merged))
+    }
+
+    /// 把锁定的值覆盖到设置上（保存设置前调用，用户无法绕过锁定）
+    pub fn enforce(&self, settings: &AppSettings) -> Result<AppSettings, serde_json::Error> {
+        if self.locked.is_empty() {
+            return Ok(settings.clone());
+        }
+        self.merge(Some(&serde_json::to_value(settings)?))
+    }
+
+    pub fn locked_keys(&self) -> Vec<String> {
+        self.locked.keys().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn policy(value: Value) -> SettingsPolicy {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_merge_precedence() {
+        let policy = policy(json!({
+            "defaults": { "mkt": "en-US", "theme": "dark" },
+            "locked": { "auto_update": false, "save_directory": "/srv/wallpapers" }
+        }));
+
+        // 首次启动：没有用户设置，默认值和锁定值都生效
+        let first_run = policy.merge(None).unwrap();
+        assert_eq!(first_run.mkt, "en-US");
+        assert_eq!(first_run.theme, "dark");
+        assert!(!first_run.auto_update);
+        assert_eq!(first_run.save_directory.as_deref(), Some("/srv/wallpapers"));
+
+        // 用户设置优先于默认值，但不能覆盖锁定值
+        let stored = json!({ "mkt": "ja-JP", "auto_update": true, "save_directory": null });
+        let merged = policy.merge(Some(&stored)).unwrap();
+        assert_eq!(merged.mkt, "ja-JP");
+        assert_eq!(merged.theme, "dark");
+        assert!(!merged.auto_update);
+        assert_eq!(merged.save_directory.as_deref(), Some("/srv/wallpapers"));
+    }
+
+    #[test]
+    fn test_enforce_only_touches_locked_fields() {
+        let policy =
+            policy(json!({ "defaults": { "mkt": "en-US" }, "locked": { "idle_prefetch": false } }));
+        let settings = AppSettings {
+            mkt: "zh-CN".to_string(),
+            idle_prefetch: true,
+            ..Default::default()
+        };
+
+        let enforced = policy.enforce(&settings).unwrap();
+        assert_eq!(enforced.mkt, "zh-CN");
+        assert!(!enforced.idle_prefetch);
+        assert_eq!(policy.locked_keys(), ["idle_prefetch"]);
+    }
+
+    #[test]
+    fn test_unknown_keys_and_invalid_values() {
+        let policy =
+            policy(json!({ "locked": { "proxy": "http://proxy:8080", "auto_update": "no" } }));
+        assert_eq!(policy.unknown_keys(), ["proxy"]);
+        assert!(policy.merge(None).is_err());
+    }
+}
@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+
+use super::AppSettings;
+
+/// 方案名称最大长度（托盘菜单中显示）
+const MAX_NAME_CHARS: usize = 32;
+
+/// 壁纸配置方案（如"工作"/"家里"）
+///
+/// 每个方案有独立的壁纸目录、市场和下载分辨率，切换方案时覆盖到当前设置上。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WallpaperProfile {
+    pub name: String,
+    /// 壁纸保存目录，None 表示默认目录
+    #[serde(default)]
+    pub save_directory: Option<String>,
+    /// Bing 市场代码，空字符串表示跟随语言
+    #[serde(default)]
+    pub mkt: String,
+    /// 横屏下载分辨率，取值同 `AppSettings::download_resolution`
+    #[serde(default = "default_download_resolution")]
+    pub download_resolution: String,
+}
+
+fn default_download_resolution() -> String {
+    "auto".to_string()
+}
+
+impl WallpaperProfile {
+    /// 以当前设置中的目录、市场和分辨率创建方案
+    pub fn from_settings(name: &str, settings: &AppSettings) -> Self {
+        Self {
+            name: name.trim().to_string(),
+            save_directory: settings.save_directory.clone(),
+            mkt: settings.mkt.clone(),
+            download_resolution: settings.download_resolution.clone(),
+        }
+    }
+
+    /// 把方案覆盖到设置上，返回新的设置
+    pub fn apply_to(&self, settings: &AppSettings) -> AppSettings {
+        AppSettings {
+            save_directory: self.save_directory.clone(),
+            mkt: self.mkt.clone(),
+            download_resolution: self.download_resolution.clone(),
+            ..settings.clone()
+        }
+    }
+
+    /// 校验方案名称（目录和市场随设置一起由 `AppSettings::validate` 校验）
+    pub fn is_valid_name(name: &str) -> bool {
+        let trimmed = name.trim();
+        !trimmed.is_empty()
+            && trimmed == name
+            && name.chars().count() <= MAX_NAME_CHARS
+            && !name.chars().any(char::is_control)
+    }
+}
+
+/// 全部配置方案（存储在独立的 `profiles.json` 中）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfilesConfig {
+    #[serde(default)]
+    pub profiles: Vec<WallpaperProfile>,
+    /// 当前使用的方案名称，None 表示未使用方案（直接编辑设置）
+    #[serde(default)]
+    pub active: Option<String>,
+}
+
+impl ProfilesConfig {
+    pub fn find(&self, name: &str) -> Option<&WallpaperProfile> {
+        self.profiles.iter().find(|profile| profile.name == name)
+    }
+
+    /// 按名称新增或覆盖方案（保持原有顺序）
+    pub fn upsert(&mut self, profile: WallpaperProfile) {
+        match self.profiles.iter_mut().find(|p| p.name == profile.name) {
+            Some(existing) => *existing = profile,
+            None => self.profiles.push(profile),
+        }
+    }
+
+    /// 删除方案，删除的是当前方案时同时清除 `active`。返回是否存在该方案。
+    pub fn remove(&mut self, name: &str) -> bool {
+        let before = self.profiles.len();
+        self.profiles.retain(|profile| profile.name != name);
+        if self.active.as_deref() == Some(name) {
+            self.active = None;
+        }
+        self.profiles.len() != before
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(name: &str, mkt: &str) -> WallpaperProfile {
+        WallpaperProfile {
+            name: name.to_string(),
+            save_directory: None,
+            mkt: mkt.to_string(),
+            download_resolution: "auto".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_apply_to_overrides_only_profile_fields() {
+        let settings = AppSettings {
+            auto_update: false,
+            mkt: "zh-CN".to_string(),
+            ..Default::default()
+        };
+        let mut work = profile("work", "en-US");
+        work.save_directory = Some("/data/work".to_string());
+        work.download_resolution = "1920x1080".to_string();
+
+        let applied = work.apply_to(&settings);
+        assert_eq!(applied.save_directory.as_deref(), Some("/data/work"));
+        assert_eq!(applied.mkt, "en-US");
+        assert_eq!(applied.download_resolution, "1920x1080");
+        assert!(!applied.auto_update);
+        assert_eq!(WallpaperProfile::from_settings(" work ", &applied), work);
+    }
+
+    #[test]
+    fn test_profile_name_validation() {
+        assert!(WallpaperProfile::is_valid_name("家里"));
+        assert!(!WallpaperProfile::is_valid_name("  "));
+        assert!(!WallpaperProfile::is_valid_name(" home"));
+        assert!(!WallpaperProfile::is_valid_name("a\nb"));
+        assert!(!WallpaperProfile::is_valid_name(&"x".repeat(33)));
+    }
+
+    #[test]
+    fn test_upsert_and_remove_keep_active_consistent() {
+        let mut config = ProfilesConfig::default();
+        config.upsert(profile("work", "en-US"));
+        config.upsert(profile("home", "zh-CN"));
+        config.upsert(profile("work", "ja-JP"));
+        assert_eq!(config.profiles.len(), 2);
+        assert_eq!(config.profiles[0].mkt, "ja-JP");
+
+        config.active = Some("work".to_string());
+        assert!(config.remove("work"));
+        assert_eq!(config.active, None);
+        assert!(!config.remove("work"));
+        assert!(config.find("home").is_some());
+    }
+}
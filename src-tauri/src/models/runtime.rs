@@ -1,4 +1,6 @@
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 
 /// Market 状态统一结构
 ///
@@ -12,6 +14,167 @@ pub struct MarketStatus {
     pub effective_mkt: String,
     /// 是否存在 mismatch
     pub is_mismatch: bool,
+    /// 请求的 mkt 近期持续请求失败（见 `MarketHealth::is_degraded`）
+    #[serde(default)]
+    pub is_degraded: bool,
+}
+
+impl MarketStatus {
+    /// 由请求的 mkt 与实际生效的 mkt 构造状态（事件推送与查询命令共用）
+    pub fn new(requested_mkt: String, effective_mkt: String) -> Self {
+        Self {
+            is_mismatch: requested_mkt != effective_mkt,
+            requested_mkt,
+            effective_mkt,
+            is_degraded: false,
+        }
+    }
+
+    pub fn with_degraded(mut self, is_degraded: bool) -> Self {
+        self.is_degraded = is_degraded;
+        self
+    }
+}
+
+/// 连续失败达到该次数后视为市场降级
+pub const MARKET_DEGRADED_FAILURES: u32 = 5;
+/// 健康市场每轮更新的请求次数（含首次请求）
+pub const HEALTHY_RETRY_BUDGET: u32 = 3;
+/// 降级市场每轮更新只请求一次，避免每次循环都在退避重试上耗时
+pub const DEGRADED_RETRY_BUDGET: u32 = 1;
+
+/// 单个 mkt 的 Bing API 健康指标（按请求次数统计）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MarketHealth {
+    pub successes: u64,
+    pub failures: u64,
+    /// 连续失败次数，任一请求成功后清零
+    pub consecutive_failures: u32,
+    /// 成功请求耗时的滑动平均（毫秒，新样本权重 1/5）
+    pub avg_latency_ms: Option<u64>,
+    /// 最近一次成功时间（RFC 3339）
+    pub last_success_at: Option<String>,
+    /// 最近一次失败时间（RFC 3339）
+    pub last_failure_at: Option<String>,
+}
+
+impl MarketHealth {
+    pub fn record_success(&mut self, latency_ms: u64, at: String) {
+        self.successes += 1;
+        self.consecutive_failures = 0;
+        self.avg_latency_ms = Some(match self.avg_latency_ms {
+            Some(avg) => (avg * 4 + latency_ms) / 5,
+            None => latency_ms,
+        });
+        self.last_success_at = Some(at);
+    }
+
+    pub fn record_failure(&mut self, at: String) {
+        self.failures += 1;
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        self.last_failure_at = Some(at);
+    }
+
+    /// 成功率（0.0~1.0），尚无请求记录时为 None
+    pub fn success_rate(&self) -> Option<f64> {
+        let total = self.successes + self.failures;
+        (total > 0).then(|| self.successes as f64 / total as f64)
+    }
+
+    pub fn is_degraded(&self) -> bool {
+        self.consecutive_failures >= MARKET_DEGRADED_FAILURES
+    }
+
+    /// 本轮更新允许的请求次数：健康市场保持积极重试，降级市场只尝试一次
+    pub fn retry_budget(&self) -> u32 {
+        if self.is_degraded() {
+            DEGRADED_RETRY_BUDGET
+        } else {
+            HEALTHY_RETRY_BUDGET
+        }
+    }
+}
+
+/// 流量统计保留的天数，更早的记录在写入时清理
+pub const BANDWIDTH_RETENTION_DAYS: i64 = 400;
+
+/// 最多记录的待重试竖屏下载数量，超出时丢弃最早的日期
+pub const MAX_PENDING_PORTRAIT_DOWNLOADS: usize = 14;
+
+/// 单日图片下载流量（包括失败后重试的尝试，反映实际消耗的数据量）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BandwidthUsage {
+    pub bytes: u64,
+    /// 下载尝试次数
+    pub downloads: u32,
+    /// 下载累计耗时（毫秒）
+    pub duration_ms: u64,
+}
+
+impl BandwidthUsage {
+    fn add(&mut self, other: &BandwidthUsage) {
+        self.bytes += other.bytes;
+        self.downloads += other.downloads;
+        self.duration_ms += other.duration_ms;
+    }
+}
+
+/// 流量统计的时间范围（均包含今天）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BandwidthRange {
+    Today,
+    Week,
+    Month,
+    Year,
+    All,
+}
+
+impl BandwidthRange {
+    /// 范围内最早的日期，`All` 不限制
+    fn first_day(self, today: NaiveDate) -> Option<NaiveDate> {
+        let days = match self {
+            Self::Today => 0,
+            Self::Week => 6,
+            Self::Month => 29,
+            Self::Year => 364,
+            Self::All => return None,
+        };
+        today.checked_sub_days(chrono::Days::new(days))
+    }
+}
+
+/// 某一天的流量
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DailyBandwidth {
+    /// 日期（YYYY-MM-DD）
+    pub date: String,
+    #[serde(flatten)]
+    pub usage: BandwidthUsage,
+}
+
+/// `get_bandwidth_stats` 的返回值
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BandwidthStats {
+    pub total: BandwidthUsage,
+    /// 按日期升序，只包含有下载的日期
+    pub days: Vec<DailyBandwidth>,
+}
+
+/// 市场可用性探测结果
+///
+/// 由 `probe_market_availability` 命令返回，设置界面在用户切换 mkt 时
+/// 据此提前提示"所选市场在当前地区不可用"。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketProbeResult {
+    /// 探测的 mkt
+    pub requested_mkt: String,
+    /// Bing 实际返回的 mkt（None 表示无法从响应中检测）
+    pub served_mkt: Option<String>,
+    /// 发生重定向时实际响应的主机（如 "cn.bing.com"）
+    pub served_host: Option<String>,
+    /// 所选市场是否真正可用（未被重定向到其他市场）
+    pub is_available: bool,
 }
 
 /// 应用内部运行时状态（不展示给用户）
@@ -42,18 +205,210 @@ pub struct AppRuntimeState {
     /// 保留 serde(default) 以兼容已有持久化数据的反序列化。
     #[serde(default, skip_serializing)]
     pub _install_method_deprecated: Option<String>,
+    /// 最近一次非正常退出后的恢复报告（供诊断查看）
+    #[serde(default)]
+    pub last_recovery_report: Option<RecoveryReport>,
+    /// 各 mkt 最近一次成功应用到桌面的壁纸（key = mkt，value = end_date）
+    ///
+    /// 重启后用于恢复 `current_wallpaper_path`，避免自动应用重复设置同一张壁纸。
+    #[serde(default)]
+    pub applied_wallpapers: std::collections::HashMap<String, String>,
+    /// 首次启动时推算的市场建议（引导状态，仅在用户确认后才应用）
+    #[serde(default)]
+    pub mkt_suggestion: Option<MktSuggestion>,
+    /// 各 mkt 的 Bing API 健康指标（key = 请求的 mkt）
+    #[serde(default)]
+    pub market_health: std::collections::HashMap<String, MarketHealth>,
+    /// 迷你窗口上次所在位置（物理像素），未拖动过时为 None
+    #[serde(default)]
+    pub mini_window_position: Option<(i32, i32)>,
+    /// 每日图片下载流量（key = YYYY-MM-DD，本地日期）
+    #[serde(default)]
+    pub bandwidth_by_day: BTreeMap<String, BandwidthUsage>,
+    /// 按 IP 定位得到的坐标（日出日落计划未设置经纬度时使用）
+    #[serde(default)]
+    pub geo_location: Option<GeoLocation>,
+    /// 各 mkt 新壁纸发布时刻的观测（UTC 当日分钟数，按时间顺序，key = 保存元数据的 mkt）
+    #[serde(default)]
+    pub publish_observations: std::collections::HashMap<String, Vec<u16>>,
+    /// 用户在设置界面确认过的壁纸变化钩子程序（与设置 `apply_hook` 一致时才会运行）
+    #[serde(default)]
+    pub apply_hook_confirmed: Option<String>,
+    /// 竖屏版本下载失败、等待后续更新周期重试的壁纸日期
+    #[serde(default)]
+    pub pending_portrait_downloads: BTreeSet<String>,
+}
+
+impl AppRuntimeState {
+    /// 标记或清除待重试的竖屏下载，返回是否有变化
+    ///
+    /// 只保留最近的 [`MAX_PENDING_PORTRAIT_DOWNLOADS`] 个日期，避免长期失败时无限增长
+    pub fn set_portrait_pending(&mut self, end_date: &str, pending: bool) -> bool {
+        if !pending {
+            return self.pending_portrait_downloads.remove(end_date);
+        }
+        if !self.pending_portrait_downloads.insert(end_date.to_string()) {
+            return false;
+        }
+        while self.pending_portrait_downloads.len() > MAX_PENDING_PORTRAIT_DOWNLOADS {
+            self.pending_portrait_downloads.pop_first();
+        }
+        true
+    }
+
+    /// 累加一次下载尝试的流量，并清理超过保留期的记录
+    pub fn record_bandwidth(&mut self, day: NaiveDate, bytes: u64, duration_ms: u64) {
+        let usage = BandwidthUsage {
+            bytes,
+            downloads: 1,
+            duration_ms,
+        };
+        self.bandwidth_by_day
+            .entry(day.format("%Y-%m-%d").to_string())
+            .or_default()
+            .add(&usage);
+        if let Some(oldest) =
+            day.checked_sub_days(chrono::Days::new(BANDWIDTH_RETENTION_DAYS as u64))
+        {
+            let oldest = oldest.format("%Y-%m-%d").to_string();
+            self.bandwidth_by_day.retain(|date, _| *date >= oldest);
+        }
+    }
+
+    /// 统计范围内的流量
+    pub fn bandwidth_stats(&self, range: BandwidthRange, today: NaiveDate) -> BandwidthStats {
+        let first = range
+            .first_day(today)
+            .map(|day| day.format("%Y-%m-%d").to_string());
+        let last = today.format("%Y-%m-%d").to_string();
+        let mut total = BandwidthUsage::default();
+        let days = self
+            .bandwidth_by_day
+            .iter()
+            .filter(|(date, _)| first.as_ref().is_none_or(|first| *date >= first) && **date <= last)
+            .map(|(date, usage)| {
+                total.add(usage);
+                DailyBandwidth {
+                    date: date.clone(),
+                    usage: *usage,
+                }
+            })
+            .collect();
+        BandwidthStats { total, days }
+    }
+
+    /// 受保护的壁纸（任一 mkt 当前应用在桌面上的 end_date）
+    ///
+    /// 所有删除壁纸文件的路径都必须排除这些日期，否则系统重启后会找不到壁纸而显示黑屏。
+    pub fn protected_end_dates(&self) -> std::collections::HashSet<String> {
+        self.applied_wallpapers.values().cloned().collect()
+    }
+}
+
+/// IP 定位结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeoLocation {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// 定位时间（RFC 3339）
+    pub resolved_at: String,
+}
+
+/// 市场建议的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MktSuggestionSource {
+    /// IP 地理位置
+    Geo,
+    /// 系统 locale 的地区部分
+    Locale,
+}
+
+/// 市场建议的处理状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MktSuggestionStatus {
+    /// 等待用户确认
+    Pending,
+    /// 已接受（或建议与当前设置一致）
+    Accepted,
+    /// 用户选择忽略
+    Dismissed,
+}
+
+/// 首次启动的市场建议
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MktSuggestion {
+    /// 建议的 mkt
+    pub mkt: String,
+    /// 推算出的国家/地区代码（如 "JP"）
+    pub region: String,
+    pub source: MktSuggestionSource,
+    pub status: MktSuggestionStatus,
+}
+
+/// 当前已应用的壁纸（由 `get_current_wallpaper` 命令返回）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CurrentWallpaper {
+    /// 壁纸所属的 mkt
+    pub mkt: String,
+    /// 壁纸日期（YYYYMMDD）
+    pub end_date: String,
+    /// 本地文件路径
+    pub file_path: String,
+}
+
+/// 某个显示器上实际显示的壁纸（由 `get_applied_wallpapers` 命令返回）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppliedWallpaper {
+    /// 屏幕索引（与 `get_screen_orientations` 一致）
+    pub screen_index: usize,
+    /// 系统报告的壁纸文件路径，无法获取时为 None
+    pub file_path: Option<String>,
+    /// 对应的存档壁纸键（日期或聚焦图片 ID），不是壁纸目录中的图片时为 None
+    pub end_date: Option<String>,
+    /// 是否与本应用最近设置的壁纸一致
+    pub matches_current: bool,
+}
+
+/// 非正常退出后的状态修复报告
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecoveryReport {
+    /// 检测到非正常退出的时间（RFC 3339）
+    pub detected_at: String,
+    /// 上次会话的启动时间（来自会话标记，可能缺失）
+    pub previous_session_started_at: Option<String>,
+    /// 删除的残留临时文件
+    pub removed_temp_files: Vec<String>,
+    /// 删除的空壁纸文件（写入中断导致）
+    pub removed_empty_files: Vec<String>,
+    /// 索引中存在但本地缺失的壁纸（将在浏览时按需重新下载）
+    pub missing_wallpapers: Vec<String>,
+    /// 清除的持久化状态字段
+    pub cleared_flags: Vec<String>,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_market_status_new_detects_mismatch() {
+        let status = MarketStatus::new("en-US".to_string(), "zh-CN".to_string());
+        assert!(status.is_mismatch);
+        assert_eq!(status.requested_mkt, "en-US");
+        assert_eq!(status.effective_mkt, "zh-CN");
+
+        assert!(!MarketStatus::new("ja-JP".to_string(), "ja-JP".to_string()).is_mismatch);
+    }
+
     #[test]
     fn test_market_status_serialization() {
         let status = MarketStatus {
             requested_mkt: "en-US".to_string(),
             effective_mkt: "zh-CN".to_string(),
             is_mismatch: true,
+            is_degraded: false,
         };
 
         let json = serde_json::to_string(&status).unwrap();
@@ -64,6 +419,32 @@ mod tests {
         assert!(deserialized.is_mismatch);
     }
 
+    #[test]
+    fn test_market_health_degrades_and_recovers() {
+        let mut health = MarketHealth::default();
+        assert_eq!(health.success_rate(), None);
+        assert_eq!(health.retry_budget(), HEALTHY_RETRY_BUDGET);
+
+        health.record_success(200, "t0".to_string());
+        health.record_success(700, "t1".to_string());
+        assert_eq!(health.avg_latency_ms, Some(300));
+
+        for i in 0..MARKET_DEGRADED_FAILURES {
+            assert!(!health.is_degraded());
+            health.record_failure(format!("f{i}"));
+        }
+        assert!(health.is_degraded());
+        assert_eq!(health.retry_budget(), DEGRADED_RETRY_BUDGET);
+        assert_eq!(health.success_rate(), Some(2.0 / 7.0));
+
+        // 一次成功即恢复积极重试
+        health.record_success(300, "t2".to_string());
+        assert!(!health.is_degraded());
+        assert_eq!(health.consecutive_failures, 0);
+        assert_eq!(health.retry_budget(), HEALTHY_RETRY_BUDGET);
+        assert_eq!(health.last_failure_at.as_deref(), Some("f4"));
+    }
+
     #[test]
     fn test_app_runtime_state_default() {
         let state = AppRuntimeState::default();
@@ -74,6 +455,89 @@ mod tests {
         assert!(!state.autostart_notification_shown);
         assert!(state.last_actual_mkt.is_none());
         assert!(state._install_method_deprecated.is_none());
+        assert!(state.applied_wallpapers.is_empty());
+    }
+
+    #[test]
+    fn test_protected_end_dates_cover_every_mkt() {
+        let mut state = AppRuntimeState::default();
+        state
+            .applied_wallpapers
+            .insert("zh-CN".to_string(), "20240101".to_string());
+        state
+            .applied_wallpapers
+            .insert("en-US".to_string(), "20240102".to_string());
+
+        let protected = state.protected_end_dates();
+        assert_eq!(protected.len(), 2);
+        assert!(protected.contains("20240101"));
+        assert!(protected.contains("20240102"));
+    }
+
+    fn day(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_record_bandwidth_accumulates_and_prunes() {
+        let mut state = AppRuntimeState::default();
+        state.record_bandwidth(day("2023-01-01"), 100, 10);
+        state.record_bandwidth(day("2024-03-01"), 1000, 200);
+        state.record_bandwidth(day("2024-03-01"), 500, 100);
+
+        assert!(!state.bandwidth_by_day.contains_key("2023-01-01"));
+        assert_eq!(
+            state.bandwidth_by_day["2024-03-01"],
+            BandwidthUsage {
+                bytes: 1500,
+                downloads: 2,
+                duration_ms: 300,
+            }
+        );
+    }
+
+    #[test]
+    fn test_set_portrait_pending_caps_oldest() {
+        let mut state = AppRuntimeState::default();
+        assert!(state.set_portrait_pending("20240101", true));
+        assert!(!state.set_portrait_pending("20240101", true));
+        for day in 2..=MAX_PENDING_PORTRAIT_DOWNLOADS + 1 {
+            state.set_portrait_pending(&format!("202401{day:02}"), true);
+        }
+        assert_eq!(
+            state.pending_portrait_downloads.len(),
+            MAX_PENDING_PORTRAIT_DOWNLOADS
+        );
+        assert!(!state.pending_portrait_downloads.contains("20240101"));
+
+        assert!(state.set_portrait_pending("20240102", false));
+        assert!(!state.set_portrait_pending("20240102", false));
+    }
+
+    #[test]
+    fn test_bandwidth_stats_range() {
+        let mut state = AppRuntimeState::default();
+        state.record_bandwidth(day("2024-01-15"), 100, 1);
+        state.record_bandwidth(day("2024-02-20"), 200, 2);
+        state.record_bandwidth(day("2024-02-28"), 300, 3);
+        state.record_bandwidth(day("2024-03-01"), 400, 4);
+        let today = day("2024-03-01");
+
+        let today_stats = state.bandwidth_stats(BandwidthRange::Today, today);
+        assert_eq!(today_stats.total.bytes, 400);
+        assert_eq!(today_stats.days.len(), 1);
+
+        let week = state.bandwidth_stats(BandwidthRange::Week, today);
+        assert_eq!(week.total.bytes, 700);
+        assert_eq!(week.days[0].date, "2024-02-28");
+
+        let month = state.bandwidth_stats(BandwidthRange::Month, today);
+        assert_eq!(month.total.bytes, 900);
+        assert_eq!(month.total.downloads, 3);
+
+        let all = state.bandwidth_stats(BandwidthRange::All, today);
+        assert_eq!(all.total.bytes, 1000);
+        assert_eq!(all.total.duration_ms, 10);
     }
 
     #[test]
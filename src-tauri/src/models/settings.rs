@@ -1,4 +1,6 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
 
 /// 应用设置
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,13 +27,228 @@ pub struct AppSettings {
     /// 默认为空字符串，normalize_mkt() 会将其回退到 resolved_language。
     #[serde(default)]
     pub mkt: String,
+    /// 是否允许通过第三方归档镜像回填 Bing 8 天窗口之前的历史壁纸
+    ///
+    /// 数据来自第三方服务，默认关闭，需用户在设置中显式开启。
+    #[serde(default)]
+    pub archive_backfill_enabled: bool,
+    /// 横屏壁纸下载分辨率
+    ///
+    /// - "auto"：根据最大显示器的物理像素决定，≤1080p 屏幕下载 1920x1080，否则下载 UHD
+    /// - "UHD"：始终优先下载 UHD
+    /// - "1920x1080"：始终下载 1920x1080
+    #[serde(default = "default_download_resolution")]
+    pub download_resolution: String,
+    /// 用户自定义图片文件夹（作为 Bing 之外的壁纸来源）
+    #[serde(default)]
+    pub local_folder: Option<String>,
+    /// 自定义文件夹中图片的轮换顺序："mtime"（最新修改优先）或 "name"（按文件名）
+    #[serde(default = "default_local_folder_order")]
+    pub local_folder_order: String,
+    /// 自定义文件夹与 Bing 壁纸的自动应用排期
+    ///
+    /// - "off"：不使用自定义文件夹
+    /// - "alternate"：按天交替使用 Bing 壁纸和自定义图片
+    /// - "mix"：Bing 最新壁纸与自定义图片一起按天轮换
+    #[serde(default = "default_local_folder_schedule")]
+    pub local_folder_schedule: String,
+    /// 切换壁纸时播放淡入淡出过渡（仅 macOS 生效）
+    #[serde(default)]
+    pub wallpaper_fade: bool,
+    /// 左键单击托盘图标的行为: "toggle_window" | "show_menu" | "next_wallpaper"
+    #[serde(default = "default_tray_left_click")]
+    pub tray_left_click: String,
+    /// 系统空闲且网络不计量时，后台预取只有元数据的壁纸图片
+    #[serde(default)]
+    pub idle_prefetch: bool,
+    /// 删除壁纸图片时移到系统回收站/废纸篓，而不是永久删除
+    #[serde(default = "default_move_to_trash")]
+    pub move_to_trash: bool,
+    /// 应用更新通道："stable"（仅正式版）或 "beta"（包含预发布版本）
+    #[serde(default = "default_update_channel")]
+    pub update_channel: String,
+    /// 使用电池供电时推迟后台图片下载（元数据照常获取），接通电源后再下载
+    #[serde(default)]
+    pub defer_downloads_on_battery: bool,
+    /// 为竖屏显示器下载并设置竖屏壁纸（`YYYYMMDDr.jpg`）；关闭后竖屏显示器也使用横屏壁纸
+    #[serde(default = "default_enable_portrait_variant")]
+    pub enable_portrait_variant: bool,
+    /// 壁纸目录可用空间低于该值（MB）时跳过后台下载，0 表示不检查
+    #[serde(default = "default_low_disk_space_threshold_mb")]
+    pub low_disk_space_threshold_mb: u64,
+    /// Windows 11 虚拟桌面的壁纸范围："all"（切换桌面后同步到所有虚拟桌面）或 "current"（仅当前桌面）
+    #[serde(default = "default_virtual_desktop_mode")]
+    pub virtual_desktop_mode: String,
+    /// 每隔多少天把 index.json 备份到壁纸目录的 `backups/` 子目录，0 表示不备份
+    #[serde(default = "default_index_backup_interval_days")]
+    pub index_backup_interval_days: u32,
+    /// 额外信任的根证书（PEM 文件路径），用于 SSL 检查代理；未设置时只使用系统证书库
+    #[serde(default)]
+    pub custom_ca_path: Option<String>,
+    /// 只信任 `custom_ca_path` 中的证书，不再使用系统证书库
+    #[serde(default)]
+    pub custom_ca_only: bool,
+    /// 从托盘或 Cmd+Q 退出时的行为："ask"（询问）、"quit"（直接退出）或 "hide"（只隐藏到托盘）
+    #[serde(default = "default_quit_behavior")]
+    pub quit_behavior: String,
+    /// 在本地 IPC 端点上向第三方工具发布壁纸变化事件（见 `extension_events` 模块）
+    #[serde(default)]
+    pub extension_events: bool,
+    /// 双击托盘图标的行为（仅 Windows）: "none" | "open_window" | "refresh" | "next_wallpaper"
+    ///
+    /// 不为 "none" 时，单击动作会等待双击间隔结束后再执行，以便区分单击和双击。
+    #[serde(default = "default_tray_double_click")]
+    pub tray_double_click: String,
+    /// 按日出日落安排壁纸（见 `solar_schedule` 模块）:
+    /// - "off"：不使用
+    /// - "sunrise"：日出时更新并应用新壁纸
+    /// - "day_night"：日出、日落时分别切换到 `solar_day_wallpaper` / `solar_night_wallpaper`
+    #[serde(default = "default_solar_schedule")]
+    pub solar_schedule: String,
+    /// 日出日落计算使用的纬度（-90 ~ 90），与经度都设置时才使用，否则按 IP 定位
+    #[serde(default)]
+    pub solar_latitude: Option<f64>,
+    /// 日出日落计算使用的经度（-180 ~ 180，东经为正）
+    #[serde(default)]
+    pub solar_longitude: Option<f64>,
+    /// day_night 模式白天使用的壁纸日期（YYYYMMDD）
+    #[serde(default)]
+    pub solar_day_wallpaper: Option<String>,
+    /// day_night 模式夜间使用的壁纸日期（YYYYMMDD）
+    #[serde(default)]
+    pub solar_night_wallpaper: Option<String>,
+    /// 下载后以该质量重新压缩横屏 JPEG（50 ~ 95），0 表示保留原图
+    ///
+    /// 仅在压缩后更小时替换原文件，原始与存储大小记录在索引中。
+    #[serde(default)]
+    pub jpeg_quality: u8,
+    /// 与 Windows 聚焦图片轮换（仅 Windows，见 `spotlight` 模块）: "off" | "alternate" | "mix"
+    ///
+    /// 规则与 `local_folder_schedule` 相同。
+    #[serde(default = "default_spotlight_schedule")]
+    pub spotlight_schedule: String,
+    /// 自动应用新壁纸时在屏幕角落显示标题和地点的浮层秒数（2 ~ 15），0 表示关闭
+    #[serde(default)]
+    pub osd_duration_secs: u8,
+    /// 壁纸信息浮层的位置: "top_left" | "top_right" | "bottom_left" | "bottom_right"
+    #[serde(default = "default_osd_position")]
+    pub osd_position: String,
+    /// HTTP 空闲连接在连接池中保留的秒数（不超过 600），0 表示不复用连接
+    ///
+    /// 网络环境频繁变化（如切换 VPN）时调小可减少复用失效连接导致的请求失败。
+    #[serde(default = "default_http_keep_alive_secs")]
+    pub http_keep_alive_secs: u32,
+    /// 首次启动无法联网时，把生成的占位壁纸设为桌面壁纸（占位图总会显示在图库中）
+    #[serde(default)]
+    pub placeholder_wallpaper: bool,
+    /// 隐私模式：只允许访问 Bing，禁止版本检查、IP 定位、历史归档等其他出站请求
+    ///
+    /// 由 `network_gate` 模块统一拦截；用户自行配置的备份目标不受影响。
+    #[serde(default)]
+    pub bing_only_network: bool,
+    /// Linux：同步设置 KDE 锁屏界面的壁纸（只修改当前用户的配置，无需额外权限）
+    #[serde(default)]
+    pub lock_screen_wallpaper: bool,
+    /// Linux：同步设置登录界面（greeter）背景: "off" | "sddm"
+    ///
+    /// 需要管理员事先授予写入权限，见 `linux_greeter` 模块说明。
+    #[serde(default = "default_greeter_background")]
+    pub greeter_background: String,
+    /// SDDM 主题目录（绝对路径），None 表示按 SDDM 配置自动检测
+    ///
+    /// 用于主题不在 `/usr/share/sddm/themes` 下的发行版。
+    #[serde(default)]
+    pub sddm_theme_dir: Option<String>,
+    /// 壁纸变化后运行的程序（绝对路径），以图片路径和标题作为参数
+    ///
+    /// 只有在设置界面中确认过的程序才会运行，见 `apply_hook` 模块。
+    #[serde(default)]
+    pub apply_hook: Option<String>,
 }
 
+/// 重新压缩 JPEG 可选的质量范围
+const JPEG_QUALITY_RANGE: std::ops::RangeInclusive<u8> = 50..=95;
+/// 壁纸信息浮层可选的显示秒数范围
+const OSD_DURATION_RANGE: std::ops::RangeInclusive<u8> = 2..=15;
+/// HTTP 空闲连接保留时间的上限（秒）
+const MAX_HTTP_KEEP_ALIVE_SECS: u32 = 600;
+
 /// 默认主题设置
 fn default_theme() -> String {
     "system".to_string()
 }
 
+/// 默认下载分辨率设置
+fn default_download_resolution() -> String {
+    "auto".to_string()
+}
+
+/// 默认自定义文件夹轮换顺序
+fn default_local_folder_order() -> String {
+    "mtime".to_string()
+}
+
+/// 默认自定义文件夹排期（关闭）
+fn default_local_folder_schedule() -> String {
+    "off".to_string()
+}
+
+fn default_move_to_trash() -> bool {
+    true
+}
+
+fn default_enable_portrait_variant() -> bool {
+    true
+}
+
+fn default_low_disk_space_threshold_mb() -> u64 {
+    500
+}
+
+fn default_index_backup_interval_days() -> u32 {
+    7
+}
+
+fn default_virtual_desktop_mode() -> String {
+    "all".to_string()
+}
+
+fn default_greeter_background() -> String {
+    "off".to_string()
+}
+
+fn default_update_channel() -> String {
+    "stable".to_string()
+}
+
+fn default_quit_behavior() -> String {
+    "ask".to_string()
+}
+
+fn default_tray_left_click() -> String {
+    "toggle_window".to_string()
+}
+
+fn default_tray_double_click() -> String {
+    "none".to_string()
+}
+
+fn default_solar_schedule() -> String {
+    "off".to_string()
+}
+
+fn default_spotlight_schedule() -> String {
+    "off".to_string()
+}
+
+fn default_osd_position() -> String {
+    "top_right".to_string()
+}
+
+fn default_http_keep_alive_secs() -> u32 {
+    90
+}
+
 /// 默认语言设置
 ///
 /// 默认为 "auto"，运行时通过系统语言检测决定使用中文还是英文
@@ -53,11 +270,79 @@ impl Default for AppSettings {
             language: lang,
             resolved_language: resolved,
             mkt,
+            archive_backfill_enabled: false,
+            download_resolution: default_download_resolution(),
+            local_folder: None,
+            local_folder_order: default_local_folder_order(),
+            local_folder_schedule: default_local_folder_schedule(),
+            wallpaper_fade: false,
+            tray_left_click: default_tray_left_click(),
+            idle_prefetch: false,
+            move_to_trash: default_move_to_trash(),
+            update_channel: default_update_channel(),
+            defer_downloads_on_battery: false,
+            enable_portrait_variant: default_enable_portrait_variant(),
+            low_disk_space_threshold_mb: default_low_disk_space_threshold_mb(),
+            virtual_desktop_mode: default_virtual_desktop_mode(),
+            index_backup_interval_days: default_index_backup_interval_days(),
+            custom_ca_path: None,
+            custom_ca_only: false,
+            quit_behavior: default_quit_behavior(),
+            extension_events: false,
+            tray_double_click: default_tray_double_click(),
+            solar_schedule: default_solar_schedule(),
+            solar_latitude: None,
+            solar_longitude: None,
+            solar_day_wallpaper: None,
+            solar_night_wallpaper: None,
+            jpeg_quality: 0,
+            spotlight_schedule: default_spotlight_schedule(),
+            osd_duration_secs: 0,
+            osd_position: default_osd_position(),
+            http_keep_alive_secs: default_http_keep_alive_secs(),
+            placeholder_wallpaper: false,
+            bing_only_network: false,
+            lock_screen_wallpaper: false,
+            greeter_background: default_greeter_background(),
+            sddm_theme_dir: None,
+            apply_hook: None,
         }
     }
 }
 
+/// 单个设置项的变化（`settings-changed` 事件载荷的元素）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SettingChange {
+    /// 设置字段名（与 AppSettings 序列化后的 key 一致）
+    pub key: String,
+    pub old_value: serde_json::Value,
+    pub new_value: serde_json::Value,
+}
+
 impl AppSettings {
+    /// 对比新旧设置，返回发生变化的字段（按字段名排序）
+    pub fn diff(&self, new: &AppSettings) -> Vec<SettingChange> {
+        let (Ok(serde_json::Value::Object(old)), Ok(serde_json::Value::Object(mut new))) =
+            (serde_json::to_value(self), serde_json::to_value(new))
+        else {
+            return Vec::new();
+        };
+
+        let mut changes: Vec<SettingChange> = old
+            .into_iter()
+            .filter_map(|(key, old_value)| {
+                let new_value = new.remove(&key).unwrap_or(serde_json::Value::Null);
+                (old_value != new_value).then_some(SettingChange {
+                    key,
+                    old_value,
+                    new_value,
+                })
+            })
+            .collect();
+        changes.sort_by(|a, b| a.key.cmp(&b.key));
+        changes
+    }
+
     /// 归一化语言设置
     ///
     /// "auto"、"zh-CN"、"en-US" 是有效值，保持不变。
@@ -81,12 +366,183 @@ impl AppSettings {
 
     /// 归一化 mkt 设置
     ///
-    /// 如果 mkt 为空或不在 SUPPORTED_MKTS 中，回退到 resolved_language。
-    /// 如果 resolved_language 也无效，最终回退到 "en-US"。
+    /// 先统一大小写（`en-sg` -> `en-SG`），方便用户手动输入自定义市场。
+    /// 如果 mkt 为空、既不在 SUPPORTED_MKTS 中也不是格式正确的自定义市场，
+    /// 回退到 resolved_language；如果 resolved_language 也无效，最终回退到 "en-US"。
     ///
     /// 应在 compute_resolved_language() 之后调用，确保 resolved_language 已填充。
     pub fn normalize_mkt(&mut self) {
-        self.mkt = crate::utils::resolve_mkt(&self.mkt, &self.resolved_language).to_string();
+        let mkt = crate::utils::normalize_mkt_case(self.mkt.trim());
+        self.mkt = crate::utils::resolve_mkt(&mkt, &self.resolved_language).to_string();
+    }
+
+    /// 按字段校验用户提交的设置，返回 字段名 -> 错误码（全部有效时为空）
+    ///
+    /// 应在 normalize_mkt() 之前调用，否则无效的 mkt 已被静默回退。
+    ///
+    /// 错误码：
+    /// - `NOT_ABSOLUTE`：目录不是绝对路径
+    /// - `NOT_DIRECTORY`：路径已存在但不是目录（自定义图片文件夹还要求已存在）
+    /// - `INVALID_MKT`：市场代码既不在支持列表中，也不符合 `ll-CC` 格式
+    /// - `INVALID_VALUE`：取值不在可选范围内
+    /// - `INVALID_END_DATE`：壁纸日期不是有效的 YYYYMMDD
+    pub fn validate(&self) -> BTreeMap<String, String> {
+        let mut errors = BTreeMap::new();
+        let mut reject = |field: &str, code: &str| {
+            errors.insert(field.to_string(), code.to_string());
+        };
+
+        if let Some(dir) = &self.save_directory {
+            let path = Path::new(dir);
+            if !path.is_absolute() {
+                reject("save_directory", "NOT_ABSOLUTE");
+            } else if path.exists() && !path.is_dir() {
+                reject("save_directory", "NOT_DIRECTORY");
+            }
+        }
+        if let Some(dir) = &self.local_folder {
+            let path = Path::new(dir);
+            if !path.is_absolute() {
+                reject("local_folder", "NOT_ABSOLUTE");
+            } else if !path.is_dir() {
+                reject("local_folder", "NOT_DIRECTORY");
+            }
+        }
+        if let Some(dir) = &self.sddm_theme_dir
+            && !Path::new(dir).is_absolute()
+        {
+            reject("sddm_theme_dir", "NOT_ABSOLUTE");
+        }
+        if let Some(file) = &self.apply_hook {
+            let path = Path::new(file);
+            if !path.is_absolute() {
+                reject("apply_hook", "NOT_ABSOLUTE");
+            } else if !path.is_file() {
+                reject("apply_hook", "NOT_FILE");
+            }
+        }
+        if let Some(file) = &self.custom_ca_path {
+            let path = Path::new(file);
+            if !path.is_absolute() {
+                reject("custom_ca_path", "NOT_ABSOLUTE");
+            } else if !path.is_file() {
+                reject("custom_ca_path", "NOT_FILE");
+            } else if crate::http_client::read_ca_bundle(path).is_err() {
+                reject("custom_ca_path", "INVALID_CERTIFICATE");
+            }
+        }
+
+        // 空字符串表示跟随语言
+        let mkt = crate::utils::normalize_mkt_case(self.mkt.trim());
+        if !mkt.is_empty()
+            && !crate::utils::is_valid_mkt(&mkt)
+            && !crate::utils::is_well_formed_mkt(&mkt)
+        {
+            reject("mkt", "INVALID_MKT");
+        }
+
+        let choices: [(&str, &str, &[&str]); 13] = [
+            (
+                "theme",
+                &self.theme,
+                &["light", "dark", "system", "wallpaper"],
+            ),
+            (
+                "download_resolution",
+                &self.download_resolution,
+                &["auto", "UHD", "1920x1080"],
+            ),
+            (
+                "local_folder_order",
+                &self.local_folder_order,
+                &["mtime", "name"],
+            ),
+            (
+                "local_folder_schedule",
+                &self.local_folder_schedule,
+                &["off", "alternate", "mix"],
+            ),
+            (
+                "spotlight_schedule",
+                &self.spotlight_schedule,
+                &["off", "alternate", "mix"],
+            ),
+            (
+                "tray_left_click",
+                &self.tray_left_click,
+                &["toggle_window", "show_menu", "next_wallpaper"],
+            ),
+            (
+                "tray_double_click",
+                &self.tray_double_click,
+                &["none", "open_window", "refresh", "next_wallpaper"],
+            ),
+            (
+                "solar_schedule",
+                &self.solar_schedule,
+                &["off", "sunrise", "day_night"],
+            ),
+            ("update_channel", &self.update_channel, &["stable", "beta"]),
+            (
+                "quit_behavior",
+                &self.quit_behavior,
+                &["ask", "quit", "hide"],
+            ),
+            (
+                "virtual_desktop_mode",
+                &self.virtual_desktop_mode,
+                &["all", "current"],
+            ),
+            (
+                "osd_position",
+                &self.osd_position,
+                &["top_left", "top_right", "bottom_left", "bottom_right"],
+            ),
+            (
+                "greeter_background",
+                &self.greeter_background,
+                &["off", "sddm"],
+            ),
+        ];
+        for (field, value, allowed) in choices {
+            if !allowed.contains(&value) {
+                reject(field, "INVALID_VALUE");
+            }
+        }
+
+        let coordinates = [
+            ("solar_latitude", self.solar_latitude, 90.0),
+            ("solar_longitude", self.solar_longitude, 180.0),
+        ];
+        for (field, value, limit) in coordinates {
+            if value.is_some_and(|v| !(-limit..=limit).contains(&v)) {
+                reject(field, "INVALID_VALUE");
+            }
+        }
+        if self.jpeg_quality != 0 && !JPEG_QUALITY_RANGE.contains(&self.jpeg_quality) {
+            reject("jpeg_quality", "INVALID_VALUE");
+        }
+        if self.osd_duration_secs != 0 && !OSD_DURATION_RANGE.contains(&self.osd_duration_secs) {
+            reject("osd_duration_secs", "INVALID_VALUE");
+        }
+        if self.http_keep_alive_secs > MAX_HTTP_KEEP_ALIVE_SECS {
+            reject("http_keep_alive_secs", "INVALID_VALUE");
+        }
+
+        let solar_wallpapers = [
+            ("solar_day_wallpaper", &self.solar_day_wallpaper),
+            ("solar_night_wallpaper", &self.solar_night_wallpaper),
+        ];
+        for (field, end_date) in solar_wallpapers {
+            if end_date
+                .as_deref()
+                .is_some_and(|date| chrono::NaiveDate::parse_from_str(date, "%Y%m%d").is_err())
+            {
+                reject(field, "INVALID_END_DATE");
+            }
+        }
+
+        errors
     }
 }
 
@@ -101,6 +557,38 @@ mod tests {
         assert!(!settings.new_wallpaper_notification);
         assert_eq!(settings.save_directory, None);
         assert!(!settings.launch_at_startup);
+        assert!(!settings.archive_backfill_enabled);
+        assert_eq!(settings.download_resolution, "auto");
+        assert_eq!(settings.local_folder, None);
+        assert_eq!(settings.local_folder_schedule, "off");
+        assert!(!settings.wallpaper_fade);
+        assert_eq!(settings.tray_left_click, "toggle_window");
+        assert!(!settings.idle_prefetch);
+        assert!(settings.move_to_trash);
+        assert!(!settings.defer_downloads_on_battery);
+        assert!(settings.enable_portrait_variant);
+        assert_eq!(settings.low_disk_space_threshold_mb, 500);
+        assert_eq!(settings.virtual_desktop_mode, "all");
+        assert_eq!(settings.index_backup_interval_days, 7);
+        assert_eq!(settings.custom_ca_path, None);
+        assert!(!settings.custom_ca_only);
+        assert_eq!(settings.quit_behavior, "ask");
+        assert!(!settings.extension_events);
+        assert_eq!(settings.tray_double_click, "none");
+        assert_eq!(settings.solar_schedule, "off");
+        assert_eq!(settings.solar_latitude, None);
+        assert_eq!(settings.jpeg_quality, 0);
+        assert_eq!(settings.spotlight_schedule, "off");
+        assert_eq!(settings.osd_duration_secs, 0);
+        assert_eq!(settings.osd_position, "top_right");
+        assert_eq!(settings.http_keep_alive_secs, 90);
+        assert!(!settings.placeholder_wallpaper);
+        assert!(!settings.bing_only_network);
+        assert!(!settings.lock_screen_wallpaper);
+        assert_eq!(settings.greeter_background, "off");
+        assert_eq!(settings.sddm_theme_dir, None);
+        assert_eq!(settings.apply_hook, None);
+        assert_eq!(settings.update_channel, "stable");
     }
 
     #[test]
@@ -114,6 +602,42 @@ mod tests {
             language: "zh-CN".to_string(),
             resolved_language: "zh-CN".to_string(),
             mkt: "zh-CN".to_string(),
+            archive_backfill_enabled: false,
+            download_resolution: "auto".to_string(),
+            local_folder: None,
+            local_folder_order: "mtime".to_string(),
+            local_folder_schedule: "off".to_string(),
+            wallpaper_fade: false,
+            tray_left_click: "toggle_window".to_string(),
+            idle_prefetch: false,
+            move_to_trash: true,
+            update_channel: "stable".to_string(),
+            defer_downloads_on_battery: false,
+            enable_portrait_variant: true,
+            low_disk_space_threshold_mb: 500,
+            virtual_desktop_mode: "all".to_string(),
+            index_backup_interval_days: 7,
+            custom_ca_path: None,
+            custom_ca_only: false,
+            quit_behavior: "ask".to_string(),
+            extension_events: false,
+            tray_double_click: "none".to_string(),
+            solar_schedule: "off".to_string(),
+            solar_latitude: None,
+            solar_longitude: None,
+            solar_day_wallpaper: None,
+            solar_night_wallpaper: None,
+            jpeg_quality: 0,
+            spotlight_schedule: "off".to_string(),
+            osd_duration_secs: 0,
+            osd_position: "top_right".to_string(),
+            http_keep_alive_secs: 90,
+            placeholder_wallpaper: false,
+            bing_only_network: false,
+            lock_screen_wallpaper: false,
+            greeter_background: "off".to_string(),
+            sddm_theme_dir: None,
+            apply_hook: None,
         };
 
         let json = serde_json::to_string(&settings).unwrap();
@@ -132,6 +656,34 @@ mod tests {
         assert_eq!(deserialized.mkt, "zh-CN");
     }
 
+    #[test]
+    fn test_app_settings_diff() {
+        let old = AppSettings::default();
+        assert!(old.diff(&old.clone()).is_empty());
+
+        let new = AppSettings {
+            theme: "dark".to_string(),
+            save_directory: Some("/custom/path".to_string()),
+            ..old.clone()
+        };
+        let changes = old.diff(&new);
+        assert_eq!(
+            changes,
+            vec![
+                SettingChange {
+                    key: "save_directory".to_string(),
+                    old_value: serde_json::Value::Null,
+                    new_value: serde_json::json!("/custom/path"),
+                },
+                SettingChange {
+                    key: "theme".to_string(),
+                    old_value: serde_json::json!(old.theme),
+                    new_value: serde_json::json!("dark"),
+                },
+            ]
+        );
+    }
+
     #[test]
     fn test_app_settings_legacy_field_ignored() {
         // Simulate old JSON with removed field keep_image_count
@@ -151,6 +703,38 @@ mod tests {
         // 旧 JSON 不含 resolved_language 和 mkt，应默认为空字符串
         assert_eq!(settings.resolved_language, "");
         assert_eq!(settings.mkt, "");
+        // 旧 JSON 不含 download_resolution，应默认为 auto
+        assert_eq!(settings.download_resolution, "auto");
+        assert_eq!(settings.local_folder, None);
+        assert_eq!(settings.local_folder_schedule, "off");
+        assert!(!settings.wallpaper_fade);
+        assert_eq!(settings.tray_left_click, "toggle_window");
+        assert!(!settings.idle_prefetch);
+        assert!(settings.move_to_trash);
+        assert!(!settings.defer_downloads_on_battery);
+        assert!(settings.enable_portrait_variant);
+        assert_eq!(settings.low_disk_space_threshold_mb, 500);
+        assert_eq!(settings.virtual_desktop_mode, "all");
+        assert_eq!(settings.index_backup_interval_days, 7);
+        assert_eq!(settings.custom_ca_path, None);
+        assert!(!settings.custom_ca_only);
+        assert_eq!(settings.quit_behavior, "ask");
+        assert!(!settings.extension_events);
+        assert_eq!(settings.tray_double_click, "none");
+        assert_eq!(settings.solar_schedule, "off");
+        assert_eq!(settings.solar_latitude, None);
+        assert_eq!(settings.jpeg_quality, 0);
+        assert_eq!(settings.spotlight_schedule, "off");
+        assert_eq!(settings.osd_duration_secs, 0);
+        assert_eq!(settings.osd_position, "top_right");
+        assert_eq!(settings.http_keep_alive_secs, 90);
+        assert!(!settings.placeholder_wallpaper);
+        assert!(!settings.bing_only_network);
+        assert!(!settings.lock_screen_wallpaper);
+        assert_eq!(settings.greeter_background, "off");
+        assert_eq!(settings.sddm_theme_dir, None);
+        assert_eq!(settings.apply_hook, None);
+        assert_eq!(settings.update_channel, "stable");
     }
 
     #[test]
@@ -164,6 +748,42 @@ mod tests {
             language: "auto".to_string(),
             resolved_language: String::new(),
             mkt: String::new(),
+            archive_backfill_enabled: false,
+            download_resolution: "auto".to_string(),
+            local_folder: None,
+            local_folder_order: "mtime".to_string(),
+            local_folder_schedule: "off".to_string(),
+            wallpaper_fade: false,
+            tray_left_click: "toggle_window".to_string(),
+            idle_prefetch: false,
+            move_to_trash: true,
+            update_channel: "stable".to_string(),
+            defer_downloads_on_battery: false,
+            enable_portrait_variant: true,
+            low_disk_space_threshold_mb: 500,
+            virtual_desktop_mode: "all".to_string(),
+            index_backup_interval_days: 7,
+            custom_ca_path: None,
+            custom_ca_only: false,
+            quit_behavior: "ask".to_string(),
+            extension_events: false,
+            tray_double_click: "none".to_string(),
+            solar_schedule: "off".to_string(),
+            solar_latitude: None,
+            solar_longitude: None,
+            solar_day_wallpaper: None,
+            solar_night_wallpaper: None,
+            jpeg_quality: 0,
+            spotlight_schedule: "off".to_string(),
+            osd_duration_secs: 0,
+            osd_position: "top_right".to_string(),
+            http_keep_alive_secs: 90,
+            placeholder_wallpaper: false,
+            bing_only_network: false,
+            lock_screen_wallpaper: false,
+            greeter_background: "off".to_string(),
+            sddm_theme_dir: None,
+            apply_hook: None,
         };
 
         // "auto" 是有效值，normalize 不应改变
@@ -223,6 +843,42 @@ mod tests {
             language: "auto".to_string(),
             resolved_language: String::new(),
             mkt: String::new(),
+            archive_backfill_enabled: false,
+            download_resolution: "auto".to_string(),
+            local_folder: None,
+            local_folder_order: "mtime".to_string(),
+            local_folder_schedule: "off".to_string(),
+            wallpaper_fade: false,
+            tray_left_click: "toggle_window".to_string(),
+            idle_prefetch: false,
+            move_to_trash: true,
+            update_channel: "stable".to_string(),
+            defer_downloads_on_battery: false,
+            enable_portrait_variant: true,
+            low_disk_space_threshold_mb: 500,
+            virtual_desktop_mode: "all".to_string(),
+            index_backup_interval_days: 7,
+            custom_ca_path: None,
+            custom_ca_only: false,
+            quit_behavior: "ask".to_string(),
+            extension_events: false,
+            tray_double_click: "none".to_string(),
+            solar_schedule: "off".to_string(),
+            solar_latitude: None,
+            solar_longitude: None,
+            solar_day_wallpaper: None,
+            solar_night_wallpaper: None,
+            jpeg_quality: 0,
+            spotlight_schedule: "off".to_string(),
+            osd_duration_secs: 0,
+            osd_position: "top_right".to_string(),
+            http_keep_alive_secs: 90,
+            placeholder_wallpaper: false,
+            bing_only_network: false,
+            lock_screen_wallpaper: false,
+            greeter_background: "off".to_string(),
+            sddm_theme_dir: None,
+            apply_hook: None,
         };
 
         // "auto" 应解析为系统语言
@@ -255,6 +911,42 @@ mod tests {
             language: "auto".to_string(),
             resolved_language: "zh-CN".to_string(),
             mkt: String::new(),
+            archive_backfill_enabled: false,
+            download_resolution: "auto".to_string(),
+            local_folder: None,
+            local_folder_order: "mtime".to_string(),
+            local_folder_schedule: "off".to_string(),
+            wallpaper_fade: false,
+            tray_left_click: "toggle_window".to_string(),
+            idle_prefetch: false,
+            move_to_trash: true,
+            update_channel: "stable".to_string(),
+            defer_downloads_on_battery: false,
+            enable_portrait_variant: true,
+            low_disk_space_threshold_mb: 500,
+            virtual_desktop_mode: "all".to_string(),
+            index_backup_interval_days: 7,
+            custom_ca_path: None,
+            custom_ca_only: false,
+            quit_behavior: "ask".to_string(),
+            extension_events: false,
+            tray_double_click: "none".to_string(),
+            solar_schedule: "off".to_string(),
+            solar_latitude: None,
+            solar_longitude: None,
+            solar_day_wallpaper: None,
+            solar_night_wallpaper: None,
+            jpeg_quality: 0,
+            spotlight_schedule: "off".to_string(),
+            osd_duration_secs: 0,
+            osd_position: "top_right".to_string(),
+            http_keep_alive_secs: 90,
+            placeholder_wallpaper: false,
+            bing_only_network: false,
+            lock_screen_wallpaper: false,
+            greeter_background: "off".to_string(),
+            sddm_theme_dir: None,
+            apply_hook: None,
         };
 
         // 空 mkt 应回退到 resolved_language
@@ -266,8 +958,8 @@ mod tests {
         settings.normalize_mkt();
         assert_eq!(settings.mkt, "ja-JP");
 
-        // 无效 mkt 应回退到 resolved_language
-        settings.mkt = "xx-YY".to_string();
+        // 格式无效的 mkt 应回退到 resolved_language
+        settings.mkt = "xx_YY".to_string();
         settings.normalize_mkt();
         assert_eq!(settings.mkt, "zh-CN");
 
@@ -278,6 +970,21 @@ mod tests {
         assert_eq!(settings.mkt, "en-US");
     }
 
+    #[test]
+    fn test_normalize_mkt_keeps_custom_market() {
+        let mut settings = AppSettings {
+            mkt: " en-sg ".to_string(),
+            resolved_language: "zh-CN".to_string(),
+            ..AppSettings::default()
+        };
+        settings.normalize_mkt();
+        assert_eq!(settings.mkt, "en-SG");
+
+        settings.mkt = "singapore".to_string();
+        settings.normalize_mkt();
+        assert_eq!(settings.mkt, "zh-CN");
+    }
+
     #[test]
     fn test_app_settings_default_mkt() {
         let settings = AppSettings::default();
@@ -306,4 +1013,124 @@ mod tests {
             "Missing mkt should default to empty string"
         );
     }
+
+    #[test]
+    fn test_validate_reports_errors_per_field() {
+        assert!(AppSettings::default().validate().is_empty());
+
+        let dir = std::env::temp_dir().join(format!("bw_settings_validate_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("not_a_dir.txt");
+        std::fs::write(&file, b"x").unwrap();
+
+        let settings = AppSettings {
+            save_directory: Some(file.to_string_lossy().to_string()),
+            local_folder: Some("relative/folder".to_string()),
+            mkt: "not a market".to_string(),
+            theme: "sepia".to_string(),
+            custom_ca_path: Some(file.to_string_lossy().to_string()),
+            ..AppSettings::default()
+        };
+        let errors = settings.validate();
+        assert_eq!(errors.len(), 5);
+        assert_eq!(errors["custom_ca_path"], "INVALID_CERTIFICATE");
+        assert_eq!(errors["save_directory"], "NOT_DIRECTORY");
+        assert_eq!(errors["local_folder"], "NOT_ABSOLUTE");
+        assert_eq!(errors["mkt"], "INVALID_MKT");
+        assert_eq!(errors["theme"], "INVALID_VALUE");
+
+        // 尚未创建的保存目录、自定义市场和大小写不规范的市场都是有效的
+        let settings = AppSettings {
+            save_directory: Some(dir.join("new").to_string_lossy().to_string()),
+            local_folder: Some(dir.to_string_lossy().to_string()),
+            mkt: "en-sg".to_string(),
+            ..AppSettings::default()
+        };
+        assert!(settings.validate().is_empty());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_validate_jpeg_quality() {
+        for (quality, valid) in [
+            (0, true),
+            (50, true),
+            (85, true),
+            (95, true),
+            (30, false),
+            (100, false),
+        ] {
+            let settings = AppSettings {
+                jpeg_quality: quality,
+                ..AppSettings::default()
+            };
+            assert_eq!(
+                settings.validate().contains_key("jpeg_quality"),
+                !valid,
+                "quality {quality}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_osd_fields() {
+        for (duration, valid) in [(0, true), (2, true), (15, true), (1, false), (30, false)] {
+            let settings = AppSettings {
+                osd_duration_secs: duration,
+                ..AppSettings::default()
+            };
+            assert_eq!(
+                settings.validate().contains_key("osd_duration_secs"),
+                !valid,
+                "duration {duration}"
+            );
+        }
+        let settings = AppSettings {
+            osd_position: "center".to_string(),
+            ..AppSettings::default()
+        };
+        assert!(settings.validate().contains_key("osd_position"));
+    }
+
+    #[test]
+    fn test_validate_http_keep_alive_secs() {
+        for (secs, valid) in [(0, true), (90, true), (600, true), (601, false)] {
+            let settings = AppSettings {
+                http_keep_alive_secs: secs,
+                ..AppSettings::default()
+            };
+            assert_eq!(
+                settings.validate().contains_key("http_keep_alive_secs"),
+                !valid,
+                "keep-alive {secs}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_solar_schedule_fields() {
+        let settings = AppSettings {
+            solar_schedule: "day_night".to_string(),
+            solar_latitude: Some(39.9),
+            solar_longitude: Some(116.4),
+            solar_day_wallpaper: Some("20240310".to_string()),
+            ..AppSettings::default()
+        };
+        assert!(settings.validate().is_empty());
+
+        let settings = AppSettings {
+            solar_schedule: "noon".to_string(),
+            solar_latitude: Some(91.0),
+            solar_longitude: Some(-180.5),
+            solar_night_wallpaper: Some("2024-03-10".to_string()),
+            ..AppSettings::default()
+        };
+        let errors = settings.validate();
+        assert_eq!(errors.len(), 4);
+        assert_eq!(errors["solar_schedule"], "INVALID_VALUE");
+        assert_eq!(errors["solar_latitude"], "INVALID_VALUE");
+        assert_eq!(errors["solar_longitude"], "INVALID_VALUE");
+        assert_eq!(errors["solar_night_wallpaper"], "INVALID_END_DATE");
+    }
 }
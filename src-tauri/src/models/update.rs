@@ -0,0 +1,252 @@
+use serde::Serialize;
+
+/// 更新循环所处的阶段
+///
+/// 一轮更新按 `CheckingCache → Fetching → SavingMetadata → Downloading → Applying` 的顺序推进，
+/// 可以跳过中间阶段（如命中缓存时直接应用），但不能回退；任何进行中的阶段都可以结束为
+/// `Done` / `Failed` / `Cancelled`。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdatePhase {
+    /// 启动后尚未运行过更新
+    #[default]
+    Idle,
+    /// 检查今天是否需要请求 API
+    CheckingCache,
+    /// 请求 Bing API
+    Fetching,
+    /// 保存壁纸元数据
+    SavingMetadata,
+    /// 下载需要应用的壁纸图片
+    Downloading,
+    /// 设置桌面壁纸
+    Applying,
+    /// 本轮更新已完成
+    Done,
+    /// 本轮更新失败，原因见 [`UpdateState::error`]
+    Failed,
+    /// 本轮更新被用户取消
+    Cancelled,
+    /// 正在重置应用，期间不允许启动更新
+    Resetting,
+}
+
+impl UpdatePhase {
+    /// 是否有更新（或重置）正在进行
+    pub fn is_busy(self) -> bool {
+        !matches!(
+            self,
+            Self::Idle | Self::Done | Self::Failed | Self::Cancelled
+        )
+    }
+
+    /// 进行中阶段的先后顺序
+    fn step(self) -> Option<u8> {
+        match self {
+            Self::CheckingCache => Some(1),
+            Self::Fetching => Some(2),
+            Self::SavingMetadata => Some(3),
+            Self::Downloading => Some(4),
+            Self::Applying => Some(5),
+            _ => None,
+        }
+    }
+
+    /// 是否允许从当前阶段切换到 `next`
+    pub fn can_transition_to(self, next: Self) -> bool {
+        match (self, next) {
+            (Self::Resetting, Self::Idle) => true,
+            (_, Self::CheckingCache | Self::Resetting) => !self.is_busy(),
+            (_, Self::Done | Self::Failed | Self::Cancelled) => self.step().is_some(),
+            _ => matches!((self.step(), next.step()), (Some(from), Some(to)) if to > from),
+        }
+    }
+}
+
+/// 一次阶段切换
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UpdateTransition {
+    pub phase: UpdatePhase,
+    /// 进入该阶段的时间（RFC 3339）
+    pub at: String,
+}
+
+/// 更新循环状态
+///
+/// 由 `get_update_state` 命令返回，同时作为 `update-state-changed` 事件的负载。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct UpdateState {
+    pub phase: UpdatePhase,
+    /// 是否为强制更新（跳过缓存检查）
+    pub force: bool,
+    /// 本轮更新开始时间（RFC 3339）
+    pub started_at: Option<String>,
+    /// 失败原因（错误码），仅在 `Failed` 时存在
+    pub error: Option<String>,
+    /// 失败时所处的阶段
+    pub failed_phase: Option<UpdatePhase>,
+    /// 本轮经过的阶段，新一轮开始时清空
+    pub transitions: Vec<UpdateTransition>,
+}
+
+impl UpdateState {
+    pub fn is_busy(&self) -> bool {
+        self.phase.is_busy()
+    }
+
+    /// 开始新一轮更新，已有更新或重置在进行时返回 `false`
+    pub fn begin(&mut self, force: bool, at: String) -> bool {
+        if !self.phase.can_transition_to(UpdatePhase::CheckingCache) {
+            return false;
+        }
+        *self = Self {
+            force,
+            started_at: Some(at.clone()),
+            ..Self::default()
+        };
+        self.enter(UpdatePhase::CheckingCache, at);
+        true
+    }
+
+    /// 切换到下一阶段，不合法的切换返回 `false`（已处于该阶段时视为成功）
+    ///
+    /// 重置结束回到 `Idle` 时清空上一轮的记录。
+    pub fn advance(&mut self, next: UpdatePhase, at: String) -> bool {
+        if self.phase == next {
+            return true;
+        }
+        if !self.phase.can_transition_to(next) {
+            return false;
+        }
+        if next == UpdatePhase::Idle {
+            *self = Self::default();
+        } else {
+            self.enter(next, at);
+        }
+        true
+    }
+
+    /// 以错误码结束本轮更新
+    pub fn fail(&mut self, error: &str, at: String) -> bool {
+        let failed_phase = self.phase;
+        if !failed_phase.can_transition_to(UpdatePhase::Failed) {
+            return false;
+        }
+        self.enter(UpdatePhase::Failed, at);
+        self.error = Some(error.to_string());
+        self.failed_phase = Some(failed_phase);
+        true
+    }
+
+    fn enter(&mut self, phase: UpdatePhase, at: String) {
+        self.phase = phase;
+        self.transitions.push(UpdateTransition { phase, at });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(second: u32) -> String {
+        format!("2024-03-10T08:00:{second:02}+08:00")
+    }
+
+    fn phases(state: &UpdateState) -> Vec<UpdatePhase> {
+        state.transitions.iter().map(|t| t.phase).collect()
+    }
+
+    #[test]
+    fn test_full_cycle_records_transitions() {
+        let mut state = UpdateState::default();
+        assert!(!state.is_busy());
+        assert!(state.begin(false, at(0)));
+        assert!(state.is_busy());
+        for (i, phase) in [
+            UpdatePhase::Fetching,
+            UpdatePhase::SavingMetadata,
+            UpdatePhase::Downloading,
+            UpdatePhase::Applying,
+            UpdatePhase::Done,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            assert!(state.advance(phase, at(i as u32 + 1)));
+        }
+        assert!(!state.is_busy());
+        assert_eq!(state.started_at.as_deref(), Some(at(0).as_str()));
+        assert_eq!(
+            phases(&state),
+            vec![
+                UpdatePhase::CheckingCache,
+                UpdatePhase::Fetching,
+                UpdatePhase::SavingMetadata,
+                UpdatePhase::Downloading,
+                UpdatePhase::Applying,
+                UpdatePhase::Done,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_begin_rejected_while_busy() {
+        let mut state = UpdateState::default();
+        assert!(state.begin(true, at(0)));
+        assert!(!state.begin(false, at(1)));
+        assert!(state.force);
+
+        assert!(state.advance(UpdatePhase::Cancelled, at(2)));
+        assert!(state.begin(false, at(3)));
+        assert_eq!(phases(&state), vec![UpdatePhase::CheckingCache]);
+    }
+
+    #[test]
+    fn test_phases_only_move_forward() {
+        let mut state = UpdateState::default();
+        assert!(!state.advance(UpdatePhase::Fetching, at(0)));
+        assert!(!state.advance(UpdatePhase::Done, at(0)));
+
+        state.begin(false, at(0));
+        // 命中缓存时可以直接应用
+        assert!(state.advance(UpdatePhase::Applying, at(1)));
+        assert!(!state.advance(UpdatePhase::Fetching, at(2)));
+        // 重复进入同一阶段不额外记录
+        assert!(state.advance(UpdatePhase::Applying, at(2)));
+        assert_eq!(state.transitions.len(), 2);
+    }
+
+    #[test]
+    fn test_fail_records_error_and_phase() {
+        let mut state = UpdateState::default();
+        state.begin(false, at(0));
+        state.advance(UpdatePhase::Fetching, at(1));
+        assert!(state.fail("FETCH_FAILED", at(2)));
+        assert_eq!(state.phase, UpdatePhase::Failed);
+        assert_eq!(state.error.as_deref(), Some("FETCH_FAILED"));
+        assert_eq!(state.failed_phase, Some(UpdatePhase::Fetching));
+        assert!(!state.fail("FETCH_FAILED", at(3)));
+    }
+
+    #[test]
+    fn test_resetting_blocks_updates_and_clears_history() {
+        let mut state = UpdateState::default();
+        state.begin(false, at(0));
+        assert!(!state.advance(UpdatePhase::Resetting, at(1)));
+        state.advance(UpdatePhase::Done, at(1));
+
+        assert!(state.advance(UpdatePhase::Resetting, at(2)));
+        assert!(state.is_busy());
+        assert!(!state.begin(false, at(3)));
+        assert!(state.advance(UpdatePhase::Idle, at(4)));
+        assert_eq!(state, UpdateState::default());
+    }
+
+    #[test]
+    fn test_phase_serializes_as_snake_case() {
+        assert_eq!(
+            serde_json::to_string(&UpdatePhase::SavingMetadata).unwrap(),
+            "\"saving_metadata\""
+        );
+    }
+}
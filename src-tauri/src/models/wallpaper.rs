@@ -10,7 +10,8 @@ use super::bing::BingImageEntry;
 /// - copyright_link -> l
 /// - end_date -> d (保留，因为代码中广泛使用)
 /// - urlbase -> u
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// - resolution -> r
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LocalWallpaper {
     #[serde(rename = "t")]
     pub title: String,
@@ -22,6 +23,187 @@ pub struct LocalWallpaper {
     pub end_date: String,
     #[serde(rename = "u", default)]
     pub urlbase: String,
+    /// 实际下载的横屏分辨率（如 "UHD"、"1920x1200"），None 表示尚未下载或旧版本数据
+    #[serde(rename = "r", default, skip_serializing_if = "Option::is_none")]
+    pub resolution: Option<String>,
+    /// 该 mkt 当日是否提供 1080x1920 竖屏版本（HEAD 探测结果），None 表示尚未探测
+    #[serde(rename = "p", default, skip_serializing_if = "Option::is_none")]
+    pub portrait_available: Option<bool>,
+    /// 磁盘上的横屏图片是否为无水印版本（仅 zh-CN 市场会尝试），None 表示未尝试或旧版本数据
+    #[serde(rename = "w", default, skip_serializing_if = "Option::is_none")]
+    pub watermark_free: Option<bool>,
+    /// 下载后重新压缩的记录，None 表示磁盘上是原图
+    #[serde(rename = "z", default, skip_serializing_if = "Option::is_none")]
+    pub recompression: Option<Recompression>,
+}
+
+/// 横屏图片重新压缩的记录
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Recompression {
+    /// 压缩时使用的 JPEG 质量
+    #[serde(rename = "q")]
+    pub quality: u8,
+    /// 原图大小（字节）
+    #[serde(rename = "o")]
+    pub original_size: u64,
+    /// 压缩后实际存储的大小（字节）
+    #[serde(rename = "s")]
+    pub stored_size: u64,
+}
+
+/// 分页获取的本地壁纸列表（按日期降序）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalWallpaperPage {
+    pub wallpapers: Vec<LocalWallpaper>,
+    /// 该 mkt 下的壁纸总数
+    pub total: usize,
+    pub offset: usize,
+    /// 实际读取的 mkt（未指定 mkt 时可能回退到索引中的其他 mkt）
+    pub mkt: String,
+}
+
+/// 单张壁纸的元数据已重新获取（wallpaper-metadata-refreshed 事件载荷）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WallpaperMetadataRefreshed {
+    /// 元数据所属的 mkt
+    pub mkt: String,
+    pub wallpaper: LocalWallpaper,
+}
+
+/// 单张壁纸的详情（供前端详情面板一次性获取）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WallpaperDetails {
+    pub end_date: String,
+    pub title: String,
+    pub copyright: String,
+    /// 壁纸所在的 mkt
+    pub mkt: String,
+    /// 本地文件路径
+    pub file_path: String,
+    /// 文件大小（字节），未下载时为 None
+    pub file_size: Option<u64>,
+    /// 重新压缩前的原图大小（字节），未重新压缩时为 None
+    pub original_file_size: Option<u64>,
+    /// 从 JPEG 文件头解析的宽度
+    pub width: Option<u32>,
+    /// 从 JPEG 文件头解析的高度
+    pub height: Option<u32>,
+    /// 索引中记录的下载分辨率档位（如 "UHD"）
+    pub resolution: Option<String>,
+    /// 下载时间（文件修改时间，RFC 3339）
+    pub downloaded_at: Option<String>,
+    /// 图片来源地址
+    pub source_url: Option<String>,
+    /// 是否已有竖屏版本
+    pub has_portrait: bool,
+    /// 用户添加的标签（按名称排序）
+    pub tags: Vec<String>,
+    /// 从版权文本解析出的地点和署名
+    pub copyright_info: CopyrightInfo,
+}
+
+/// 从 Bing 版权文本解析出的结构化信息
+///
+/// 版权文本通常为 `地点 (© 摄影师/机构)`，如
+/// `Moraine Lake, Alberta, Canada (© Paul Zizka/Minden Pictures)`，中文市场使用全角括号。
+/// 署名中没有 `/` 时无法区分摄影师和机构，按名称是否像图片机构（如 `Getty Images`）判断。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CopyrightInfo {
+    /// 地点（版权括号之前的部分）
+    pub location: String,
+    /// 摄影师
+    pub photographer: Option<String>,
+    /// 图片机构（如 Minden Pictures、Getty Images）
+    pub agency: Option<String>,
+}
+
+/// 图片机构名称中常见的词（小写），用于判断没有 `/` 的署名
+const AGENCY_HINTS: [&str; 9] = [
+    "images",
+    "pictures",
+    "stock",
+    "alamy",
+    "agency",
+    "library",
+    "photography",
+    "photos",
+    "media",
+];
+
+impl CopyrightInfo {
+    /// 解析版权文本（使用最后一个 `(©` 标记，地点中的括号不受影响）
+    pub fn parse(copyright: &str) -> Self {
+        let copyright = copyright.trim();
+        let split = ["(©", "（©"]
+            .iter()
+            .filter_map(|marker| copyright.rfind(marker).map(|index| (index, marker.len())))
+            .max_by_key(|(index, _)| *index);
+
+        let Some((index, marker_len)) = split else {
+            return Self {
+                location: copyright.to_string(),
+                ..Self::default()
+            };
+        };
+
+        let location = copyright[..index].trim().to_string();
+        let credit = copyright[index + marker_len..]
+            .trim()
+            .trim_end_matches([')', '）'])
+            .trim();
+        let non_empty = |value: &str| {
+            let value = value.trim();
+            (!value.is_empty()).then(|| value.to_string())
+        };
+        let (photographer, agency) = match credit.split_once('/') {
+            Some((photographer, agency)) => (non_empty(photographer), non_empty(agency)),
+            None if is_agency_name(credit) => (None, non_empty(credit)),
+            None => (non_empty(credit), None),
+        };
+        Self {
+            location,
+            photographer,
+            agency,
+        }
+    }
+
+    /// 署名原文（`摄影师/机构`），都没有时返回 None
+    pub fn credit(&self) -> Option<String> {
+        match (&self.photographer, &self.agency) {
+            (Some(photographer), Some(agency)) => Some(format!("{photographer}/{agency}")),
+            (Some(name), None) | (None, Some(name)) => Some(name.clone()),
+            (None, None) => None,
+        }
+    }
+
+    /// 地点的最后一段，通常是国家或地区（如 `Moraine Lake, Alberta, Canada` -> `Canada`）
+    pub fn region(&self) -> Option<&str> {
+        self.location
+            .rsplit([',', '，', '、'])
+            .next()
+            .map(str::trim)
+            .filter(|region| !region.is_empty())
+    }
+}
+
+fn is_agency_name(name: &str) -> bool {
+    let name = name.to_lowercase();
+    AGENCY_HINTS.iter().any(|hint| name.contains(hint))
+}
+
+/// 某一日期壁纸的本地状态（前端无需拉取整个列表推断是否存在）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WallpaperStatus {
+    /// 是否有任一 mkt 的索引记录
+    pub in_index: bool,
+    /// 横屏图片文件是否存在
+    pub file_exists: bool,
+    /// 竖屏图片文件是否存在
+    pub portrait_exists: bool,
+    /// 横屏文件大小（字节），文件不存在时为 None
+    pub file_size: Option<u64>,
+    /// 索引中包含该日期的 mkt（按名称排序）
+    pub mkt_list: Vec<String>,
 }
 
 impl From<BingImageEntry> for LocalWallpaper {
@@ -32,10 +214,21 @@ impl From<BingImageEntry> for LocalWallpaper {
             copyright_link: entry.copyrightlink.clone(),
             end_date: entry.enddate.clone(),
             urlbase: entry.urlbase.clone(),
+            resolution: None,
+            portrait_available: None,
+            watermark_free: None,
+            recompression: None,
         }
     }
 }
 
+impl LocalWallpaper {
+    /// 解析版权文本中的地点和署名
+    pub fn copyright_info(&self) -> CopyrightInfo {
+        CopyrightInfo::parse(&self.copyright)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -68,6 +261,10 @@ mod tests {
             copyright_link: "https://example.com".to_string(),
             end_date: "20240102".to_string(),
             urlbase: "/th?id=OHR.Test_EN-US1234567890".to_string(),
+            resolution: None,
+            portrait_available: None,
+            watermark_free: None,
+            recompression: None,
         };
 
         let json = serde_json::to_string(&wallpaper).unwrap();
@@ -76,4 +273,46 @@ mod tests {
         assert_eq!(deserialized.title, wallpaper.title);
         assert_eq!(deserialized.end_date, wallpaper.end_date);
     }
+
+    #[test]
+    fn test_copyright_info_splits_photographer_and_agency() {
+        let info =
+            CopyrightInfo::parse("Moraine Lake, Alberta, Canada (© Paul Zizka/Minden Pictures)");
+        assert_eq!(info.location, "Moraine Lake, Alberta, Canada");
+        assert_eq!(info.photographer.as_deref(), Some("Paul Zizka"));
+        assert_eq!(info.agency.as_deref(), Some("Minden Pictures"));
+        assert_eq!(info.credit().as_deref(), Some("Paul Zizka/Minden Pictures"));
+        assert_eq!(info.region(), Some("Canada"));
+
+        let info = CopyrightInfo::parse("Bridge (© Jane Doe/Getty Images/iStockphoto)");
+        assert_eq!(info.photographer.as_deref(), Some("Jane Doe"));
+        assert_eq!(info.agency.as_deref(), Some("Getty Images/iStockphoto"));
+    }
+
+    #[test]
+    fn test_copyright_info_single_credit() {
+        let info = CopyrightInfo::parse("梦莲湖，加拿大阿尔伯塔省 （© Getty Images）");
+        assert_eq!(info.location, "梦莲湖，加拿大阿尔伯塔省");
+        assert_eq!(info.photographer, None);
+        assert_eq!(info.agency.as_deref(), Some("Getty Images"));
+        assert_eq!(info.region(), Some("加拿大阿尔伯塔省"));
+
+        let info = CopyrightInfo::parse("Tower (old (©) name) (© Jane Doe)");
+        assert_eq!(info.location, "Tower (old (©) name)");
+        assert_eq!(info.photographer.as_deref(), Some("Jane Doe"));
+        assert_eq!(info.agency, None);
+    }
+
+    #[test]
+    fn test_copyright_info_without_credit() {
+        let info = CopyrightInfo::parse("  Somewhere quiet  ");
+        assert_eq!(info.location, "Somewhere quiet");
+        assert_eq!(info.credit(), None);
+
+        let info = CopyrightInfo::parse("Beach (©)");
+        assert_eq!(info.location, "Beach");
+        assert_eq!(info.credit(), None);
+
+        assert_eq!(CopyrightInfo::parse("(© Someone)").region(), None);
+    }
 }
@@ -0,0 +1,83 @@
+//! 出站请求闸门
+//!
+//! 开启 `bing_only_network` 设置后只允许访问 Bing 的域名，版本检查（GitHub）、IP 定位、
+//! 历史归档等其他出站请求都在发起前被拦截，受限网络环境中的用户无需再在外部防火墙中屏蔽本应用。
+//! 检查在 `http_client::send` 中执行，请求都应通过它发送；用户自行配置的备份目标
+//! （WebDAV/S3）属于显式操作，不受此限制。
+
+use log::info;
+use reqwest::Url;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// 被拦截时命令返回的错误码
+pub(crate) const NETWORK_RESTRICTED: &str = "NETWORK_RESTRICTED";
+
+/// Bing 使用的域名（含子域名，如 `cn.bing.com`、`th.bing.net`）
+const BING_DOMAINS: [&str; 2] = ["bing.com", "bing.net"];
+
+static BING_ONLY: AtomicBool = AtomicBool::new(false);
+
+/// 应用设置中的出站限制
+pub(crate) fn configure(bing_only: bool) {
+    if BING_ONLY.swap(bing_only, Ordering::SeqCst) != bing_only {
+        info!(
+            target: "http",
+            "出站请求限制: {}",
+            if bing_only { "仅允许 Bing" } else { "不限制" }
+        );
+    }
+}
+
+fn is_bing_url(url: &str) -> bool {
+    let Ok(url) = Url::parse(url) else {
+        return false;
+    };
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    BING_DOMAINS.iter().any(|domain| {
+        host == *domain
+            || host
+                .strip_suffix(domain)
+                .is_some_and(|prefix| prefix.ends_with('.'))
+    })
+}
+
+fn allowed(bing_only: bool, url: &str) -> bool {
+    !bing_only || is_bing_url(url)
+}
+
+/// 当前设置是否允许访问 `url`（被拦截时记录日志）
+pub(crate) fn is_allowed(url: &str) -> bool {
+    let allowed = allowed(BING_ONLY.load(Ordering::SeqCst), url);
+    if !allowed {
+        info!(target: "http", "已拦截非 Bing 请求: {}", url);
+    }
+    allowed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_bing_url_matches_bing_domains_only() {
+        assert!(is_bing_url("https://www.bing.com/HPImageArchive.aspx"));
+        assert!(is_bing_url("https://cn.bing.com/th?id=OHR.Test_UHD.jpg"));
+        assert!(is_bing_url("https://BING.com./"));
+        assert!(is_bing_url("https://th.bing.net/th?id=OHR.Test.jpg"));
+        assert!(!is_bing_url("https://notbing.com/"));
+        assert!(!is_bing_url("https://bing.com.example.com/"));
+        assert!(!is_bing_url("https://api.github.com/repos"));
+        assert!(!is_bing_url("not a url"));
+    }
+
+    #[test]
+    fn test_allowed_only_restricts_when_enabled() {
+        let github = "https://api.github.com/repos/qiyuey/bing-wallpaper-now/releases";
+        assert!(allowed(false, github));
+        assert!(!allowed(true, github));
+        assert!(allowed(true, "https://www.bing.com/"));
+    }
+}
@@ -0,0 +1,77 @@
+//! 网络环境变化检测
+//!
+//! 长时间运行时，切换 VPN、Wi-Fi 等网络变化会让连接池中的连接失效（或仍指向旧网络解析出的地址），
+//! 后续请求一直失败直到重启应用。这里定期检查访问公网时系统选用的本机地址：对 UDP 套接字调用
+//! `connect` 只会查询路由表而不发送数据，本机地址变化即说明默认路由已切换，此时重建共享 HTTP 客户端，
+//! 新连接会重新解析 DNS。用户也可以通过 `reset_network` 命令手动重置。
+
+use log::info;
+use std::net::{IpAddr, UdpSocket};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::{AppState, http_client};
+
+const NETWORK_WATCH_JOB: &str = "network_watch";
+const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+/// 用于查询路由的公网地址（不会真正发送数据）
+const PROBE_V4: &str = "1.1.1.1:53";
+const PROBE_V6: &str = "[2606:4700:4700::1111]:53";
+
+/// 访问 `target` 时系统选用的本机地址，无可用路由时返回 None
+fn route_address(bind: &str, target: &str) -> Option<IpAddr> {
+    let socket = UdpSocket::bind(bind).ok()?;
+    socket.connect(target).ok()?;
+    socket.local_addr().ok().map(|addr| addr.ip())
+}
+
+/// 当前网络环境的标识：IPv4 与 IPv6 默认路由各自使用的本机地址
+fn network_fingerprint() -> (Option<IpAddr>, Option<IpAddr>) {
+    (
+        route_address("0.0.0.0:0", PROBE_V4),
+        route_address("[::]:0", PROBE_V6),
+    )
+}
+
+/// 启动网络变化检测任务
+pub(crate) fn start_network_watch_task(app: AppHandle) {
+    let scheduler = app.state::<AppState>().scheduler.clone();
+    scheduler.spawn(NETWORK_WATCH_JOB, async move {
+        let mut previous = network_fingerprint();
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+            let current = network_fingerprint();
+            if current != previous {
+                info!(
+                    target: "http",
+                    "检测到网络变化: {:?} -> {:?}",
+                    previous,
+                    current
+                );
+                http_client::reset_clients("网络变化");
+                previous = current;
+            }
+        }
+    });
+}
+
+/// 手动重置网络：丢弃所有 HTTP 连接，后续请求重新建立连接并解析 DNS
+#[tauri::command]
+pub(crate) async fn reset_network() -> Result<(), String> {
+    http_client::reset_clients("用户手动重置");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_route_address_reports_local_address() {
+        assert_eq!(
+            route_address("127.0.0.1:0", "127.0.0.1:9"),
+            Some(IpAddr::from([127, 0, 0, 1]))
+        );
+        assert_eq!(route_address("127.0.0.1:0", "not an address"), None);
+    }
+}
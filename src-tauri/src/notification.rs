@@ -95,7 +95,7 @@ pub(crate) fn build_wallpaper_notification_content(
 }
 
 /// 与 WallpaperCard 保持一致：版权括号外的部分作为副标题。
-fn card_subtitle(copyright: &str) -> String {
+pub(crate) fn card_subtitle(copyright: &str) -> String {
     let copyright = copyright.trim();
     let Some(open_index) = copyright.find('(') else {
         return copyright.to_string();
@@ -272,6 +272,10 @@ mod tests {
             copyright_link: String::new(),
             end_date: date.to_string(),
             urlbase: String::new(),
+            resolution: None,
+            portrait_available: None,
+            watermark_free: None,
+            recompression: None,
         }
     }
 
@@ -0,0 +1,215 @@
+//! 离线占位壁纸
+//!
+//! 首次启动时如果无法访问 Bing（如处于需要登录的公共 Wi-Fi 后），图库会一直为空。此时生成几张
+//! 渐变风景占位图写入壁纸目录和索引，让图库有内容可看；开启 `placeholder_wallpaper` 设置时还会
+//! 设为桌面壁纸。占位图由程序绘制而不是随安装包分发，不涉及版权。
+//!
+//! 占位图使用固定的早期日期作为键，排在真实壁纸之后；第一次成功保存 Bing 元数据后即从索引和磁盘中删除，
+//! 随后的更新流程会照常应用最新的 Bing 壁纸。
+
+use anyhow::{Context, Result};
+use log::{info, warn};
+use std::path::Path;
+
+use crate::models::LocalWallpaper;
+use crate::storage;
+
+/// 占位图尺寸
+pub(crate) const WIDTH: u32 = 1920;
+pub(crate) const HEIGHT: u32 = 1080;
+const JPEG_QUALITY: u8 = 85;
+
+/// 一张占位图：索引键、中英文名称、天空顶部/地平线/地面颜色
+struct Placeholder {
+    end_date: &'static str,
+    title_zh: &'static str,
+    title_en: &'static str,
+    sky_top: [u8; 3],
+    horizon: [u8; 3],
+    ground: [u8; 3],
+}
+
+const PLACEHOLDERS: [Placeholder; 3] = [
+    Placeholder {
+        end_date: "19700103",
+        title_zh: "黎明",
+        title_en: "Dawn",
+        sky_top: [44, 62, 122],
+        horizon: [246, 177, 122],
+        ground: [38, 44, 72],
+    },
+    Placeholder {
+        end_date: "19700102",
+        title_zh: "海岸",
+        title_en: "Coast",
+        sky_top: [34, 98, 160],
+        horizon: [176, 222, 236],
+        ground: [22, 74, 96],
+    },
+    Placeholder {
+        end_date: "19700101",
+        title_zh: "黄昏",
+        title_en: "Dusk",
+        sky_top: [36, 24, 66],
+        horizon: [222, 98, 84],
+        ground: [28, 18, 40],
+    },
+];
+
+/// 是否为占位壁纸的索引键
+pub(crate) fn is_placeholder(end_date: &str) -> bool {
+    PLACEHOLDERS.iter().any(|p| p.end_date == end_date)
+}
+
+fn lerp(from: [u8; 3], to: [u8; 3], t: f32) -> [u8; 3] {
+    let mix = |a: u8, b: u8| (f32::from(a) + (f32::from(b) - f32::from(a)) * t).round() as u8;
+    [
+        mix(from[0], to[0]),
+        mix(from[1], to[1]),
+        mix(from[2], to[2]),
+    ]
+}
+
+/// 绘制占位图：天空渐变加一道起伏的山脊
+///
+/// 测试壁纸（见 `test_wallpapers` 模块）也使用同样的画法。
+pub(crate) fn render_scene(sky_top: [u8; 3], horizon: [u8; 3], ground: [u8; 3]) -> image::RgbImage {
+    let horizon_y = HEIGHT as f32 * 0.68;
+    image::RgbImage::from_fn(WIDTH, HEIGHT, |x, y| {
+        let (x, y) = (x as f32, y as f32);
+        let ridge = horizon_y
+            + 36.0 * (x / 310.0).sin()
+            + 18.0 * (x / 97.0 + 1.3).sin()
+            + 8.0 * (x / 41.0 + 0.4).sin();
+        let color = if y < ridge {
+            lerp(sky_top, horizon, (y / ridge).powf(1.6))
+        } else {
+            let depth = (y - ridge) / (HEIGHT as f32 - ridge).max(1.0);
+            lerp(lerp(ground, horizon, 0.25), ground, depth)
+        };
+        image::Rgb(color)
+    })
+}
+
+/// 以 JPEG 写入图片
+pub(crate) fn write_jpeg(path: &Path, image: &image::RgbImage) -> Result<()> {
+    let mut bytes = Vec::new();
+    image::codecs::jpeg::JpegEncoder::new_with_quality(&mut bytes, JPEG_QUALITY)
+        .encode_image(image)
+        .context("Failed to encode placeholder")?;
+    std::fs::write(path, bytes).context("Failed to write placeholder")
+}
+
+fn render(placeholder: &Placeholder) -> image::RgbImage {
+    render_scene(placeholder.sky_top, placeholder.horizon, placeholder.ground)
+}
+
+/// 占位图的索引条目
+fn metadata(placeholder: &Placeholder, resolved_language: &str) -> LocalWallpaper {
+    let (title, copyright) = if resolved_language == "zh-CN" {
+        (
+            format!("离线占位壁纸 · {}", placeholder.title_zh),
+            "由 Bing Wallpaper Now 生成，联网后将替换为 Bing 壁纸".to_string(),
+        )
+    } else {
+        (
+            format!("Offline Placeholder · {}", placeholder.title_en),
+            "Generated by Bing Wallpaper Now; replaced by Bing wallpapers once online".to_string(),
+        )
+    };
+    LocalWallpaper {
+        title,
+        copyright,
+        copyright_link: String::new(),
+        end_date: placeholder.end_date.to_string(),
+        urlbase: String::new(),
+        resolution: Some(format!("{WIDTH}x{HEIGHT}")),
+        portrait_available: None,
+        watermark_free: None,
+        recompression: None,
+    }
+}
+
+/// 写入尚不存在的占位图文件
+fn write_images(wallpaper_dir: &Path) -> Result<()> {
+    for placeholder in &PLACEHOLDERS {
+        let path = storage::get_wallpaper_path(wallpaper_dir, placeholder.end_date);
+        if path.exists() {
+            continue;
+        }
+        write_jpeg(&path, &render(placeholder))?;
+    }
+    Ok(())
+}
+
+/// 生成占位图并写入 `mkt` 的索引，返回是否新写入了索引条目
+pub(crate) async fn seed(wallpaper_dir: &Path, mkt: &str, resolved_language: &str) -> Result<bool> {
+    let dir = wallpaper_dir.to_path_buf();
+    tokio::task::spawn_blocking(move || write_images(&dir))
+        .await
+        .context("Placeholder task failed")??;
+    let entries = PLACEHOLDERS
+        .iter()
+        .map(|placeholder| metadata(placeholder, resolved_language))
+        .collect();
+    let result = storage::save_wallpapers_metadata(entries, wallpaper_dir, mkt).await?;
+    if result.new_count > 0 {
+        info!(target: "placeholder", "无法获取 Bing 壁纸，已生成 {} 张离线占位壁纸", result.new_count);
+    }
+    Ok(result.new_count > 0)
+}
+
+/// 删除占位壁纸的索引条目和图片文件（没有占位壁纸时不做任何事）
+pub(crate) async fn remove(wallpaper_dir: &Path) {
+    let keys: Vec<String> = PLACEHOLDERS
+        .iter()
+        .map(|placeholder| placeholder.end_date.to_string())
+        .collect();
+    match storage::remove_index_entries(wallpaper_dir, &keys).await {
+        Ok(true) => info!(target: "placeholder", "已获取 Bing 壁纸，移除离线占位壁纸"),
+        Ok(false) => {}
+        Err(e) => warn!(target: "placeholder", "移除占位壁纸索引失败: {}", e),
+    }
+    for key in &keys {
+        let path = storage::get_wallpaper_path(wallpaper_dir, key);
+        if path.exists()
+            && let Err(e) = tokio::fs::remove_file(&path).await
+        {
+            warn!(target: "placeholder", "删除占位壁纸失败 {}: {}", path.display(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_placeholder_keys_sort_before_real_dates() {
+        for placeholder in &PLACEHOLDERS {
+            assert!(is_placeholder(placeholder.end_date));
+            assert!(chrono::NaiveDate::parse_from_str(placeholder.end_date, "%Y%m%d").is_ok());
+            assert!(placeholder.end_date < "20090101");
+        }
+        assert!(!is_placeholder("20240101"));
+    }
+
+    #[test]
+    fn test_render_draws_sky_above_ridge() {
+        let image = render(&PLACEHOLDERS[0]);
+        assert_eq!(image.dimensions(), (WIDTH, HEIGHT));
+        assert_eq!(image.get_pixel(0, 0).0, PLACEHOLDERS[0].sky_top);
+        assert_eq!(image.get_pixel(0, HEIGHT - 1).0, PLACEHOLDERS[0].ground);
+    }
+
+    #[test]
+    fn test_metadata_is_localized() {
+        assert_eq!(
+            metadata(&PLACEHOLDERS[2], "zh-CN").title,
+            "离线占位壁纸 · 黄昏"
+        );
+        let wallpaper = metadata(&PLACEHOLDERS[2], "en-US");
+        assert_eq!(wallpaper.title, "Offline Placeholder · Dusk");
+        assert!(wallpaper.urlbase.is_empty());
+    }
+}
@@ -0,0 +1,77 @@
+//! 企业部署的设置策略
+//!
+//! 管理员可以在系统级目录放置 `policy.json`，为设置提供默认值或锁定部分设置
+//! （如壁纸目录、关闭自动更新）。策略在首次使用时读取一次，之后不再变化；
+//! 合并规则见 `SettingsPolicy`。
+//!
+//! - Windows：`%ProgramData%\BingWallpaperNow\policy.json`
+//! - macOS：`/Library/Application Support/BingWallpaperNow/policy.json`
+//! - Linux：`/etc/bing-wallpaper-now/policy.json`
+
+use log::{info, warn};
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+use crate::models::{SettingsPolicy, SettingsPolicyStatus};
+
+const POLICY_FILE: &str = "policy.json";
+
+/// 已加载的策略及其来源路径
+static POLICY: LazyLock<(SettingsPolicy, Option<PathBuf>)> = LazyLock::new(load_policy);
+
+fn policy_path() -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    let dir = std::env::var_os("ProgramData")
+        .map(PathBuf::from)?
+        .join("BingWallpaperNow");
+    #[cfg(target_os = "macos")]
+    let dir = PathBuf::from("/Library/Application Support/BingWallpaperNow");
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let dir = PathBuf::from("/etc/bing-wallpaper-now");
+    Some(dir.join(POLICY_FILE))
+}
+
+fn load_policy() -> (SettingsPolicy, Option<PathBuf>) {
+    let Some(path) = policy_path().filter(|path| path.is_file()) else {
+        return (SettingsPolicy::default(), None);
+    };
+    let parsed = std::fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|content| {
+            serde_json::from_str::<SettingsPolicy>(&content).map_err(|e| e.to_string())
+        });
+    match parsed {
+        Ok(policy) => {
+            for key in policy.unknown_keys() {
+                warn!(target: "policy", "策略中的设置 {} 不受支持，已忽略", key);
+            }
+            info!(
+                target: "policy",
+                "已加载设置策略 {}：默认值 {} 项，锁定 {:?}",
+                path.display(),
+                policy.defaults.len(),
+                policy.locked_keys()
+            );
+            (policy, Some(path))
+        }
+        Err(e) => {
+            warn!(target: "policy", "读取设置策略 {} 失败: {}，忽略策略", path.display(), e);
+            (SettingsPolicy::default(), None)
+        }
+    }
+}
+
+/// 当前生效的设置策略（没有策略文件时为空策略）
+pub(crate) fn current() -> &'static SettingsPolicy {
+    &POLICY.0
+}
+
+/// 获取设置策略状态（前端据此把锁定的设置显示为只读）
+#[tauri::command]
+pub(crate) fn get_settings_policy() -> SettingsPolicyStatus {
+    let (policy, path) = &*POLICY;
+    SettingsPolicyStatus {
+        path: path.as_ref().map(|path| path.to_string_lossy().to_string()),
+        locked: policy.locked_keys(),
+    }
+}
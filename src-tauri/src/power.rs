@@ -0,0 +1,192 @@
+//! 电源状态检测
+//!
+//! 开启 `defer_downloads_on_battery` 后，使用电池供电时推迟后台的壁纸图片下载（元数据照常获取），
+//! 接通电源后再补齐最新壁纸。用户在画廊中主动查看触发的按需下载不受影响。
+//!
+//! - macOS：`IOPSCopyPowerSourcesInfo` + `IOPSGetProvidingPowerSourceType`
+//! - Windows：`GetSystemPowerStatus`
+//! - Linux：读取 `/sys/class/power_supply`
+//!
+//! 无法检测时视为接通电源，不推迟下载。
+
+use log::info;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::{AppState, update_cycle};
+
+/// 调度器中电源监听任务的名称
+pub(crate) const POWER_WATCH_JOB: &str = "power_watch";
+
+/// 检查电源状态的间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 当前是否使用电池供电（平台不支持检测或检测失败时返回 None）
+#[cfg(target_os = "macos")]
+fn is_on_battery() -> Option<bool> {
+    use objc2_foundation::NSString;
+    use std::ffi::c_void;
+
+    #[link(name = "IOKit", kind = "framework")]
+    unsafe extern "C" {
+        fn IOPSCopyPowerSourcesInfo() -> *const c_void;
+        fn IOPSGetProvidingPowerSourceType(snapshot: *const c_void) -> *const c_void;
+    }
+    #[link(name = "CoreFoundation", kind = "framework")]
+    unsafe extern "C" {
+        fn CFRelease(cf: *const c_void);
+    }
+
+    // SAFETY: snapshot 由 Copy 函数返回，使用完毕后释放；电源类型字符串归 snapshot 所有，
+    // CFString 与 NSString 可免费桥接，在释放 snapshot 前转换为 Rust 字符串
+    unsafe {
+        let snapshot = IOPSCopyPowerSourcesInfo();
+        if snapshot.is_null() {
+            return None;
+        }
+        let source_type = IOPSGetProvidingPowerSourceType(snapshot);
+        let result = (!source_type.is_null())
+            .then(|| (*source_type.cast::<NSString>()).to_string() == "Battery Power");
+        CFRelease(snapshot);
+        result
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn is_on_battery() -> Option<bool> {
+    use windows_sys::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    /// BatteryFlag：没有电池
+    const NO_SYSTEM_BATTERY: u8 = 128;
+
+    // SAFETY: 结构体为纯数据，全零是合法的初始值
+    let mut status: SYSTEM_POWER_STATUS = unsafe { std::mem::zeroed() };
+    // SAFETY: status 指向有效的可写内存
+    if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+        return None;
+    }
+    if status.BatteryFlag == NO_SYSTEM_BATTERY {
+        return Some(false);
+    }
+    match status.ACLineStatus {
+        0 => Some(true),
+        1 => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn is_on_battery() -> Option<bool> {
+    sysfs::is_on_battery(std::path::Path::new("/sys/class/power_supply"))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod sysfs {
+    use std::path::Path;
+
+    fn read_attribute(supply: &Path, name: &str) -> Option<String> {
+        std::fs::read_to_string(supply.join(name))
+            .ok()
+            .map(|value| value.trim().to_string())
+    }
+
+    /// 任一外接电源在线即视为接通电源；没有外接电源信息时根据电池是否放电判断
+    pub(super) fn is_on_battery(power_supply_dir: &Path) -> Option<bool> {
+        let mut has_mains = false;
+        let mut discharging = false;
+        for entry in std::fs::read_dir(power_supply_dir).ok()?.flatten() {
+            let supply = entry.path();
+            match read_attribute(&supply, "type").as_deref() {
+                Some("Mains" | "USB") => {
+                    has_mains = true;
+                    if read_attribute(&supply, "online").as_deref() == Some("1") {
+                        return Some(false);
+                    }
+                }
+                Some("Battery") => {
+                    discharging |=
+                        read_attribute(&supply, "status").as_deref() == Some("Discharging");
+                }
+                _ => {}
+            }
+        }
+        (has_mains || discharging).then_some(true)
+    }
+}
+
+/// 当前是否应推迟后台图片下载（设置已开启且正在使用电池供电）
+pub(crate) async fn downloads_deferred(app: &AppHandle) -> bool {
+    if !app
+        .state::<AppState>()
+        .settings
+        .read()
+        .await
+        .defer_downloads_on_battery
+    {
+        return false;
+    }
+    is_on_battery() == Some(true)
+}
+
+/// 启动电源监听任务：从电池切换到外接电源时补齐被推迟的最新壁纸
+pub(crate) fn start_power_watch_task(app: AppHandle) {
+    let scheduler = app.state::<AppState>().scheduler.clone();
+    scheduler.spawn(POWER_WATCH_JOB, async move {
+        let mut was_deferred = downloads_deferred(&app).await;
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+            let deferred = downloads_deferred(&app).await;
+            if was_deferred && !deferred {
+                info!(target: "power", "已接通电源，恢复被推迟的壁纸下载");
+                update_cycle::resume_deferred_downloads(&app).await;
+            }
+            was_deferred = deferred;
+        }
+    });
+}
+
+#[cfg(all(test, not(any(target_os = "macos", target_os = "windows"))))]
+mod tests {
+    use super::sysfs::is_on_battery;
+    use std::path::{Path, PathBuf};
+
+    fn power_supply_dir(supplies: &[(&str, &[(&str, &str)])]) -> PathBuf {
+        let unique = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("bw_power_{unique}"));
+        for (name, attributes) in supplies {
+            std::fs::create_dir_all(dir.join(name)).unwrap();
+            for (attribute, value) in *attributes {
+                std::fs::write(dir.join(name).join(attribute), format!("{value}\n")).unwrap();
+            }
+        }
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_sysfs_power_state() {
+        let on_ac = power_supply_dir(&[
+            ("AC", &[("type", "Mains"), ("online", "1")]),
+            ("BAT0", &[("type", "Battery"), ("status", "Charging")]),
+        ]);
+        assert_eq!(is_on_battery(&on_ac), Some(false));
+
+        let unplugged = power_supply_dir(&[
+            ("AC", &[("type", "Mains"), ("online", "0")]),
+            ("BAT0", &[("type", "Battery"), ("status", "Discharging")]),
+        ]);
+        assert_eq!(is_on_battery(&unplugged), Some(true));
+
+        // 台式机：没有任何电源信息
+        let desktop = power_supply_dir(&[]);
+        assert_eq!(is_on_battery(&desktop), None);
+        assert_eq!(is_on_battery(Path::new("/nonexistent/power_supply")), None);
+
+        for dir in [on_ac, unplugged, desktop] {
+            let _ = std::fs::remove_dir_all(dir);
+        }
+    }
+}
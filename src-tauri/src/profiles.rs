@@ -0,0 +1,176 @@
+//! 壁纸配置方案（profiles）
+//!
+//! 用户可以为不同场景（如工作/家里）保存独立的壁纸目录、市场和下载分辨率，
+//! 通过托盘子菜单或 `switch_profile` 命令一键切换。
+//!
+//! - 方案保存在独立的 `profiles.json` store 中，启动时加载到 `AppState::profiles`
+//! - 切换时把方案覆盖到当前设置上，复用 `update_settings` 的流程切换壁纸目录、
+//!   释放旧目录的 IndexManager 并广播新设置
+//! - 使用方案期间直接修改设置中的目录/市场/分辨率，会同步回当前方案
+
+use anyhow::Result;
+use log::{info, warn};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_store::StoreExt;
+
+use crate::models::{AppSettings, ProfilesConfig, WallpaperProfile};
+use crate::{AppState, commands, events, get_effective_mkt, storage, tray, update_cycle};
+
+const PROFILES_STORE_FILE: &str = "profiles.json";
+const PROFILES_KEY: &str = "profiles";
+
+/// 从 store 加载配置方案（不存在时返回空配置）
+pub(crate) fn load_profiles(app: &AppHandle) -> Result<ProfilesConfig> {
+    let store = app
+        .store(PROFILES_STORE_FILE)
+        .map_err(|e| anyhow::anyhow!("Failed to access profiles store: {}", e))?;
+    match store.get(PROFILES_KEY) {
+        Some(value) => Ok(serde_json::from_value(value)?),
+        None => Ok(ProfilesConfig::default()),
+    }
+}
+
+fn save_profiles(app: &AppHandle, config: &ProfilesConfig) -> Result<()> {
+    let store = app
+        .store(PROFILES_STORE_FILE)
+        .map_err(|e| anyhow::anyhow!("Failed to access profiles store: {}", e))?;
+    store.set(PROFILES_KEY, serde_json::to_value(config)?);
+    store
+        .save()
+        .map_err(|e| anyhow::anyhow!("Failed to save profiles store: {}", e))
+}
+
+/// 修改内存中的方案并持久化，失败时内存保持不变
+async fn modify_profiles(
+    app: &AppHandle,
+    modify: impl FnOnce(&mut ProfilesConfig) -> Result<(), String>,
+) -> Result<ProfilesConfig, String> {
+    let state = app.state::<AppState>();
+    let mut profiles = state.profiles.lock().await;
+    let mut updated = profiles.clone();
+    modify(&mut updated)?;
+    save_profiles(app, &updated).map_err(|e| format!("保存配置方案失败: {e}"))?;
+    *profiles = updated.clone();
+    Ok(updated)
+}
+
+/// 把直接修改的设置同步到当前方案（`update_settings` 成功后调用）
+pub(crate) async fn sync_active_profile(app: &AppHandle, settings: &AppSettings) {
+    let Some(active) = app.state::<AppState>().profiles.lock().await.active.clone() else {
+        return;
+    };
+    let synced = WallpaperProfile::from_settings(&active, settings);
+    let result = modify_profiles(app, |config| {
+        config.upsert(synced);
+        Ok(())
+    })
+    .await;
+    if let Err(e) = result {
+        warn!(target: "profiles", "同步当前方案 {} 失败: {}", active, e);
+    }
+}
+
+/// 退出当前方案（保留方案本身），重置应用时调用，避免默认设置被同步到方案中
+pub(crate) async fn clear_active_profile(app: &AppHandle) {
+    let result = modify_profiles(app, |config| {
+        config.active = None;
+        Ok(())
+    })
+    .await;
+    if let Err(e) = result {
+        warn!(target: "profiles", "清除当前方案失败: {}", e);
+    }
+}
+
+/// 获取全部配置方案
+#[tauri::command]
+pub(crate) async fn get_profiles(
+    state: tauri::State<'_, AppState>,
+) -> Result<ProfilesConfig, String> {
+    Ok(state.profiles.lock().await.clone())
+}
+
+/// 把当前设置中的目录、市场和分辨率保存为方案（同名方案会被覆盖）
+///
+/// 名称无效时返回 "INVALID_NAME"。
+#[tauri::command]
+pub(crate) async fn save_profile(name: String, app: AppHandle) -> Result<ProfilesConfig, String> {
+    let name = name.trim().to_string();
+    if !WallpaperProfile::is_valid_name(&name) {
+        return Err("INVALID_NAME".to_string());
+    }
+    let settings = app.state::<AppState>().settings.read().await;
+    let profile = WallpaperProfile::from_settings(&name, &settings);
+    let config = modify_profiles(&app, |config| {
+        config.upsert(profile);
+        Ok(())
+    })
+    .await?;
+    info!(target: "profiles", "已保存配置方案: {}", name);
+    let _ = tray::update_tray_menu(&app).await;
+    Ok(config)
+}
+
+/// 删除方案（不影响当前设置）。方案不存在时返回 "PROFILE_NOT_FOUND"。
+#[tauri::command]
+pub(crate) async fn delete_profile(name: String, app: AppHandle) -> Result<ProfilesConfig, String> {
+    let config = modify_profiles(&app, |config| {
+        if config.remove(&name) {
+            Ok(())
+        } else {
+            Err("PROFILE_NOT_FOUND".to_string())
+        }
+    })
+    .await?;
+    info!(target: "profiles", "已删除配置方案: {}", name);
+    let _ = tray::update_tray_menu(&app).await;
+    Ok(config)
+}
+
+/// 切换到指定方案
+///
+/// 方案不存在时返回 "PROFILE_NOT_FOUND"，更新进行中时返回 "UPDATE_IN_PROGRESS"
+/// （避免更新循环把壁纸写入即将切走的目录）。
+#[tauri::command]
+pub(crate) async fn switch_profile(name: String, app: AppHandle) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let profile = state
+        .profiles
+        .lock()
+        .await
+        .find(&name)
+        .cloned()
+        .ok_or_else(|| "PROFILE_NOT_FOUND".to_string())?;
+    if state.update_state.lock().await.is_busy() {
+        return Err("UPDATE_IN_PROGRESS".to_string());
+    }
+
+    // 读取当前设置到记录当前方案都在写入锁内，并发的设置修改不会被方案覆盖或同步到错误的方案
+    let writes = state.settings.lock_writes().await;
+    let new_settings = profile.apply_to(&state.settings.read().await);
+    commands::settings::apply_settings(new_settings, &state, &app)
+        .await
+        .map_err(|e| e.to_string())?;
+    modify_profiles(&app, |config| {
+        config.active = Some(name.clone());
+        Ok(())
+    })
+    .await?;
+    drop(writes);
+    info!(target: "profiles", "已切换到配置方案: {}", name);
+
+    let wallpaper_dir = state.wallpaper_directory.lock().await.clone();
+    if let Err(e) = storage::ensure_wallpaper_directory(&wallpaper_dir).await {
+        warn!(target: "profiles", "创建方案壁纸目录失败: {}", e);
+    }
+
+    let _ = events::WALLPAPER_UPDATED.emit(&app, &());
+    if let Err(e) = tray::update_tray_menu(&app).await {
+        warn!(target: "profiles", "更新托盘菜单失败: {}", e);
+    }
+
+    // 新目录中还没有壁纸时立即获取
+    let mkt = get_effective_mkt(&state).await;
+    update_cycle::try_trigger_update_if_empty(&app, &mkt).await;
+    Ok(())
+}
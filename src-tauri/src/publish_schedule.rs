@@ -0,0 +1,187 @@
+//! 学习各 mkt 的壁纸发布时间
+//!
+//! Bing 按市场所在时区的零点发布新壁纸，与用户本地零点往往相差数小时，追赶模式下全天按固定间隔
+//! 请求 API 会浪费大量请求。每次发现新日期的壁纸时，记录"上次未发现"与"本次发现"两次检查的中点
+//! （UTC 分钟数），取多次观测的中位数作为该 mkt 的典型发布时间；之后追赶模式只在其前后 2 小时内
+//! 密集检查，窗口外稀疏检查。两次检查间隔过长时观测误差太大，直接丢弃。
+
+use chrono::{DateTime, Duration as ChronoDuration, Local, Timelike, Utc};
+use log::info;
+use std::time::Duration;
+use tauri::AppHandle;
+
+use crate::runtime_state;
+
+/// 每个 mkt 保留的观测数量
+const OBSERVATION_LIMIT: usize = 14;
+/// 推算典型发布时间至少需要的观测数量
+const MIN_OBSERVATIONS: usize = 3;
+/// 两次检查间隔超过该值时不记录观测
+const MAX_OBSERVATION_GAP_MINUTES: i64 = 4 * 60;
+/// 发布时间窗口的半宽
+const WINDOW_HALF_WIDTH_MINUTES: i64 = 2 * 60;
+const MINUTES_PER_DAY: i64 = 24 * 60;
+
+/// 当前时刻相对典型发布时间窗口的位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PublishWindow {
+    /// 观测不足，尚未学到发布时间
+    Unknown,
+    /// 处于发布时间前后 2 小时内
+    Inside,
+    /// 不在窗口内，附带距下一个窗口开始的时长
+    Outside(Duration),
+}
+
+/// 由两次检查的时间推算发布时刻（UTC 当日分钟数），间隔过长或时间回退时返回 None
+pub(crate) fn observed_minute(
+    previous_check: DateTime<Local>,
+    seen_at: DateTime<Local>,
+) -> Option<u16> {
+    let gap = seen_at - previous_check;
+    if gap < ChronoDuration::zero() || gap > ChronoDuration::minutes(MAX_OBSERVATION_GAP_MINUTES) {
+        return None;
+    }
+    let midpoint = (previous_check + gap / 2).with_timezone(&Utc);
+    Some((midpoint.hour() * 60 + midpoint.minute()) as u16)
+}
+
+/// 一天中两个分钟数之间的最短距离（跨零点时绕回）
+fn circular_distance(a: u16, b: u16) -> i64 {
+    let diff = (i64::from(a) - i64::from(b)).rem_euclid(MINUTES_PER_DAY);
+    diff.min(MINUTES_PER_DAY - diff)
+}
+
+/// 典型发布时刻：到其他观测距离之和最小的观测（环形中位数），观测不足时返回 None
+pub(crate) fn typical_minute(observations: &[u16]) -> Option<u16> {
+    if observations.len() < MIN_OBSERVATIONS {
+        return None;
+    }
+    observations.iter().copied().min_by_key(|&candidate| {
+        observations
+            .iter()
+            .map(|&other| circular_distance(candidate, other))
+            .sum::<i64>()
+    })
+}
+
+/// 判断 `now` 是否处于以 `publish_minute`（UTC）为中心的发布窗口内
+pub(crate) fn window(now: DateTime<Utc>, publish_minute: Option<u16>) -> PublishWindow {
+    let Some(publish_minute) = publish_minute else {
+        return PublishWindow::Unknown;
+    };
+    let now_minute = i64::from(now.hour() * 60 + now.minute());
+    if circular_distance(now_minute as u16, publish_minute) <= WINDOW_HALF_WIDTH_MINUTES {
+        return PublishWindow::Inside;
+    }
+    let window_start = i64::from(publish_minute) - WINDOW_HALF_WIDTH_MINUTES;
+    let wait_minutes = (window_start - now_minute).rem_euclid(MINUTES_PER_DAY);
+    PublishWindow::Outside(Duration::from_secs(
+        (wait_minutes * 60) as u64 - u64::from(now.second()),
+    ))
+}
+
+/// 追加一次观测，只保留最近的 [`OBSERVATION_LIMIT`] 次
+pub(crate) fn push_observation(observations: &mut Vec<u16>, minute: u16) {
+    observations.push(minute);
+    if observations.len() > OBSERVATION_LIMIT {
+        observations.drain(..observations.len() - OBSERVATION_LIMIT);
+    }
+}
+
+/// 记录 `mkt` 的一次发布观测（间隔过长时忽略）
+pub(crate) fn record(
+    app: &AppHandle,
+    mkt: &str,
+    previous_check: DateTime<Local>,
+    seen_at: DateTime<Local>,
+) -> anyhow::Result<()> {
+    let Some(minute) = observed_minute(previous_check, seen_at) else {
+        return Ok(());
+    };
+    runtime_state::modify_runtime_state(app, |state| {
+        push_observation(
+            state
+                .publish_observations
+                .entry(mkt.to_string())
+                .or_default(),
+            minute,
+        );
+        true
+    })?;
+    info!(
+        target: "auto_update",
+        "记录 {} 的壁纸发布时间观测: UTC {:02}:{:02}",
+        mkt,
+        minute / 60,
+        minute % 60
+    );
+    Ok(())
+}
+
+/// 读取 `mkt` 的典型发布时刻（UTC 当日分钟数）
+pub(crate) fn typical_minute_for(app: &AppHandle, mkt: &str) -> Option<u16> {
+    let state = runtime_state::load_runtime_state(app).ok()?;
+    typical_minute(state.publish_observations.get(mkt)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn utc(hour: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, 1, hour, min, 0).unwrap()
+    }
+
+    #[test]
+    fn test_observed_minute_uses_midpoint_and_rejects_long_gaps() {
+        let previous = utc(7, 0).with_timezone(&Local);
+        assert_eq!(
+            observed_minute(previous, utc(8, 0).with_timezone(&Local)),
+            Some(7 * 60 + 30)
+        );
+        assert_eq!(
+            observed_minute(previous, utc(12, 0).with_timezone(&Local)),
+            None
+        );
+        assert_eq!(
+            observed_minute(previous, utc(6, 0).with_timezone(&Local)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_typical_minute_wraps_around_midnight() {
+        assert_eq!(typical_minute(&[480, 490]), None);
+        assert_eq!(typical_minute(&[480, 495, 470, 900]), Some(480));
+        // 23:50、00:10、00:00 的中位数是 00:00，而不是算术平均的 08:00
+        assert_eq!(typical_minute(&[1430, 10, 0]), Some(0));
+    }
+
+    #[test]
+    fn test_window_inside_and_outside() {
+        assert_eq!(window(utc(8, 0), None), PublishWindow::Unknown);
+        assert_eq!(window(utc(9, 0), Some(8 * 60)), PublishWindow::Inside);
+        assert_eq!(window(utc(23, 0), Some(30)), PublishWindow::Inside);
+        assert_eq!(
+            window(utc(1, 0), Some(8 * 60)),
+            PublishWindow::Outside(Duration::from_secs(5 * 3600))
+        );
+        // 窗口已过，等待到次日窗口开始
+        assert_eq!(
+            window(utc(11, 0), Some(8 * 60)),
+            PublishWindow::Outside(Duration::from_secs(19 * 3600))
+        );
+    }
+
+    #[test]
+    fn test_push_observation_keeps_recent() {
+        let mut observations = Vec::new();
+        for minute in 0..20 {
+            push_observation(&mut observations, minute);
+        }
+        assert_eq!(observations.len(), OBSERVATION_LIMIT);
+        assert_eq!(observations[0], 6);
+    }
+}
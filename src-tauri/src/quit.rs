@@ -0,0 +1,201 @@
+//! 退出确认
+//!
+//! 托盘"退出"和 macOS 的 Cmd+Q 会让后台自动更新一起停止，而用户往往只是想关掉窗口。
+//! 这两个入口统一交给 [`request_quit`]，按设置 `quit_behavior` 处理：
+//! - "ask"：弹出对话框，可选择隐藏到托盘（继续在后台运行）或真正退出
+//! - "quit"：直接退出
+//! - "hide"：只隐藏主窗口
+//!
+//! 对话框中提示可以在设置中记住选择，之后不再询问。
+
+use log::info;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::{
+    DialogExt, MessageDialogButtons, MessageDialogKind, MessageDialogResult,
+};
+
+use crate::AppState;
+
+/// macOS 应用菜单中替换系统"退出"项的菜单 ID
+#[cfg(target_os = "macos")]
+pub(crate) const APP_QUIT_MENU_ID: &str = "app_quit";
+
+/// 对话框正在显示时忽略重复的退出请求（如连按 Cmd+Q）
+static ASKING: AtomicBool = AtomicBool::new(false);
+
+/// 退出请求的处理结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum QuitAction {
+    Quit,
+    Hide,
+    Cancel,
+}
+
+/// 设置中记住的选择，"ask" 或未知值返回 None（需要询问）
+fn remembered_action(quit_behavior: &str) -> Option<QuitAction> {
+    match quit_behavior {
+        "quit" => Some(QuitAction::Quit),
+        "hide" => Some(QuitAction::Hide),
+        _ => None,
+    }
+}
+
+/// 退出确认对话框文本
+struct QuitDialogTexts {
+    title: &'static str,
+    message: &'static str,
+    quit: &'static str,
+    hide: &'static str,
+    cancel: &'static str,
+}
+
+fn quit_dialog_texts(resolved_language: &str) -> QuitDialogTexts {
+    if resolved_language == "zh-CN" {
+        QuitDialogTexts {
+            title: "退出 Bing Wallpaper Now？",
+            message: "退出后将不再自动更新壁纸。\n\n如果只是想关闭窗口，可以隐藏到托盘，应用会继续在后台运行。\n\n可在 设置 → 退出时 中记住你的选择。",
+            quit: "退出",
+            hide: "隐藏到托盘",
+            cancel: "取消",
+        }
+    } else {
+        QuitDialogTexts {
+            title: "Quit Bing Wallpaper Now?",
+            message: "Wallpapers will no longer update automatically after quitting.\n\nIf you only want to close the window, hide it to the tray and the app keeps running in the background.\n\nYou can remember your choice in Settings → When Quitting.",
+            quit: "Quit",
+            hide: "Hide to Tray",
+            cancel: "Cancel",
+        }
+    }
+}
+
+/// 对话框按钮 → 处理结果（自定义按钮在不同平台上可能返回按钮文本或 Yes/No）
+fn action_from_dialog(result: &MessageDialogResult, texts: &QuitDialogTexts) -> QuitAction {
+    match result {
+        MessageDialogResult::Yes => QuitAction::Quit,
+        MessageDialogResult::No => QuitAction::Hide,
+        MessageDialogResult::Custom(text) if text == texts.quit => QuitAction::Quit,
+        MessageDialogResult::Custom(text) if text == texts.hide => QuitAction::Hide,
+        _ => QuitAction::Cancel,
+    }
+}
+
+fn perform(app: &AppHandle, action: QuitAction) {
+    match action {
+        QuitAction::Quit => {
+            info!(target: "quit", "退出应用");
+            app.exit(0);
+        }
+        QuitAction::Hide => {
+            info!(target: "quit", "隐藏到托盘，应用继续在后台运行");
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.hide();
+            }
+        }
+        QuitAction::Cancel => {}
+    }
+}
+
+/// 处理用户主动发起的退出（托盘"退出"、macOS Cmd+Q）
+pub(crate) fn request_quit(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let (quit_behavior, language) = {
+            let settings = app.state::<AppState>().settings.read().await;
+            (
+                settings.quit_behavior.clone(),
+                settings.resolved_language.clone(),
+            )
+        };
+        if let Some(action) = remembered_action(&quit_behavior) {
+            perform(&app, action);
+            return;
+        }
+        if ASKING.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let texts = quit_dialog_texts(&language);
+        app.dialog()
+            .message(texts.message)
+            .title(texts.title)
+            .kind(MessageDialogKind::Info)
+            .buttons(MessageDialogButtons::YesNoCancelCustom(
+                texts.quit.to_string(),
+                texts.hide.to_string(),
+                texts.cancel.to_string(),
+            ))
+            .show_with_result({
+                let app = app.clone();
+                move |result| {
+                    ASKING.store(false, Ordering::SeqCst);
+                    perform(&app, action_from_dialog(&result, &texts));
+                }
+            });
+    });
+}
+
+/// macOS 应用菜单：在默认菜单基础上，把系统"退出"项换成经过 [`request_quit`] 的同名菜单项
+///
+/// 系统退出项直接终止进程，无法拦截 Cmd+Q。
+#[cfg(target_os = "macos")]
+pub(crate) fn app_menu(app: &AppHandle) -> tauri::Result<tauri::menu::Menu<tauri::Wry>> {
+    use tauri::menu::{Menu, MenuItem, MenuItemKind};
+
+    let menu = Menu::default(app)?;
+    if let Some(MenuItemKind::Submenu(app_submenu)) = menu.items()?.into_iter().next() {
+        let items = app_submenu.items()?;
+        if let Some(MenuItemKind::Predefined(quit)) = items.last() {
+            let text = quit.text()?;
+            app_submenu.remove_at(items.len() - 1)?;
+            app_submenu.append(&MenuItem::with_id(
+                app,
+                APP_QUIT_MENU_ID,
+                text,
+                true,
+                Some("CmdOrCtrl+Q"),
+            )?)?;
+        }
+    }
+    Ok(menu)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remembered_action() {
+        assert_eq!(remembered_action("quit"), Some(QuitAction::Quit));
+        assert_eq!(remembered_action("hide"), Some(QuitAction::Hide));
+        assert_eq!(remembered_action("ask"), None);
+        assert_eq!(remembered_action(""), None);
+    }
+
+    #[test]
+    fn test_action_from_dialog() {
+        let texts = quit_dialog_texts("en-US");
+        let custom = |text: &str| MessageDialogResult::Custom(text.to_string());
+        assert_eq!(
+            action_from_dialog(&custom("Quit"), &texts),
+            QuitAction::Quit
+        );
+        assert_eq!(
+            action_from_dialog(&custom("Hide to Tray"), &texts),
+            QuitAction::Hide
+        );
+        assert_eq!(
+            action_from_dialog(&custom("Cancel"), &texts),
+            QuitAction::Cancel
+        );
+        assert_eq!(
+            action_from_dialog(&MessageDialogResult::Yes, &texts),
+            QuitAction::Quit
+        );
+        assert_eq!(
+            action_from_dialog(&MessageDialogResult::Cancel, &texts),
+            QuitAction::Cancel
+        );
+    }
+}
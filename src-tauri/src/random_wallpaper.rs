@@ -0,0 +1,144 @@
+//! 随机应用存档壁纸
+//!
+//! 从当前 mkt 已下载到本地的壁纸中随机挑选一张设为桌面壁纸，跳过最近若干天的壁纸、离线占位图和
+//! 当前正在使用的壁纸。设置流程与手动点选相同（`set_desktop_wallpaper`），因此同样会记录
+//! `manually_set_latest_wallpapers`，每日自动应用不会随即用最新壁纸覆盖它，直到有新壁纸发布。
+
+use chrono::{Days, NaiveDate};
+use log::info;
+
+use crate::models::LocalWallpaper;
+use crate::{AppState, get_effective_mkt, placeholder, storage};
+
+/// 托盘菜单使用的排除天数
+pub(crate) const DEFAULT_EXCLUDE_RECENT_DAYS: u32 = 7;
+const MAX_EXCLUDE_RECENT_DAYS: u32 = 3650;
+
+/// 随机数（不依赖额外的 crate：每个 `RandomState` 使用不同的随机密钥）
+fn random_u64() -> u64 {
+    use std::hash::{BuildHasher, Hasher};
+
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    hasher.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos(),
+    );
+    hasher.finish()
+}
+
+/// 可供随机挑选的壁纸：早于 `today` 前 `exclude_recent_days` 天，且不是占位图或当前壁纸
+fn candidates<'a>(
+    wallpapers: &'a [LocalWallpaper],
+    exclude_recent_days: u32,
+    today: NaiveDate,
+    current: Option<&str>,
+) -> Vec<&'a str> {
+    let cutoff = today
+        .checked_sub_days(Days::new(u64::from(exclude_recent_days)))
+        .unwrap_or(NaiveDate::MIN);
+    wallpapers
+        .iter()
+        .map(|wallpaper| wallpaper.end_date.as_str())
+        .filter(|end_date| !placeholder::is_placeholder(end_date) && Some(*end_date) != current)
+        .filter(|end_date| {
+            NaiveDate::parse_from_str(end_date, "%Y%m%d").is_ok_and(|date| date <= cutoff)
+        })
+        .collect()
+}
+
+/// 随机挑选一张本地壁纸并设为桌面壁纸，返回选中壁纸的日期
+///
+/// `exclude_recent_days` 超过 3650 时返回 "INVALID_EXCLUDE_RECENT_DAYS"，没有符合条件的本地壁纸时
+/// 返回 "NO_CANDIDATES"。
+#[tauri::command]
+pub(crate) async fn apply_random_wallpaper(
+    exclude_recent_days: u32,
+    state: tauri::State<'_, AppState>,
+    app: tauri::AppHandle,
+) -> Result<String, String> {
+    if exclude_recent_days > MAX_EXCLUDE_RECENT_DAYS {
+        return Err("INVALID_EXCLUDE_RECENT_DAYS".to_string());
+    }
+    let wallpaper_dir = state.wallpaper_directory.lock().await.clone();
+    let mkt = get_effective_mkt(&state).await;
+    let wallpapers = storage::get_local_wallpapers(&wallpaper_dir, &mkt)
+        .await
+        .map_err(|e| format!("Failed to load wallpapers: {}", e))?;
+    let current = state
+        .current_wallpaper_path
+        .lock()
+        .await
+        .as_ref()
+        .and_then(|path| path.file_stem())
+        .and_then(|stem| stem.to_str())
+        .map(str::to_string);
+
+    let today = state.clock.now().date_naive();
+    let available: Vec<&str> =
+        candidates(&wallpapers, exclude_recent_days, today, current.as_deref())
+            .into_iter()
+            .filter(|end_date| storage::get_wallpaper_path(&wallpaper_dir, end_date).exists())
+            .collect();
+    if available.is_empty() {
+        return Err("NO_CANDIDATES".to_string());
+    }
+    let end_date = available[(random_u64() % available.len() as u64) as usize].to_string();
+
+    info!(
+        target: "wallpaper",
+        "随机应用壁纸: {}（候选 {} 张，排除最近 {} 天）",
+        end_date,
+        available.len(),
+        exclude_recent_days
+    );
+    let path = storage::get_wallpaper_path(&wallpaper_dir, &end_date);
+    crate::commands::wallpaper::set_desktop_wallpaper(
+        path.to_string_lossy().to_string(),
+        state,
+        app,
+    )
+    .await?;
+    Ok(end_date)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wallpaper(end_date: &str) -> LocalWallpaper {
+        LocalWallpaper {
+            title: String::new(),
+            copyright: String::new(),
+            copyright_link: String::new(),
+            end_date: end_date.to_string(),
+            urlbase: String::new(),
+            resolution: None,
+            portrait_available: None,
+            watermark_free: None,
+            recompression: None,
+        }
+    }
+
+    #[test]
+    fn test_candidates_skip_recent_current_and_placeholders() {
+        let wallpapers: Vec<_> = [
+            "20240110", "20240108", "20240103", "20240102", "20240101", "19700101",
+        ]
+        .into_iter()
+        .map(wallpaper)
+        .collect();
+        let today = NaiveDate::from_ymd_opt(2024, 1, 10).unwrap();
+
+        assert_eq!(
+            candidates(&wallpapers, 7, today, Some("20240102")),
+            ["20240103", "20240101"]
+        );
+        assert_eq!(
+            candidates(&wallpapers, 0, today, None),
+            ["20240110", "20240108", "20240103", "20240102", "20240101"]
+        );
+        assert!(candidates(&wallpapers, 3650, today, None).is_empty());
+    }
+}
@@ -0,0 +1,283 @@
+//! 非正常退出检测与状态修复
+//!
+//! 启动时在应用数据目录写入会话标记，正常退出（`RunEvent::Exit`）时删除。
+//! 若启动时标记仍然存在，说明上次会话崩溃或被强制结束，此时在自动更新开始前
+//! 执行一次修复：清理残留临时文件、校验索引与壁纸文件、清除可能过期的持久化状态，
+//! 并把修复报告保存到运行时状态，供诊断命令读取。
+
+use anyhow::{Context, Result};
+use chrono::Local;
+use log::{info, warn};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use crate::models::RecoveryReport;
+use crate::{runtime_state, storage, utils};
+
+const SESSION_MARKER_FILE: &str = ".session";
+
+/// 目录修复结果
+#[derive(Debug, Default, PartialEq, Eq)]
+struct DirectoryRepair {
+    removed_temp_files: Vec<String>,
+    removed_empty_files: Vec<String>,
+    missing_wallpapers: Vec<String>,
+}
+
+fn session_marker_path(app: &AppHandle) -> Result<PathBuf> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .context("Failed to resolve app data directory")?;
+    Ok(dir.join(SESSION_MARKER_FILE))
+}
+
+/// 开始新会话
+///
+/// 返回上次会话的标记内容（即启动时间）；`Some` 表示上次没有正常退出。
+pub(crate) fn begin_session(app: &AppHandle) -> Result<Option<String>> {
+    let marker = session_marker_path(app)?;
+    let previous = std::fs::read_to_string(&marker)
+        .ok()
+        .map(|content| content.trim().to_string());
+
+    if let Some(parent) = marker.parent() {
+        std::fs::create_dir_all(parent).context("Failed to create app data directory")?;
+    }
+    std::fs::write(&marker, Local::now().to_rfc3339()).context("Failed to write session marker")?;
+    Ok(previous)
+}
+
+/// 正常退出时清除会话标记
+pub(crate) fn end_session(app: &AppHandle) {
+    match session_marker_path(app) {
+        Ok(marker) => {
+            if let Err(e) = std::fs::remove_file(&marker)
+                && e.kind() != std::io::ErrorKind::NotFound
+            {
+                warn!(target: "recovery", "清除会话标记失败: {}", e);
+            }
+        }
+        Err(e) => warn!(target: "recovery", "定位会话标记失败: {}", e),
+    }
+}
+
+/// 是否为应用自己的壁纸文件名主干（`YYYYMMDD` 或竖屏版 `YYYYMMDDr`）
+pub(crate) fn is_wallpaper_stem(stem: &str) -> bool {
+    let date = stem.strip_suffix('r').unwrap_or(stem);
+    utils::parse_end_date(date).is_some()
+}
+
+/// 修复壁纸目录
+///
+/// - 删除下载、索引保存残留的临时文件（`YYYYMMDD(r).tmp`、`index.tmp`）；
+/// - 删除大小为 0 的 `YYYYMMDD(r).jpg`（写入中断导致，保留会阻止重新下载）；
+/// - 列出索引中存在但本地缺失的壁纸。
+///
+/// 保存目录可能是用户的任意文件夹或其他实例正在写入的共享目录，
+/// 无法识别为本应用生成的文件一律不动。
+/// 空文件即使是当前桌面壁纸也会删除：它本身无法显示，删除后才能重新下载。
+async fn repair_wallpaper_directory(
+    directory: &Path,
+    indexed_end_dates: &[String],
+) -> Result<DirectoryRepair> {
+    let mut repair = DirectoryRepair::default();
+    let mut present = HashSet::new();
+
+    let mut entries = tokio::fs::read_dir(directory)
+        .await
+        .context("Failed to read wallpaper directory")?;
+    while let Some(entry) = entries.next_entry().await? {
+        let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+            continue;
+        };
+        let path = entry.path();
+
+        if let Some(stem) = name.strip_suffix(".tmp")
+            && (stem == "index" || is_wallpaper_stem(stem))
+        {
+            match tokio::fs::remove_file(&path).await {
+                Ok(()) => repair.removed_temp_files.push(name),
+                Err(e) => warn!(target: "recovery", "删除临时文件失败 {}: {}", name, e),
+            }
+            continue;
+        }
+
+        if let Some(stem) = name.strip_suffix(".jpg") {
+            let is_empty = entry
+                .metadata()
+                .await
+                .map(|m| m.len() == 0)
+                .unwrap_or(false);
+            if is_empty && !is_wallpaper_stem(stem) {
+                continue;
+            } else if is_empty {
+                match tokio::fs::remove_file(&path).await {
+                    Ok(()) => repair.removed_empty_files.push(name),
+                    Err(e) => warn!(target: "recovery", "删除空文件失败 {}: {}", name, e),
+                }
+            } else {
+                present.insert(stem.to_string());
+            }
+        }
+    }
+
+    repair.missing_wallpapers = indexed_end_dates
+        .iter()
+        .filter(|end_date| !present.contains(end_date.as_str()))
+        .cloned()
+        .collect();
+
+    repair.removed_temp_files.sort();
+    repair.removed_empty_files.sort();
+    repair.missing_wallpapers.sort();
+    Ok(repair)
+}
+
+/// 执行修复并保存报告
+pub(crate) async fn reconcile_after_unclean_shutdown(
+    app: &AppHandle,
+    wallpaper_dir: &Path,
+    previous_session_started_at: Option<String>,
+) -> RecoveryReport {
+    let mut report = RecoveryReport {
+        detected_at: utils::format_rfc3339_timestamp(&Local::now()),
+        previous_session_started_at: previous_session_started_at.filter(|s| !s.is_empty()),
+        ..Default::default()
+    };
+
+    if wallpaper_dir.exists() {
+        let indexed_end_dates: Vec<String> = match storage::get_index_snapshot(wallpaper_dir).await
+        {
+            Ok(index) => index
+                .get_all_wallpapers_unique()
+                .into_iter()
+                .map(|w| w.end_date)
+                .collect(),
+            Err(e) => {
+                warn!(target: "recovery", "读取索引失败，跳过索引校验: {}", e);
+                Vec::new()
+            }
+        };
+
+        match repair_wallpaper_directory(wallpaper_dir, &indexed_end_dates).await {
+            Ok(repair) => {
+                report.removed_temp_files = repair.removed_temp_files;
+                report.removed_empty_files = repair.removed_empty_files;
+                report.missing_wallpapers = repair.missing_wallpapers;
+            }
+            Err(e) => warn!(target: "recovery", "修复壁纸目录失败: {}", e),
+        }
+    }
+
+    // 上次检查时间可能是在更新中途崩溃前写入的，保留它会让缓存策略跳过本次更新
+    let runtime = runtime_state::load_runtime_state(app).unwrap_or_default();
+    if runtime.last_check_time.is_some() {
+        report.cleared_flags.push("last_check_time".to_string());
+    }
+    if let Err(e) = runtime_state::modify_runtime_state(app, |runtime| {
+        runtime.last_check_time = None;
+        runtime.last_recovery_report = Some(report.clone());
+        true
+    }) {
+        warn!(target: "recovery", "保存恢复报告失败: {}", e);
+    }
+
+    info!(
+        target: "recovery",
+        "检测到上次非正常退出，已完成修复: 临时文件 {} 个, 空文件 {} 个, 缺失壁纸 {} 个, 清除状态 {:?}",
+        report.removed_temp_files.len(),
+        report.removed_empty_files.len(),
+        report.missing_wallpapers.len(),
+        report.cleared_flags
+    );
+    report
+}
+
+/// 获取最近一次非正常退出的恢复报告
+#[tauri::command]
+pub(crate) async fn get_recovery_report(app: AppHandle) -> Result<Option<RecoveryReport>, String> {
+    runtime_state::load_runtime_state(&app)
+        .map(|state| state.last_recovery_report)
+        .map_err(|e| format!("Failed to load runtime state: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::SystemTime;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let unique = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("bw_recovery_{name}_{unique}"));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn test_repair_removes_temp_and_empty_files() {
+        let dir = temp_dir("cleanup");
+        std::fs::write(dir.join("20240101.jpg"), b"jpeg").unwrap();
+        std::fs::write(dir.join("20240102.jpg"), b"").unwrap();
+        std::fs::write(dir.join("20240103.tmp"), b"partial").unwrap();
+        std::fs::write(dir.join("index.tmp"), b"{}").unwrap();
+        std::fs::write(dir.join("notes.tmp"), b"user").unwrap();
+        std::fs::write(dir.join("holiday.jpg"), b"").unwrap();
+        std::fs::write(dir.join("index.json"), b"{}").unwrap();
+
+        let repair = repair_wallpaper_directory(&dir, &[]).await.unwrap();
+
+        assert_eq!(repair.removed_temp_files, vec!["20240103.tmp", "index.tmp"]);
+        assert_eq!(repair.removed_empty_files, vec!["20240102.jpg"]);
+        assert!(dir.join("20240101.jpg").exists());
+        assert!(dir.join("index.json").exists());
+        assert!(!dir.join("20240102.jpg").exists());
+        assert!(!dir.join("index.tmp").exists());
+        assert!(dir.join("notes.tmp").exists());
+        assert!(dir.join("holiday.jpg").exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_repair_reports_missing_indexed_wallpapers() {
+        let dir = temp_dir("missing");
+        std::fs::write(dir.join("20240101.jpg"), b"jpeg").unwrap();
+        std::fs::write(dir.join("20240102.jpg"), b"").unwrap();
+
+        let indexed = vec![
+            "20240103".to_string(),
+            "20240102".to_string(),
+            "20240101".to_string(),
+        ];
+        let repair = repair_wallpaper_directory(&dir, &indexed).await.unwrap();
+
+        // 空文件被删除后同样视为缺失
+        assert_eq!(repair.missing_wallpapers, vec!["20240102", "20240103"]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_repair_removes_empty_applied_wallpaper() {
+        let dir = temp_dir("applied");
+        // 20240101 为当前桌面壁纸，但写入中断只留下空文件
+        std::fs::write(dir.join("20240101.jpg"), b"").unwrap();
+        std::fs::write(dir.join("20240102.jpg"), b"jpeg").unwrap();
+
+        let indexed = vec!["20240101".to_string(), "20240102".to_string()];
+        let repair = repair_wallpaper_directory(&dir, &indexed).await.unwrap();
+
+        // 空文件照常删除并列为缺失，之后重新下载；有效文件不受影响
+        assert_eq!(repair.removed_empty_files, vec!["20240101.jpg"]);
+        assert_eq!(repair.missing_wallpapers, vec!["20240101"]);
+        assert!(!dir.join("20240101.jpg").exists());
+        assert!(dir.join("20240102.jpg").exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
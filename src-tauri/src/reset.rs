@@ -0,0 +1,270 @@
+//! 重置应用
+//!
+//! 用户反馈状态损坏（设置无法保存、索引错乱等）时，无需再手动删除应用数据文件：
+//! `reset_application` 会停止后台任务，清除设置、运行时状态和壁纸索引（可选保留图片文件），
+//! 释放 IndexManager 缓存并恢复默认值，最后重新启动后台任务。
+//!
+//! 备份配置（`backup.json`）属于外部服务凭据，不在重置范围内；壁纸配置方案会保留，但退出当前方案。
+
+use anyhow::{Context, Result};
+use log::{info, warn};
+use std::collections::HashSet;
+use std::path::Path;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_autostart::ManagerExt;
+
+use crate::index_manager::INDEX_FILE;
+use crate::models::{AppRuntimeState, AppSettings, UpdatePhase};
+use crate::{
+    AppState, commands, events, policy, profiles, runtime_state, settings_store, smart_crop,
+    storage, transfer, trash, tray, update_cycle,
+};
+
+/// 目录清理结果
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ClearedDirectory {
+    removed_index: bool,
+    removed_images: usize,
+}
+
+/// 清除壁纸目录中由应用生成的文件
+///
+/// 只删除索引文件、`.derived/` 派生目录，以及（`keep_images` 为 false 时）
+/// 符合 `YYYYMMDD.jpg` / `YYYYMMDDr.jpg` 命名的壁纸图片，目录中的其他文件保持不变。
+/// `protected` 中的日期（当前应用在桌面上的壁纸）始终保留，避免桌面在重启后变黑。
+/// 壁纸图片按 `move_to_trash` 移到回收站或永久删除。
+async fn clear_wallpaper_directory(
+    directory: &Path,
+    keep_images: bool,
+    protected: &HashSet<String>,
+    move_to_trash: bool,
+) -> Result<ClearedDirectory> {
+    let mut cleared = ClearedDirectory::default();
+    if !directory.exists() {
+        return Ok(cleared);
+    }
+
+    match tokio::fs::remove_file(directory.join(INDEX_FILE)).await {
+        Ok(()) => cleared.removed_index = true,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e).context("Failed to remove index file"),
+    }
+
+    let derived = directory.join(smart_crop::DERIVED_DIR);
+    if derived.exists() {
+        tokio::fs::remove_dir_all(&derived)
+            .await
+            .context("Failed to remove derived directory")?;
+    }
+
+    if keep_images {
+        return Ok(cleared);
+    }
+
+    let mut entries = tokio::fs::read_dir(directory)
+        .await
+        .context("Failed to read wallpaper directory")?;
+    while let Some(entry) = entries.next_entry().await? {
+        let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+            continue;
+        };
+        if !transfer::is_wallpaper_image_name(&name) || protected.contains(&name[..8]) {
+            continue;
+        }
+        match trash::remove_file(&entry.path(), move_to_trash).await {
+            Ok(()) => cleared.removed_images += 1,
+            Err(e) => warn!(target: "reset", "删除壁纸文件失败 {}: {}", name, e),
+        }
+    }
+    Ok(cleared)
+}
+
+/// 按默认值（含管理员策略）生成设置，开机自启动保持系统实际状态（不在重置时修改登录项）
+fn default_settings(app: &AppHandle) -> AppSettings {
+    let launch_at_startup = app.autolaunch().is_enabled().unwrap_or_else(|e| {
+        warn!(target: "reset", "读取自启动状态失败: {}，假设为未启用", e);
+        false
+    });
+    let defaults = policy::current().merge(None).unwrap_or_else(|e| {
+        warn!(target: "reset", "应用设置策略失败: {}，使用内置默认值", e);
+        AppSettings::default()
+    });
+    let mut settings = AppSettings {
+        launch_at_startup,
+        ..defaults
+    };
+    settings.compute_resolved_language();
+    settings.normalize_mkt();
+    settings
+}
+
+async fn reset_state(app: &AppHandle, keep_images: bool) -> Result<(), String> {
+    let state = app.state::<AppState>();
+
+    let old_wallpaper_dir = state.wallpaper_directory.lock().await.clone();
+    let move_to_trash = state.settings.read().await.move_to_trash;
+    let protected = runtime_state::load_runtime_state(app)
+        .map(|runtime| runtime.protected_end_dates())
+        .unwrap_or_default();
+    let cleared =
+        clear_wallpaper_directory(&old_wallpaper_dir, keep_images, &protected, move_to_trash)
+            .await
+            .map_err(|e| format!("清除壁纸目录失败: {e}"))?;
+    storage::remove_index_manager(&old_wallpaper_dir);
+    info!(
+        target: "reset",
+        "已清除壁纸目录 {}：索引 {}，壁纸图片 {} 张",
+        old_wallpaper_dir.display(),
+        if cleared.removed_index { "已删除" } else { "不存在" },
+        cleared.removed_images
+    );
+
+    // 保存默认设置到替换内存设置都在写入锁内，并发的设置修改不会写回旧设置
+    let writes = state.settings.lock_writes().await;
+    let settings = default_settings(app);
+    settings_store::save_settings_async(app, &settings)
+        .await
+        .map_err(|e| format!("保存默认设置失败: {e}"))?;
+
+    runtime_state::modify_runtime_state(app, |runtime| {
+        *runtime = AppRuntimeState::default();
+        true
+    })
+    .map_err(|e| format!("清除运行时状态失败: {e}"))?;
+    if settings.launch_at_startup {
+        commands::settings::set_autostart_notification_flag_if_needed(app, "reset");
+    }
+
+    // 管理员策略可能指定了壁纸目录
+    let wallpaper_dir = match &settings.save_directory {
+        Some(dir) => std::path::PathBuf::from(dir),
+        None => storage::get_default_wallpaper_directory().map_err(|e| e.to_string())?,
+    };
+    if let Err(e) = storage::ensure_wallpaper_directory(&wallpaper_dir).await {
+        warn!(target: "reset", "创建默认壁纸目录失败: {}", e);
+    }
+
+    *state.wallpaper_directory.lock().await = wallpaper_dir;
+    *state.current_wallpaper_path.lock().await = None;
+    *state.last_update_time.lock().await = None;
+    *state.last_actual_mkt.lock().await = None;
+    profiles::clear_active_profile(app).await;
+
+    state.settings.replace(settings.clone()).await;
+    drop(writes);
+
+    tray::apply_left_click_behavior(app, &settings.tray_left_click).await;
+    if let Err(e) = tray::update_tray_menu(app).await {
+        warn!(target: "reset", "更新托盘菜单失败: {}", e);
+    }
+    Ok(())
+}
+
+/// 重置应用到初始状态
+///
+/// 更新进行中时拒绝执行（返回 "UPDATE_IN_PROGRESS"），重置期间占用更新标志，
+/// 避免更新循环或空闲预取在清理过程中写入索引。
+#[tauri::command]
+pub(crate) async fn reset_application(keep_images: bool, app: AppHandle) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    if !update_cycle::transition_update_state(&app, |update, at| {
+        update.advance(UpdatePhase::Resetting, at)
+    })
+    .await
+    {
+        return Err("UPDATE_IN_PROGRESS".to_string());
+    }
+
+    info!(target: "reset", "开始重置应用（保留图片: {}）", keep_images);
+    state.scheduler.shutdown();
+
+    let result = reset_state(&app, keep_images).await;
+    update_cycle::transition_update_state(&app, |update, at| update.advance(UpdatePhase::Idle, at))
+        .await;
+
+    // 无论重置是否完整成功都要恢复后台任务，避免应用停止自动更新
+    crate::start_background_tasks(&app);
+
+    match result {
+        Ok(()) => {
+            info!(target: "reset", "应用已重置");
+            if let Err(e) = events::WALLPAPER_UPDATED.emit(&app, &()) {
+                warn!(target: "reset", "通知前端失败: {}", e);
+            }
+            Ok(())
+        }
+        Err(e) => {
+            warn!(target: "reset", "重置应用失败: {}", e);
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let unique = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("bw_reset_{name}_{unique}"));
+        std::fs::create_dir_all(dir.join(smart_crop::DERIVED_DIR)).unwrap();
+        for file in ["index.json", "20240101.jpg", "20240101r.jpg", "notes.txt"] {
+            std::fs::write(dir.join(file), b"data").unwrap();
+        }
+        dir
+    }
+
+    #[tokio::test]
+    async fn test_clear_wallpaper_directory_keeps_images() {
+        let dir = temp_dir("keep");
+        let cleared = clear_wallpaper_directory(&dir, true, &HashSet::new(), false)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            cleared,
+            ClearedDirectory {
+                removed_index: true,
+                removed_images: 0,
+            }
+        );
+        assert!(!dir.join("index.json").exists());
+        assert!(!dir.join(smart_crop::DERIVED_DIR).exists());
+        assert!(dir.join("20240101.jpg").exists());
+        assert!(dir.join("20240101r.jpg").exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_clear_wallpaper_directory_removes_only_wallpaper_images() {
+        let dir = temp_dir("remove");
+        std::fs::write(dir.join("20240102.jpg"), b"data").unwrap();
+        let protected = HashSet::from(["20240102".to_string()]);
+        let cleared = clear_wallpaper_directory(&dir, false, &protected, false)
+            .await
+            .unwrap();
+
+        assert_eq!(cleared.removed_images, 2);
+        assert!(!dir.join("20240101.jpg").exists());
+        assert!(!dir.join("20240101r.jpg").exists());
+        assert!(dir.join("20240102.jpg").exists());
+        assert!(dir.join("notes.txt").exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_clear_missing_directory_is_noop() {
+        let dir = std::env::temp_dir().join("bw_reset_does_not_exist");
+        assert_eq!(
+            clear_wallpaper_directory(&dir, false, &HashSet::new(), false)
+                .await
+                .unwrap(),
+            ClearedDirectory::default()
+        );
+    }
+}
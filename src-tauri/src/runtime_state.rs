@@ -3,10 +3,12 @@
 //! 使用 tauri-plugin-store 管理应用运行时状态的持久化存储
 //! 与用户设置 (settings.json) 分离，存储在隐藏文件 .runtime.json 中
 
-use crate::models::AppRuntimeState;
+use crate::clock::Clock;
+use crate::models::{AppRuntimeState, MarketHealth};
 use anyhow::Result;
-use chrono::Local;
+use chrono::{Local, NaiveDate};
 use std::path::Path;
+use std::sync::Mutex;
 use tauri::AppHandle;
 use tauri_plugin_store::StoreExt;
 
@@ -30,8 +32,30 @@ pub fn load_runtime_state(app: &AppHandle) -> Result<AppRuntimeState> {
     }
 }
 
-/// 保存运行时状态
-pub fn save_runtime_state(app: &AppHandle, state: &AppRuntimeState) -> Result<()> {
+/// 串行化 .runtime.json 的读-改-写
+///
+/// 运行时状态作为一个整体保存，各字段的更新必须在同一把锁内重新读取最新状态，
+/// 否则并发的更新（下载流量、应用记录、市场健康等）会互相覆盖。
+static RUNTIME_STATE_LOCK: Mutex<()> = Mutex::new(());
+
+/// 读取最新的运行时状态，修改后保存
+///
+/// 闭包返回 `false` 表示没有变化，此时不写盘。返回值为是否写盘。
+pub fn modify_runtime_state(
+    app: &AppHandle,
+    modify: impl FnOnce(&mut AppRuntimeState) -> bool,
+) -> Result<bool> {
+    let _guard = RUNTIME_STATE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut state = load_runtime_state(app)?;
+    if !modify(&mut state) {
+        return Ok(false);
+    }
+    save_runtime_state(app, &state)?;
+    Ok(true)
+}
+
+/// 保存运行时状态（整体覆盖，更新部分字段请使用 [`modify_runtime_state`]）
+fn save_runtime_state(app: &AppHandle, state: &AppRuntimeState) -> Result<()> {
     let store = app
         .store(RUNTIME_STORE_FILE)
         .map_err(|e| anyhow::anyhow!("Failed to access runtime store: {}", e))?;
@@ -50,7 +74,7 @@ pub fn save_runtime_state(app: &AppHandle, state: &AppRuntimeState) -> Result<()
 
 /// 检查今天是否需要更新
 /// 返回 true 表示需要更新，false 表示可以跳过
-pub fn should_update_today(state: &AppRuntimeState) -> bool {
+pub fn should_update_today(state: &AppRuntimeState, clock: &dyn Clock) -> bool {
     // 如果从未更新过，需要更新
     let Some(ref last_update) = state.last_successful_update else {
         log::info!(target: "runtime", "从未更新过，需要执行更新");
@@ -66,7 +90,7 @@ pub fn should_update_today(state: &AppRuntimeState) -> bool {
         }
     };
 
-    let today = Local::now().date_naive();
+    let today = clock.now().date_naive();
 
     // 如果最后更新不是今天，需要更新
     if last_update_date < today {
@@ -87,10 +111,11 @@ pub fn should_update_today(state: &AppRuntimeState) -> bool {
 /// # Arguments
 /// * `wallpaper_dir` - 壁纸存储目录
 /// * `language` - 语言代码（如 "zh-CN", "en-US"）
-pub async fn has_today_wallpaper(wallpaper_dir: &Path, language: &str) -> bool {
+/// * `clock` - 时间源
+pub async fn has_today_wallpaper(wallpaper_dir: &Path, language: &str, clock: &dyn Clock) -> bool {
     // 获取今天的日期字符串 (YYYYMMDD 格式)
     use chrono::Datelike;
-    let today = Local::now().date_naive();
+    let today = clock.now().date_naive();
     let today_str = format!("{:04}{:02}{:02}", today.year(), today.month(), today.day());
 
     // 读取本地壁纸列表
@@ -121,16 +146,81 @@ pub async fn has_today_wallpaper(wallpaper_dir: &Path, language: &str) -> bool {
 }
 
 /// 更新最后成功更新时间
-pub fn update_last_successful_time(app: &AppHandle, state: &mut AppRuntimeState) -> Result<()> {
-    state.last_successful_update = Some(Local::now().to_rfc3339());
-    save_runtime_state(app, state)?;
+pub fn update_last_successful_time(
+    app: &AppHandle,
+    state: &mut AppRuntimeState,
+    clock: &dyn Clock,
+) -> Result<()> {
+    let now = clock.now().to_rfc3339();
+    state.last_successful_update = Some(now.clone());
+    modify_runtime_state(app, |stored| {
+        stored.last_successful_update = Some(now);
+        true
+    })?;
     Ok(())
 }
 
 /// 更新最后检查时间
-pub fn update_last_check_time(app: &AppHandle, state: &mut AppRuntimeState) -> Result<()> {
-    state.last_check_time = Some(Local::now().to_rfc3339());
-    save_runtime_state(app, state)?;
+pub fn update_last_check_time(
+    app: &AppHandle,
+    state: &mut AppRuntimeState,
+    clock: &dyn Clock,
+) -> Result<()> {
+    let now = clock.now().to_rfc3339();
+    state.last_check_time = Some(now.clone());
+    modify_runtime_state(app, |stored| {
+        stored.last_check_time = Some(now);
+        true
+    })?;
+    Ok(())
+}
+
+/// 记录指定 mkt 下成功应用到桌面的壁纸
+pub fn record_applied_wallpaper(app: &AppHandle, mkt: &str, end_date: &str) -> Result<()> {
+    modify_runtime_state(app, |state| {
+        state
+            .applied_wallpapers
+            .insert(mkt.to_string(), end_date.to_string())
+            .as_deref()
+            != Some(end_date)
+    })?;
+    Ok(())
+}
+
+/// 读取指定 mkt 的 Bing API 健康指标（没有记录或读取失败时为初始状态）
+pub fn market_health(app: &AppHandle, mkt: &str) -> MarketHealth {
+    load_runtime_state(app)
+        .ok()
+        .and_then(|mut state| state.market_health.remove(mkt))
+        .unwrap_or_default()
+}
+
+/// 保存指定 mkt 的 Bing API 健康指标
+pub fn save_market_health(app: &AppHandle, mkt: &str, health: MarketHealth) -> Result<()> {
+    modify_runtime_state(app, |state| {
+        state.market_health.insert(mkt.to_string(), health);
+        true
+    })?;
+    Ok(())
+}
+
+/// 累加一次图片下载的流量
+pub fn record_bandwidth(
+    app: &AppHandle,
+    day: NaiveDate,
+    bytes: u64,
+    duration_ms: u64,
+) -> Result<()> {
+    modify_runtime_state(app, |state| {
+        state.record_bandwidth(day, bytes, duration_ms);
+        true
+    })?;
+    Ok(())
+}
+
+/// 标记或清除某天竖屏壁纸的待重试状态（没有变化时不写盘）
+pub fn set_portrait_pending(app: &AppHandle, end_date: &str, pending: bool) -> Result<()> {
+    modify_runtime_state(app, |state| state.set_portrait_pending(end_date, pending))?;
     Ok(())
 }
 
@@ -142,10 +232,12 @@ pub fn update_last_check_time(app: &AppHandle, state: &mut AppRuntimeState) -> R
 /// * `state` - 运行时状态
 /// * `wallpaper_dir` - 壁纸存储目录
 /// * `language` - 语言代码（如 "zh-CN", "en-US"）
+/// * `clock` - 时间源
 pub async fn can_skip_api_request(
     state: &AppRuntimeState,
     wallpaper_dir: &Path,
     language: &str,
+    clock: &dyn Clock,
 ) -> bool {
     // 检查是否有最后检查时间
     let Some(ref last_check_str) = state.last_check_time else {
@@ -159,7 +251,7 @@ pub async fn can_skip_api_request(
     };
 
     // 检查距离上次检查是否不足 5 分钟
-    let now = Local::now();
+    let now = clock.now();
     let duration_since_check = now.signed_duration_since(last_check);
     const CACHE_DURATION_MINUTES: i64 = 5;
 
@@ -185,7 +277,7 @@ pub async fn can_skip_api_request(
 
     if duration_since_check.num_minutes() < CACHE_DURATION_MINUTES {
         // 如果距离上次检查不足 5 分钟，检查本地是否有今日壁纸
-        if has_today_wallpaper(wallpaper_dir, language).await {
+        if has_today_wallpaper(wallpaper_dir, language, clock).await {
             log::info!(target: "runtime", 
                 "距离上次 API 请求不足 5 分钟且本地有今日壁纸，跳过 API 请求（缓存策略）");
             return true;
@@ -198,65 +290,91 @@ pub async fn can_skip_api_request(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::{Duration, Local};
+    use crate::clock::{Clock, MockClock};
+    use chrono::Duration;
+
+    /// 测试统一使用的"当前时间"：2024-03-15 10:00:00
+    fn mock_clock() -> MockClock {
+        MockClock::at(2024, 3, 15, 10, 0, 0)
+    }
 
     #[test]
     fn test_should_update_today_never_updated() {
+        let clock = mock_clock();
         let state = AppRuntimeState::default();
-        assert!(should_update_today(&state));
+        assert!(should_update_today(&state, &clock));
+    }
+
+    #[test]
+    fn test_should_update_today_after_midnight() {
+        // 23:59 更新过，零点过后应重新更新
+        let clock = MockClock::at(2024, 3, 15, 23, 59, 0);
+        let state = AppRuntimeState {
+            last_successful_update: Some(clock.now().to_rfc3339()),
+            ..Default::default()
+        };
+        assert!(!should_update_today(&state, &clock));
+
+        clock.advance(Duration::minutes(2));
+        assert!(should_update_today(&state, &clock));
     }
 
     #[test]
     fn test_should_update_today_updated_yesterday() {
-        let yesterday = Local::now() - Duration::days(1);
+        let clock = mock_clock();
+        let yesterday = clock.now() - Duration::days(1);
         let state = AppRuntimeState {
             last_successful_update: Some(yesterday.to_rfc3339()),
             ..Default::default()
         };
 
-        assert!(should_update_today(&state));
+        assert!(should_update_today(&state, &clock));
     }
 
     #[test]
     fn test_should_update_today_updated_today() {
+        let clock = mock_clock();
         let state = AppRuntimeState {
-            last_successful_update: Some(Local::now().to_rfc3339()),
+            last_successful_update: Some(clock.now().to_rfc3339()),
             ..Default::default()
         };
 
-        assert!(!should_update_today(&state));
+        assert!(!should_update_today(&state, &clock));
     }
 
     #[test]
     fn test_should_update_today_invalid_timestamp() {
+        let clock = mock_clock();
         let state = AppRuntimeState {
             last_successful_update: Some("invalid-timestamp".to_string()),
             ..Default::default()
         };
 
-        assert!(should_update_today(&state));
+        assert!(should_update_today(&state, &clock));
     }
 
     #[test]
     fn test_should_update_today_old_date() {
-        let old_date = Local::now() - Duration::days(7);
+        let clock = mock_clock();
+        let old_date = clock.now() - Duration::days(7);
         let state = AppRuntimeState {
             last_successful_update: Some(old_date.to_rfc3339()),
             ..Default::default()
         };
 
-        assert!(should_update_today(&state));
+        assert!(should_update_today(&state, &clock));
     }
 
     #[test]
     fn test_should_update_today_future_date() {
-        let future = Local::now() + Duration::days(1);
+        let clock = mock_clock();
+        let future = clock.now() + Duration::days(1);
         let state = AppRuntimeState {
             last_successful_update: Some(future.to_rfc3339()),
             ..Default::default()
         };
 
-        assert!(!should_update_today(&state));
+        assert!(!should_update_today(&state, &clock));
     }
 
     // ─── can_skip_api_request 纯逻辑路径测试 ───
@@ -272,29 +390,32 @@ mod tests {
 
     #[tokio::test]
     async fn test_can_skip_no_last_check_time() {
+        let clock = mock_clock();
         // 没有 last_check_time 时，不应跳过
         let state = make_state(None, None);
         let dir = std::env::temp_dir();
-        let result = can_skip_api_request(&state, &dir, "zh-CN").await;
+        let result = can_skip_api_request(&state, &dir, "zh-CN", &clock).await;
         assert!(!result, "Should not skip when no last_check_time");
     }
 
     #[tokio::test]
     async fn test_can_skip_invalid_last_check_time() {
+        let clock = mock_clock();
         // last_check_time 格式无效时，不应跳过
         let state = make_state(Some("invalid-time".to_string()), None);
         let dir = std::env::temp_dir();
-        let result = can_skip_api_request(&state, &dir, "zh-CN").await;
+        let result = can_skip_api_request(&state, &dir, "zh-CN", &clock).await;
         assert!(!result, "Should not skip when last_check_time is invalid");
     }
 
     #[tokio::test]
     async fn test_can_skip_old_check_time() {
+        let clock = mock_clock();
         // 上次检查超过 5 分钟，不应跳过
-        let old_time = (Local::now() - Duration::minutes(10)).to_rfc3339();
+        let old_time = (clock.now() - Duration::minutes(10)).to_rfc3339();
         let state = make_state(Some(old_time), None);
         let dir = std::env::temp_dir();
-        let result = can_skip_api_request(&state, &dir, "zh-CN").await;
+        let result = can_skip_api_request(&state, &dir, "zh-CN", &clock).await;
         assert!(
             !result,
             "Should not skip when last check was over 5 minutes ago"
@@ -303,12 +424,13 @@ mod tests {
 
     #[tokio::test]
     async fn test_can_skip_cross_day() {
+        let clock = mock_clock();
         // 跨天场景：即使不足 5 分钟，也不应跳过
         // 模拟上次检查在昨天 23:59
-        let yesterday_late = (Local::now() - Duration::days(1)).to_rfc3339();
+        let yesterday_late = (clock.now() - Duration::days(1)).to_rfc3339();
         let state = make_state(Some(yesterday_late), None);
         let dir = std::env::temp_dir();
-        let result = can_skip_api_request(&state, &dir, "zh-CN").await;
+        let result = can_skip_api_request(&state, &dir, "zh-CN", &clock).await;
         assert!(
             !result,
             "Should not skip when last check was on a different day"
@@ -317,14 +439,27 @@ mod tests {
 
     #[tokio::test]
     async fn test_can_skip_time_regression() {
+        let clock = mock_clock();
         // 系统时间回退场景
-        let future_time = (Local::now() + Duration::hours(1)).to_rfc3339();
+        let future_time = (clock.now() + Duration::hours(1)).to_rfc3339();
         let state = make_state(Some(future_time), None);
         let dir = std::env::temp_dir();
-        let result = can_skip_api_request(&state, &dir, "zh-CN").await;
+        let result = can_skip_api_request(&state, &dir, "zh-CN", &clock).await;
         assert!(
             !result,
             "Should not skip when system time has gone backwards"
         );
     }
+
+    #[tokio::test]
+    async fn test_can_skip_cross_midnight_within_cache_window() {
+        // 23:58 检查过，00:01 再次检查：虽不足 5 分钟，但已跨天，不能跳过
+        let clock = MockClock::at(2024, 3, 15, 23, 58, 0);
+        let state = make_state(Some(clock.now().to_rfc3339()), None);
+        clock.advance(Duration::minutes(3));
+
+        let dir = std::env::temp_dir();
+        let result = can_skip_api_request(&state, &dir, "zh-CN", &clock).await;
+        assert!(!result, "Should not skip right after midnight");
+    }
 }
@@ -0,0 +1,189 @@
+//! 路径安全校验
+//!
+//! 前端传入的文件路径在使用前都要确认位于允许的目录（通常是壁纸目录）之内。
+//! 校验统一基于规范化后的真实路径：符号链接会被展开，`..` 无法借此跳出目录；
+//! 尚不存在的文件（例如待按需下载的壁纸）按其最近的已存在祖先目录规范化后再比较。
+//! Windows 上 `canonicalize` 会返回 `\\?\` / `\\?\UNC\` 形式的路径，
+//! 基准目录与候选路径都经过同样的处理，因此可以直接按路径前缀比较。
+
+use anyhow::{Context, Result, bail};
+use std::path::{Path, PathBuf};
+
+/// 把 `candidate` 解析为 `base` 目录内的规范化路径
+///
+/// 相对路径相对于 `base` 解析。路径（展开符号链接后）不在 `base` 之内、
+/// 或无法确定其真实位置时返回错误；`candidate` 就是 `base` 本身时视为在目录内。
+pub(crate) fn resolve_in_dir(base: &Path, candidate: &Path) -> Result<PathBuf> {
+    let base = base
+        .canonicalize()
+        .with_context(|| format!("无法解析目录: {}", base.display()))?;
+    let resolved = canonicalize_lenient(&base.join(candidate))?;
+    if !resolved.starts_with(&base) {
+        bail!(
+            "路径不在允许的目录内: {} (期望在: {})",
+            candidate.display(),
+            base.display()
+        );
+    }
+    Ok(resolved)
+}
+
+/// `candidate` 是否为 `base` 本身或位于其中
+pub(crate) fn is_within(base: &Path, candidate: &Path) -> bool {
+    resolve_in_dir(base, candidate).is_ok()
+}
+
+/// 规范化可能尚不存在的路径
+///
+/// 从路径末尾向上找到最近的已存在祖先并规范化，再拼回不存在的部分。
+/// 不存在的部分中不允许出现 `..`，也不允许是悬空的符号链接（写入时会跟随到别处）。
+fn canonicalize_lenient(path: &Path) -> Result<PathBuf> {
+    let mut existing = path;
+    let mut missing = Vec::new();
+    loop {
+        match existing.canonicalize() {
+            Ok(canonical) => {
+                return Ok(missing
+                    .iter()
+                    .rev()
+                    .fold(canonical, |acc, name| acc.join(name)));
+            }
+            Err(_) if existing.symlink_metadata().is_ok() => {
+                bail!("路径指向无效的符号链接: {}", existing.display());
+            }
+            Err(_) => {}
+        }
+        let (Some(name), Some(parent)) = (existing.file_name(), existing.parent()) else {
+            bail!("无法解析路径: {}", path.display());
+        };
+        missing.push(name);
+        existing = parent;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let unique = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("bw_safe_path_{name}_{unique}"));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.canonicalize().unwrap()
+    }
+
+    #[test]
+    fn test_resolve_existing_and_missing_files() {
+        let dir = temp_dir("basic");
+        std::fs::write(dir.join("20240101.jpg"), b"jpeg").unwrap();
+
+        assert_eq!(
+            resolve_in_dir(&dir, &dir.join("20240101.jpg")).unwrap(),
+            dir.join("20240101.jpg")
+        );
+        // 尚未下载的文件，以及尚不存在的子目录
+        assert_eq!(
+            resolve_in_dir(&dir, &dir.join("20240102.jpg")).unwrap(),
+            dir.join("20240102.jpg")
+        );
+        assert_eq!(
+            resolve_in_dir(&dir, Path::new(".derived/a.jpg")).unwrap(),
+            dir.join(".derived").join("a.jpg")
+        );
+        assert_eq!(resolve_in_dir(&dir, &dir).unwrap(), dir);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_resolve_rejects_paths_outside_base() {
+        let dir = temp_dir("outside");
+        let base = dir.join("wallpapers");
+        std::fs::create_dir_all(&base).unwrap();
+        std::fs::write(dir.join("secret.jpg"), b"jpeg").unwrap();
+
+        assert!(resolve_in_dir(&base, &dir.join("secret.jpg")).is_err());
+        assert!(resolve_in_dir(&base, &base.join("..").join("secret.jpg")).is_err());
+        assert!(resolve_in_dir(&base, &base.join("missing").join("..").join("..")).is_err());
+        // 前缀相同但不是子目录
+        assert!(resolve_in_dir(&base, &dir.join("wallpapers2").join("a.jpg")).is_err());
+        assert!(!is_within(&base, &dir));
+        assert!(is_within(&base, &base));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_resolve_requires_existing_base() {
+        let dir = temp_dir("missing_base");
+        let base = dir.join("not_created");
+        assert!(resolve_in_dir(&base, &base.join("a.jpg")).is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_follows_symlinks() {
+        use std::os::unix::fs::symlink;
+
+        let dir = temp_dir("symlink");
+        let base = dir.join("wallpapers");
+        let outside = dir.join("outside");
+        std::fs::create_dir_all(&base).unwrap();
+        std::fs::create_dir_all(&outside).unwrap();
+        std::fs::write(outside.join("secret.jpg"), b"jpeg").unwrap();
+        std::fs::write(base.join("20240101.jpg"), b"jpeg").unwrap();
+
+        // 指向目录外的链接（文件、目录、悬空链接）都应拒绝
+        symlink(outside.join("secret.jpg"), base.join("link.jpg")).unwrap();
+        symlink(&outside, base.join("linked_dir")).unwrap();
+        symlink(outside.join("gone.jpg"), base.join("dangling.jpg")).unwrap();
+        assert!(resolve_in_dir(&base, &base.join("link.jpg")).is_err());
+        assert!(resolve_in_dir(&base, &base.join("linked_dir").join("new.jpg")).is_err());
+        assert!(resolve_in_dir(&base, &base.join("dangling.jpg")).is_err());
+
+        // 目录内的链接解析为真实路径
+        symlink(base.join("20240101.jpg"), base.join("alias.jpg")).unwrap();
+        assert_eq!(
+            resolve_in_dir(&base, &base.join("alias.jpg")).unwrap(),
+            base.join("20240101.jpg")
+        );
+
+        // 通过链接访问基准目录本身同样有效
+        symlink(&base, dir.join("base_link")).unwrap();
+        assert_eq!(
+            resolve_in_dir(&dir.join("base_link"), &base.join("20240102.jpg")).unwrap(),
+            base.join("20240102.jpg")
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_resolve_handles_verbatim_and_unc_paths() {
+        let dir = temp_dir("unc");
+        std::fs::write(dir.join("20240101.jpg"), b"jpeg").unwrap();
+
+        // canonicalize 返回 \\?\ 前缀；不带前缀的普通写法应得到相同结果
+        let plain = PathBuf::from(
+            dir.to_string_lossy()
+                .trim_start_matches(r"\\?\")
+                .to_string(),
+        );
+        assert_eq!(
+            resolve_in_dir(&plain, &plain.join("20240101.jpg")).unwrap(),
+            dir.join("20240101.jpg")
+        );
+
+        // 无法访问的网络共享路径不在壁纸目录内
+        assert!(resolve_in_dir(&dir, Path::new(r"\\unreachable-host\share\a.jpg")).is_err());
+        assert!(resolve_in_dir(&dir, Path::new(r"\\?\UNC\unreachable-host\share\a.jpg")).is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
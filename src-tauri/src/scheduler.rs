@@ -0,0 +1,246 @@
+//! 后台任务调度器
+//!
+//! 统一管理应用内的周期任务（自动更新、定时备份等）。每个任务按名称注册，
+//! 拥有独立的取消令牌：重新注册同名任务会先取消旧任务，互不影响。
+//!
+//! - [`Schedule::Daily`]：每天固定时刻执行（类似 cron 的 `M H * * *`）；
+//! - [`Schedule::Solar`]：每天日出或日落时执行；
+//! - [`Scheduler::spawn`]：自行管理节奏的常驻任务（如自动更新循环），只交由调度器取消。
+//!
+//! 周期任务可以设置随机抖动，避免大量客户端在同一时刻请求远端服务。
+
+use chrono::{DateTime, Duration as ChronoDuration, Local, NaiveTime, TimeZone};
+use log::{debug, info};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::AppHandle;
+use tokio_util::sync::CancellationToken;
+
+use crate::clock::Clock;
+use crate::solar_schedule::{self, SolarEvent};
+
+/// 任务执行体：每次触发时以 `AppHandle` 调用一次
+pub(crate) type JobFn =
+    Arc<dyn Fn(AppHandle) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+
+/// 周期任务的触发规则
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Schedule {
+    /// 每天本地时间 `hour:minute`
+    Daily { hour: u32, minute: u32 },
+    /// 每天在指定坐标的日出或日落时刻，极昼/极夜期间每天触发一次
+    Solar {
+        event: SolarEvent,
+        latitude: f64,
+        longitude: f64,
+    },
+}
+
+impl Schedule {
+    /// 计算 `now` 之后的下一次触发时刻（不含抖动）
+    pub(crate) fn next_run(&self, now: DateTime<Local>) -> DateTime<Local> {
+        match *self {
+            Schedule::Daily { hour, minute } => {
+                let time = NaiveTime::from_hms_opt(hour, minute, 0).unwrap_or(NaiveTime::MIN);
+                let mut date = now.date_naive();
+                loop {
+                    // 夏令时跳过的时刻取不到本地时间，顺延到下一天
+                    if let Some(candidate) =
+                        Local.from_local_datetime(&date.and_time(time)).earliest()
+                        && candidate > now
+                    {
+                        return candidate;
+                    }
+                    match date.succ_opt() {
+                        Some(next) => date = next,
+                        None => return now + ChronoDuration::days(1),
+                    }
+                }
+            }
+            Schedule::Solar {
+                event,
+                latitude,
+                longitude,
+            } => solar_schedule::next_event(now, latitude, longitude, event)
+                .unwrap_or_else(|| now + ChronoDuration::days(1)),
+        }
+    }
+}
+
+/// `[0, max)` 范围内的随机抖动
+fn jitter_delay(max: Duration) -> Duration {
+    use std::hash::{BuildHasher, Hasher};
+
+    let max_ms = max.as_millis() as u64;
+    if max_ms == 0 {
+        return Duration::ZERO;
+    }
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    hasher.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos(),
+    );
+    Duration::from_millis(hasher.finish() % max_ms)
+}
+
+/// 后台任务调度器
+#[derive(Default)]
+pub(crate) struct Scheduler {
+    jobs: std::sync::Mutex<HashMap<&'static str, CancellationToken>>,
+}
+
+impl Scheduler {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// 登记任务并返回其取消令牌（同名旧任务会被取消）
+    fn register(&self, name: &'static str) -> CancellationToken {
+        let token = CancellationToken::new();
+        let mut jobs = self.jobs.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(previous) = jobs.insert(name, token.clone()) {
+            previous.cancel();
+            debug!(target: "scheduler", "任务 {} 已重新注册，取消旧任务", name);
+        }
+        token
+    }
+
+    /// 启动常驻任务，任务自行决定执行节奏
+    pub(crate) fn spawn<F>(&self, name: &'static str, task: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let token = self.register(name);
+        tauri::async_runtime::spawn(async move {
+            tokio::select! {
+                _ = token.cancelled() => {
+                    info!(target: "scheduler", "任务 {} 已取消", name);
+                }
+                _ = task => {}
+            }
+        });
+    }
+
+    /// 注册周期任务
+    ///
+    /// 每次触发时间为 `schedule` 计算出的时刻加上 `[0, jitter)` 的随机延迟；
+    /// 任务执行期间被取消时会中断当前执行。
+    pub(crate) fn schedule(
+        &self,
+        app: &AppHandle,
+        name: &'static str,
+        schedule: Schedule,
+        jitter: Duration,
+        clock: Arc<dyn Clock>,
+        job: JobFn,
+    ) {
+        let app = app.clone();
+        self.spawn(name, async move {
+            loop {
+                let now = clock.now();
+                let wait = (schedule.next_run(now) - now).to_std().unwrap_or_default()
+                    + jitter_delay(jitter);
+                debug!(target: "scheduler", "任务 {} 将在 {}s 后执行", name, wait.as_secs());
+
+                tokio::time::sleep(wait).await;
+                info!(target: "scheduler", "执行任务 {}", name);
+                job(app.clone()).await;
+            }
+        });
+        info!(target: "scheduler", "已注册任务 {} ({:?})", name, schedule);
+    }
+
+    /// 取消指定任务（未注册时忽略）
+    pub(crate) fn cancel(&self, name: &str) {
+        let token = self
+            .jobs
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(name);
+        if let Some(token) = token {
+            token.cancel();
+            debug!(target: "scheduler", "任务 {} 已停止", name);
+        }
+    }
+
+    /// 取消全部任务（应用退出时调用）
+    pub(crate) fn shutdown(&self) {
+        let jobs = std::mem::take(&mut *self.jobs.lock().unwrap_or_else(|e| e.into_inner()));
+        for (name, token) in jobs {
+            token.cancel();
+            debug!(target: "scheduler", "任务 {} 已停止", name);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+
+    #[test]
+    fn daily_schedule_runs_later_today_or_tomorrow() {
+        let schedule = Schedule::Daily { hour: 3, minute: 0 };
+
+        let clock = MockClock::at(2024, 3, 15, 1, 0, 0);
+        let next = schedule.next_run(clock.now());
+        assert_eq!(next.naive_local().to_string(), "2024-03-15 03:00:00");
+
+        // 恰好在触发时刻时应顺延到次日，避免同一时刻重复执行
+        let clock = MockClock::at(2024, 3, 15, 3, 0, 0);
+        let next = schedule.next_run(clock.now());
+        assert_eq!(next.naive_local().to_string(), "2024-03-16 03:00:00");
+    }
+
+    #[test]
+    fn solar_schedule_falls_back_to_next_day_during_polar_night() {
+        let now = MockClock::at(2024, 12, 21, 12, 0, 0).now();
+        let schedule = Schedule::Solar {
+            event: SolarEvent::Sunrise,
+            latitude: 78.2,
+            longitude: 15.6,
+        };
+        assert!(schedule.next_run(now) > now);
+
+        let schedule = Schedule::Solar {
+            event: SolarEvent::Sunset,
+            latitude: 39.9,
+            longitude: 116.4,
+        };
+        let next = schedule.next_run(now);
+        assert!(next > now && next - now < ChronoDuration::days(1));
+    }
+
+    #[test]
+    fn jitter_stays_within_bounds() {
+        assert_eq!(jitter_delay(Duration::ZERO), Duration::ZERO);
+        for _ in 0..100 {
+            assert!(jitter_delay(Duration::from_secs(60)) < Duration::from_secs(60));
+        }
+    }
+
+    #[tokio::test]
+    async fn reregistering_cancels_previous_token() {
+        let scheduler = Scheduler::new();
+        let first = scheduler.register("backup");
+        let second = scheduler.register("backup");
+        assert!(first.is_cancelled());
+        assert!(!second.is_cancelled());
+
+        let update = scheduler.register("auto_update");
+        let sunrise = scheduler.register("sunrise");
+        scheduler.cancel("sunrise");
+        scheduler.cancel("missing");
+        assert!(sunrise.is_cancelled());
+        assert!(!update.is_cancelled());
+        scheduler.shutdown();
+        assert!(second.is_cancelled());
+        assert!(update.is_cancelled());
+        assert!(scheduler.jobs.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,268 @@
+//! 设置的并发访问
+//!
+//! `SettingsService` 统一持有内存中的设置和 watch 广播通道：
+//! - 读取只拿到快照，调用方不再持有设置锁；
+//! - `update` 在同一把锁内完成"读取 → 修改 → 归一化 → 持久化 → 广播"，
+//!   并发修改按顺序执行，不会互相覆盖；持久化失败时内存中的设置保持不变；
+//! - `lock_writes` 串行化完整的写入流程（`update` 之后的副作用、同步配置方案等），
+//!   避免并发命令的副作用交错执行，与最终设置不一致；
+//! - 每次提交递增修订号，`refresh_cache` 据此丢弃读取期间已过期的快照。
+
+use log::warn;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tauri::AppHandle;
+use tokio::sync::{Mutex, MutexGuard, watch};
+
+use crate::models::AppSettings;
+use crate::{http_client, network_gate, settings_store, wallpaper_manager};
+
+/// 一次设置修改前后的快照
+pub(crate) struct SettingsChange {
+    pub previous: AppSettings,
+    pub current: AppSettings,
+}
+
+pub(crate) struct SettingsService {
+    current: Mutex<AppSettings>,
+    tx: watch::Sender<AppSettings>,
+    /// 创建时的接收端：订阅者从它克隆，因此 setup 阶段加载设置也算作一次变更
+    rx: watch::Receiver<AppSettings>,
+    /// 完整写入流程的串行锁
+    writer: Mutex<()>,
+    /// 提交次数，每次 `commit` 递增
+    revision: AtomicU64,
+}
+
+/// 归一化语言与 mkt（保存和广播前统一执行）
+fn normalize(settings: &mut AppSettings) {
+    settings.normalize_language();
+    settings.compute_resolved_language();
+    settings.normalize_mkt();
+}
+
+impl SettingsService {
+    pub(crate) fn new(initial: AppSettings) -> Self {
+        let (tx, rx) = watch::channel(initial.clone());
+        Self {
+            current: Mutex::new(initial),
+            tx,
+            rx,
+            writer: Mutex::new(()),
+            revision: AtomicU64::new(0),
+        }
+    }
+
+    /// 当前设置的快照
+    pub(crate) async fn read(&self) -> AppSettings {
+        self.current.lock().await.clone()
+    }
+
+    /// 同步读取快照（用于托盘等同步回调），设置正被修改时返回 None
+    pub(crate) fn try_read(&self) -> Option<AppSettings> {
+        self.current
+            .try_lock()
+            .ok()
+            .map(|settings| settings.clone())
+    }
+
+    /// 订阅设置变更（自动更新任务等据此响应设置修改）
+    pub(crate) fn subscribe(&self) -> watch::Receiver<AppSettings> {
+        self.rx.clone()
+    }
+
+    /// 获取写入锁，持有期间其他写入流程等待
+    ///
+    /// 只用于串行化 `update` 前后的完整流程；`update` 本身不获取此锁，持有者可以直接调用。
+    pub(crate) async fn lock_writes(&self) -> MutexGuard<'_, ()> {
+        self.writer.lock().await
+    }
+
+    /// 当前修订号（读取持久化设置前记录，刷新缓存时用于判断快照是否过期）
+    pub(crate) fn revision(&self) -> u64 {
+        self.revision.load(Ordering::Acquire)
+    }
+
+    /// 写入内存并广播（不持久化）
+    fn commit(&self, current: &mut AppSettings, settings: AppSettings) -> AppSettings {
+        wallpaper_manager::set_portrait_variant_enabled(settings.enable_portrait_variant);
+        wallpaper_manager::set_virtual_desktop_mode(&settings.virtual_desktop_mode);
+        http_client::configure_tls(settings.custom_ca_path.as_deref(), settings.custom_ca_only);
+        http_client::configure_keep_alive(settings.http_keep_alive_secs);
+        network_gate::configure(settings.bing_only_network);
+        self.tx.send_replace(settings.clone());
+        self.revision.fetch_add(1, Ordering::AcqRel);
+        std::mem::replace(current, settings)
+    }
+
+    /// setup 阶段加载持久化设置
+    ///
+    /// setup 是同步回调，此时没有其他任务持有设置锁，因此使用 `try_lock`。
+    pub(crate) fn init(&self, settings: AppSettings) {
+        match self.current.try_lock() {
+            Ok(mut current) => {
+                self.commit(&mut current, settings);
+            }
+            Err(_) => warn!(target: "startup", "状态 settings 被占用，跳过初始化"),
+        }
+    }
+
+    /// 用已持久化的设置替换内存中的设置并广播（调用方负责保存，如重置应用）
+    pub(crate) async fn replace(&self, settings: AppSettings) {
+        let mut current = self.current.lock().await;
+        self.commit(&mut current, settings);
+    }
+
+    /// 只刷新内存缓存，不广播（`get_settings` 从 store 重新读取时使用）
+    ///
+    /// `revision` 为读取前记录的修订号；期间已有新的提交时快照已过期，不覆盖并返回 `false`。
+    pub(crate) async fn refresh_cache(&self, settings: AppSettings, revision: u64) -> bool {
+        let mut current = self.current.lock().await;
+        if self.revision() != revision {
+            return false;
+        }
+        *current = settings;
+        true
+    }
+
+    /// 修改设置：在锁内执行 `f`，归一化后持久化并广播
+    ///
+    /// `f` 返回错误或持久化失败时不修改内存中的设置。
+    pub(crate) async fn update<E>(
+        &self,
+        app: &AppHandle,
+        f: impl FnOnce(&mut AppSettings) -> Result<(), E>,
+    ) -> Result<SettingsChange, E>
+    where
+        E: From<String>,
+    {
+        let mut current = self.current.lock().await;
+        let mut next = current.clone();
+        f(&mut next)?;
+        normalize(&mut next);
+        settings_store::save_settings_async(app, &next)
+            .await
+            .map_err(|e| E::from(format!("保存设置到 store 失败: {e}")))?;
+        let previous = self.commit(&mut current, next.clone());
+        Ok(SettingsChange {
+            previous,
+            current: next,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_replace_broadcasts_to_subscribers() {
+        let service = SettingsService::new(AppSettings::default());
+        let mut rx = service.subscribe();
+
+        service
+            .replace(AppSettings {
+                mkt: "ja-JP".to_string(),
+                ..Default::default()
+            })
+            .await;
+
+        assert!(rx.has_changed().unwrap());
+        assert_eq!(rx.borrow_and_update().mkt, "ja-JP");
+        assert_eq!(service.read().await.mkt, "ja-JP");
+        assert_eq!(service.try_read().unwrap().mkt, "ja-JP");
+    }
+
+    #[tokio::test]
+    async fn test_refresh_cache_does_not_broadcast() {
+        let service = SettingsService::new(AppSettings::default());
+        let rx = service.subscribe();
+
+        let revision = service.revision();
+        let refreshed = service
+            .refresh_cache(
+                AppSettings {
+                    theme: "dark".to_string(),
+                    ..Default::default()
+                },
+                revision,
+            )
+            .await;
+
+        assert!(refreshed);
+        assert!(!rx.has_changed().unwrap());
+        assert_eq!(service.read().await.theme, "dark");
+    }
+
+    #[tokio::test]
+    async fn test_stale_refresh_does_not_overwrite_commit() {
+        let service = SettingsService::new(AppSettings::default());
+        // get_settings 读取 store 期间，另一个命令提交了新设置
+        let revision = service.revision();
+        service
+            .replace(AppSettings {
+                mkt: "ja-JP".to_string(),
+                ..Default::default()
+            })
+            .await;
+
+        let refreshed = service
+            .refresh_cache(AppSettings::default(), revision)
+            .await;
+
+        assert!(!refreshed);
+        assert_eq!(service.read().await.mkt, "ja-JP");
+    }
+
+    #[tokio::test]
+    async fn test_lock_writes_serializes_concurrent_writers() {
+        let service = std::sync::Arc::new(SettingsService::new(AppSettings::default()));
+        let log = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let writers: Vec<_> = (0..4)
+            .map(|id| {
+                let service = service.clone();
+                let log = log.clone();
+                tokio::spawn(async move {
+                    let _writes = service.lock_writes().await;
+                    log.lock().unwrap().push(("begin", id));
+                    service
+                        .replace(AppSettings {
+                            mkt: format!("writer-{id}"),
+                            ..Default::default()
+                        })
+                        .await;
+                    // 模拟提交后的副作用（托盘、主题等）
+                    tokio::task::yield_now().await;
+                    log.lock().unwrap().push(("end", id));
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.await.unwrap();
+        }
+
+        let log = log.lock().unwrap().clone();
+        assert_eq!(log.len(), 8);
+        // 每个写入流程的开始与结束相邻，没有交错
+        for pair in log.chunks(2) {
+            assert_eq!(pair[0].0, "begin");
+            assert_eq!(pair[1], ("end", pair[0].1));
+        }
+        let last = log.last().unwrap().1;
+        assert_eq!(service.read().await.mkt, format!("writer-{last}"));
+        assert_eq!(service.revision(), 4);
+    }
+
+    #[test]
+    fn test_normalize_fills_resolved_language_and_mkt() {
+        let mut settings = AppSettings {
+            language: "en-US".to_string(),
+            resolved_language: String::new(),
+            mkt: String::new(),
+            ..Default::default()
+        };
+        normalize(&mut settings);
+        assert_eq!(settings.resolved_language, "en-US");
+        assert_eq!(settings.mkt, "en-US");
+    }
+}
@@ -1,39 +1,50 @@
 //! 设置持久化模块
 //!
 //! 使用 tauri-plugin-store 管理应用设置的持久化存储
+//!
+//! 同步版本读写磁盘，仅用于 setup 等同步上下文；异步命令中应使用 `*_async` 版本，
+//! 它们在阻塞线程池中执行，不会阻塞运行时线程。
 
-use crate::models::AppSettings;
-use log::info;
+use crate::models::{AppSettings, SettingsPolicy};
+use crate::policy;
+use log::{info, warn};
+use std::path::PathBuf;
 use tauri::AppHandle;
 use tauri_plugin_store::StoreExt;
 
 const SETTINGS_STORE_FILE: &str = "settings.json";
 const SETTINGS_KEY: &str = "app_settings";
 
-/// 从 store 加载设置
+/// 从 store 加载设置，并按管理员策略合并默认值和锁定值
 pub fn load_settings(app: &AppHandle) -> anyhow::Result<AppSettings> {
     let store = app
         .store(SETTINGS_STORE_FILE)
         .map_err(|e| anyhow::anyhow!("Failed to access store: {}", e))?;
 
-    match store.get(SETTINGS_KEY) {
-        Some(value) => {
-            let mut settings: AppSettings = serde_json::from_value(value.clone())
-                .map_err(|e| anyhow::anyhow!("Failed to deserialize settings: {}", e))?;
-
-            // 归一化语言设置：非中文/英文的值一律走系统语言检测
-            settings.normalize_language();
-            // 先计算 resolved_language，再归一化 mkt（mkt 回退依赖 resolved_language）
-            settings.compute_resolved_language();
-            settings.normalize_mkt();
-
-            Ok(settings)
-        }
-        None => {
-            info!(target: "settings_store", "Store 中没有设置，使用默认设置");
-            Ok(AppSettings::default())
-        }
+    let stored = store.get(SETTINGS_KEY);
+    if stored.is_none() {
+        info!(target: "settings_store", "Store 中没有设置，使用默认设置");
     }
+    settings_from_stored(stored.as_ref())
+}
+
+/// 按管理员策略合并保存的设置，并归一化语言和 mkt
+fn settings_from_stored(stored: Option<&serde_json::Value>) -> anyhow::Result<AppSettings> {
+    let mut settings = policy::current()
+        .merge(stored)
+        .or_else(|e| {
+            warn!(target: "settings_store", "应用设置策略失败: {}，忽略策略", e);
+            SettingsPolicy::default().merge(stored)
+        })
+        .map_err(|e| anyhow::anyhow!("Failed to deserialize settings: {}", e))?;
+
+    // 归一化语言设置：非中文/英文的值一律走系统语言检测
+    settings.normalize_language();
+    // 先计算 resolved_language，再归一化 mkt（mkt 回退依赖 resolved_language）
+    settings.compute_resolved_language();
+    settings.normalize_mkt();
+
+    Ok(settings)
 }
 
 /// 保存设置到 store
@@ -55,6 +66,55 @@ pub fn save_settings(app: &AppHandle, settings: &AppSettings) -> anyhow::Result<
     Ok(())
 }
 
+/// settings.json 在磁盘上的路径
+pub fn settings_file_path(app: &AppHandle) -> anyhow::Result<PathBuf> {
+    tauri_plugin_store::resolve_store_path(app, SETTINGS_STORE_FILE)
+        .map_err(|e| anyhow::anyhow!("Failed to resolve store path: {}", e))
+}
+
+/// 解析 settings.json 的文件内容
+fn parse_settings_file(contents: &str) -> anyhow::Result<AppSettings> {
+    let file: serde_json::Value = serde_json::from_str(contents)
+        .map_err(|e| anyhow::anyhow!("Failed to parse settings file: {}", e))?;
+    settings_from_stored(file.get(SETTINGS_KEY))
+}
+
+/// 直接读取磁盘上的 settings.json（感知外部修改）
+///
+/// 不经过 store：外部修改被拒绝时 store 的内存缓存仍是当前设置，之后的 `load_settings`
+/// 不会读到被拒绝的值。修改被接受后由 `save_settings` 写入 store。
+pub fn read_settings_file(app: &AppHandle) -> anyhow::Result<AppSettings> {
+    let path = settings_file_path(app)?;
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+    parse_settings_file(&contents)
+}
+
+/// 异步加载设置（在阻塞线程池中执行）
+pub async fn load_settings_async(app: &AppHandle) -> anyhow::Result<AppSettings> {
+    let app = app.clone();
+    tokio::task::spawn_blocking(move || load_settings(&app))
+        .await
+        .map_err(|e| anyhow::anyhow!("Settings load task failed: {}", e))?
+}
+
+/// 异步读取磁盘上的 settings.json（在阻塞线程池中执行）
+pub async fn read_settings_file_async(app: &AppHandle) -> anyhow::Result<AppSettings> {
+    let app = app.clone();
+    tokio::task::spawn_blocking(move || read_settings_file(&app))
+        .await
+        .map_err(|e| anyhow::anyhow!("Settings read task failed: {}", e))?
+}
+
+/// 异步保存设置（在阻塞线程池中执行）
+pub async fn save_settings_async(app: &AppHandle, settings: &AppSettings) -> anyhow::Result<()> {
+    let app = app.clone();
+    let settings = settings.clone();
+    tokio::task::spawn_blocking(move || save_settings(&app, &settings))
+        .await
+        .map_err(|e| anyhow::anyhow!("Settings save task failed: {}", e))?
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -73,4 +133,18 @@ mod tests {
 
         assert_eq!(deserialized.auto_update, settings.auto_update);
     }
+
+    #[test]
+    fn test_parse_settings_file() {
+        let settings =
+            parse_settings_file(r#"{"app_settings":{"theme":"dark","save_directory":"relative"}}"#)
+                .unwrap();
+        assert_eq!(settings.theme, "dark");
+        // 校验失败的修改同样能解析出来，由 apply_settings 拒绝；解析本身不经过 store
+        assert!(settings.validate().contains_key("save_directory"));
+
+        let empty = parse_settings_file("{}").unwrap();
+        assert_eq!(empty.theme, AppSettings::default().theme);
+        assert!(parse_settings_file(r#"{"app_settings":"#).is_err());
+    }
 }
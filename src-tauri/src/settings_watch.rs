@@ -0,0 +1,117 @@
+//! 外部修改 settings.json 的检测
+//!
+//! 用户手动编辑或同步工具（Syncthing、网盘等）会直接改写 settings.json，而应用保存设置时以内存中的
+//! 设置为准，外部修改会在下一次保存时被静默覆盖。这里定期检查文件的修改时间和大小，变化后直接解析
+//! 文件（不经过 store，被拒绝的修改不会留在 store 的缓存中）；与内存中的设置不同时走
+//! `update_settings` 的同一流程（策略、校验、归一化、持久化、广播及副作用），再通知前端重新获取。
+//!
+//! 应用自身保存引起的变化重新读取后与内存一致，不做任何事。内容无法解析（如同步工具只写了一半）或
+//! 校验失败时保留当前设置，等待文件的下一次变化。
+
+use log::{info, warn};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Manager};
+
+use crate::models::AppSettings;
+use crate::{AppState, commands, settings_store};
+
+const SETTINGS_WATCH_JOB: &str = "settings_watch";
+const CHECK_INTERVAL: Duration = Duration::from_secs(3);
+
+/// 文件的修改时间与大小，文件不存在时返回 None
+fn file_fingerprint(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// 两份设置的内容是否不同（`AppSettings` 没有实现 `PartialEq`，按序列化结果比较）
+fn settings_differ(a: &AppSettings, b: &AppSettings) -> bool {
+    serde_json::to_value(a).ok() != serde_json::to_value(b).ok()
+}
+
+/// 重新加载磁盘上的设置，与内存中不同时应用并通知前端
+async fn reload_external_changes(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let writes = state.settings.lock_writes().await;
+    let mut stored = match settings_store::read_settings_file_async(app).await {
+        Ok(settings) => settings,
+        Err(e) => {
+            warn!(target: "settings", "重新读取 settings.json 失败: {:#}，保留当前设置", e);
+            return;
+        }
+    };
+    let current = state.settings.read().await;
+    // 自启动以系统实际状态为准（见 `get_settings`），不随文件内容切换
+    stored.launch_at_startup = current.launch_at_startup;
+    if !settings_differ(&stored, &current) {
+        return;
+    }
+
+    info!(target: "settings", "检测到 settings.json 被外部修改，重新加载设置");
+    if let Err(e) = commands::settings::apply_settings(stored, &state, app).await {
+        warn!(target: "settings", "应用外部修改的设置失败: {}，保留当前设置", e);
+        return;
+    }
+    // apply_settings 已通过 settings-changed 事件通知前端具体变化
+    drop(writes);
+}
+
+/// 启动 settings.json 外部修改检测任务
+pub(crate) fn start_settings_watch_task(app: AppHandle) {
+    let path = match settings_store::settings_file_path(&app) {
+        Ok(path) => path,
+        Err(e) => {
+            warn!(target: "settings", "无法确定 settings.json 路径，不检测外部修改: {}", e);
+            return;
+        }
+    };
+    let scheduler = app.state::<AppState>().scheduler.clone();
+    scheduler.spawn(SETTINGS_WATCH_JOB, async move {
+        let mut previous = file_fingerprint(&path);
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+            let current = file_fingerprint(&path);
+            // 文件被删除时不做处理，下一次保存会重新创建
+            if current.is_none() || current == previous {
+                continue;
+            }
+            previous = current;
+            reload_external_changes(&app).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_fingerprint_tracks_size_changes() {
+        let unique = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let path = std::env::temp_dir().join(format!("bw_settings_watch_{unique}.json"));
+        assert_eq!(file_fingerprint(&path), None);
+
+        std::fs::write(&path, b"{}").unwrap();
+        let first = file_fingerprint(&path).unwrap();
+        assert_eq!(first.1, 2);
+        std::fs::write(&path, br#"{"app_settings":{}}"#).unwrap();
+        assert_ne!(file_fingerprint(&path), Some(first));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_settings_differ_compares_content() {
+        let settings = AppSettings::default();
+        assert!(!settings_differ(&settings, &settings.clone()));
+        let edited = AppSettings {
+            theme: "dark".to_string(),
+            ..settings.clone()
+        };
+        assert!(settings_differ(&settings, &edited));
+    }
+}
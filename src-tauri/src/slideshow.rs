@@ -0,0 +1,272 @@
+//! 壁纸"故事模式"幻灯片
+//!
+//! 打开一个全屏无边框窗口，按时间顺序轮播存档中的壁纸并叠加标题和版权信息，
+//! 可以临时当作屏保，或用来展示收藏。窗口页面为静态的 `slideshow.html`，
+//! 首张壁纸通过初始化脚本注入，之后由后端定时器调用页面的 `__showSlide` 切换。
+//!
+//! 按 Esc 或点击画面时页面导航到约定的关闭地址，由后端拦截后关闭窗口。
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+
+use crate::models::LocalWallpaper;
+use crate::{AppState, get_effective_mkt, storage};
+
+const SLIDESHOW_LABEL: &str = "slideshow";
+const SLIDESHOW_PAGE: &str = "slideshow.html";
+/// 页面请求关闭时导航到的地址（被 `on_navigation` 拦截，不会真正加载）
+const CLOSE_URL_HOST: &str = "close.slideshow";
+/// 托盘启动幻灯片时使用的切换间隔
+pub(crate) const DEFAULT_INTERVAL_SECS: u64 = 10;
+const MIN_INTERVAL_SECS: u64 = 3;
+const MAX_INTERVAL_SECS: u64 = 3600;
+
+/// 每次打开幻灯片递增，避免旧的定时器继续驱动新打开的窗口
+static SLIDESHOW_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// 播放范围：按壁纸日期（`YYYYMMDD`，闭区间）筛选，未指定的一端不限制
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub(crate) struct SlideshowRange {
+    #[serde(default)]
+    pub from: Option<String>,
+    #[serde(default)]
+    pub to: Option<String>,
+}
+
+impl SlideshowRange {
+    fn contains(&self, end_date: &str) -> bool {
+        self.from.as_deref().is_none_or(|from| end_date >= from)
+            && self.to.as_deref().is_none_or(|to| end_date <= to)
+    }
+
+    fn is_valid(&self) -> bool {
+        let valid_date = |date: &str| date.len() == 8 && date.bytes().all(|b| b.is_ascii_digit());
+        self.from.as_deref().is_none_or(valid_date) && self.to.as_deref().is_none_or(valid_date)
+    }
+}
+
+/// 单张幻灯片（注入页面的数据）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Slide {
+    path: String,
+    title: String,
+    copyright: String,
+    end_date: String,
+}
+
+/// 注入页面的初始数据（`window.__SLIDESHOW__`）
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SlideshowInit<'a> {
+    lang: &'a str,
+    close_url: String,
+    first: &'a Slide,
+    next: Option<&'a Slide>,
+}
+
+/// 从壁纸列表中选出范围内、图片已下载的壁纸，按时间从旧到新排列
+///
+/// `image_path` 返回已下载图片的路径，未下载时返回 None。
+fn select_slides(
+    wallpapers: &[LocalWallpaper],
+    range: &SlideshowRange,
+    image_path: impl Fn(&str) -> Option<String>,
+) -> Vec<Slide> {
+    let mut slides: Vec<Slide> = wallpapers
+        .iter()
+        .filter(|w| range.contains(&w.end_date))
+        .filter_map(|w| {
+            Some(Slide {
+                path: image_path(&w.end_date)?,
+                title: w.title.clone(),
+                copyright: w.copyright.clone(),
+                end_date: w.end_date.clone(),
+            })
+        })
+        .collect();
+    slides.sort_by(|a, b| a.end_date.cmp(&b.end_date));
+    slides.dedup_by(|a, b| a.end_date == b.end_date);
+    slides
+}
+
+fn close_slideshow(app: &AppHandle) {
+    // 使后台定时器失效
+    SLIDESHOW_GENERATION.fetch_add(1, Ordering::SeqCst);
+    if let Some(window) = app.get_webview_window(SLIDESHOW_LABEL) {
+        let _ = window.close();
+    }
+}
+
+/// 打开幻灯片窗口（已打开时先关闭旧窗口）
+pub(crate) async fn open_slideshow(
+    app: &AppHandle,
+    interval: Duration,
+    range: &SlideshowRange,
+) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let wallpaper_dir = state.wallpaper_directory.lock().await.clone();
+    let language = state.settings.read().await.resolved_language;
+    let mkt = get_effective_mkt(&state).await;
+
+    let wallpapers = storage::get_local_wallpapers(&wallpaper_dir, &mkt)
+        .await
+        .map_err(|e| e.to_string())?;
+    let slides = select_slides(&wallpapers, range, |end_date| {
+        let path = storage::get_wallpaper_path(&wallpaper_dir, end_date);
+        path.exists().then(|| path.to_string_lossy().to_string())
+    });
+    if slides.is_empty() {
+        return Err("NO_WALLPAPERS".to_string());
+    }
+    close_slideshow(app);
+
+    let init = SlideshowInit {
+        lang: &language,
+        close_url: format!("https://{CLOSE_URL_HOST}/"),
+        first: &slides[0],
+        next: slides.get(1),
+    };
+    let data = serde_json::to_string(&init).map_err(|e| e.to_string())?;
+    let close_handle = app.clone();
+    WebviewWindowBuilder::new(app, SLIDESHOW_LABEL, WebviewUrl::App(SLIDESHOW_PAGE.into()))
+        .title("Bing Wallpaper Now")
+        .fullscreen(true)
+        .decorations(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .focused(true)
+        .initialization_script(format!("window.__SLIDESHOW__ = {data};"))
+        .on_navigation(move |url| {
+            if url.host_str() != Some(CLOSE_URL_HOST) {
+                return url.path().ends_with(SLIDESHOW_PAGE);
+            }
+            let app = close_handle.clone();
+            // 不能在导航回调中同步关闭自身所在的窗口
+            tauri::async_runtime::spawn(async move { close_slideshow(&app) });
+            false
+        })
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let generation = SLIDESHOW_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    info!(
+        target: "slideshow",
+        "开始幻灯片放映: {} 张，间隔 {:?}",
+        slides.len(),
+        interval
+    );
+    if slides.len() < 2 {
+        return Ok(());
+    }
+
+    let timer_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut index = 0;
+        loop {
+            tokio::time::sleep(interval).await;
+            if SLIDESHOW_GENERATION.load(Ordering::SeqCst) != generation {
+                break;
+            }
+            let Some(window) = timer_handle.get_webview_window(SLIDESHOW_LABEL) else {
+                break;
+            };
+            index = (index + 1) % slides.len();
+            let next = &slides[(index + 1) % slides.len()];
+            let script = match (
+                serde_json::to_string(&slides[index]),
+                serde_json::to_string(next),
+            ) {
+                (Ok(slide), Ok(next)) => format!("window.__showSlide({slide}, {next});"),
+                _ => continue,
+            };
+            if let Err(e) = window.eval(script) {
+                warn!(target: "slideshow", "切换幻灯片失败: {}", e);
+                break;
+            }
+        }
+    });
+    Ok(())
+}
+
+/// 打开全屏幻灯片轮播存档中的壁纸
+///
+/// `interval` 为切换间隔（秒，3～3600，超出范围时返回 "INVALID_INTERVAL"），
+/// `range` 为日期范围（格式错误时返回 "INVALID_RANGE"），范围内没有已下载的壁纸时返回 "NO_WALLPAPERS"。
+#[tauri::command]
+pub(crate) async fn start_slideshow(
+    interval: u64,
+    range: Option<SlideshowRange>,
+    app: AppHandle,
+) -> Result<(), String> {
+    if !(MIN_INTERVAL_SECS..=MAX_INTERVAL_SECS).contains(&interval) {
+        return Err("INVALID_INTERVAL".to_string());
+    }
+    let range = range.unwrap_or_default();
+    if !range.is_valid() {
+        return Err("INVALID_RANGE".to_string());
+    }
+    open_slideshow(&app, Duration::from_secs(interval), &range).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wallpaper(end_date: &str) -> LocalWallpaper {
+        LocalWallpaper {
+            title: format!("Title {end_date}"),
+            copyright: String::new(),
+            copyright_link: String::new(),
+            end_date: end_date.to_string(),
+            urlbase: String::new(),
+            resolution: None,
+            portrait_available: None,
+            watermark_free: None,
+            recompression: None,
+        }
+    }
+
+    #[test]
+    fn test_select_slides_filters_range_and_sorts_oldest_first() {
+        let wallpapers = [
+            wallpaper("20240105"),
+            wallpaper("20240104"),
+            wallpaper("20240103"),
+            wallpaper("20240101"),
+        ];
+        let range = SlideshowRange {
+            from: Some("20240102".to_string()),
+            to: None,
+        };
+        let slides = select_slides(&wallpapers, &range, |end_date| {
+            (end_date != "20240104").then(|| format!("/w/{end_date}.jpg"))
+        });
+        let dates: Vec<_> = slides.iter().map(|s| s.end_date.as_str()).collect();
+        assert_eq!(dates, ["20240103", "20240105"]);
+        assert_eq!(slides[0].title, "Title 20240103");
+        assert_eq!(slides[0].path, "/w/20240103.jpg");
+    }
+
+    #[test]
+    fn test_range_validation() {
+        assert!(SlideshowRange::default().is_valid());
+        let range = SlideshowRange {
+            from: Some("20240101".to_string()),
+            to: Some("2024-01-31".to_string()),
+        };
+        assert!(!range.is_valid());
+
+        let january = SlideshowRange {
+            from: Some("20240101".to_string()),
+            to: Some("20240131".to_string()),
+        };
+        assert!(january.is_valid());
+        assert!(january.contains("20240101"));
+        assert!(january.contains("20240131"));
+        assert!(!january.contains("20240201"));
+    }
+}
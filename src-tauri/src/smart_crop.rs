@@ -0,0 +1,384 @@
+//! 超宽屏智能裁剪
+//!
+//! Bing 壁纸为 16:9，在 21:9 / 32:9 超宽屏上由系统缩放时会被拉伸或出现黑边。
+//! 检测到超宽屏时，按显示器比例从原图中裁出一块区域：垂直方向在"边缘能量"
+//! （画面细节）与"居中偏好"之间取最优位置，再缩放到显示器尺寸，
+//! 作为派生文件保存在壁纸目录的 `.derived/` 子目录中，原图保持不变。
+//!
+//! 某些日期/市场没有 1080x1920 竖屏版本时，也用同样的裁剪从横屏原图生成竖屏壁纸。
+//! 用户导出常见比例（16:9、16:10、4:3、21:9、手机竖屏）的版本时同样复用该裁剪。
+
+use anyhow::{Context, Result};
+use image::imageops::FilterType;
+use image::{DynamicImage, GrayImage};
+use log::{info, warn};
+use std::path::{Path, PathBuf};
+
+use crate::wallpaper_manager;
+
+/// 宽高比不低于该值视为超宽屏（21:9 ≈ 2.33，32:9 ≈ 3.56）
+const ULTRAWIDE_MIN_ASPECT: f64 = 2.2;
+
+/// 派生文件子目录（不在顶层，避免被索引校验、备份、导入导出当作壁纸）
+pub(crate) const DERIVED_DIR: &str = ".derived";
+
+/// 计算边缘能量时使用的缩略图高度，兼顾速度与精度
+const ANALYSIS_HEIGHT: u32 = 270;
+
+/// 居中偏好强度：裁剪窗口偏离中心到边缘时，得分最多降低该比例
+const CENTER_BIAS: f64 = 0.5;
+
+const DERIVED_JPEG_QUALITY: u8 = 92;
+
+/// 本地生成竖屏壁纸的尺寸（与 Bing 竖屏版本一致）
+const PORTRAIT_TARGET: (u32, u32) = (1080, 1920);
+
+/// 从显示器物理分辨率中找出需要裁剪的超宽屏尺寸（取面积最大的屏幕）
+pub(crate) fn ultrawide_target(screens: &[(u32, u32)]) -> Option<(u32, u32)> {
+    let &(width, height) = screens
+        .iter()
+        .max_by_key(|&&(w, h)| u64::from(w) * u64::from(h))?;
+    (height > 0 && f64::from(width) / f64::from(height) >= ULTRAWIDE_MIN_ASPECT)
+        .then_some((width, height))
+}
+
+/// 计算目标宽高比下的裁剪尺寸（尽量保留原图面积）
+fn crop_size(width: u32, height: u32, target: (u32, u32)) -> (u32, u32) {
+    let target_aspect = f64::from(target.0) / f64::from(target.1);
+    if f64::from(width) / f64::from(height) < target_aspect {
+        let crop_height = (f64::from(width) / target_aspect).round() as u32;
+        (width, crop_height.clamp(1, height))
+    } else {
+        let crop_width = (f64::from(height) * target_aspect).round() as u32;
+        (crop_width.clamp(1, width), height)
+    }
+}
+
+/// 每行的边缘能量（与下一行、右侧像素的亮度差之和）
+fn row_energy(gray: &GrayImage) -> Vec<f64> {
+    let (width, height) = gray.dimensions();
+    (0..height)
+        .map(|y| {
+            (0..width)
+                .map(|x| {
+                    let p = i32::from(gray.get_pixel(x, y)[0]);
+                    let right = i32::from(gray.get_pixel((x + 1).min(width - 1), y)[0]);
+                    let below = i32::from(gray.get_pixel(x, (y + 1).min(height - 1))[0]);
+                    f64::from((p - right).abs() + (p - below).abs())
+                })
+                .sum()
+        })
+        .collect()
+}
+
+/// 选择裁剪窗口的起始行：窗口内边缘能量越大越好，同时偏好靠近画面中心
+fn best_offset(energy: &[f64], window: usize) -> usize {
+    let total = energy.len();
+    if window >= total {
+        return 0;
+    }
+
+    let mut prefix = vec![0.0; total + 1];
+    for (i, e) in energy.iter().enumerate() {
+        prefix[i + 1] = prefix[i] + e;
+    }
+
+    let max_offset = total - window;
+    let center = max_offset as f64 / 2.0;
+    (0..=max_offset)
+        .map(|offset| {
+            let sum = prefix[offset + window] - prefix[offset];
+            let distance = if center > 0.0 {
+                (offset as f64 - center).abs() / center
+            } else {
+                0.0
+            };
+            (offset, sum * (1.0 - CENTER_BIAS * distance))
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1).then(b.0.cmp(&a.0)))
+        .map(|(offset, _)| offset)
+        .unwrap_or(max_offset / 2)
+}
+
+/// 按目标尺寸智能裁剪并缩放（不放大）
+fn smart_crop(image: &DynamicImage, target: (u32, u32)) -> DynamicImage {
+    let (width, height) = (image.width(), image.height());
+    let (crop_width, crop_height) = crop_size(width, height, target);
+
+    let (x, y) = if crop_height < height {
+        // 在缩略图上分析，再映射回原图坐标
+        let scale = f64::from(ANALYSIS_HEIGHT.min(height)) / f64::from(height);
+        let small = image
+            .resize_exact(
+                ((f64::from(width) * scale).round() as u32).max(1),
+                ((f64::from(height) * scale).round() as u32).max(1),
+                FilterType::Triangle,
+            )
+            .to_luma8();
+        let window = ((f64::from(crop_height) * scale).round() as usize).max(1);
+        let offset = best_offset(&row_energy(&small), window);
+        let y = ((offset as f64 / scale).round() as u32).min(height - crop_height);
+        (0, y)
+    } else {
+        ((width - crop_width) / 2, 0)
+    };
+
+    let cropped = image.crop_imm(x, y, crop_width, crop_height);
+    if target.0 < crop_width {
+        cropped.resize_exact(target.0, target.1, FilterType::Lanczos3)
+    } else {
+        cropped
+    }
+}
+
+/// 派生文件路径：`<壁纸目录>/.derived/<end_date>_<宽>x<高>.jpg`
+fn derivative_path(source: &Path, target: (u32, u32)) -> Option<PathBuf> {
+    let stem = source.file_stem()?.to_str()?;
+    let dir = source.parent()?.join(DERIVED_DIR);
+    Some(dir.join(format!("{stem}_{}x{}.jpg", target.0, target.1)))
+}
+
+/// 生成派生文件（已存在时直接复用）
+fn ensure_derivative(source: &Path, target: (u32, u32)) -> Result<PathBuf> {
+    let output = derivative_path(source, target).context("Invalid wallpaper path")?;
+    if output.exists() {
+        return Ok(output);
+    }
+
+    write_cropped(source, &output, target)?;
+    Ok(output)
+}
+
+/// 解码原图、按目标尺寸裁剪并写入 output
+fn write_cropped(source: &Path, output: &Path, target: (u32, u32)) -> Result<()> {
+    let image = image::open(source)
+        .with_context(|| format!("Failed to decode wallpaper: {}", source.display()))?;
+    save_jpeg(&smart_crop(&image, target), output)
+}
+
+/// 以 JPEG 原子写入 output（先写临时文件再重命名）
+fn save_jpeg(image: &DynamicImage, output: &Path) -> Result<()> {
+    let rgb = image.to_rgb8();
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent).context("Failed to create derived directory")?;
+    }
+    // 先写临时文件再重命名，避免中断后留下半张图片被复用
+    let temp = output.with_extension("jpg.tmp");
+    {
+        let file = std::fs::File::create(&temp).context("Failed to create derived file")?;
+        let mut writer = std::io::BufWriter::new(file);
+        image::codecs::jpeg::JpegEncoder::new_with_quality(&mut writer, DERIVED_JPEG_QUALITY)
+            .encode_image(&rgb)
+            .context("Failed to encode derived wallpaper")?;
+    }
+    std::fs::rename(&temp, output).context("Failed to save derived wallpaper")?;
+    Ok(())
+}
+
+/// 从横屏原图本地生成竖屏壁纸（Bing 未提供 1080x1920 版本时的兜底）
+///
+/// 保持原图高度、居中截取 9:16 区域，再缩放到 1080x1920（不放大）。
+pub(crate) fn generate_portrait(landscape: &Path, output: &Path) -> Result<()> {
+    write_cropped(landscape, output, PORTRAIT_TARGET)
+}
+
+/// 导出的常见比例：(文件名后缀, 目标尺寸)
+const EXPORT_PRESETS: [(&str, (u32, u32)); 5] = [
+    ("16x9", (3840, 2160)),
+    ("16x10", (3840, 2400)),
+    ("4x3", (2880, 2160)),
+    ("21x9", (5040, 2160)),
+    ("phone", (1179, 2556)),
+];
+
+/// 把壁纸导出为常见比例的裁剪版本，返回生成的文件路径
+///
+/// 文件名为 `<end_date>_<比例>.jpg`，已存在的同名文件会被覆盖。
+/// 只解码一次原图；原图小于目标尺寸时保留裁剪区域的原始分辨率（不放大）。
+/// 解码与编码较慢，调用方应放到 `spawn_blocking` 中执行。
+pub(crate) fn export_crops(source: &Path, output_dir: &Path) -> Result<Vec<PathBuf>> {
+    let stem = source
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .context("Invalid wallpaper path")?;
+    let image = image::open(source)
+        .with_context(|| format!("Failed to decode wallpaper: {}", source.display()))?;
+
+    let mut outputs = Vec::with_capacity(EXPORT_PRESETS.len());
+    for (suffix, target) in EXPORT_PRESETS {
+        let output = output_dir.join(format!("{stem}_{suffix}.jpg"));
+        save_jpeg(&smart_crop(&image, target), &output)?;
+        outputs.push(output);
+    }
+    Ok(outputs)
+}
+
+/// 获取实际交给系统设置的横屏壁纸路径
+///
+/// 非超宽屏或裁剪失败时返回原图路径，不影响壁纸设置流程。
+pub(crate) async fn prepare_for_display(source: &Path) -> PathBuf {
+    let screens = match tokio::task::spawn_blocking(wallpaper_manager::get_screen_pixel_sizes).await
+    {
+        Ok(screens) => screens,
+        Err(_) => return source.to_path_buf(),
+    };
+    let Some(target) = ultrawide_target(&screens) else {
+        return source.to_path_buf();
+    };
+
+    let source_owned = source.to_path_buf();
+    match tokio::task::spawn_blocking(move || ensure_derivative(&source_owned, target)).await {
+        Ok(Ok(path)) => {
+            info!(
+                target: "wallpaper",
+                "检测到超宽屏 {}x{}，使用裁剪后的壁纸: {}",
+                target.0,
+                target.1,
+                path.display()
+            );
+            path
+        }
+        Ok(Err(e)) => {
+            warn!(target: "wallpaper", "生成超宽屏裁剪壁纸失败: {e}，使用原图");
+            source.to_path_buf()
+        }
+        Err(e) => {
+            warn!(target: "wallpaper", "超宽屏裁剪任务异常: {e}，使用原图");
+            source.to_path_buf()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    #[test]
+    fn test_ultrawide_target_uses_largest_screen() {
+        assert_eq!(ultrawide_target(&[(3440, 1440)]), Some((3440, 1440)));
+        assert_eq!(ultrawide_target(&[(5120, 1440)]), Some((5120, 1440)));
+        assert_eq!(ultrawide_target(&[(2560, 1440), (3840, 2160)]), None);
+        assert_eq!(
+            ultrawide_target(&[(1920, 1080), (3440, 1440)]),
+            Some((3440, 1440))
+        );
+        assert_eq!(ultrawide_target(&[]), None);
+    }
+
+    #[test]
+    fn test_crop_size_keeps_full_width_for_wider_target() {
+        assert_eq!(crop_size(3840, 2160, (3440, 1440)), (3840, 1607));
+        assert_eq!(crop_size(3840, 2160, (5120, 1440)), (3840, 1080));
+        assert_eq!(crop_size(3840, 1080, (2560, 1080)), (2560, 1080));
+    }
+
+    #[test]
+    fn test_best_offset_prefers_detail_and_center() {
+        // 无细节时取正中
+        assert_eq!(best_offset(&[1.0; 10], 4), 3);
+        // 细节集中在底部时窗口下移
+        let mut energy = vec![0.0; 10];
+        energy[8] = 100.0;
+        energy[9] = 100.0;
+        assert_eq!(best_offset(&energy, 4), 6);
+    }
+
+    #[test]
+    fn test_smart_crop_follows_detailed_region() {
+        // 上半部分纯色，下半部分为棋盘格
+        let image = RgbImage::from_fn(320, 180, |x, y| {
+            if y >= 120 && (x / 4 + y / 4) % 2 == 0 {
+                Rgb([255, 255, 255])
+            } else {
+                Rgb([20, 40, 80])
+            }
+        });
+        let cropped = smart_crop(&DynamicImage::ImageRgb8(image), (320, 100));
+        assert_eq!((cropped.width(), cropped.height()), (320, 100));
+
+        let rgb = cropped.to_rgb8();
+        let has_detail = (0..cropped.width()).any(|x| rgb.get_pixel(x, 99)[0] == 255);
+        assert!(has_detail, "裁剪窗口应包含底部的细节区域");
+    }
+
+    #[test]
+    fn test_ensure_derivative_writes_and_reuses_file() {
+        let unique = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("bw_smart_crop_{unique}"));
+        std::fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("20240101.jpg");
+        RgbImage::from_pixel(640, 360, Rgb([10, 20, 30]))
+            .save(&source)
+            .unwrap();
+
+        let output = ensure_derivative(&source, (344, 144)).unwrap();
+        assert_eq!(output, dir.join(".derived").join("20240101_344x144.jpg"));
+        assert_eq!(image::image_dimensions(&output).unwrap(), (344, 144));
+
+        let modified = std::fs::metadata(&output).unwrap().modified().unwrap();
+        ensure_derivative(&source, (344, 144)).unwrap();
+        assert_eq!(
+            std::fs::metadata(&output).unwrap().modified().unwrap(),
+            modified
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_generate_portrait_keeps_full_height() {
+        let unique = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("bw_portrait_crop_{unique}"));
+        std::fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("20240101.jpg");
+        RgbImage::from_pixel(640, 360, Rgb([10, 20, 30]))
+            .save(&source)
+            .unwrap();
+
+        let output = dir.join("20240101r.jpg");
+        generate_portrait(&source, &output).unwrap();
+        // 原图高度不足 1920，不放大，只裁剪出 9:16 区域
+        assert_eq!(
+            image::image_dimensions(&output).unwrap(),
+            crop_size(640, 360, PORTRAIT_TARGET)
+        );
+        assert_eq!(crop_size(640, 360, PORTRAIT_TARGET).1, 360);
+        assert!(!dir.join("20240101r.jpg.tmp").exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_export_crops_writes_every_preset() {
+        let unique = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("bw_export_crops_{unique}"));
+        let output_dir = dir.join("export");
+        std::fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("20240101.jpg");
+        RgbImage::from_pixel(640, 360, Rgb([10, 20, 30]))
+            .save(&source)
+            .unwrap();
+
+        let outputs = export_crops(&source, &output_dir).unwrap();
+        assert_eq!(outputs.len(), EXPORT_PRESETS.len());
+        assert_eq!(outputs[0], output_dir.join("20240101_16x9.jpg"));
+        for (output, (_, target)) in outputs.iter().zip(EXPORT_PRESETS) {
+            assert_eq!(
+                image::image_dimensions(output).unwrap(),
+                crop_size(640, 360, target)
+            );
+        }
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,422 @@
+//! 按日出日落安排壁纸
+//!
+//! 对应设置 `solar_schedule`：
+//! - `"sunrise"`：日出时运行一次更新循环，应用当天的新壁纸；
+//! - `"day_night"`：日出切换到 `solar_day_wallpaper`，日落切换到 `solar_night_wallpaper`，
+//!   期间不再自动应用最新壁纸。
+//!
+//! 坐标优先使用设置中的经纬度，未设置时按 IP 定位（结果缓存在运行时状态中，
+//! 超过 [`GEO_LOCATION_MAX_AGE_DAYS`] 天后重新定位）。日出日落时刻按 NOAA 简化公式计算，
+//! 误差在一两分钟以内，两个时刻分别作为调度器中的 [`Schedule::Solar`] 任务注册。
+
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use log::{info, warn};
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::models::{AppSettings, GeoLocation};
+use crate::scheduler::Schedule;
+use crate::{AppState, commands, http_client, runtime_state, storage};
+
+/// 监听设置变化的常驻任务
+const SOLAR_WATCH_JOB: &str = "solar_schedule";
+const SUNRISE_JOB: &str = "sunrise";
+const SUNSET_JOB: &str = "sunset";
+
+/// 返回 JSON 格式坐标（`latitude` / `longitude`）的 IP 定位接口
+const GEO_LOCATION_URL: &str = "https://ipapi.co/json/";
+const GEO_TIMEOUT: Duration = Duration::from_secs(5);
+/// IP 定位结果的有效期
+const GEO_LOCATION_MAX_AGE_DAYS: i64 = 30;
+/// 定位失败后的重试间隔
+const LOCATION_RETRY_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// 日出/日落时太阳中心的高度角（考虑大气折射和太阳视半径）
+const SUN_ALTITUDE_DEG: f64 = -0.833;
+/// 查找下一次日出/日落的最大天数，覆盖极地最长约半年的极昼/极夜
+const MAX_SEARCH_DAYS: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SolarEvent {
+    Sunrise,
+    Sunset,
+}
+
+/// 计算指定日期（以经度换算的当地太阳日）的日出、日落时刻，极昼/极夜时返回 None
+fn sun_times(
+    date: NaiveDate,
+    latitude: f64,
+    longitude: f64,
+) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let days = (date - NaiveDate::from_ymd_opt(2000, 1, 1)?).num_days() as f64;
+    let mean_solar_noon = days - longitude / 360.0;
+    let anomaly = (357.5291 + 0.985_600_28 * mean_solar_noon).rem_euclid(360.0);
+    let anomaly_rad = anomaly.to_radians();
+    let center = 1.9148 * anomaly_rad.sin()
+        + 0.02 * (2.0 * anomaly_rad).sin()
+        + 0.0003 * (3.0 * anomaly_rad).sin();
+    let ecliptic_longitude = (anomaly + center + 180.0 + 102.9372)
+        .rem_euclid(360.0)
+        .to_radians();
+    let transit = 2_451_545.0 + mean_solar_noon + 0.0053 * anomaly_rad.sin()
+        - 0.0069 * (2.0 * ecliptic_longitude).sin();
+
+    let sin_declination = ecliptic_longitude.sin() * 23.4397_f64.to_radians().sin();
+    let cos_declination = sin_declination.asin().cos();
+    let latitude = latitude.to_radians();
+    let cos_hour_angle = (SUN_ALTITUDE_DEG.to_radians().sin() - latitude.sin() * sin_declination)
+        / (latitude.cos() * cos_declination);
+    if !(-1.0..=1.0).contains(&cos_hour_angle) {
+        return None;
+    }
+    let half_day = cos_hour_angle.acos().to_degrees() / 360.0;
+    Some((
+        julian_to_utc(transit - half_day)?,
+        julian_to_utc(transit + half_day)?,
+    ))
+}
+
+fn julian_to_utc(julian_day: f64) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp_millis(((julian_day - 2_440_587.5) * 86_400_000.0).round() as i64)
+}
+
+/// `now` 之后的下一次日出或日落，附近半年内都没有时返回 None
+pub(crate) fn next_event(
+    now: DateTime<Local>,
+    latitude: f64,
+    longitude: f64,
+    event: SolarEvent,
+) -> Option<DateTime<Local>> {
+    // 从前一天开始查找，覆盖本地日期与太阳日错开的时区
+    now.date_naive()
+        .pred_opt()?
+        .iter_days()
+        .take(MAX_SEARCH_DAYS)
+        .find_map(|date| {
+            let (sunrise, sunset) = sun_times(date, latitude, longitude)?;
+            let at = match event {
+                SolarEvent::Sunrise => sunrise,
+                SolarEvent::Sunset => sunset,
+            }
+            .with_timezone(&Local);
+            (at > now).then_some(at)
+        })
+}
+
+/// `now` 是否处于白天（下一次日落早于下一次日出），无法判断时返回 None
+fn is_daytime(now: DateTime<Local>, latitude: f64, longitude: f64) -> Option<bool> {
+    match (
+        next_event(now, latitude, longitude, SolarEvent::Sunrise),
+        next_event(now, latitude, longitude, SolarEvent::Sunset),
+    ) {
+        (Some(sunrise), Some(sunset)) => Some(sunset < sunrise),
+        (None, Some(_)) => Some(true),
+        (Some(_), None) => Some(false),
+        (None, None) => None,
+    }
+}
+
+/// 影响任务注册的设置
+#[derive(Debug, Clone, PartialEq)]
+struct SolarConfig {
+    mode: String,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+    day_wallpaper: Option<String>,
+    night_wallpaper: Option<String>,
+}
+
+impl SolarConfig {
+    fn from_settings(settings: &AppSettings) -> Self {
+        Self {
+            mode: settings.solar_schedule.clone(),
+            latitude: settings.solar_latitude,
+            longitude: settings.solar_longitude,
+            day_wallpaper: settings.solar_day_wallpaper.clone(),
+            night_wallpaper: settings.solar_night_wallpaper.clone(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct IpLocation {
+    latitude: f64,
+    longitude: f64,
+}
+
+async fn fetch_ip_location() -> Option<(f64, f64)> {
+    let client = http_client::builder().timeout(GEO_TIMEOUT).build().ok()?;
+    let response = match http_client::send(client.get(GEO_LOCATION_URL)).await {
+        Ok(response) if response.status().is_success() => response,
+        Ok(response) => {
+            warn!(target: "solar", "IP 定位失败: HTTP {}", response.status());
+            return None;
+        }
+        Err(http_client::SendError::Restricted) => return None,
+        Err(e) => {
+            warn!(target: "solar", "IP 定位失败: {}", e);
+            return None;
+        }
+    };
+    let location: IpLocation = response.json().await.ok()?;
+    Some((location.latitude, location.longitude))
+}
+
+fn geo_location_fresh(location: &GeoLocation, now: DateTime<Local>) -> bool {
+    DateTime::parse_from_rfc3339(&location.resolved_at)
+        .is_ok_and(|at| (now - at.with_timezone(&Local)).num_days() < GEO_LOCATION_MAX_AGE_DAYS)
+}
+
+/// 确定计算日出日落所用的坐标：设置中的经纬度优先，否则使用（缓存的）IP 定位
+async fn resolve_location(app: &AppHandle, config: &SolarConfig) -> Option<(f64, f64)> {
+    if let (Some(latitude), Some(longitude)) = (config.latitude, config.longitude) {
+        return Some((latitude, longitude));
+    }
+
+    let now = app.state::<AppState>().clock.now();
+    let runtime = runtime_state::load_runtime_state(app).unwrap_or_default();
+    if let Some(cached) = &runtime.geo_location
+        && geo_location_fresh(cached, now)
+    {
+        return Some((cached.latitude, cached.longitude));
+    }
+
+    match fetch_ip_location().await {
+        Some((latitude, longitude)) => {
+            info!(target: "solar", "IP 定位成功: ({:.2}, {:.2})", latitude, longitude);
+            if let Err(e) = runtime_state::modify_runtime_state(app, |runtime| {
+                runtime.geo_location = Some(GeoLocation {
+                    latitude,
+                    longitude,
+                    resolved_at: now.to_rfc3339(),
+                });
+                true
+            }) {
+                warn!(target: "solar", "保存定位结果失败: {}", e);
+            }
+            Some((latitude, longitude))
+        }
+        // 重新定位失败时继续使用过期的结果
+        None => runtime
+            .geo_location
+            .map(|cached| (cached.latitude, cached.longitude)),
+    }
+}
+
+/// 应用白天或夜晚对应的壁纸（未设置、文件不存在或已是当前壁纸时跳过）
+async fn apply_period_wallpaper(app: &AppHandle, daytime: bool) {
+    let state = app.state::<AppState>();
+    let end_date = {
+        let settings = state.settings.read().await;
+        if daytime {
+            settings.solar_day_wallpaper
+        } else {
+            settings.solar_night_wallpaper
+        }
+    };
+    let Some(end_date) = end_date else {
+        return;
+    };
+
+    let wallpaper_dir = state.wallpaper_directory.lock().await.clone();
+    let path = storage::get_wallpaper_path(&wallpaper_dir, &end_date);
+    if !path.exists() {
+        warn!(target: "solar", "{} 的壁纸不在本地，跳过切换", end_date);
+        return;
+    }
+    if state.current_wallpaper_path.lock().await.as_ref() == Some(&path) {
+        return;
+    }
+
+    info!(
+        target: "solar",
+        "切换到{}壁纸 {}",
+        if daytime { "白天" } else { "夜晚" },
+        end_date
+    );
+    if let Err(e) = commands::wallpaper::set_desktop_wallpaper(
+        path.to_string_lossy().to_string(),
+        app.state::<AppState>(),
+        app.clone(),
+    )
+    .await
+    {
+        warn!(target: "solar", "切换壁纸失败 {}: {}", end_date, e);
+    }
+}
+
+/// 日出/日落任务
+async fn on_solar_event(app: AppHandle, latitude: f64, longitude: f64, event: SolarEvent) {
+    let state = app.state::<AppState>();
+    let mode = state.settings.read().await.solar_schedule;
+    match mode.as_str() {
+        "sunrise" if event == SolarEvent::Sunrise => {
+            info!(target: "solar", "日出，更新并应用新壁纸");
+            crate::update_cycle::run_update_cycle_internal(&app, false).await;
+        }
+        // 按当前时刻判断时段，极昼/极夜期间的每日触发也能落在正确的壁纸上
+        "day_night" => {
+            if let Some(daytime) = is_daytime(state.clock.now(), latitude, longitude) {
+                apply_period_wallpaper(&app, daytime).await;
+            }
+        }
+        _ => {}
+    }
+}
+
+fn schedule_event(
+    app: &AppHandle,
+    name: &'static str,
+    event: SolarEvent,
+    latitude: f64,
+    longitude: f64,
+) {
+    let state = app.state::<AppState>();
+    state.scheduler.schedule(
+        app,
+        name,
+        Schedule::Solar {
+            event,
+            latitude,
+            longitude,
+        },
+        Duration::ZERO,
+        state.clock.clone(),
+        Arc::new(move |app| Box::pin(on_solar_event(app, latitude, longitude, event))),
+    );
+}
+
+/// 按设置（重新）注册日出日落任务，定位失败时返回 `false`
+async fn configure(app: &AppHandle, config: &SolarConfig) -> bool {
+    let state = app.state::<AppState>();
+    if config.mode == "off" {
+        state.scheduler.cancel(SUNRISE_JOB);
+        state.scheduler.cancel(SUNSET_JOB);
+        return true;
+    }
+
+    let Some((latitude, longitude)) = resolve_location(app, config).await else {
+        warn!(target: "solar", "无法确定所在位置，日出日落计划暂不可用");
+        state.scheduler.cancel(SUNRISE_JOB);
+        state.scheduler.cancel(SUNSET_JOB);
+        return false;
+    };
+
+    schedule_event(app, SUNRISE_JOB, SolarEvent::Sunrise, latitude, longitude);
+    if config.mode == "day_night" {
+        schedule_event(app, SUNSET_JOB, SolarEvent::Sunset, latitude, longitude);
+        // 立即切换到当前时段对应的壁纸
+        if let Some(daytime) = is_daytime(state.clock.now(), latitude, longitude) {
+            apply_period_wallpaper(app, daytime).await;
+        }
+    } else {
+        state.scheduler.cancel(SUNSET_JOB);
+    }
+    true
+}
+
+/// 启动日出日落计划：跟随设置变化重新注册任务，定位失败时定期重试
+pub(crate) fn start_solar_schedule_task(app: AppHandle) {
+    let state = app.state::<AppState>();
+    let mut rx = state.settings.subscribe();
+    let task_app = app.clone();
+    state.scheduler.spawn(SOLAR_WATCH_JOB, async move {
+        let app = task_app;
+        let mut applied: Option<SolarConfig> = None;
+        let mut located = true;
+        loop {
+            let config = SolarConfig::from_settings(&rx.borrow_and_update());
+            if applied.as_ref() != Some(&config) {
+                located = configure(&app, &config).await;
+                applied = Some(config);
+            }
+
+            tokio::select! {
+                changed = rx.changed() => {
+                    if changed.is_err() {
+                        break;
+                    }
+                }
+                _ = tokio::time::sleep(LOCATION_RETRY_INTERVAL), if !located => {
+                    applied = None;
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{Clock, MockClock};
+
+    fn assert_close(actual: DateTime<Utc>, expected: &str) {
+        let expected = DateTime::parse_from_rfc3339(expected).unwrap();
+        let diff = (actual - expected.with_timezone(&Utc)).num_seconds().abs();
+        assert!(diff <= 5 * 60, "{actual} 与 {expected} 相差 {diff}s");
+    }
+
+    #[test]
+    fn test_sun_times_match_reference_values() {
+        // 伦敦夏至
+        let (sunrise, sunset) = sun_times(
+            NaiveDate::from_ymd_opt(2024, 6, 21).unwrap(),
+            51.5074,
+            -0.1278,
+        )
+        .unwrap();
+        assert_close(sunrise, "2024-06-21T03:43:00Z");
+        assert_close(sunset, "2024-06-21T20:21:00Z");
+
+        // 北京春分（日出在 UTC 前一天）
+        let (sunrise, sunset) = sun_times(
+            NaiveDate::from_ymd_opt(2024, 3, 20).unwrap(),
+            39.9042,
+            116.4074,
+        )
+        .unwrap();
+        assert_close(sunrise, "2024-03-19T22:19:00Z");
+        assert_close(sunset, "2024-03-20T10:28:00Z");
+    }
+
+    #[test]
+    fn test_polar_day_and_night_have_no_sun_times() {
+        let date = NaiveDate::from_ymd_opt(2024, 6, 21).unwrap();
+        assert!(sun_times(date, 78.2, 15.6).is_none());
+        let date = NaiveDate::from_ymd_opt(2024, 12, 21).unwrap();
+        assert!(sun_times(date, 78.2, 15.6).is_none());
+    }
+
+    #[test]
+    fn test_next_event_and_daytime() {
+        let now = MockClock::at(2024, 3, 20, 12, 0, 0).now();
+        let sunrise = next_event(now, 39.9042, 116.4074, SolarEvent::Sunrise).unwrap();
+        let sunset = next_event(now, 39.9042, 116.4074, SolarEvent::Sunset).unwrap();
+        assert!(sunrise > now && sunset > now);
+        assert!(sunrise - now <= chrono::Duration::days(1));
+        assert!(sunset - now <= chrono::Duration::days(1));
+        assert_eq!(is_daytime(now, 39.9042, 116.4074), Some(sunset < sunrise));
+
+        // 极昼期间下一次日落在数月之后
+        let now = MockClock::at(2024, 6, 21, 12, 0, 0).now();
+        let sunset = next_event(now, 78.2, 15.6, SolarEvent::Sunset).unwrap();
+        assert!(sunset - now > chrono::Duration::days(30));
+    }
+
+    #[test]
+    fn test_geo_location_expires() {
+        let now = MockClock::at(2024, 3, 20, 12, 0, 0).now();
+        let location = |days_ago: i64| GeoLocation {
+            latitude: 0.0,
+            longitude: 0.0,
+            resolved_at: (now - chrono::Duration::days(days_ago)).to_rfc3339(),
+        };
+        assert!(geo_location_fresh(&location(1), now));
+        assert!(!geo_location_fresh(
+            &location(GEO_LOCATION_MAX_AGE_DAYS),
+            now
+        ));
+    }
+}
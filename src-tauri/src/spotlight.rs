@@ -0,0 +1,250 @@
+//! Windows 聚焦（Spotlight）图片来源
+//!
+//! Windows 聚焦把锁屏图片缓存在 ContentDeliveryManager 的 `Assets` 目录中，文件没有扩展名，
+//! 且混有图标、竖屏图等小文件。这里收集其中的横屏大图：按内容 SHA-256 去重后复制到壁纸目录的
+//! `spotlight/` 子目录，并以 [`SPOTLIGHT_MKT`] 写入索引（键为哈希前缀）。
+//! 之后按 `spotlight_schedule` 与 Bing 壁纸按天交替或混合轮换，规则与自定义文件夹相同。
+
+use anyhow::{Context, Result};
+use chrono::Datelike;
+use log::{info, warn};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use crate::models::{LocalWallpaper, SPOTLIGHT_MKT};
+use crate::{AppState, directory_permission, local_folder, storage};
+
+/// 壁纸目录下保存聚焦图片的子目录
+pub(crate) const SPOTLIGHT_DIR: &str = "spotlight";
+/// 聚焦缓存目录（相对于 `%LOCALAPPDATA%`）
+#[cfg(windows)]
+const ASSETS_RELATIVE_DIR: &str =
+    r"Packages\Microsoft.Windows.ContentDeliveryManager_cw5n1h2txyewy\LocalState\Assets";
+/// 小于该大小的缓存文件是图标或缩略图
+const MIN_ASSET_BYTES: u64 = 100 * 1024;
+/// 横屏壁纸的最小宽度
+const MIN_WIDTH: u32 = 1920;
+/// 索引键使用的哈希前缀长度（十六进制字符数）
+const ID_LEN: usize = 16;
+const SPOTLIGHT_TITLE: &str = "Windows Spotlight";
+
+/// 聚焦缓存目录，非 Windows 或目录不存在时返回 None
+fn assets_dir() -> Option<PathBuf> {
+    #[cfg(windows)]
+    {
+        dirs::data_local_dir()
+            .map(|dir| dir.join(ASSETS_RELATIVE_DIR))
+            .filter(|dir| dir.is_dir())
+    }
+    #[cfg(not(windows))]
+    {
+        None
+    }
+}
+
+/// 聚焦图片在壁纸目录中的路径
+pub(crate) fn image_path(wallpaper_dir: &Path, id: &str) -> PathBuf {
+    wallpaper_dir.join(SPOTLIGHT_DIR).join(format!("{id}.jpg"))
+}
+
+/// 读取文件头，横屏 JPEG 大图返回其尺寸
+fn landscape_dimensions(path: &Path) -> Option<(u32, u32)> {
+    let reader = image::ImageReader::open(path)
+        .ok()?
+        .with_guessed_format()
+        .ok()?;
+    if reader.format() != Some(image::ImageFormat::Jpeg) {
+        return None;
+    }
+    let (width, height) = reader.into_dimensions().ok()?;
+    (width >= MIN_WIDTH && width > height).then_some((width, height))
+}
+
+fn content_id(bytes: &[u8]) -> String {
+    let mut id = hex::encode(Sha256::digest(bytes));
+    id.truncate(ID_LEN);
+    id
+}
+
+/// 从聚焦缓存目录导入新的横屏图片，`known` 为已导入的图片 ID
+///
+/// 返回新导入图片的索引条目（标题固定，分辨率为实际像素尺寸）。
+fn harvest(
+    assets: &Path,
+    wallpaper_dir: &Path,
+    known: &HashSet<String>,
+) -> Result<Vec<LocalWallpaper>> {
+    let mut seen = known.clone();
+    let mut imported = Vec::new();
+    for entry in std::fs::read_dir(assets).context("Failed to read Spotlight assets")? {
+        let path = entry?.path();
+        let Ok(metadata) = std::fs::metadata(&path) else {
+            continue;
+        };
+        if !metadata.is_file() || metadata.len() < MIN_ASSET_BYTES {
+            continue;
+        }
+        let Some((width, height)) = landscape_dimensions(&path) else {
+            continue;
+        };
+
+        let bytes = std::fs::read(&path).context("Failed to read Spotlight asset")?;
+        let id = content_id(&bytes);
+        if !seen.insert(id.clone()) {
+            continue;
+        }
+        let target = image_path(wallpaper_dir, &id);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent).context("Failed to create Spotlight directory")?;
+        }
+        std::fs::write(&target, &bytes).context("Failed to save Spotlight image")?;
+
+        imported.push(LocalWallpaper {
+            title: SPOTLIGHT_TITLE.to_string(),
+            copyright: String::new(),
+            copyright_link: String::new(),
+            end_date: id,
+            urlbase: String::new(),
+            resolution: Some(format!("{width}x{height}")),
+            portrait_available: None,
+            watermark_free: None,
+            recompression: None,
+        });
+    }
+    Ok(imported)
+}
+
+/// 导入新的聚焦图片，返回新增数量（非 Windows 或缓存目录不存在时为 0）
+async fn import_new_images(wallpaper_dir: &Path) -> Result<usize> {
+    let Some(assets) = assets_dir() else {
+        return Ok(0);
+    };
+
+    // 索引中有记录但文件已被删除的图片重新导入
+    let index = storage::get_index_snapshot(wallpaper_dir).await?;
+    let known: HashSet<String> = index
+        .mkt
+        .get(SPOTLIGHT_MKT)
+        .map(|wallpapers| {
+            wallpapers
+                .keys()
+                .filter(|id| image_path(wallpaper_dir, id).exists())
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let dir = wallpaper_dir.to_path_buf();
+    let imported = tokio::task::spawn_blocking(move || harvest(&assets, &dir, &known))
+        .await
+        .context("Spotlight import task failed")??;
+    if imported.is_empty() {
+        return Ok(0);
+    }
+    storage::save_wallpapers_metadata(imported.clone(), wallpaper_dir, SPOTLIGHT_MKT).await?;
+    info!(target: "spotlight", "导入了 {} 张 Windows 聚焦图片", imported.len());
+    Ok(imported.len())
+}
+
+/// 按排期应用聚焦图片
+///
+/// 应用前先导入新缓存的图片。返回 `true` 表示今天由聚焦图片接管，调用方不再应用 Bing 壁纸。
+pub(crate) async fn apply_scheduled_image(app: &AppHandle, state: &AppState) -> bool {
+    let schedule = state.settings.read().await.spotlight_schedule.clone();
+    if schedule == "off" || !cfg!(windows) {
+        return false;
+    }
+
+    let wallpaper_dir = state.wallpaper_directory.lock().await.clone();
+    if let Err(e) = import_new_images(&wallpaper_dir).await {
+        warn!(target: "spotlight", "导入 Windows 聚焦图片失败: {}", e);
+    }
+    let ids: Vec<String> = storage::get_local_wallpapers(&wallpaper_dir, SPOTLIGHT_MKT)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|wallpaper| wallpaper.end_date)
+        .filter(|id| image_path(&wallpaper_dir, id).exists())
+        .collect();
+
+    let day_number = i64::from(state.clock.now().date_naive().num_days_from_ce());
+    let Some(id) = local_folder::pick_local_index(&schedule, day_number, ids.len())
+        .and_then(|index| ids.get(index))
+    else {
+        return false;
+    };
+    let path = image_path(&wallpaper_dir, id);
+    local_folder::apply_image(app, state, &path, SPOTLIGHT_MKT, id).await
+}
+
+/// 立即导入新的 Windows 聚焦图片，返回新增数量
+#[tauri::command]
+pub(crate) async fn import_spotlight_images(app: AppHandle) -> Result<usize, String> {
+    if !cfg!(windows) {
+        return Err("UNSUPPORTED_PLATFORM".to_string());
+    }
+    let wallpaper_dir = app
+        .state::<AppState>()
+        .wallpaper_directory
+        .lock()
+        .await
+        .clone();
+    if directory_permission::is_read_only_archive(&wallpaper_dir).await {
+        return Err(directory_permission::READ_ONLY_DIRECTORY.to_string());
+    }
+    import_new_images(&wallpaper_dir)
+        .await
+        .map_err(|e| format!("Failed to import Spotlight images: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::SystemTime;
+
+    fn write_jpeg(path: &Path, width: u32, height: u32, seed: u8) {
+        let image = image::RgbImage::from_fn(width, height, |x, y| {
+            image::Rgb([(x as u8) ^ seed, (y as u8).wrapping_mul(3), seed])
+        });
+        let mut bytes = Vec::new();
+        image::codecs::jpeg::JpegEncoder::new_with_quality(&mut bytes, 100)
+            .encode_image(&image)
+            .unwrap();
+        // 填充到最小大小之上（JPEG 解码器忽略 EOI 之后的数据）
+        bytes.resize(bytes.len().max(MIN_ASSET_BYTES as usize + 1), 0);
+        std::fs::write(path, bytes).unwrap();
+    }
+
+    #[test]
+    fn test_harvest_imports_landscape_images_once() {
+        let unique = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("bw_spotlight_{unique}"));
+        let assets = root.join("Assets");
+        let wallpaper_dir = root.join("wallpapers");
+        std::fs::create_dir_all(&assets).unwrap();
+
+        write_jpeg(&assets.join("a1"), 1920, 1080, 1);
+        // 同一图片的另一份缓存
+        std::fs::copy(assets.join("a1"), assets.join("a2")).unwrap();
+        write_jpeg(&assets.join("portrait"), 1080, 1920, 2);
+        write_jpeg(&assets.join("small"), 1280, 720, 3);
+        std::fs::write(assets.join("icon"), b"not an image").unwrap();
+
+        let imported = harvest(&assets, &wallpaper_dir, &HashSet::new()).unwrap();
+        assert_eq!(imported.len(), 1);
+        let id = &imported[0].end_date;
+        assert_eq!(id.len(), ID_LEN);
+        assert_eq!(imported[0].resolution.as_deref(), Some("1920x1080"));
+        assert!(image_path(&wallpaper_dir, id).exists());
+
+        let known = HashSet::from([id.clone()]);
+        assert!(harvest(&assets, &wallpaper_dir, &known).unwrap().is_empty());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
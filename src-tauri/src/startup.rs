@@ -0,0 +1,106 @@
+//! 启动流程的延后阶段
+//!
+//! setup 回调只准备窗口显示所需的关键状态（设置、壁纸目录、运行时状态、托盘），
+//! 其余耗时或依赖外部系统的工作放到后台依次执行：
+//!
+//! 1. 与系统登录项同步自启动状态
+//! 2. 修复上次未正常退出残留的状态
+//! 3. 预加载壁纸索引
+//! 4. 启动后台任务（首次更新检查由自动更新任务发起）
+//!
+//! 全部完成后广播 `startup-complete`，前端据此刷新列表和当前壁纸。
+
+use log::{info, warn};
+use std::path::PathBuf;
+use std::time::Instant;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_autostart::ManagerExt;
+
+use crate::models::AppRuntimeState;
+use crate::{
+    AppState, directory_permission, events, recovery, runtime_state, storage, wallpaper_theme,
+};
+
+/// 关键路径超过该时长时记录警告，便于发现拖慢窗口显示的新增工作
+pub(crate) const CRITICAL_PATH_BUDGET_MS: u128 = 300;
+
+/// 从操作系统读取真实的自启动状态并同步到设置
+///
+/// 用户可能在系统设置中手动修改了登录项，以系统实际状态为准。
+async fn sync_autostart_state(app: &AppHandle, mut runtime_state: AppRuntimeState) {
+    let system_autostart_enabled = app.autolaunch().is_enabled().unwrap_or_else(|e| {
+        warn!(target: "startup", "读取系统自启动状态失败: {}，假设为未启用", e);
+        false
+    });
+
+    let state = app.state::<AppState>();
+    let settings_autostart_enabled = state.settings.read().await.launch_at_startup;
+    if settings_autostart_enabled != system_autostart_enabled {
+        info!(target: "startup",
+            "检测到自启动状态不一致（设置: {}，系统: {}），更新设置为系统实际状态",
+            settings_autostart_enabled, system_autostart_enabled);
+        // 广播后 auto_update_task 等监听者能获取到正确的自启动状态
+        if let Err(e) = state
+            .settings
+            .update(app, |settings| {
+                settings.launch_at_startup = system_autostart_enabled;
+                Ok::<_, String>(())
+            })
+            .await
+        {
+            warn!(target: "startup", "保存同步后的设置失败: {}", e);
+        }
+    }
+
+    // 如果自启动已启用，但通知标志未设置，则自动设置标志
+    // 这适用于在更新到 0.4.10 之前就已经启用自启动的用户
+    if system_autostart_enabled && !runtime_state.autostart_notification_shown {
+        runtime_state.autostart_notification_shown = true;
+        if let Err(e) = runtime_state::modify_runtime_state(app, |stored| {
+            stored.autostart_notification_shown = true;
+            true
+        }) {
+            warn!(target: "startup", "保存自启动通知标志失败: {}", e);
+        } else {
+            info!(target: "startup", "检测到自启动已启用但通知标志未设置，已自动设置标志");
+        }
+    }
+}
+
+/// 执行启动的延后阶段，完成后广播 `startup-complete`
+pub(crate) async fn run_deferred_startup(
+    app: AppHandle,
+    runtime_state: AppRuntimeState,
+    wallpaper_dir: PathBuf,
+    previous_session_started_at: Option<String>,
+) {
+    let started = Instant::now();
+
+    sync_autostart_state(&app, runtime_state).await;
+
+    // 上次未正常退出时，需要在自动更新开始前修复残留状态（只读存档无法也无需修复）
+    if let Some(previous_started_at) = previous_session_started_at
+        && !directory_permission::archive_read_only(&app).await
+    {
+        recovery::reconcile_after_unclean_shutdown(&app, &wallpaper_dir, Some(previous_started_at))
+            .await;
+    }
+
+    // 预加载索引，首次打开画廊和更新检查时无需再等待磁盘读取
+    if let Err(e) = storage::get_available_mkt_keys(&wallpaper_dir).await {
+        warn!(target: "startup", "预加载壁纸索引失败: {}", e);
+    }
+
+    // 主题跟随壁纸时，按启动时恢复的当前壁纸同步托盘图标
+    let theme = app.state::<AppState>().settings.read().await.theme;
+    if theme == wallpaper_theme::WALLPAPER_THEME {
+        wallpaper_theme::on_theme_changed(&app, &theme).await;
+    }
+
+    crate::start_background_tasks(&app);
+
+    info!(target: "startup", "启动延后阶段完成，耗时 {:?}", started.elapsed());
+    if let Err(e) = events::STARTUP_COMPLETE.emit(&app, &()) {
+        warn!(target: "startup", "广播启动完成事件失败: {}", e);
+    }
+}
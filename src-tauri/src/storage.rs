@@ -1,24 +1,133 @@
 use crate::index_manager::IndexManager;
-use crate::models::{LocalWallpaper, WallpaperIndex};
+use crate::models::{LocalWallpaper, Recompression, WallpaperIndex};
 use anyhow::{Context, Result};
+use chrono::NaiveDate;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::fs;
 
-#[cfg(not(test))]
 use std::collections::HashMap;
 #[cfg(not(test))]
 use std::sync::{Mutex, OnceLock};
 
-/// 全局索引管理器映射表（支持多目录）
+/// 全局索引管理器映射表的容量上限
+///
+/// 正常运行时只有当前壁纸目录常驻，导入/导出和切换保存目录会短暂引入其他目录。
+/// 超过上限时按 LRU 淘汰没有外部引用的条目，避免映射表无限增长。
+#[cfg(not(test))]
+const MAX_INDEX_MANAGERS: usize = 8;
+
+/// 索引管理器注册表条目
+struct RegistryEntry {
+    manager: Arc<IndexManager>,
+    /// 最近一次访问的逻辑时钟，用于 LRU 淘汰
+    last_used: u64,
+}
+
+/// 按目录管理 IndexManager 的注册表（LRU + 引用计数淘汰）
+///
+/// 淘汰只针对 `Arc::strong_count == 1` 的条目，即除注册表外没有任何调用方
+/// 持有的管理器；正在使用的管理器不会被淘汰，避免同一目录出现两份缓存。
+struct IndexManagerRegistry {
+    capacity: usize,
+    entries: HashMap<String, RegistryEntry>,
+    clock: u64,
+}
+
+impl IndexManagerRegistry {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: HashMap::new(),
+            clock: 0,
+        }
+    }
+
+    /// 获取目录对应的管理器，不存在时创建，并在超出容量时淘汰最久未使用的条目
+    fn get_or_insert(&mut self, key: String, directory: &Path) -> Arc<IndexManager> {
+        self.clock += 1;
+        let now = self.clock;
+
+        if let Some(entry) = self.entries.get_mut(&key) {
+            entry.last_used = now;
+            return entry.manager.clone();
+        }
+
+        let manager = Arc::new(IndexManager::new(directory.to_path_buf()));
+        self.entries.insert(
+            key.clone(),
+            RegistryEntry {
+                manager: manager.clone(),
+                last_used: now,
+            },
+        );
+        self.evict_over_capacity(&key);
+        manager
+    }
+
+    /// 移除指定 key 的条目，返回是否存在
+    fn remove(&mut self, key: &str) -> bool {
+        self.entries.remove(key).is_some()
+    }
+
+    /// 超出容量时按 LRU 淘汰没有外部引用的条目（`keep` 为刚插入的 key，不参与淘汰）
+    fn evict_over_capacity(&mut self, keep: &str) {
+        while self.entries.len() > self.capacity {
+            let victim = self
+                .entries
+                .iter()
+                .filter(|(key, entry)| {
+                    key.as_str() != keep && Arc::strong_count(&entry.manager) == 1
+                })
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone());
+
+            match victim {
+                Some(key) => {
+                    self.entries.remove(&key);
+                    log::debug!("淘汰最久未使用的 IndexManager: {}", key);
+                }
+                None => {
+                    // 所有条目都在使用中，暂时允许超出容量，下次插入时再尝试
+                    log::debug!(
+                        "IndexManager 数量 ({}) 超过上限 ({})，但所有条目都在使用中",
+                        self.entries.len(),
+                        self.capacity
+                    );
+                    break;
+                }
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+/// 计算目录在注册表中的 key（规范化路径字符串）
+fn registry_key(directory: &Path) -> String {
+    directory
+        .canonicalize()
+        .unwrap_or_else(|_| directory.to_path_buf())
+        .to_string_lossy()
+        .to_string()
+}
+
+/// 全局索引管理器注册表（支持多目录）
 /// Key: 目录路径的规范化字符串
 /// Value: 对应目录的 IndexManager
 #[cfg(not(test))]
-static INDEX_MANAGERS: OnceLock<Mutex<HashMap<String, Arc<IndexManager>>>> = OnceLock::new();
+static INDEX_MANAGERS: OnceLock<Mutex<IndexManagerRegistry>> = OnceLock::new();
+
+#[cfg(not(test))]
+fn index_managers() -> &'static Mutex<IndexManagerRegistry> {
+    INDEX_MANAGERS.get_or_init(|| Mutex::new(IndexManagerRegistry::new(MAX_INDEX_MANAGERS)))
+}
 
 /// 获取索引管理器
 ///
-/// 在生产环境中使用全局映射表管理多个目录的 IndexManager；
+/// 在生产环境中使用全局注册表管理多个目录的 IndexManager；
 /// 在测试环境中为每个目录创建新实例
 fn get_index_manager(directory: &Path) -> Arc<IndexManager> {
     #[cfg(test)]
@@ -29,26 +138,15 @@ fn get_index_manager(directory: &Path) -> Arc<IndexManager> {
 
     #[cfg(not(test))]
     {
-        // 生产环境：使用全局映射表，支持多目录
-        let managers = INDEX_MANAGERS.get_or_init(|| Mutex::new(HashMap::new()));
-        let mut map = managers.lock().unwrap();
-
-        // 使用规范化的路径作为 key
-        let key = directory
-            .canonicalize()
-            .unwrap_or_else(|_| directory.to_path_buf())
-            .to_string_lossy()
-            .to_string();
-
-        map.entry(key)
-            .or_insert_with(|| Arc::new(IndexManager::new(directory.to_path_buf())))
-            .clone()
+        // 生产环境：使用全局注册表，支持多目录
+        let mut registry = index_managers().lock().unwrap();
+        registry.get_or_insert(registry_key(directory), directory)
     }
 }
 
 /// 移除指定目录的 IndexManager 缓存
 ///
-/// 用于导入/导出完成后清理临时目录的缓存条目，防止全局映射表无限增长。
+/// 用于导入/导出完成后清理临时目录的缓存条目，以及保存目录切换后释放旧目录。
 /// 测试环境下为空操作（测试不使用全局缓存）。
 pub fn remove_index_manager(directory: &Path) {
     #[cfg(test)]
@@ -58,14 +156,14 @@ pub fn remove_index_manager(directory: &Path) {
 
     #[cfg(not(test))]
     {
-        let managers = INDEX_MANAGERS.get_or_init(|| Mutex::new(HashMap::new()));
-        let mut map = managers.lock().unwrap();
-        let key = directory
-            .canonicalize()
-            .unwrap_or_else(|_| directory.to_path_buf())
-            .to_string_lossy()
-            .to_string();
-        map.remove(&key);
+        let mut registry = index_managers().lock().unwrap();
+        if registry.remove(&registry_key(directory)) {
+            log::debug!(
+                "已移除 IndexManager: {}（剩余 {} 个）",
+                directory.display(),
+                registry.len()
+            );
+        }
     }
 }
 
@@ -100,6 +198,10 @@ pub async fn ensure_wallpaper_directory(path: &Path) -> Result<()> {
 
 /// 获取壁纸的保存路径
 /// 使用 end_date 作为文件名，因为 Bing 的壁纸 startdate 是昨天，enddate 才是今天
+///
+/// 文件名固定为 `YYYYMMDD.jpg`（竖屏为 `YYYYMMDDr.jpg`），不提供可配置的命名模板：
+/// 索引只记录 end_date，缩略图协议、按需下载、回收站、导入导出和异常恢复都由 end_date
+/// 推算文件路径，因此也不存在需要迁移的旧命名文件。
 pub fn get_wallpaper_path(directory: &Path, end_date: &str) -> PathBuf {
     directory.join(format!("{}.jpg", end_date))
 }
@@ -116,6 +218,17 @@ pub async fn get_local_wallpapers(directory: &Path, mkt: &str) -> Result<Vec<Loc
     manager.get_all_wallpapers(mkt).await
 }
 
+/// 分页获取本地壁纸列表（按日期降序），返回当前页和该 mkt 的壁纸总数
+pub async fn get_local_wallpapers_page(
+    directory: &Path,
+    mkt: &str,
+    offset: usize,
+    limit: Option<usize>,
+) -> Result<(Vec<LocalWallpaper>, usize)> {
+    let manager = get_index_manager(directory);
+    manager.get_wallpapers_page(mkt, offset, limit).await
+}
+
 /// 获取 index.json 中所有可用的 mkt key
 ///
 /// 复用全局 IndexManager 缓存，避免重复磁盘 I/O。
@@ -133,6 +246,94 @@ pub async fn get_index_snapshot(directory: &Path) -> Result<WallpaperIndex> {
     manager.load_index().await
 }
 
+/// 按设置的间隔把 index.json 备份到壁纸目录的 `backups/` 子目录
+///
+/// 返回新写入的备份路径，未到备份时间时返回 `None`。
+pub async fn backup_index_if_due(
+    directory: &Path,
+    today: NaiveDate,
+    interval_days: u32,
+) -> Result<Option<PathBuf>> {
+    let manager = get_index_manager(directory);
+    manager.backup_if_due(today, interval_days).await
+}
+
+/// 列出壁纸目录中已有的索引备份日期（按日期降序）
+pub async fn list_index_backups(directory: &Path) -> Result<Vec<String>> {
+    let manager = get_index_manager(directory);
+    manager.list_backups().await
+}
+
+/// 用指定日期的备份替换 index.json，备份不存在时返回 `None`
+pub async fn restore_index_backup(directory: &Path, date: &str) -> Result<Option<usize>> {
+    let manager = get_index_manager(directory);
+    manager.restore_backup(date).await
+}
+
+/// 记录壁纸实际下载的横屏分辨率
+///
+/// 复用全局 IndexManager，写入所有 mkt 下的同日期条目。
+pub async fn record_wallpaper_resolution(
+    directory: &Path,
+    end_date: &str,
+    resolution: &str,
+) -> Result<()> {
+    let manager = get_index_manager(directory);
+    manager.set_resolution(end_date, resolution).await?;
+    Ok(())
+}
+
+/// 记录横屏图片的重新压缩结果（None 表示磁盘上是原图）
+pub async fn record_recompression(
+    directory: &Path,
+    end_date: &str,
+    recompression: Option<Recompression>,
+) -> Result<()> {
+    let manager = get_index_manager(directory);
+    manager.set_recompression(end_date, recompression).await?;
+    Ok(())
+}
+
+/// 从索引的所有 mkt 中删除指定键的条目（不删除图片文件），返回是否有条目被删除
+pub async fn remove_index_entries(directory: &Path, keys: &[String]) -> Result<bool> {
+    get_index_manager(directory).remove_entries(keys).await
+}
+
+/// 为壁纸添加标签，返回添加后的标签
+pub async fn add_tag(directory: &Path, end_date: &str, tag: &str) -> Result<Vec<String>> {
+    get_index_manager(directory).add_tag(end_date, tag).await
+}
+
+/// 移除壁纸的标签，返回移除后的标签
+pub async fn remove_tag(directory: &Path, end_date: &str, tag: &str) -> Result<Vec<String>> {
+    get_index_manager(directory).remove_tag(end_date, tag).await
+}
+
+/// 记录壁纸磁盘上的横屏图片是否为无水印版本
+pub async fn record_watermark_free(
+    directory: &Path,
+    end_date: &str,
+    watermark_free: bool,
+) -> Result<()> {
+    let manager = get_index_manager(directory);
+    manager.set_watermark_free(end_date, watermark_free).await?;
+    Ok(())
+}
+
+/// 记录指定 mkt 某日竖屏版本的探测结果
+pub async fn record_portrait_availability(
+    directory: &Path,
+    mkt: &str,
+    end_date: &str,
+    available: bool,
+) -> Result<()> {
+    let manager = get_index_manager(directory);
+    manager
+        .set_portrait_available(mkt, end_date, available)
+        .await?;
+    Ok(())
+}
+
 /// 验证壁纸数据的市场代码是否匹配
 ///
 /// 检查 urlbase 字段中的市场代码是否与期望的 mkt 匹配。
@@ -160,7 +361,7 @@ static MKT_UPPERCASE_MARKERS: std::sync::LazyLock<Vec<String>> = std::sync::Lazy
         .collect()
 });
 
-fn validate_wallpaper_mkt(wallpaper: &LocalWallpaper, expected_mkt: &str) -> bool {
+pub(crate) fn validate_wallpaper_mkt(wallpaper: &LocalWallpaper, expected_mkt: &str) -> bool {
     // 如果 urlbase 为空，不进行验证（向后兼容）
     if wallpaper.urlbase.is_empty() {
         return true;
@@ -257,6 +458,10 @@ mod tests {
             copyright_link: "https://example.com".to_string(),
             end_date: "20250102".to_string(),
             urlbase: "/th?id=OHR.Test_ZH-CN1234567890".to_string(),
+            resolution: None,
+            portrait_available: None,
+            watermark_free: None,
+            recompression: None,
         };
 
         assert!(validate_wallpaper_mkt(&wallpaper_zh, "zh-CN"));
@@ -271,6 +476,10 @@ mod tests {
             copyright_link: "https://example.com".to_string(),
             end_date: "20250102".to_string(),
             urlbase: "/th?id=OHR.Test_EN-US1234567890".to_string(),
+            resolution: None,
+            portrait_available: None,
+            watermark_free: None,
+            recompression: None,
         };
 
         assert!(validate_wallpaper_mkt(&wallpaper_en, "en-US"));
@@ -286,6 +495,10 @@ mod tests {
             copyright_link: "https://example.com".to_string(),
             end_date: "20250102".to_string(),
             urlbase: "/th?id=OHR.Test_JA-JP1234567890".to_string(),
+            resolution: None,
+            portrait_available: None,
+            watermark_free: None,
+            recompression: None,
         };
 
         assert!(validate_wallpaper_mkt(&wallpaper_jp, "ja-JP"));
@@ -302,6 +515,10 @@ mod tests {
             copyright_link: "https://example.com".to_string(),
             end_date: "20250102".to_string(),
             urlbase: "".to_string(),
+            resolution: None,
+            portrait_available: None,
+            watermark_free: None,
+            recompression: None,
         };
 
         assert!(validate_wallpaper_mkt(&wallpaper_empty, "zh-CN"));
@@ -318,6 +535,10 @@ mod tests {
             copyright_link: "https://example.com".to_string(),
             end_date: "20250102".to_string(),
             urlbase: "/th?id=OHR.Test1234567890".to_string(),
+            resolution: None,
+            portrait_available: None,
+            watermark_free: None,
+            recompression: None,
         };
 
         assert!(validate_wallpaper_mkt(&wallpaper_no_marker, "zh-CN"));
@@ -343,6 +564,88 @@ mod tests {
         );
     }
 
+    fn registry_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("bw_registry_{name}"))
+    }
+
+    #[test]
+    fn test_registry_reuses_manager_for_same_directory() {
+        let mut registry = IndexManagerRegistry::new(4);
+        let dir = registry_dir("same");
+
+        let first = registry.get_or_insert(registry_key(&dir), &dir);
+        let second = registry.get_or_insert(registry_key(&dir), &dir);
+
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(registry.len(), 1);
+    }
+
+    #[test]
+    fn test_registry_separates_multiple_directories() {
+        let mut registry = IndexManagerRegistry::new(4);
+        let dir_a = registry_dir("multi_a");
+        let dir_b = registry_dir("multi_b");
+
+        let a = registry.get_or_insert(registry_key(&dir_a), &dir_a);
+        let b = registry.get_or_insert(registry_key(&dir_b), &dir_b);
+
+        assert!(!Arc::ptr_eq(&a, &b));
+        assert_eq!(registry.len(), 2);
+    }
+
+    #[test]
+    fn test_registry_evicts_least_recently_used_when_over_capacity() {
+        let mut registry = IndexManagerRegistry::new(2);
+        let dir_a = registry_dir("lru_a");
+        let dir_b = registry_dir("lru_b");
+        let dir_c = registry_dir("lru_c");
+
+        drop(registry.get_or_insert(registry_key(&dir_a), &dir_a));
+        drop(registry.get_or_insert(registry_key(&dir_b), &dir_b));
+        // 再次访问 a，使 b 成为最久未使用的条目
+        drop(registry.get_or_insert(registry_key(&dir_a), &dir_a));
+        drop(registry.get_or_insert(registry_key(&dir_c), &dir_c));
+
+        assert_eq!(registry.len(), 2);
+        assert!(registry.entries.contains_key(&registry_key(&dir_a)));
+        assert!(!registry.entries.contains_key(&registry_key(&dir_b)));
+        assert!(registry.entries.contains_key(&registry_key(&dir_c)));
+    }
+
+    #[test]
+    fn test_registry_does_not_evict_managers_in_use() {
+        let mut registry = IndexManagerRegistry::new(1);
+        let dir_a = registry_dir("inuse_a");
+        let dir_b = registry_dir("inuse_b");
+
+        let held = registry.get_or_insert(registry_key(&dir_a), &dir_a);
+        drop(registry.get_or_insert(registry_key(&dir_b), &dir_b));
+
+        // a 仍被外部持有，不能淘汰；允许暂时超出容量
+        assert_eq!(registry.len(), 2);
+        let again = registry.get_or_insert(registry_key(&dir_a), &dir_a);
+        assert!(Arc::ptr_eq(&held, &again));
+
+        // 释放引用后，下次插入时可被淘汰
+        drop(held);
+        drop(again);
+        let dir_c = registry_dir("inuse_c");
+        drop(registry.get_or_insert(registry_key(&dir_c), &dir_c));
+        assert_eq!(registry.len(), 1);
+        assert!(registry.entries.contains_key(&registry_key(&dir_c)));
+    }
+
+    #[test]
+    fn test_registry_remove() {
+        let mut registry = IndexManagerRegistry::new(4);
+        let dir = registry_dir("remove");
+
+        drop(registry.get_or_insert(registry_key(&dir), &dir));
+        assert!(registry.remove(&registry_key(&dir)));
+        assert!(!registry.remove(&registry_key(&dir)));
+        assert_eq!(registry.len(), 0);
+    }
+
     #[test]
     fn test_get_wallpaper_path() {
         let dir = PathBuf::from("/tmp/wallpapers");
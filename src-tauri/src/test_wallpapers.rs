@@ -0,0 +1,163 @@
+//! 开发用测试壁纸
+//!
+//! 仅 debug 构建可用：`inject_test_wallpapers` 生成若干合成的索引条目和渐变风景图片，前端开发无需联网
+//! 即可看到完整的图库，端到端测试也能得到确定的数据。测试壁纸使用 2008 年及以前的日期（早于 Bing
+//! 每日壁纸），不会覆盖真实壁纸；同样的参数总是生成相同的标题、版权和图片。
+
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use log::info;
+use std::path::Path;
+
+use crate::models::LocalWallpaper;
+use crate::{AppState, events, get_effective_mkt, placeholder, storage};
+
+/// 单次最多生成的数量
+const MAX_TEST_WALLPAPERS: u32 = 365;
+/// 最新一张测试壁纸的日期，之后的按天倒推
+const LAST_TEST_DATE: (i32, u32, u32) = (2008, 12, 31);
+
+const LOCATIONS: [&str; 6] = [
+    "Test Lake, Northland",
+    "Sample Peak, Eastshire",
+    "Mock Coast, Westmarch",
+    "Fixture Valley, Southvale",
+    "Stub Canyon, Midlands",
+    "Demo Forest, Highreach",
+];
+
+/// 第 `index` 张测试壁纸的日期（YYYYMMDD）
+fn test_end_date(index: u32) -> String {
+    let (year, month, day) = LAST_TEST_DATE;
+    let last = NaiveDate::from_ymd_opt(year, month, day).expect("valid date");
+    (last - chrono::Days::new(u64::from(index)))
+        .format("%Y%m%d")
+        .to_string()
+}
+
+fn test_metadata(index: u32) -> LocalWallpaper {
+    let location = LOCATIONS[index as usize % LOCATIONS.len()];
+    LocalWallpaper {
+        title: format!("Test Wallpaper {}", index + 1),
+        copyright: format!("{location} (© Photographer {}/Test Images)", index % 4 + 1),
+        copyright_link: String::new(),
+        end_date: test_end_date(index),
+        urlbase: String::new(),
+        resolution: Some(format!("{}x{}", placeholder::WIDTH, placeholder::HEIGHT)),
+        portrait_available: None,
+        watermark_free: None,
+        recompression: None,
+    }
+}
+
+/// HSV（色相 0~360）转 RGB
+fn hsv(hue: f32, saturation: f32, value: f32) -> [u8; 3] {
+    let c = value * saturation;
+    let h = (hue % 360.0) / 60.0;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = value - c;
+    let to_u8 = |v: f32| ((v + m) * 255.0).round() as u8;
+    [to_u8(r), to_u8(g), to_u8(b)]
+}
+
+/// 第 `index` 张测试壁纸的配色：按黄金角旋转色相，相邻图片颜色差异明显
+fn test_palette(index: u32) -> ([u8; 3], [u8; 3], [u8; 3]) {
+    let hue = (index as f32 * 137.5) % 360.0;
+    (
+        hsv(hue, 0.6, 0.45),
+        hsv(hue + 40.0, 0.45, 0.95),
+        hsv(hue + 180.0, 0.5, 0.25),
+    )
+}
+
+/// 写入尚不存在的测试壁纸图片
+fn write_images(wallpaper_dir: &Path, count: u32) -> Result<()> {
+    for index in 0..count {
+        let path = storage::get_wallpaper_path(wallpaper_dir, &test_end_date(index));
+        if path.exists() {
+            continue;
+        }
+        let (sky_top, horizon, ground) = test_palette(index);
+        placeholder::write_jpeg(&path, &placeholder::render_scene(sky_top, horizon, ground))?;
+    }
+    Ok(())
+}
+
+/// 生成 `count` 张测试壁纸写入当前 mkt 的索引（1 ~ 365），返回新增的条目数
+///
+/// 非 debug 构建始终返回 "DEBUG_ONLY"。
+#[tauri::command]
+pub(crate) async fn inject_test_wallpapers(
+    count: u32,
+    state: tauri::State<'_, AppState>,
+    app: tauri::AppHandle,
+) -> Result<usize, String> {
+    if !cfg!(debug_assertions) {
+        return Err("DEBUG_ONLY".to_string());
+    }
+    if count == 0 || count > MAX_TEST_WALLPAPERS {
+        return Err("INVALID_COUNT".to_string());
+    }
+    let wallpaper_dir = state.wallpaper_directory.lock().await.clone();
+    let mkt = get_effective_mkt(&state).await;
+
+    tokio::fs::create_dir_all(&wallpaper_dir)
+        .await
+        .map_err(|e| format!("Failed to create wallpaper directory: {}", e))?;
+    let dir = wallpaper_dir.clone();
+    tokio::task::spawn_blocking(move || write_images(&dir, count))
+        .await
+        .context("Test wallpaper task failed")
+        .and_then(|result| result)
+        .map_err(|e| format!("Failed to write test wallpapers: {:#}", e))?;
+
+    let entries = (0..count).map(test_metadata).collect();
+    let result = storage::save_wallpapers_metadata(entries, &wallpaper_dir, &mkt)
+        .await
+        .map_err(|e| format!("Failed to save test wallpapers: {}", e))?;
+    info!(
+        target: "test_wallpapers",
+        "已生成 {} 张测试壁纸（新增 {} 条索引），mkt={}",
+        count,
+        result.new_count,
+        mkt
+    );
+    let _ = events::WALLPAPER_UPDATED.emit(&app, &());
+    Ok(result.new_count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_test_wallpapers_are_deterministic_and_before_bing() {
+        assert_eq!(test_end_date(0), "20081231");
+        assert_eq!(test_end_date(365), "20080101");
+
+        let first = test_metadata(0);
+        assert_eq!(first.title, "Test Wallpaper 1");
+        assert_eq!(
+            first.copyright_info().photographer.as_deref(),
+            Some("Photographer 1")
+        );
+        assert_eq!(test_palette(7), test_palette(7));
+        assert_ne!(test_palette(0), test_palette(1));
+    }
+
+    #[test]
+    fn test_hsv_primary_colors() {
+        assert_eq!(hsv(0.0, 1.0, 1.0), [255, 0, 0]);
+        assert_eq!(hsv(120.0, 1.0, 1.0), [0, 255, 0]);
+        assert_eq!(hsv(240.0, 1.0, 1.0), [0, 0, 255]);
+        assert_eq!(hsv(30.0, 0.0, 0.5), [128, 128, 128]);
+    }
+}
@@ -0,0 +1,290 @@
+//! 画廊缩略图协议（`thumb://`）
+//!
+//! 前端通过 `convertFileSrc(endDate, "thumb")` 加上 `?w=<宽度>` 请求缩小后的壁纸，
+//! 避免画廊每张卡片都解码整张 UHD 原图。缩略图按 (end_date, 宽度档位) 缓存在内存 LRU 中，
+//! 来回滚动画廊时直接命中缓存，不再重复读盘和解码。
+//!
+//! 缓存条目记录原图的路径、大小与修改时间，原图重新下载（如分辨率升级）或壁纸目录变更后自动失效。
+
+use image::imageops::FilterType;
+use indexmap::IndexMap;
+use log::warn;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::SystemTime;
+use tauri::http::{Request, Response, StatusCode, Uri, header};
+use tauri::{Manager, Runtime, UriSchemeContext, UriSchemeResponder};
+
+use crate::{AppState, storage};
+
+/// 协议名
+pub(crate) const PROTOCOL: &str = "thumb";
+
+/// 缩略图宽度档位：请求宽度向上取整到档位，提高缓存命中率
+const WIDTH_BUCKETS: [u32; 5] = [320, 480, 640, 960, 1280];
+/// 未指定宽度时使用的档位
+const DEFAULT_WIDTH: u32 = 640;
+/// 缓存占用上限
+const CACHE_CAPACITY_BYTES: usize = 64 * 1024 * 1024;
+const THUMBNAIL_JPEG_QUALITY: u8 = 85;
+
+type CacheKey = (String, u32);
+
+/// 生成缩略图时原图的状态，用于判断缓存是否失效
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SourceStamp {
+    path: PathBuf,
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+struct CachedThumbnail {
+    stamp: SourceStamp,
+    bytes: Arc<Vec<u8>>,
+}
+
+/// 按字节数限制容量的 LRU 缓存（IndexMap 的顺序即使用顺序，末尾为最近使用）
+struct ThumbnailCache {
+    entries: IndexMap<CacheKey, CachedThumbnail>,
+    total_bytes: usize,
+    capacity_bytes: usize,
+}
+
+impl ThumbnailCache {
+    fn new(capacity_bytes: usize) -> Self {
+        Self {
+            entries: IndexMap::new(),
+            total_bytes: 0,
+            capacity_bytes,
+        }
+    }
+
+    fn get(&mut self, key: &CacheKey, stamp: &SourceStamp) -> Option<Arc<Vec<u8>>> {
+        let index = self.entries.get_index_of(key)?;
+        if self.entries[index].stamp != *stamp {
+            self.remove_index(index);
+            return None;
+        }
+        let last = self.entries.len() - 1;
+        self.entries.move_index(index, last);
+        Some(self.entries[last].bytes.clone())
+    }
+
+    fn insert(&mut self, key: CacheKey, stamp: SourceStamp, bytes: Arc<Vec<u8>>) {
+        if let Some(index) = self.entries.get_index_of(&key) {
+            self.remove_index(index);
+        }
+        self.total_bytes += bytes.len();
+        self.entries.insert(key, CachedThumbnail { stamp, bytes });
+        // 至少保留刚插入的条目
+        while self.total_bytes > self.capacity_bytes && self.entries.len() > 1 {
+            self.remove_index(0);
+        }
+    }
+
+    fn remove_index(&mut self, index: usize) {
+        if let Some((_, entry)) = self.entries.shift_remove_index(index) {
+            self.total_bytes -= entry.bytes.len();
+        }
+    }
+}
+
+static CACHE: LazyLock<Mutex<ThumbnailCache>> =
+    LazyLock::new(|| Mutex::new(ThumbnailCache::new(CACHE_CAPACITY_BYTES)));
+
+/// 把请求宽度向上取整到档位
+fn width_bucket(requested: u32) -> u32 {
+    WIDTH_BUCKETS
+        .into_iter()
+        .find(|&bucket| bucket >= requested)
+        .unwrap_or(WIDTH_BUCKETS[WIDTH_BUCKETS.len() - 1])
+}
+
+/// 解析 `thumb://localhost/<end_date>?w=<宽度>`，返回 (end_date, 宽度档位)
+fn parse_request_uri(uri: &Uri) -> Option<CacheKey> {
+    let end_date = uri.path().trim_matches('/');
+    if end_date.len() != 8 || !end_date.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let width = uri
+        .query()
+        .into_iter()
+        .flat_map(|query| query.split('&'))
+        .find_map(|pair| pair.strip_prefix("w="))
+        .and_then(|value| value.parse::<u32>().ok())
+        .unwrap_or(DEFAULT_WIDTH);
+    Some((end_date.to_string(), width_bucket(width)))
+}
+
+/// 解码原图并缩放到指定宽度（原图更窄时保持原尺寸），编码为 JPEG
+fn render_thumbnail(path: &Path, width: u32) -> anyhow::Result<Vec<u8>> {
+    let image = image::open(path)?;
+    let image = if image.width() > width {
+        image.resize(width, u32::MAX, FilterType::Triangle)
+    } else {
+        image
+    };
+    let mut bytes = Vec::new();
+    image::codecs::jpeg::JpegEncoder::new_with_quality(
+        &mut Cursor::new(&mut bytes),
+        THUMBNAIL_JPEG_QUALITY,
+    )
+    .encode_image(&image.to_rgb8())?;
+    Ok(bytes)
+}
+
+async fn load_thumbnail<R: Runtime>(
+    app: &tauri::AppHandle<R>,
+    key: CacheKey,
+) -> Result<Arc<Vec<u8>>, StatusCode> {
+    let wallpaper_dir = app
+        .state::<AppState>()
+        .wallpaper_directory
+        .lock()
+        .await
+        .clone();
+    let path = storage::get_wallpaper_path(&wallpaper_dir, &key.0);
+    let metadata = tokio::fs::metadata(&path)
+        .await
+        .map_err(|_| StatusCode::NOT_FOUND)?;
+    let stamp = SourceStamp {
+        path,
+        len: metadata.len(),
+        modified: metadata.modified().ok(),
+    };
+
+    if let Some(bytes) = CACHE.lock().ok().and_then(|mut c| c.get(&key, &stamp)) {
+        return Ok(bytes);
+    }
+
+    let source = stamp.path.clone();
+    let width = key.1;
+    let bytes = tokio::task::spawn_blocking(move || render_thumbnail(&source, width))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .map_err(|e| {
+            // 下载中的文件可能暂时无法解码，前端收到 image-downloaded 后会重新请求
+            warn!(target: "thumbnail", "生成缩略图失败 {}: {}", key.0, e);
+            StatusCode::UNPROCESSABLE_ENTITY
+        })?;
+    let bytes = Arc::new(bytes);
+    if let Ok(mut cache) = CACHE.lock() {
+        cache.insert(key, stamp, bytes.clone());
+    }
+    Ok(bytes)
+}
+
+fn empty_response(status: StatusCode) -> Response<Vec<u8>> {
+    let mut response = Response::new(Vec::new());
+    *response.status_mut() = status;
+    response
+}
+
+/// `thumb://` 协议处理函数
+pub(crate) fn handle_request<R: Runtime>(
+    ctx: UriSchemeContext<'_, R>,
+    request: Request<Vec<u8>>,
+    responder: UriSchemeResponder,
+) {
+    let Some(key) = parse_request_uri(request.uri()) else {
+        responder.respond(empty_response(StatusCode::BAD_REQUEST));
+        return;
+    };
+    let app = ctx.app_handle().clone();
+    tauri::async_runtime::spawn(async move {
+        let response = match load_thumbnail(&app, key).await {
+            Ok(bytes) => {
+                let mut response = Response::new(bytes.as_ref().clone());
+                response.headers_mut().insert(
+                    header::CONTENT_TYPE,
+                    header::HeaderValue::from_static("image/jpeg"),
+                );
+                response
+            }
+            Err(status) => empty_response(status),
+        };
+        responder.respond(response);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stamp(name: &str, len: u64) -> SourceStamp {
+        SourceStamp {
+            path: PathBuf::from(name),
+            len,
+            modified: None,
+        }
+    }
+
+    fn key(end_date: &str, width: u32) -> CacheKey {
+        (end_date.to_string(), width)
+    }
+
+    #[test]
+    fn test_parse_request_uri_buckets_width() {
+        let parse = |uri: &str| parse_request_uri(&uri.parse::<Uri>().unwrap());
+        assert_eq!(
+            parse("thumb://localhost/20240101?w=500"),
+            Some(key("20240101", 640))
+        );
+        assert_eq!(
+            parse("http://thumb.localhost/20240101?retry=1&w=100"),
+            Some(key("20240101", 320))
+        );
+        assert_eq!(
+            parse("thumb://localhost/20240101"),
+            Some(key("20240101", DEFAULT_WIDTH))
+        );
+        assert_eq!(
+            parse("thumb://localhost/20240101?w=5000"),
+            Some(key("20240101", 1280))
+        );
+        assert_eq!(parse("thumb://localhost/../secret"), None);
+        assert_eq!(parse("thumb://localhost/2024010"), None);
+    }
+
+    #[test]
+    fn test_cache_evicts_least_recently_used() {
+        let mut cache = ThumbnailCache::new(10);
+        cache.insert(key("a", 320), stamp("a", 1), Arc::new(vec![0; 4]));
+        cache.insert(key("b", 320), stamp("b", 1), Arc::new(vec![0; 4]));
+        // 访问 a 后，b 成为最久未使用
+        assert!(cache.get(&key("a", 320), &stamp("a", 1)).is_some());
+        cache.insert(key("c", 320), stamp("c", 1), Arc::new(vec![0; 4]));
+
+        assert!(cache.get(&key("b", 320), &stamp("b", 1)).is_none());
+        assert!(cache.get(&key("a", 320), &stamp("a", 1)).is_some());
+        assert!(cache.get(&key("c", 320), &stamp("c", 1)).is_some());
+        assert_eq!(cache.total_bytes, 8);
+    }
+
+    #[test]
+    fn test_cache_invalidates_when_source_changes() {
+        let mut cache = ThumbnailCache::new(100);
+        cache.insert(key("a", 320), stamp("a", 1), Arc::new(vec![0; 4]));
+        assert!(cache.get(&key("a", 320), &stamp("a", 2)).is_none());
+        assert!(cache.entries.is_empty());
+        assert_eq!(cache.total_bytes, 0);
+    }
+
+    #[test]
+    fn test_render_thumbnail_limits_width() {
+        let dir = std::env::temp_dir().join(format!("bw_thumbnail_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("20240101.jpg");
+        image::RgbImage::from_pixel(800, 450, image::Rgb([10, 20, 30]))
+            .save(&path)
+            .unwrap();
+
+        let small = image::load_from_memory(&render_thumbnail(&path, 320).unwrap()).unwrap();
+        assert_eq!((small.width(), small.height()), (320, 180));
+        let original = image::load_from_memory(&render_thumbnail(&path, 1280).unwrap()).unwrap();
+        assert_eq!(original.width(), 800);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
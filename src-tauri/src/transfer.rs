@@ -1,9 +1,11 @@
 use log::{info, warn};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
-use tauri::Emitter;
 
-use crate::{AppState, index_manager, models, storage};
+use crate::{
+    AppState, command_guard, directory_permission, events, index_manager, models, safe_path,
+    storage,
+};
 
 /// 导入/导出结果统计
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,6 +22,26 @@ pub(crate) struct TransferResult {
     mkt_count: usize,
 }
 
+/// 导入预览（dry-run，不写入任何文件）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ImportPreview {
+    /// 将新增的元数据条目数
+    metadata_new: usize,
+    /// 将覆盖更新的条目数（同一张壁纸，元数据有变化）
+    metadata_updated: usize,
+    /// 冲突条目数：同一 mkt 同一日期但指向不同图片（urlbase 或标题不同），导入后以源数据为准
+    metadata_conflicting: usize,
+    /// 与当前索引完全相同、导入后不变的条目数
+    metadata_unchanged: usize,
+    /// 将因 mkt 验证失败而跳过的条目数
+    metadata_skipped: usize,
+    /// 将复制的图片数
+    images_to_copy: usize,
+    /// 目标目录已存在而将跳过的图片数
+    images_skipped: usize,
+    mkt_count: usize,
+}
+
 /// 图片复制结果
 struct ImageCopyResult {
     copied: usize,
@@ -27,18 +49,161 @@ struct ImageCopyResult {
     failed: usize,
 }
 
-/// 复制壁纸图片文件（仅复制目标目录中不存在的文件）
+/// 是否为壁纸图片文件名（`YYYYMMDD.jpg` 或 `YYYYMMDDr.jpg`）
+pub(crate) fn is_wallpaper_image_name(name: &str) -> bool {
+    let stem = name
+        .strip_suffix("r.jpg")
+        .or_else(|| name.strip_suffix(".jpg"));
+    matches!(stem, Some(s) if s.len() == 8 && s.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// 待复制的图片（源文件路径, 目标文件名）
+type ImageFile = (PathBuf, String);
+
+/// 列出目录中本应用格式的壁纸图片
+async fn list_wallpaper_images(dir: &Path) -> Result<Vec<ImageFile>, String> {
+    let mut images = Vec::new();
+    let mut read_dir = tokio::fs::read_dir(dir)
+        .await
+        .map_err(|e| format!("Failed to read source directory: {}", e))?;
+
+    while let Some(entry) = read_dir
+        .next_entry()
+        .await
+        .map_err(|e| format!("Failed to read directory entry: {}", e))?
+    {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if is_wallpaper_image_name(&name) {
+            images.push((entry.path(), name));
+        }
+    }
+
+    Ok(images)
+}
+
+/// 从其他 Bing 壁纸工具的文件名中识别出的信息
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ForeignImageName {
+    /// 文件名中的日期（YYYYMMDD），没有时使用文件修改日期
+    end_date: Option<String>,
+    /// 从 OHR 编号中解析的 mkt（如 "en-US"）
+    mkt: Option<String>,
+    urlbase: String,
+    title: String,
+    portrait: bool,
+}
+
+/// 解析文件名开头的日期（`YYYY-MM-DD`、`YYYY_MM_DD`、`YYYY.MM.DD` 或 `YYYYMMDD`），返回 (YYYYMMDD, 剩余部分)
+fn split_date_prefix(stem: &str) -> Option<(String, &str)> {
+    let (digits, rest) = match stem.as_bytes() {
+        [_, _, _, _, sep, _, _, sep2, _, _, ..] if sep == sep2 && b"-_.".contains(sep) => {
+            (stem.get(..10)?.replace(*sep as char, ""), stem.get(10..)?)
+        }
+        _ => (stem.get(..8)?.to_string(), stem.get(8..)?),
+    };
+    if digits.len() != 8
+        || !digits.bytes().all(|b| b.is_ascii_digit())
+        || rest.starts_with(|c: char| c.is_ascii_digit())
+    {
+        return None;
+    }
+    chrono::NaiveDate::parse_from_str(&digits, "%Y%m%d").ok()?;
+    Some((digits, rest.trim_start_matches([' ', '_', '-', '.'])))
+}
+
+/// 解析 `OHR.Name_EN-US1234567890[_UHD|_1920x1080]` 格式的 Bing 图片编号，返回 (urlbase, mkt, 名称, 是否竖屏)
+fn parse_ohr_id(text: &str) -> Option<(String, Option<String>, &str, bool)> {
+    let id = &text[text.find("OHR.")? + 4..];
+    let mut parts = id.split('_');
+    let name = parts.next().filter(|name| !name.is_empty())?;
+    let number = parts.next()?.to_ascii_uppercase();
+    let portrait = parts
+        .next()
+        .and_then(|res| {
+            let (width, height) = res.split_once(['x', 'X'])?;
+            Some(width.parse::<u32>().ok()? < height.parse::<u32>().ok()?)
+        })
+        .unwrap_or(false);
+
+    let region = number.trim_end_matches(|c: char| c.is_ascii_digit());
+    let mkt = region
+        .split_once('-')
+        .map(|(lang, country)| format!("{}-{}", lang.to_ascii_lowercase(), country))
+        .filter(|mkt| crate::utils::is_well_formed_mkt(mkt));
+
+    Some((
+        format!("/th?id=OHR.{}_{}", name, number),
+        mkt,
+        name,
+        portrait,
+    ))
+}
+
+/// 将 OHR 编号中的驼峰名称还原为标题（`GreatWall` → `Great Wall`）
+fn title_from_ohr_name(name: &str) -> String {
+    let mut title = String::with_capacity(name.len() + 4);
+    for (i, c) in name.chars().enumerate() {
+        if i > 0 && c.is_ascii_uppercase() {
+            title.push(' ');
+        }
+        title.push(c);
+    }
+    title
+}
+
+/// 识别其他 Bing 壁纸工具的文件命名
 ///
-/// 识别 YYYYMMDD.jpg 和 YYYYMMDDr.jpg 格式的壁纸文件，
-/// 使用 atomic copy（先写临时文件再 rename）确保数据完整性。
-async fn copy_wallpaper_images(
+/// 支持 `OHR.Name_EN-US..._UHD.jpg` 这类保留 Bing 图片编号的命名，以及日期开头的命名
+/// （如 `2024-01-01.jpg`、`20240101_Great Wall.jpg`、`2024-01-01_OHR.Name_EN-US....jpg`）。
+/// 既没有日期也没有图片编号的文件无法与壁纸对应，返回 None。
+fn parse_foreign_image_name(name: &str) -> Option<ForeignImageName> {
+    let lower = name.to_ascii_lowercase();
+    let stem_len = lower
+        .strip_suffix(".jpg")
+        .or_else(|| lower.strip_suffix(".jpeg"))?
+        .len();
+    let stem = &name[..stem_len];
+
+    let (end_date, rest) = match split_date_prefix(stem) {
+        Some((date, rest)) => (Some(date), rest),
+        None => (None, stem),
+    };
+
+    if let Some((urlbase, mkt, ohr_name, portrait)) = parse_ohr_id(rest) {
+        return Some(ForeignImageName {
+            end_date,
+            mkt,
+            urlbase,
+            title: title_from_ohr_name(ohr_name),
+            portrait,
+        });
+    }
+
+    let end_date = end_date?;
+    let title = rest.replace('_', " ").trim().to_string();
+    Some(ForeignImageName {
+        title: if title.is_empty() {
+            end_date.clone()
+        } else {
+            title
+        },
+        end_date: Some(end_date),
+        mkt: None,
+        urlbase: String::new(),
+        portrait: false,
+    })
+}
+
+/// 扫描没有 index.json 的目录，为其他工具下载的壁纸生成索引条目
+///
+/// 文件名中没有 mkt 的壁纸归入 `fallback_mkt`。同一天的多个文件（如不同分辨率）只保留最大的一个。
+async fn scan_foreign_images(
     source_dir: &Path,
-    target_dir: &Path,
-    log_target: &str,
-) -> Result<ImageCopyResult, String> {
-    let mut copied: usize = 0;
-    let mut skipped: usize = 0;
-    let mut failed: usize = 0;
+    fallback_mkt: &str,
+) -> Result<(models::WallpaperIndex, Vec<ImageFile>), String> {
+    // 目标文件名 → (源文件, 大小, 识别结果)
+    let mut found: std::collections::BTreeMap<String, (PathBuf, u64, ForeignImageName)> =
+        std::collections::BTreeMap::new();
 
     let mut read_dir = tokio::fs::read_dir(source_dir)
         .await
@@ -49,33 +214,117 @@ async fn copy_wallpaper_images(
         .await
         .map_err(|e| format!("Failed to read directory entry: {}", e))?
     {
-        let file_name = entry.file_name();
-        let name = file_name.to_string_lossy();
-
-        if !name.ends_with(".jpg") {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let Some(mut parsed) = parse_foreign_image_name(&name) else {
+            continue;
+        };
+        let Ok(metadata) = entry.metadata().await else {
+            continue;
+        };
+        if !metadata.is_file() {
             continue;
         }
-        let stem = name
-            .strip_suffix("r.jpg")
-            .or_else(|| name.strip_suffix(".jpg"));
-        match stem {
-            Some(s) if s.len() == 8 && s.chars().all(|c| c.is_ascii_digit()) => {}
-            _ => continue,
+        let end_date = match parsed.end_date.take() {
+            Some(date) => date,
+            None => match metadata.modified() {
+                Ok(modified) => chrono::DateTime::<chrono::Local>::from(modified)
+                    .format("%Y%m%d")
+                    .to_string(),
+                Err(_) => continue,
+            },
+        };
+        parsed.end_date = Some(end_date.clone());
+
+        let target_name = format!("{}{}.jpg", end_date, if parsed.portrait { "r" } else { "" });
+        if found
+            .get(&target_name)
+            .is_some_and(|(_, size, _)| *size >= metadata.len())
+        {
+            continue;
         }
+        found.insert(target_name, (entry.path(), metadata.len(), parsed));
+    }
 
-        let target_file = target_dir.join(&*name);
+    let mut index = models::WallpaperIndex::default();
+    let mut images = Vec::with_capacity(found.len());
+    for (target_name, (path, _, parsed)) in found {
+        images.push((path, target_name));
+        if parsed.portrait {
+            continue;
+        }
+        let mkt = parsed.mkt.as_deref().unwrap_or(fallback_mkt);
+        index.upsert_wallpapers_for_mkt(
+            mkt,
+            vec![models::LocalWallpaper {
+                title: parsed.title,
+                copyright: String::new(),
+                copyright_link: String::new(),
+                end_date: parsed.end_date.unwrap_or_default(),
+                urlbase: parsed.urlbase,
+                resolution: None,
+                portrait_available: None,
+                watermark_free: None,
+                recompression: None,
+            }],
+        );
+    }
+
+    Ok((index, images))
+}
+
+/// 读取导入源：有 index.json 时使用其中的元数据，否则按其他工具的命名方式识别图片
+async fn load_import_source(
+    source_path: &Path,
+    state: &AppState,
+) -> Result<(models::WallpaperIndex, Vec<ImageFile>), String> {
+    let index_path = source_path.join(index_manager::INDEX_FILE);
+    let (index, images) = if tokio::fs::try_exists(&index_path).await.unwrap_or(false) {
+        let index = index_manager::IndexManager::load_external_index(source_path)
+            .await
+            .map_err(|e| format!("Failed to load external index: {}", e))?;
+        (index, list_wallpaper_images(source_path).await?)
+    } else {
+        let fallback_mkt = crate::get_effective_mkt(state).await;
+        let (index, images) = scan_foreign_images(source_path, &fallback_mkt).await?;
+        info!(
+            target: "import",
+            "源目录没有 index.json，按文件名识别到 {} 张壁纸",
+            images.len()
+        );
+        (index, images)
+    };
+
+    if index.mkt.is_empty() {
+        return Err("NO_DATA".to_string());
+    }
+    Ok((index, images))
+}
+
+/// 复制壁纸图片文件（仅复制目标目录中不存在的文件）
+///
+/// 使用 atomic copy（先写临时文件再 rename）确保数据完整性。
+async fn copy_wallpaper_images(
+    images: &[ImageFile],
+    target_dir: &Path,
+    log_target: &str,
+) -> ImageCopyResult {
+    let mut copied: usize = 0;
+    let mut skipped: usize = 0;
+    let mut failed: usize = 0;
+
+    for (source_file, name) in images {
+        let target_file = target_dir.join(name);
         if tokio::fs::try_exists(&target_file).await.unwrap_or(false) {
             skipped += 1;
             continue;
         }
 
-        let source_file = entry.path();
         let nonce = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap_or_default()
             .subsec_nanos();
         let temp_file = target_dir.join(format!("{}.{}{:x}.tmp", name, std::process::id(), nonce));
-        if let Err(e) = tokio::fs::copy(&source_file, &temp_file).await {
+        if let Err(e) = tokio::fs::copy(source_file, &temp_file).await {
             warn!(target: log_target, "Failed to copy {}: {}", name, e);
             let _ = tokio::fs::remove_file(&temp_file).await;
             failed += 1;
@@ -90,11 +339,11 @@ async fn copy_wallpaper_images(
         copied += 1;
     }
 
-    Ok(ImageCopyResult {
+    ImageCopyResult {
         copied,
         skipped,
         failed,
-    })
+    }
 }
 
 /// 合并元数据到目标目录（best-effort：单个 mkt 失败不中断整体）
@@ -129,22 +378,88 @@ async fn merge_metadata_to_directory(
     (metadata_new, metadata_updated, metadata_skipped)
 }
 
-/// 检查两个路径是否指向同一目录
-fn is_same_directory(a: &Path, b: &Path) -> bool {
-    let a_canonical = a.canonicalize().unwrap_or_else(|_| a.to_path_buf());
-    let b_canonical = b.canonicalize().unwrap_or_else(|_| b.to_path_buf());
-    a_canonical == b_canonical
+/// 统计导入时将复制/跳过的图片数（与 `copy_wallpaper_images` 的判定一致）
+async fn count_images_to_copy(images: &[ImageFile], target_dir: &Path) -> (usize, usize) {
+    let mut to_copy: usize = 0;
+    let mut skipped: usize = 0;
+
+    for (_, name) in images {
+        if tokio::fs::try_exists(target_dir.join(name))
+            .await
+            .unwrap_or(false)
+        {
+            skipped += 1;
+        } else {
+            to_copy += 1;
+        }
+    }
+
+    (to_copy, skipped)
+}
+
+/// 模拟元数据合并，按与 `storage::save_wallpapers_metadata` 相同的规则分类
+fn preview_metadata_merge(
+    source: &models::WallpaperIndex,
+    target: &models::WallpaperIndex,
+) -> ImportPreview {
+    let mut preview = ImportPreview {
+        mkt_count: source.mkt.len(),
+        ..Default::default()
+    };
+
+    for (mkt, wallpapers) in &source.mkt {
+        let existing = target.mkt.get(mkt);
+        for wallpaper in wallpapers.values() {
+            if !storage::validate_wallpaper_mkt(wallpaper, mkt) {
+                preview.metadata_skipped += 1;
+                continue;
+            }
+            match existing.and_then(|m| m.get(&wallpaper.end_date)) {
+                None => preview.metadata_new += 1,
+                Some(current)
+                    if current.urlbase != wallpaper.urlbase || current.title != wallpaper.title =>
+                {
+                    preview.metadata_conflicting += 1;
+                }
+                Some(current)
+                    if current.copyright == wallpaper.copyright
+                        && current.copyright_link == wallpaper.copyright_link
+                        && (wallpaper.resolution.is_none()
+                            || current.resolution == wallpaper.resolution) =>
+                {
+                    preview.metadata_unchanged += 1;
+                }
+                Some(_) => preview.metadata_updated += 1,
+            }
+        }
+    }
+
+    preview
 }
 
 /// 从外部壁纸目录导入数据（index.json + 壁纸图片）
 ///
 /// 读取源目录的 index.json，将元数据合并到当前索引，
 /// 并将源目录中的壁纸图片复制到当前壁纸目录。
+/// 源目录没有 index.json 时，按其他 Bing 壁纸工具的命名方式识别图片并生成元数据。
+/// 导入或导出正在执行、或调用过于频繁时返回 "BUSY"；当前目录为只读存档时返回 "READ_ONLY_DIRECTORY"。
 #[tauri::command]
 pub(crate) async fn import_wallpapers(
     source_dir: String,
     state: tauri::State<'_, AppState>,
     app: tauri::AppHandle,
+) -> Result<TransferResult, String> {
+    command_guard::guarded(
+        command_guard::TRANSFER,
+        import_wallpapers_from(source_dir, &state, &app),
+    )
+    .await
+}
+
+async fn import_wallpapers_from(
+    source_dir: String,
+    state: &AppState,
+    app: &tauri::AppHandle,
 ) -> Result<TransferResult, String> {
     let source_path = PathBuf::from(&source_dir);
 
@@ -154,18 +469,15 @@ pub(crate) async fn import_wallpapers(
 
     let wallpaper_dir = state.wallpaper_directory.lock().await.clone();
 
-    if is_same_directory(&source_path, &wallpaper_dir) {
+    if safe_path::is_within(&wallpaper_dir, &source_path) {
         return Err("SAME_DIRECTORY".to_string());
     }
-
-    let external_index = index_manager::IndexManager::load_external_index(&source_path)
-        .await
-        .map_err(|e| format!("Failed to load external index: {}", e))?;
-
-    if external_index.mkt.is_empty() {
-        return Err("NO_DATA".to_string());
+    if directory_permission::is_read_only_archive(&wallpaper_dir).await {
+        return Err(directory_permission::READ_ONLY_DIRECTORY.to_string());
     }
 
+    let (external_index, images) = load_import_source(&source_path, state).await?;
+
     storage::ensure_wallpaper_directory(&wallpaper_dir)
         .await
         .map_err(|e| format!("Failed to ensure wallpaper directory: {}", e))?;
@@ -174,7 +486,7 @@ pub(crate) async fn import_wallpapers(
     let (metadata_new, metadata_updated, metadata_skipped) =
         merge_metadata_to_directory(&external_index.mkt, &wallpaper_dir, "import").await;
 
-    let images = copy_wallpaper_images(&source_path, &wallpaper_dir, "import").await?;
+    let images = copy_wallpaper_images(&images, &wallpaper_dir, "import").await;
 
     info!(
         target: "import",
@@ -183,7 +495,7 @@ pub(crate) async fn import_wallpapers(
         images.copied, images.skipped, images.failed, mkt_count
     );
 
-    let _ = app.emit("wallpaper-updated", ());
+    let _ = events::WALLPAPER_UPDATED.emit(app, &());
 
     Ok(TransferResult {
         metadata_new,
@@ -196,14 +508,82 @@ pub(crate) async fn import_wallpapers(
     })
 }
 
+/// 预览从外部目录导入的结果（不写入任何文件）
+///
+/// 校验规则与错误码和 `import_wallpapers` 一致，便于前端在确认前直接展示。
+#[tauri::command]
+pub(crate) async fn preview_import(
+    source_dir: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<ImportPreview, String> {
+    command_guard::guarded(
+        command_guard::PREVIEW_IMPORT,
+        preview_import_from(source_dir, &state),
+    )
+    .await
+}
+
+async fn preview_import_from(
+    source_dir: String,
+    state: &AppState,
+) -> Result<ImportPreview, String> {
+    let source_path = PathBuf::from(&source_dir);
+
+    if !source_path.is_dir() {
+        return Err("NOT_DIRECTORY".to_string());
+    }
+
+    let wallpaper_dir = state.wallpaper_directory.lock().await.clone();
+
+    if safe_path::is_within(&wallpaper_dir, &source_path) {
+        return Err("SAME_DIRECTORY".to_string());
+    }
+
+    let (external_index, images) = load_import_source(&source_path, state).await?;
+
+    let current_index = if wallpaper_dir.exists() {
+        storage::get_index_snapshot(&wallpaper_dir)
+            .await
+            .map_err(|e| format!("Failed to load current index: {}", e))?
+    } else {
+        models::WallpaperIndex::default()
+    };
+
+    let mut preview = preview_metadata_merge(&external_index, &current_index);
+    (preview.images_to_copy, preview.images_skipped) =
+        count_images_to_copy(&images, &wallpaper_dir).await;
+
+    info!(
+        target: "import",
+        "导入预览: 新增 {} 条, 更新 {} 条, 冲突 {} 条, 不变 {} 条, 跳过 {} 条, 将复制图片 {} 张, 跳过 {} 张",
+        preview.metadata_new, preview.metadata_updated, preview.metadata_conflicting,
+        preview.metadata_unchanged, preview.metadata_skipped,
+        preview.images_to_copy, preview.images_skipped
+    );
+
+    Ok(preview)
+}
+
 /// 将当前壁纸数据导出到指定目录（index.json + 壁纸图片）
 ///
 /// 读取当前壁纸目录的 index.json，将元数据合并到目标目录的索引，
 /// 并将壁纸图片复制到目标目录。如果目标目录已有数据，执行合并。
+/// 导入或导出正在执行、或调用过于频繁时返回 "BUSY"。
 #[tauri::command]
 pub(crate) async fn export_wallpapers(
     target_dir: String,
     state: tauri::State<'_, AppState>,
+) -> Result<TransferResult, String> {
+    command_guard::guarded(
+        command_guard::TRANSFER,
+        export_wallpapers_to(target_dir, &state),
+    )
+    .await
+}
+
+async fn export_wallpapers_to(
+    target_dir: String,
+    state: &AppState,
 ) -> Result<TransferResult, String> {
     let target_path = PathBuf::from(&target_dir);
 
@@ -213,7 +593,7 @@ pub(crate) async fn export_wallpapers(
 
     let wallpaper_dir = state.wallpaper_directory.lock().await.clone();
 
-    if is_same_directory(&wallpaper_dir, &target_path) {
+    if safe_path::is_within(&wallpaper_dir, &target_path) {
         return Err("SAME_DIRECTORY".to_string());
     }
 
@@ -233,7 +613,8 @@ pub(crate) async fn export_wallpapers(
     let (metadata_new, metadata_updated, metadata_skipped) =
         merge_metadata_to_directory(&source_index.mkt, &target_path, "export").await;
 
-    let images = copy_wallpaper_images(&wallpaper_dir, &target_path, "export").await?;
+    let images = list_wallpaper_images(&wallpaper_dir).await?;
+    let images = copy_wallpaper_images(&images, &target_path, "export").await;
 
     storage::remove_index_manager(&target_path);
 
@@ -254,3 +635,151 @@ pub(crate) async fn export_wallpapers(
         mkt_count,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{LocalWallpaper, WallpaperIndex};
+
+    fn wallpaper(end_date: &str, title: &str, urlbase: &str) -> LocalWallpaper {
+        LocalWallpaper {
+            title: title.to_string(),
+            copyright: "Copyright".to_string(),
+            copyright_link: "https://example.com".to_string(),
+            end_date: end_date.to_string(),
+            urlbase: urlbase.to_string(),
+            resolution: None,
+            portrait_available: None,
+            watermark_free: None,
+            recompression: None,
+        }
+    }
+
+    #[test]
+    fn test_is_wallpaper_image_name() {
+        assert!(is_wallpaper_image_name("20240101.jpg"));
+        assert!(is_wallpaper_image_name("20240101r.jpg"));
+        assert!(!is_wallpaper_image_name("cover.jpg"));
+        assert!(!is_wallpaper_image_name("20240101.png"));
+        assert!(!is_wallpaper_image_name("index.json"));
+    }
+
+    #[test]
+    fn test_parse_foreign_image_name_ohr() {
+        let parsed = parse_foreign_image_name("OHR.GreatWall_en-US1234567890_UHD.jpg").unwrap();
+        assert_eq!(
+            parsed,
+            ForeignImageName {
+                end_date: None,
+                mkt: Some("en-US".to_string()),
+                urlbase: "/th?id=OHR.GreatWall_EN-US1234567890".to_string(),
+                title: "Great Wall".to_string(),
+                portrait: false,
+            }
+        );
+
+        let parsed =
+            parse_foreign_image_name("2024-01-05_OHR.Huangshan_ZH-CN42_1080x1920.JPG").unwrap();
+        assert_eq!(parsed.end_date.as_deref(), Some("20240105"));
+        assert_eq!(parsed.mkt.as_deref(), Some("zh-CN"));
+        assert!(parsed.portrait);
+    }
+
+    #[test]
+    fn test_parse_foreign_image_name_date_prefixed() {
+        let parsed = parse_foreign_image_name("2024.03.01 West_Lake.jpeg").unwrap();
+        assert_eq!(parsed.end_date.as_deref(), Some("20240301"));
+        assert_eq!(parsed.title, "West Lake");
+        assert_eq!(parsed.mkt, None);
+        assert!(parsed.urlbase.is_empty());
+
+        let parsed = parse_foreign_image_name("20240301.jpg").unwrap();
+        assert_eq!(parsed.title, "20240301");
+
+        assert_eq!(parse_foreign_image_name("20241301.jpg"), None);
+        assert_eq!(parse_foreign_image_name("202403011.jpg"), None);
+        assert_eq!(parse_foreign_image_name("holiday.jpg"), None);
+        assert_eq!(parse_foreign_image_name("2024-03-01.png"), None);
+        assert_eq!(parse_foreign_image_name("2024-03-0é.jpg"), None);
+    }
+
+    #[tokio::test]
+    async fn test_scan_foreign_images_keeps_largest_per_day() {
+        let dir = std::env::temp_dir().join(format!("bw_foreign_scan_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("2024-01-05_OHR.Huangshan_ZH-CN42_UHD.jpg"),
+            [0u8; 10],
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("2024-01-05_OHR.Huangshan_ZH-CN42_1920x1080.jpg"),
+            [0u8; 5],
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("2024-01-05_OHR.Huangshan_ZH-CN42_1080x1920.jpg"),
+            [0u8; 5],
+        )
+        .unwrap();
+        std::fs::write(dir.join("2024-01-06 Taishan.jpg"), [0u8; 5]).unwrap();
+        std::fs::write(dir.join("notes.txt"), "").unwrap();
+
+        let (index, images) = scan_foreign_images(&dir, "en-US").await.unwrap();
+        let targets: Vec<_> = images.iter().map(|(_, name)| name.as_str()).collect();
+        assert_eq!(targets, ["20240105.jpg", "20240105r.jpg", "20240106.jpg"]);
+        assert!(
+            images[0]
+                .0
+                .ends_with("2024-01-05_OHR.Huangshan_ZH-CN42_UHD.jpg")
+        );
+        assert_eq!(index.mkt["zh-CN"]["20240105"].title, "Huangshan");
+        assert_eq!(index.mkt["en-US"]["20240106"].title, "Taishan");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_preview_metadata_merge_classifies_entries() {
+        let mut target = WallpaperIndex::default();
+        target.upsert_wallpapers_for_mkt(
+            "zh-CN",
+            vec![
+                wallpaper("20240101", "长城", "/th?id=OHR.GreatWall_ZH-CN1"),
+                wallpaper("20240102", "黄山", "/th?id=OHR.Huangshan_ZH-CN2"),
+                wallpaper("20240103", "西湖", "/th?id=OHR.WestLake_ZH-CN3"),
+            ],
+        );
+
+        let mut changed_copyright = wallpaper("20240102", "黄山", "/th?id=OHR.Huangshan_ZH-CN2");
+        changed_copyright.copyright = "New Copyright".to_string();
+
+        let mut source = WallpaperIndex::default();
+        source.upsert_wallpapers_for_mkt(
+            "zh-CN",
+            vec![
+                wallpaper("20240101", "长城", "/th?id=OHR.GreatWall_ZH-CN1"),
+                changed_copyright,
+                wallpaper("20240103", "桂林", "/th?id=OHR.Guilin_ZH-CN3"),
+                wallpaper("20240104", "泰山", "/th?id=OHR.Taishan_ZH-CN4"),
+                wallpaper("20240105", "Wrong", "/th?id=OHR.Wrong_EN-US5"),
+            ],
+        );
+
+        let preview = preview_metadata_merge(&source, &target);
+        assert_eq!(
+            preview,
+            ImportPreview {
+                metadata_new: 1,
+                metadata_updated: 1,
+                metadata_conflicting: 1,
+                metadata_unchanged: 1,
+                metadata_skipped: 1,
+                images_to_copy: 0,
+                images_skipped: 0,
+                mkt_count: 1,
+            }
+        );
+    }
+}
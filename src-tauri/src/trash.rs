@@ -0,0 +1,48 @@
+//! 移到回收站
+//!
+//! 删除壁纸图片时（重置应用等）默认移到系统回收站/废纸篓，避免误操作一次性毁掉多年的壁纸存档。
+//! 由设置项 `move_to_trash` 控制，关闭后直接永久删除。
+//! 各平台的回收站实现由 `trash` crate 提供（Linux 上遵循 freedesktop.org Trash 规范）。
+
+use anyhow::{Context, Result};
+use log::info;
+use std::path::Path;
+
+/// 删除壁纸文件：`move_to_trash` 为 true 时移到回收站，失败时保留原文件并返回错误
+pub(crate) async fn remove_file(path: &Path, move_to_trash: bool) -> Result<()> {
+    if !move_to_trash {
+        return tokio::fs::remove_file(path)
+            .await
+            .with_context(|| format!("Failed to remove {}", path.display()));
+    }
+
+    let owned = path.to_path_buf();
+    tokio::task::spawn_blocking(move || trash::delete(&owned))
+        .await
+        .context("Trash task failed")?
+        .with_context(|| format!("Failed to trash {}", path.display()))?;
+    info!(target: "trash", "已移到回收站: {}", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_remove_file_permanently_when_trash_disabled() {
+        let unique = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("bw_trash_{unique}"));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("20240101.jpg");
+        std::fs::write(&path, b"data").unwrap();
+
+        remove_file(&path, false).await.unwrap();
+        assert!(!path.exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,16 +1,22 @@
-use crate::{AppState, utils};
+use crate::models::{LocalWallpaper, ProfilesConfig};
+use crate::{AppState, events, get_effective_mkt, storage, utils};
 use log::{info, warn};
-use std::time::{Duration, Instant};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
 #[cfg(target_os = "windows")]
 use std::{
     sync::atomic::{AtomicBool, Ordering},
     thread,
 };
 use tauri::{
-    AppHandle, Emitter, Manager,
+    AppHandle, Manager,
     image::Image,
-    menu::{MenuBuilder, MenuItemBuilder},
-    tray::{TrayIconBuilder, TrayIconEvent},
+    menu::{
+        CheckMenuItemBuilder, IconMenuItemBuilder, IsMenuItem, Menu, MenuBuilder, MenuItemBuilder,
+        PredefinedMenuItem, SubmenuBuilder,
+    },
+    tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
 };
 #[cfg(target_os = "windows")]
 use windows_sys::Win32::{
@@ -19,6 +25,7 @@ use windows_sys::Win32::{
         HKEY, HKEY_CURRENT_USER, KEY_NOTIFY, KEY_QUERY_VALUE, REG_NOTIFY_CHANGE_LAST_SET,
         RRF_RT_REG_DWORD, RegCloseKey, RegGetValueW, RegNotifyChangeKeyValue, RegOpenKeyExW,
     },
+    UI::Input::KeyboardAndMouse::GetDoubleClickTime,
 };
 
 #[cfg(target_os = "windows")]
@@ -32,6 +39,62 @@ const WINDOWS_PERSONALIZE_KEY: &str =
 const WINDOWS_SYSTEM_THEME_VALUE: &str = "SystemUsesLightTheme";
 #[cfg(target_os = "windows")]
 static WINDOWS_THEME_WATCHER_STARTED: AtomicBool = AtomicBool::new(false);
+/// 托盘图标是否跟随壁纸明暗（为 true 时忽略系统主题变化）
+#[cfg(target_os = "windows")]
+static WINDOWS_TRAY_FOLLOWS_WALLPAPER: AtomicBool = AtomicBool::new(false);
+
+/// "最近壁纸"子菜单中显示的壁纸数量
+const RECENT_WALLPAPER_COUNT: usize = 7;
+/// 最近壁纸菜单项 ID 前缀，完整 ID 为 `recent:YYYYMMDD`
+const RECENT_MENU_ID_PREFIX: &str = "recent:";
+/// 配置方案菜单项 ID 前缀，完整 ID 为 `profile:<方案名>`
+const PROFILE_MENU_ID_PREFIX: &str = "profile:";
+/// 最近壁纸缩略图尺寸（保持 16:9，按 2x 渲染）
+const RECENT_THUMBNAIL_SIZE: (u32, u32) = (64, 36);
+/// 菜单标题最大字符数，超出部分以省略号表示
+const RECENT_TITLE_MAX_CHARS: usize = 32;
+
+/// 子菜单中的一项最近壁纸
+struct RecentMenuEntry {
+    end_date: String,
+    label: String,
+}
+
+/// 托盘菜单的声明式描述
+///
+/// 由 `tray_menu_model` 根据语言和运行状态生成，再由 `render_tray_menu` 转换为 Tauri 菜单；
+/// 模型不依赖运行中的应用，可以直接在单元测试中检查。
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TrayMenuNode {
+    Item {
+        id: String,
+        label: String,
+    },
+    /// 带缩略图的菜单项（缩略图在渲染时按 ID 查找，没有缩略图时显示为普通菜单项）
+    IconItem {
+        id: String,
+        label: String,
+    },
+    Check {
+        id: String,
+        label: String,
+        checked: bool,
+    },
+    Submenu {
+        label: String,
+        items: Vec<TrayMenuNode>,
+    },
+    Separator,
+}
+
+impl TrayMenuNode {
+    fn item(id: &str, label: &str) -> Self {
+        Self::Item {
+            id: id.to_string(),
+            label: label.to_string(),
+        }
+    }
+}
 
 fn load_tray_image(icon_bytes: &[u8]) -> tauri::Result<Image<'static>> {
     let icon_img = image::load_from_memory(icon_bytes)
@@ -164,7 +227,9 @@ fn watch_windows_system_theme(app: AppHandle) {
 
         let current_theme = windows_system_uses_light_theme();
         if current_theme != previous_theme {
-            if let Some(system_uses_light_theme) = current_theme {
+            if let Some(system_uses_light_theme) = current_theme
+                && !WINDOWS_TRAY_FOLLOWS_WALLPAPER.load(Ordering::Acquire)
+            {
                 set_windows_tray_icon(&app, system_uses_light_theme);
             }
             previous_theme = current_theme;
@@ -196,11 +261,23 @@ fn start_windows_theme_watcher(app: AppHandle) {
 /// 当 Tauri 收到 Windows 主题事件时刷新托盘图标。
 #[cfg(target_os = "windows")]
 pub(crate) fn refresh_windows_tray_theme(app: &AppHandle, fallback_theme: tauri::Theme) {
+    if WINDOWS_TRAY_FOLLOWS_WALLPAPER.load(Ordering::Acquire) {
+        return;
+    }
     let system_uses_light_theme =
         windows_system_uses_light_theme().unwrap_or(matches!(fallback_theme, tauri::Theme::Light));
     set_windows_tray_icon(app, system_uses_light_theme);
 }
 
+/// 让托盘图标跟随壁纸明暗（`Some(true)` 为浅色壁纸）；传入 None 时恢复跟随系统主题。
+#[cfg(target_os = "windows")]
+pub(crate) fn set_windows_tray_tone(app: &AppHandle, light: Option<bool>) {
+    WINDOWS_TRAY_FOLLOWS_WALLPAPER.store(light.is_some(), Ordering::Release);
+    let uses_light_theme =
+        light.unwrap_or_else(|| windows_system_uses_light_theme().unwrap_or(true));
+    set_windows_tray_icon(app, uses_light_theme);
+}
+
 /// 根据 resolved_language 获取托盘菜单文本
 ///
 /// 传入值应为 "zh-CN" 或 "en-US"（已在设置加载时归一化）
@@ -228,6 +305,527 @@ fn get_tray_menu_texts(resolved_language: &str) -> (&str, &str, &str, &str, &str
     }
 }
 
+/// 更新进行中时替代"更新壁纸"的菜单文本
+fn get_cancel_refresh_text(resolved_language: &str) -> &'static str {
+    if resolved_language == "zh-CN" {
+        "取消更新"
+    } else {
+        "Cancel Refresh"
+    }
+}
+
+/// "这是哪里？"菜单文本（打开当前壁纸信息浮窗）
+fn get_photo_info_text(resolved_language: &str) -> &'static str {
+    if resolved_language == "zh-CN" {
+        "这是哪里？"
+    } else {
+        "What Is This Photo?"
+    }
+}
+
+/// "幻灯片放映"菜单文本（全屏轮播存档中的壁纸）
+fn get_slideshow_text(resolved_language: &str) -> &'static str {
+    if resolved_language == "zh-CN" {
+        "幻灯片放映"
+    } else {
+        "Slideshow"
+    }
+}
+
+/// "随机壁纸"菜单文本（从存档中随机应用一张）
+fn get_random_wallpaper_text(resolved_language: &str) -> &'static str {
+    if resolved_language == "zh-CN" {
+        "随机壁纸"
+    } else {
+        "Random Wallpaper"
+    }
+}
+
+/// "迷你窗口"菜单项文本
+fn get_mini_window_text(resolved_language: &str) -> &'static str {
+    if resolved_language == "zh-CN" {
+        "迷你窗口"
+    } else {
+        "Mini Window"
+    }
+}
+
+/// "最近壁纸"子菜单标题
+fn get_recent_menu_text(resolved_language: &str) -> &'static str {
+    if resolved_language == "zh-CN" {
+        "最近壁纸"
+    } else {
+        "Recent Wallpapers"
+    }
+}
+
+/// "配置方案"子菜单标题
+fn get_profiles_menu_text(resolved_language: &str) -> &'static str {
+    if resolved_language == "zh-CN" {
+        "配置方案"
+    } else {
+        "Profiles"
+    }
+}
+
+fn profile_menu_id(name: &str) -> String {
+    format!("{PROFILE_MENU_ID_PREFIX}{name}")
+}
+
+fn parse_profile_menu_id(id: &str) -> Option<&str> {
+    id.strip_prefix(PROFILE_MENU_ID_PREFIX)
+        .filter(|name| !name.is_empty())
+}
+
+fn recent_menu_id(end_date: &str) -> String {
+    format!("{RECENT_MENU_ID_PREFIX}{end_date}")
+}
+
+/// 从菜单 ID 中解析壁纸日期，只接受 `recent:YYYYMMDD`
+fn parse_recent_menu_id(id: &str) -> Option<&str> {
+    let end_date = id.strip_prefix(RECENT_MENU_ID_PREFIX)?;
+    (end_date.len() == 8 && end_date.bytes().all(|b| b.is_ascii_digit())).then_some(end_date)
+}
+
+/// 菜单项文本：`2024-01-01  标题`
+fn format_recent_label(title: &str, end_date: &str) -> String {
+    let date = if end_date.len() == 8 {
+        format!("{}-{}-{}", &end_date[..4], &end_date[4..6], &end_date[6..])
+    } else {
+        end_date.to_string()
+    };
+
+    let title = title.trim();
+    if title.is_empty() {
+        return date;
+    }
+    let title = if title.chars().count() > RECENT_TITLE_MAX_CHARS {
+        let truncated: String = title.chars().take(RECENT_TITLE_MAX_CHARS - 1).collect();
+        format!("{truncated}…")
+    } else {
+        title.to_string()
+    };
+    format!("{date}  {title}")
+}
+
+/// 生成壁纸缩略图（文件不存在或解码失败时返回 None）
+fn load_thumbnail(path: &Path) -> Option<Image<'static>> {
+    let (width, height) = RECENT_THUMBNAIL_SIZE;
+    let thumbnail = image::open(path).ok()?.thumbnail(width, height).to_rgba8();
+    let (width, height) = thumbnail.dimensions();
+    Some(Image::new_owned(thumbnail.into_raw(), width, height))
+}
+
+/// 读取最近壁纸并生成缩略图（缩略图按菜单项 ID 索引）
+async fn load_recent_entries(
+    app: &AppHandle,
+) -> (Vec<RecentMenuEntry>, HashMap<String, Image<'static>>) {
+    let state = app.state::<AppState>();
+    let wallpaper_dir = state.wallpaper_directory.lock().await.clone();
+    let mkt = get_effective_mkt(&state).await;
+
+    let wallpapers: Vec<LocalWallpaper> =
+        match storage::get_local_wallpapers(&wallpaper_dir, &mkt).await {
+            Ok(wallpapers) => wallpapers
+                .into_iter()
+                .take(RECENT_WALLPAPER_COUNT)
+                .collect(),
+            Err(e) => {
+                warn!(target: "tray", "读取最近壁纸失败: {}", e);
+                return (Vec::new(), HashMap::new());
+            }
+        };
+
+    let entries = wallpapers
+        .iter()
+        .map(|wallpaper| RecentMenuEntry {
+            end_date: wallpaper.end_date.clone(),
+            label: format_recent_label(&wallpaper.title, &wallpaper.end_date),
+        })
+        .collect();
+
+    // 解码 JPEG 较耗时，放到阻塞线程池执行
+    let thumbnails = tauri::async_runtime::spawn_blocking(move || {
+        wallpapers
+            .into_iter()
+            .filter_map(|wallpaper| {
+                let path = storage::get_wallpaper_path(&wallpaper_dir, &wallpaper.end_date);
+                load_thumbnail(&path)
+                    .map(|thumbnail| (recent_menu_id(&wallpaper.end_date), thumbnail))
+            })
+            .collect()
+    })
+    .await
+    .unwrap_or_default();
+
+    (entries, thumbnails)
+}
+
+/// 生成托盘菜单模型
+///
+/// `updating` 为 `true` 时，"更新壁纸"替换为"取消更新"。
+/// 有最近壁纸时显示"最近壁纸"子菜单；定义了配置方案时显示"配置方案"子菜单，当前方案带勾选标记。
+fn tray_menu_model(
+    language: &str,
+    recent: &[RecentMenuEntry],
+    profiles: &ProfilesConfig,
+    updating: bool,
+    mini_visible: bool,
+) -> Vec<TrayMenuNode> {
+    let (
+        show_text,
+        refresh_text,
+        open_folder_text,
+        settings_text,
+        about_text,
+        check_updates_text,
+        quit_text,
+    ) = get_tray_menu_texts(language);
+
+    let mut nodes = vec![
+        TrayMenuNode::item("show", show_text),
+        TrayMenuNode::Separator,
+        if updating {
+            TrayMenuNode::item("cancel_refresh", get_cancel_refresh_text(language))
+        } else {
+            TrayMenuNode::item("refresh", refresh_text)
+        },
+    ];
+
+    if !recent.is_empty() {
+        nodes.push(TrayMenuNode::Submenu {
+            label: get_recent_menu_text(language).to_string(),
+            items: recent
+                .iter()
+                .map(|entry| TrayMenuNode::IconItem {
+                    id: recent_menu_id(&entry.end_date),
+                    label: entry.label.clone(),
+                })
+                .collect(),
+        });
+    }
+
+    if !profiles.profiles.is_empty() {
+        nodes.push(TrayMenuNode::Submenu {
+            label: get_profiles_menu_text(language).to_string(),
+            items: profiles
+                .profiles
+                .iter()
+                .map(|profile| TrayMenuNode::Check {
+                    id: profile_menu_id(&profile.name),
+                    label: profile.name.clone(),
+                    checked: profiles.active.as_deref() == Some(profile.name.as_str()),
+                })
+                .collect(),
+        });
+    }
+
+    nodes.extend([
+        TrayMenuNode::item("photo_info", get_photo_info_text(language)),
+        TrayMenuNode::item("slideshow", get_slideshow_text(language)),
+        TrayMenuNode::item("random_wallpaper", get_random_wallpaper_text(language)),
+        TrayMenuNode::Check {
+            id: "mini_window".to_string(),
+            label: get_mini_window_text(language).to_string(),
+            checked: mini_visible,
+        },
+        TrayMenuNode::item("open_folder", open_folder_text),
+        TrayMenuNode::item("settings", settings_text),
+        TrayMenuNode::item("check_updates", check_updates_text),
+        TrayMenuNode::item("about", about_text),
+        TrayMenuNode::Separator,
+        TrayMenuNode::item("quit", quit_text),
+    ]);
+    nodes
+}
+
+/// 把一个菜单模型节点转换为 Tauri 菜单项
+fn render_menu_node(
+    app: &AppHandle,
+    node: TrayMenuNode,
+    thumbnails: &mut HashMap<String, Image<'static>>,
+) -> tauri::Result<Box<dyn IsMenuItem<tauri::Wry>>> {
+    Ok(match node {
+        TrayMenuNode::Item { id, label } => {
+            Box::new(MenuItemBuilder::with_id(id, label).build(app)?)
+        }
+        TrayMenuNode::IconItem { id, label } => {
+            let mut builder = IconMenuItemBuilder::with_id(id.clone(), label);
+            if let Some(thumbnail) = thumbnails.remove(&id) {
+                builder = builder.icon(thumbnail);
+            }
+            Box::new(builder.build(app)?)
+        }
+        TrayMenuNode::Check { id, label, checked } => Box::new(
+            CheckMenuItemBuilder::with_id(id, label)
+                .checked(checked)
+                .build(app)?,
+        ),
+        TrayMenuNode::Submenu { label, items } => {
+            let mut builder = SubmenuBuilder::new(app, label);
+            for item in items {
+                builder = builder.item(render_menu_node(app, item, thumbnails)?.as_ref());
+            }
+            Box::new(builder.build()?)
+        }
+        TrayMenuNode::Separator => Box::new(PredefinedMenuItem::separator(app)?),
+    })
+}
+
+/// 把菜单模型渲染为 Tauri 菜单
+fn render_tray_menu(
+    app: &AppHandle,
+    nodes: Vec<TrayMenuNode>,
+    mut thumbnails: HashMap<String, Image<'static>>,
+) -> tauri::Result<Menu<tauri::Wry>> {
+    let mut builder = MenuBuilder::new(app);
+    for node in nodes {
+        builder = builder.item(render_menu_node(app, node, &mut thumbnails)?.as_ref());
+    }
+    builder.build()
+}
+
+/// 应用"最近壁纸"子菜单中选中的壁纸（复用前端设置壁纸的同一路径）
+fn apply_recent_wallpaper(app: &AppHandle, end_date: &str) {
+    let app_handle = app.clone();
+    let end_date = end_date.to_string();
+    tauri::async_runtime::spawn(async move {
+        let state = app_handle.state::<AppState>();
+        let wallpaper_dir = state.wallpaper_directory.lock().await.clone();
+        let path = storage::get_wallpaper_path(&wallpaper_dir, &end_date);
+        if let Err(e) = crate::commands::wallpaper::set_desktop_wallpaper(
+            path.to_string_lossy().to_string(),
+            state,
+            app_handle.clone(),
+        )
+        .await
+        {
+            warn!(target: "tray", "从托盘设置最近壁纸失败 {}: {}", end_date, e);
+        }
+    });
+}
+
+/// 左键单击是否直接弹出托盘菜单（对应设置 `tray_left_click = "show_menu"`）
+fn left_click_shows_menu(tray_left_click: &str) -> bool {
+    tray_left_click == "show_menu"
+}
+
+/// 在壁纸列表（从新到旧）中找到当前壁纸的下一张（更早的一张），到末尾后回到最新
+fn next_end_date<'a>(end_dates: &'a [String], current: Option<&str>) -> Option<&'a str> {
+    let next_index = current
+        .and_then(|current| end_dates.iter().position(|date| date == current))
+        .map_or(0, |index| (index + 1) % end_dates.len());
+    end_dates.get(next_index).map(String::as_str)
+}
+
+/// 托盘左键单击/双击判别
+///
+/// 系统在按下和松开时各发送一次单击事件，这里只处理松开。Windows 双击的事件顺序为
+/// 松开 → 双击 → 松开：设置了双击操作时，单击动作推迟到双击间隔结束后执行，期间收到双击则取消；
+/// 双击之后的那次松开不再视为单击。
+#[derive(Debug, Default)]
+pub(crate) struct TrayClickTracker {
+    /// 每次左键松开或双击递增，推迟的单击动作据此判断是否已被双击取代
+    generation: u64,
+    /// 刚收到双击，忽略随后的那次松开
+    after_double_click: bool,
+}
+
+/// 左键松开后单击动作的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SingleClick {
+    /// 双击的第二次松开，不执行
+    Ignore,
+    /// 立即执行
+    Now,
+    /// 等待双击间隔，`generation` 未变化时再执行
+    Deferred(u64),
+}
+
+impl TrayClickTracker {
+    fn on_click_up(&mut self, wait_for_double_click: bool) -> SingleClick {
+        self.generation += 1;
+        if std::mem::take(&mut self.after_double_click) {
+            SingleClick::Ignore
+        } else if wait_for_double_click {
+            SingleClick::Deferred(self.generation)
+        } else {
+            SingleClick::Now
+        }
+    }
+
+    fn on_double_click(&mut self) {
+        self.generation += 1;
+        self.after_double_click = true;
+    }
+
+    fn is_pending(&self, generation: u64) -> bool {
+        self.generation == generation
+    }
+}
+
+/// 单击是否需要等待双击（只有 Windows 会发送双击事件）
+fn waits_for_double_click(tray_double_click: &str) -> bool {
+    cfg!(target_os = "windows") && tray_double_click != "none"
+}
+
+/// 系统设置的双击间隔
+fn double_click_interval() -> Duration {
+    #[cfg(target_os = "windows")]
+    {
+        // SAFETY: GetDoubleClickTime has no preconditions.
+        Duration::from_millis(u64::from(unsafe { GetDoubleClickTime() }))
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        Duration::ZERO
+    }
+}
+
+/// 执行左键单击动作（对应设置 `tray_left_click`）
+fn run_left_click_action(app: &AppHandle, tray_left_click: &str) {
+    match tray_left_click {
+        // 菜单由系统弹出（show_menu_on_left_click），这里无需处理
+        "show_menu" => {}
+        "next_wallpaper" => apply_next_wallpaper(app),
+        _ => toggle_main_window(app),
+    }
+}
+
+/// 执行左键双击动作（对应设置 `tray_double_click`）
+fn run_double_click_action(app: &AppHandle, tray_double_click: &str) {
+    match tray_double_click {
+        "open_window" => show_main_window(app),
+        "refresh" => start_forced_update(app),
+        "next_wallpaper" => apply_next_wallpaper(app),
+        _ => {}
+    }
+}
+
+/// 处理托盘左键松开
+fn handle_left_click(app: &AppHandle) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    // 读取设置失败时按默认行为切换窗口
+    let (tray_left_click, tray_double_click) = state
+        .settings
+        .try_read()
+        .map(|settings| (settings.tray_left_click, settings.tray_double_click))
+        .unwrap_or_default();
+
+    // 使用 try_lock 避免阻塞，如果失败则直接执行单击动作
+    let decision = match state.tray_clicks.try_lock() {
+        Ok(mut tracker) => tracker.on_click_up(waits_for_double_click(&tray_double_click)),
+        Err(_) => SingleClick::Now,
+    };
+    match decision {
+        SingleClick::Ignore => {}
+        SingleClick::Now => run_left_click_action(app, &tray_left_click),
+        SingleClick::Deferred(generation) => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                tokio::time::sleep(double_click_interval()).await;
+                let pending = app
+                    .state::<AppState>()
+                    .tray_clicks
+                    .lock()
+                    .await
+                    .is_pending(generation);
+                if pending {
+                    run_left_click_action(&app, &tray_left_click);
+                }
+            });
+        }
+    }
+}
+
+/// 处理托盘左键双击（仅 Windows）
+fn handle_double_click(app: &AppHandle) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    if let Ok(mut tracker) = state.tray_clicks.try_lock() {
+        tracker.on_double_click();
+    }
+    let tray_double_click = state
+        .settings
+        .try_read()
+        .map(|settings| settings.tray_double_click)
+        .unwrap_or_default();
+    run_double_click_action(app, &tray_double_click);
+}
+
+/// 显示并聚焦主窗口
+fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// 异步触发一次强制更新；更新期间菜单项会切换为"取消更新"
+fn start_forced_update(app: &AppHandle) {
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        crate::update_cycle::run_update_cycle_internal(&app_handle, true).await;
+    });
+}
+
+/// 切换主窗口显示/隐藏
+fn toggle_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        // hide() 可能失败，但失败时忽略错误（窗口可能已经关闭）
+        if window.is_visible().unwrap_or(false) {
+            let _ = window.hide();
+        } else {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+    }
+}
+
+/// 应用本地壁纸列表中当前壁纸的下一张
+fn apply_next_wallpaper(app: &AppHandle) {
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app_handle.state::<AppState>();
+        let wallpaper_dir = state.wallpaper_directory.lock().await.clone();
+        let mkt = get_effective_mkt(&state).await;
+        let end_dates: Vec<String> = match storage::get_local_wallpapers(&wallpaper_dir, &mkt).await
+        {
+            Ok(wallpapers) => wallpapers.into_iter().map(|w| w.end_date).collect(),
+            Err(e) => {
+                warn!(target: "tray", "读取本地壁纸失败: {}", e);
+                return;
+            }
+        };
+        let current = state
+            .current_wallpaper_path
+            .lock()
+            .await
+            .as_ref()
+            .and_then(|path| path.file_stem())
+            .and_then(|stem| stem.to_str())
+            .map(str::to_string);
+
+        match next_end_date(&end_dates, current.as_deref()) {
+            Some(end_date) => apply_recent_wallpaper(&app_handle, end_date),
+            None => info!(target: "tray", "没有可切换的本地壁纸"),
+        }
+    });
+}
+
+/// 按设置更新托盘图标的左键行为（设置变更后调用）
+pub(crate) async fn apply_left_click_behavior(app: &AppHandle, tray_left_click: &str) {
+    let tray = app.state::<AppState>().tray_icon.lock().await.clone();
+    if let Some(tray) = tray
+        && let Err(e) = tray.set_show_menu_on_left_click(left_click_shows_menu(tray_left_click))
+    {
+        warn!(target: "tray", "更新托盘左键行为失败: {}", e);
+    }
+}
+
 /// 更新托盘菜单（仅更新菜单，不重新创建托盘图标）
 pub(crate) async fn update_tray_menu(app: &AppHandle) -> tauri::Result<()> {
     info!(target: "tray", "开始更新托盘菜单");
@@ -243,43 +841,18 @@ pub(crate) async fn update_tray_menu(app: &AppHandle) -> tauri::Result<()> {
         // 获取 resolved_language（已归一化为 "zh-CN" 或 "en-US"）
         let language = {
             let state = app.state::<AppState>();
-            let settings = state.settings.lock().await;
+            let settings = state.settings.read().await;
             settings.resolved_language.clone()
         };
 
         info!(target: "tray", "更新托盘菜单，使用语言: {}", language);
 
-        let (
-            show_text,
-            refresh_text,
-            open_folder_text,
-            settings_text,
-            about_text,
-            check_updates_text,
-            quit_text,
-        ) = get_tray_menu_texts(&language);
-
-        let show_item = MenuItemBuilder::with_id("show", show_text).build(app)?;
-        let refresh_item = MenuItemBuilder::with_id("refresh", refresh_text).build(app)?;
-        let open_folder_item =
-            MenuItemBuilder::with_id("open_folder", open_folder_text).build(app)?;
-        let settings_item = MenuItemBuilder::with_id("settings", settings_text).build(app)?;
-        let about_item = MenuItemBuilder::with_id("about", about_text).build(app)?;
-        let check_updates_item =
-            MenuItemBuilder::with_id("check_updates", check_updates_text).build(app)?;
-        let quit_item = MenuItemBuilder::with_id("quit", quit_text).build(app)?;
-
-        let menu = MenuBuilder::new(app)
-            .item(&show_item)
-            .separator()
-            .item(&refresh_item)
-            .item(&open_folder_item)
-            .item(&settings_item)
-            .item(&check_updates_item)
-            .item(&about_item)
-            .separator()
-            .item(&quit_item)
-            .build()?;
+        let updating = app.state::<AppState>().update_state.lock().await.is_busy();
+        let profiles = app.state::<AppState>().profiles.lock().await.clone();
+        let (recent, thumbnails) = load_recent_entries(app).await;
+        let mini_visible = crate::mini_window::is_visible(app);
+        let model = tray_menu_model(&language, &recent, &profiles, updating, mini_visible);
+        let menu = render_tray_menu(app, model, thumbnails)?;
 
         // 使用 set_menu 直接更新菜单（不重新创建托盘图标）
         // set_menu 需要 Option<M>，其中 M 实现 ContextMenu trait
@@ -297,56 +870,37 @@ pub(crate) async fn update_tray_menu(app: &AppHandle) -> tauri::Result<()> {
 pub(crate) fn setup_tray(app: &AppHandle) -> tauri::Result<()> {
     info!(target: "tray", "开始设置托盘菜单");
 
-    // 获取 resolved_language（同步方式，仅在初始化时使用）
-    let language = {
+    // 获取 resolved_language 与左键行为（同步方式，仅在初始化时使用）
+    let (language, tray_left_click) = {
         if let Some(state) = app.try_state::<AppState>() {
-            if let Ok(settings) = state.settings.try_lock() {
-                if settings.resolved_language.is_empty() {
+            if let Some(settings) = state.settings.try_read() {
+                let language = if settings.resolved_language.is_empty() {
                     // resolved_language 未计算时（理论上不应发生），回退到系统检测
                     utils::detect_system_language().to_string()
                 } else {
                     settings.resolved_language.clone()
-                }
+                };
+                (language, settings.tray_left_click.clone())
             } else {
-                utils::detect_system_language().to_string()
+                (utils::detect_system_language().to_string(), String::new())
             }
         } else {
-            utils::detect_system_language().to_string()
+            (utils::detect_system_language().to_string(), String::new())
         }
     };
 
     info!(target: "tray", "使用语言: {}", language);
 
-    let (
-        show_text,
-        refresh_text,
-        open_folder_text,
-        settings_text,
-        about_text,
-        check_updates_text,
-        quit_text,
-    ) = get_tray_menu_texts(&language);
-
-    let show_item = MenuItemBuilder::with_id("show", show_text).build(app)?;
-    let refresh_item = MenuItemBuilder::with_id("refresh", refresh_text).build(app)?;
-    let open_folder_item = MenuItemBuilder::with_id("open_folder", open_folder_text).build(app)?;
-    let settings_item = MenuItemBuilder::with_id("settings", settings_text).build(app)?;
-    let about_item = MenuItemBuilder::with_id("about", about_text).build(app)?;
-    let check_updates_item =
-        MenuItemBuilder::with_id("check_updates", check_updates_text).build(app)?;
-    let quit_item = MenuItemBuilder::with_id("quit", quit_text).build(app)?;
-
-    let menu = MenuBuilder::new(app)
-        .item(&show_item)
-        .separator()
-        .item(&refresh_item)
-        .item(&open_folder_item)
-        .item(&settings_item)
-        .item(&check_updates_item)
-        .item(&about_item)
-        .separator()
-        .item(&quit_item)
-        .build()?;
+    // 最近壁纸需要读取索引和解码图片，初始菜单先不包含，首次更新循环结束后刷新
+    let profiles = app
+        .try_state::<AppState>()
+        .and_then(|state| state.profiles.try_lock().ok().map(|p| p.clone()))
+        .unwrap_or_default();
+    let menu = render_tray_menu(
+        app,
+        tray_menu_model(&language, &[], &profiles, false, false),
+        HashMap::new(),
+    )?;
 
     info!(target: "tray", "菜单创建完成，正在创建托盘图标");
 
@@ -370,7 +924,7 @@ pub(crate) fn setup_tray(app: &AppHandle) -> tauri::Result<()> {
             .menu(&menu)
             .icon(icon)
             .tooltip("Bing Wallpaper Now")
-            .show_menu_on_left_click(false);
+            .show_menu_on_left_click(left_click_shows_menu(&tray_left_click));
 
         // macOS 设置模板图标以支持深色/浅色模式自动切换
         #[cfg(target_os = "macos")]
@@ -384,50 +938,78 @@ pub(crate) fn setup_tray(app: &AppHandle) -> tauri::Result<()> {
     };
 
     let tray = tray_builder
-        .on_tray_icon_event(|tray, event| {
-            if let TrayIconEvent::Click { button, .. } = event
-                && button == tauri::tray::MouseButton::Left
-            {
-                let app = tray.app_handle();
-                if let Some(state) = app.try_state::<AppState>() {
-                    let now = Instant::now();
-
-                    // 使用 try_lock 避免阻塞，如果失败则跳过防抖检查
-                    if let Ok(mut last_click) = state.last_tray_click.try_lock() {
-                        if let Some(last) = *last_click
-                            && now.duration_since(last) < Duration::from_millis(300)
-                        {
-                            return;
-                        }
-                        *last_click = Some(now);
-                    }
-
-                    if let Some(window) = app.get_webview_window("main") {
-                        // hide() 可能失败，但失败时忽略错误（窗口可能已经关闭）
-                        if window.is_visible().unwrap_or(false) {
-                            let _ = window.hide();
-                        } else {
-                            let _ = window.show();
-                            let _ = window.set_focus();
-                        }
-                    }
-                }
-            }
+        .on_tray_icon_event(|tray, event| match event {
+            TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } => handle_left_click(tray.app_handle()),
+            TrayIconEvent::DoubleClick {
+                button: MouseButton::Left,
+                ..
+            } => handle_double_click(tray.app_handle()),
+            _ => {}
         })
         .on_menu_event(|app, event| {
             info!(target: "tray", "托盘菜单事件: {}", event.id().as_ref());
             match event.id().as_ref() {
-                "show" => {
-                    if let Some(window) = app.get_webview_window("main") {
-                        let _ = window.show();
-                        let _ = window.set_focus();
-                    }
+                "show" => show_main_window(app),
+                "refresh" => start_forced_update(app),
+                "cancel_refresh" => {
+                    let app_handle = app.clone();
+                    tauri::async_runtime::spawn(async move {
+                        if !crate::update_cycle::cancel_update_cycle(&app_handle).await {
+                            // 更新已结束但菜单尚未刷新，直接恢复菜单
+                            if let Err(e) = update_tray_menu(&app_handle).await {
+                                warn!(target: "tray", "刷新托盘菜单失败: {}", e);
+                            }
+                        }
+                    });
+                }
+                "photo_info" => {
+                    let app_handle = app.clone();
+                    tauri::async_runtime::spawn(async move {
+                        if let Err(e) = crate::attribution::show_overlay(&app_handle).await {
+                            warn!(target: "tray", "打开壁纸信息浮窗失败: {}", e);
+                        }
+                    });
+                }
+                "slideshow" => {
+                    let app_handle = app.clone();
+                    tauri::async_runtime::spawn(async move {
+                        let interval = Duration::from_secs(crate::slideshow::DEFAULT_INTERVAL_SECS);
+                        if let Err(e) = crate::slideshow::open_slideshow(
+                            &app_handle,
+                            interval,
+                            &Default::default(),
+                        )
+                        .await
+                        {
+                            warn!(target: "tray", "打开幻灯片失败: {}", e);
+                        }
+                    });
+                }
+                "random_wallpaper" => {
+                    let app_handle = app.clone();
+                    tauri::async_runtime::spawn(async move {
+                        let state = app_handle.state::<AppState>();
+                        if let Err(e) = crate::random_wallpaper::apply_random_wallpaper(
+                            crate::random_wallpaper::DEFAULT_EXCLUDE_RECENT_DAYS,
+                            state,
+                            app_handle.clone(),
+                        )
+                        .await
+                        {
+                            warn!(target: "tray", "从托盘应用随机壁纸失败: {}", e);
+                        }
+                    });
                 }
-                "refresh" => {
-                    // 异步触发一次强制更新
+                "mini_window" => {
                     let app_handle = app.clone();
                     tauri::async_runtime::spawn(async move {
-                        crate::update_cycle::run_update_cycle_internal(&app_handle, true).await;
+                        if let Err(e) = crate::mini_window::toggle(&app_handle).await {
+                            warn!(target: "tray", "切换迷你窗口失败: {}", e);
+                        }
                     });
                 }
                 "open_folder" => {
@@ -436,7 +1018,7 @@ pub(crate) fn setup_tray(app: &AppHandle) -> tauri::Result<()> {
                         let _ = window.show();
                         let _ = window.set_focus();
                     }
-                    let _ = app.emit("open-folder", ());
+                    let _ = events::OPEN_FOLDER.emit(app, &());
                 }
                 "settings" => {
                     // 显示主窗口并向前端发送事件，前端可监听此事件弹出设置
@@ -444,7 +1026,7 @@ pub(crate) fn setup_tray(app: &AppHandle) -> tauri::Result<()> {
                         let _ = window.show();
                         let _ = window.set_focus();
                     }
-                    let _ = app.emit("open-settings", ());
+                    let _ = events::OPEN_SETTINGS.emit(app, &());
                 }
                 "about" => {
                     // 显示主窗口并向前端发送事件，前端可监听此事件弹出关于对话框
@@ -452,21 +1034,38 @@ pub(crate) fn setup_tray(app: &AppHandle) -> tauri::Result<()> {
                         let _ = window.show();
                         let _ = window.set_focus();
                     }
-                    let _ = app.emit("open-about", ());
+                    let _ = events::OPEN_ABOUT.emit(app, &());
                 }
                 "check_updates" => {
                     // 通知前端执行更新检查。
                     // 实际检查逻辑由前端 useUpdateCheck 通过 @tauri-apps/plugin-updater 完成
-                    if let Err(e) = app.emit("tray-check-updates", ()) {
+                    if let Err(e) = events::TRAY_CHECK_UPDATES.emit(app, &()) {
                         warn!(target: "tray", "Failed to emit tray-check-updates event: {}", e);
                     }
                 }
                 "quit" => {
-                    // 优雅退出应用
-                    app.exit(0);
+                    // 按设置确认后退出，或只隐藏到托盘
+                    crate::quit::request_quit(app);
                 }
-                _ => {
-                    warn!(target: "tray", "未知的托盘菜单事件: {}", event.id().as_ref());
+                id => {
+                    if let Some(end_date) = parse_recent_menu_id(id) {
+                        apply_recent_wallpaper(app, end_date);
+                    } else if let Some(name) = parse_profile_menu_id(id) {
+                        let app_handle = app.clone();
+                        let name = name.to_string();
+                        tauri::async_runtime::spawn(async move {
+                            if let Err(e) =
+                                crate::profiles::switch_profile(name.clone(), app_handle.clone())
+                                    .await
+                            {
+                                warn!(target: "tray", "切换配置方案 {} 失败: {}", name, e);
+                                // 恢复勾选状态
+                                let _ = update_tray_menu(&app_handle).await;
+                            }
+                        });
+                    } else {
+                        warn!(target: "tray", "未知的托盘菜单事件: {}", id);
+                    }
                 }
             }
         })
@@ -490,16 +1089,234 @@ pub(crate) fn setup_tray(app: &AppHandle) -> tauri::Result<()> {
     Ok(())
 }
 
-#[cfg(all(test, target_os = "windows"))]
+#[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn recent_menu_id_round_trip() {
+        let id = recent_menu_id("20240101");
+        assert_eq!(id, "recent:20240101");
+        assert_eq!(parse_recent_menu_id(&id), Some("20240101"));
+    }
+
+    #[test]
+    fn recent_menu_id_rejects_other_ids() {
+        assert_eq!(parse_recent_menu_id("refresh"), None);
+        assert_eq!(parse_recent_menu_id("recent:"), None);
+        assert_eq!(parse_recent_menu_id("recent:../secret"), None);
+        assert_eq!(parse_recent_menu_id("recent:2024010"), None);
+    }
+
+    #[test]
+    fn profile_menu_id_round_trip() {
+        assert_eq!(
+            parse_profile_menu_id(&profile_menu_id("家里")),
+            Some("家里")
+        );
+        assert_eq!(parse_profile_menu_id("profile:"), None);
+        assert_eq!(parse_profile_menu_id("recent:20240101"), None);
+        assert_eq!(parse_recent_menu_id(&profile_menu_id("20240101")), None);
+    }
+
+    /// 把菜单模型渲染为便于比较的文本快照
+    fn snapshot(nodes: &[TrayMenuNode]) -> String {
+        fn write(nodes: &[TrayMenuNode], indent: usize, out: &mut String) {
+            let pad = "  ".repeat(indent);
+            for node in nodes {
+                match node {
+                    TrayMenuNode::Item { id, label } => {
+                        out.push_str(&format!("{pad}{id}: {label}\n"))
+                    }
+                    TrayMenuNode::IconItem { id, label } => {
+                        out.push_str(&format!("{pad}{id}: {label} [icon]\n"))
+                    }
+                    TrayMenuNode::Check { id, label, checked } => {
+                        let mark = if *checked { "x" } else { " " };
+                        out.push_str(&format!("{pad}[{mark}] {id}: {label}\n"));
+                    }
+                    TrayMenuNode::Submenu { label, items } => {
+                        out.push_str(&format!("{pad}> {label}\n"));
+                        write(items, indent + 1, out);
+                    }
+                    TrayMenuNode::Separator => out.push_str(&format!("{pad}---\n")),
+                }
+            }
+        }
+        let mut out = String::new();
+        write(nodes, 0, &mut out);
+        out
+    }
+
+    fn profile(name: &str) -> crate::models::WallpaperProfile {
+        crate::models::WallpaperProfile {
+            name: name.to_string(),
+            save_directory: None,
+            mkt: String::new(),
+            download_resolution: "auto".to_string(),
+        }
+    }
+
+    #[test]
+    fn tray_menu_snapshot_minimal_en() {
+        let model = tray_menu_model("en-US", &[], &ProfilesConfig::default(), false, false);
+        assert_eq!(
+            snapshot(&model),
+            "\
+show: Show Window
+---
+refresh: Refresh Wallpaper
+photo_info: What Is This Photo?
+slideshow: Slideshow
+random_wallpaper: Random Wallpaper
+[ ] mini_window: Mini Window
+open_folder: Open Save Directory
+settings: Open Settings
+check_updates: Check for Updates
+about: About
+---
+quit: Quit
+"
+        );
+    }
+
+    #[test]
+    fn tray_menu_snapshot_with_dynamic_items_zh() {
+        let recent = [
+            RecentMenuEntry {
+                end_date: "20240102".to_string(),
+                label: format_recent_label("雪山", "20240102"),
+            },
+            RecentMenuEntry {
+                end_date: "20240101".to_string(),
+                label: format_recent_label("", "20240101"),
+            },
+        ];
+        let profiles = ProfilesConfig {
+            profiles: vec![profile("工作"), profile("家里")],
+            active: Some("家里".to_string()),
+        };
+        let model = tray_menu_model("zh-CN", &recent, &profiles, true, true);
+        assert_eq!(
+            snapshot(&model),
+            "\
+show: 显示窗口
+---
+cancel_refresh: 取消更新
+> 最近壁纸
+  recent:20240102: 2024-01-02  雪山 [icon]
+  recent:20240101: 2024-01-01 [icon]
+> 配置方案
+  [ ] profile:工作: 工作
+  [x] profile:家里: 家里
+photo_info: 这是哪里？
+slideshow: 幻灯片放映
+random_wallpaper: 随机壁纸
+[x] mini_window: 迷你窗口
+open_folder: 打开保存目录
+settings: 打开设置
+check_updates: 检查更新
+about: 关于
+---
+quit: 退出
+"
+        );
+    }
+
+    #[test]
+    fn cancel_refresh_text_is_localized() {
+        assert_eq!(get_cancel_refresh_text("zh-CN"), "取消更新");
+        assert_eq!(get_cancel_refresh_text("en-US"), "Cancel Refresh");
+        assert_eq!(parse_recent_menu_id("cancel_refresh"), None);
+    }
+
+    #[test]
+    fn photo_info_text_is_localized() {
+        assert_eq!(get_photo_info_text("zh-CN"), "这是哪里？");
+        assert_eq!(get_photo_info_text("en-US"), "What Is This Photo?");
+        assert_eq!(get_slideshow_text("zh-CN"), "幻灯片放映");
+        assert_eq!(get_slideshow_text("en-US"), "Slideshow");
+        assert_eq!(get_mini_window_text("zh-CN"), "迷你窗口");
+        assert_eq!(get_mini_window_text("en-US"), "Mini Window");
+        assert_eq!(parse_recent_menu_id("photo_info"), None);
+    }
+
+    #[test]
+    fn left_click_menu_only_for_show_menu() {
+        assert!(left_click_shows_menu("show_menu"));
+        assert!(!left_click_shows_menu("toggle_window"));
+        assert!(!left_click_shows_menu("next_wallpaper"));
+        assert!(!left_click_shows_menu(""));
+    }
+
+    #[test]
+    fn single_click_runs_immediately_without_double_click_action() {
+        let mut tracker = TrayClickTracker::default();
+        assert_eq!(tracker.on_click_up(false), SingleClick::Now);
+        // 双击：松开 → 双击 → 松开，第二次松开不再切换窗口
+        tracker.on_double_click();
+        assert_eq!(tracker.on_click_up(false), SingleClick::Ignore);
+        assert_eq!(tracker.on_click_up(false), SingleClick::Now);
+    }
+
+    #[test]
+    fn double_click_cancels_deferred_single_click() {
+        let mut tracker = TrayClickTracker::default();
+        let SingleClick::Deferred(first) = tracker.on_click_up(true) else {
+            panic!("single click should wait for a possible double click");
+        };
+        tracker.on_double_click();
+        assert_eq!(tracker.on_click_up(true), SingleClick::Ignore);
+        assert!(!tracker.is_pending(first));
+
+        let SingleClick::Deferred(second) = tracker.on_click_up(true) else {
+            panic!("single click should wait for a possible double click");
+        };
+        assert!(tracker.is_pending(second));
+    }
+
+    #[test]
+    fn next_end_date_cycles_to_older_wallpaper() {
+        let end_dates: Vec<String> = ["20240103", "20240102", "20240101"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(
+            next_end_date(&end_dates, Some("20240103")),
+            Some("20240102")
+        );
+        assert_eq!(
+            next_end_date(&end_dates, Some("20240101")),
+            Some("20240103")
+        );
+        // 当前壁纸不在列表中（如自定义图片）时从最新一张开始
+        assert_eq!(next_end_date(&end_dates, Some("photo")), Some("20240103"));
+        assert_eq!(next_end_date(&end_dates, None), Some("20240103"));
+        assert_eq!(next_end_date(&[], Some("20240101")), None);
+    }
+
+    #[test]
+    fn recent_label_formats_date_and_truncates_title() {
+        assert_eq!(format_recent_label("长城", "20240101"), "2024-01-01  长城");
+        assert_eq!(format_recent_label("  ", "20240101"), "2024-01-01");
+
+        let long_title = "a".repeat(RECENT_TITLE_MAX_CHARS + 5);
+        let label = format_recent_label(&long_title, "20240101");
+        assert!(label.ends_with('…'));
+        assert_eq!(
+            label.chars().count(),
+            "2024-01-01  ".chars().count() + RECENT_TITLE_MAX_CHARS
+        );
+    }
+
+    #[cfg(target_os = "windows")]
     #[test]
     fn windows_tray_theme_selects_the_matching_asset() {
         assert_eq!(windows_tray_icon_bytes(true), WINDOWS_TRAY_ICON_LIGHT);
         assert_eq!(windows_tray_icon_bytes(false), WINDOWS_TRAY_ICON_DARK);
     }
 
+    #[cfg(target_os = "windows")]
     #[test]
     fn windows_tray_assets_are_monochrome_and_high_dpi() {
         for (bytes, expected_channel) in [
@@ -545,6 +1362,7 @@ mod tests {
         }
     }
 
+    #[cfg(target_os = "windows")]
     #[test]
     fn wide_null_produces_a_single_null_terminator() {
         let encoded = wide_null("SystemUsesLightTheme");
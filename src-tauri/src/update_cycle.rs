@@ -1,13 +1,28 @@
-use crate::models::{LocalWallpaper, MarketStatus};
+use crate::bing_api::ImageSource;
+use crate::clock::Clock;
+use crate::models::{
+    AppRuntimeState, LocalWallpaper, MarketHealth, MarketStatus, UpdatePhase, UpdateState,
+};
 use crate::{
-    AppState, bing_api, download_manager, get_effective_mkt, notification, runtime_state, storage,
-    wallpaper_manager,
+    AppState, backup, bing_api, command_guard, directory_permission, disk_space, download_manager,
+    events, extension_events, get_effective_mkt, local_folder, mini_window, notification,
+    placeholder, power, publish_schedule, runtime_state, smart_crop, spotlight, storage, tray,
+    utils, wallpaper_manager, wallpaper_osd, wallpaper_theme, wallpaper_transition,
 };
-use chrono::Local;
-use log::{error, info, warn};
+use log::{debug, error, info, warn};
 use std::path::{Path, PathBuf};
 use std::time::Duration;
-use tauri::{AppHandle, Emitter, Manager};
+use tauri::{AppHandle, Manager};
+use tokio_util::sync::CancellationToken;
+
+/// 当前是否应暂停后台图片下载（电池供电时推迟、壁纸目录磁盘空间不足或为只读存档）
+///
+/// 暂停期间跳过的下载在下一次更新循环（或接通电源时）重试。
+pub(crate) async fn downloads_paused(app: &AppHandle) -> bool {
+    power::downloads_deferred(app).await
+        || disk_space::space_low(app).await
+        || directory_permission::archive_read_only(app).await
+}
 
 /// 重新下载缺失的壁纸文件
 pub(crate) async fn redownload_missing_wallpapers(
@@ -15,6 +30,14 @@ pub(crate) async fn redownload_missing_wallpapers(
     wallpaper_dir: PathBuf,
     app: tauri::AppHandle,
 ) {
+    if downloads_paused(&app).await {
+        info!(
+            target: "commands",
+            "后台下载已暂停（电池供电、磁盘空间不足或只读存档），推迟重新下载 {} 张缺失的壁纸",
+            missing_wallpapers.len()
+        );
+        return;
+    }
     info!(target: "commands", "开始重新下载 {} 张缺失的壁纸", missing_wallpapers.len());
 
     for wallpaper in missing_wallpapers {
@@ -24,17 +47,27 @@ pub(crate) async fn redownload_missing_wallpapers(
             continue;
         }
 
-        // 构建完整的图片 URL
-        let image_url = bing_api::get_wallpaper_url(&wallpaper.urlbase, "UHD");
-
-        // 构建保存路径（使用 end_date，因为文件名使用 end_date）
-        let save_path = wallpaper_dir.join(format!("{}.jpg", wallpaper.end_date));
-
-        match download_manager::download_image(&image_url, &save_path).await {
-            Ok(()) => {
-                info!(target: "commands", "成功重新下载壁纸: {}", save_path.display());
+        // 按分辨率阶梯下载（UHD 不可用时自动降级）
+        let ladder = download_manager::landscape_ladder_for(&app).await;
+        let jpeg_quality = download_manager::jpeg_quality_for(&app).await;
+        match download_manager::download_landscape_wallpaper(
+            &wallpaper.urlbase,
+            &wallpaper.end_date,
+            &wallpaper_dir,
+            ladder,
+            jpeg_quality,
+        )
+        .await
+        {
+            Ok(resolution) => {
+                info!(
+                    target: "commands",
+                    "成功重新下载壁纸: {} ({})",
+                    wallpaper.end_date,
+                    resolution
+                );
                 // 发送事件通知前端
-                let _ = app.emit("image-downloaded", &wallpaper.end_date);
+                let _ = events::IMAGE_DOWNLOADED.emit(&app, &wallpaper.end_date);
             }
             Err(e) => {
                 error!(target: "commands", "重新下载壁纸失败 {}: {}", wallpaper.end_date, e);
@@ -63,7 +96,7 @@ pub(crate) async fn run_update_cycle(app: &AppHandle) {
 pub(crate) async fn try_trigger_update_if_empty(app: &AppHandle, mkt: &str) -> bool {
     let state = app.state::<AppState>();
 
-    // 先快速检查索引（不需要持有 update_in_progress 锁）
+    // 先快速检查索引（不需要持有 update_state 锁）
     let wallpaper_dir = {
         let dir = state.wallpaper_directory.lock().await;
         dir.clone()
@@ -81,10 +114,7 @@ pub(crate) async fn try_trigger_update_if_empty(app: &AppHandle, mkt: &str) -> b
     // 索引为空，检查是否已有更新在进行
     // 注意：这里只检查，不设置标志，让 run_update_cycle_internal 来处理
     // 这样可以避免与 run_update_cycle_internal 内部的并发保护冲突
-    let is_updating = {
-        let flag = state.update_in_progress.lock().await;
-        *flag
-    };
+    let is_updating = state.update_state.lock().await.is_busy();
 
     if is_updating {
         info!(
@@ -150,16 +180,38 @@ pub(crate) async fn check_and_trigger_update_if_needed(app: &AppHandle) -> bool
 /// 只有在 auto_update 设置开启时才会自动应用
 async fn apply_latest_wallpaper_if_needed(app: &AppHandle, state: &AppState, wallpaper_dir: &Path) {
     // 一次性获取 auto_update，然后读 effective_mkt（减少锁间设置变化的窗口）
-    let should_apply = state.settings.lock().await.auto_update;
+    let (should_apply, solar_day_night, placeholder_wallpaper) = {
+        let settings = state.settings.read().await;
+        (
+            settings.auto_update,
+            settings.solar_schedule == "day_night",
+            settings.placeholder_wallpaper,
+        )
+    };
     if !should_apply {
         return;
     }
+    // 按日出日落切换固定壁纸时，由 solar_schedule 负责应用
+    if solar_day_night {
+        return;
+    }
+    // 自定义文件夹按排期接管今天的壁纸时，不再应用 Bing 壁纸
+    if local_folder::apply_scheduled_image(app, state).await {
+        return;
+    }
+    if spotlight::apply_scheduled_image(app, state).await {
+        return;
+    }
     let mkt = get_effective_mkt(state).await;
 
     let latest_wallpapers = storage::get_local_wallpapers(wallpaper_dir, &mkt)
         .await
         .unwrap_or_default();
     if let Some(first) = latest_wallpapers.first() {
+        // 只有离线占位壁纸时，按设置决定是否设为桌面壁纸
+        if placeholder::is_placeholder(&first.end_date) && !placeholder_wallpaper {
+            return;
+        }
         // 检查用户是否手动设置过壁纸，且当前最新壁纸和手动设置时的最新壁纸相同
         let runtime_state = runtime_state::load_runtime_state(app).unwrap_or_default();
         if runtime_state
@@ -180,17 +232,9 @@ async fn apply_latest_wallpaper_if_needed(app: &AppHandle, state: &AppState, wal
 
         // 检测屏幕方向，获取竖屏壁纸路径
         let screen_orientations = wallpaper_manager::get_screen_orientations();
-        let has_portrait_screen = screen_orientations.iter().any(|s| s.is_portrait);
-        let portrait_path = if has_portrait_screen {
-            let portrait_file = wallpaper_dir.join(format!("{}r.jpg", first.end_date));
-            if portrait_file.exists() {
-                Some(portrait_file)
-            } else {
-                None
-            }
-        } else {
-            None
-        };
+        let has_portrait_screen = wallpaper_manager::portrait_variant_wanted(&screen_orientations);
+        let mut portrait_path =
+            has_portrait_screen.then(|| wallpaper_dir.join(format!("{}r.jpg", first.end_date)));
 
         // 检查当前壁纸是否已经是目标壁纸
         let current_path_guard = state.current_wallpaper_path.lock().await;
@@ -201,6 +245,20 @@ async fn apply_latest_wallpaper_if_needed(app: &AppHandle, state: &AppState, wal
         drop(current_path_guard);
 
         if needs_set {
+            // 电池供电或磁盘空间不足时推迟下载，接通电源后或下一次更新循环时重新应用
+            let files_missing = !path.exists()
+                || portrait_path
+                    .as_ref()
+                    .is_some_and(|portrait| !portrait.exists());
+            if files_missing && downloads_paused(app).await {
+                info!(target: "update", "后台下载已暂停（电池供电或磁盘空间不足），推迟下载最新壁纸");
+                return;
+            }
+
+            if files_missing {
+                set_update_phase(app, UpdatePhase::Downloading).await;
+            }
+
             // 如果文件不存在，尝试按需下载
             if !path.exists() {
                 info!(
@@ -233,20 +291,39 @@ async fn apply_latest_wallpaper_if_needed(app: &AppHandle, state: &AppState, wal
                 .await
                 {
                     warn!(target: "update", "按需下载竖屏壁纸失败: {e}，将仅设置横屏壁纸");
+                    portrait_path = None;
                 }
             }
 
-            if let Err(e) = wallpaper_manager::set_wallpaper(&path, portrait_path.as_deref()) {
+            set_update_phase(app, UpdatePhase::Applying).await;
+            // 与用户手动设置串行执行，避免并发调用系统 API
+            let _apply_guard = state.wallpaper_apply_queue.lock().await;
+            let landscape_path = smart_crop::prepare_for_display(&path).await;
+            if let Err(e) =
+                wallpaper_transition::set_wallpaper(app, &landscape_path, portrait_path.as_deref())
+                    .await
+            {
                 error!(target: "update", "设置壁纸失败: {e}");
             } else {
                 let mut current_path = state.current_wallpaper_path.lock().await;
                 *current_path = Some(path.clone());
                 drop(current_path);
 
-                let _ = app.emit(
-                    "current-wallpaper-changed",
-                    path.to_string_lossy().to_string(),
-                );
+                let _ = events::CURRENT_WALLPAPER_CHANGED.emit(app, &path.to_string_lossy());
+                wallpaper_theme::on_wallpaper_applied(app, &path);
+                extension_events::publish_wallpaper_changed(
+                    app,
+                    &path,
+                    &mkt,
+                    Some(&first.end_date),
+                )
+                .await;
+
+                if let Err(e) = runtime_state::record_applied_wallpaper(app, &mkt, &first.end_date)
+                {
+                    warn!(target: "update", "保存当前壁纸记录失败: {e}");
+                }
+                wallpaper_osd::show(app, first);
             }
         }
     }
@@ -254,25 +331,138 @@ async fn apply_latest_wallpaper_if_needed(app: &AppHandle, state: &AppState, wal
     let _ = app;
 }
 
+/// 重试之前下载失败的竖屏壁纸（成功或文件已存在时从待重试队列移除）
+async fn retry_pending_portraits(app: &AppHandle, wallpaper_dir: &Path) {
+    let pending = runtime_state::load_runtime_state(app)
+        .map(|state| state.pending_portrait_downloads)
+        .unwrap_or_default();
+    if pending.is_empty()
+        || !wallpaper_manager::portrait_variant_wanted(&wallpaper_manager::get_screen_orientations())
+        || downloads_paused(app).await
+    {
+        return;
+    }
+
+    info!(target: "update", "重试 {} 张下载失败的竖屏壁纸", pending.len());
+    for end_date in pending {
+        let portrait_path = wallpaper_dir.join(format!("{end_date}r.jpg"));
+        match download_manager::download_wallpaper_if_needed(&portrait_path, wallpaper_dir, app)
+            .await
+        {
+            Ok(()) => {
+                if let Err(e) = runtime_state::set_portrait_pending(app, &end_date, false) {
+                    warn!(target: "update", "清除待重试竖屏下载失败 {}: {}", end_date, e);
+                }
+            }
+            Err(e) if e == download_manager::METADATA_NOT_FOUND => {
+                info!(target: "update", "竖屏壁纸 {} 已无元数据，放弃重试", end_date);
+                if let Err(e) = runtime_state::set_portrait_pending(app, &end_date, false) {
+                    warn!(target: "update", "清除待重试竖屏下载失败 {}: {}", end_date, e);
+                }
+            }
+            Err(e) => warn!(target: "update", "竖屏壁纸重试失败 {}: {}", end_date, e),
+        }
+    }
+}
+
+/// 首次启动无法获取壁纸时生成离线占位壁纸，并按设置应用
+async fn seed_placeholders(
+    app: &AppHandle,
+    state: &AppState,
+    dir: &Path,
+    mkt: &str,
+    resolved_language: &str,
+) {
+    match placeholder::seed(dir, mkt, resolved_language).await {
+        Ok(true) => {
+            if let Err(e) = events::WALLPAPER_UPDATED.emit(app, &()) {
+                warn!(target: "update", "通知前端失败: {e}");
+            }
+        }
+        Ok(false) => {}
+        Err(e) => {
+            warn!(target: "update", "生成离线占位壁纸失败: {e}");
+            return;
+        }
+    }
+    apply_latest_wallpaper_if_needed(app, state, dir).await;
+}
+
 /// 带重试的 Bing 图片获取
-async fn fetch_bing_images_with_retry(mkt: &str) -> Option<bing_api::BingFetchResult> {
+///
+/// 重试次数由该 mkt 的健康指标决定：健康市场最多请求 3 次，持续失败（降级）的市场每轮只请求一次。
+/// 每次请求的结果和耗时都会计入健康指标，降级状态变化时通过 `mkt-status-changed` 通知前端。
+async fn fetch_bing_images_with_retry(
+    app: &AppHandle,
+    mkt: &str,
+) -> Option<bing_api::BingFetchResult> {
+    let state = app.state::<AppState>();
+    let mut health = runtime_state::market_health(app, mkt);
+    let was_degraded = health.is_degraded();
+
+    let result_opt = fetch_with_retry(
+        state.image_source.as_ref(),
+        mkt,
+        &mut health,
+        state.clock.as_ref(),
+    )
+    .await;
+
+    let is_degraded = health.is_degraded();
+    if is_degraded != was_degraded {
+        if is_degraded {
+            warn!(
+                target: "update",
+                "mkt {} 连续 {} 次请求失败（累计成功率 {:.0}%），标记为降级，后续每轮只请求一次",
+                mkt,
+                health.consecutive_failures,
+                health.success_rate().unwrap_or(0.0) * 100.0
+            );
+        } else {
+            info!(target: "update", "mkt {} 请求恢复正常，取消降级", mkt);
+        }
+        let status = MarketStatus::new(mkt.to_string(), get_effective_mkt(&state).await)
+            .with_degraded(is_degraded);
+        if let Err(e) = events::MKT_STATUS_CHANGED.emit(app, &status) {
+            warn!(target: "update", "发送 mkt-status-changed 事件失败: {}", e);
+        }
+    }
+    if let Err(e) = runtime_state::save_market_health(app, mkt, health) {
+        warn!(target: "update", "保存 mkt 健康指标失败: {}", e);
+    }
+
+    result_opt
+}
+
+/// 按 `health` 的重试预算请求图片列表，失败时指数退避，每次结果都计入 `health`
+async fn fetch_with_retry(
+    source: &dyn ImageSource,
+    mkt: &str,
+    health: &mut MarketHealth,
+    clock: &dyn Clock,
+) -> Option<bing_api::BingFetchResult> {
     let mut result_opt = None;
-    const MAX_RETRIES: u32 = 3;
     const MAX_BACKOFF_SECS: u64 = 16; // 最大延迟 16 秒
 
-    info!(target: "update", "开始获取 Bing 图片（市场代码: {}, 最大重试次数: {}）", mkt, MAX_RETRIES);
+    let max_retries = health.retry_budget();
+
+    info!(target: "update", "开始获取 Bing 图片（市场代码: {}, 最大重试次数: {}{}）", mkt, max_retries, if health.is_degraded() { "，市场已降级" } else { "" });
 
-    for attempt in 0..MAX_RETRIES {
-        info!(target: "update", "Bing API 请求第 {} 次尝试（共 {} 次）", attempt + 1, MAX_RETRIES);
+    for attempt in 0..max_retries {
+        info!(target: "update", "Bing API 请求第 {} 次尝试（共 {} 次）", attempt + 1, max_retries);
 
-        match bing_api::fetch_bing_images(8, 0, mkt).await {
+        let started = std::time::Instant::now();
+        match source.fetch(8, 0, mkt).await {
             Ok(v) => {
-                info!(target: "update", "Bing API 请求成功（第 {} 次尝试）: 获取到 {} 张图片, actual_mkt={:?}", attempt + 1, v.images.len(), v.actual_mkt);
+                let latency_ms = started.elapsed().as_millis() as u64;
+                health.record_success(latency_ms, clock.now().to_rfc3339());
+                info!(target: "update", "Bing API 请求成功（第 {} 次尝试，耗时 {}ms）: 获取到 {} 张图片, actual_mkt={:?}", attempt + 1, latency_ms, v.images.len(), v.actual_mkt);
                 result_opt = Some(v);
                 break;
             }
             Err(e) => {
-                if attempt < MAX_RETRIES - 1 {
+                health.record_failure(clock.now().to_rfc3339());
+                if attempt < max_retries - 1 {
                     // 优化：限制最大延迟时间，避免等待时间过长
                     let base_backoff = 1 << attempt; // 指数退避：1, 2, 4
                     let backoff = base_backoff.min(MAX_BACKOFF_SECS); // 限制最大 16 秒
@@ -306,6 +496,17 @@ async fn fetch_bing_images_with_retry(mkt: &str) -> Option<bing_api::BingFetchRe
     result_opt
 }
 
+/// 补齐因电池供电而推迟的下载：按需下载并应用最新壁纸
+pub(crate) async fn resume_deferred_downloads(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    if state.update_state.lock().await.is_busy() {
+        // 更新循环结束时会自行应用最新壁纸
+        return;
+    }
+    let wallpaper_dir = state.wallpaper_directory.lock().await.clone();
+    apply_latest_wallpaper_if_needed(app, &state, &wallpaper_dir).await;
+}
+
 /// 下载新壁纸图片并发送原生系统通知。
 ///
 /// 图片下载失败时仍会发送文本通知，通知失败不影响更新循环。
@@ -319,12 +520,21 @@ async fn notify_new_wallpaper(
     let wallpaper_path = storage::get_wallpaper_path(wallpaper_dir, &wallpaper.end_date);
     let mut image_path = wallpaper_path.exists().then_some(wallpaper_path.clone());
 
-    if image_path.is_none() && !wallpaper.urlbase.is_empty() {
-        let image_url = bing_api::get_wallpaper_url(&wallpaper.urlbase, "UHD");
-        match download_manager::download_image(&image_url, &wallpaper_path).await {
-            Ok(()) => {
+    if image_path.is_none() && !wallpaper.urlbase.is_empty() && !downloads_paused(app).await {
+        let ladder = download_manager::landscape_ladder_for(app).await;
+        let jpeg_quality = download_manager::jpeg_quality_for(app).await;
+        match download_manager::download_landscape_wallpaper(
+            &wallpaper.urlbase,
+            &wallpaper.end_date,
+            wallpaper_dir,
+            ladder,
+            jpeg_quality,
+        )
+        .await
+        {
+            Ok(_) => {
                 image_path = Some(wallpaper_path);
-                let _ = app.emit("image-downloaded", &wallpaper.end_date);
+                let _ = events::IMAGE_DOWNLOADED.emit(app, &wallpaper.end_date);
             }
             Err(e) => {
                 warn!(
@@ -348,28 +558,120 @@ async fn notify_new_wallpaper(
 
 /// 内部更新循环实现
 /// @param force_update: 是否强制更新（忽略智能检查）
+/// 非强制更新时，请求 Bing API 之前的检查结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CycleGate {
+    /// 距上次检查不足 5 分钟且未跨天，直接使用本地壁纸
+    UseCache,
+    /// 今天已成功更新且本地已有今日壁纸
+    UpToDate,
+    /// 需要请求 API
+    Fetch,
+}
+
+async fn check_cycle_gate(
+    runtime_state: &AppRuntimeState,
+    dir: &Path,
+    read_mkt: &str,
+    clock: &dyn Clock,
+) -> CycleGate {
+    if runtime_state::can_skip_api_request(runtime_state, dir, read_mkt, clock).await {
+        return CycleGate::UseCache;
+    }
+    if !runtime_state::should_update_today(runtime_state, clock) {
+        if runtime_state::has_today_wallpaper(dir, read_mkt, clock).await {
+            return CycleGate::UpToDate;
+        }
+        info!(target: "update", "今天已更新但本地没有今日壁纸，继续更新");
+    }
+    CycleGate::Fetch
+}
+
+/// 一次成功请求后的 mkt 状态
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MktResolution {
+    /// 保存元数据使用的 mkt（Bing 实际返回的 mkt，无法检测时为请求的 mkt）
+    save_mkt: String,
+    /// 新的 last_actual_mkt：与请求的 mkt 一致时为 `None`
+    last_actual_mkt: Option<String>,
+    /// mismatch 状态是否发生变化（需要通知前端）
+    mismatch_changed: bool,
+}
+
+fn resolve_mkt(
+    request_mkt: &str,
+    actual_mkt: Option<&str>,
+    previous_actual: Option<&str>,
+) -> MktResolution {
+    let save_mkt = actual_mkt.unwrap_or(request_mkt).to_string();
+    let old_mismatch = previous_actual.is_some_and(|previous| previous != request_mkt);
+    let new_mismatch = save_mkt != request_mkt;
+    MktResolution {
+        last_actual_mkt: new_mismatch.then(|| save_mkt.clone()),
+        save_mkt,
+        mismatch_changed: old_mismatch != new_mismatch,
+    }
+}
+
+/// 修改更新循环状态，成功时通知前端
+///
+/// `change` 接收当前状态和 RFC 3339 格式的当前时间，返回 `false` 表示切换不合法（状态不变）。
+pub(crate) async fn transition_update_state(
+    app: &AppHandle,
+    change: impl FnOnce(&mut UpdateState, String) -> bool,
+) -> bool {
+    let state = app.state::<AppState>();
+    let at = utils::format_rfc3339_timestamp(&state.clock.now());
+    let snapshot = {
+        let mut update_state = state.update_state.lock().await;
+        let from = update_state.phase;
+        if !change(&mut update_state, at) {
+            debug!(target: "update", "忽略不合法的更新阶段切换（当前阶段: {:?}）", from);
+            return false;
+        }
+        if update_state.phase == from {
+            return true;
+        }
+        update_state.clone()
+    };
+    debug!(target: "update", "更新阶段: {:?}", snapshot.phase);
+    if let Err(e) = events::UPDATE_STATE_CHANGED.emit(app, &snapshot) {
+        warn!(target: "update", "发送 update-state-changed 事件失败: {e}");
+    }
+    true
+}
+
+/// 切换到更新循环的下一阶段（没有进行中的更新时忽略，如按需应用壁纸）
+async fn set_update_phase(app: &AppHandle, phase: UpdatePhase) {
+    transition_update_state(app, |update, at| update.advance(phase, at)).await;
+}
+
 pub(crate) async fn run_update_cycle_internal(app: &AppHandle, force_update: bool) {
     let state = app.state::<AppState>();
 
-    // 并发保护：若已有更新在进行，直接跳过
-    {
-        let mut flag = state.update_in_progress.lock().await;
-        if *flag {
-            return;
-        }
-        *flag = true;
+    // 并发保护：若已有更新或重置在进行，直接跳过
+    if !transition_update_state(app, |update, at| update.begin(force_update, at)).await {
+        return;
     }
 
-    // 核心逻辑在 async block 中：所有 return 只退出此 block，
-    // 确保下方的 update_in_progress 重置一定会执行。
-    let _: () = async {
+    let cancel_token = CancellationToken::new();
+    *state.update_cancel_token.lock().await = Some(cancel_token.clone());
+    // 托盘"更新壁纸"切换为"取消更新"
+    if let Err(e) = tray::update_tray_menu(app).await {
+        warn!(target: "update", "刷新托盘菜单失败: {e}");
+    }
+
+    // 核心逻辑在 async block 中：所有 return 只退出此 block（返回失败时的错误码），
+    // 确保下方的状态收尾一定会执行。
+    // 取消时直接丢弃该 future；索引写入是原子替换，中断的下载只会留下临时文件。
+    let cycle = async {
         let dir = {
             let d = state.wallpaper_directory.lock().await;
             d.clone()
         };
 
         let (request_mkt, new_wallpaper_notification, resolved_language) = {
-            let settings = state.settings.lock().await;
+            let settings = state.settings.read().await;
             (
                 settings.mkt.clone(),
                 settings.new_wallpaper_notification,
@@ -378,138 +680,166 @@ pub(crate) async fn run_update_cycle_internal(app: &AppHandle, force_update: boo
         };
         let read_mkt = get_effective_mkt(&state).await;
 
-        let existing_wallpapers = storage::get_local_wallpapers(&dir, &read_mkt)
-            .await
-            .unwrap_or_default();
+        // 离线占位壁纸不算作已有壁纸（首次启动判断和新壁纸通知的基线）
+        let existing_wallpapers: Vec<LocalWallpaper> =
+            storage::get_local_wallpapers(&dir, &read_mkt)
+                .await
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|wallpaper| !placeholder::is_placeholder(&wallpaper.end_date))
+                .collect();
 
+        // 上一次请求 API 的时间，用于推算新壁纸的发布时刻（强制更新时不记录）
+        let mut previous_check = None;
         if !force_update {
             let runtime_state = runtime_state::load_runtime_state(app).unwrap_or_default();
 
-            if runtime_state::can_skip_api_request(&runtime_state, &dir, &read_mkt).await {
-                info!(target: "update", "使用缓存策略跳过 API 请求，直接使用本地壁纸");
-                apply_latest_wallpaper_if_needed(app, &state, &dir).await;
-                return;
-            }
-
-            if !runtime_state::should_update_today(&runtime_state) {
-                if runtime_state::has_today_wallpaper(&dir, &read_mkt).await {
+            match check_cycle_gate(&runtime_state, &dir, &read_mkt, state.clock.as_ref()).await {
+                CycleGate::UseCache => {
+                    info!(target: "update", "使用缓存策略跳过 API 请求，直接使用本地壁纸");
+                    retry_pending_portraits(app, &dir).await;
+                    apply_latest_wallpaper_if_needed(app, &state, &dir).await;
+                    return Ok(());
+                }
+                CycleGate::UpToDate => {
                     info!(target: "update", "跳过更新：今天已更新且本地有今日壁纸");
+                    retry_pending_portraits(app, &dir).await;
                     apply_latest_wallpaper_if_needed(app, &state, &dir).await;
-                    return;
+                    return Ok(());
                 }
-                info!(target: "update", "今天已更新但本地没有今日壁纸，继续更新");
+                CycleGate::Fetch => {}
             }
 
             let mut runtime_state = runtime_state::load_runtime_state(app).unwrap_or_default();
-            let _ = runtime_state::update_last_check_time(app, &mut runtime_state);
+            previous_check = runtime_state
+                .last_check_time
+                .as_deref()
+                .and_then(|time| chrono::DateTime::parse_from_rfc3339(time).ok())
+                .map(|time| time.with_timezone(&chrono::Local));
+            let _ = runtime_state::update_last_check_time(
+                app,
+                &mut runtime_state,
+                state.clock.as_ref(),
+            );
         } else {
             info!(target: "update", "强制更新模式，跳过智能检查");
         }
 
-        if let Err(e) = storage::ensure_wallpaper_directory(&dir).await {
+        // 只读存档只浏览：不请求 API、不下载也不清理，仍按需应用本地最新壁纸
+        if directory_permission::archive_read_only(app).await {
+            info!(target: "update", "壁纸目录为只读存档，跳过下载");
+            apply_latest_wallpaper_if_needed(app, &state, &dir).await;
+            return Ok(());
+        }
+
+        // 下载前确认目录可写：无权限时提示用户回退到默认目录，而不是每次循环都下载失败
+        if let Err(e) = directory_permission::ensure_writable(&dir).await {
+            if directory_permission::is_permission_error(&e) {
+                directory_permission::report(app, &dir, &e);
+                return Err("DIRECTORY_NOT_WRITABLE");
+            }
             error!(target: "update", "创建目录失败: {e}");
-            return;
+            return Err("DIRECTORY_UNAVAILABLE");
         }
 
-        let fetch_result = match fetch_bing_images_with_retry(&request_mkt).await {
+        set_update_phase(app, UpdatePhase::Fetching).await;
+        let fetch_result = match fetch_bing_images_with_retry(app, &request_mkt).await {
             Some(v) => v,
             None => {
                 error!(target: "update", "多次重试仍失败，跳过本次循环");
-                return;
+                if existing_wallpapers.is_empty() {
+                    seed_placeholders(app, &state, &dir, &read_mkt, &resolved_language).await;
+                }
+                return Err("FETCH_FAILED");
             }
         };
 
         let images = fetch_result.images;
-        let save_mkt = fetch_result
-            .actual_mkt
-            .as_deref()
-            .unwrap_or(&request_mkt)
-            .to_string();
 
         // 更新 last_actual_mkt（内存 + 持久化），确保后续读取路径与写入一致
         // 使用边沿触发：仅在 mismatch 状态发生变化时（false→true / true→false）才发事件
-        {
-            let old_effective = {
-                let guard = state.last_actual_mkt.lock().await;
-                guard.clone().unwrap_or_else(|| request_mkt.clone())
-            };
-            let old_mismatch = old_effective != request_mkt;
-            let new_mismatch = save_mkt != request_mkt;
-
-            let new_actual_mkt = if new_mismatch {
+        let save_mkt = {
+            let previous_actual = state.last_actual_mkt.lock().await.clone();
+            let resolution = resolve_mkt(
+                &request_mkt,
+                fetch_result.actual_mkt.as_deref(),
+                previous_actual.as_deref(),
+            );
+
+            if resolution.last_actual_mkt.is_some() {
                 info!(
                     target: "update",
                     "mkt 不一致：请求={}, 实际={}, 将使用实际 mkt 保存元数据",
-                    request_mkt, save_mkt
+                    request_mkt, resolution.save_mkt
                 );
-                Some(save_mkt.clone())
-            } else {
-                None
-            };
+            }
 
-            *state.last_actual_mkt.lock().await = new_actual_mkt.clone();
+            *state.last_actual_mkt.lock().await = resolution.last_actual_mkt.clone();
 
-            if let Ok(mut runtime_state) = runtime_state::load_runtime_state(app) {
-                runtime_state.last_actual_mkt = new_actual_mkt;
-                if let Err(e) = runtime_state::save_runtime_state(app, &runtime_state) {
-                    warn!(target: "update", "持久化 last_actual_mkt 失败: {}", e);
-                }
+            if let Err(e) = runtime_state::modify_runtime_state(app, |runtime_state| {
+                runtime_state.last_actual_mkt = resolution.last_actual_mkt.clone();
+                true
+            }) {
+                warn!(target: "update", "持久化 last_actual_mkt 失败: {}", e);
             }
 
-            if new_mismatch != old_mismatch {
-                let status = MarketStatus {
-                    requested_mkt: request_mkt.clone(),
-                    effective_mkt: save_mkt.clone(),
-                    is_mismatch: new_mismatch,
-                };
-                if let Err(e) = app.emit("mkt-status-changed", &status) {
+            if resolution.mismatch_changed {
+                // 能走到这里说明本轮请求已成功，市场不处于降级状态
+                let status = MarketStatus::new(request_mkt.clone(), resolution.save_mkt.clone());
+                if let Err(e) = events::MKT_STATUS_CHANGED.emit(app, &status) {
                     warn!(target: "update", "发送 mkt-status-changed 事件失败: {}", e);
                 }
                 info!(
                     target: "update",
                     "mkt 状态变化：mismatch {} → {}",
-                    old_mismatch, new_mismatch
+                    !status.is_mismatch, status.is_mismatch
                 );
             }
-        }
+            resolution.save_mkt
+        };
 
         let metadata_list: Vec<LocalWallpaper> = images
             .iter()
             .map(|image| LocalWallpaper::from(image.clone()))
             .collect();
 
-        let notification_wallpaper = if new_wallpaper_notification {
-            let existing_for_save_mkt = if read_mkt == save_mkt {
-                existing_wallpapers.clone()
-            } else {
-                match storage::get_local_wallpapers(&dir, &save_mkt).await {
-                    Ok(wallpapers) => wallpapers,
-                    Err(e) => {
-                        warn!(
-                            target: "notification",
-                            "读取通知基线失败，跳过本次新壁纸通知: {}",
-                            e
-                        );
-                        Vec::new()
-                    }
+        let existing_for_save_mkt = if read_mkt == save_mkt {
+            existing_wallpapers.clone()
+        } else {
+            match storage::get_local_wallpapers(&dir, &save_mkt).await {
+                Ok(wallpapers) => wallpapers,
+                Err(e) => {
+                    warn!(
+                        target: "update",
+                        "读取已有壁纸失败，跳过本次新壁纸通知和发布时间记录: {}",
+                        e
+                    );
+                    Vec::new()
                 }
-            };
-
+            }
+        };
+        let notification_wallpaper = if new_wallpaper_notification {
             notification::find_new_latest_wallpaper(&metadata_list, &existing_for_save_mkt).cloned()
         } else {
             None
         };
+        // 本次请求是否首次看到新日期的壁纸（据此学习该 mkt 的发布时间）
+        let newly_published = match (metadata_list.first(), existing_for_save_mkt.first()) {
+            (Some(latest), Some(previous)) => latest.end_date > previous.end_date,
+            _ => false,
+        };
 
         let is_first_launch = existing_wallpapers.is_empty();
 
         let screen_orientations = wallpaper_manager::get_screen_orientations();
-        let has_portrait_screen = screen_orientations.iter().any(|s| s.is_portrait);
+        let has_portrait_screen = wallpaper_manager::portrait_variant_wanted(&screen_orientations);
         let latest_wallpaper_for_portrait = if has_portrait_screen && !metadata_list.is_empty() {
             Some(metadata_list[0].clone())
         } else {
             None
         };
 
+        set_update_phase(app, UpdatePhase::SavingMetadata).await;
         if !metadata_list.is_empty() {
             let count = metadata_list.len();
             match storage::save_wallpapers_metadata(metadata_list, &dir, &save_mkt).await {
@@ -521,6 +851,18 @@ pub(crate) async fn run_update_cycle_internal(app: &AppHandle, force_update: boo
                     }
                 }
                 Ok(result) => {
+                    placeholder::remove(&dir).await;
+                    if newly_published
+                        && let Some(previous_check) = previous_check
+                        && let Err(e) = publish_schedule::record(
+                            app,
+                            &save_mkt,
+                            previous_check,
+                            state.clock.now(),
+                        )
+                    {
+                        warn!(target: "update", "记录壁纸发布时间失败: {e}");
+                    }
                     info!(
                         target: "update",
                         "已{}壁纸元数据（{} 条，新增 {} 条）",
@@ -529,7 +871,7 @@ pub(crate) async fn run_update_cycle_internal(app: &AppHandle, force_update: boo
                         result.new_count
                     );
                     if is_first_launch {
-                        if let Err(e) = app.emit("wallpaper-updated", ()) {
+                        if let Err(e) = events::WALLPAPER_UPDATED.emit(app, &()) {
                             warn!(target: "update", "通知前端失败: {e}");
                         }
                         info!(target: "update", "元信息已保存并通知前端，图片将按需下载");
@@ -545,42 +887,33 @@ pub(crate) async fn run_update_cycle_internal(app: &AppHandle, force_update: boo
             }
         }
 
+        // 先同步重试之前失败的竖屏下载，避免与下面后台准备的同一文件并发写入
+        retry_pending_portraits(app, &dir).await;
+
         if let Some(ref latest_wallpaper) = latest_wallpaper_for_portrait
             && !latest_wallpaper.urlbase.is_empty()
         {
             let portrait_file_path = dir.join(format!("{}r.jpg", latest_wallpaper.end_date));
 
-            if !portrait_file_path.exists() {
-                let portrait_url =
-                    bing_api::get_wallpaper_url(&latest_wallpaper.urlbase, "1080x1920");
-                let end_date = latest_wallpaper.end_date.clone();
+            if !portrait_file_path.exists() && !downloads_paused(app).await {
                 info!(
                     target: "update",
-                    "检测到竖屏显示器，开始下载竖屏壁纸: {}",
+                    "检测到竖屏显示器，开始准备竖屏壁纸: {}",
                     portrait_file_path.display()
                 );
 
+                // 元数据已保存，按需下载会读取索引中缓存的竖屏探测结果
                 let app_clone = app.clone();
-                let portrait_path_clone = portrait_file_path.clone();
+                let dir_clone = dir.clone();
                 tauri::async_runtime::spawn(async move {
-                    match download_manager::download_image(&portrait_url, &portrait_path_clone)
-                        .await
+                    if let Err(e) = download_manager::download_wallpaper_if_needed(
+                        &portrait_file_path,
+                        &dir_clone,
+                        &app_clone,
+                    )
+                    .await
                     {
-                        Ok(()) => {
-                            info!(
-                                target: "update",
-                                "竖屏壁纸下载成功: {}",
-                                portrait_path_clone.display()
-                            );
-                            let _ = app_clone.emit("image-downloaded", end_date);
-                        }
-                        Err(e) => {
-                            error!(
-                                target: "update",
-                                "竖屏壁纸下载失败: {}",
-                                e
-                            );
-                        }
+                        error!(target: "update", "竖屏壁纸准备失败: {}", e);
                     }
                 });
             }
@@ -591,33 +924,83 @@ pub(crate) async fn run_update_cycle_internal(app: &AppHandle, force_update: boo
         info!(target: "update", "完成一次更新循环");
         {
             let mut last = state.last_update_time.lock().await;
-            *last = Some(Local::now());
+            *last = Some(state.clock.now());
         }
 
         {
             let mut runtime_state = runtime_state::load_runtime_state(app).unwrap_or_default();
-            let _ = runtime_state::update_last_successful_time(app, &mut runtime_state);
+            let _ = runtime_state::update_last_successful_time(
+                app,
+                &mut runtime_state,
+                state.clock.as_ref(),
+            );
         }
 
-        if !is_first_launch && let Err(e) = app.emit("wallpaper-updated", ()) {
+        if !is_first_launch && let Err(e) = events::WALLPAPER_UPDATED.emit(app, &()) {
             warn!(target: "update", "通知前端失败: {e}");
         }
-    }
+        mini_window::refresh(app).await;
+
+        // 仍在后台下载的图片会在下一轮备份中补传
+        backup::spawn_backup_if_enabled(app);
+
+        let interval_days = state.settings.read().await.index_backup_interval_days;
+        if let Err(e) =
+            storage::backup_index_if_due(&dir, state.clock.now().date_naive(), interval_days).await
+        {
+            warn!(target: "update", "备份索引文件失败: {e}");
+        }
+        Ok::<(), &'static str>(())
+    };
+
+    let outcome = tokio::select! {
+        result = cycle => Some(result),
+        () = cancel_token.cancelled() => {
+            info!(target: "update", "更新循环已被用户取消");
+            let _ = events::UPDATE_CANCELLED.emit(app, &());
+            None
+        }
+    };
+
+    // 统一结束本轮状态，无论上方逻辑如何退出
+    *state.update_cancel_token.lock().await = None;
+    transition_update_state(app, |update, at| match outcome {
+        Some(Ok(())) => update.advance(UpdatePhase::Done, at),
+        Some(Err(error)) => update.fail(error, at),
+        None => update.advance(UpdatePhase::Cancelled, at),
+    })
     .await;
+    // 刷新托盘"最近壁纸"子菜单
+    if let Err(e) = tray::update_tray_menu(app).await {
+        warn!(target: "update", "刷新托盘菜单失败: {e}");
+    }
+}
 
-    // 统一重置 update_in_progress，无论上方逻辑如何退出
-    {
-        let mut flag = state.update_in_progress.lock().await;
-        *flag = false;
+/// 取消正在进行的更新循环
+///
+/// # Returns
+/// 存在进行中的更新并已发出取消信号时返回 `true`
+pub(crate) async fn cancel_update_cycle(app: &AppHandle) -> bool {
+    let state = app.state::<AppState>();
+    let token = state.update_cancel_token.lock().await.clone();
+    match token {
+        Some(token) => {
+            token.cancel();
+            true
+        }
+        None => false,
     }
 }
 
-/// 手动强制执行一次更新
+/// 手动强制执行一次更新（执行中或调用过于频繁时返回 "BUSY"）
 #[tauri::command]
 pub(crate) async fn force_update(app: tauri::AppHandle) -> Result<(), String> {
-    // 调用强制更新版本，跳过智能检查
-    run_update_cycle_internal(&app, true).await;
-    Ok(())
+    command_guard::guarded(command_guard::FORCE_UPDATE, async {
+        // 调用强制更新版本，跳过智能检查
+        run_update_cycle_internal(&app, true).await;
+        Ok(())
+    })
+    .await
 }
 
 /// 使用当前市场的最新壁纸发送一条预览通知。
@@ -625,7 +1008,7 @@ pub(crate) async fn force_update(app: tauri::AppHandle) -> Result<(), String> {
 pub(crate) async fn send_test_wallpaper_notification(app: tauri::AppHandle) -> Result<(), String> {
     let state = app.state::<AppState>();
     let wallpaper_dir = state.wallpaper_directory.lock().await.clone();
-    let resolved_language = state.settings.lock().await.resolved_language.clone();
+    let resolved_language = state.settings.read().await.resolved_language;
     let mkt = get_effective_mkt(&state).await;
     let wallpaper = storage::get_local_wallpapers(&wallpaper_dir, &mkt)
         .await
@@ -636,3 +1019,141 @@ pub(crate) async fn send_test_wallpaper_notification(app: tauri::AppHandle) -> R
 
     notify_new_wallpaper(&app, &wallpaper_dir, &wallpaper, &resolved_language).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bing_api::{BingFetchResult, MockImageSource};
+    use crate::clock::MockClock;
+
+    fn fetch_result(actual_mkt: Option<&str>) -> BingFetchResult {
+        BingFetchResult {
+            images: vec![],
+            actual_mkt: actual_mkt.map(str::to_string),
+            served_host: None,
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let unique = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        std::env::temp_dir().join(format!("bw_update_cycle_{name}_{unique}"))
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_fetch_with_retry_retries_until_success() {
+        let source = MockImageSource::new(vec![
+            Err("timeout".to_string()),
+            Ok(fetch_result(Some("zh-CN"))),
+        ]);
+        let clock = MockClock::at(2024, 3, 15, 10, 0, 0);
+        let mut health = MarketHealth::default();
+
+        let result = fetch_with_retry(&source, "en-US", &mut health, &clock).await;
+
+        assert_eq!(result.unwrap().actual_mkt.as_deref(), Some("zh-CN"));
+        assert_eq!(source.requests(), vec!["en-US", "en-US"]);
+        assert_eq!((health.successes, health.failures), (1, 1));
+        assert_eq!(health.consecutive_failures, 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_fetch_with_retry_degraded_market_requests_once() {
+        let source = MockImageSource::new(vec![Err("timeout".to_string()), Ok(fetch_result(None))]);
+        let clock = MockClock::at(2024, 3, 15, 10, 0, 0);
+        let mut health = MarketHealth::default();
+        while !health.is_degraded() {
+            health.record_failure(clock.now().to_rfc3339());
+        }
+
+        let result = fetch_with_retry(&source, "ja-JP", &mut health, &clock).await;
+
+        assert!(result.is_none());
+        assert_eq!(source.requests().len(), 1);
+    }
+
+    #[test]
+    fn test_resolve_mkt_tracks_mismatch_edges() {
+        // Bing 返回请求的 mkt：无 mismatch，状态不变
+        let same = resolve_mkt("en-US", Some("en-US"), None);
+        assert_eq!(same.save_mkt, "en-US");
+        assert_eq!(same.last_actual_mkt, None);
+        assert!(!same.mismatch_changed);
+
+        // 无法检测实际 mkt 时按请求的 mkt 保存
+        assert_eq!(resolve_mkt("en-US", None, None).save_mkt, "en-US");
+
+        // 首次被重定向：false → true
+        let redirected = resolve_mkt("en-US", Some("zh-CN"), None);
+        assert_eq!(redirected.save_mkt, "zh-CN");
+        assert_eq!(redirected.last_actual_mkt.as_deref(), Some("zh-CN"));
+        assert!(redirected.mismatch_changed);
+
+        // 持续被重定向：不重复通知
+        assert!(!resolve_mkt("en-US", Some("zh-CN"), Some("zh-CN")).mismatch_changed);
+
+        // 恢复正常：true → false
+        let recovered = resolve_mkt("en-US", Some("en-US"), Some("zh-CN"));
+        assert_eq!(recovered.last_actual_mkt, None);
+        assert!(recovered.mismatch_changed);
+    }
+
+    #[tokio::test]
+    async fn test_check_cycle_gate_branches() {
+        let dir = temp_dir("gate");
+        let clock = MockClock::at(2024, 3, 15, 10, 0, 0);
+
+        // 首次启动：从未更新过
+        let first_launch = AppRuntimeState::default();
+        assert_eq!(
+            check_cycle_gate(&first_launch, &dir, "en-US", &clock).await,
+            CycleGate::Fetch
+        );
+
+        // 今天已更新，但本地还没有今日壁纸
+        let updated_today = AppRuntimeState {
+            last_successful_update: Some(clock.now().to_rfc3339()),
+            ..Default::default()
+        };
+        assert_eq!(
+            check_cycle_gate(&updated_today, &dir, "en-US", &clock).await,
+            CycleGate::Fetch
+        );
+
+        storage::save_wallpapers_metadata(
+            vec![LocalWallpaper {
+                title: "Today".to_string(),
+                copyright: "Test".to_string(),
+                copyright_link: "https://example.com".to_string(),
+                end_date: "20240315".to_string(),
+                urlbase: "/th?id=OHR.Today".to_string(),
+                resolution: None,
+                portrait_available: None,
+                watermark_free: None,
+                recompression: None,
+            }],
+            &dir,
+            "en-US",
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            check_cycle_gate(&updated_today, &dir, "en-US", &clock).await,
+            CycleGate::UpToDate
+        );
+
+        // 刚检查过 API：走缓存
+        let just_checked = AppRuntimeState {
+            last_check_time: Some((clock.now() - chrono::Duration::minutes(1)).to_rfc3339()),
+            ..Default::default()
+        };
+        assert_eq!(
+            check_cycle_gate(&just_checked, &dir, "en-US", &clock).await,
+            CycleGate::UseCache
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -60,6 +60,30 @@ pub fn is_valid_mkt(mkt: &str) -> bool {
     SUPPORTED_MKTS.contains(&mkt)
 }
 
+/// 检查 mkt 是否符合 `ll-CC` 格式（2~3 位小写语言代码 + 2 位大写地区代码）
+///
+/// 用于用户自定义市场：不在 [`SUPPORTED_MKTS`] 中的代码只要格式正确，
+/// 就直接交给 Bing API 尝试，由重定向检测判断其是否真正可用。
+pub fn is_well_formed_mkt(mkt: &str) -> bool {
+    let Some((lang, country)) = mkt.split_once('-') else {
+        return false;
+    };
+    (2..=3).contains(&lang.len())
+        && lang.bytes().all(|b| b.is_ascii_lowercase())
+        && country.len() == 2
+        && country.bytes().all(|b| b.is_ascii_uppercase())
+}
+
+/// 解析壁纸日期 `YYYYMMDD`
+///
+/// end_date 会拼接为文件名，只接受 8 位数字组成的真实日期（如拒绝 `20241399`、`+2024011`）。
+pub fn parse_end_date(end_date: &str) -> Option<chrono::NaiveDate> {
+    if end_date.len() != 8 || !end_date.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    chrono::NaiveDate::parse_from_str(end_date, "%Y%m%d").ok()
+}
+
 /// 市场分组（用于前端下拉列表渲染）
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct MarketGroup {
@@ -309,6 +333,29 @@ pub fn detect_actual_mkt(copyrightlink: &str) -> Option<String> {
     }
 }
 
+/// 从 Bing API 响应的 urlbase 中检测图片所属的市场代码
+///
+/// urlbase 示例：`/th?id=OHR.GreatWall_ZH-CN1234567890`，
+/// 最后一个下划线之后的 `ZH-CN` 即图片发布的市场。
+/// 当 copyrightlink 缺少 mkt 参数时，可作为重定向检测的补充信号。
+///
+/// # Returns
+/// 标准化后的 mkt（如 "zh-CN"），如果无法解析则返回 None
+pub fn detect_mkt_from_urlbase(urlbase: &str) -> Option<String> {
+    let suffix = urlbase.rsplit_once('_')?.1;
+    let code: String = suffix
+        .chars()
+        .take_while(|c| c.is_ascii_alphabetic() || *c == '-')
+        .collect();
+
+    let (lang, country) = code.split_once('-')?;
+    if lang.len() != 2 || country.len() != 2 {
+        return None;
+    }
+
+    Some(normalize_mkt_case(&code))
+}
+
 /// 判断 API 返回的日期是否超前于本地日期（需要减一天）
 ///
 /// 通过比较 Bing API 返回的第一张图片的 enddate 与本地日期来判断：
@@ -330,7 +377,7 @@ pub fn is_date_ahead_of_local(enddate: &str) -> bool {
 
 /// 解析 mkt 设置，确保返回有效的市场代码
 ///
-/// 验证 mkt 是否在 SUPPORTED_MKTS 中，无效时使用 fallback_language 回退。
+/// 验证 mkt 是否在 SUPPORTED_MKTS 中或是格式正确的自定义市场，无效时使用 fallback_language 回退。
 ///
 /// # Arguments
 /// * `mkt` - 用户设置的市场代码
@@ -339,7 +386,7 @@ pub fn is_date_ahead_of_local(enddate: &str) -> bool {
 /// # Returns
 /// 有效的市场代码
 pub fn resolve_mkt<'a>(mkt: &'a str, fallback_language: &str) -> &'a str {
-    if is_valid_mkt(mkt) {
+    if is_valid_mkt(mkt) || is_well_formed_mkt(mkt) {
         return mkt;
     }
     // mkt 无效时，从 SUPPORTED_MKTS 中查找 fallback_language 对应的 &'static str 返回
@@ -367,6 +414,100 @@ pub fn effective_mkt(last_actual_mkt: Option<&str>, settings_mkt: &str) -> Strin
         .unwrap_or_else(|| settings_mkt.to_string())
 }
 
+// ─── 图片相关 ───
+
+/// 从 JPEG 文件头解析图片尺寸（宽, 高）
+///
+/// 只遍历标记段直到遇到 SOF（Start Of Frame），无需解码整张图片。
+/// 数据不完整或不是 JPEG 时返回 None。
+pub fn jpeg_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+    if data.get(..2)? != [0xFF, 0xD8] {
+        return None;
+    }
+
+    let mut pos = 2;
+    loop {
+        // 跳过段间可能存在的填充字节 0xFF
+        while *data.get(pos)? == 0xFF && *data.get(pos + 1)? == 0xFF {
+            pos += 1;
+        }
+        if *data.get(pos)? != 0xFF {
+            return None;
+        }
+        let marker = *data.get(pos + 1)?;
+        pos += 2;
+
+        // 无长度字段的独立标记（TEM、RSTn）
+        if marker == 0x01 || (0xD0..=0xD7).contains(&marker) {
+            continue;
+        }
+
+        let length = u16::from_be_bytes([*data.get(pos)?, *data.get(pos + 1)?]) as usize;
+        if length < 2 {
+            return None;
+        }
+
+        // SOF0-SOF15，排除 DHT(C4)、JPG(C8)、DAC(CC)
+        if (0xC0..=0xCF).contains(&marker) && !matches!(marker, 0xC4 | 0xC8 | 0xCC) {
+            let segment = data.get(pos + 2..pos + 7)?;
+            let height = u16::from_be_bytes([segment[1], segment[2]]) as u32;
+            let width = u16::from_be_bytes([segment[3], segment[4]]) as u32;
+            return Some((width, height));
+        }
+
+        // 到达图像数据仍未找到 SOF
+        if marker == 0xDA {
+            return None;
+        }
+        pos += length;
+    }
+}
+
+// ─── 时间格式化 ───
+
+/// 命令返回时间戳时使用的格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampFormat {
+    /// 按界面语言格式化的展示文本
+    #[default]
+    Display,
+    /// RFC 3339（带时区偏移，精确到秒），供前端解析或自行本地化
+    Rfc3339,
+}
+
+/// 按界面语言格式化本地时间，用于后端直接生成的展示文本
+///
+/// `resolved_language` 应为已解析的语言（"zh-CN" / "en-US"），未知值按英文处理。
+pub fn format_display_timestamp(
+    time: &chrono::DateTime<chrono::Local>,
+    resolved_language: &str,
+) -> String {
+    let pattern = if resolved_language == "zh-CN" {
+        "%Y-%m-%d %H:%M:%S"
+    } else {
+        "%b %-d, %Y %H:%M:%S"
+    };
+    time.format(pattern).to_string()
+}
+
+/// 格式化为 RFC 3339（本地时区偏移，精确到秒）
+pub fn format_rfc3339_timestamp(time: &chrono::DateTime<chrono::Local>) -> String {
+    time.to_rfc3339_opts(chrono::SecondsFormat::Secs, false)
+}
+
+/// 按指定格式输出时间戳，所有返回时间的命令统一经由此函数
+pub fn format_timestamp(
+    time: &chrono::DateTime<chrono::Local>,
+    format: TimestampFormat,
+    resolved_language: &str,
+) -> String {
+    match format {
+        TimestampFormat::Display => format_display_timestamp(time, resolved_language),
+        TimestampFormat::Rfc3339 => format_rfc3339_timestamp(time),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -424,7 +565,7 @@ mod tests {
     #[test]
     fn test_resolve_mkt_invalid_uses_fallback() {
         assert_eq!(resolve_mkt("", "zh-CN"), "zh-CN");
-        assert_eq!(resolve_mkt("xx-YY", "en-US"), "en-US");
+        assert_eq!(resolve_mkt("xx_YY", "en-US"), "en-US");
         assert_eq!(resolve_mkt("invalid", "ja-JP"), "ja-JP");
     }
 
@@ -434,6 +575,42 @@ mod tests {
         assert_eq!(resolve_mkt("invalid", "also-invalid"), "en-US");
     }
 
+    #[test]
+    fn test_is_well_formed_mkt() {
+        assert!(is_well_formed_mkt("en-SG"));
+        assert!(is_well_formed_mkt("fil-PH"));
+        assert!(is_well_formed_mkt("zh-CN"));
+        assert!(!is_well_formed_mkt("en-sg"));
+        assert!(!is_well_formed_mkt("EN-SG"));
+        assert!(!is_well_formed_mkt("en-SGP"));
+        assert!(!is_well_formed_mkt("e-SG"));
+        assert!(!is_well_formed_mkt("en_SG"));
+        assert!(!is_well_formed_mkt("en-SG-x"));
+        assert!(!is_well_formed_mkt(""));
+    }
+
+    #[test]
+    fn test_parse_end_date() {
+        assert_eq!(
+            parse_end_date("20240229"),
+            chrono::NaiveDate::from_ymd_opt(2024, 2, 29)
+        );
+        assert_eq!(parse_end_date("20230229"), None);
+        assert_eq!(parse_end_date("20241399"), None);
+        assert_eq!(parse_end_date("+2024011"), None);
+        assert_eq!(parse_end_date("2024011"), None);
+        assert_eq!(parse_end_date("../../x"), None);
+        assert_eq!(parse_end_date(""), None);
+    }
+
+    #[test]
+    fn test_resolve_mkt_accepts_custom_market() {
+        // 不在预置列表中的市场只要格式正确就原样保留
+        assert_eq!(resolve_mkt("en-SG", "zh-CN"), "en-SG");
+        assert_eq!(resolve_mkt("xx-YY", "en-US"), "xx-YY");
+        assert_eq!(resolve_mkt("en-sg", "zh-CN"), "zh-CN");
+    }
+
     // ─── detect_actual_mkt 测试 ───
 
     #[test]
@@ -472,6 +649,28 @@ mod tests {
         assert_eq!(detect_actual_mkt(link), None);
     }
 
+    // ─── detect_mkt_from_urlbase 测试 ───
+
+    #[test]
+    fn test_detect_mkt_from_urlbase() {
+        assert_eq!(
+            detect_mkt_from_urlbase("/th?id=OHR.GreatWall_ZH-CN1234567890"),
+            Some("zh-CN".to_string())
+        );
+        assert_eq!(
+            detect_mkt_from_urlbase("/th?id=OHR.Some_Name_EN-US0987654321"),
+            Some("en-US".to_string())
+        );
+    }
+
+    #[test]
+    fn test_detect_mkt_from_urlbase_invalid() {
+        assert_eq!(detect_mkt_from_urlbase(""), None);
+        assert_eq!(detect_mkt_from_urlbase("/th?id=OHR.NoMarket"), None);
+        assert_eq!(detect_mkt_from_urlbase("/th?id=OHR.Test_ROW123"), None);
+        assert_eq!(detect_mkt_from_urlbase("/th?id=OHR.Test_1234567890"), None);
+    }
+
     // ─── is_date_ahead_of_local 测试 ───
 
     #[test]
@@ -656,4 +855,77 @@ mod tests {
     fn test_effective_mkt_falls_back_to_settings() {
         assert_eq!(effective_mkt(None, "ja-JP"), "ja-JP");
     }
+
+    // ─── 图片相关 ───
+
+    /// 构造最小 JPEG 头：SOI + APP0 + SOF0(宽 x 高)
+    fn make_jpeg_header(width: u16, height: u16) -> Vec<u8> {
+        let mut data = vec![0xFF, 0xD8];
+        data.extend_from_slice(&[0xFF, 0xE0, 0x00, 0x04, 0x00, 0x00]);
+        data.extend_from_slice(&[0xFF, 0xC0, 0x00, 0x11, 0x08]);
+        data.extend_from_slice(&height.to_be_bytes());
+        data.extend_from_slice(&width.to_be_bytes());
+        data.extend_from_slice(&[0x03, 0x01, 0x22, 0x00]);
+        data
+    }
+
+    #[test]
+    fn test_jpeg_dimensions() {
+        assert_eq!(
+            jpeg_dimensions(&make_jpeg_header(3840, 2160)),
+            Some((3840, 2160))
+        );
+        assert_eq!(
+            jpeg_dimensions(&make_jpeg_header(1080, 1920)),
+            Some((1080, 1920))
+        );
+    }
+
+    #[test]
+    fn test_jpeg_dimensions_skips_huffman_table() {
+        let mut data = vec![0xFF, 0xD8, 0xFF, 0xC4, 0x00, 0x03, 0x00];
+        data.extend_from_slice(&make_jpeg_header(1920, 1200)[2..]);
+        assert_eq!(jpeg_dimensions(&data), Some((1920, 1200)));
+    }
+
+    #[test]
+    fn test_jpeg_dimensions_invalid_data() {
+        assert_eq!(jpeg_dimensions(b"\x89PNG\r\n"), None);
+        assert_eq!(jpeg_dimensions(&[0xFF, 0xD8]), None);
+        // 截断在 SOF 段中
+        let header = make_jpeg_header(3840, 2160);
+        assert_eq!(jpeg_dimensions(&header[..header.len() - 6]), None);
+    }
+
+    #[test]
+    fn test_format_timestamp_by_language() {
+        use chrono::TimeZone;
+        let time = chrono::Local
+            .with_ymd_and_hms(2024, 3, 5, 9, 7, 30)
+            .earliest()
+            .unwrap();
+
+        assert_eq!(
+            format_timestamp(&time, TimestampFormat::Display, "zh-CN"),
+            "2024-03-05 09:07:30"
+        );
+        assert_eq!(
+            format_timestamp(&time, TimestampFormat::Display, "en-US"),
+            "Mar 5, 2024 09:07:30"
+        );
+
+        let raw = format_timestamp(&time, TimestampFormat::Rfc3339, "zh-CN");
+        assert!(raw.starts_with("2024-03-05T09:07:30"));
+        assert_eq!(
+            chrono::DateTime::parse_from_rfc3339(&raw).unwrap(),
+            time.fixed_offset()
+        );
+    }
+
+    #[test]
+    fn test_timestamp_format_deserialize() {
+        let format: TimestampFormat = serde_json::from_str(r#""rfc3339""#).unwrap();
+        assert_eq!(format, TimestampFormat::Rfc3339);
+        assert_eq!(TimestampFormat::default(), TimestampFormat::Display);
+    }
 }
@@ -1,6 +1,150 @@
-use crate::runtime_state;
-use log::info;
-use tauri::AppHandle;
+//! 应用更新检查
+//!
+//! 更新通道为 "beta" 时需要包含预发布版本，而默认端点 `/releases/latest` 只指向最新正式版，
+//! 因此先通过 GitHub Releases API 列出全部发布，选出符合通道的最高版本，
+//! 再用该版本的 `latest.json` 作为更新端点交给 updater 插件校验签名。
+
+use crate::{AppState, http_client, network_gate, runtime_state};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, ResourceId, Url, Webview};
+use tauri_plugin_updater::UpdaterExt;
+
+const RELEASES_API_URL: &str =
+    "https://api.github.com/repos/qiyuey/bing-wallpaper-now/releases?per_page=30";
+const RELEASE_DOWNLOAD_BASE: &str =
+    "https://github.com/qiyuey/bing-wallpaper-now/releases/download";
+/// 列出发布的超时（更新检查本身的超时由前端传入）
+const RELEASES_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// GitHub Releases API 返回的发布信息（只取需要的字段）
+#[derive(Debug, Clone, Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    draft: bool,
+}
+
+/// 与 updater 插件 `check` 命令相同的返回结构，前端据此构造 `Update` 对象
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct UpdateMetadata {
+    rid: ResourceId,
+    current_version: String,
+    version: String,
+    date: Option<String>,
+    body: Option<String>,
+    raw_json: serde_json::Value,
+}
+
+/// 从发布列表中选出符合通道的最高版本
+///
+/// 草稿始终忽略；stable 通道忽略预发布（GitHub 标记或版本号带预发布后缀），
+/// beta 通道两者都接受。无法解析为 semver 的标签会被跳过。
+fn select_release<'a>(releases: &'a [GithubRelease], channel: &str) -> Option<&'a GithubRelease> {
+    let include_prerelease = channel == "beta";
+    releases
+        .iter()
+        .filter(|release| !release.draft)
+        .filter_map(|release| {
+            let version =
+                semver::Version::parse(release.tag_name.trim_start_matches(['v', 'V'])).ok()?;
+            let is_prerelease = release.prerelease || !version.pre.is_empty();
+            (include_prerelease || !is_prerelease).then_some((version, release))
+        })
+        .max_by(|a, b| a.0.cmp(&b.0))
+        .map(|(_, release)| release)
+}
+
+fn manifest_url(tag: &str) -> String {
+    format!("{RELEASE_DOWNLOAD_BASE}/{tag}/latest.json")
+}
+
+async fn fetch_releases() -> anyhow::Result<Vec<GithubRelease>> {
+    let client = http_client::builder()
+        .timeout(RELEASES_TIMEOUT)
+        .user_agent(concat!("BingWallpaperNow/", env!("CARGO_PKG_VERSION")))
+        .build()?;
+    let request = client
+        .get(RELEASES_API_URL)
+        .header(reqwest::header::ACCEPT, "application/vnd.github+json");
+    let releases = http_client::send(request)
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    Ok(releases)
+}
+
+/// 按设置中的更新通道检查应用更新
+///
+/// 列出发布失败时：stable 通道回退到配置中的默认端点；beta 通道返回错误，
+/// 避免把"检查失败"误报为"没有更新"。
+#[tauri::command]
+pub(crate) async fn check_for_updates(
+    webview: Webview,
+    timeout: Option<u64>,
+) -> Result<Option<UpdateMetadata>, String> {
+    let channel = webview
+        .state::<AppState>()
+        .settings
+        .read()
+        .await
+        .update_channel;
+
+    let mut builder = webview.updater_builder();
+    if let Some(timeout) = timeout {
+        builder = builder.timeout(Duration::from_millis(timeout));
+    }
+
+    match fetch_releases().await {
+        Ok(releases) => {
+            let Some(release) = select_release(&releases, &channel) else {
+                info!(target: "version_check", "通道 {} 没有可用的发布", channel);
+                return Ok(None);
+            };
+            info!(
+                target: "version_check",
+                "通道 {} 的最新发布: {}",
+                channel,
+                release.tag_name
+            );
+            let url = Url::parse(&manifest_url(&release.tag_name)).map_err(|e| e.to_string())?;
+            builder = builder.endpoints(vec![url]).map_err(|e| e.to_string())?;
+        }
+        // 默认更新端点同样在 GitHub 上，发布列表被拦截时不再回退
+        Err(e) if http_client::is_restricted(&e) => {
+            return Err(network_gate::NETWORK_RESTRICTED.to_string());
+        }
+        Err(e) if channel == "stable" => {
+            warn!(target: "version_check", "获取发布列表失败: {}，使用默认更新端点", e);
+        }
+        Err(e) => return Err(format!("获取发布列表失败: {e}")),
+    }
+
+    let update = builder
+        .build()
+        .map_err(|e| e.to_string())?
+        .check()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(update.map(|update| UpdateMetadata {
+        current_version: update.current_version.clone(),
+        version: update.version.clone(),
+        date: update
+            .raw_json
+            .get("pub_date")
+            .and_then(|date| date.as_str())
+            .map(str::to_string),
+        body: update.body.clone(),
+        raw_json: update.raw_json.clone(),
+        rid: webview.resources_table().add(update),
+    }))
+}
 
 /// 添加版本到"不再提醒"列表（保存最大版本）
 #[tauri::command]
@@ -8,7 +152,7 @@ pub(crate) async fn add_ignored_update_version(
     app: AppHandle,
     version: String,
 ) -> Result<(), String> {
-    let mut runtime_state = runtime_state::load_runtime_state(&app)
+    let runtime_state = runtime_state::load_runtime_state(&app)
         .map_err(|e| format!("Failed to load runtime state: {}", e))?;
 
     let should_update = runtime_state
@@ -25,9 +169,11 @@ pub(crate) async fn add_ignored_update_version(
         .unwrap_or(true);
 
     if should_update {
-        runtime_state.ignored_update_version = Some(version.clone());
-        runtime_state::save_runtime_state(&app, &runtime_state)
-            .map_err(|e| format!("Failed to save runtime state: {}", e))?;
+        runtime_state::modify_runtime_state(&app, |runtime_state| {
+            runtime_state.ignored_update_version = Some(version.clone());
+            true
+        })
+        .map_err(|e| format!("Failed to save runtime state: {}", e))?;
         info!(
             target: "version_check",
             "Updated ignored update version to: {}",
@@ -59,6 +205,51 @@ pub(crate) async fn is_version_ignored(app: AppHandle, version: String) -> Resul
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    fn release(tag: &str, prerelease: bool, draft: bool) -> GithubRelease {
+        GithubRelease {
+            tag_name: tag.to_string(),
+            prerelease,
+            draft,
+        }
+    }
+
+    #[test]
+    fn test_select_release_respects_channel() {
+        let releases = vec![
+            release("v1.3.0-rc.1", true, false),
+            release("v1.2.1", false, false),
+            release("v1.4.0", false, true),
+            release("v1.2.0", false, false),
+            release("nightly", true, false),
+        ];
+
+        let stable = select_release(&releases, "stable").unwrap();
+        assert_eq!(stable.tag_name, "v1.2.1");
+        let beta = select_release(&releases, "beta").unwrap();
+        assert_eq!(beta.tag_name, "v1.3.0-rc.1");
+    }
+
+    #[test]
+    fn test_select_release_treats_prerelease_suffix_as_beta() {
+        // 即使 GitHub 上未勾选 pre-release，带预发布后缀的版本也不进入 stable 通道
+        let releases = vec![release("v2.0.0-beta.2", false, false)];
+        assert!(select_release(&releases, "stable").is_none());
+        assert_eq!(
+            select_release(&releases, "beta").unwrap().tag_name,
+            "v2.0.0-beta.2"
+        );
+    }
+
+    #[test]
+    fn test_manifest_url_points_to_release_asset() {
+        assert_eq!(
+            manifest_url("v1.2.3"),
+            "https://github.com/qiyuey/bing-wallpaper-now/releases/download/v1.2.3/latest.json"
+        );
+    }
+
     #[test]
     fn test_version_comparison_with_semver() {
         let v1 = semver::Version::parse("1.0.0").unwrap();
@@ -0,0 +1,122 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::sync::{Mutex, MutexGuard};
+
+/// 用户连续点击时的防抖窗口。
+///
+/// 窗口内的新请求会取代尚未开始应用的旧请求，只有最后一次点击会真正设置壁纸。
+pub(crate) const APPLY_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// 壁纸应用队列（latest-wins）
+///
+/// 快速点击画廊中的不同壁纸时，每次点击都会启动一个后台任务；
+/// 这些任务可能乱序完成，导致系统壁纸与 `current_wallpaper_path` 不一致。
+/// 该队列为每次请求分配递增的票据，并用互斥锁串行化实际的系统调用：
+/// - 被更新请求取代的票据在防抖结束、获取锁后、以及耗时步骤之间都会被丢弃；
+/// - 同一时刻只有一个任务在调用系统 API 设置壁纸。
+pub(crate) struct WallpaperApplyQueue {
+    generation: AtomicU64,
+    apply_lock: Mutex<()>,
+}
+
+impl WallpaperApplyQueue {
+    pub(crate) fn new() -> Self {
+        Self {
+            generation: AtomicU64::new(0),
+            apply_lock: Mutex::new(()),
+        }
+    }
+
+    /// 登记一次新的应用请求，返回其票据（同时使之前的所有票据失效）
+    pub(crate) fn enqueue(&self) -> u64 {
+        self.generation.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    /// 票据是否仍是最新请求
+    pub(crate) fn is_latest(&self, ticket: u64) -> bool {
+        self.generation.load(Ordering::SeqCst) == ticket
+    }
+
+    /// 等待防抖窗口后获取应用锁
+    ///
+    /// 若在等待期间出现了更新的请求，返回 `None`，调用方应直接放弃。
+    pub(crate) async fn acquire(
+        &self,
+        ticket: u64,
+        debounce: Duration,
+    ) -> Option<MutexGuard<'_, ()>> {
+        if !debounce.is_zero() {
+            tokio::time::sleep(debounce).await;
+        }
+        if !self.is_latest(ticket) {
+            return None;
+        }
+        let guard = self.apply_lock.lock().await;
+        // 等锁期间可能又有新请求进入
+        if !self.is_latest(ticket) {
+            return None;
+        }
+        Some(guard)
+    }
+
+    /// 不参与 latest-wins 竞争，仅与其他应用任务串行（用于自动应用最新壁纸）
+    pub(crate) async fn lock(&self) -> MutexGuard<'_, ()> {
+        self.apply_lock.lock().await
+    }
+}
+
+impl Default for WallpaperApplyQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_enqueue_invalidates_previous_tickets() {
+        let queue = WallpaperApplyQueue::new();
+        let first = queue.enqueue();
+        let second = queue.enqueue();
+        assert!(!queue.is_latest(first));
+        assert!(queue.is_latest(second));
+    }
+
+    #[tokio::test]
+    async fn test_acquire_drops_superseded_request() {
+        let queue = Arc::new(WallpaperApplyQueue::new());
+        let first = queue.enqueue();
+
+        let q = queue.clone();
+        let pending =
+            tokio::spawn(
+                async move { q.acquire(first, Duration::from_millis(50)).await.is_some() },
+            );
+        let second = queue.enqueue();
+
+        assert!(!pending.await.unwrap(), "被取代的请求不应获取到应用锁");
+        assert!(queue.acquire(second, Duration::ZERO).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_acquire_rechecks_after_waiting_for_lock() {
+        let queue = Arc::new(WallpaperApplyQueue::new());
+        let running = queue.lock().await;
+
+        let ticket = queue.enqueue();
+        let q = queue.clone();
+        let waiting =
+            tokio::spawn(async move { q.acquire(ticket, Duration::ZERO).await.is_some() });
+
+        // 等待期间出现新请求，持锁结束后旧请求应被丢弃
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let newer = queue.enqueue();
+        drop(running);
+
+        assert!(!waiting.await.unwrap());
+        assert!(queue.is_latest(newer));
+    }
+}
@@ -3,6 +3,7 @@ use anyhow::Context;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 
 #[cfg(target_os = "windows")]
 use log::{info, warn};
@@ -32,9 +33,28 @@ use std::iter;
 #[cfg(windows)]
 use std::os::windows::ffi::OsStrExt;
 #[cfg(windows)]
+use std::sync::Mutex;
+#[cfg(windows)]
+use std::sync::atomic::AtomicU64;
+#[cfg(windows)]
+use std::time::Duration;
+#[cfg(windows)]
 use windows_sys::Win32::UI::WindowsAndMessaging::{
-    SPI_GETDESKWALLPAPER, SPI_SETDESKWALLPAPER, SPIF_SENDCHANGE, SPIF_UPDATEINIFILE,
-    SystemParametersInfoW,
+    CreateWindowExW, DefWindowProcW, DispatchMessageW, GetMessageW, MSG, PBT_APMRESUMEAUTOMATIC,
+    RegisterClassW, SPI_GETDESKWALLPAPER, SPI_SETDESKWALLPAPER, SPIF_SENDCHANGE,
+    SPIF_UPDATEINIFILE, SystemParametersInfoW, TranslateMessage, WM_DISPLAYCHANGE,
+    WM_POWERBROADCAST, WM_WTSSESSION_CHANGE, WNDCLASSW, WTS_SESSION_LOGON, WTS_SESSION_UNLOCK,
+};
+#[cfg(windows)]
+use windows_sys::Win32::{
+    Foundation::{ERROR_SUCCESS, HWND, LPARAM, LRESULT, WPARAM},
+    System::LibraryLoader::GetModuleHandleW,
+    System::Registry::{
+        HKEY, HKEY_CURRENT_USER, KEY_NOTIFY, KEY_QUERY_VALUE, REG_NOTIFY_CHANGE_LAST_SET,
+        RRF_RT_REG_BINARY, RRF_RT_REG_SZ, RegCloseKey, RegGetValueW, RegNotifyChangeKeyValue,
+        RegOpenKeyExW,
+    },
+    System::RemoteDesktop::{NOTIFY_FOR_THIS_SESSION, WTSRegisterSessionNotification},
 };
 
 /// 壁纸状态：记录期望壁纸和各显示器实际壁纸
@@ -68,6 +88,29 @@ struct PortraitFallbackNoticeState {
 static PORTRAIT_FALLBACK_NOTICE: LazyLock<Mutex<PortraitFallbackNoticeState>> =
     LazyLock::new(|| Mutex::new(PortraitFallbackNoticeState::default()));
 
+/// 是否为竖屏显示器使用竖屏壁纸，镜像设置 `enable_portrait_variant`，
+/// 供无法访问异步设置锁的系统回调（macOS Space 切换）读取。
+static PORTRAIT_VARIANT_ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// 同步 `enable_portrait_variant` 设置（加载或修改设置后调用）
+pub fn set_portrait_variant_enabled(enabled: bool) {
+    PORTRAIT_VARIANT_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// 是否把壁纸同步到所有虚拟桌面，镜像设置 `virtual_desktop_mode`（仅 Windows 11 生效）。
+/// 为 false 时只修改当前虚拟桌面，切换桌面后不再重新应用。
+static APPLY_TO_ALL_VIRTUAL_DESKTOPS: AtomicBool = AtomicBool::new(true);
+
+/// 同步 `virtual_desktop_mode` 设置（加载或修改设置后调用）
+pub fn set_virtual_desktop_mode(mode: &str) {
+    APPLY_TO_ALL_VIRTUAL_DESKTOPS.store(mode != "current", Ordering::Relaxed);
+}
+
+/// 是否需要竖屏壁纸：存在竖屏显示器且未关闭竖屏壁纸
+pub fn portrait_variant_wanted(screens: &[ScreenOrientation]) -> bool {
+    PORTRAIT_VARIANT_ENABLED.load(Ordering::Relaxed) && screens.iter().any(|s| s.is_portrait)
+}
+
 /// 获取 Windows 当前桌面壁纸路径。
 #[cfg(windows)]
 fn get_current_wallpaper_windows() -> Result<String> {
@@ -123,6 +166,107 @@ pub fn get_current_wallpaper_path() -> Result<Option<PathBuf>> {
     }
 }
 
+/// 获取各显示器当前显示的壁纸路径，按屏幕索引排列（无法获取的显示器为 None）。
+///
+/// macOS 逐个查询显示器；Windows 通过 `IDesktopWallpaper` 按显示器查询，COM 不可用时回退到
+/// `SPI_GETDESKWALLPAPER`，每个显示器都报告该共用路径；Linux 通过 Plasma 脚本读取当前 activity
+/// 各桌面的配置。其他环境返回空列表。
+pub fn get_applied_wallpapers() -> Vec<Option<PathBuf>> {
+    #[cfg(target_os = "macos")]
+    {
+        // SAFETY: 与 `get_desktop_image_url_for_screen` 相同，只读取屏幕列表
+        let count = unsafe { NSScreen::screens(MainThreadMarker::new_unchecked()).len() };
+        (0..count).map(get_desktop_image_url_for_screen).collect()
+    }
+
+    #[cfg(windows)]
+    {
+        get_monitor_wallpapers_windows().unwrap_or_else(|e| {
+            warn!(target: "wallpaper", "通过 IDesktopWallpaper 读取各显示器壁纸失败: {e}");
+            let path = get_current_wallpaper_path().ok().flatten();
+            vec![path; get_screen_pixel_sizes().len().max(1)]
+        })
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        if !crate::kde_wallpaper::is_kde_session() {
+            return Vec::new();
+        }
+        crate::kde_wallpaper::get_screen_wallpapers_kde().unwrap_or_else(|e| {
+            log::warn!(target: "wallpaper", "读取 KDE 各显示器壁纸失败: {e}");
+            Vec::new()
+        })
+    }
+
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+    {
+        Vec::new()
+    }
+}
+
+/// 通过 `IDesktopWallpaper` 逐个读取已连接显示器的壁纸
+///
+/// 未连接的显示器（`GetMonitorRECT` 失败）不计入结果；壁纸为空（如纯色背景）或文件已不存在时为 `None`。
+#[cfg(windows)]
+fn get_monitor_wallpapers_windows() -> windows::core::Result<Vec<Option<PathBuf>>> {
+    use windows::Win32::System::Com::{
+        CLSCTX_ALL, COINIT_APARTMENTTHREADED, CoCreateInstance, CoInitializeEx, CoTaskMemFree,
+        CoUninitialize,
+    };
+    use windows::Win32::UI::Shell::{DesktopWallpaper, IDesktopWallpaper};
+    use windows::core::{PCWSTR, PWSTR};
+
+    /// 调用方线程上的 COM 初始化，成功初始化时在离开作用域后反初始化
+    struct ComGuard(bool);
+    impl Drop for ComGuard {
+        fn drop(&mut self) {
+            if self.0 {
+                // SAFETY: 与同一线程上成功的 CoInitializeEx 配对
+                unsafe { CoUninitialize() };
+            }
+        }
+    }
+
+    /// 读取 COM 分配的字符串并释放
+    fn take_string(value: PWSTR) -> String {
+        // SAFETY: `value` 由 IDesktopWallpaper 通过 CoTaskMemAlloc 分配，读取后立即释放
+        unsafe {
+            let text = value.to_string().unwrap_or_default();
+            CoTaskMemFree(Some(value.0 as *const std::ffi::c_void));
+            text
+        }
+    }
+
+    // 线程已按其他模式初始化时（RPC_E_CHANGED_MODE）COM 仍可用，只是不能由这里反初始化
+    // SAFETY: 参数均为合法值
+    let _com = ComGuard(unsafe { CoInitializeEx(None, COINIT_APARTMENTTHREADED) }.is_ok());
+    // SAFETY: 在已初始化 COM 的线程上创建系统提供的 DesktopWallpaper 对象
+    let desktop: IDesktopWallpaper =
+        unsafe { CoCreateInstance(&DesktopWallpaper, None, CLSCTX_ALL) }?;
+
+    // SAFETY: 以下调用只读取壁纸配置，返回的字符串由 `take_string` 释放
+    let count = unsafe { desktop.GetMonitorDevicePathCount() }?;
+    let mut wallpapers = Vec::new();
+    for index in 0..count {
+        let monitor_id = unsafe { desktop.GetMonitorDevicePathAt(index) }?;
+        let connected = unsafe { desktop.GetMonitorRECT(PCWSTR(monitor_id.0)) }.is_ok();
+        let wallpaper = connected
+            .then(|| unsafe { desktop.GetWallpaper(PCWSTR(monitor_id.0)) }.ok())
+            .flatten();
+        take_string(monitor_id);
+        if !connected {
+            continue;
+        }
+        let path = PathBuf::from(wallpaper.map(take_string).unwrap_or_default());
+        wallpapers.push(
+            (!path.as_os_str().is_empty() && path.exists())
+                .then(|| path.canonicalize().unwrap_or(path)),
+        );
+    }
+    Ok(wallpapers)
+}
+
 /// 规范化 Windows 路径用于比较，避免大小写和分隔符差异导致重复设置。
 #[cfg(windows)]
 fn normalize_windows_path(path: &Path) -> String {
@@ -170,12 +314,393 @@ fn set_wallpaper_windows(image_path: &Path) -> Result<()> {
     };
 
     if successful {
+        remember_expected_wallpaper(image_path);
         Ok(())
     } else {
         Err(std::io::Error::last_os_error()).context("设置 Windows 壁纸失败")
     }
 }
 
+/// Explorer 保存虚拟桌面信息的注册表键（Windows 11）。
+///
+/// Windows 11 中每个虚拟桌面可以有独立的壁纸，`SPI_SETDESKWALLPAPER` 只修改当前桌面。
+/// 公开的 `IVirtualDesktopManager` 接口只能查询窗口所在的桌面，不提供桌面枚举，
+/// 因此桌面列表、当前桌面和各桌面的壁纸都从该键读取。
+#[cfg(windows)]
+const VIRTUAL_DESKTOPS_KEY: &str =
+    r"Software\Microsoft\Windows\CurrentVersion\Explorer\VirtualDesktops";
+
+/// Windows 最近一次设置的壁纸，切换虚拟桌面后据此重新应用
+#[cfg(windows)]
+static EXPECTED_WINDOWS_WALLPAPER: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+#[cfg(windows)]
+fn remember_expected_wallpaper(image_path: &Path) {
+    *EXPECTED_WINDOWS_WALLPAPER
+        .lock()
+        .unwrap_or_else(|e| e.into_inner()) = Some(image_path.to_path_buf());
+}
+
+/// 一个 Windows 虚拟桌面
+#[cfg(windows)]
+#[derive(Debug, Clone)]
+struct VirtualDesktop {
+    id: String,
+    is_current: bool,
+    /// 该桌面单独记录的壁纸，未记录时为 `None`
+    wallpaper: Option<PathBuf>,
+}
+
+/// 把注册表中的 16 字节 GUID 格式化为 `{XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX}`
+#[cfg(any(windows, test))]
+fn format_guid(bytes: &[u8; 16]) -> String {
+    let data1 = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    let data2 = u16::from_le_bytes([bytes[4], bytes[5]]);
+    let data3 = u16::from_le_bytes([bytes[6], bytes[7]]);
+    let node: String = bytes[10..].iter().map(|b| format!("{b:02X}")).collect();
+    format!(
+        "{{{data1:08X}-{data2:04X}-{data3:04X}-{:02X}{:02X}-{node}}}",
+        bytes[8], bytes[9]
+    )
+}
+
+/// 解析 `VirtualDesktopIDs` / `CurrentVirtualDesktop`：连续存放的 16 字节 GUID
+#[cfg(any(windows, test))]
+fn parse_desktop_ids(bytes: &[u8]) -> Vec<String> {
+    bytes
+        .chunks_exact(16)
+        .filter_map(|chunk| chunk.try_into().ok())
+        .map(format_guid)
+        .collect()
+}
+
+#[cfg(windows)]
+fn wide_null(value: &str) -> Vec<u16> {
+    value.encode_utf16().chain(iter::once(0)).collect()
+}
+
+/// 读取 HKEY_CURRENT_USER 下的注册表值，不存在或类型不符时返回 `None`
+#[cfg(windows)]
+fn read_registry_value(subkey: &str, value_name: &str, flags: u32) -> Option<Vec<u8>> {
+    let subkey = wide_null(subkey);
+    let value_name = wide_null(value_name);
+    let mut size = 0_u32;
+
+    // SAFETY: Both UTF-16 strings are null-terminated. A null data pointer asks the API to
+    // report the required buffer size only.
+    let status = unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            subkey.as_ptr(),
+            value_name.as_ptr(),
+            flags,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            std::ptr::addr_of_mut!(size),
+        )
+    };
+    if status != ERROR_SUCCESS || size == 0 {
+        return None;
+    }
+
+    let mut data = vec![0_u8; size as usize];
+    // SAFETY: `data` is a writable buffer of exactly `size` bytes.
+    let status = unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            subkey.as_ptr(),
+            value_name.as_ptr(),
+            flags,
+            std::ptr::null_mut(),
+            data.as_mut_ptr().cast(),
+            std::ptr::addr_of_mut!(size),
+        )
+    };
+    if status != ERROR_SUCCESS {
+        return None;
+    }
+    data.truncate(size as usize);
+    Some(data)
+}
+
+#[cfg(windows)]
+fn read_registry_string(subkey: &str, value_name: &str) -> Option<String> {
+    let data = read_registry_value(subkey, value_name, RRF_RT_REG_SZ)?;
+    let wide: Vec<u16> = data
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .take_while(|&ch| ch != 0)
+        .collect();
+    let value = String::from_utf16_lossy(&wide);
+    (!value.trim().is_empty()).then_some(value)
+}
+
+#[cfg(windows)]
+fn current_virtual_desktop_id() -> Option<String> {
+    read_registry_value(
+        VIRTUAL_DESKTOPS_KEY,
+        "CurrentVirtualDesktop",
+        RRF_RT_REG_BINARY,
+    )
+    .and_then(|bytes| parse_desktop_ids(&bytes).into_iter().next())
+}
+
+/// 枚举虚拟桌面；Windows 10 或从未创建过虚拟桌面时返回空列表
+#[cfg(windows)]
+fn list_virtual_desktops() -> Vec<VirtualDesktop> {
+    let Some(ids) =
+        read_registry_value(VIRTUAL_DESKTOPS_KEY, "VirtualDesktopIDs", RRF_RT_REG_BINARY)
+    else {
+        return Vec::new();
+    };
+    let current = current_virtual_desktop_id();
+
+    parse_desktop_ids(&ids)
+        .into_iter()
+        .map(|id| {
+            let desktop_key = format!(r"{VIRTUAL_DESKTOPS_KEY}\Desktops\{id}");
+            VirtualDesktop {
+                is_current: current.as_deref() == Some(id.as_str()),
+                wallpaper: read_registry_string(&desktop_key, "Wallpaper").map(PathBuf::from),
+                id,
+            }
+        })
+        .collect()
+}
+
+/// 切换虚拟桌面后，若当前桌面的壁纸与期望不一致则重新设置（与 macOS 切换 Space 的处理一致）
+#[cfg(windows)]
+fn reapply_on_virtual_desktop_switch() {
+    if !APPLY_TO_ALL_VIRTUAL_DESKTOPS.load(Ordering::Relaxed) {
+        return;
+    }
+    let Some(expected) = EXPECTED_WINDOWS_WALLPAPER
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+    else {
+        return;
+    };
+    if !expected.exists() {
+        return;
+    }
+
+    let desktops = list_virtual_desktops();
+    let Some(current) = desktops.iter().find(|desktop| desktop.is_current) else {
+        return;
+    };
+    let expected_normalized = normalize_windows_path(&expected);
+    if current
+        .wallpaper
+        .as_deref()
+        .is_some_and(|path| normalize_windows_path(path) == expected_normalized)
+    {
+        return;
+    }
+
+    info!(target: "wallpaper", "切换到虚拟桌面 {}（共 {} 个），重新应用壁纸", current.id, desktops.len());
+    if let Err(e) = set_wallpaper_windows(&expected) {
+        warn!(target: "wallpaper", "切换虚拟桌面后重新应用壁纸失败: {e}");
+    }
+}
+
+/// 监听虚拟桌面切换（`CurrentVirtualDesktop` 变化）
+#[cfg(windows)]
+fn watch_virtual_desktops() {
+    let subkey = wide_null(VIRTUAL_DESKTOPS_KEY);
+    let mut key: HKEY = std::ptr::null_mut();
+
+    // SAFETY: `subkey` is a valid null-terminated UTF-16 path and `key` is a writable handle.
+    let status = unsafe {
+        RegOpenKeyExW(
+            HKEY_CURRENT_USER,
+            subkey.as_ptr(),
+            0,
+            KEY_QUERY_VALUE | KEY_NOTIFY,
+            std::ptr::addr_of_mut!(key),
+        )
+    };
+    if status != ERROR_SUCCESS {
+        info!(target: "wallpaper", "未找到虚拟桌面注册表键，跳过虚拟桌面监听，错误码: {}", status);
+        return;
+    }
+
+    let mut previous = current_virtual_desktop_id();
+    loop {
+        // SAFETY: `key` is an open registry handle owned by this thread. A null event handle with
+        // synchronous mode makes this call block until the watched key changes.
+        let status = unsafe {
+            RegNotifyChangeKeyValue(key, 0, REG_NOTIFY_CHANGE_LAST_SET, std::ptr::null_mut(), 0)
+        };
+        if status != ERROR_SUCCESS {
+            warn!(target: "wallpaper", "虚拟桌面监听已停止，注册表错误码: {}", status);
+            break;
+        }
+
+        let current = current_virtual_desktop_id();
+        if current != previous {
+            reapply_on_virtual_desktop_switch();
+            previous = current;
+        }
+    }
+
+    // SAFETY: `key` was successfully opened above and is closed exactly once by this thread.
+    unsafe {
+        RegCloseKey(key);
+    }
+}
+
+/// 解锁/登录/唤醒后等待系统完成恢复再检查，期间的重复事件合并为一次检查
+#[cfg(windows)]
+const SESSION_RECONCILE_DELAY: Duration = Duration::from_secs(5);
+
+/// 会话事件计数，延迟检查时若已有更新的事件则由后者负责
+#[cfg(windows)]
+static SESSION_EVENT_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// 启动时恢复上次应用的壁纸作为期望值（重启后尚未设置过壁纸时，会话事件也能据此检查）
+#[cfg(windows)]
+pub fn restore_expected_wallpaper(image_path: &Path) {
+    let mut expected = EXPECTED_WINDOWS_WALLPAPER
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    if expected.is_none() {
+        *expected = Some(image_path.to_path_buf());
+    }
+}
+
+/// 系统壁纸与期望不一致时重新设置
+///
+/// Windows 在登录、解锁、睡眠唤醒或显卡驱动重置后偶尔会把壁纸恢复成旧图片。
+#[cfg(windows)]
+fn reapply_if_reverted(reason: &str) {
+    let Some(expected) = EXPECTED_WINDOWS_WALLPAPER
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+    else {
+        return;
+    };
+    if !expected.exists() {
+        return;
+    }
+    // 按虚拟桌面分别设置壁纸时，当前桌面的壁纸可能本来就与期望不同
+    if !APPLY_TO_ALL_VIRTUAL_DESKTOPS.load(Ordering::Relaxed) && list_virtual_desktops().len() > 1 {
+        return;
+    }
+
+    let actual = match get_current_wallpaper_windows() {
+        Ok(actual) => actual,
+        Err(e) => {
+            warn!(target: "wallpaper", "{reason}后读取当前 Windows 壁纸失败: {e}");
+            return;
+        }
+    };
+    if !actual.is_empty()
+        && normalize_windows_path(Path::new(&actual)) == normalize_windows_path(&expected)
+    {
+        return;
+    }
+
+    info!(target: "wallpaper", "{reason}后系统壁纸被还原为 {:?}，重新应用 {:?}", actual, expected);
+    if let Err(e) = set_wallpaper_windows(&expected) {
+        warn!(target: "wallpaper", "{reason}后重新应用壁纸失败: {e}");
+    }
+}
+
+/// 延迟检查壁纸，连续的会话事件只检查最后一次
+#[cfg(windows)]
+fn schedule_session_reconcile(reason: &'static str) {
+    let generation = SESSION_EVENT_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let spawned = std::thread::Builder::new()
+        .name("session-wallpaper-check".to_string())
+        .spawn(move || {
+            std::thread::sleep(SESSION_RECONCILE_DELAY);
+            if SESSION_EVENT_GENERATION.load(Ordering::SeqCst) == generation {
+                reapply_if_reverted(reason);
+            }
+        });
+    if let Err(e) = spawned {
+        warn!(target: "wallpaper", "无法启动壁纸检查线程: {e}");
+    }
+}
+
+#[cfg(windows)]
+unsafe extern "system" fn session_window_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    match msg {
+        WM_WTSSESSION_CHANGE => match wparam as u32 {
+            WTS_SESSION_UNLOCK => schedule_session_reconcile("解锁"),
+            WTS_SESSION_LOGON => schedule_session_reconcile("登录"),
+            _ => {}
+        },
+        WM_POWERBROADCAST if wparam as u32 == PBT_APMRESUMEAUTOMATIC => {
+            schedule_session_reconcile("睡眠唤醒")
+        }
+        WM_DISPLAYCHANGE => schedule_session_reconcile("显示设置变化"),
+        _ => {}
+    }
+    // SAFETY: forwards the original message arguments to the default window procedure.
+    unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) }
+}
+
+/// 监听会话解锁/登录、睡眠唤醒和显示设置变化
+///
+/// 电源与显示变化是广播消息，仅消息窗口（HWND_MESSAGE）收不到，因此创建一个不显示的顶层窗口。
+#[cfg(windows)]
+fn watch_session_events() {
+    let class_name = wide_null("BingWallpaperNowSessionWatcher");
+
+    // SAFETY: all pointers passed below reference null-terminated UTF-16 buffers or structures that
+    // live on this thread's stack for the duration of the calls; the window and its message loop
+    // stay on this thread.
+    unsafe {
+        let instance = GetModuleHandleW(std::ptr::null());
+        let class = WNDCLASSW {
+            lpfnWndProc: Some(session_window_proc),
+            hInstance: instance,
+            lpszClassName: class_name.as_ptr(),
+            ..Default::default()
+        };
+        if RegisterClassW(&class) == 0 {
+            warn!(target: "wallpaper", "注册会话监听窗口类失败: {}", std::io::Error::last_os_error());
+            return;
+        }
+
+        let hwnd = CreateWindowExW(
+            0,
+            class_name.as_ptr(),
+            class_name.as_ptr(),
+            0,
+            0,
+            0,
+            0,
+            0,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            instance,
+            std::ptr::null(),
+        );
+        if hwnd.is_null() {
+            warn!(target: "wallpaper", "创建会话监听窗口失败: {}", std::io::Error::last_os_error());
+            return;
+        }
+        if WTSRegisterSessionNotification(hwnd, NOTIFY_FOR_THIS_SESSION) == 0 {
+            warn!(target: "wallpaper", "注册会话通知失败，仅监听唤醒和显示变化: {}", std::io::Error::last_os_error());
+        }
+
+        let mut msg = MSG::default();
+        while GetMessageW(&mut msg, std::ptr::null_mut(), 0, 0) > 0 {
+            TranslateMessage(&msg);
+            DispatchMessageW(&msg);
+        }
+    }
+}
+
 /// 获取指定显示器的当前壁纸路径
 #[cfg(target_os = "macos")]
 fn get_desktop_image_url_for_screen(screen_index: usize) -> Option<PathBuf> {
@@ -246,8 +771,11 @@ define_class!(
                 let actual = get_all_desktop_images();
                 let screen_orientations = get_screen_orientations();
 
-                // 计算实际可用的竖屏壁纸路径（不存在则视为 None，由 fallback 走横屏）
-                let portrait_path = derive_portrait_path(expected).filter(|p| p.exists());
+                // 计算实际可用的竖屏壁纸路径（不存在或已关闭竖屏壁纸则视为 None，由 fallback 走横屏）
+                let portrait_path = portrait_variant_wanted(&screen_orientations)
+                    .then(|| derive_portrait_path(expected))
+                    .flatten()
+                    .filter(|p| p.exists());
 
                 // 检查是否所有显示器的壁纸都与期望一致（考虑屏幕方向 + 竖屏 fallback）
                 let all_match = screen_orientations.iter().all(|screen| {
@@ -294,9 +822,25 @@ pub fn initialize_observer() {
     }
 }
 
+/// 初始化 Windows 虚拟桌面与会话监听
+/// 必须在应用启动时调用一次
+///
+/// - 当用户切换到壁纸不同的虚拟桌面时，按 `virtual_desktop_mode` 重新应用壁纸
+/// - 解锁、登录、睡眠唤醒或显示设置变化后，若系统壁纸被还原则重新应用
 #[cfg(target_os = "windows")]
 pub fn initialize_observer() {
-    // Windows 不需要初始化
+    if let Err(e) = std::thread::Builder::new()
+        .name("virtual-desktop-watcher".to_string())
+        .spawn(watch_virtual_desktops)
+    {
+        warn!(target: "wallpaper", "无法启动虚拟桌面监听线程: {e}");
+    }
+    if let Err(e) = std::thread::Builder::new()
+        .name("session-watcher".to_string())
+        .spawn(watch_session_events)
+    {
+        warn!(target: "wallpaper", "无法启动会话监听线程: {e}");
+    }
 }
 
 /// 设置 Workspace 观察者
@@ -341,8 +885,8 @@ pub fn set_wallpaper(image_path: &Path, portrait_image_path: Option<&Path>) -> R
         anyhow::bail!("Wallpaper image does not exist: {:?}", image_path);
     }
 
-    // portrait_image_path 仅在 macOS 上使用（Windows 暂不支持竖屏壁纸）
-    #[cfg(target_os = "windows")]
+    // portrait_image_path 仅在 macOS 上使用（Windows / Linux 暂不支持竖屏壁纸）
+    #[cfg(any(target_os = "windows", target_os = "linux"))]
     let _ = portrait_image_path;
 
     // macOS 使用 NSWorkspace API 来处理多显示器和全屏场景
@@ -356,6 +900,15 @@ pub fn set_wallpaper(image_path: &Path, portrait_image_path: Option<&Path>) -> R
     {
         set_wallpaper_windows(image_path)
     }
+    // Linux 目前仅支持 KDE Plasma
+    #[cfg(target_os = "linux")]
+    {
+        if crate::kde_wallpaper::is_kde_session() {
+            crate::kde_wallpaper::set_wallpaper_kde(image_path)
+        } else {
+            anyhow::bail!("Unsupported desktop environment: only KDE Plasma is supported on Linux")
+        }
+    }
 }
 
 /// macOS 专用壁纸设置函数
@@ -620,6 +1173,80 @@ pub fn get_screen_orientations() -> Vec<ScreenOrientation> {
     vec![]
 }
 
+/// 获取所有显示器的物理像素尺寸（宽, 高）
+///
+/// 用于按屏幕实际分辨率选择下载档位；Retina 等高 DPI 屏幕按缩放后的物理像素计算。
+#[cfg(target_os = "macos")]
+pub fn get_screen_pixel_sizes() -> Vec<(u32, u32)> {
+    unsafe {
+        let mtm = MainThreadMarker::new_unchecked();
+        let screens = NSScreen::screens(mtm);
+
+        (0..screens.len())
+            .map(|i| {
+                let screen = screens.objectAtIndex(i);
+                let frame = screen.frame();
+                let scale = screen.backingScaleFactor();
+                (
+                    (frame.size.width * scale).round() as u32,
+                    (frame.size.height * scale).round() as u32,
+                )
+            })
+            .collect()
+    }
+}
+
+/// 获取所有显示器的物理像素尺寸（Windows 平台）
+///
+/// 应用以 Per-Monitor DPI Aware 方式运行，枚举得到的显示器矩形即为物理像素。
+#[cfg(target_os = "windows")]
+pub fn get_screen_pixel_sizes() -> Vec<(u32, u32)> {
+    use windows_sys::Win32::Foundation::{LPARAM, RECT};
+    use windows_sys::Win32::Graphics::Gdi::{EnumDisplayMonitors, HDC, HMONITOR};
+
+    unsafe extern "system" fn collect_monitor(
+        _monitor: HMONITOR,
+        _hdc: HDC,
+        rect: *mut RECT,
+        data: LPARAM,
+    ) -> windows_sys::core::BOOL {
+        // SAFETY: `data` is the address of the Vec passed to EnumDisplayMonitors below, which
+        // outlives the synchronous enumeration; `rect` is provided by the system for this call.
+        unsafe {
+            let sizes = &mut *(data as *mut Vec<(u32, u32)>);
+            if let Some(rect) = rect.as_ref() {
+                sizes.push((
+                    (rect.right - rect.left).max(0) as u32,
+                    (rect.bottom - rect.top).max(0) as u32,
+                ));
+            }
+        }
+        1
+    }
+
+    let mut sizes: Vec<(u32, u32)> = Vec::new();
+    // SAFETY: the callback only runs during this call and writes into `sizes`.
+    let ok = unsafe {
+        EnumDisplayMonitors(
+            std::ptr::null_mut(),
+            std::ptr::null(),
+            Some(collect_monitor),
+            std::ptr::addr_of_mut!(sizes) as LPARAM,
+        )
+    };
+    if ok == 0 {
+        warn!(target: "wallpaper", "枚举显示器失败，无法获取屏幕分辨率");
+        return Vec::new();
+    }
+    sizes
+}
+
+/// 其他平台暂不支持获取屏幕分辨率
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn get_screen_pixel_sizes() -> Vec<(u32, u32)> {
+    Vec::new()
+}
+
 /// 根据屏幕方向计算"该屏幕期望显示的壁纸路径"。
 ///
 /// - 横屏屏幕 → 横屏壁纸 (`landscape`)
@@ -672,6 +1299,48 @@ mod tests {
     #[cfg(windows)]
     use std::path::Path;
 
+    #[test]
+    fn portrait_variant_follows_setting_and_screens() {
+        use super::{ScreenOrientation, portrait_variant_wanted, set_portrait_variant_enabled};
+        let screens = |portrait: bool| {
+            vec![ScreenOrientation {
+                screen_index: 0,
+                is_portrait: portrait,
+                width: if portrait { 1080.0 } else { 1920.0 },
+                height: if portrait { 1920.0 } else { 1080.0 },
+            }]
+        };
+
+        assert!(portrait_variant_wanted(&screens(true)));
+        assert!(!portrait_variant_wanted(&screens(false)));
+        set_portrait_variant_enabled(false);
+        assert!(!portrait_variant_wanted(&screens(true)));
+        set_portrait_variant_enabled(true);
+        assert!(portrait_variant_wanted(&screens(true)));
+    }
+
+    #[test]
+    fn desktop_ids_are_parsed_as_registry_guids() {
+        use super::parse_desktop_ids;
+        let first = [
+            0x78, 0x56, 0x34, 0x12, 0xBC, 0x9A, 0xF0, 0xDE, 0x01, 0x23, 0x45, 0x67, 0x89, 0xAB,
+            0xCD, 0xEF,
+        ];
+        let mut bytes = first.to_vec();
+        bytes.extend([0_u8; 16]);
+        // 不足 16 字节的尾部数据被忽略
+        bytes.extend([0xFF; 3]);
+
+        assert_eq!(
+            parse_desktop_ids(&bytes),
+            vec![
+                "{12345678-9ABC-DEF0-0123-456789ABCDEF}".to_string(),
+                "{00000000-0000-0000-0000-000000000000}".to_string(),
+            ]
+        );
+        assert!(parse_desktop_ids(&[]).is_empty());
+    }
+
     #[cfg(windows)]
     #[test]
     fn windows_path_normalization_is_case_insensitive_and_uses_backslashes() {
@@ -0,0 +1,203 @@
+//! 壁纸信息浮层（OSD）
+//!
+//! 自动应用新壁纸后，在屏幕角落短暂显示一个始终置顶、不抢焦点、鼠标可穿透的小窗口，
+//! 展示壁纸标题和地点。页面为静态的 `osd.html`，内容通过初始化脚本注入；浮层仍在显示时
+//! 再次应用壁纸则调用页面的 `__showOsd` 更新内容并重新计时。显示时长和位置由设置控制，
+//! 时长为 0 时不显示。
+
+use log::warn;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, LogicalPosition, Manager, WebviewUrl, WebviewWindowBuilder};
+
+use crate::AppState;
+use crate::models::LocalWallpaper;
+use crate::notification::card_subtitle;
+
+const OSD_LABEL: &str = "osd";
+const OSD_PAGE: &str = "osd.html";
+const OSD_SIZE: (f64, f64) = (360.0, 72.0);
+/// 浮层与屏幕工作区边缘的间距（逻辑像素）
+const OSD_MARGIN: f64 = 24.0;
+
+/// 每次显示递增，只有最后一次显示到期时关闭窗口
+static SHOW_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// 浮层显示的内容（`window.__OSD__` 及 `__showOsd` 的参数）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OsdContent {
+    title: String,
+    location: String,
+    duration_ms: u64,
+}
+
+/// 由壁纸元数据构建浮层内容，标题和地点都为空时返回 None
+fn osd_content(wallpaper: &LocalWallpaper, duration: Duration) -> Option<OsdContent> {
+    let title = wallpaper.title.trim().to_string();
+    let location = card_subtitle(&wallpaper.copyright);
+    if title.is_empty() && location.is_empty() {
+        return None;
+    }
+    Some(OsdContent {
+        title,
+        location,
+        duration_ms: duration.as_millis() as u64,
+    })
+}
+
+/// 按设置的位置计算浮层左上角坐标
+///
+/// `area` 为工作区的 (x, y, 宽, 高)，均为逻辑像素；未知位置按右上角处理。
+fn osd_position(area: (f64, f64, f64, f64), position: &str) -> (f64, f64) {
+    let (x, y, width, height) = area;
+    let left = x + OSD_MARGIN;
+    let right = x + width - OSD_SIZE.0 - OSD_MARGIN;
+    let top = y + OSD_MARGIN;
+    let bottom = y + height - OSD_SIZE.1 - OSD_MARGIN;
+    match position {
+        "top_left" => (left, top),
+        "bottom_left" => (left, bottom),
+        "bottom_right" => (right, bottom),
+        _ => (right, top),
+    }
+}
+
+/// 主显示器的工作区（逻辑像素）
+fn work_area(app: &AppHandle) -> Option<(f64, f64, f64, f64)> {
+    let monitor = app.primary_monitor().ok().flatten()?;
+    let scale = monitor.scale_factor();
+    let area = monitor.work_area();
+    Some((
+        f64::from(area.position.x) / scale,
+        f64::from(area.position.y) / scale,
+        f64::from(area.size.width) / scale,
+        f64::from(area.size.height) / scale,
+    ))
+}
+
+async fn open_or_update(
+    app: &AppHandle,
+    content: &OsdContent,
+    position: &str,
+) -> Result<(), String> {
+    let data = serde_json::to_string(content).map_err(|e| e.to_string())?;
+    let window = match app.get_webview_window(OSD_LABEL) {
+        Some(window) => {
+            window
+                .eval(format!("window.__showOsd({data});"))
+                .map_err(|e| e.to_string())?;
+            window
+        }
+        None => WebviewWindowBuilder::new(app, OSD_LABEL, WebviewUrl::App(OSD_PAGE.into()))
+            .title("Bing Wallpaper Now")
+            .inner_size(OSD_SIZE.0, OSD_SIZE.1)
+            .decorations(false)
+            .resizable(false)
+            .always_on_top(true)
+            .skip_taskbar(true)
+            .focused(false)
+            .visible(false)
+            .initialization_script(format!("window.__OSD__ = {data};"))
+            .on_navigation(|url| url.path().ends_with(OSD_PAGE))
+            .build()
+            .map_err(|e| e.to_string())?,
+    };
+
+    if let Some(area) = work_area(app) {
+        let (x, y) = osd_position(area, position);
+        let _ = window.set_position(LogicalPosition::new(x, y));
+    }
+    // 浮层只用于展示，点击穿透到下方的窗口
+    let _ = window.set_ignore_cursor_events(true);
+    window.show().map_err(|e| e.to_string())
+}
+
+/// 显示刚应用的壁纸信息（设置中关闭时不做任何事）
+pub(crate) fn show(app: &AppHandle, wallpaper: &LocalWallpaper) {
+    let app = app.clone();
+    let wallpaper = wallpaper.clone();
+    tauri::async_runtime::spawn(async move {
+        let settings = app.state::<AppState>().settings.read().await;
+        if settings.osd_duration_secs == 0 {
+            return;
+        }
+        let duration = Duration::from_secs(u64::from(settings.osd_duration_secs));
+        let Some(content) = osd_content(&wallpaper, duration) else {
+            return;
+        };
+
+        let generation = SHOW_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+        if let Err(e) = open_or_update(&app, &content, &settings.osd_position).await {
+            warn!(target: "wallpaper_osd", "显示壁纸信息浮层失败: {}", e);
+            return;
+        }
+
+        tokio::time::sleep(duration).await;
+        if SHOW_GENERATION.load(Ordering::SeqCst) != generation {
+            return;
+        }
+        if let Some(window) = app.get_webview_window(OSD_LABEL) {
+            let _ = window.close();
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wallpaper(title: &str, copyright: &str) -> LocalWallpaper {
+        LocalWallpaper {
+            title: title.to_string(),
+            copyright: copyright.to_string(),
+            copyright_link: String::new(),
+            end_date: "20240101".to_string(),
+            urlbase: String::new(),
+            resolution: None,
+            portrait_available: None,
+            watermark_free: None,
+            recompression: None,
+        }
+    }
+
+    #[test]
+    fn test_osd_content_uses_copyright_location() {
+        let content = osd_content(
+            &wallpaper(
+                " Moraine Lake ",
+                "Moraine Lake, Banff National Park, Alberta (© Example/Getty Images)",
+            ),
+            Duration::from_secs(4),
+        )
+        .unwrap();
+        assert_eq!(content.title, "Moraine Lake");
+        assert_eq!(
+            content.location,
+            "Moraine Lake, Banff National Park, Alberta"
+        );
+
+        let json = serde_json::to_value(&content).unwrap();
+        assert_eq!(json["durationMs"], 4000);
+
+        assert!(osd_content(&wallpaper(" ", ""), Duration::from_secs(4)).is_none());
+    }
+
+    #[test]
+    fn test_osd_position_corners() {
+        let area = (0.0, 25.0, 1440.0, 875.0);
+        assert_eq!(osd_position(area, "top_left"), (24.0, 49.0));
+        assert_eq!(osd_position(area, "top_right"), (1056.0, 49.0));
+        assert_eq!(osd_position(area, "bottom_left"), (24.0, 804.0));
+        assert_eq!(osd_position(area, "bottom_right"), (1056.0, 804.0));
+        assert_eq!(
+            osd_position(area, "unknown"),
+            osd_position(area, "top_right")
+        );
+
+        // 副屏在主屏左侧时工作区原点为负
+        let (x, _) = osd_position((-1920.0, 0.0, 1920.0, 1080.0), "top_left");
+        assert_eq!(x, -1896.0);
+    }
+}
@@ -0,0 +1,385 @@
+//! 壁纸历史统计（"年度壁纸"看板数据）
+//!
+//! 基于当前市场的壁纸元数据统计出现最多的地点（取版权文本中地点的最后一段，通常是国家或地区）、
+//! 摄影师和每月数量，并计算每张已下载图片的平均色。
+//!
+//! 平均色需要解码图片，结果缓存在 `.derived/color_cache.json`，按文件大小与修改时间判断是否失效；
+//! 每次调用只计算新增或变化的图片，已删除图片的缓存条目会被清理。
+
+use anyhow::{Context, Result};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::models::LocalWallpaper;
+use crate::{AppState, get_effective_mkt, smart_crop, storage};
+
+const COLOR_CACHE_FILE: &str = "color_cache.json";
+/// 计算平均色时使用的缩略图边长
+const COLOR_SAMPLE_SIZE: u32 = 32;
+/// 返回的热门地点、摄影师数量
+const TOP_COUNT: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct LocationCount {
+    location: String,
+    count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct PhotographerCount {
+    photographer: String,
+    count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct MonthCount {
+    /// 月份（YYYY-MM）
+    month: String,
+    count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct WallpaperColor {
+    end_date: String,
+    /// 平均色（#rrggbb）
+    color: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct ArchiveStatistics {
+    /// 统计的年份（None 表示全部）
+    year: Option<i32>,
+    total: usize,
+    top_locations: Vec<LocationCount>,
+    top_photographers: Vec<PhotographerCount>,
+    /// 按月份升序
+    monthly_counts: Vec<MonthCount>,
+    /// 按日期升序，只包含已下载的图片
+    average_colors: Vec<WallpaperColor>,
+}
+
+/// 平均色缓存条目，文件大小或修改时间变化时重新计算
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct CachedColor {
+    rgb: [u8; 3],
+    size: u64,
+    modified: u64,
+}
+
+type ColorCache = HashMap<String, CachedColor>;
+
+/// 按出现次数降序（次数相同按名称）取前 [`TOP_COUNT`] 个
+fn top_counts(names: impl Iterator<Item = String>) -> Vec<(String, usize)> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for name in names {
+        *counts.entry(name).or_default() += 1;
+    }
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts.truncate(TOP_COUNT);
+    counts
+}
+
+/// 出现最多的地点（版权文本中地点的最后一段，通常是国家或地区）
+fn top_locations(wallpapers: &[LocalWallpaper]) -> Vec<LocationCount> {
+    top_counts(
+        wallpapers
+            .iter()
+            .filter_map(|wallpaper| wallpaper.copyright_info().region().map(str::to_string)),
+    )
+    .into_iter()
+    .map(|(location, count)| LocationCount { location, count })
+    .collect()
+}
+
+/// 作品最多的摄影师
+fn top_photographers(wallpapers: &[LocalWallpaper]) -> Vec<PhotographerCount> {
+    top_counts(
+        wallpapers
+            .iter()
+            .filter_map(|wallpaper| wallpaper.copyright_info().photographer),
+    )
+    .into_iter()
+    .map(|(photographer, count)| PhotographerCount {
+        photographer,
+        count,
+    })
+    .collect()
+}
+
+fn monthly_counts(wallpapers: &[LocalWallpaper]) -> Vec<MonthCount> {
+    let mut counts: std::collections::BTreeMap<String, usize> = Default::default();
+    for wallpaper in wallpapers {
+        if let (Some(year), Some(month)) =
+            (wallpaper.end_date.get(0..4), wallpaper.end_date.get(4..6))
+        {
+            *counts.entry(format!("{year}-{month}")).or_default() += 1;
+        }
+    }
+    counts
+        .into_iter()
+        .map(|(month, count)| MonthCount { month, count })
+        .collect()
+}
+
+fn color_cache_path(wallpaper_dir: &Path) -> PathBuf {
+    wallpaper_dir
+        .join(smart_crop::DERIVED_DIR)
+        .join(COLOR_CACHE_FILE)
+}
+
+fn load_color_cache(wallpaper_dir: &Path) -> ColorCache {
+    std::fs::read(color_cache_path(wallpaper_dir))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+fn save_color_cache(wallpaper_dir: &Path, cache: &ColorCache) -> Result<()> {
+    let path = color_cache_path(wallpaper_dir);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).context("Failed to create derived directory")?;
+    }
+    let temp = path.with_extension("json.tmp");
+    std::fs::write(&temp, serde_json::to_vec(cache)?).context("Failed to write color cache")?;
+    std::fs::rename(&temp, &path).context("Failed to save color cache")?;
+    Ok(())
+}
+
+/// 计算图片平均色（先缩小再取均值）
+fn compute_average_color(path: &Path) -> Result<[u8; 3]> {
+    let image = image::open(path)
+        .with_context(|| format!("Failed to decode wallpaper: {}", path.display()))?
+        .thumbnail(COLOR_SAMPLE_SIZE, COLOR_SAMPLE_SIZE)
+        .to_rgb8();
+    let pixel_count = u64::from(image.width()) * u64::from(image.height());
+    if pixel_count == 0 {
+        anyhow::bail!("Empty image: {}", path.display());
+    }
+    let mut sums = [0u64; 3];
+    for pixel in image.pixels() {
+        for (sum, value) in sums.iter_mut().zip(pixel.0) {
+            *sum += u64::from(value);
+        }
+    }
+    Ok(sums.map(|sum| (sum / pixel_count) as u8))
+}
+
+/// 读取已下载图片的平均色，只为新增或变化的图片重新计算
+///
+/// 返回 `(end_date, rgb)` 列表和缓存是否有变化。
+fn average_colors_incremental(
+    wallpaper_dir: &Path,
+    end_dates: &[String],
+    cache: &mut ColorCache,
+) -> (Vec<(String, [u8; 3])>, bool) {
+    let mut changed = false;
+    let mut colors = Vec::new();
+    for end_date in end_dates {
+        let path = storage::get_wallpaper_path(wallpaper_dir, end_date);
+        let Ok(metadata) = std::fs::metadata(&path) else {
+            continue;
+        };
+        let size = metadata.len();
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |duration| duration.as_secs());
+
+        if let Some(cached) = cache.get(end_date)
+            && cached.size == size
+            && cached.modified == modified
+        {
+            colors.push((end_date.clone(), cached.rgb));
+            continue;
+        }
+        match compute_average_color(&path) {
+            Ok(rgb) => {
+                cache.insert(
+                    end_date.clone(),
+                    CachedColor {
+                        rgb,
+                        size,
+                        modified,
+                    },
+                );
+                colors.push((end_date.clone(), rgb));
+                changed = true;
+            }
+            Err(e) => warn!(target: "wallpaper_stats", "计算平均色失败 {}: {}", end_date, e),
+        }
+    }
+
+    // 清理已不在统计范围内且图片已删除的条目
+    let before = cache.len();
+    cache.retain(|end_date, _| {
+        end_dates.contains(end_date)
+            || storage::get_wallpaper_path(wallpaper_dir, end_date).exists()
+    });
+    changed |= cache.len() != before;
+
+    (colors, changed)
+}
+
+/// 获取壁纸历史统计
+///
+/// `year` 为空时统计全部历史。
+#[tauri::command]
+pub(crate) async fn get_archive_statistics(
+    year: Option<i32>,
+    state: tauri::State<'_, AppState>,
+) -> Result<ArchiveStatistics, String> {
+    let wallpaper_dir = state.wallpaper_directory.lock().await.clone();
+    let mkt = get_effective_mkt(&state).await;
+    let mut wallpapers = storage::get_local_wallpapers(&wallpaper_dir, &mkt)
+        .await
+        .map_err(|e| e.to_string())?;
+    if let Some(year) = year {
+        let prefix = format!("{year:04}");
+        wallpapers.retain(|wallpaper| wallpaper.end_date.starts_with(&prefix));
+    }
+
+    let mut end_dates: Vec<String> = wallpapers.iter().map(|w| w.end_date.clone()).collect();
+    end_dates.sort();
+    let colors = tokio::task::spawn_blocking(move || {
+        let mut cache = load_color_cache(&wallpaper_dir);
+        let (colors, changed) = average_colors_incremental(&wallpaper_dir, &end_dates, &mut cache);
+        if changed && let Err(e) = save_color_cache(&wallpaper_dir, &cache) {
+            warn!(target: "wallpaper_stats", "保存平均色缓存失败: {}", e);
+        }
+        colors
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(ArchiveStatistics {
+        year,
+        total: wallpapers.len(),
+        top_locations: top_locations(&wallpapers),
+        top_photographers: top_photographers(&wallpapers),
+        monthly_counts: monthly_counts(&wallpapers),
+        average_colors: colors
+            .into_iter()
+            .map(|(end_date, [r, g, b])| WallpaperColor {
+                end_date,
+                color: format!("#{r:02x}{g:02x}{b:02x}"),
+            })
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    fn wallpaper(end_date: &str, copyright: &str) -> LocalWallpaper {
+        LocalWallpaper {
+            title: String::new(),
+            copyright: copyright.to_string(),
+            copyright_link: String::new(),
+            end_date: end_date.to_string(),
+            urlbase: String::new(),
+            resolution: None,
+            portrait_available: None,
+            watermark_free: None,
+            recompression: None,
+        }
+    }
+
+    #[test]
+    fn test_top_photographers_skips_agency_only_credits() {
+        let wallpapers = vec![
+            wallpaper("20240103", "Lake (© Paul Zizka/Minden Pictures)"),
+            wallpaper("20240102", "Peak (© Paul Zizka/Alamy)"),
+            wallpaper("20240101", "Coast (© Getty Images)"),
+        ];
+        assert_eq!(
+            top_photographers(&wallpapers),
+            vec![PhotographerCount {
+                photographer: "Paul Zizka".to_string(),
+                count: 2
+            }]
+        );
+    }
+
+    #[test]
+    fn test_top_locations_and_monthly_counts() {
+        let wallpapers = vec![
+            wallpaper("20240215", "Lake, Canada (© A)"),
+            wallpaper("20240201", "Alps, Switzerland (© B)"),
+            wallpaper("20240131", "Banff, Canada (© C)"),
+            wallpaper("20231231", "Zurich, Switzerland (© D)"),
+            wallpaper("20231230", "Kyoto, Japan (© E)"),
+        ];
+
+        assert_eq!(
+            top_locations(&wallpapers),
+            vec![
+                LocationCount {
+                    location: "Canada".to_string(),
+                    count: 2
+                },
+                LocationCount {
+                    location: "Switzerland".to_string(),
+                    count: 2
+                },
+                LocationCount {
+                    location: "Japan".to_string(),
+                    count: 1
+                },
+            ]
+        );
+        let months: Vec<_> = monthly_counts(&wallpapers)
+            .into_iter()
+            .map(|m| (m.month, m.count))
+            .collect();
+        assert_eq!(
+            months,
+            vec![
+                ("2023-12".to_string(), 2),
+                ("2024-01".to_string(), 1),
+                ("2024-02".to_string(), 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_average_colors_are_cached_incrementally() {
+        let unique = std::time::SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("bw_wallpaper_stats_{unique}"));
+        std::fs::create_dir_all(&dir).unwrap();
+        RgbImage::from_pixel(8, 8, Rgb([200, 100, 50]))
+            .save(storage::get_wallpaper_path(&dir, "20240101"))
+            .unwrap();
+        let end_dates = vec!["20240101".to_string(), "20240102".to_string()];
+
+        let mut cache = ColorCache::new();
+        let (colors, changed) = average_colors_incremental(&dir, &end_dates, &mut cache);
+        assert!(changed);
+        assert_eq!(colors, vec![("20240101".to_string(), [200, 100, 50])]);
+        save_color_cache(&dir, &cache).unwrap();
+
+        // 第二次读取命中缓存，不再变化
+        let mut cache = load_color_cache(&dir);
+        let (colors, changed) = average_colors_incremental(&dir, &end_dates, &mut cache);
+        assert!(!changed);
+        assert_eq!(colors.len(), 1);
+
+        // 图片删除后缓存条目被清理
+        std::fs::remove_file(storage::get_wallpaper_path(&dir, "20240101")).unwrap();
+        let (colors, changed) = average_colors_incremental(&dir, &[], &mut cache);
+        assert!(changed);
+        assert!(colors.is_empty());
+        assert!(cache.is_empty());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,148 @@
+//! 跟随壁纸明暗的主题
+//!
+//! 主题设置为 `"wallpaper"` 时，每次应用壁纸后分析图片整体亮度，判断偏亮还是偏暗，
+//! 通过 `suggested-theme` 事件通知前端切换浅色/深色界面；Windows 上托盘图标同步切换。
+//! 分析结果按图片路径缓存，前端启动时通过 `get_wallpaper_theme` 获取。
+
+use image::DynamicImage;
+use log::{info, warn};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::{AppState, events};
+
+/// 主题设置中表示跟随壁纸的值
+pub(crate) const WALLPAPER_THEME: &str = "wallpaper";
+/// 分析前缩小到的边长，足以反映整体亮度
+const SAMPLE_SIZE: u32 = 64;
+/// 平均亮度（0~255）不低于该值时视为浅色壁纸
+const LIGHT_LUMA_THRESHOLD: f64 = 128.0;
+
+/// 最近一次分析的图片及结果
+static LAST_TONE: Mutex<Option<(PathBuf, &'static str)>> = Mutex::new(None);
+
+/// 根据平均亮度判断图片偏亮（"light"）还是偏暗（"dark"）
+fn classify(image: &DynamicImage) -> &'static str {
+    let sample = image.thumbnail(SAMPLE_SIZE, SAMPLE_SIZE).to_luma8();
+    let pixels = sample.as_raw();
+    if pixels.is_empty() {
+        return "dark";
+    }
+    let mean = pixels.iter().map(|&p| f64::from(p)).sum::<f64>() / pixels.len() as f64;
+    if mean >= LIGHT_LUMA_THRESHOLD {
+        "light"
+    } else {
+        "dark"
+    }
+}
+
+/// 分析图片明暗（带缓存，解码在阻塞线程池中执行）
+async fn analyze(path: &Path) -> Option<&'static str> {
+    {
+        let cached = LAST_TONE.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((cached_path, tone)) = cached.as_ref()
+            && cached_path == path
+        {
+            return Some(tone);
+        }
+    }
+
+    let owned = path.to_path_buf();
+    let result =
+        tokio::task::spawn_blocking(move || image::open(&owned).map(|img| classify(&img))).await;
+    match result {
+        Ok(Ok(tone)) => {
+            *LAST_TONE.lock().unwrap_or_else(|e| e.into_inner()) = Some((path.to_path_buf(), tone));
+            Some(tone)
+        }
+        Ok(Err(e)) => {
+            warn!(target: "theme", "分析壁纸明暗失败 {}: {}", path.display(), e);
+            None
+        }
+        Err(e) => {
+            warn!(target: "theme", "分析壁纸明暗任务失败: {}", e);
+            None
+        }
+    }
+}
+
+/// Windows 上让托盘图标跟随壁纸明暗；离开"跟随壁纸"主题时恢复跟随系统
+#[cfg(target_os = "windows")]
+pub(crate) fn sync_tray_icon(app: &AppHandle, tone: Option<&str>) {
+    crate::tray::set_windows_tray_tone(app, tone.map(|tone| tone == "light"));
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn sync_tray_icon(_app: &AppHandle, _tone: Option<&str>) {}
+
+/// 壁纸应用成功后调用：主题跟随壁纸时分析新壁纸并通知前端
+pub(crate) fn on_wallpaper_applied(app: &AppHandle, path: &Path) {
+    let app = app.clone();
+    let path = path.to_path_buf();
+    tauri::async_runtime::spawn(async move {
+        if app.state::<AppState>().settings.read().await.theme != WALLPAPER_THEME {
+            return;
+        }
+        let Some(tone) = analyze(&path).await else {
+            return;
+        };
+        info!(target: "theme", "壁纸整体偏{}，建议使用{}主题", if tone == "light" { "亮" } else { "暗" }, tone);
+        sync_tray_icon(&app, Some(tone));
+        if let Err(e) = events::SUGGESTED_THEME.emit(&app, tone) {
+            warn!(target: "theme", "发送 suggested-theme 事件失败: {}", e);
+        }
+    });
+}
+
+/// 主题设置变更后调用：切换到"跟随壁纸"时立即按当前壁纸同步，离开时恢复托盘图标
+pub(crate) async fn on_theme_changed(app: &AppHandle, theme: &str) {
+    if theme != WALLPAPER_THEME {
+        sync_tray_icon(app, None);
+        return;
+    }
+    let current = app
+        .state::<AppState>()
+        .current_wallpaper_path
+        .lock()
+        .await
+        .clone();
+    if let Some(path) = current {
+        on_wallpaper_applied(app, &path);
+    }
+}
+
+/// 获取当前壁纸的明暗（"light" / "dark"），没有当前壁纸或分析失败时返回 None
+#[tauri::command]
+pub(crate) async fn get_wallpaper_theme(
+    state: tauri::State<'_, AppState>,
+) -> Result<Option<String>, String> {
+    let Some(path) = state.current_wallpaper_path.lock().await.clone() else {
+        return Ok(None);
+    };
+    Ok(analyze(&path).await.map(str::to_string))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    #[test]
+    fn test_classify_by_mean_brightness() {
+        let snow = RgbImage::from_pixel(320, 180, Rgb([235, 240, 245]));
+        assert_eq!(classify(&DynamicImage::ImageRgb8(snow)), "light");
+
+        let night = RgbImage::from_pixel(320, 180, Rgb([12, 18, 40]));
+        assert_eq!(classify(&DynamicImage::ImageRgb8(night)), "dark");
+
+        // 大片夜空中的一小块亮区不改变整体判断
+        let mut moon = RgbImage::from_pixel(320, 180, Rgb([20, 20, 30]));
+        for x in 0..40 {
+            for y in 0..40 {
+                moon.put_pixel(x, y, Rgb([255, 255, 240]));
+            }
+        }
+        assert_eq!(classify(&DynamicImage::ImageRgb8(moon)), "dark");
+    }
+}
@@ -0,0 +1,189 @@
+//! 壁纸切换淡入淡出（macOS）
+//!
+//! macOS 切换桌面图片时没有过渡，每日壁纸更新会在所有 Space 上突然跳变。
+//! 开启 `wallpaper_fade` 后，先把旧壁纸与新壁纸按比例混合生成一组过渡帧，
+//! 依次设为桌面图片，最后再设置真正的新壁纸。过渡帧保存在壁纸目录的
+//! `.derived/transition/` 下，每次切换前清理上一次的帧。
+
+use anyhow::{Context, Result};
+use image::RgbImage;
+use image::imageops::FilterType;
+use log::warn;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::{AppState, wallpaper_manager};
+
+/// 过渡帧数量（不含首尾两张原图）
+const FADE_STEPS: u32 = 6;
+/// 相邻两帧之间的间隔
+const FRAME_INTERVAL: Duration = Duration::from_millis(70);
+/// 过渡帧的最大宽度：帧只显示几十毫秒，降低分辨率以缩短生成时间
+const FRAME_MAX_WIDTH: u32 = 1920;
+const FRAME_JPEG_QUALITY: u8 = 85;
+
+/// 把 `from` 与 `to` 按 `t`（0.0 ~ 1.0）线性混合，两张图尺寸必须一致
+fn blend(from: &RgbImage, to: &RgbImage, t: f32) -> RgbImage {
+    let mut output = to.clone();
+    for (out, (a, b)) in output.pixels_mut().zip(from.pixels().zip(to.pixels())) {
+        for channel in 0..3 {
+            let value = f32::from(a[channel]) * (1.0 - t) + f32::from(b[channel]) * t;
+            out[channel] = value.round().clamp(0.0, 255.0) as u8;
+        }
+    }
+    output
+}
+
+/// 生成中间过渡帧（不含首尾两张原图），帧尺寸以新壁纸为准
+fn blend_frames(from: &RgbImage, to: &RgbImage, steps: u32) -> Vec<RgbImage> {
+    let from = if from.dimensions() == to.dimensions() {
+        from.clone()
+    } else {
+        image::imageops::resize(from, to.width(), to.height(), FilterType::Triangle)
+    };
+    (1..=steps)
+        .map(|step| blend(&from, to, step as f32 / (steps + 1) as f32))
+        .collect()
+}
+
+fn load_frame_source(path: &Path) -> Result<RgbImage> {
+    let image = image::open(path)
+        .with_context(|| format!("Failed to decode wallpaper: {}", path.display()))?;
+    let image = if image.width() > FRAME_MAX_WIDTH {
+        image.resize(FRAME_MAX_WIDTH, u32::MAX, FilterType::Triangle)
+    } else {
+        image
+    };
+    Ok(image.to_rgb8())
+}
+
+/// 生成过渡帧文件
+///
+/// macOS 按 URL 缓存桌面图片，因此每次切换使用带时间戳的新文件名。
+fn write_transition_frames(from: &Path, to: &Path, frames_dir: &Path) -> Result<Vec<PathBuf>> {
+    let from_image = load_frame_source(from)?;
+    let to_image = load_frame_source(to)?;
+
+    let _ = std::fs::remove_dir_all(frames_dir);
+    std::fs::create_dir_all(frames_dir).context("Failed to create transition directory")?;
+
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    blend_frames(&from_image, &to_image, FADE_STEPS)
+        .into_iter()
+        .enumerate()
+        .map(|(index, frame)| {
+            let path = frames_dir.join(format!("fade_{stamp}_{index}.jpg"));
+            let file = std::fs::File::create(&path).context("Failed to create frame file")?;
+            let mut writer = std::io::BufWriter::new(file);
+            image::codecs::jpeg::JpegEncoder::new_with_quality(&mut writer, FRAME_JPEG_QUALITY)
+                .encode_image(&frame)
+                .context("Failed to encode transition frame")?;
+            Ok(path)
+        })
+        .collect()
+}
+
+/// 设置壁纸，已开启淡入淡出且平台支持时先播放过渡帧
+///
+/// 过渡失败只记录日志，最终总会直接设置目标壁纸。Linux 上随后按设置同步锁屏和登录界面背景。
+pub(crate) async fn set_wallpaper(
+    app: &AppHandle,
+    image_path: &Path,
+    portrait_image_path: Option<&Path>,
+) -> Result<()> {
+    if cfg!(target_os = "macos") && portrait_image_path.is_none() {
+        let state = app.state::<AppState>();
+        let enabled = state.settings.read().await.wallpaper_fade;
+        let wallpaper_dir = state.wallpaper_directory.lock().await.clone();
+        if enabled && let Err(e) = play_transition(image_path, &wallpaper_dir).await {
+            warn!(target: "wallpaper", "壁纸过渡动画失败: {e}，直接设置壁纸");
+        }
+    }
+    wallpaper_manager::set_wallpaper(image_path, portrait_image_path)?;
+    #[cfg(target_os = "linux")]
+    crate::linux_greeter::apply(app, image_path).await;
+    Ok(())
+}
+
+async fn play_transition(target: &Path, wallpaper_dir: &Path) -> Result<()> {
+    let Some(current) = wallpaper_manager::get_current_wallpaper_path()? else {
+        return Ok(());
+    };
+    if !current.exists() || current == target {
+        return Ok(());
+    }
+
+    let frames_dir = wallpaper_dir.join(".derived").join("transition");
+    let target_owned = target.to_path_buf();
+    let frames = tokio::task::spawn_blocking(move || {
+        write_transition_frames(&current, &target_owned, &frames_dir)
+    })
+    .await
+    .context("Transition task failed")??;
+
+    for frame in &frames {
+        wallpaper_manager::set_wallpaper(frame, None)?;
+        tokio::time::sleep(FRAME_INTERVAL).await;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgb;
+
+    #[test]
+    fn test_blend_frames_interpolate_between_images() {
+        let from = RgbImage::from_pixel(4, 2, Rgb([0, 0, 0]));
+        let to = RgbImage::from_pixel(4, 2, Rgb([210, 140, 70]));
+
+        let frames = blend_frames(&from, &to, 6);
+        assert_eq!(frames.len(), 6);
+        assert_eq!(frames[0].get_pixel(0, 0), &Rgb([30, 20, 10]));
+        assert_eq!(frames[5].get_pixel(3, 1), &Rgb([180, 120, 60]));
+    }
+
+    #[test]
+    fn test_blend_frames_resize_source_to_target() {
+        let from = RgbImage::from_pixel(8, 8, Rgb([100, 100, 100]));
+        let to = RgbImage::from_pixel(4, 2, Rgb([100, 100, 100]));
+
+        let frames = blend_frames(&from, &to, 2);
+        assert!(frames.iter().all(|frame| frame.dimensions() == (4, 2)));
+        assert_eq!(frames[1].get_pixel(1, 1), &Rgb([100, 100, 100]));
+    }
+
+    #[test]
+    fn test_write_transition_frames_replaces_previous_frames() {
+        let unique = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("bw_transition_{unique}"));
+        std::fs::create_dir_all(&dir).unwrap();
+        let from = dir.join("20240101.jpg");
+        let to = dir.join("20240102.jpg");
+        RgbImage::from_pixel(32, 18, Rgb([0, 0, 0]))
+            .save(&from)
+            .unwrap();
+        RgbImage::from_pixel(32, 18, Rgb([255, 255, 255]))
+            .save(&to)
+            .unwrap();
+
+        let frames_dir = dir.join(".derived").join("transition");
+        std::fs::create_dir_all(&frames_dir).unwrap();
+        std::fs::write(frames_dir.join("fade_old_0.jpg"), b"stale").unwrap();
+
+        let frames = write_transition_frames(&from, &to, &frames_dir).unwrap();
+        assert_eq!(frames.len(), FADE_STEPS as usize);
+        assert!(frames.iter().all(|frame| frame.exists()));
+        assert!(!frames_dir.join("fade_old_0.jpg").exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -10,7 +10,8 @@ import React from "react";
 import App from "./App";
 import { convertFileSrc, invoke } from "@tauri-apps/api/core";
 import { listen, type Event } from "@tauri-apps/api/event";
-import { check, Update } from "@tauri-apps/plugin-updater";
+import { Update } from "@tauri-apps/plugin-updater";
+import { checkForUpdates } from "./utils/updater";
 import { ThemeProvider } from "./contexts/ThemeContext";
 import { renderWithI18n } from "./test/test-utils";
 import { LocalWallpaperRaw } from "./types";
@@ -21,9 +22,11 @@ vi.mock("@tauri-apps/plugin-opener");
 vi.mock("@tauri-apps/api/event");
 vi.mock("./utils/notification");
 vi.mock("@tauri-apps/plugin-updater", () => ({
-  check: vi.fn(),
   Update: vi.fn(),
 }));
+vi.mock("./utils/updater", () => ({
+  checkForUpdates: vi.fn(),
+}));
 vi.mock("@tauri-apps/plugin-process", () => ({
   relaunch: vi.fn().mockResolvedValue(undefined),
 }));
@@ -137,7 +140,7 @@ describe("App", () => {
     // Mock event listener
     vi.mocked(listen).mockResolvedValue(() => {});
     // Mock updater plugin (no update by default)
-    vi.mocked(check).mockResolvedValue(null);
+    vi.mocked(checkForUpdates).mockResolvedValue(null);
   });
 
   afterEach(() => {
@@ -780,7 +783,7 @@ describe("App", () => {
 
     it("should check for updates after 1 minute delay", async () => {
       const mockUpdate = createMockUpdate("0.4.6");
-      vi.mocked(check).mockResolvedValue(mockUpdate);
+      vi.mocked(checkForUpdates).mockResolvedValue(mockUpdate);
       vi.mocked(invoke).mockImplementation((cmd: string) => {
         if (cmd === "is_version_ignored") {
           return Promise.resolve(false);
@@ -811,7 +814,7 @@ describe("App", () => {
 
       // 快进 59 秒，版本检查应该还没执行
       vi.advanceTimersByTime(59000);
-      expect(check).not.toHaveBeenCalled();
+      expect(checkForUpdates).not.toHaveBeenCalled();
 
       // 快进 1 秒，到达 60 秒，版本检查应该执行
       await act(async () => {
@@ -820,12 +823,12 @@ describe("App", () => {
         await Promise.resolve();
       });
 
-      expect(check).toHaveBeenCalled();
+      expect(checkForUpdates).toHaveBeenCalled();
     });
 
     it("should display update dialog when update is available and not ignored", async () => {
       const mockUpdate = createMockUpdate("0.4.6");
-      vi.mocked(check).mockResolvedValue(mockUpdate);
+      vi.mocked(checkForUpdates).mockResolvedValue(mockUpdate);
       vi.mocked(invoke).mockImplementation((cmd: string) => {
         if (cmd === "is_version_ignored") {
           return Promise.resolve(false);
@@ -868,7 +871,7 @@ describe("App", () => {
 
     it("should not display update dialog when version is ignored", async () => {
       const mockUpdate = createMockUpdate("0.4.6");
-      vi.mocked(check).mockResolvedValue(mockUpdate);
+      vi.mocked(checkForUpdates).mockResolvedValue(mockUpdate);
       vi.mocked(invoke).mockImplementation((cmd: string) => {
         if (cmd === "is_version_ignored") {
           return Promise.resolve(true);
@@ -903,14 +906,14 @@ describe("App", () => {
         await Promise.resolve();
       });
 
-      expect(check).toHaveBeenCalled();
+      expect(checkForUpdates).toHaveBeenCalled();
       expect(
         screen.queryByText(/有新版本可用|Update Available/),
       ).not.toBeInTheDocument();
     });
 
     it("should not display update dialog when no update is available", async () => {
-      vi.mocked(check).mockResolvedValue(null);
+      vi.mocked(checkForUpdates).mockResolvedValue(null);
 
       renderWithTheme(<App />);
 
@@ -920,7 +923,7 @@ describe("App", () => {
         await Promise.resolve();
       });
 
-      expect(check).toHaveBeenCalled();
+      expect(checkForUpdates).toHaveBeenCalled();
       expect(
         screen.queryByText(/有新版本可用|Update Available/),
       ).not.toBeInTheDocument();
@@ -971,7 +974,7 @@ describe("App", () => {
         .spyOn(console, "error")
         .mockImplementation(() => {});
 
-      vi.mocked(check).mockRejectedValue(new Error("Network error"));
+      vi.mocked(checkForUpdates).mockRejectedValue(new Error("Network error"));
 
       renderWithTheme(<App />);
 
@@ -1135,7 +1138,7 @@ describe("App", () => {
 
     it("should close update dialog when Esc is pressed", async () => {
       const mockUpdate = createMockUpdate("0.4.6");
-      vi.mocked(check).mockResolvedValue(mockUpdate);
+      vi.mocked(checkForUpdates).mockResolvedValue(mockUpdate);
 
       let trayCheckUpdatesCallback:
         ((event: Event<unknown>) => void) | undefined;
@@ -1213,7 +1216,7 @@ describe("App", () => {
 
     it("should display update dialog when tray-check-updates event triggers and update found", async () => {
       const mockUpdate = createMockUpdate("0.4.6");
-      vi.mocked(check).mockResolvedValue(mockUpdate);
+      vi.mocked(checkForUpdates).mockResolvedValue(mockUpdate);
 
       let trayCheckUpdatesCallback:
         ((event: Event<unknown>) => void) | undefined;
@@ -1279,7 +1282,7 @@ describe("App", () => {
     });
 
     it("should show system notification when tray-check-updates finds no update", async () => {
-      vi.mocked(check).mockResolvedValue(null);
+      vi.mocked(checkForUpdates).mockResolvedValue(null);
 
       let trayCheckUpdatesCallback:
         ((event: Event<unknown>) => void) | undefined;
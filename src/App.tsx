@@ -18,9 +18,12 @@ import { listen } from "@tauri-apps/api/event";
 import { openPath } from "@tauri-apps/plugin-opener";
 import { version } from "../package.json";
 import { getStandardIconProps } from "./config/icons";
+import { EVENTS } from "./config/ui";
 import { useI18n } from "./i18n/I18nContext";
 import { useUpdateCheck } from "./hooks/useUpdateCheck";
 import { useTrayEvents } from "./hooks/useTrayEvents";
+import { useDirectoryPermission } from "./hooks/useDirectoryPermission";
+import { useLowDiskSpace } from "./hooks/useLowDiskSpace";
 import { cn } from "./utils/cn";
 import { createSafeUnlisten } from "./utils/eventListener";
 import styles from "./App.module.css";
@@ -134,7 +137,7 @@ function App() {
     (async () => {
       try {
         const unlistenFn = await listen<string>(
-          "current-wallpaper-changed",
+          EVENTS.CURRENT_WALLPAPER_CHANGED,
           (event) => {
             if (mounted && event.payload) {
               setCurrentWallpaperPath(event.payload);
@@ -180,6 +183,16 @@ function App() {
     onOpenFolder: handleOpenFolder,
   });
 
+  // 壁纸目录无写入权限时，确认后切回默认目录并刷新显示的目录
+  useDirectoryPermission(() => {
+    invoke<string>("get_wallpaper_directory")
+      .then(setWallpaperDirectory)
+      .catch((err) => console.error("Failed to get wallpaper directory:", err));
+  });
+
+  // 磁盘空间不足时提示用户（后台下载已暂停）
+  useLowDiskSpace();
+
   // 键盘快捷键支持（使用 ref 避免频繁重新绑定事件监听器）
   const showSettingsRef = useRef(showSettings);
   const showAboutRef = useRef(showAbout);
@@ -38,6 +38,42 @@ describe("Settings", () => {
     language: "zh-CN" as const,
     resolved_language: "zh-CN" as const,
     mkt: "zh-CN" as const,
+    archive_backfill_enabled: false,
+    download_resolution: "auto",
+    local_folder: null,
+    local_folder_order: "mtime",
+    local_folder_schedule: "off",
+    wallpaper_fade: false,
+    tray_left_click: "toggle_window",
+    idle_prefetch: false,
+    move_to_trash: true,
+    update_channel: "stable",
+    defer_downloads_on_battery: false,
+    enable_portrait_variant: true,
+    low_disk_space_threshold_mb: 500,
+    virtual_desktop_mode: "all",
+    index_backup_interval_days: 7,
+    custom_ca_path: null,
+    custom_ca_only: false,
+    quit_behavior: "ask",
+    extension_events: false,
+    tray_double_click: "none",
+    solar_schedule: "off",
+    solar_latitude: null,
+    solar_longitude: null,
+    solar_day_wallpaper: null,
+    solar_night_wallpaper: null,
+    jpeg_quality: 0,
+    spotlight_schedule: "off",
+    osd_duration_secs: 0,
+    osd_position: 'top_right',
+    http_keep_alive_secs: 90,
+    placeholder_wallpaper: false,
+    bing_only_network: false,
+    lock_screen_wallpaper: false,
+    greeter_background: "off",
+    sddm_theme_dir: null,
+    apply_hook: null,
   };
   const mockWallpaperDataStats = {
     count: 3,
@@ -91,6 +127,7 @@ describe("Settings", () => {
       settings: mockSettings,
       loading: false,
       error: null,
+      fieldErrors: {},
       fetchSettings: vi.fn(),
       updateSettings: mockUpdateSettings,
       getDefaultDirectory: mockGetDefaultDirectory,
@@ -151,6 +188,33 @@ describe("Settings", () => {
     });
   });
 
+  it("should make settings locked by policy read-only", async () => {
+    const defaultInvoke = vi.mocked(invoke).getMockImplementation();
+    vi.mocked(invoke).mockImplementation((cmd: string, args?: unknown) => {
+      if (cmd === "get_settings_policy") {
+        return Promise.resolve({
+          path: "/etc/bing-wallpaper-now/policy.json",
+          locked: ["auto_update"],
+        });
+      }
+      return defaultInvoke!(cmd, args as never);
+    });
+
+    renderWithTheme(<Settings onClose={mockOnClose} />);
+
+    expect(
+      await screen.findByText(
+        "部分设置由管理员统一配置，无法修改",
+        {},
+        { timeout: 3000 },
+      ),
+    ).toBeInTheDocument();
+    const checkbox = screen.getByLabelText(/自动应用新壁纸/i);
+    expect(checkbox).toBeDisabled();
+    fireEvent.click(checkbox);
+    expect(mockUpdateSettings).not.toHaveBeenCalled();
+  });
+
   it("should toggle new-wallpaper notification checkbox", async () => {
     renderWithTheme(<Settings onClose={mockOnClose} />);
 
@@ -322,6 +386,43 @@ describe("Settings", () => {
     });
   });
 
+  it("should switch to another profile", async () => {
+    const defaultInvoke = vi.mocked(invoke).getMockImplementation();
+    vi.mocked(invoke).mockImplementation((cmd: string, args?: unknown) => {
+      if (cmd === "get_profiles") {
+        return Promise.resolve({
+          profiles: [
+            {
+              name: "工作",
+              save_directory: null,
+              mkt: "zh-CN",
+              download_resolution: "auto",
+            },
+            {
+              name: "家里",
+              save_directory: "/Users/Test/Home",
+              mkt: "en-US",
+              download_resolution: "UHD",
+            },
+          ],
+          active: "工作",
+        });
+      }
+      return defaultInvoke!(cmd, args as never);
+    });
+
+    renderWithTheme(<Settings onClose={mockOnClose} />);
+
+    await screen.findByText("家里", {}, { timeout: 3000 });
+    const switchButtons = screen.getAllByRole("button", { name: "切换" });
+    expect(switchButtons[0]).toBeDisabled();
+    fireEvent.click(switchButtons[1]);
+
+    await waitFor(() => {
+      expect(invoke).toHaveBeenCalledWith("switch_profile", { name: "家里" });
+    });
+  });
+
   it("should handle folder selection error", async () => {
     const mockOpen = vi.fn().mockRejectedValue(new Error("Permission denied"));
     vi.mocked(dialog.open).mockImplementation(mockOpen);
@@ -356,6 +457,7 @@ describe("Settings", () => {
       settings: { ...mockSettings, save_directory: "/custom/folder" },
       loading: false,
       error: null,
+      fieldErrors: {},
       fetchSettings: vi.fn(),
       updateSettings: mockUpdateSettings,
       getDefaultDirectory: mockGetDefaultDirectory,
@@ -370,12 +472,31 @@ describe("Settings", () => {
     expect(screen.getByText(/恢复默认目录/i)).toBeInTheDocument();
   });
 
+  it("should show field errors returned by settings validation", async () => {
+    vi.spyOn(useSettingsModule, "useSettings").mockReturnValue({
+      settings: { ...mockSettings, save_directory: "/custom/folder" },
+      loading: false,
+      error: "save_directory: NOT_DIRECTORY",
+      fieldErrors: { save_directory: "NOT_DIRECTORY" },
+      fetchSettings: vi.fn(),
+      updateSettings: mockUpdateSettings,
+      getDefaultDirectory: mockGetDefaultDirectory,
+    });
+
+    renderWithTheme(<Settings onClose={mockOnClose} />);
+
+    expect(
+      await screen.findByText("该路径不是可用的文件夹"),
+    ).toBeInTheDocument();
+  });
+
   it("should restore default directory when restore button clicked", async () => {
     // Mock settings with custom directory
     vi.spyOn(useSettingsModule, "useSettings").mockReturnValue({
       settings: { ...mockSettings, save_directory: "/custom/folder" },
       loading: false,
       error: null,
+      fieldErrors: {},
       fetchSettings: vi.fn(),
       updateSettings: mockUpdateSettings,
       getDefaultDirectory: mockGetDefaultDirectory,
@@ -538,6 +659,7 @@ describe("Settings", () => {
       settings: null,
       loading: true,
       error: null,
+      fieldErrors: {},
       fetchSettings: vi.fn(),
       updateSettings: mockUpdateSettings,
       getDefaultDirectory: mockGetDefaultDirectory,
@@ -636,6 +758,73 @@ describe("Settings", () => {
     });
   });
 
+  it("should show degraded notice when market keeps failing", async () => {
+    vi.mocked(invoke).mockImplementation((cmd: string) => {
+      if (cmd === "get_settings") {
+        return Promise.resolve(mockSettings);
+      }
+      if (cmd === "get_market_status") {
+        return Promise.resolve({
+          requested_mkt: "en-US",
+          effective_mkt: "en-US",
+          is_mismatch: false,
+          is_degraded: true,
+        });
+      }
+      if (cmd === "get_wallpaper_data_stats") {
+        return Promise.resolve(mockWallpaperDataStats);
+      }
+      return Promise.resolve(undefined);
+    });
+
+    renderWithTheme(<Settings onClose={mockOnClose} />);
+
+    expect(
+      await screen.findByText(/en-US 市场近期多次获取壁纸失败/),
+    ).toBeInTheDocument();
+  });
+
+  // ─── 首次启动市场建议 ───
+
+  it("should apply market suggestion only after user confirms", async () => {
+    vi.mocked(invoke).mockImplementation((cmd: string) => {
+      if (cmd === "get_settings") {
+        return Promise.resolve(mockSettings);
+      }
+      if (cmd === "get_mkt_suggestion") {
+        return Promise.resolve({
+          mkt: "ja-JP",
+          region: "JP",
+          source: "geo",
+          status: "pending",
+        });
+      }
+      if (cmd === "get_wallpaper_data_stats") {
+        return Promise.resolve(mockWallpaperDataStats);
+      }
+      return Promise.resolve(undefined);
+    });
+
+    renderWithTheme(<Settings onClose={mockOnClose} />);
+
+    expect(
+      await screen.findByText(/建议使用 ja-JP 市场/),
+    ).toBeInTheDocument();
+    expect(invoke).not.toHaveBeenCalledWith(
+      "respond_mkt_suggestion",
+      expect.anything(),
+    );
+
+    fireEvent.click(screen.getByText("切换"));
+
+    await waitFor(() => {
+      expect(invoke).toHaveBeenCalledWith("respond_mkt_suggestion", {
+        accept: true,
+      });
+    });
+    expect(screen.queryByText(/建议使用 ja-JP 市场/)).not.toBeInTheDocument();
+  });
+
   // ─── mkt 切换时序 ───
 
   it("should await settings save before triggering refresh on mkt change", async () => {
@@ -1040,4 +1229,113 @@ describe("Settings", () => {
       expect.anything(),
     );
   });
+
+  // ─── 远程备份 ───
+
+  it("should save WebDAV backup settings with the password", async () => {
+    mockInvokeWith((cmd) => {
+      if (cmd === "get_backup_config") {
+        return Promise.resolve({ enabled: false, target: { kind: "none" } });
+      }
+    });
+
+    renderWithTheme(<Settings onClose={mockOnClose} />);
+
+    const select = await screen.findByLabelText(
+      "远程备份",
+      {},
+      { timeout: 3000 },
+    );
+    fireEvent.change(select, { target: { value: "web_dav" } });
+    fireEvent.change(screen.getByLabelText("WebDAV 地址"), {
+      target: { value: "https://dav.example.com/bing" },
+    });
+    fireEvent.change(screen.getByLabelText("用户名"), {
+      target: { value: "me" },
+    });
+    fireEvent.change(screen.getByLabelText("密码"), {
+      target: { value: "secret" },
+    });
+    fireEvent.click(screen.getByLabelText("每次更新后自动备份"));
+    fireEvent.click(screen.getByRole("button", { name: "保存" }));
+
+    await waitFor(() => {
+      expect(invoke).toHaveBeenCalledWith("set_backup_config", {
+        config: {
+          enabled: true,
+          target: {
+            kind: "web_dav",
+            url: "https://dav.example.com/bing",
+            username: "me",
+          },
+        },
+        secret: "secret",
+      });
+    });
+    expect(await screen.findByText("备份设置已保存")).toBeInTheDocument();
+  });
+
+  it("should show the backup report after backing up", async () => {
+    mockInvokeWith((cmd) => {
+      if (cmd === "get_backup_config") {
+        return Promise.resolve({
+          enabled: true,
+          target: {
+            kind: "web_dav",
+            url: "https://dav.example.com",
+            username: "me",
+          },
+        });
+      }
+      if (cmd === "backup_now") {
+        return Promise.resolve({ uploaded: 3, skipped: 10, failed: [] });
+      }
+    });
+
+    renderWithTheme(<Settings onClose={mockOnClose} />);
+
+    fireEvent.click(
+      await screen.findByRole(
+        "button",
+        { name: "立即备份" },
+        { timeout: 3000 },
+      ),
+    );
+
+    expect(
+      await screen.findByText("已上传 3 个文件，跳过 10 个未变化的文件"),
+    ).toBeInTheDocument();
+  });
+
+  it("should show busy message while a backup is running", async () => {
+    mockInvokeWith((cmd) => {
+      if (cmd === "get_backup_config") {
+        return Promise.resolve({
+          enabled: false,
+          target: {
+            kind: "web_dav",
+            url: "https://dav.example.com",
+            username: "me",
+          },
+        });
+      }
+      if (cmd === "backup_now") {
+        return Promise.reject("BACKUP_IN_PROGRESS");
+      }
+    });
+
+    renderWithTheme(<Settings onClose={mockOnClose} />);
+
+    fireEvent.click(
+      await screen.findByRole(
+        "button",
+        { name: "立即备份" },
+        { timeout: 3000 },
+      ),
+    );
+
+    expect(
+      await screen.findByText("备份正在进行，请稍后再试"),
+    ).toBeInTheDocument();
+  });
 });
@@ -1,11 +1,19 @@
 import { useState, useEffect, useCallback } from "react";
 import {
   AppSettings,
+  BackupConfig,
+  BackupReport,
+  BackupTarget,
   MarketStatus,
+  MarketProbeResult,
   MarketGroup,
+  MktSuggestion,
+  ProfilesConfig,
+  SettingsFieldError,
+  SettingsPolicyStatus,
   WallpaperDataStats,
 } from "../types";
-import { useSettings } from "../hooks/useSettings";
+import { describeSettingsError, useSettings } from "../hooks/useSettings";
 import { useTheme, Theme } from "../contexts/ThemeContext";
 import { useI18n } from "../i18n/I18nContext";
 import { showSystemNotification } from "../utils/notification";
@@ -16,7 +24,7 @@ import {
   buildTransferMessage,
   buildTransferErrorMessage,
 } from "../utils/transferHelpers";
-import { open } from "@tauri-apps/plugin-dialog";
+import { ask, open } from "@tauri-apps/plugin-dialog";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import { openPath } from "@tauri-apps/plugin-opener";
@@ -34,6 +42,44 @@ interface SettingsProps {
   onLanguageChange?: () => void;
 }
 
+/** 市场下拉框中"自定义"选项的值 */
+const CUSTOM_MARKET = "__custom__";
+
+/** 磁盘空间阈值下拉框的可选值（MB），0 表示不检查单独列出 */
+const LOW_DISK_SPACE_THRESHOLDS_MB = [200, 500, 1024, 2048];
+
+/** 开启重新压缩时的默认 JPEG 质量 */
+const DEFAULT_JPEG_QUALITY = 85;
+/** 开启壁纸信息浮层时的默认显示秒数 */
+const DEFAULT_OSD_DURATION_SECS = 4;
+
+/** 切换备份目标类型时使用的空白目标 */
+function emptyBackupTarget(kind: BackupTarget["kind"]): BackupTarget {
+  switch (kind) {
+    case "web_dav":
+      return { kind, url: "", username: "" };
+    case "s3":
+      return {
+        kind,
+        endpoint: "",
+        bucket: "",
+        region: "",
+        access_key_id: "",
+        prefix: "",
+        path_style: true,
+      };
+    default:
+      return { kind: "none" };
+  }
+}
+
+/** 将自定义市场规范为 ll-CC 形式，格式不正确时返回 null */
+function normalizeCustomMarket(value: string): string | null {
+  const match = /^([a-z]{2,3})-([a-z]{2})$/i.exec(value.trim());
+  if (!match) return null;
+  return `${match[1].toLowerCase()}-${match[2].toUpperCase()}`;
+}
+
 function formatEndDate(value: string | null): string | null {
   if (!value) return null;
 
@@ -48,17 +94,33 @@ export function Settings({
   version,
   onLanguageChange,
 }: SettingsProps) {
-  const { settings, loading, updateSettings, getDefaultDirectory } =
-    useSettings();
+  const {
+    settings,
+    loading,
+    fieldErrors,
+    autostartStatus,
+    fetchSettings,
+    updateSettings,
+    getDefaultDirectory,
+  } = useSettings();
   const { applyThemeToUI } = useTheme();
   const { t, setLanguage } = useI18n();
 
   const [defaultDir, setDefaultDir] = useState<string>("");
   const [marketGroups, setMarketGroups] = useState<MarketGroup[]>([]);
   const [marketStatus, setMarketStatus] = useState<MarketStatus | null>(null);
+  const [marketProbe, setMarketProbe] = useState<MarketProbeResult | null>(
+    null,
+  );
   // dismiss 只控制当前打开的 Settings 面板内是否隐藏警告，
   // 下次重新打开 Settings 会重新 pull，如果仍然 mismatch 则重新显示
   const [dismissed, setDismissed] = useState(false);
+  const [mktSuggestion, setMktSuggestion] = useState<MktSuggestion | null>(
+    null,
+  );
+  const [customMarketOpen, setCustomMarketOpen] = useState(false);
+  const [customMarket, setCustomMarket] = useState("");
+  const [customMarketInvalid, setCustomMarketInvalid] = useState(false);
 
   const [importing, setImporting] = useState(false);
   const [importMessage, setImportMessage] = useState<TransferMessage | null>(
@@ -76,6 +138,27 @@ export function Settings({
   const [wallpaperDataStats, setWallpaperDataStats] =
     useState<WallpaperDataStats | null>(null);
   const [wallpaperDataStatsError, setWallpaperDataStatsError] = useState(false);
+  const [profiles, setProfiles] = useState<ProfilesConfig | null>(null);
+  const [profileName, setProfileName] = useState("");
+  const [profileError, setProfileError] = useState<string | null>(null);
+  const [policy, setPolicy] = useState<SettingsPolicyStatus | null>(null);
+  const [extensionEndpoint, setExtensionEndpoint] = useState<string | null>(
+    null,
+  );
+  const [archiveReadOnly, setArchiveReadOnly] = useState(false);
+  const [spotlightImport, setSpotlightImport] = useState<
+    number | "failed" | null
+  >(null);
+  const [networkReset, setNetworkReset] = useState<"done" | "failed" | null>(
+    null,
+  );
+  // 备份配置在本地编辑，点击保存后才写入后端
+  const [backupConfig, setBackupConfig] = useState<BackupConfig | null>(null);
+  const [backupSecret, setBackupSecret] = useState("");
+  const [backingUp, setBackingUp] = useState(false);
+  const [backupMessage, setBackupMessage] = useState<TransferMessage | null>(
+    null,
+  );
 
   useEffect(() => {
     getDefaultDirectory()
@@ -112,14 +195,58 @@ export function Settings({
     }
   }, []);
 
+  // 切换 mkt 时探测该市场是否会被 Bing 重定向，提前给出提示
+  const probeMarket = useCallback(async (mkt: string) => {
+    setMarketProbe(null);
+    try {
+      const probe = await invoke<MarketProbeResult>(
+        "probe_market_availability",
+        { mkt },
+      );
+      setMarketProbe(probe ?? null);
+    } catch (err) {
+      console.error("Failed to probe market availability:", err);
+    }
+  }, []);
+
+  const fetchMktSuggestion = useCallback(async () => {
+    try {
+      const suggestion = await invoke<MktSuggestion | null>(
+        "get_mkt_suggestion",
+      );
+      setMktSuggestion(suggestion ?? null);
+    } catch (err) {
+      console.error("Failed to fetch market suggestion:", err);
+    }
+  }, []);
+
+  // 接受或忽略首次启动的市场建议，二者都只处理一次
+  const respondMktSuggestion = useCallback(
+    async (accept: boolean) => {
+      setMktSuggestion(null);
+      try {
+        await invoke("respond_mkt_suggestion", { accept });
+        if (accept) {
+          await fetchSettings();
+          await fetchMarketStatus();
+          onLanguageChange?.();
+        }
+      } catch (err) {
+        console.error("Failed to respond to market suggestion:", err);
+      }
+    },
+    [fetchSettings, fetchMarketStatus, onLanguageChange],
+  );
+
   // Settings 打开时从后端加载市场列表和 mkt 状态
   useEffect(() => {
     fetchMarketStatus();
+    fetchMktSuggestion();
     fetchWallpaperDataStats();
     invoke<MarketGroup[]>("get_supported_mkts")
       .then(setMarketGroups)
       .catch((err) => console.error("Failed to load market groups:", err));
-  }, [fetchMarketStatus, fetchWallpaperDataStats]);
+  }, [fetchMarketStatus, fetchMktSuggestion, fetchWallpaperDataStats]);
 
   // 监听 mkt-status-changed 事件，收到后重新 pull
   useEffect(() => {
@@ -157,7 +284,7 @@ export function Settings({
 
     (async () => {
       try {
-        const unlistenFn = await listen("wallpaper-updated", () => {
+        const unlistenFn = await listen(EVENTS.WALLPAPER_UPDATED, () => {
           if (mounted) {
             fetchWallpaperDataStats();
           }
@@ -180,6 +307,125 @@ export function Settings({
     };
   }, [fetchWallpaperDataStats]);
 
+  const fetchProfiles = useCallback(async () => {
+    try {
+      setProfiles(await invoke<ProfilesConfig>("get_profiles"));
+    } catch (err) {
+      console.error("Failed to fetch profiles:", err);
+    }
+  }, []);
+
+  useEffect(() => {
+    fetchProfiles();
+  }, [fetchProfiles]);
+
+  // 管理员策略锁定的设置显示为只读
+  useEffect(() => {
+    invoke<SettingsPolicyStatus>("get_settings_policy")
+      .then(setPolicy)
+      .catch((err) => console.error("Failed to fetch settings policy:", err));
+  }, []);
+
+  // 开启扩展事件后展示端点地址，方便配置外部工具
+  const extensionEventsEnabled = settings?.extension_events ?? false;
+  useEffect(() => {
+    if (!extensionEventsEnabled) return;
+    invoke<string | null>("get_extension_endpoint")
+      .then(setExtensionEndpoint)
+      .catch((err) =>
+        console.error("Failed to fetch extension endpoint:", err),
+      );
+  }, [extensionEventsEnabled]);
+
+  // 壁纸目录为只读存档时处于浏览模式，切换目录后重新检查
+  const saveDirectory = settings?.save_directory;
+  useEffect(() => {
+    invoke<boolean>("get_archive_read_only")
+      .then(setArchiveReadOnly)
+      .catch((err) =>
+        console.error("Failed to check archive read-only mode:", err),
+      );
+  }, [saveDirectory]);
+
+  useEffect(() => {
+    invoke<BackupConfig>("get_backup_config")
+      .then(setBackupConfig)
+      .catch((err) => console.error("Failed to fetch backup config:", err));
+  }, []);
+
+  const isLocked = (field: keyof AppSettings) =>
+    policy?.locked.includes(field) ?? false;
+
+  // 从托盘切换配置方案后，后端设置已变化，重新获取
+  useEffect(() => {
+    let mounted = true;
+    let unlisten: (() => void) | undefined;
+
+    (async () => {
+      try {
+        const unlistenFn = await listen(EVENTS.SETTINGS_CHANGED, () => {
+          if (mounted) {
+            fetchSettings();
+            fetchProfiles();
+          }
+        });
+        const safeUnlisten = createSafeUnlisten(unlistenFn);
+
+        if (mounted) {
+          unlisten = safeUnlisten;
+        } else {
+          safeUnlisten();
+        }
+      } catch (e) {
+        console.error("Failed to bind settings-changed event:", e);
+      }
+    })();
+
+    return () => {
+      mounted = false;
+      unlisten?.();
+    };
+  }, [fetchSettings, fetchProfiles]);
+
+  const handleSaveProfile = async () => {
+    try {
+      setProfiles(
+        await invoke<ProfilesConfig>("save_profile", { name: profileName }),
+      );
+      setProfileName("");
+      setProfileError(null);
+    } catch (err) {
+      setProfileError(
+        err === "INVALID_NAME" ? t("profileInvalidName") : String(err),
+      );
+    }
+  };
+
+  const handleSwitchProfile = async (name: string) => {
+    try {
+      await invoke("switch_profile", { name });
+      setProfileError(null);
+      await Promise.all([
+        fetchSettings(),
+        fetchProfiles(),
+        fetchWallpaperDataStats(),
+      ]);
+    } catch (err) {
+      setProfileError(
+        err === "UPDATE_IN_PROGRESS" ? t("profileSwitchBusy") : String(err),
+      );
+    }
+  };
+
+  const handleDeleteProfile = async (name: string) => {
+    try {
+      setProfiles(await invoke<ProfilesConfig>("delete_profile", { name }));
+      setProfileError(null);
+    } catch (err) {
+      setProfileError(String(err));
+    }
+  };
+
   // 后端 region ID → 翻译 key 映射
   const regionI18nKey: Record<string, Parameters<typeof t>[0]> = {
     asia_pacific: "marketRegionAsiaPacific",
@@ -188,11 +434,35 @@ export function Settings({
     africa: "marketRegionAfrica",
   };
 
+  // 后端字段校验错误码 → 翻译 key 映射
+  const fieldErrorI18nKey: Record<
+    SettingsFieldError,
+    Parameters<typeof t>[0]
+  > = {
+    NOT_ABSOLUTE: "settingsFieldNotAbsolute",
+    NOT_DIRECTORY: "settingsFieldNotDirectory",
+    NOT_FILE: "settingsFieldNotFile",
+    INVALID_CERTIFICATE: "settingsFieldInvalidCertificate",
+    INVALID_MKT: "settingsFieldInvalidMkt",
+    INVALID_VALUE: "settingsFieldInvalidValue",
+    INVALID_END_DATE: "settingsFieldInvalidEndDate",
+  };
+
+  const renderFieldError = (field: keyof AppSettings) => {
+    const code = fieldErrors[field];
+    if (!code) return null;
+    return (
+      <div className={styles.fieldError} role="alert">
+        {t(fieldErrorI18nKey[code])}
+      </div>
+    );
+  };
+
   const handleChange = async (
     field: keyof AppSettings,
     value: string | number | boolean | null,
   ) => {
-    if (!settings) return;
+    if (!settings || isLocked(field)) return;
 
     try {
       const updatedSettings = { ...settings, [field]: value };
@@ -216,11 +486,40 @@ export function Settings({
       console.error("Update settings error:", err);
       await showSystemNotification(
         t("settingsSaveError"),
-        t("settingsSaveError") + ": " + err,
+        t("settingsSaveError") + ": " + describeSettingsError(err),
       );
     }
   };
 
+  // 市场不在预置列表中时（用户自定义），下拉框显示"自定义"并展示输入框
+  const isKnownMarket = marketGroups.some((group) =>
+    group.markets.some((m) => m.code === settings?.mkt),
+  );
+  const showCustomMarket =
+    customMarketOpen || (marketGroups.length > 0 && !isKnownMarket);
+
+  const applyMarket = async (mkt: string) => {
+    await handleChange("mkt", mkt);
+    await fetchMarketStatus();
+    void probeMarket(mkt);
+    if (onLanguageChange) {
+      onLanguageChange();
+    }
+  };
+
+  const submitCustomMarket = async () => {
+    if (!customMarketOpen) return;
+    const mkt = normalizeCustomMarket(customMarket);
+    if (!mkt) {
+      setCustomMarketInvalid(true);
+      return;
+    }
+    setCustomMarketOpen(false);
+    if (mkt !== settings?.mkt) {
+      await applyMarket(mkt);
+    }
+  };
+
   const handleSelectFolder = async () => {
     if (!settings) return;
 
@@ -271,6 +570,170 @@ export function Settings({
     }
   };
 
+  const handleSelectLocalFolder = async () => {
+    if (!settings) return;
+
+    try {
+      const selected = await open({
+        directory: true,
+        multiple: false,
+        defaultPath: settings.local_folder ?? undefined,
+        title: t("localFolderSelect"),
+      });
+
+      if (selected && typeof selected === "string") {
+        await handleChange("local_folder", selected);
+      }
+    } catch (err) {
+      console.error("Failed to select local folder:", err);
+      await showSystemNotification(
+        t("settingsFolderSelectError"),
+        t("settingsFolderSelectError") + ": " + String(err),
+      );
+    }
+  };
+
+  const handleSelectApplyHook = async () => {
+    if (!settings) return;
+
+    try {
+      const selected = await open({
+        directory: false,
+        multiple: false,
+        defaultPath: settings.apply_hook ?? undefined,
+        title: t("applyHookSelect"),
+      });
+      if (!selected || typeof selected !== "string") return;
+
+      // 钩子会在每次换壁纸时运行任意程序，必须由用户明确确认
+      const confirmed = await ask(
+        t("applyHookConfirm").replace("{path}", selected),
+        { title: t("applyHook"), kind: "warning" },
+      );
+      if (!confirmed) return;
+      await invoke("confirm_apply_hook", { path: selected });
+      await handleChange("apply_hook", selected);
+    } catch (err) {
+      console.error("Failed to select apply hook:", err);
+    }
+  };
+
+  const handleImportSpotlight = async () => {
+    try {
+      setSpotlightImport(await invoke<number>("import_spotlight_images"));
+    } catch (err) {
+      console.error("Failed to import Spotlight images:", err);
+      setSpotlightImport("failed");
+    }
+  };
+
+  const handleResetNetwork = async () => {
+    try {
+      await invoke("reset_network");
+      setNetworkReset("done");
+    } catch (err) {
+      console.error("Failed to reset network:", err);
+      setNetworkReset("failed");
+    }
+  };
+
+  const updateBackupTarget = (patch: Record<string, string | boolean>) => {
+    setBackupConfig((config) =>
+      config
+        ? { ...config, target: { ...config.target, ...patch } as BackupTarget }
+        : config,
+    );
+  };
+
+  const handleSaveBackup = async () => {
+    if (!backupConfig) return;
+    try {
+      await invoke("set_backup_config", {
+        config: backupConfig,
+        secret: backupSecret || null,
+      });
+      setBackupSecret("");
+      setBackupMessage({ type: "success", text: t("backupSaved") });
+    } catch (err) {
+      setBackupMessage({
+        type: "error",
+        text: `${t("backupSaveError")}: ${String(err)}`,
+      });
+    }
+  };
+
+  const handleBackupNow = async () => {
+    setBackingUp(true);
+    setBackupMessage(null);
+    try {
+      const report = await invoke<BackupReport>("backup_now");
+      const summary = t("backupResult")
+        .replace("{uploaded}", String(report.uploaded))
+        .replace("{skipped}", String(report.skipped));
+      setBackupMessage(
+        report.failed.length === 0
+          ? { type: "success", text: summary }
+          : {
+              type: "error",
+              text:
+                summary +
+                t("warningSeparator") +
+                t("backupResultFailed")
+                  .replace("{count}", String(report.failed.length))
+                  .replace("{files}", report.failed.join(", ")),
+            },
+      );
+    } catch (err) {
+      const text =
+        err === "BUSY" || err === "BACKUP_IN_PROGRESS"
+          ? t("backupBusy")
+          : err === "BACKUP_NOT_CONFIGURED"
+            ? t("backupNotConfigured")
+            : `${t("backupError")}: ${String(err)}`;
+      setBackupMessage({ type: "error", text });
+    } finally {
+      setBackingUp(false);
+    }
+  };
+
+  const renderBackupField = (field: string, label: Parameters<typeof t>[0]) => {
+    const target: Record<string, unknown> = backupConfig?.target ?? {};
+    return (
+      <div className={styles.settingRow} key={field}>
+        <span className={styles.label}>{t(label)}</span>
+        <div className={styles.settingControl}>
+          <input
+            className={styles.input}
+            type="text"
+            aria-label={t(label)}
+            value={String(target[field] ?? "")}
+            onChange={(e) => updateBackupTarget({ [field]: e.target.value })}
+          />
+        </div>
+      </div>
+    );
+  };
+
+  const handleSelectCustomCa = async () => {
+    if (!settings) return;
+
+    try {
+      const selected = await open({
+        directory: false,
+        multiple: false,
+        defaultPath: settings.custom_ca_path ?? undefined,
+        title: t("customCaSelect"),
+        filters: [{ name: "PEM", extensions: ["pem", "crt", "cer"] }],
+      });
+
+      if (selected && typeof selected === "string") {
+        await handleChange("custom_ca_path", selected);
+      }
+    } catch (err) {
+      console.error("Failed to select CA bundle:", err);
+    }
+  };
+
   const handleTransfer = async (
     command: string,
     paramKey: string,
@@ -318,6 +781,8 @@ export function Settings({
         metadataSkipped: t("importMetadataSkipped"),
         imagesFailed: t("importImagesFailed"),
         notDirectory: t("transferNotDirectory"),
+        busy: t("transferBusy"),
+        readOnly: t("transferReadOnly"),
         sameDirectory: t("importSameDirectory"),
         noData: t("importNoData"),
         error: t("importError"),
@@ -338,6 +803,8 @@ export function Settings({
         metadataSkipped: t("exportMetadataSkipped"),
         imagesFailed: t("exportImagesFailed"),
         notDirectory: t("transferNotDirectory"),
+        busy: t("transferBusy"),
+        readOnly: t("transferReadOnly"),
         sameDirectory: t("exportSameDirectory"),
         noData: t("exportNoData"),
         error: t("exportError"),
@@ -396,11 +863,17 @@ export function Settings({
         </div>
 
         <div className={modalStyles.body}>
+          {policy && policy.locked.length > 0 && (
+            <div className={styles.mktWarning} title={policy.path ?? ""}>
+              <span>{t("settingsManagedByPolicy")}</span>
+            </div>
+          )}
           <section className={styles.settingsGroup}>
             <h3 className={styles.groupTitle}>{t("settingsGroupGeneral")}</h3>
             <div className={styles.settingRow}>
               <span className={styles.label}>{t("launchAtStartup")}</span>
               <input
+                disabled={isLocked("launch_at_startup")}
                 className={styles.switch}
                 type="checkbox"
                 aria-label={t("launchAtStartup")}
@@ -410,9 +883,22 @@ export function Settings({
                 }
               />
             </div>
+            {autostartStatus && (
+              <div className={styles.mktWarning} title={autostartStatus.reason}>
+                <span>
+                  {autostartStatus.status === "unsupported"
+                    ? t("autostartUnsupported")
+                    : t("autostartFailed").replace(
+                        "{reason}",
+                        autostartStatus.reason,
+                      )}
+                </span>
+              </div>
+            )}
             <div className={styles.settingRow}>
               <span className={styles.label}>{t("autoUpdate")}</span>
               <input
+                disabled={isLocked("auto_update")}
                 className={styles.switch}
                 type="checkbox"
                 aria-label={t("autoUpdate")}
@@ -443,6 +929,7 @@ export function Settings({
                   </button>
                 </div>
                 <input
+                  disabled={isLocked("new_wallpaper_notification")}
                   className={styles.switch}
                   type="checkbox"
                   aria-label={t("newWallpaperNotification")}
@@ -473,6 +960,7 @@ export function Settings({
                     { value: "system", label: t("themeSystem") },
                     { value: "light", label: t("themeLight") },
                     { value: "dark", label: t("themeDark") },
+                    { value: "wallpaper", label: t("themeWallpaper") },
                   ]}
                   value={(settings?.theme ?? "system") as string}
                   onChange={(v) => handleChange("theme", v as Theme)}
@@ -504,13 +992,24 @@ export function Settings({
                 <div className={styles.settingControl}>
                   <select
                     className={styles.select}
-                    value={settings?.mkt ?? "zh-CN"}
+                    disabled={isLocked("mkt")}
+                    value={
+                      showCustomMarket
+                        ? CUSTOM_MARKET
+                        : (settings?.mkt ?? "zh-CN")
+                    }
                     onChange={async (e) => {
-                      await handleChange("mkt", e.target.value);
-                      await fetchMarketStatus();
-                      if (onLanguageChange) {
-                        onLanguageChange();
+                      const mkt = e.target.value;
+                      if (mkt === CUSTOM_MARKET) {
+                        setCustomMarket(
+                          isKnownMarket ? "" : (settings?.mkt ?? ""),
+                        );
+                        setCustomMarketInvalid(false);
+                        setCustomMarketOpen(true);
+                        return;
                       }
+                      setCustomMarketOpen(false);
+                      await applyMarket(mkt);
                     }}
                   >
                     {marketGroups.map((group) => (
@@ -525,10 +1024,66 @@ export function Settings({
                         ))}
                       </optgroup>
                     ))}
+                    <option value={CUSTOM_MARKET}>{t("marketCustom")}</option>
                   </select>
                 </div>
               </div>
+              {showCustomMarket && (
+                <div className={styles.settingRow}>
+                  <span className={styles.label} />
+                  <div className={styles.settingControl}>
+                    <input
+                      className={cn(
+                        styles.input,
+                        fieldErrors.mkt && styles.inputInvalid,
+                      )}
+                      type="text"
+                      aria-label={t("marketCustom")}
+                      aria-invalid={Boolean(fieldErrors.mkt)}
+                      placeholder={t("marketCustomPlaceholder")}
+                      value={
+                        customMarketOpen ? customMarket : (settings?.mkt ?? "")
+                      }
+                      onChange={(e) => {
+                        setCustomMarketOpen(true);
+                        setCustomMarket(e.target.value);
+                        setCustomMarketInvalid(false);
+                      }}
+                      onBlur={() => void submitCustomMarket()}
+                      onKeyDown={(e) => {
+                        if (e.key === "Enter") {
+                          void submitCustomMarket();
+                        }
+                      }}
+                    />
+                  </div>
+                </div>
+              )}
+              {showCustomMarket && customMarketInvalid && (
+                <div className={styles.hint}>{t("marketCustomInvalid")}</div>
+              )}
+              {renderFieldError("mkt")}
               <div className={styles.hint}>{t("marketHint")}</div>
+              {mktSuggestion && mktSuggestion.mkt !== settings?.mkt && (
+                <div className={styles.mktWarning}>
+                  <span>
+                    {t("marketSuggestion").replace("{mkt}", mktSuggestion.mkt)}
+                  </span>
+                  <button
+                    className={styles.btnDismiss}
+                    onClick={() => void respondMktSuggestion(true)}
+                  >
+                    {t("marketSuggestionAccept")}
+                  </button>
+                  <button
+                    className={styles.btnDismiss}
+                    onClick={() => void respondMktSuggestion(false)}
+                    aria-label="dismiss suggestion"
+                  >
+                    ×
+                  </button>
+                </div>
+              )}
               {marketStatus?.is_mismatch && !dismissed && (
                 <div className={styles.mktWarning}>
                   <span>
@@ -545,49 +1100,1084 @@ export function Settings({
                   </button>
                 </div>
               )}
+              {marketStatus?.is_degraded && (
+                <div className={styles.mktWarning}>
+                  <span>
+                    {t("marketDegradedWarning").replace(
+                      "{mkt}",
+                      marketStatus.requested_mkt,
+                    )}
+                  </span>
+                </div>
+              )}
+              {!marketStatus?.is_mismatch &&
+                marketProbe &&
+                !marketProbe.is_available &&
+                marketProbe.requested_mkt === settings?.mkt &&
+                !dismissed && (
+                  <div className={styles.mktWarning}>
+                    <span>
+                      {t("marketMismatchWarning")
+                        .replace(
+                          "{actualMkt}",
+                          marketProbe.served_mkt ?? marketProbe.requested_mkt,
+                        )
+                        .replace("{requestedMkt}", marketProbe.requested_mkt)}
+                    </span>
+                    <button
+                      className={styles.btnDismiss}
+                      onClick={() => setDismissed(true)}
+                      aria-label="dismiss"
+                    >
+                      ×
+                    </button>
+                  </div>
+                )}
             </div>
             <div className={styles.settingBlock}>
               <div className={styles.settingRow}>
-                <span className={styles.label}>{t("saveDirectory")}</span>
-                <div className={styles.inlineActions}>
-                  <button
-                    onClick={handleOpenFolder}
-                    className={cn(
-                      btnStyles.btn,
-                      btnStyles.btnSecondary,
-                      btnStyles.btnSmall,
-                      styles.controlButton,
-                    )}
-                    type="button"
-                  >
-                    {t("openAction")}
-                  </button>
-                  <button
-                    onClick={handleSelectFolder}
-                    className={cn(
-                      btnStyles.btn,
-                      btnStyles.btnSecondary,
-                      btnStyles.btnSmall,
-                      styles.controlButton,
-                    )}
-                    type="button"
-                  >
-                    {t("selectFolder")}
-                  </button>
-                </div>
+                <span className={styles.label}>{t("archiveBackfill")}</span>
+                <input
+                  disabled={isLocked("archive_backfill_enabled")}
+                  className={styles.switch}
+                  type="checkbox"
+                  aria-label={t("archiveBackfill")}
+                  checked={settings?.archive_backfill_enabled ?? false}
+                  onChange={(e) =>
+                    handleChange("archive_backfill_enabled", e.target.checked)
+                  }
+                />
               </div>
-              <div
-                className={styles.dirInfo}
-                title={
-                  settings?.save_directory ??
-                  (defaultDir ? defaultDir : t("loading"))
+              <div className={styles.hint}>{t("archiveBackfillHint")}</div>
+            </div>
+            <div className={styles.settingBlock}>
+              <div className={styles.settingRow}>
+                <span className={styles.label}>{t("idlePrefetch")}</span>
+                <input
+                  disabled={isLocked("idle_prefetch")}
+                  className={styles.switch}
+                  type="checkbox"
+                  aria-label={t("idlePrefetch")}
+                  checked={settings?.idle_prefetch ?? false}
+                  onChange={(e) =>
+                    handleChange("idle_prefetch", e.target.checked)
+                  }
+                />
+              </div>
+              <div className={styles.hint}>{t("idlePrefetchHint")}</div>
+            </div>
+            <div className={styles.settingBlock}>
+              <div className={styles.settingRow}>
+                <span className={styles.label}>
+                  {t("deferDownloadsOnBattery")}
+                </span>
+                <input
+                  disabled={isLocked("defer_downloads_on_battery")}
+                  className={styles.switch}
+                  type="checkbox"
+                  aria-label={t("deferDownloadsOnBattery")}
+                  checked={settings?.defer_downloads_on_battery ?? false}
+                  onChange={(e) =>
+                    handleChange(
+                      "defer_downloads_on_battery",
+                      e.target.checked,
+                    )
+                  }
+                />
+              </div>
+              <div className={styles.hint}>
+                {t("deferDownloadsOnBatteryHint")}
+              </div>
+            </div>
+            <div className={styles.settingBlock}>
+              <div className={styles.settingRow}>
+                <span className={styles.label}>{t("moveToTrash")}</span>
+                <input
+                  disabled={isLocked("move_to_trash")}
+                  className={styles.switch}
+                  type="checkbox"
+                  aria-label={t("moveToTrash")}
+                  checked={settings?.move_to_trash ?? true}
+                  onChange={(e) =>
+                    handleChange("move_to_trash", e.target.checked)
+                  }
+                />
+              </div>
+              <div className={styles.hint}>{t("moveToTrashHint")}</div>
+            </div>
+            <div className={styles.settingBlock}>
+              <div className={styles.settingRow}>
+                <span className={styles.label}>{t("updateChannel")}</span>
+                <select
+                  disabled={isLocked("update_channel")}
+                  className={styles.select}
+                  aria-label={t("updateChannel")}
+                  value={settings?.update_channel ?? "stable"}
+                  onChange={(e) =>
+                    handleChange("update_channel", e.target.value)
+                  }
+                >
+                  <option value="stable">{t("updateChannelStable")}</option>
+                  <option value="beta">{t("updateChannelBeta")}</option>
+                </select>
+              </div>
+              <div className={styles.hint}>{t("updateChannelHint")}</div>
+            </div>
+            <div className={styles.settingBlock}>
+              <div className={styles.settingRow}>
+                <span className={styles.label}>{t("wallpaperFade")}</span>
+                <input
+                  disabled={isLocked("wallpaper_fade")}
+                  className={styles.switch}
+                  type="checkbox"
+                  aria-label={t("wallpaperFade")}
+                  checked={settings?.wallpaper_fade ?? false}
+                  onChange={(e) =>
+                    handleChange("wallpaper_fade", e.target.checked)
+                  }
+                />
+              </div>
+              <div className={styles.hint}>{t("wallpaperFadeHint")}</div>
+            </div>
+            <div className={styles.settingBlock}>
+              <div className={styles.settingRow}>
+                <span className={styles.label}>
+                  {t("lockScreenWallpaper")}
+                </span>
+                <input
+                  disabled={isLocked("lock_screen_wallpaper")}
+                  className={styles.switch}
+                  type="checkbox"
+                  aria-label={t("lockScreenWallpaper")}
+                  checked={settings?.lock_screen_wallpaper ?? false}
+                  onChange={(e) =>
+                    handleChange("lock_screen_wallpaper", e.target.checked)
+                  }
+                />
+              </div>
+              <div className={styles.hint}>{t("lockScreenWallpaperHint")}</div>
+            </div>
+            <div className={styles.settingBlock}>
+              <div className={styles.settingRow}>
+                <span className={styles.label}>{t("greeterBackground")}</span>
+                <select
+                  disabled={isLocked("greeter_background")}
+                  className={styles.select}
+                  aria-label={t("greeterBackground")}
+                  value={settings?.greeter_background ?? "off"}
+                  onChange={(e) =>
+                    handleChange("greeter_background", e.target.value)
+                  }
+                >
+                  <option value="off">{t("greeterBackgroundOff")}</option>
+                  <option value="sddm">SDDM</option>
+                </select>
+              </div>
+              {settings?.greeter_background === "sddm" && (
+                <div className={styles.settingRow}>
+                  <span className={styles.label}>{t("sddmThemeDir")}</span>
+                  <input
+                    key={settings?.sddm_theme_dir ?? ""}
+                    className={cn(
+                      styles.input,
+                      fieldErrors.sddm_theme_dir && styles.inputInvalid,
+                    )}
+                    type="text"
+                    disabled={isLocked("sddm_theme_dir")}
+                    aria-label={t("sddmThemeDir")}
+                    aria-invalid={Boolean(fieldErrors.sddm_theme_dir)}
+                    placeholder={t("sddmThemeDirAuto")}
+                    defaultValue={settings?.sddm_theme_dir ?? ""}
+                    onBlur={(e) =>
+                      handleChange(
+                        "sddm_theme_dir",
+                        e.target.value.trim() === ""
+                          ? null
+                          : e.target.value.trim(),
+                      )
+                    }
+                  />
+                </div>
+              )}
+              <div className={styles.hint}>{t("greeterBackgroundHint")}</div>
+            </div>
+            <div className={styles.settingBlock}>
+              <div className={styles.settingRow}>
+                <span className={styles.label}>{t("wallpaperOsd")}</span>
+                <input
+                  disabled={isLocked("osd_duration_secs")}
+                  className={styles.switch}
+                  type="checkbox"
+                  aria-label={t("wallpaperOsd")}
+                  checked={(settings?.osd_duration_secs ?? 0) > 0}
+                  onChange={(e) =>
+                    handleChange(
+                      "osd_duration_secs",
+                      e.target.checked ? DEFAULT_OSD_DURATION_SECS : 0,
+                    )
+                  }
+                />
+              </div>
+              {settings && settings.osd_duration_secs > 0 && (
+                <>
+                  <div className={styles.settingRow}>
+                    <span className={styles.label}>
+                      {t("wallpaperOsdDuration").replace(
+                        "{seconds}",
+                        String(settings.osd_duration_secs),
+                      )}
+                    </span>
+                    <input
+                      disabled={isLocked("osd_duration_secs")}
+                      className={styles.range}
+                      type="range"
+                      min={2}
+                      max={15}
+                      step={1}
+                      aria-label={t("wallpaperOsd")}
+                      value={settings.osd_duration_secs}
+                      onChange={(e) =>
+                        handleChange(
+                          "osd_duration_secs",
+                          Number(e.target.value),
+                        )
+                      }
+                    />
+                  </div>
+                  <div className={styles.settingRow}>
+                    <span className={styles.label}>
+                      {t("wallpaperOsdPosition")}
+                    </span>
+                    <select
+                      disabled={isLocked("osd_position")}
+                      className={styles.select}
+                      aria-label={t("wallpaperOsdPosition")}
+                      value={settings.osd_position}
+                      onChange={(e) =>
+                        handleChange("osd_position", e.target.value)
+                      }
+                    >
+                      <option value="top_left">{t("osdTopLeft")}</option>
+                      <option value="top_right">{t("osdTopRight")}</option>
+                      <option value="bottom_left">
+                        {t("osdBottomLeft")}
+                      </option>
+                      <option value="bottom_right">
+                        {t("osdBottomRight")}
+                      </option>
+                    </select>
+                  </div>
+                </>
+              )}
+              {renderFieldError("osd_duration_secs")}
+              {renderFieldError("osd_position")}
+              <div className={styles.hint}>{t("wallpaperOsdHint")}</div>
+            </div>
+            <div className={styles.settingBlock}>
+              <div className={styles.settingRow}>
+                <span className={styles.label}>
+                  {t("placeholderWallpaper")}
+                </span>
+                <input
+                  disabled={isLocked("placeholder_wallpaper")}
+                  className={styles.switch}
+                  type="checkbox"
+                  aria-label={t("placeholderWallpaper")}
+                  checked={settings?.placeholder_wallpaper ?? false}
+                  onChange={(e) =>
+                    handleChange("placeholder_wallpaper", e.target.checked)
+                  }
+                />
+              </div>
+              <div className={styles.hint}>
+                {t("placeholderWallpaperHint")}
+              </div>
+            </div>
+            <div className={styles.settingBlock}>
+              <div className={styles.settingRow}>
+                <span className={styles.label}>{t("virtualDesktopMode")}</span>
+                <select
+                  disabled={isLocked("virtual_desktop_mode")}
+                  className={styles.select}
+                  aria-label={t("virtualDesktopMode")}
+                  value={settings?.virtual_desktop_mode ?? "all"}
+                  onChange={(e) =>
+                    handleChange("virtual_desktop_mode", e.target.value)
+                  }
+                >
+                  <option value="all">{t("virtualDesktopModeAll")}</option>
+                  <option value="current">
+                    {t("virtualDesktopModeCurrent")}
+                  </option>
+                </select>
+              </div>
+              <div className={styles.hint}>{t("virtualDesktopModeHint")}</div>
+            </div>
+            <div className={styles.settingBlock}>
+              <div className={styles.settingRow}>
+                <span className={styles.label}>{t("trayLeftClick")}</span>
+                <select
+                  disabled={isLocked("tray_left_click")}
+                  className={styles.select}
+                  aria-label={t("trayLeftClick")}
+                  value={settings?.tray_left_click ?? "toggle_window"}
+                  onChange={(e) =>
+                    handleChange("tray_left_click", e.target.value)
+                  }
+                >
+                  <option value="toggle_window">
+                    {t("trayLeftClickToggleWindow")}
+                  </option>
+                  <option value="show_menu">
+                    {t("trayLeftClickShowMenu")}
+                  </option>
+                  <option value="next_wallpaper">
+                    {t("trayLeftClickNextWallpaper")}
+                  </option>
+                </select>
+              </div>
+            </div>
+            <div className={styles.settingBlock}>
+              <div className={styles.settingRow}>
+                <span className={styles.label}>{t("trayDoubleClick")}</span>
+                <select
+                  disabled={isLocked("tray_double_click")}
+                  className={styles.select}
+                  aria-label={t("trayDoubleClick")}
+                  value={settings?.tray_double_click ?? "none"}
+                  onChange={(e) =>
+                    handleChange("tray_double_click", e.target.value)
+                  }
+                >
+                  <option value="none">{t("trayDoubleClickNone")}</option>
+                  <option value="open_window">
+                    {t("trayDoubleClickOpenWindow")}
+                  </option>
+                  <option value="refresh">{t("trayDoubleClickRefresh")}</option>
+                  <option value="next_wallpaper">
+                    {t("trayDoubleClickNextWallpaper")}
+                  </option>
+                </select>
+              </div>
+              <div className={styles.hint}>{t("trayDoubleClickHint")}</div>
+            </div>
+            <div className={styles.settingBlock}>
+              <div className={styles.settingRow}>
+                <span className={styles.label}>{t("quitBehavior")}</span>
+                <select
+                  disabled={isLocked("quit_behavior")}
+                  className={styles.select}
+                  aria-label={t("quitBehavior")}
+                  value={settings?.quit_behavior ?? "ask"}
+                  onChange={(e) =>
+                    handleChange("quit_behavior", e.target.value)
+                  }
+                >
+                  <option value="ask">{t("quitBehaviorAsk")}</option>
+                  <option value="quit">{t("quitBehaviorQuit")}</option>
+                  <option value="hide">{t("quitBehaviorHide")}</option>
+                </select>
+              </div>
+              <div className={styles.hint}>{t("quitBehaviorHint")}</div>
+            </div>
+            <div className={styles.settingBlock}>
+              <div className={styles.settingRow}>
+                <span className={styles.label}>{t("extensionEvents")}</span>
+                <input
+                  disabled={isLocked("extension_events")}
+                  className={styles.switch}
+                  type="checkbox"
+                  aria-label={t("extensionEvents")}
+                  checked={settings?.extension_events ?? false}
+                  onChange={(e) =>
+                    handleChange("extension_events", e.target.checked)
+                  }
+                />
+              </div>
+              <div className={styles.hint}>{t("extensionEventsHint")}</div>
+              {settings?.extension_events && extensionEndpoint && (
+                <div className={styles.hint}>
+                  {t("extensionEventsEndpoint").replace(
+                    "{endpoint}",
+                    extensionEndpoint,
+                  )}
+                </div>
+              )}
+            </div>
+            <div className={styles.settingBlock}>
+              <div className={styles.settingRow}>
+                <span className={styles.label}>{t("applyHook")}</span>
+                <div className={styles.inlineActions}>
+                  <button
+                    onClick={handleSelectApplyHook}
+                    disabled={isLocked("apply_hook")}
+                    className={cn(
+                      btnStyles.btn,
+                      btnStyles.btnSecondary,
+                      btnStyles.btnSmall,
+                      styles.controlButton,
+                    )}
+                    type="button"
+                  >
+                    {t("applyHookSelect")}
+                  </button>
+                </div>
+              </div>
+              {settings?.apply_hook && (
+                <>
+                  <div className={styles.dirInfo} title={settings.apply_hook}>
+                    {settings.apply_hook}
+                  </div>
+                  <button
+                    onClick={() => handleChange("apply_hook", null)}
+                    disabled={isLocked("apply_hook")}
+                    className={cn(
+                      btnStyles.btn,
+                      btnStyles.btnLink,
+                      btnStyles.btnSmall,
+                    )}
+                    type="button"
+                  >
+                    {t("applyHookClear")}
+                  </button>
+                </>
+              )}
+              {renderFieldError("apply_hook")}
+              <div className={styles.hint}>{t("applyHookHint")}</div>
+            </div>
+            <div className={styles.settingBlock}>
+              <div className={styles.settingRow}>
+                <span className={styles.label}>{t("downloadResolution")}</span>
+                <select
+                  disabled={isLocked("download_resolution")}
+                  className={styles.select}
+                  aria-label={t("downloadResolution")}
+                  value={settings?.download_resolution ?? "auto"}
+                  onChange={(e) =>
+                    handleChange("download_resolution", e.target.value)
+                  }
+                >
+                  <option value="auto">{t("downloadResolutionAuto")}</option>
+                  <option value="UHD">UHD</option>
+                  <option value="1920x1080">1920x1080</option>
+                </select>
+              </div>
+              <div className={styles.hint}>{t("downloadResolutionHint")}</div>
+            </div>
+            <div className={styles.settingBlock}>
+              <div className={styles.settingRow}>
+                <span className={styles.label}>
+                  {t("enablePortraitVariant")}
+                </span>
+                <input
+                  disabled={isLocked("enable_portrait_variant")}
+                  className={styles.switch}
+                  type="checkbox"
+                  aria-label={t("enablePortraitVariant")}
+                  checked={settings?.enable_portrait_variant ?? true}
+                  onChange={(e) =>
+                    handleChange("enable_portrait_variant", e.target.checked)
+                  }
+                />
+              </div>
+              <div className={styles.hint}>
+                {t("enablePortraitVariantHint")}
+              </div>
+            </div>
+            <div className={styles.settingBlock}>
+              <div className={styles.settingRow}>
+                <span className={styles.label}>
+                  {t("lowDiskSpaceThreshold")}
+                </span>
+                <select
+                  disabled={isLocked("low_disk_space_threshold_mb")}
+                  className={styles.select}
+                  aria-label={t("lowDiskSpaceThreshold")}
+                  value={settings?.low_disk_space_threshold_mb ?? 500}
+                  onChange={(e) =>
+                    handleChange(
+                      "low_disk_space_threshold_mb",
+                      Number(e.target.value),
+                    )
+                  }
+                >
+                  <option value={0}>{t("lowDiskSpaceThresholdOff")}</option>
+                  {LOW_DISK_SPACE_THRESHOLDS_MB.map((mb) => (
+                    <option key={mb} value={mb}>
+                      {mb >= 1024 ? `${mb / 1024} GB` : `${mb} MB`}
+                    </option>
+                  ))}
+                </select>
+              </div>
+              <div className={styles.hint}>
+                {t("lowDiskSpaceThresholdHint")}
+              </div>
+            </div>
+            <div className={styles.settingBlock}>
+              <div className={styles.settingRow}>
+                <span className={styles.label}>{t("jpegRecompress")}</span>
+                <input
+                  disabled={isLocked("jpeg_quality")}
+                  className={styles.switch}
+                  type="checkbox"
+                  aria-label={t("jpegRecompress")}
+                  checked={(settings?.jpeg_quality ?? 0) > 0}
+                  onChange={(e) =>
+                    handleChange(
+                      "jpeg_quality",
+                      e.target.checked ? DEFAULT_JPEG_QUALITY : 0,
+                    )
+                  }
+                />
+              </div>
+              {settings && settings.jpeg_quality > 0 && (
+                <div className={styles.settingRow}>
+                  <span className={styles.label}>
+                    {t("jpegQuality").replace(
+                      "{quality}",
+                      String(settings.jpeg_quality),
+                    )}
+                  </span>
+                  <input
+                    disabled={isLocked("jpeg_quality")}
+                    className={styles.range}
+                    type="range"
+                    min={50}
+                    max={95}
+                    step={5}
+                    aria-label={t("jpegRecompress")}
+                    value={settings.jpeg_quality}
+                    onChange={(e) =>
+                      handleChange("jpeg_quality", Number(e.target.value))
+                    }
+                  />
+                </div>
+              )}
+              {renderFieldError("jpeg_quality")}
+              <div className={styles.hint}>{t("jpegRecompressHint")}</div>
+            </div>
+            <div className={styles.settingBlock}>
+              <div className={styles.settingRow}>
+                <span className={styles.label}>{t("indexBackupInterval")}</span>
+                <select
+                  disabled={isLocked("index_backup_interval_days")}
+                  className={styles.select}
+                  aria-label={t("indexBackupInterval")}
+                  value={settings?.index_backup_interval_days ?? 7}
+                  onChange={(e) =>
+                    handleChange(
+                      "index_backup_interval_days",
+                      Number(e.target.value),
+                    )
+                  }
+                >
+                  <option value={0}>{t("indexBackupIntervalOff")}</option>
+                  <option value={1}>{t("indexBackupIntervalDaily")}</option>
+                  <option value={7}>{t("indexBackupIntervalWeekly")}</option>
+                  <option value={30}>{t("indexBackupIntervalMonthly")}</option>
+                </select>
+              </div>
+              <div className={styles.hint}>{t("indexBackupIntervalHint")}</div>
+            </div>
+            <div className={styles.settingBlock}>
+              <div className={styles.settingRow}>
+                <span className={styles.label}>{t("solarSchedule")}</span>
+                <select
+                  disabled={isLocked("solar_schedule")}
+                  className={styles.select}
+                  aria-label={t("solarSchedule")}
+                  value={settings?.solar_schedule ?? "off"}
+                  onChange={(e) =>
+                    handleChange("solar_schedule", e.target.value)
+                  }
+                >
+                  <option value="off">{t("solarScheduleOff")}</option>
+                  <option value="sunrise">{t("solarScheduleSunrise")}</option>
+                  <option value="day_night">
+                    {t("solarScheduleDayNight")}
+                  </option>
+                </select>
+              </div>
+              {settings && settings.solar_schedule !== "off" && (
+                <>
+                  <div className={styles.settingRow}>
+                    <span className={styles.label}>{t("solarLatitude")}</span>
+                    <input
+                      key={`${settings?.solar_latitude}`}
+                      className={cn(
+                        styles.input,
+                        fieldErrors.solar_latitude && styles.inputInvalid,
+                      )}
+                      type="number"
+                      step="0.0001"
+                      disabled={isLocked("solar_latitude")}
+                      aria-label={t("solarLatitude")}
+                      aria-invalid={Boolean(fieldErrors.solar_latitude)}
+                      defaultValue={settings?.solar_latitude ?? ""}
+                      onBlur={(e) =>
+                        handleChange(
+                          "solar_latitude",
+                          e.target.value.trim() === ""
+                            ? null
+                            : Number(e.target.value),
+                        )
+                      }
+                    />
+                  </div>
+                  <div className={styles.settingRow}>
+                    <span className={styles.label}>{t("solarLongitude")}</span>
+                    <input
+                      key={`${settings?.solar_longitude}`}
+                      className={cn(
+                        styles.input,
+                        fieldErrors.solar_longitude && styles.inputInvalid,
+                      )}
+                      type="number"
+                      step="0.0001"
+                      disabled={isLocked("solar_longitude")}
+                      aria-label={t("solarLongitude")}
+                      aria-invalid={Boolean(fieldErrors.solar_longitude)}
+                      defaultValue={settings?.solar_longitude ?? ""}
+                      onBlur={(e) =>
+                        handleChange(
+                          "solar_longitude",
+                          e.target.value.trim() === ""
+                            ? null
+                            : Number(e.target.value),
+                        )
+                      }
+                    />
+                  </div>
+                  {renderFieldError("solar_latitude")}
+                  {renderFieldError("solar_longitude")}
+                  <div className={styles.hint}>{t("solarLocationHint")}</div>
+                </>
+              )}
+              {settings?.solar_schedule === "day_night" && (
+                <>
+                  <div className={styles.settingRow}>
+                    <span className={styles.label}>
+                      {t("solarDayWallpaper")}
+                    </span>
+                    <input
+                      key={settings?.solar_day_wallpaper ?? ""}
+                      className={cn(
+                        styles.input,
+                        fieldErrors.solar_day_wallpaper && styles.inputInvalid,
+                      )}
+                      type="text"
+                      placeholder="YYYYMMDD"
+                      disabled={isLocked("solar_day_wallpaper")}
+                      aria-label={t("solarDayWallpaper")}
+                      aria-invalid={Boolean(fieldErrors.solar_day_wallpaper)}
+                      defaultValue={settings?.solar_day_wallpaper ?? ""}
+                      onBlur={(e) =>
+                        handleChange(
+                          "solar_day_wallpaper",
+                          e.target.value.trim() || null,
+                        )
+                      }
+                    />
+                  </div>
+                  <div className={styles.settingRow}>
+                    <span className={styles.label}>
+                      {t("solarNightWallpaper")}
+                    </span>
+                    <input
+                      key={settings?.solar_night_wallpaper ?? ""}
+                      className={cn(
+                        styles.input,
+                        fieldErrors.solar_night_wallpaper &&
+                          styles.inputInvalid,
+                      )}
+                      type="text"
+                      placeholder="YYYYMMDD"
+                      disabled={isLocked("solar_night_wallpaper")}
+                      aria-label={t("solarNightWallpaper")}
+                      aria-invalid={Boolean(fieldErrors.solar_night_wallpaper)}
+                      defaultValue={settings?.solar_night_wallpaper ?? ""}
+                      onBlur={(e) =>
+                        handleChange(
+                          "solar_night_wallpaper",
+                          e.target.value.trim() || null,
+                        )
+                      }
+                    />
+                  </div>
+                  {renderFieldError("solar_day_wallpaper")}
+                  {renderFieldError("solar_night_wallpaper")}
+                  <div className={styles.hint}>{t("solarWallpaperHint")}</div>
+                </>
+              )}
+              {renderFieldError("solar_schedule")}
+              <div className={styles.hint}>{t("solarScheduleHint")}</div>
+            </div>
+            <div className={styles.settingBlock}>
+              <div className={styles.settingRow}>
+                <span className={styles.label}>{t("localFolder")}</span>
+                <div className={styles.inlineActions}>
+                  <button
+                    onClick={handleSelectLocalFolder}
+                    disabled={isLocked("local_folder")}
+                    className={cn(
+                      btnStyles.btn,
+                      btnStyles.btnSecondary,
+                      btnStyles.btnSmall,
+                      styles.controlButton,
+                    )}
+                    type="button"
+                  >
+                    {t("selectFolder")}
+                  </button>
+                </div>
+              </div>
+              {settings?.local_folder && (
+                <>
+                  <div className={styles.dirInfo} title={settings.local_folder}>
+                    {settings.local_folder}
+                  </div>
+                  <div className={styles.settingRow}>
+                    <span className={styles.label}>
+                      {t("localFolderSchedule")}
+                    </span>
+                    <select
+                      disabled={isLocked("local_folder_schedule")}
+                      className={styles.select}
+                      aria-label={t("localFolderSchedule")}
+                      value={settings.local_folder_schedule ?? "off"}
+                      onChange={(e) =>
+                        handleChange("local_folder_schedule", e.target.value)
+                      }
+                    >
+                      <option value="off">{t("localFolderScheduleOff")}</option>
+                      <option value="alternate">
+                        {t("localFolderScheduleAlternate")}
+                      </option>
+                      <option value="mix">{t("localFolderScheduleMix")}</option>
+                    </select>
+                  </div>
+                  <div className={styles.settingRow}>
+                    <span className={styles.label}>
+                      {t("localFolderOrder")}
+                    </span>
+                    <select
+                      disabled={isLocked("local_folder_order")}
+                      className={styles.select}
+                      aria-label={t("localFolderOrder")}
+                      value={settings.local_folder_order ?? "mtime"}
+                      onChange={(e) =>
+                        handleChange("local_folder_order", e.target.value)
+                      }
+                    >
+                      <option value="mtime">
+                        {t("localFolderOrderMtime")}
+                      </option>
+                      <option value="name">{t("localFolderOrderName")}</option>
+                    </select>
+                  </div>
+                  <button
+                    onClick={() => handleChange("local_folder", null)}
+                    className={cn(
+                      btnStyles.btn,
+                      btnStyles.btnLink,
+                      btnStyles.btnSmall,
+                    )}
+                    type="button"
+                  >
+                    {t("localFolderClear")}
+                  </button>
+                </>
+              )}
+              {renderFieldError("local_folder")}
+              <div className={styles.hint}>{t("localFolderHint")}</div>
+            </div>
+            <div className={styles.settingBlock}>
+              <div className={styles.settingRow}>
+                <span className={styles.label}>{t("spotlight")}</span>
+                <select
+                  disabled={isLocked("spotlight_schedule")}
+                  className={styles.select}
+                  aria-label={t("spotlight")}
+                  value={settings?.spotlight_schedule ?? "off"}
+                  onChange={(e) =>
+                    handleChange("spotlight_schedule", e.target.value)
+                  }
+                >
+                  <option value="off">{t("localFolderScheduleOff")}</option>
+                  <option value="alternate">
+                    {t("localFolderScheduleAlternate")}
+                  </option>
+                  <option value="mix">{t("localFolderScheduleMix")}</option>
+                </select>
+              </div>
+              {settings && settings.spotlight_schedule !== "off" && (
+                <div className={styles.settingRow}>
+                  <span className={styles.label} />
+                  <div className={styles.inlineActions}>
+                    {spotlightImport !== null && (
+                      <span className={styles.hint}>
+                        {spotlightImport === "failed"
+                          ? t("spotlightImportFailed")
+                          : t("spotlightImported").replace(
+                              "{count}",
+                              String(spotlightImport),
+                            )}
+                      </span>
+                    )}
+                    <button
+                      onClick={() => void handleImportSpotlight()}
+                      className={cn(
+                        btnStyles.btn,
+                        btnStyles.btnSecondary,
+                        btnStyles.btnSmall,
+                        styles.controlButton,
+                      )}
+                      type="button"
+                    >
+                      {t("spotlightImport")}
+                    </button>
+                  </div>
+                </div>
+              )}
+              {renderFieldError("spotlight_schedule")}
+              <div className={styles.hint}>{t("spotlightHint")}</div>
+            </div>
+            <div className={styles.settingBlock}>
+              <div className={styles.settingRow}>
+                <span className={styles.label}>{t("customCa")}</span>
+                <div className={styles.inlineActions}>
+                  <button
+                    onClick={handleSelectCustomCa}
+                    disabled={isLocked("custom_ca_path")}
+                    className={cn(
+                      btnStyles.btn,
+                      btnStyles.btnSecondary,
+                      btnStyles.btnSmall,
+                      styles.controlButton,
+                    )}
+                    type="button"
+                  >
+                    {t("customCaChoose")}
+                  </button>
+                </div>
+              </div>
+              {settings?.custom_ca_path && (
+                <>
+                  <div
+                    className={styles.dirInfo}
+                    title={settings.custom_ca_path}
+                  >
+                    {settings.custom_ca_path}
+                  </div>
+                  <div className={styles.settingRow}>
+                    <span className={styles.label}>{t("customCaOnly")}</span>
+                    <input
+                      disabled={isLocked("custom_ca_only")}
+                      className={styles.switch}
+                      type="checkbox"
+                      aria-label={t("customCaOnly")}
+                      checked={settings.custom_ca_only}
+                      onChange={(e) =>
+                        handleChange("custom_ca_only", e.target.checked)
+                      }
+                    />
+                  </div>
+                  <button
+                    onClick={() => handleChange("custom_ca_path", null)}
+                    disabled={isLocked("custom_ca_path")}
+                    className={cn(
+                      btnStyles.btn,
+                      btnStyles.btnLink,
+                      btnStyles.btnSmall,
+                    )}
+                    type="button"
+                  >
+                    {t("customCaClear")}
+                  </button>
+                </>
+              )}
+              {renderFieldError("custom_ca_path")}
+              <div className={styles.hint}>{t("customCaHint")}</div>
+            </div>
+            <div className={styles.settingBlock}>
+              <div className={styles.settingRow}>
+                <span className={styles.label}>{t("httpKeepAlive")}</span>
+                <select
+                  disabled={isLocked("http_keep_alive_secs")}
+                  className={styles.select}
+                  aria-label={t("httpKeepAlive")}
+                  value={settings?.http_keep_alive_secs ?? 90}
+                  onChange={(e) =>
+                    handleChange("http_keep_alive_secs", Number(e.target.value))
+                  }
+                >
+                  <option value={0}>{t("httpKeepAliveOff")}</option>
+                  <option value={30}>{t("httpKeepAlive30s")}</option>
+                  <option value={90}>{t("httpKeepAlive90s")}</option>
+                  <option value={300}>{t("httpKeepAlive5m")}</option>
+                </select>
+              </div>
+              <div className={styles.settingRow}>
+                <span className={styles.label} />
+                <div className={styles.inlineActions}>
+                  {networkReset !== null && (
+                    <span className={styles.hint}>
+                      {networkReset === "done"
+                        ? t("resetNetworkDone")
+                        : t("resetNetworkFailed")}
+                    </span>
+                  )}
+                  <button
+                    onClick={() => void handleResetNetwork()}
+                    className={cn(
+                      btnStyles.btn,
+                      btnStyles.btnSecondary,
+                      btnStyles.btnSmall,
+                      styles.controlButton,
+                    )}
+                    type="button"
+                  >
+                    {t("resetNetwork")}
+                  </button>
+                </div>
+              </div>
+              {renderFieldError("http_keep_alive_secs")}
+              <div className={styles.hint}>{t("httpKeepAliveHint")}</div>
+            </div>
+            <div className={styles.settingBlock}>
+              <div className={styles.settingRow}>
+                <span className={styles.label}>{t("bingOnlyNetwork")}</span>
+                <input
+                  disabled={isLocked("bing_only_network")}
+                  className={styles.switch}
+                  type="checkbox"
+                  aria-label={t("bingOnlyNetwork")}
+                  checked={settings?.bing_only_network ?? false}
+                  onChange={(e) =>
+                    handleChange("bing_only_network", e.target.checked)
+                  }
+                />
+              </div>
+              <div className={styles.hint}>{t("bingOnlyNetworkHint")}</div>
+            </div>
+            <div className={styles.settingBlock}>
+              <div className={styles.settingRow}>
+                <span className={styles.label}>{t("profiles")}</span>
+                <div className={styles.inlineActions}>
+                  <input
+                    className={styles.input}
+                    type="text"
+                    aria-label={t("profileName")}
+                    placeholder={t("profileName")}
+                    value={profileName}
+                    onChange={(e) => setProfileName(e.target.value)}
+                    onKeyDown={(e) => {
+                      if (e.key === "Enter") {
+                        void handleSaveProfile();
+                      }
+                    }}
+                  />
+                  <button
+                    onClick={() => void handleSaveProfile()}
+                    className={cn(
+                      btnStyles.btn,
+                      btnStyles.btnSecondary,
+                      btnStyles.btnSmall,
+                      styles.controlButton,
+                    )}
+                    type="button"
+                    disabled={!profileName.trim()}
+                  >
+                    {t("profileSave")}
+                  </button>
+                </div>
+              </div>
+              {(profiles?.profiles ?? []).map((profile) => (
+                <div className={styles.settingRow} key={profile.name}>
+                  <span className={styles.label}>
+                    {profile.name}
+                    {profiles?.active === profile.name &&
+                      ` (${t("profileActive")})`}
+                  </span>
+                  <div className={styles.inlineActions}>
+                    <button
+                      onClick={() => void handleSwitchProfile(profile.name)}
+                      className={cn(
+                        btnStyles.btn,
+                        btnStyles.btnSecondary,
+                        btnStyles.btnSmall,
+                        styles.controlButton,
+                      )}
+                      type="button"
+                      disabled={profiles?.active === profile.name}
+                    >
+                      {t("profileSwitch")}
+                    </button>
+                    <button
+                      onClick={() => void handleDeleteProfile(profile.name)}
+                      className={cn(
+                        btnStyles.btn,
+                        btnStyles.btnLink,
+                        btnStyles.btnSmall,
+                      )}
+                      type="button"
+                    >
+                      {t("profileDelete")}
+                    </button>
+                  </div>
+                </div>
+              ))}
+              {profileError && (
+                <div className={styles.fieldError} role="alert">
+                  {profileError}
+                </div>
+              )}
+              <div className={styles.hint}>{t("profilesHint")}</div>
+            </div>
+            <div className={styles.settingBlock}>
+              <div className={styles.settingRow}>
+                <span className={styles.label}>{t("saveDirectory")}</span>
+                <div className={styles.inlineActions}>
+                  <button
+                    onClick={handleOpenFolder}
+                    className={cn(
+                      btnStyles.btn,
+                      btnStyles.btnSecondary,
+                      btnStyles.btnSmall,
+                      styles.controlButton,
+                    )}
+                    type="button"
+                  >
+                    {t("openAction")}
+                  </button>
+                  <button
+                    onClick={handleSelectFolder}
+                    disabled={isLocked("save_directory")}
+                    className={cn(
+                      btnStyles.btn,
+                      btnStyles.btnSecondary,
+                      btnStyles.btnSmall,
+                      styles.controlButton,
+                    )}
+                    type="button"
+                  >
+                    {t("selectFolder")}
+                  </button>
+                </div>
+              </div>
+              <div
+                className={styles.dirInfo}
+                title={
+                  settings?.save_directory ??
+                  (defaultDir ? defaultDir : t("loading"))
                 }
               >
                 {settings?.save_directory ??
                   (defaultDir ? defaultDir : t("loading"))}
               </div>
               {settings?.save_directory &&
-                settings.save_directory !== defaultDir && (
+                settings.save_directory !== defaultDir &&
+                !isLocked("save_directory") && (
                   <button
                     onClick={() => handleChange("save_directory", null)}
                     className={cn(
@@ -600,6 +2190,12 @@ export function Settings({
                     {t("restoreDefault")}
                   </button>
                 )}
+              {renderFieldError("save_directory")}
+              {archiveReadOnly && (
+                <div className={styles.mktWarning}>
+                  {t("archiveReadOnly")}
+                </div>
+              )}
             </div>
 
             <div className={styles.settingBlock}>
@@ -662,6 +2258,143 @@ export function Settings({
                 </div>
               )}
             </div>
+
+            {backupConfig && (
+              <div className={styles.settingBlock}>
+                <div className={styles.settingRow}>
+                  <span className={styles.label}>{t("backup")}</span>
+                  <div className={styles.settingControl}>
+                    <select
+                      className={styles.select}
+                      aria-label={t("backup")}
+                      value={backupConfig.target.kind}
+                      onChange={(e) =>
+                        setBackupConfig({
+                          ...backupConfig,
+                          target: emptyBackupTarget(
+                            e.target.value as BackupTarget["kind"],
+                          ),
+                        })
+                      }
+                    >
+                      <option value="none">{t("backupTargetNone")}</option>
+                      <option value="web_dav">{t("backupTargetWebDav")}</option>
+                      <option value="s3">{t("backupTargetS3")}</option>
+                    </select>
+                  </div>
+                </div>
+                {backupConfig.target.kind === "web_dav" && [
+                  renderBackupField("url", "backupUrl"),
+                  renderBackupField("username", "backupUsername"),
+                ]}
+                {backupConfig.target.kind === "s3" && [
+                  renderBackupField("endpoint", "backupEndpoint"),
+                  renderBackupField("bucket", "backupBucket"),
+                  renderBackupField("region", "backupRegion"),
+                  renderBackupField("access_key_id", "backupAccessKeyId"),
+                  renderBackupField("prefix", "backupPrefix"),
+                ]}
+                {backupConfig.target.kind !== "none" && (
+                  <div className={styles.settingRow}>
+                    <span className={styles.label}>
+                      {backupConfig.target.kind === "s3"
+                        ? t("backupSecretKey")
+                        : t("backupPassword")}
+                    </span>
+                    <div className={styles.settingControl}>
+                      <input
+                        className={styles.input}
+                        type="password"
+                        aria-label={
+                          backupConfig.target.kind === "s3"
+                            ? t("backupSecretKey")
+                            : t("backupPassword")
+                        }
+                        placeholder={t("backupSecretPlaceholder")}
+                        value={backupSecret}
+                        onChange={(e) => setBackupSecret(e.target.value)}
+                      />
+                    </div>
+                  </div>
+                )}
+                {backupConfig.target.kind === "s3" && (
+                  <div className={styles.settingRow}>
+                    <span className={styles.label}>{t("backupPathStyle")}</span>
+                    <input
+                      className={styles.switch}
+                      type="checkbox"
+                      aria-label={t("backupPathStyle")}
+                      checked={backupConfig.target.path_style}
+                      onChange={(e) =>
+                        updateBackupTarget({ path_style: e.target.checked })
+                      }
+                    />
+                  </div>
+                )}
+                {backupConfig.target.kind !== "none" && (
+                  <div className={styles.settingRow}>
+                    <span className={styles.label}>{t("backupAuto")}</span>
+                    <input
+                      className={styles.switch}
+                      type="checkbox"
+                      aria-label={t("backupAuto")}
+                      checked={backupConfig.enabled}
+                      onChange={(e) =>
+                        setBackupConfig({
+                          ...backupConfig,
+                          enabled: e.target.checked,
+                        })
+                      }
+                    />
+                  </div>
+                )}
+                <div className={styles.settingRow}>
+                  <span className={styles.label} />
+                  <div className={styles.inlineActions}>
+                    <button
+                      onClick={() => void handleSaveBackup()}
+                      className={cn(
+                        btnStyles.btn,
+                        btnStyles.btnSecondary,
+                        btnStyles.btnSmall,
+                        styles.controlButton,
+                      )}
+                      type="button"
+                    >
+                      {t("backupSave")}
+                    </button>
+                    <button
+                      onClick={() => void handleBackupNow()}
+                      className={cn(
+                        btnStyles.btn,
+                        btnStyles.btnSecondary,
+                        btnStyles.btnSmall,
+                        styles.controlButton,
+                      )}
+                      type="button"
+                      disabled={
+                        backingUp || backupConfig.target.kind === "none"
+                      }
+                    >
+                      {backingUp ? t("backupInProgress") : t("backupNow")}
+                    </button>
+                  </div>
+                </div>
+                {backupMessage && (
+                  <div
+                    className={
+                      backupMessage.type === "success"
+                        ? styles.transferSuccess
+                        : styles.transferError
+                    }
+                    role={backupMessage.type === "error" ? "alert" : undefined}
+                  >
+                    {backupMessage.text}
+                  </div>
+                )}
+                <div className={styles.hint}>{t("backupHint")}</div>
+              </div>
+            )}
           </section>
         </div>
       </div>
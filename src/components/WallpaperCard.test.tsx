@@ -20,7 +20,9 @@ vi.mock("@tauri-apps/api/core", () => {
       }
       return Promise.resolve(undefined);
     },
-    convertFileSrc: vi.fn((path: string) => `asset://localhost/${path}`),
+    convertFileSrc: vi.fn(
+      (path: string, protocol = "asset") => `${protocol}://localhost/${path}`,
+    ),
   };
 });
 
@@ -69,7 +71,7 @@ describe("WallpaperCard", () => {
 
     const image = screen.getByAltText("测试壁纸") as HTMLImageElement;
     expect(image).toBeInTheDocument();
-    expect(image.src).toContain("asset://localhost/");
+    expect(image.src).toContain("thumb://localhost/20240102?w=");
   });
 
   it("should call onSetWallpaper when button is clicked", () => {
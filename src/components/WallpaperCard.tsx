@@ -4,6 +4,7 @@ import { openUrl } from "@tauri-apps/plugin-opener";
 import { convertFileSrc } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import { useI18n } from "../i18n/I18nContext";
+import { EVENTS, THUMBNAIL } from "../config/ui";
 import { showSystemNotification } from "../utils/notification";
 import styles from "./WallpaperCard.module.css";
 import spinnerStyles from "../styles/spinner.module.css";
@@ -53,7 +54,7 @@ export const WallpaperCard = memo(
 
     // 监听后端下载完成事件，自动重新加载对应的图片
     useEffect(() => {
-      const unlisten = listen<string>("image-downloaded", (event) => {
+      const unlisten = listen<string>(EVENTS.IMAGE_DOWNLOADED, (event) => {
         // 下载完成的事件 payload 是 end_date
         if (event.payload === wallpaper.end_date) {
           // 清除缓存，强制浏览器重新加载图片
@@ -126,7 +127,7 @@ export const WallpaperCard = memo(
       return { title, subtitle };
     }, [wallpaper.title, wallpaper.copyright]);
 
-    // 将本地文件路径转换为前端可访问的 URL（使用 useMemo 缓存）
+    // 通过缩略图协议加载缩小后的图片（使用 useMemo 缓存）
     // 注意：包含 retryCount 作为查询参数，强制浏览器在重试时重新加载图片
     const imageUrl = useMemo(() => {
       // 如果路径为空，返回空字符串（不渲染图片）
@@ -134,13 +135,14 @@ export const WallpaperCard = memo(
         return "";
       }
 
-      // 确保路径格式正确（Windows 路径需要转换为正斜杠）
-      const normalizedPath = filePath.replace(/\\/g, "/");
-      const baseUrl = convertFileSrc(normalizedPath);
+      const width = Math.round(
+        THUMBNAIL.CARD_WIDTH * (window.devicePixelRatio || 1),
+      );
+      const baseUrl = `${convertFileSrc(wallpaper.end_date, THUMBNAIL.PROTOCOL)}?w=${width}`;
       // 添加 retryCount 作为查询参数，确保浏览器不会使用缓存的损坏图片
       // 仅在 retryCount > 0 时添加（首次加载不需要）
-      return retryCount > 0 ? `${baseUrl}?retry=${retryCount}` : baseUrl;
-    }, [filePath, retryCount]);
+      return retryCount > 0 ? `${baseUrl}&retry=${retryCount}` : baseUrl;
+    }, [filePath, wallpaper.end_date, retryCount]);
 
     return (
       <div className={styles.cardShell}>
@@ -0,0 +1,86 @@
+// 此文件由 src-tauri/src/events.rs 生成，请勿手动修改。
+// 修改事件后在 src-tauri 目录运行 `UPDATE_EVENT_TYPES=1 cargo test events` 重新生成。
+
+import type {
+  ActiveDownload,
+  DirectoryPermissionError,
+  DownloadFailure,
+  LowDiskSpace,
+  MarketStatus,
+  MktSuggestion,
+  SettingChange,
+  UpdateState,
+  WallpaperMetadataRefreshed,
+} from "../types";
+
+/**
+ * 后端事件名
+ */
+export const EVENTS = {
+  /** 壁纸列表或元数据已更新，需要重新加载 */
+  WALLPAPER_UPDATED: "wallpaper-updated",
+  /** 单张壁纸的标题和版权信息已重新获取（只需更新这一条） */
+  WALLPAPER_METADATA_REFRESHED: "wallpaper-metadata-refreshed",
+  /** 单张壁纸图片下载完成（payload 为 end_date） */
+  IMAGE_DOWNLOADED: "image-downloaded",
+  /** 当前桌面壁纸已变化（payload 为图片路径） */
+  CURRENT_WALLPAPER_CHANGED: "current-wallpaper-changed",
+  /** 用户取消了正在进行的更新 */
+  UPDATE_CANCELLED: "update-cancelled",
+  /** 更新循环阶段变化 */
+  UPDATE_STATE_CHANGED: "update-state-changed",
+  /** 图片下载开始（含每次重试） */
+  DOWNLOAD_STARTED: "download-started",
+  /** 图片下载完成 */
+  DOWNLOAD_FINISHED: "download-finished",
+  /** 图片下载失败（will_retry 表示是否还会自动重试） */
+  DOWNLOAD_FAILED: "download-failed",
+  /** 打开设置 */
+  OPEN_SETTINGS: "open-settings",
+  /** 打开关于 */
+  OPEN_ABOUT: "open-about",
+  /** 打开文件夹 */
+  OPEN_FOLDER: "open-folder",
+  /** 托盘手动检查更新 */
+  TRAY_CHECK_UPDATES: "tray-check-updates",
+  /** mkt 状态变化（mismatch 边沿触发：false→true / true→false） */
+  MKT_STATUS_CHANGED: "mkt-status-changed",
+  /** 首次启动的市场建议已生成，等待用户确认 */
+  MKT_SUGGESTION_READY: "mkt-suggestion-ready",
+  /** 设置已修改（保存设置、切换配置方案等），payload 为变化的设置项列表 */
+  SETTINGS_CHANGED: "settings-changed",
+  /** 启动延后阶段（状态修复、索引预加载、后台任务启动）已完成 */
+  STARTUP_COMPLETE: "startup-complete",
+  /** 壁纸目录无写入权限，可回退到默认目录 */
+  DIRECTORY_PERMISSION_ERROR: "directory-permission-error",
+  /** 壁纸目录磁盘空间不足，已暂停下载（进入空间不足状态时发送一次） */
+  LOW_DISK_SPACE: "low-disk-space",
+  /** 主题跟随壁纸时，新壁纸的明暗分析结果 */
+  SUGGESTED_THEME: "suggested-theme",
+} as const;
+
+/**
+ * 各事件的 payload 类型
+ */
+export interface EventPayloads {
+  "wallpaper-updated": null;
+  "wallpaper-metadata-refreshed": WallpaperMetadataRefreshed;
+  "image-downloaded": string;
+  "current-wallpaper-changed": string;
+  "update-cancelled": null;
+  "update-state-changed": UpdateState;
+  "download-started": ActiveDownload;
+  "download-finished": ActiveDownload;
+  "download-failed": DownloadFailure;
+  "open-settings": null;
+  "open-about": null;
+  "open-folder": null;
+  "tray-check-updates": null;
+  "mkt-status-changed": MarketStatus;
+  "mkt-suggestion-ready": MktSuggestion;
+  "settings-changed": SettingChange[];
+  "startup-complete": null;
+  "directory-permission-error": DirectoryPermissionError;
+  "low-disk-space": LowDiskSpace;
+  "suggested-theme": "light" | "dark";
+}
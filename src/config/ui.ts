@@ -42,17 +42,17 @@ export const TEXT = {
 } as const;
 
 /**
- * 事件名称配置
+ * 事件名称配置（由 src-tauri/src/events.rs 生成）
  */
-export const EVENTS = {
-  /** 打开设置 */
-  OPEN_SETTINGS: "open-settings",
-  /** 打开关于 */
-  OPEN_ABOUT: "open-about",
-  /** 打开文件夹 */
-  OPEN_FOLDER: "open-folder",
-  /** 托盘手动检查更新 */
-  TRAY_CHECK_UPDATES: "tray-check-updates",
-  /** mkt 状态变化（mismatch 边沿触发：false→true / true→false） */
-  MKT_STATUS_CHANGED: "mkt-status-changed",
+export { EVENTS } from "./events.generated";
+export type { EventPayloads } from "./events.generated";
+
+/**
+ * 画廊缩略图配置
+ */
+export const THUMBNAIL = {
+  /** 缩略图协议（后端 thumbnail_cache，按宽度档位缓存缩小后的图片） */
+  PROTOCOL: "thumb",
+  /** 卡片图片的 CSS 宽度，实际请求宽度按 devicePixelRatio 放大 */
+  CARD_WIDTH: 480,
 } as const;
@@ -2,7 +2,9 @@ import { describe, it, expect, vi, beforeEach, afterEach } from "vitest";
 import { renderHook, waitFor, act } from "@testing-library/react";
 import { THEME_STORAGE_KEY, ThemeProvider, useTheme } from "./ThemeContext";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { ReactNode } from "react";
+import { EVENTS } from "../config/ui";
 
 vi.mock("@tauri-apps/api/core");
 
@@ -16,6 +18,42 @@ describe("ThemeContext", () => {
     language: "zh-CN",
     resolved_language: "zh-CN",
     mkt: "zh-CN",
+    archive_backfill_enabled: false,
+    download_resolution: "auto",
+    local_folder: null,
+    local_folder_order: "mtime",
+    local_folder_schedule: "off",
+    wallpaper_fade: false,
+    tray_left_click: "toggle_window",
+    idle_prefetch: false,
+    move_to_trash: true,
+    update_channel: "stable",
+    defer_downloads_on_battery: false,
+    enable_portrait_variant: true,
+    low_disk_space_threshold_mb: 500,
+    virtual_desktop_mode: "all",
+    index_backup_interval_days: 7,
+    custom_ca_path: null,
+    custom_ca_only: false,
+    quit_behavior: "ask",
+    extension_events: false,
+    tray_double_click: "none",
+    solar_schedule: "off",
+    solar_latitude: null,
+    solar_longitude: null,
+    solar_day_wallpaper: null,
+    solar_night_wallpaper: null,
+    jpeg_quality: 0,
+    spotlight_schedule: "off",
+    osd_duration_secs: 0,
+    osd_position: 'top_right',
+    http_keep_alive_secs: 90,
+    placeholder_wallpaper: false,
+    bing_only_network: false,
+    lock_screen_wallpaper: false,
+    greeter_background: "off",
+    sddm_theme_dir: null,
+    apply_hook: null,
   };
 
   let matchMediaMock: {
@@ -243,6 +281,42 @@ describe("ThemeContext", () => {
         new_wallpaper_notification: mockSettings.new_wallpaper_notification,
         save_directory: mockSettings.save_directory,
         launch_at_startup: mockSettings.launch_at_startup,
+        archive_backfill_enabled: mockSettings.archive_backfill_enabled,
+        download_resolution: mockSettings.download_resolution,
+        local_folder: mockSettings.local_folder,
+        local_folder_order: mockSettings.local_folder_order,
+        local_folder_schedule: mockSettings.local_folder_schedule,
+        wallpaper_fade: mockSettings.wallpaper_fade,
+        tray_left_click: mockSettings.tray_left_click,
+        idle_prefetch: mockSettings.idle_prefetch,
+        move_to_trash: mockSettings.move_to_trash,
+        update_channel: mockSettings.update_channel,
+        defer_downloads_on_battery: mockSettings.defer_downloads_on_battery,
+        enable_portrait_variant: mockSettings.enable_portrait_variant,
+        low_disk_space_threshold_mb: mockSettings.low_disk_space_threshold_mb,
+        virtual_desktop_mode: mockSettings.virtual_desktop_mode,
+        index_backup_interval_days: mockSettings.index_backup_interval_days,
+        custom_ca_path: mockSettings.custom_ca_path,
+        custom_ca_only: mockSettings.custom_ca_only,
+        quit_behavior: mockSettings.quit_behavior,
+        extension_events: mockSettings.extension_events,
+        tray_double_click: mockSettings.tray_double_click,
+        solar_schedule: mockSettings.solar_schedule,
+        solar_latitude: mockSettings.solar_latitude,
+        solar_longitude: mockSettings.solar_longitude,
+        solar_day_wallpaper: mockSettings.solar_day_wallpaper,
+        solar_night_wallpaper: mockSettings.solar_night_wallpaper,
+        jpeg_quality: mockSettings.jpeg_quality,
+        spotlight_schedule: mockSettings.spotlight_schedule,
+        osd_duration_secs: mockSettings.osd_duration_secs,
+        osd_position: mockSettings.osd_position,
+        http_keep_alive_secs: mockSettings.http_keep_alive_secs,
+        placeholder_wallpaper: mockSettings.placeholder_wallpaper,
+        bing_only_network: mockSettings.bing_only_network,
+        lock_screen_wallpaper: mockSettings.lock_screen_wallpaper,
+        greeter_background: mockSettings.greeter_background,
+        sddm_theme_dir: mockSettings.sddm_theme_dir,
+        apply_hook: mockSettings.apply_hook,
         theme: "dark",
       },
     });
@@ -543,4 +617,50 @@ describe("ThemeContext", () => {
     });
     expect(result.current.theme).toBe("system");
   });
+
+  it("should follow wallpaper brightness when theme is wallpaper", async () => {
+    let onSuggestedTheme: ((event: { payload: string }) => void) | undefined;
+    vi.mocked(listen).mockImplementation(async (event, cb) => {
+      if (event === EVENTS.SUGGESTED_THEME) {
+        onSuggestedTheme = cb as typeof onSuggestedTheme;
+      }
+      return () => {};
+    });
+    vi.mocked(invoke).mockImplementation((cmd: string) => {
+      if (cmd === "get_settings") {
+        return Promise.resolve({ ...mockSettings, theme: "wallpaper" });
+      }
+      if (cmd === "get_wallpaper_theme") {
+        return Promise.resolve("dark");
+      }
+      return Promise.resolve(null);
+    });
+
+    const { result } = renderHook(() => useTheme(), { wrapper });
+
+    await waitFor(() => {
+      expect(result.current.theme).toBe("wallpaper");
+      expect(result.current.actualTheme).toBe("dark");
+    });
+    expect(onSuggestedTheme).toBeDefined();
+
+    // 应用了一张明亮的新壁纸
+    act(() => {
+      onSuggestedTheme!({ payload: "light" });
+    });
+    expect(result.current.actualTheme).toBe("light");
+    expect(document.documentElement.setAttribute).toHaveBeenCalledWith(
+      "data-theme",
+      "light",
+    );
+
+    // 切换到固定主题后不再跟随壁纸
+    act(() => {
+      result.current.applyThemeToUI("dark");
+    });
+    act(() => {
+      onSuggestedTheme!({ payload: "light" });
+    });
+    expect(result.current.actualTheme).toBe("dark");
+  });
 });
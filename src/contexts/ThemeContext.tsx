@@ -8,8 +8,13 @@ import {
   useRef,
 } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { createSafeUnlisten } from "../utils/eventListener";
+import { EVENTS } from "../config/ui";
 
-export type Theme = "light" | "dark" | "system";
+/** "wallpaper"：根据当前壁纸的明暗自动选择浅色或深色 */
+export type Theme = "light" | "dark" | "system" | "wallpaper";
+type ResolvedTheme = "light" | "dark";
 export const THEME_STORAGE_KEY = "bing-wallpaper-now.theme";
 
 interface ThemeContextType {
@@ -22,7 +27,16 @@ interface ThemeContextType {
 const ThemeContext = createContext<ThemeContextType | undefined>(undefined);
 
 function isTheme(value: unknown): value is Theme {
-  return value === "light" || value === "dark" || value === "system";
+  return (
+    value === "light" ||
+    value === "dark" ||
+    value === "system" ||
+    value === "wallpaper"
+  );
+}
+
+function isResolvedTheme(value: unknown): value is ResolvedTheme {
+  return value === "light" || value === "dark";
 }
 
 function getSystemTheme(): "light" | "dark" {
@@ -49,10 +63,27 @@ function writeStoredTheme(theme: Theme) {
   }
 }
 
-function resolveTheme(theme: Theme): "light" | "dark" {
+// 跟随壁纸时若尚未得到壁纸明暗（无当前壁纸或分析失败），退回跟随系统
+function resolveTheme(
+  theme: Theme,
+  wallpaperTone: ResolvedTheme | null = null,
+): ResolvedTheme {
+  if (theme === "wallpaper") {
+    return wallpaperTone ?? getSystemTheme();
+  }
   return theme === "system" ? getSystemTheme() : theme;
 }
 
+async function fetchWallpaperTone(): Promise<ResolvedTheme | null> {
+  try {
+    const tone = await invoke<string | null>("get_wallpaper_theme");
+    return isResolvedTheme(tone) ? tone : null;
+  } catch (error) {
+    console.error("Failed to get wallpaper theme:", error);
+    return null;
+  }
+}
+
 function applyTheme(theme: "light" | "dark") {
   document.documentElement.setAttribute("data-theme", theme);
 }
@@ -62,6 +93,7 @@ export function ThemeProvider({ children }: { children: ReactNode }) {
   const [actualTheme, setActualTheme] = useState<"light" | "dark">(() =>
     resolveTheme(readStoredTheme()),
   );
+  const wallpaperToneRef = useRef<ResolvedTheme | null>(null);
 
   // Apply a synchronous fallback theme before the first paint.
   // Persisted settings still take precedence once get_settings resolves.
@@ -79,6 +111,42 @@ export function ThemeProvider({ children }: { children: ReactNode }) {
           new_wallpaper_notification: boolean;
           save_directory: string | null;
           launch_at_startup: boolean;
+          archive_backfill_enabled: boolean;
+          download_resolution: string;
+          local_folder: string | null;
+          local_folder_order: string;
+          local_folder_schedule: string;
+          wallpaper_fade: boolean;
+          tray_left_click: string;
+          idle_prefetch: boolean;
+          move_to_trash: boolean;
+          update_channel: string;
+          defer_downloads_on_battery: boolean;
+          enable_portrait_variant: boolean;
+          low_disk_space_threshold_mb: number;
+          virtual_desktop_mode: string;
+          index_backup_interval_days: number;
+          custom_ca_path: string | null;
+          custom_ca_only: boolean;
+          quit_behavior: string;
+          extension_events: boolean;
+          tray_double_click: string;
+          solar_schedule: string;
+          solar_latitude: number | null;
+          solar_longitude: number | null;
+          solar_day_wallpaper: string | null;
+          solar_night_wallpaper: string | null;
+          jpeg_quality: number;
+          spotlight_schedule: string;
+          osd_duration_secs: number;
+          osd_position: string;
+          http_keep_alive_secs: number;
+          placeholder_wallpaper: boolean;
+          bing_only_network: boolean;
+          lock_screen_wallpaper: boolean;
+          greeter_background: "off" | "sddm";
+          sddm_theme_dir: string | null;
+          apply_hook: string | null;
         }>("get_settings");
 
         if (!settings || typeof settings !== "object") {
@@ -88,7 +156,13 @@ export function ThemeProvider({ children }: { children: ReactNode }) {
         const savedTheme = isTheme(settings.theme) ? settings.theme : "system";
         setThemeState(savedTheme);
 
-        const resolvedTheme = resolveTheme(savedTheme);
+        if (savedTheme === "wallpaper") {
+          wallpaperToneRef.current = await fetchWallpaperTone();
+        }
+        const resolvedTheme = resolveTheme(
+          savedTheme,
+          wallpaperToneRef.current,
+        );
         setActualTheme(resolvedTheme);
         writeStoredTheme(savedTheme);
         applyTheme(resolvedTheme);
@@ -118,7 +192,10 @@ export function ThemeProvider({ children }: { children: ReactNode }) {
     const mediaQuery = window.matchMedia("(prefers-color-scheme: dark)");
 
     const handleChange = (e: { matches: boolean }) => {
-      if (themeRef.current === "system") {
+      if (
+        themeRef.current === "system" ||
+        (themeRef.current === "wallpaper" && !wallpaperToneRef.current)
+      ) {
         const newTheme = e.matches ? "dark" : "light";
         setActualTheme(newTheme);
         applyTheme(newTheme);
@@ -129,13 +206,59 @@ export function ThemeProvider({ children }: { children: ReactNode }) {
     return () => mediaQuery.removeEventListener("change", handleChange);
   }, []); // Empty dependency array - listener created once
 
+  // Listen for wallpaper brightness suggestions from the backend
+  useEffect(() => {
+    let mounted = true;
+    let unlisten: (() => void) | undefined;
+
+    (async () => {
+      try {
+        const unlistenFn = await listen<string>(
+          EVENTS.SUGGESTED_THEME,
+          (event) => {
+            if (!isResolvedTheme(event.payload)) return;
+            wallpaperToneRef.current = event.payload;
+            if (themeRef.current === "wallpaper") {
+              setActualTheme(event.payload);
+              applyTheme(event.payload);
+            }
+          },
+        );
+        const safeUnlisten = createSafeUnlisten(unlistenFn);
+        if (mounted) {
+          unlisten = safeUnlisten;
+        } else {
+          safeUnlisten();
+        }
+      } catch (e) {
+        console.error(`Failed to bind ${EVENTS.SUGGESTED_THEME} event:`, e);
+      }
+    })();
+
+    return () => {
+      mounted = false;
+      unlisten?.();
+    };
+  }, []);
+
   // Apply theme to UI only, without saving to backend
   const applyThemeToUI = (newTheme: Theme) => {
     setThemeState(newTheme);
-    const resolvedTheme = resolveTheme(newTheme);
+    themeRef.current = newTheme;
+    const resolvedTheme = resolveTheme(newTheme, wallpaperToneRef.current);
     setActualTheme(resolvedTheme);
     writeStoredTheme(newTheme);
     applyTheme(resolvedTheme);
+
+    if (newTheme === "wallpaper" && !wallpaperToneRef.current) {
+      void fetchWallpaperTone().then((tone) => {
+        wallpaperToneRef.current = tone;
+        if (tone && themeRef.current === "wallpaper") {
+          setActualTheme(tone);
+          applyTheme(tone);
+        }
+      });
+    }
   };
 
   const setTheme = async (newTheme: Theme) => {
@@ -147,6 +270,42 @@ export function ThemeProvider({ children }: { children: ReactNode }) {
         new_wallpaper_notification: boolean;
         save_directory: string | null;
         launch_at_startup: boolean;
+        archive_backfill_enabled: boolean;
+        download_resolution: string;
+        local_folder: string | null;
+        local_folder_order: string;
+        local_folder_schedule: string;
+        wallpaper_fade: boolean;
+        tray_left_click: string;
+        idle_prefetch: boolean;
+        move_to_trash: boolean;
+        update_channel: string;
+        defer_downloads_on_battery: boolean;
+        enable_portrait_variant: boolean;
+        low_disk_space_threshold_mb: number;
+        virtual_desktop_mode: string;
+        index_backup_interval_days: number;
+        custom_ca_path: string | null;
+        custom_ca_only: boolean;
+        quit_behavior: string;
+        extension_events: boolean;
+        tray_double_click: string;
+        solar_schedule: string;
+        solar_latitude: number | null;
+        solar_longitude: number | null;
+        solar_day_wallpaper: string | null;
+        solar_night_wallpaper: string | null;
+        jpeg_quality: number;
+        spotlight_schedule: string;
+        osd_duration_secs: number;
+        osd_position: string;
+        http_keep_alive_secs: number;
+        placeholder_wallpaper: boolean;
+        bing_only_network: boolean;
+        lock_screen_wallpaper: boolean;
+        greeter_background: "off" | "sddm";
+        sddm_theme_dir: string | null;
+        apply_hook: string | null;
       }>("get_settings");
 
       // Update theme in settings - 使用驼峰命名 newSettings
@@ -156,6 +315,42 @@ export function ThemeProvider({ children }: { children: ReactNode }) {
           new_wallpaper_notification: settings.new_wallpaper_notification,
           save_directory: settings.save_directory,
           launch_at_startup: settings.launch_at_startup,
+          archive_backfill_enabled: settings.archive_backfill_enabled,
+          download_resolution: settings.download_resolution,
+          local_folder: settings.local_folder,
+          local_folder_order: settings.local_folder_order,
+          local_folder_schedule: settings.local_folder_schedule,
+          wallpaper_fade: settings.wallpaper_fade,
+          tray_left_click: settings.tray_left_click,
+          idle_prefetch: settings.idle_prefetch,
+          move_to_trash: settings.move_to_trash,
+          update_channel: settings.update_channel,
+          defer_downloads_on_battery: settings.defer_downloads_on_battery,
+          enable_portrait_variant: settings.enable_portrait_variant,
+          low_disk_space_threshold_mb: settings.low_disk_space_threshold_mb,
+          virtual_desktop_mode: settings.virtual_desktop_mode,
+          index_backup_interval_days: settings.index_backup_interval_days,
+          custom_ca_path: settings.custom_ca_path,
+          custom_ca_only: settings.custom_ca_only,
+          quit_behavior: settings.quit_behavior,
+          extension_events: settings.extension_events,
+          tray_double_click: settings.tray_double_click,
+          solar_schedule: settings.solar_schedule,
+          solar_latitude: settings.solar_latitude,
+          solar_longitude: settings.solar_longitude,
+          solar_day_wallpaper: settings.solar_day_wallpaper,
+          solar_night_wallpaper: settings.solar_night_wallpaper,
+          jpeg_quality: settings.jpeg_quality,
+          spotlight_schedule: settings.spotlight_schedule,
+          osd_duration_secs: settings.osd_duration_secs,
+          osd_position: settings.osd_position,
+          http_keep_alive_secs: settings.http_keep_alive_secs,
+          placeholder_wallpaper: settings.placeholder_wallpaper,
+          bing_only_network: settings.bing_only_network,
+          lock_screen_wallpaper: settings.lock_screen_wallpaper,
+          greeter_background: settings.greeter_background,
+          sddm_theme_dir: settings.sddm_theme_dir,
+          apply_hook: settings.apply_hook,
           theme: newTheme,
         },
       });
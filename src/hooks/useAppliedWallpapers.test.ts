@@ -0,0 +1,58 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { renderHook, waitFor } from "@testing-library/react";
+import { invoke } from "@tauri-apps/api/core";
+import { useAppliedWallpapers } from "./useAppliedWallpapers";
+
+// Mock Tauri API
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
+
+describe("useAppliedWallpapers", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+  });
+
+  it("应该获取各显示器的壁纸并标出不一致的显示器", async () => {
+    const mockWallpapers = [
+      {
+        screen_index: 0,
+        file_path: "/wallpapers/20240101.jpg",
+        end_date: "20240101",
+        matches_current: true,
+      },
+      {
+        screen_index: 1,
+        file_path: "/usr/share/backgrounds/default.jpg",
+        end_date: null,
+        matches_current: false,
+      },
+    ];
+
+    vi.mocked(invoke).mockResolvedValue(mockWallpapers);
+
+    const { result } = renderHook(() => useAppliedWallpapers());
+
+    await waitFor(() => {
+      expect(result.current.loading).toBe(false);
+    });
+
+    expect(result.current.wallpapers).toEqual(mockWallpapers);
+    expect(result.current.mismatched).toEqual([mockWallpapers[1]]);
+    expect(result.current.error).toBeNull();
+    expect(invoke).toHaveBeenCalledWith("get_applied_wallpapers");
+  });
+
+  it("应该正确处理错误", async () => {
+    vi.mocked(invoke).mockRejectedValue(new Error("获取显示器壁纸失败"));
+
+    const { result } = renderHook(() => useAppliedWallpapers());
+
+    await waitFor(() => {
+      expect(result.current.loading).toBe(false);
+    });
+
+    expect(result.current.error).toBe("获取显示器壁纸失败");
+    expect(result.current.wallpapers).toEqual([]);
+  });
+});
@@ -0,0 +1,51 @@
+import { useState, useEffect, useCallback } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { AppliedWallpaper } from "../types";
+
+/**
+ * 获取各显示器实际显示的壁纸的 Hook
+ */
+export function useAppliedWallpapers() {
+  const [wallpapers, setWallpapers] = useState<AppliedWallpaper[]>([]);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  /**
+   * 获取所有显示器的壁纸
+   */
+  const fetchAppliedWallpapers = useCallback(async () => {
+    setLoading(true);
+    setError(null);
+    try {
+      const result = await invoke<AppliedWallpaper[]>(
+        "get_applied_wallpapers",
+      );
+      setWallpapers(result);
+    } catch (err) {
+      const errorMessage =
+        err instanceof Error ? err.message : "获取显示器壁纸失败";
+      setError(errorMessage);
+      console.error("Failed to get applied wallpapers:", err);
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
+  // 组件挂载时自动获取一次
+  useEffect(() => {
+    fetchAppliedWallpapers();
+  }, [fetchAppliedWallpapers]);
+
+  /**
+   * 显示的壁纸与本应用最近设置的不一致的显示器
+   */
+  const mismatched = wallpapers.filter((w) => !w.matches_current);
+
+  return {
+    wallpapers,
+    loading,
+    error,
+    fetchAppliedWallpapers,
+    mismatched,
+  };
+}
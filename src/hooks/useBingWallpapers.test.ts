@@ -1,6 +1,7 @@
 import { describe, it, expect, beforeEach, vi } from "vitest";
 import { renderHook, waitFor, act } from "@testing-library/react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { useBingWallpapers } from "./useBingWallpapers";
 import { LocalWallpaperRaw } from "../types";
 
@@ -152,6 +153,28 @@ describe("useBingWallpapers", () => {
     expect(invoke).toHaveBeenCalledWith("force_update");
   });
 
+  it("should ignore BUSY from a repeated forceUpdate", async () => {
+    vi.mocked(invoke).mockImplementation((cmd: string) => {
+      if (cmd === "force_update") {
+        return Promise.reject("BUSY");
+      }
+      return Promise.resolve([]);
+    });
+
+    const { result } = renderHook(() => useBingWallpapers());
+
+    await waitFor(() => {
+      expect(result.current.loading).toBe(false);
+    });
+
+    await act(async () => {
+      await result.current.forceUpdate();
+    });
+
+    expect(result.current.error).toBeNull();
+    expect(result.current.loading).toBe(false);
+  });
+
   it("should fetch lastUpdateTime from backend", async () => {
     const mockTime = "2024-01-01 12:00:00";
 
@@ -362,4 +385,91 @@ describe("useBingWallpapers", () => {
     // Should be the same reference (no state update)
     expect(result.current.localWallpapers).toBe(firstWallpapers);
   });
+
+  it("should refresh wallpapers when startup completes", async () => {
+    const handlers = new Map<string, () => void>();
+    vi.mocked(listen).mockImplementation(async (event, handler) => {
+      handlers.set(event, handler as () => void);
+      return () => {};
+    });
+
+    const { result } = renderHook(() => useBingWallpapers());
+    await waitFor(() => {
+      expect(result.current.loading).toBe(false);
+      expect(handlers.has("startup-complete")).toBe(true);
+    });
+
+    const callsBefore = vi
+      .mocked(invoke)
+      .mock.calls.filter(([cmd]) => cmd === "get_local_wallpapers").length;
+    act(() => handlers.get("startup-complete")!());
+
+    await waitFor(() => {
+      const callsAfter = vi
+        .mocked(invoke)
+        .mock.calls.filter(([cmd]) => cmd === "get_local_wallpapers").length;
+      expect(callsAfter).toBeGreaterThan(callsBefore);
+    });
+  });
+
+  it("should patch a wallpaper when its metadata is refreshed", async () => {
+    const handlers = new Map<string, (event: { payload: unknown }) => void>();
+    vi.mocked(listen).mockImplementation(async (event, handler) => {
+      handlers.set(event, handler as (event: { payload: unknown }) => void);
+      return () => {};
+    });
+    vi.mocked(invoke).mockImplementation((cmd: string) => {
+      if (cmd === "get_local_wallpapers") {
+        return Promise.resolve([
+          { t: "Old", c: "Old Copyright", l: "", d: "20240102" },
+          { t: "Other", c: "Other Copyright", l: "", d: "20240101" },
+        ]);
+      }
+      if (cmd === "get_market_status") {
+        return Promise.resolve({
+          requested_mkt: "zh-CN",
+          effective_mkt: "zh-CN",
+          is_mismatch: false,
+        });
+      }
+      if (cmd === "get_supported_mkts") {
+        return Promise.resolve([
+          {
+            region: "asia_pacific",
+            markets: [{ code: "zh-CN", label: "中国大陆" }],
+          },
+        ]);
+      }
+      return Promise.resolve([]);
+    });
+
+    const { result } = renderHook(() => useBingWallpapers());
+    await waitFor(() => {
+      expect(result.current.localWallpapers).toHaveLength(2);
+      expect(result.current.effectiveMktLabel).toBe("中国大陆");
+      expect(handlers.has("wallpaper-metadata-refreshed")).toBe(true);
+    });
+
+    const refresh = (mkt: string, title: string) =>
+      act(() =>
+        handlers.get("wallpaper-metadata-refreshed")!({
+          payload: {
+            mkt,
+            wallpaper: { t: title, c: "New Copyright", l: "", d: "20240102" },
+          },
+        }),
+      );
+
+    // 其他 mkt 的刷新不影响当前列表
+    refresh("en-US", "English");
+    expect(result.current.localWallpapers[0].title).toBe("Old");
+
+    refresh("zh-CN", "New");
+    expect(result.current.localWallpapers[0]).toMatchObject({
+      title: "New",
+      copyright: "New Copyright",
+      end_date: "20240102",
+    });
+    expect(result.current.localWallpapers[1].title).toBe("Other");
+  });
 });
@@ -6,8 +6,11 @@ import {
   LocalWallpaperRaw,
   MarketGroup,
   MarketStatus,
+  WallpaperMetadataRefreshed,
+  normalizeWallpaper,
   normalizeWallpapers,
 } from "../types";
+import { EVENTS } from "../config/ui";
 import { createSafeUnlisten } from "../utils/eventListener";
 
 /**
@@ -112,6 +115,8 @@ export function useBingWallpapers() {
         await fetchLocalWallpapers(true);
         await pollStatus();
       } catch (err) {
+        // 更新正在进行或刚刚执行过，后端拒绝重复触发，不视为错误
+        if (err === "BUSY") return;
         setError(String(err));
         throw err;
       } finally {
@@ -144,6 +149,7 @@ export function useBingWallpapers() {
   // Use refs to keep stable references to the callback functions
   const fetchLocalWallpapersRef = useRef(fetchLocalWallpapers);
   const pollStatusRef = useRef(pollStatus);
+  const effectiveMktRef = useRef(effectiveMkt);
 
   // Update refs when functions change
   useEffect(() => {
@@ -154,32 +160,85 @@ export function useBingWallpapers() {
     pollStatusRef.current = pollStatus;
   }, [pollStatus]);
 
-  // 监听后端壁纸更新事件，自动刷新列表（静默刷新，不显示 loading）
+  useEffect(() => {
+    effectiveMktRef.current = effectiveMkt;
+  }, [effectiveMkt]);
+
+  // 监听后端壁纸更新事件和启动完成事件，自动刷新列表（静默刷新，不显示 loading）
+  // 启动完成时上次异常退出的修复和首次更新检查已开始，列表可能与初次加载时不同
   // 使用空依赖数组和 ref，确保监听器只创建一次，避免重复创建
   useEffect(() => {
-    let unlisten: (() => void) | undefined;
+    const unlisteners: (() => void)[] = [];
     let mounted = true;
 
-    (async () => {
-      try {
-        if (!mounted) return;
+    for (const event of [EVENTS.WALLPAPER_UPDATED, EVENTS.STARTUP_COMPLETE]) {
+      (async () => {
+        try {
+          if (!mounted) return;
 
-        const unlistenFn = await listen("wallpaper-updated", () => {
-          // 使用 ref 来获取最新的函数，避免闭包陷阱
-          fetchLocalWallpapersRef.current(false);
-          pollStatusRef.current();
-        });
+          const unlistenFn = await listen(event, () => {
+            // 使用 ref 来获取最新的函数，避免闭包陷阱
+            fetchLocalWallpapersRef.current(false);
+            pollStatusRef.current();
+          });
 
-        // Wrap unlisten to make it safe (handles React StrictMode double-mount)
+          // Wrap unlisten to make it safe (handles React StrictMode double-mount)
+          const safeUnlisten = createSafeUnlisten(unlistenFn);
+
+          if (mounted) {
+            unlisteners.push(safeUnlisten);
+          } else {
+            safeUnlisten(); // Cleanup immediately if unmounted
+          }
+        } catch (e) {
+          console.error(`Failed to bind ${event} event:`, e);
+        }
+      })();
+    }
+
+    return () => {
+      mounted = false;
+      unlisteners.forEach((unlisten) => unlisten());
+    };
+  }, []); // Empty deps - listeners created once, never recreated
+
+  // 单张壁纸元数据刷新后只更新列表中的这一条，其他 mkt 的刷新不影响当前列表
+  useEffect(() => {
+    let mounted = true;
+    let unlisten: (() => void) | undefined;
+
+    (async () => {
+      try {
+        const unlistenFn = await listen<WallpaperMetadataRefreshed>(
+          EVENTS.WALLPAPER_METADATA_REFRESHED,
+          (event) => {
+            if (!mounted || event.payload.mkt !== effectiveMktRef.current) {
+              return;
+            }
+            const refreshed = normalizeWallpaper(event.payload.wallpaper);
+            setLocalWallpapers((prev) =>
+              prev.map((w) =>
+                w.end_date === refreshed.end_date
+                  ? {
+                      ...w,
+                      title: refreshed.title,
+                      copyright: refreshed.copyright,
+                      copyright_link: refreshed.copyright_link,
+                    }
+                  : w,
+              ),
+            );
+          },
+        );
         const safeUnlisten = createSafeUnlisten(unlistenFn);
 
         if (mounted) {
           unlisten = safeUnlisten;
         } else {
-          safeUnlisten(); // Cleanup immediately if unmounted
+          safeUnlisten();
         }
       } catch (e) {
-        console.error("Failed to bind wallpaper-updated event:", e);
+        console.error("Failed to bind wallpaper-metadata-refreshed event:", e);
       }
     })();
 
@@ -187,7 +246,7 @@ export function useBingWallpapers() {
       mounted = false;
       unlisten?.();
     };
-  }, []); // Empty deps - listener created once, never recreated
+  }, []);
 
   // 优化：智能轮询后台状态
   // 使用页面可见性 API 和焦点检测，在应用获得焦点或变为可见时才轮询
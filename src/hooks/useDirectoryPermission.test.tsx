@@ -0,0 +1,86 @@
+import { ReactNode } from "react";
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { renderHook, waitFor, act } from "@testing-library/react";
+import { listen } from "@tauri-apps/api/event";
+import { invoke } from "@tauri-apps/api/core";
+import { ask } from "@tauri-apps/plugin-dialog";
+import { useDirectoryPermission } from "./useDirectoryPermission";
+import { showSystemNotification } from "../utils/notification";
+import { I18nProvider } from "../i18n/I18nContext";
+import { EVENTS } from "../config/ui";
+
+vi.mock("@tauri-apps/api/core");
+vi.mock("@tauri-apps/api/event");
+vi.mock("../utils/notification");
+
+function wrapper({ children }: { children: ReactNode }) {
+  return <I18nProvider>{children}</I18nProvider>;
+}
+
+// oxlint-disable-next-line typescript/no-explicit-any
+type AnyEventHandler = (...args: any[]) => void;
+
+describe("useDirectoryPermission", () => {
+  let eventCallbacks: Map<string, AnyEventHandler>;
+
+  beforeEach(() => {
+    vi.clearAllMocks();
+    eventCallbacks = new Map();
+
+    vi.mocked(listen).mockImplementation(async (event, cb) => {
+      eventCallbacks.set(event as string, cb as AnyEventHandler);
+      return () => {};
+    });
+    vi.mocked(invoke).mockResolvedValue(undefined);
+    vi.mocked(showSystemNotification).mockResolvedValue(undefined);
+  });
+
+  const emitError = async (defaultDirectory: string | null) => {
+    await waitFor(() => {
+      expect(eventCallbacks.has(EVENTS.DIRECTORY_PERMISSION_ERROR)).toBe(true);
+    });
+    await act(async () => {
+      eventCallbacks.get(EVENTS.DIRECTORY_PERMISSION_ERROR)!({
+        payload: {
+          directory: "/mnt/readonly",
+          default_directory: defaultDirectory,
+          message: "Permission denied",
+        },
+      });
+    });
+  };
+
+  it("should fall back to the default directory after confirmation", async () => {
+    vi.mocked(ask).mockResolvedValue(true);
+    const onDirectoryChanged = vi.fn();
+    renderHook(() => useDirectoryPermission(onDirectoryChanged), { wrapper });
+
+    await emitError("/home/user/Pictures/Bing Wallpaper Now");
+
+    await waitFor(() => {
+      expect(invoke).toHaveBeenCalledWith("fallback_to_default_directory");
+      expect(onDirectoryChanged).toHaveBeenCalled();
+    });
+  });
+
+  it("should keep the directory when the user declines", async () => {
+    vi.mocked(ask).mockResolvedValue(false);
+    const onDirectoryChanged = vi.fn();
+    renderHook(() => useDirectoryPermission(onDirectoryChanged), { wrapper });
+
+    await emitError("/home/user/Pictures/Bing Wallpaper Now");
+
+    await waitFor(() => expect(ask).toHaveBeenCalled());
+    expect(invoke).not.toHaveBeenCalledWith("fallback_to_default_directory");
+    expect(onDirectoryChanged).not.toHaveBeenCalled();
+  });
+
+  it("should only notify when no fallback is available", async () => {
+    renderHook(() => useDirectoryPermission(vi.fn()), { wrapper });
+
+    await emitError(null);
+
+    await waitFor(() => expect(showSystemNotification).toHaveBeenCalled());
+    expect(ask).not.toHaveBeenCalled();
+  });
+});
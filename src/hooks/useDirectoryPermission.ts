@@ -0,0 +1,87 @@
+import { useEffect, useRef } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { ask } from "@tauri-apps/plugin-dialog";
+import { createSafeUnlisten } from "../utils/eventListener";
+import { EVENTS } from "../config/ui";
+import { DirectoryPermissionError } from "../types";
+import { useI18n } from "../i18n/I18nContext";
+import { showSystemNotification } from "../utils/notification";
+
+/**
+ * 壁纸目录无写入权限时询问用户是否切回默认目录。
+ *
+ * 后端对同一目录只发送一次 directory-permission-error 事件；
+ * 无法回退（已是默认目录或被管理员锁定）时只提示错误。
+ */
+export function useDirectoryPermission(onDirectoryChanged: () => void) {
+  const { t } = useI18n();
+  const tRef = useRef(t);
+  const onDirectoryChangedRef = useRef(onDirectoryChanged);
+  useEffect(() => {
+    tRef.current = t;
+    onDirectoryChangedRef.current = onDirectoryChanged;
+  });
+
+  useEffect(() => {
+    let mounted = true;
+    let unlisten: (() => void) | undefined;
+
+    const handleError = async (error: DirectoryPermissionError) => {
+      const t = tRef.current;
+      const message = t("directoryPermissionErrorMessage").replace(
+        "{directory}",
+        error.directory,
+      );
+      if (!error.default_directory) {
+        await showSystemNotification(t("directoryPermissionError"), message);
+        return;
+      }
+
+      const confirmed = await ask(
+        `${message}\n\n${t("directoryPermissionFallback").replace(
+          "{directory}",
+          error.default_directory,
+        )}`,
+        { title: t("directoryPermissionError"), kind: "warning" },
+      );
+      if (!confirmed) return;
+
+      try {
+        await invoke<string>("fallback_to_default_directory");
+        onDirectoryChangedRef.current();
+      } catch (err) {
+        console.error("Failed to fall back to default directory:", err);
+        await showSystemNotification(
+          t("directoryPermissionError"),
+          `${t("directoryPermissionFallbackFailed")}: ${String(err)}`,
+        );
+      }
+    };
+
+    (async () => {
+      try {
+        const unlistenFn = await listen<DirectoryPermissionError>(
+          EVENTS.DIRECTORY_PERMISSION_ERROR,
+          (event) => {
+            void handleError(event.payload);
+          },
+        );
+        const safeUnlisten = createSafeUnlisten(unlistenFn);
+
+        if (mounted) {
+          unlisten = safeUnlisten;
+        } else {
+          safeUnlisten();
+        }
+      } catch (e) {
+        console.error("Failed to bind directory-permission-error event:", e);
+      }
+    })();
+
+    return () => {
+      mounted = false;
+      unlisten?.();
+    };
+  }, []);
+}
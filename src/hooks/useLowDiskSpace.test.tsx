@@ -0,0 +1,56 @@
+import { ReactNode } from "react";
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { renderHook, waitFor, act } from "@testing-library/react";
+import { listen } from "@tauri-apps/api/event";
+import { useLowDiskSpace } from "./useLowDiskSpace";
+import { showSystemNotification } from "../utils/notification";
+import { I18nProvider } from "../i18n/I18nContext";
+import { EVENTS } from "../config/ui";
+
+vi.mock("@tauri-apps/api/event");
+vi.mock("../utils/notification");
+
+function wrapper({ children }: { children: ReactNode }) {
+  return <I18nProvider>{children}</I18nProvider>;
+}
+
+// oxlint-disable-next-line typescript/no-explicit-any
+type AnyEventHandler = (...args: any[]) => void;
+
+describe("useLowDiskSpace", () => {
+  let eventCallbacks: Map<string, AnyEventHandler>;
+
+  beforeEach(() => {
+    vi.clearAllMocks();
+    eventCallbacks = new Map();
+
+    vi.mocked(listen).mockImplementation(async (event, cb) => {
+      eventCallbacks.set(event as string, cb as AnyEventHandler);
+      return () => {};
+    });
+    vi.mocked(showSystemNotification).mockResolvedValue(undefined);
+  });
+
+  it("should notify with the free space and threshold in MB", async () => {
+    renderHook(() => useLowDiskSpace(), { wrapper });
+
+    await waitFor(() => {
+      expect(eventCallbacks.has(EVENTS.LOW_DISK_SPACE)).toBe(true);
+    });
+    await act(async () => {
+      eventCallbacks.get(EVENTS.LOW_DISK_SPACE)!({
+        payload: {
+          directory: "/data/wallpapers",
+          available_bytes: 120 * 1024 * 1024,
+          threshold_bytes: 500 * 1024 * 1024,
+        },
+      });
+    });
+
+    expect(showSystemNotification).toHaveBeenCalledTimes(1);
+    const message = vi.mocked(showSystemNotification).mock.calls[0][1];
+    expect(message).toContain("/data/wallpapers");
+    expect(message).toContain("120");
+    expect(message).toContain("500");
+  });
+});
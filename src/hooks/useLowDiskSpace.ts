@@ -0,0 +1,66 @@
+import { useEffect, useRef } from "react";
+import { listen } from "@tauri-apps/api/event";
+import { createSafeUnlisten } from "../utils/eventListener";
+import { EVENTS } from "../config/ui";
+import { LowDiskSpace } from "../types";
+import { useI18n } from "../i18n/I18nContext";
+import { showSystemNotification } from "../utils/notification";
+
+const BYTES_PER_MB = 1024 * 1024;
+
+/**
+ * 壁纸目录磁盘空间不足时发送系统通知。
+ *
+ * 后端在进入空间不足状态时发送一次 low-disk-space 事件，
+ * 并在之后的更新循环中自动重试下载，前端只负责提示。
+ */
+export function useLowDiskSpace() {
+  const { t } = useI18n();
+  const tRef = useRef(t);
+  useEffect(() => {
+    tRef.current = t;
+  });
+
+  useEffect(() => {
+    let mounted = true;
+    let unlisten: (() => void) | undefined;
+
+    (async () => {
+      try {
+        const unlistenFn = await listen<LowDiskSpace>(
+          EVENTS.LOW_DISK_SPACE,
+          (event) => {
+            const t = tRef.current;
+            const { directory, available_bytes, threshold_bytes } =
+              event.payload;
+            const message = t("lowDiskSpaceMessage")
+              .replace("{directory}", directory)
+              .replace(
+                "{available}",
+                String(Math.floor(available_bytes / BYTES_PER_MB)),
+              )
+              .replace(
+                "{threshold}",
+                String(Math.floor(threshold_bytes / BYTES_PER_MB)),
+              );
+            void showSystemNotification(t("lowDiskSpace"), message);
+          },
+        );
+        const safeUnlisten = createSafeUnlisten(unlistenFn);
+
+        if (mounted) {
+          unlisten = safeUnlisten;
+        } else {
+          safeUnlisten();
+        }
+      } catch (e) {
+        console.error("Failed to bind low-disk-space event:", e);
+      }
+    })();
+
+    return () => {
+      mounted = false;
+      unlisten?.();
+    };
+  }, []);
+}
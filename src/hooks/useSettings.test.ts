@@ -1,10 +1,12 @@
 import { describe, it, expect, beforeEach, vi } from "vitest";
 import { renderHook, waitFor, act } from "@testing-library/react";
 import { invoke } from "@tauri-apps/api/core";
-import { useSettings } from "./useSettings";
+import { listen } from "@tauri-apps/api/event";
+import { applySettingChanges, useSettings } from "./useSettings";
 import { AppSettings } from "../types";
 
 vi.mock("@tauri-apps/api/core");
+vi.mock("@tauri-apps/api/event");
 
 describe("useSettings", () => {
   const mockSettings: AppSettings = {
@@ -16,10 +18,47 @@ describe("useSettings", () => {
     language: "zh-CN",
     resolved_language: "zh-CN",
     mkt: "zh-CN",
+    archive_backfill_enabled: false,
+    download_resolution: "auto",
+    local_folder: null,
+    local_folder_order: "mtime",
+    local_folder_schedule: "off",
+    wallpaper_fade: false,
+    tray_left_click: "toggle_window",
+    idle_prefetch: false,
+    move_to_trash: true,
+    update_channel: "stable",
+    defer_downloads_on_battery: false,
+    enable_portrait_variant: true,
+    low_disk_space_threshold_mb: 500,
+    virtual_desktop_mode: "all",
+    index_backup_interval_days: 7,
+    custom_ca_path: null,
+    custom_ca_only: false,
+    quit_behavior: "ask",
+    extension_events: false,
+    tray_double_click: "none",
+    solar_schedule: "off",
+    solar_latitude: null,
+    solar_longitude: null,
+    solar_day_wallpaper: null,
+    solar_night_wallpaper: null,
+    jpeg_quality: 0,
+    spotlight_schedule: "off",
+    osd_duration_secs: 0,
+    osd_position: 'top_right',
+    http_keep_alive_secs: 90,
+    placeholder_wallpaper: false,
+    bing_only_network: false,
+    lock_screen_wallpaper: false,
+    greeter_background: "off",
+    sddm_theme_dir: null,
+    apply_hook: null,
   };
 
   beforeEach(() => {
     vi.clearAllMocks();
+    vi.mocked(listen).mockResolvedValue(() => {});
   });
 
   it("should initialize with default values", async () => {
@@ -111,6 +150,42 @@ describe("useSettings", () => {
         theme: updatedSettings.theme,
         language: updatedSettings.language,
         mkt: updatedSettings.mkt,
+        archive_backfill_enabled: updatedSettings.archive_backfill_enabled,
+        download_resolution: updatedSettings.download_resolution,
+        local_folder: updatedSettings.local_folder,
+        local_folder_order: updatedSettings.local_folder_order,
+        local_folder_schedule: updatedSettings.local_folder_schedule,
+        wallpaper_fade: updatedSettings.wallpaper_fade,
+        tray_left_click: updatedSettings.tray_left_click,
+        idle_prefetch: updatedSettings.idle_prefetch,
+        move_to_trash: updatedSettings.move_to_trash,
+        update_channel: updatedSettings.update_channel,
+        defer_downloads_on_battery: updatedSettings.defer_downloads_on_battery,
+        enable_portrait_variant: updatedSettings.enable_portrait_variant,
+        low_disk_space_threshold_mb: updatedSettings.low_disk_space_threshold_mb,
+        virtual_desktop_mode: updatedSettings.virtual_desktop_mode,
+        index_backup_interval_days: updatedSettings.index_backup_interval_days,
+        custom_ca_path: updatedSettings.custom_ca_path,
+        custom_ca_only: updatedSettings.custom_ca_only,
+        quit_behavior: updatedSettings.quit_behavior,
+        extension_events: updatedSettings.extension_events,
+        tray_double_click: updatedSettings.tray_double_click,
+        solar_schedule: updatedSettings.solar_schedule,
+        solar_latitude: updatedSettings.solar_latitude,
+        solar_longitude: updatedSettings.solar_longitude,
+        solar_day_wallpaper: updatedSettings.solar_day_wallpaper,
+        solar_night_wallpaper: updatedSettings.solar_night_wallpaper,
+        jpeg_quality: updatedSettings.jpeg_quality,
+        spotlight_schedule: updatedSettings.spotlight_schedule,
+        osd_duration_secs: updatedSettings.osd_duration_secs,
+        osd_position: updatedSettings.osd_position,
+        http_keep_alive_secs: updatedSettings.http_keep_alive_secs,
+        placeholder_wallpaper: updatedSettings.placeholder_wallpaper,
+        bing_only_network: updatedSettings.bing_only_network,
+        lock_screen_wallpaper: updatedSettings.lock_screen_wallpaper,
+        greeter_background: updatedSettings.greeter_background,
+        sddm_theme_dir: updatedSettings.sddm_theme_dir,
+        apply_hook: updatedSettings.apply_hook,
       },
     });
 
@@ -168,6 +243,107 @@ describe("useSettings", () => {
     });
   });
 
+  it("should expose autostart failures without failing the save", async () => {
+    vi.mocked(invoke).mockImplementation((cmd: string) => {
+      if (cmd === "get_settings") {
+        return Promise.resolve(mockSettings);
+      }
+      return Promise.resolve(undefined);
+    });
+
+    const { result } = renderHook(() => useSettings());
+
+    await waitFor(() => {
+      expect(result.current.loading).toBe(false);
+    });
+
+    const failed = { status: "failed", reason: "denied by policy" };
+    vi.mocked(invoke).mockImplementation((cmd: string) => {
+      if (cmd === "update_settings") {
+        return Promise.resolve(failed);
+      }
+      if (cmd === "get_settings") {
+        return Promise.resolve(mockSettings);
+      }
+      return Promise.resolve(undefined);
+    });
+
+    await act(async () => {
+      await result.current.updateSettings({
+        ...mockSettings,
+        launch_at_startup: true,
+      });
+    });
+
+    expect(result.current.autostartStatus).toEqual(failed);
+    expect(result.current.error).toBeNull();
+
+    vi.mocked(invoke).mockImplementation((cmd: string) => {
+      if (cmd === "update_settings") {
+        return Promise.resolve({ status: "ok" });
+      }
+      if (cmd === "get_settings") {
+        return Promise.resolve(mockSettings);
+      }
+      return Promise.resolve(undefined);
+    });
+
+    await act(async () => {
+      await result.current.updateSettings(mockSettings);
+    });
+
+    expect(result.current.autostartStatus).toBeNull();
+  });
+
+  it("should expose field errors when validation fails", async () => {
+    vi.mocked(invoke).mockImplementation((cmd: string) => {
+      if (cmd === "get_settings") {
+        return Promise.resolve(mockSettings);
+      }
+      return Promise.resolve(undefined);
+    });
+
+    const { result } = renderHook(() => useSettings());
+
+    await waitFor(() => {
+      expect(result.current.loading).toBe(false);
+    });
+
+    const validationError = {
+      kind: "validation",
+      fields: { save_directory: "NOT_DIRECTORY", mkt: "INVALID_MKT" },
+    };
+    vi.mocked(invoke).mockImplementation((cmd: string) => {
+      if (cmd === "update_settings") {
+        return Promise.reject(validationError);
+      }
+      return Promise.resolve(mockSettings);
+    });
+
+    const consoleErrorSpy = vi
+      .spyOn(console, "error")
+      .mockImplementation(() => {});
+
+    await act(async () => {
+      await expect(
+        result.current.updateSettings({ ...mockSettings, mkt: "bad" }),
+      ).rejects.toBe(validationError);
+    });
+
+    consoleErrorSpy.mockRestore();
+
+    expect(result.current.fieldErrors).toEqual(validationError.fields);
+    expect(result.current.error).toBe(
+      "save_directory: NOT_DIRECTORY, mkt: INVALID_MKT",
+    );
+
+    // 保存成功后清除字段错误
+    await act(async () => {
+      await result.current.updateSettings(mockSettings);
+    });
+    expect(result.current.fieldErrors).toEqual({});
+  });
+
   it("should get default directory successfully", async () => {
     vi.mocked(invoke).mockResolvedValue(mockSettings);
 
@@ -304,4 +480,15 @@ describe("useSettings", () => {
       expect(result.current.loading).toBe(false);
     });
   });
+
+  it("should merge settings-changed payload into settings", () => {
+    const merged = applySettingChanges(mockSettings, [
+      { key: "theme", old_value: "system", new_value: "dark" },
+      { key: "save_directory", old_value: "C:\\Old", new_value: null },
+    ]);
+
+    expect(merged.theme).toBe("dark");
+    expect(merged.save_directory).toBeNull();
+    expect(merged.mkt).toBe(mockSettings.mkt);
+  });
 });
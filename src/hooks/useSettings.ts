@@ -1,6 +1,48 @@
 import { useState, useEffect, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { AppSettings } from "../types";
+import { listen } from "@tauri-apps/api/event";
+import {
+  AppSettings,
+  AutostartStatus,
+  SettingChange,
+  SettingsFieldErrors,
+  UpdateSettingsError,
+} from "../types";
+import { EVENTS } from "../config/ui";
+import { createSafeUnlisten } from "../utils/eventListener";
+
+/**
+ * 把 settings-changed 事件中的变化合并到当前设置
+ */
+export function applySettingChanges(
+  settings: AppSettings,
+  changes: SettingChange[],
+): AppSettings {
+  const next = { ...settings } as Record<string, unknown>;
+  for (const change of changes) {
+    next[change.key] = change.new_value;
+  }
+  return next as unknown as AppSettings;
+}
+
+function isUpdateSettingsError(err: unknown): err is UpdateSettingsError {
+  return typeof err === "object" && err !== null && "kind" in err;
+}
+
+/**
+ * 把 update_settings 的错误转为可读文本（兼容纯字符串错误）
+ */
+export function describeSettingsError(err: unknown): string {
+  if (!isUpdateSettingsError(err)) {
+    return String(err);
+  }
+  if (err.kind === "failed") {
+    return err.message;
+  }
+  return Object.entries(err.fields)
+    .map(([field, code]) => `${field}: ${code}`)
+    .join(", ");
+}
 
 /**
  * 应用设置 Hook
@@ -9,6 +51,10 @@ export function useSettings() {
   const [settings, setSettings] = useState<AppSettings | null>(null);
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
+  const [fieldErrors, setFieldErrors] = useState<SettingsFieldErrors>({});
+  // 最近一次保存时自启动未能应用的原因（成功时为 null）
+  const [autostartStatus, setAutostartStatus] =
+    useState<AutostartStatus | null>(null);
 
   /**
    * 获取设置
@@ -34,23 +80,71 @@ export function useSettings() {
     setError(null);
     try {
       // Tauri 2 的参数传递：使用驼峰命名，Tauri 会自动转换为 Rust 的蛇形命名
-      await invoke("update_settings", {
-        newSettings: {
-          auto_update: newSettings.auto_update,
-          new_wallpaper_notification: newSettings.new_wallpaper_notification,
-          save_directory: newSettings.save_directory,
-          launch_at_startup: newSettings.launch_at_startup,
-          theme: newSettings.theme,
-          language: newSettings.language,
-          mkt: newSettings.mkt,
+      const autostart = await invoke<AutostartStatus | undefined>(
+        "update_settings",
+        {
+          newSettings: {
+            auto_update: newSettings.auto_update,
+            new_wallpaper_notification: newSettings.new_wallpaper_notification,
+            save_directory: newSettings.save_directory,
+            launch_at_startup: newSettings.launch_at_startup,
+            theme: newSettings.theme,
+            language: newSettings.language,
+            mkt: newSettings.mkt,
+            archive_backfill_enabled: newSettings.archive_backfill_enabled,
+            download_resolution: newSettings.download_resolution,
+            local_folder: newSettings.local_folder,
+            local_folder_order: newSettings.local_folder_order,
+            local_folder_schedule: newSettings.local_folder_schedule,
+            wallpaper_fade: newSettings.wallpaper_fade,
+            tray_left_click: newSettings.tray_left_click,
+            idle_prefetch: newSettings.idle_prefetch,
+            move_to_trash: newSettings.move_to_trash,
+            update_channel: newSettings.update_channel,
+            defer_downloads_on_battery: newSettings.defer_downloads_on_battery,
+            enable_portrait_variant: newSettings.enable_portrait_variant,
+            low_disk_space_threshold_mb:
+              newSettings.low_disk_space_threshold_mb,
+            virtual_desktop_mode: newSettings.virtual_desktop_mode,
+            index_backup_interval_days: newSettings.index_backup_interval_days,
+            custom_ca_path: newSettings.custom_ca_path,
+            custom_ca_only: newSettings.custom_ca_only,
+            quit_behavior: newSettings.quit_behavior,
+            extension_events: newSettings.extension_events,
+            tray_double_click: newSettings.tray_double_click,
+            solar_schedule: newSettings.solar_schedule,
+            solar_latitude: newSettings.solar_latitude,
+            solar_longitude: newSettings.solar_longitude,
+            solar_day_wallpaper: newSettings.solar_day_wallpaper,
+            solar_night_wallpaper: newSettings.solar_night_wallpaper,
+            jpeg_quality: newSettings.jpeg_quality,
+            spotlight_schedule: newSettings.spotlight_schedule,
+            osd_duration_secs: newSettings.osd_duration_secs,
+            osd_position: newSettings.osd_position,
+            http_keep_alive_secs: newSettings.http_keep_alive_secs,
+            placeholder_wallpaper: newSettings.placeholder_wallpaper,
+            bing_only_network: newSettings.bing_only_network,
+            lock_screen_wallpaper: newSettings.lock_screen_wallpaper,
+            greeter_background: newSettings.greeter_background,
+            sddm_theme_dir: newSettings.sddm_theme_dir,
+            apply_hook: newSettings.apply_hook,
+          },
         },
-      });
+      );
+      setAutostartStatus(
+        autostart && autostart.status !== "ok" ? autostart : null,
+      );
       // 从后端重新获取设置（含 resolved_language 等后端计算字段），确保前端状态完全一致
       const refreshed = await invoke<AppSettings>("get_settings");
       setSettings(refreshed);
+      setFieldErrors({});
     } catch (err) {
       console.error("updateSettings error:", err);
-      setError(err as string);
+      // 字段校验失败时记录每个字段的错误码，供设置界面标出对应输入
+      if (isUpdateSettingsError(err) && err.kind === "validation") {
+        setFieldErrors(err.fields);
+      }
+      setError(describeSettingsError(err));
       throw err;
     } finally {
       setLoading(false);
@@ -74,10 +168,47 @@ export function useSettings() {
     fetchSettings();
   }, [fetchSettings]);
 
+  // 其他视图（如托盘、其他窗口）保存设置后，只合并变化的字段
+  useEffect(() => {
+    let mounted = true;
+    let unlisten: (() => void) | undefined;
+
+    (async () => {
+      try {
+        const unlistenFn = await listen<SettingChange[]>(
+          EVENTS.SETTINGS_CHANGED,
+          (event) => {
+            if (mounted) {
+              setSettings((prev) =>
+                prev ? applySettingChanges(prev, event.payload) : prev,
+              );
+            }
+          },
+        );
+        const safeUnlisten = createSafeUnlisten(unlistenFn);
+
+        if (mounted) {
+          unlisten = safeUnlisten;
+        } else {
+          safeUnlisten();
+        }
+      } catch (e) {
+        console.error("Failed to bind settings-changed event:", e);
+      }
+    })();
+
+    return () => {
+      mounted = false;
+      unlisten?.();
+    };
+  }, []);
+
   return {
     settings,
     loading,
     error,
+    fieldErrors,
+    autostartStatus,
     fetchSettings,
     updateSettings,
     getDefaultDirectory,
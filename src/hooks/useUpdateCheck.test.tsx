@@ -4,7 +4,8 @@ import { renderHook, waitFor, act } from "@testing-library/react";
 import { getVersion } from "@tauri-apps/api/app";
 import { listen } from "@tauri-apps/api/event";
 import { invoke } from "@tauri-apps/api/core";
-import { check, Update } from "@tauri-apps/plugin-updater";
+import { Update } from "@tauri-apps/plugin-updater";
+import { checkForUpdates } from "../utils/updater";
 import { useUpdateCheck } from "./useUpdateCheck";
 import { showSystemNotification } from "../utils/notification";
 import { I18nProvider } from "../i18n/I18nContext";
@@ -14,9 +15,11 @@ vi.mock("@tauri-apps/api/core");
 vi.mock("@tauri-apps/api/event");
 vi.mock("../utils/notification");
 vi.mock("@tauri-apps/plugin-updater", () => ({
-  check: vi.fn(),
   Update: vi.fn(),
 }));
+vi.mock("../utils/updater", () => ({
+  checkForUpdates: vi.fn(),
+}));
 
 function wrapper({ children }: { children: ReactNode }) {
   return <I18nProvider>{children}</I18nProvider>;
@@ -52,7 +55,7 @@ describe("useUpdateCheck", () => {
     });
 
     vi.mocked(invoke).mockResolvedValue(undefined);
-    vi.mocked(check).mockResolvedValue(null);
+    vi.mocked(checkForUpdates).mockResolvedValue(null);
     vi.mocked(getVersion).mockResolvedValue("1.4.8");
     vi.mocked(showSystemNotification).mockResolvedValue(undefined);
     Object.defineProperty(window, "fetch", {
@@ -79,7 +82,7 @@ describe("useUpdateCheck", () => {
 
   it("should set updateInfo when tray check finds an update", async () => {
     const mockUpdate = createMockUpdate("2.0.0");
-    vi.mocked(check).mockResolvedValue(mockUpdate);
+    vi.mocked(checkForUpdates).mockResolvedValue(mockUpdate);
     vi.mocked(invoke).mockResolvedValue(false);
 
     const { result } = renderHook(() => useUpdateCheck(), { wrapper });
@@ -100,7 +103,7 @@ describe("useUpdateCheck", () => {
   });
 
   it("should show notification when tray check finds no update", async () => {
-    vi.mocked(check).mockResolvedValue(null);
+    vi.mocked(checkForUpdates).mockResolvedValue(null);
 
     renderHook(() => useUpdateCheck(), { wrapper });
 
@@ -119,7 +122,7 @@ describe("useUpdateCheck", () => {
   });
 
   it("should show notification when tray check errors", async () => {
-    vi.mocked(check).mockRejectedValue(new Error("Network error"));
+    vi.mocked(checkForUpdates).mockRejectedValue(new Error("Network error"));
 
     renderHook(() => useUpdateCheck(), { wrapper });
 
@@ -137,7 +140,7 @@ describe("useUpdateCheck", () => {
   });
 
   it("should show no-update notification when plugin check fails but latest.json matches current version", async () => {
-    vi.mocked(check).mockRejectedValue(new Error("TLS error"));
+    vi.mocked(checkForUpdates).mockRejectedValue(new Error("TLS error"));
     vi.mocked(window.fetch).mockResolvedValue({
       ok: true,
       json: vi.fn().mockResolvedValue({ version: "1.4.8" }),
@@ -162,9 +165,31 @@ describe("useUpdateCheck", () => {
     expect(invoke).not.toHaveBeenCalledWith("show_main_window");
   });
 
+  it("should skip latest.json fallback when network is restricted", async () => {
+    vi.mocked(checkForUpdates).mockRejectedValue("NETWORK_RESTRICTED");
+
+    renderHook(() => useUpdateCheck(), { wrapper });
+
+    await waitFor(() => {
+      expect(eventCallbacks.has("tray-check-updates")).toBe(true);
+    });
+
+    await act(async () => {
+      await eventCallbacks.get("tray-check-updates")!({
+        payload: null,
+      });
+    });
+
+    expect(window.fetch).not.toHaveBeenCalled();
+    expect(showSystemNotification).toHaveBeenCalledWith(
+      "Check for Updates",
+      "Update checks are disabled while only Bing access is allowed",
+    );
+  });
+
   it("should not set updateInfo when tray check finds ignored version", async () => {
     const mockUpdate = createMockUpdate("2.0.0");
-    vi.mocked(check).mockResolvedValue(mockUpdate);
+    vi.mocked(checkForUpdates).mockResolvedValue(mockUpdate);
     vi.mocked(invoke).mockResolvedValue(true); // is_version_ignored = true
 
     const { result } = renderHook(() => useUpdateCheck(), { wrapper });
@@ -186,7 +211,7 @@ describe("useUpdateCheck", () => {
 
   it("should allow clearing updateInfo via setUpdateInfo(null)", async () => {
     const mockUpdate = createMockUpdate("2.0.0");
-    vi.mocked(check).mockResolvedValue(mockUpdate);
+    vi.mocked(checkForUpdates).mockResolvedValue(mockUpdate);
     vi.mocked(invoke).mockResolvedValue(false);
 
     const { result } = renderHook(() => useUpdateCheck(), { wrapper });
@@ -213,7 +238,7 @@ describe("useUpdateCheck", () => {
   describe("auto-check on startup", () => {
     it("should auto-check after 60s and set updateInfo if update available", async () => {
       const mockUpdate = createMockUpdate("3.0.0");
-      vi.mocked(check).mockResolvedValue(mockUpdate);
+      vi.mocked(checkForUpdates).mockResolvedValue(mockUpdate);
       vi.mocked(invoke).mockResolvedValue(false); // not ignored
 
       vi.useFakeTimers();
@@ -233,7 +258,7 @@ describe("useUpdateCheck", () => {
 
     it("should not set updateInfo if version is ignored", async () => {
       const mockUpdate = createMockUpdate("3.0.0");
-      vi.mocked(check).mockResolvedValue(mockUpdate);
+      vi.mocked(checkForUpdates).mockResolvedValue(mockUpdate);
       vi.mocked(invoke).mockResolvedValue(true); // is_version_ignored = true
 
       vi.useFakeTimers();
@@ -254,7 +279,7 @@ describe("useUpdateCheck", () => {
     });
 
     it("should not set updateInfo if no update available", async () => {
-      vi.mocked(check).mockResolvedValue(null);
+      vi.mocked(checkForUpdates).mockResolvedValue(null);
 
       vi.useFakeTimers();
       const { result } = renderHook(() => useUpdateCheck(), { wrapper });
@@ -275,7 +300,7 @@ describe("useUpdateCheck", () => {
         .spyOn(console, "error")
         .mockImplementation(() => {});
 
-      vi.mocked(check).mockRejectedValue(new Error("Network error"));
+      vi.mocked(checkForUpdates).mockRejectedValue(new Error("Network error"));
 
       vi.useFakeTimers();
       const { result } = renderHook(() => useUpdateCheck(), { wrapper });
@@ -304,7 +329,7 @@ describe("useUpdateCheck", () => {
         .mockImplementation(() => {});
 
       // check() returns a promise that never resolves (simulating DNS hang)
-      vi.mocked(check).mockReturnValue(new Promise(() => {}));
+      vi.mocked(checkForUpdates).mockReturnValue(new Promise(() => {}));
 
       renderHook(() => useUpdateCheck(), { wrapper });
 
@@ -334,7 +359,7 @@ describe("useUpdateCheck", () => {
     });
 
     it("should not time out when check() resolves quickly", async () => {
-      vi.mocked(check).mockResolvedValue(null);
+      vi.mocked(checkForUpdates).mockResolvedValue(null);
 
       renderHook(() => useUpdateCheck(), { wrapper });
 
@@ -2,9 +2,11 @@ import { useState, useEffect, useRef } from "react";
 import { getVersion } from "@tauri-apps/api/app";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
-import { check, Update } from "@tauri-apps/plugin-updater";
+import { Update } from "@tauri-apps/plugin-updater";
+import { EVENTS } from "../config/ui";
 import { createSafeUnlisten } from "../utils/eventListener";
 import { showSystemNotification } from "../utils/notification";
+import { checkForUpdates } from "../utils/updater";
 import { useI18n } from "../i18n/I18nContext";
 
 interface UpdateInfo {
@@ -98,7 +100,7 @@ export function useUpdateCheck() {
         const currentT = tRef.current;
         try {
           const update = await withTimeout(
-            check({ timeout: 10000 }),
+            checkForUpdates({ timeout: 10000 }),
             CHECK_TIMEOUT_MS,
           );
 
@@ -135,6 +137,16 @@ export function useUpdateCheck() {
           };
         } catch (err) {
           console.error("Failed to check for updates:", err);
+          // 隐私模式下后端拒绝访问 GitHub，前端也不能再直接请求 latest.json
+          if (err === "NETWORK_RESTRICTED") {
+            if (options.showNoUpdate) {
+              showSystemNotification(
+                currentT("checkForUpdates"),
+                currentT("updateCheckRestricted"),
+              );
+            }
+            return null;
+          }
           if (options.showNoUpdate) {
             const fallbackResult = await checkLatestJsonFallback();
             showSystemNotification(
@@ -166,7 +178,7 @@ export function useUpdateCheck() {
 
     (async () => {
       try {
-        const unlistenFn = await listen("tray-check-updates", async () => {
+        const unlistenFn = await listen(EVENTS.TRAY_CHECK_UPDATES, async () => {
           const info = await performCheckRef.current({
             showNoUpdate: true,
           });
@@ -19,6 +19,42 @@ function buildSettings(
     language,
     resolved_language,
     mkt: "zh-CN",
+    archive_backfill_enabled: false,
+    download_resolution: "auto",
+    local_folder: null,
+    local_folder_order: "mtime",
+    local_folder_schedule: "off",
+    wallpaper_fade: false,
+    tray_left_click: "toggle_window",
+    idle_prefetch: false,
+    move_to_trash: true,
+    update_channel: "stable",
+    defer_downloads_on_battery: false,
+    enable_portrait_variant: true,
+    low_disk_space_threshold_mb: 500,
+    virtual_desktop_mode: "all",
+    index_backup_interval_days: 7,
+    custom_ca_path: null,
+    custom_ca_only: false,
+    quit_behavior: "ask",
+    extension_events: false,
+    tray_double_click: "none",
+    solar_schedule: "off",
+    solar_latitude: null,
+    solar_longitude: null,
+    solar_day_wallpaper: null,
+    solar_night_wallpaper: null,
+    jpeg_quality: 0,
+    spotlight_schedule: "off",
+    osd_duration_secs: 0,
+    osd_position: 'top_right',
+    http_keep_alive_secs: 90,
+    placeholder_wallpaper: false,
+    bing_only_network: false,
+    lock_screen_wallpaper: false,
+    greeter_background: "off",
+    sddm_theme_dir: null,
+    apply_hook: null,
   };
 }
 
@@ -144,6 +180,42 @@ describe("I18nContext", () => {
           language: "fr-FR", // invalid
           resolved_language: "zh-CN",
           mkt: "zh-CN",
+          archive_backfill_enabled: false,
+          download_resolution: "auto",
+          local_folder: null,
+          local_folder_order: "mtime",
+          local_folder_schedule: "off",
+          wallpaper_fade: false,
+          tray_left_click: "toggle_window",
+          idle_prefetch: false,
+          move_to_trash: true,
+          update_channel: "stable",
+          defer_downloads_on_battery: false,
+          enable_portrait_variant: true,
+          low_disk_space_threshold_mb: 500,
+          virtual_desktop_mode: "all",
+          index_backup_interval_days: 7,
+          custom_ca_path: null,
+          custom_ca_only: false,
+          quit_behavior: "ask",
+          extension_events: false,
+          tray_double_click: "none",
+          solar_schedule: "off",
+          solar_latitude: null,
+          solar_longitude: null,
+          solar_day_wallpaper: null,
+          solar_night_wallpaper: null,
+          jpeg_quality: 0,
+          spotlight_schedule: "off",
+          osd_duration_secs: 0,
+          osd_position: 'top_right',
+          http_keep_alive_secs: 90,
+          placeholder_wallpaper: false,
+          bing_only_network: false,
+          lock_screen_wallpaper: false,
+          greeter_background: "off",
+          sddm_theme_dir: null,
+          apply_hook: null,
         });
       }
       return Promise.resolve(undefined);
@@ -185,6 +257,42 @@ describe("I18nContext", () => {
           language: "en-US",
           resolved_language: "fr-FR", // invalid
           mkt: "zh-CN",
+          archive_backfill_enabled: false,
+          download_resolution: "auto",
+          local_folder: null,
+          local_folder_order: "mtime",
+          local_folder_schedule: "off",
+          wallpaper_fade: false,
+          tray_left_click: "toggle_window",
+          idle_prefetch: false,
+          move_to_trash: true,
+          update_channel: "stable",
+          defer_downloads_on_battery: false,
+          enable_portrait_variant: true,
+          low_disk_space_threshold_mb: 500,
+          virtual_desktop_mode: "all",
+          index_backup_interval_days: 7,
+          custom_ca_path: null,
+          custom_ca_only: false,
+          quit_behavior: "ask",
+          extension_events: false,
+          tray_double_click: "none",
+          solar_schedule: "off",
+          solar_latitude: null,
+          solar_longitude: null,
+          solar_day_wallpaper: null,
+          solar_night_wallpaper: null,
+          jpeg_quality: 0,
+          spotlight_schedule: "off",
+          osd_duration_secs: 0,
+          osd_position: 'top_right',
+          http_keep_alive_secs: 90,
+          placeholder_wallpaper: false,
+          bing_only_network: false,
+          lock_screen_wallpaper: false,
+          greeter_background: "off",
+          sddm_theme_dir: null,
+          apply_hook: null,
         });
       }
       return Promise.resolve(undefined);
@@ -35,6 +35,8 @@ export const translations = {
     openAction: "打开",
     selectFolder: "更改",
     restoreDefault: "恢复默认目录",
+    archiveReadOnly:
+      "该目录为只读存档，当前处于浏览模式：可以浏览和设置壁纸，但不会下载新壁纸或清理旧壁纸",
     selectDirectory: "选择壁纸保存目录",
 
     // 设置相关
@@ -44,6 +46,8 @@ export const translations = {
     settingsGroupAppearance: "外观",
     settingsGroupStorage: "存储与数据",
     launchAtStartup: "开机自启动",
+    autostartFailed: "系统未允许修改开机自启动（{reason}），其他设置已保存",
+    autostartUnsupported: "当前构建不支持开机自启动，请使用正式版",
     autoUpdate: "自动应用新壁纸",
     autoUpdateHint:
       "开启时：自动获取新壁纸，并在检测到更新的壁纸时自动应用该壁纸\n关闭时：只有手动点击设置壁纸才会设置，但是仍然会自动获取新壁纸",
@@ -52,18 +56,215 @@ export const translations = {
     themeSystem: "跟随系统",
     themeLight: "浅色",
     themeDark: "深色",
+    themeWallpaper: "跟随壁纸",
     language: "语言",
     languageAuto: "自动",
     languageZhCN: "中文",
     languageEnUS: "English",
     market: "壁纸市场",
     marketHint: "决定获取哪个地区的壁纸，与界面语言独立",
+    marketCustom: "自定义…",
+    marketCustomPlaceholder: "例如 en-SG",
+    marketCustomInvalid: "格式应为 语言-地区，例如 en-SG",
     marketRegionAsiaPacific: "亚太",
     marketRegionEurope: "欧洲",
     marketRegionAmericas: "美洲",
     marketRegionAfrica: "非洲",
     marketMismatchWarning:
       "注意：Bing 实际返回了 {actualMkt} 的壁纸，与您选择的 {requestedMkt} 不同。这通常是因为您所在地区的 Bing 不支持该市场。",
+    marketDegradedWarning:
+      "{mkt} 市场近期多次获取壁纸失败，已减少自动重试；恢复后将自动回到正常重试策略",
+    marketSuggestion: "根据您所在的地区，建议使用 {mkt} 市场的壁纸",
+    marketSuggestionAccept: "切换",
+    archiveBackfill: "历史壁纸归档",
+    archiveBackfillHint:
+      "允许从第三方归档镜像补全 Bing 8 天之前的历史壁纸（数据来自第三方服务）",
+    downloadResolution: "下载分辨率",
+    downloadResolutionAuto: "自动（按屏幕）",
+    downloadResolutionHint:
+      "自动模式下，最大屏幕不超过 1080p 时下载 1920x1080，否则下载 UHD",
+    localFolder: "自定义图片文件夹",
+    localFolderSelect: "选择自定义图片文件夹",
+    localFolderSchedule: "使用方式",
+    localFolderScheduleOff: "不使用",
+    localFolderScheduleAlternate: "与 Bing 壁纸隔天交替",
+    localFolderScheduleMix: "与 Bing 壁纸混合轮换",
+    localFolderOrder: "轮换顺序",
+    localFolderOrderMtime: "按修改时间（最新优先）",
+    localFolderOrderName: "按文件名",
+    localFolderClear: "移除文件夹",
+    customCa: "自定义根证书",
+    customCaChoose: "选择证书",
+    customCaSelect: "选择根证书文件（PEM）",
+    customCaOnly: "仅信任此证书",
+    customCaClear: "移除证书",
+    customCaHint:
+      "公司网络使用 SSL 检查代理导致无法连接 Bing 时，选择代理的根证书。默认同时信任系统证书库",
+    httpKeepAlive: "保持空闲连接",
+    httpKeepAliveOff: "不保持",
+    httpKeepAlive30s: "30 秒",
+    httpKeepAlive90s: "90 秒",
+    httpKeepAlive5m: "5 分钟",
+    resetNetwork: "重置网络连接",
+    resetNetworkDone: "已重置",
+    resetNetworkFailed: "重置失败",
+    httpKeepAliveHint:
+      "切换 VPN 等网络变化后会自动重建连接；如果仍然无法连接，可以手动重置或缩短保持时间",
+    bingOnlyNetwork: "仅访问 Bing",
+    bingOnlyNetworkHint:
+      "禁止检查更新、IP 定位和历史归档等请求，只连接 Bing 以及自行配置的备份目标",
+    localFolderHint:
+      "自动应用壁纸时，按所选方式从该文件夹中轮换 JPG / PNG 图片（仅读取，不会复制或修改）",
+    spotlight: "Windows 聚焦图片",
+    spotlightImport: "立即导入",
+    spotlightImported: "新导入 {count} 张图片",
+    spotlightImportFailed: "导入失败",
+    spotlightHint:
+      "仅 Windows。收集锁屏聚焦缓存中的横屏图片（按内容去重，复制到壁纸目录的 spotlight 文件夹），按所选方式与 Bing 壁纸轮换",
+    idlePrefetch: "空闲时预取图片",
+    idlePrefetchHint:
+      "电脑空闲且未使用按流量计费的网络时，在后台下载只有信息尚无图片的壁纸，以便离线浏览",
+    deferDownloadsOnBattery: "使用电池时推迟下载",
+    deferDownloadsOnBatteryHint:
+      "使用电池供电时只获取壁纸信息，图片在接通电源后再下载，以节省电量",
+    enablePortraitVariant: "竖屏壁纸",
+    enablePortraitVariantHint:
+      "为竖屏显示器下载并设置竖屏版本的壁纸；关闭后竖屏显示器也使用横屏壁纸",
+    lowDiskSpaceThreshold: "磁盘空间不足时暂停下载",
+    lowDiskSpaceThresholdHint:
+      "壁纸目录所在磁盘的可用空间低于该值时跳过后台下载，空间恢复后在下次更新时继续",
+    lowDiskSpaceThresholdOff: "不检查",
+    indexBackupInterval: "自动备份索引",
+    indexBackupIntervalOff: "关闭",
+    indexBackupIntervalDaily: "每天",
+    indexBackupIntervalWeekly: "每周",
+    indexBackupIntervalMonthly: "每月",
+    indexBackupIntervalHint:
+      "定期把 index.json 备份到壁纸目录的 backups 文件夹（保留最近 8 份），索引损坏时可从备份恢复",
+    lowDiskSpace: "磁盘空间不足",
+    lowDiskSpaceMessage:
+      "壁纸目录 {directory} 所在磁盘仅剩 {available} MB 可用空间（低于 {threshold} MB），已暂停下载壁纸",
+    profiles: "配置方案",
+    profilesHint:
+      "把当前的保存目录、市场和下载分辨率保存为方案（如工作/家里），之后可在此处或托盘菜单中一键切换",
+    profileName: "方案名称",
+    profileSave: "保存当前设置",
+    profileSwitch: "切换",
+    profileDelete: "删除",
+    profileActive: "使用中",
+    profileInvalidName: "方案名称不能为空，且不超过 32 个字符",
+    profileSwitchBusy: "壁纸正在更新，请稍后再切换方案",
+    settingsManagedByPolicy: "部分设置由管理员统一配置，无法修改",
+    directoryPermissionError: "壁纸目录无法写入",
+    directoryPermissionErrorMessage:
+      "没有权限写入壁纸目录 {directory}，新壁纸暂时无法下载。",
+    directoryPermissionFallback: "是否改为使用默认目录 {directory}？",
+    directoryPermissionFallbackFailed: "切换到默认目录失败",
+    moveToTrash: "删除图片时移到回收站",
+    moveToTrashHint:
+      "重置应用等操作删除壁纸图片时，先移到系统回收站/废纸篓，误删后仍可恢复；关闭后将永久删除",
+    backup: "远程备份",
+    backupHint:
+      "把壁纸图片和 index.json 上传到 WebDAV 或 S3 兼容存储，只上传新增或有变化的文件；密码保存在系统钥匙串中",
+    backupTargetNone: "不备份",
+    backupTargetWebDav: "WebDAV",
+    backupTargetS3: "S3 兼容存储",
+    backupUrl: "WebDAV 地址",
+    backupUsername: "用户名",
+    backupPassword: "密码",
+    backupEndpoint: "服务地址",
+    backupBucket: "存储桶",
+    backupRegion: "区域",
+    backupAccessKeyId: "Access Key ID",
+    backupSecretKey: "Secret Key",
+    backupPrefix: "路径前缀",
+    backupPathStyle: "使用 Path-Style 地址",
+    backupSecretPlaceholder: "留空则保留已保存的凭据",
+    backupAuto: "每次更新后自动备份",
+    backupSave: "保存",
+    backupSaved: "备份设置已保存",
+    backupSaveError: "保存备份设置失败",
+    backupNow: "立即备份",
+    backupInProgress: "备份中...",
+    backupResult: "已上传 {uploaded} 个文件，跳过 {skipped} 个未变化的文件",
+    backupResultFailed: "{count} 个文件上传失败：{files}",
+    backupBusy: "备份正在进行，请稍后再试",
+    backupNotConfigured: "请先选择并保存备份目标",
+    backupError: "备份失败",
+    updateChannel: "更新通道",
+    updateChannelStable: "正式版",
+    updateChannelBeta: "测试版",
+    updateChannelHint: "测试版会提前收到预发布版本，可能不够稳定",
+    wallpaperFade: "切换壁纸时淡入淡出",
+    wallpaperFadeHint: "更换桌面壁纸时播放短暂的渐变过渡（仅 macOS）",
+    lockScreenWallpaper: "同步锁屏壁纸",
+    lockScreenWallpaperHint: "同时设为 KDE 锁屏界面的背景（仅 Linux）",
+    greeterBackground: "登录界面背景",
+    greeterBackgroundOff: "不修改",
+    greeterBackgroundHint:
+      "同时设为 SDDM 登录界面的背景（仅 Linux，不支持 GDM）。需要管理员授予 /var/lib/bing-wallpaper-now 和主题 theme.conf.user 的写入权限",
+    sddmThemeDir: "SDDM 主题目录",
+    sddmThemeDirAuto: "自动检测",
+    wallpaperOsd: "显示壁纸信息",
+    wallpaperOsdDuration: "显示 {seconds} 秒",
+    wallpaperOsdPosition: "显示位置",
+    osdTopLeft: "左上角",
+    osdTopRight: "右上角",
+    osdBottomLeft: "左下角",
+    osdBottomRight: "右下角",
+    wallpaperOsdHint: "自动更换壁纸后在屏幕角落短暂显示壁纸的标题和地点",
+    placeholderWallpaper: "离线时使用占位壁纸",
+    placeholderWallpaperHint:
+      "首次启动无法连接 Bing 时，把生成的占位图设为桌面壁纸。联网获取到壁纸后自动替换",
+    virtualDesktopMode: "虚拟桌面",
+    virtualDesktopModeAll: "所有虚拟桌面",
+    virtualDesktopModeCurrent: "仅当前虚拟桌面",
+    virtualDesktopModeHint:
+      "选择“所有虚拟桌面”时，切换到其他虚拟桌面后会自动应用相同的壁纸（仅 Windows 11）",
+    trayLeftClick: "单击托盘图标",
+    trayLeftClickToggleWindow: "显示/隐藏窗口",
+    trayLeftClickShowMenu: "打开菜单",
+    trayLeftClickNextWallpaper: "切换到下一张壁纸",
+    trayDoubleClick: "双击托盘图标",
+    trayDoubleClickNone: "无操作",
+    trayDoubleClickOpenWindow: "打开窗口",
+    trayDoubleClickRefresh: "立即更新",
+    trayDoubleClickNextWallpaper: "切换到下一张壁纸",
+    trayDoubleClickHint:
+      "仅 Windows。设置双击操作后，单击操作会稍作等待以区分单击和双击",
+    jpegRecompress: "压缩下载的壁纸",
+    jpegQuality: "压缩质量 {quality}%",
+    jpegRecompressHint:
+      "下载后按所选质量重新压缩横屏图片以节省磁盘空间，只在文件变小时替换原图",
+    solarSchedule: "日出日落",
+    solarScheduleOff: "关闭",
+    solarScheduleSunrise: "日出时应用新壁纸",
+    solarScheduleDayNight: "白天/夜晚切换壁纸",
+    solarScheduleHint: "按所在位置的日出日落时间安排壁纸",
+    solarLatitude: "纬度",
+    solarLongitude: "经度",
+    solarLocationHint: "经纬度留空时按 IP 自动定位",
+    solarDayWallpaper: "白天壁纸",
+    solarNightWallpaper: "夜晚壁纸",
+    solarWallpaperHint:
+      "填写本地壁纸的日期（YYYYMMDD），切换期间不再自动应用最新壁纸",
+    quitBehavior: "退出时",
+    quitBehaviorAsk: "询问",
+    quitBehaviorQuit: "直接退出",
+    quitBehaviorHide: "只隐藏到托盘",
+    quitBehaviorHint:
+      "从托盘或 Cmd+Q 退出后将不再自动更新壁纸；隐藏到托盘可让应用继续在后台运行",
+    extensionEvents: "向第三方工具发布事件",
+    extensionEventsHint:
+      "壁纸变化时通过本地 socket / 命名管道发布 JSON 事件，供 conky、Rainmeter、OBS 脚本等工具订阅",
+    extensionEventsEndpoint: "端点：{endpoint}",
+    applyHook: "壁纸变化后运行程序",
+    applyHookSelect: "选择程序",
+    applyHookClear: "移除程序",
+    applyHookConfirm:
+      "每次更换壁纸时都会运行 {path}，并传入图片路径和标题。请只选择你信任的程序。确定要继续吗？",
+    applyHookHint:
+      "可用于同步终端配色、更新直播叠加层等；程序超过 30 秒未退出会被终止",
     saveDirectory: "保存目录",
     dataActions: "数据管理",
     dataStatsSummary: "{count} 张壁纸 · {range}",
@@ -73,6 +274,13 @@ export const translations = {
     dataStatsError: "无法读取壁纸数据",
     settingsLoading: "加载设置中...",
     settingsSaveError: "保存设置失败",
+    settingsFieldNotAbsolute: "请使用完整的绝对路径",
+    settingsFieldNotDirectory: "该路径不是可用的文件夹",
+    settingsFieldNotFile: "该路径不是文件",
+    settingsFieldInvalidCertificate: "文件中没有有效的 PEM 证书",
+    settingsFieldInvalidMkt: "无效的市场代码",
+    settingsFieldInvalidValue: "无效的选项",
+    settingsFieldInvalidEndDate: "无效的日期，格式为 YYYYMMDD",
     settingsFolderSelectError: "选择文件夹失败",
     testWallpaperNotification: "预览",
     testingWallpaperNotification: "预览中...",
@@ -90,6 +298,8 @@ export const translations = {
     importImagesFailed: "{count} 张图片复制失败",
     warningSeparator: "，",
     transferNotDirectory: "所选路径不是有效目录",
+    transferBusy: "导入或导出正在进行，请稍后再试",
+    transferReadOnly: "壁纸目录为只读存档，无法导入",
     importNoData: "所选目录中没有可导入的数据",
     importAlreadyUpToDate: "所有数据已是最新，无需导入",
     importError: "导入失败",
@@ -143,6 +353,7 @@ export const translations = {
     noUpdateAvailable: "已是最新版本",
     updateCheckError: "检查更新失败",
     updateCheckFailed: "无法检查更新，请稍后重试",
+    updateCheckRestricted: "已开启仅访问 Bing，不检查更新",
 
     // 托盘菜单
     showWindow: "显示窗口",
@@ -184,6 +395,8 @@ export const translations = {
     openAction: "Open",
     selectFolder: "Change",
     restoreDefault: "Restore Default",
+    archiveReadOnly:
+      "This folder is a read-only archive. Browse mode is on: you can browse and set wallpapers, but new wallpapers are not downloaded and old ones are not cleaned up",
     selectDirectory: "Select Wallpaper Save Directory",
 
     // 设置相关
@@ -193,6 +406,10 @@ export const translations = {
     settingsGroupAppearance: "Appearance",
     settingsGroupStorage: "Storage & Data",
     launchAtStartup: "Launch at Startup",
+    autostartFailed:
+      "The system did not allow changing launch at startup ({reason}); other settings were saved",
+    autostartUnsupported:
+      "Launch at startup is not available in this build; use a release build",
     autoUpdate: "Auto Apply New Wallpaper",
     autoUpdateHint:
       "When enabled: Automatically fetch new wallpapers and apply them when detected\nWhen disabled: Only set wallpaper when manually clicked, but still automatically fetch new wallpapers",
@@ -201,6 +418,7 @@ export const translations = {
     themeSystem: "System",
     themeLight: "Light",
     themeDark: "Dark",
+    themeWallpaper: "Wallpaper",
     language: "Language",
     languageAuto: "Auto",
     languageZhCN: "中文",
@@ -208,12 +426,215 @@ export const translations = {
     market: "Wallpaper Market",
     marketHint:
       "Determines which region's wallpapers to fetch, independent of UI language",
+    marketCustom: "Custom…",
+    marketCustomPlaceholder: "e.g. en-SG",
+    marketCustomInvalid: "Use the language-REGION format, e.g. en-SG",
     marketRegionAsiaPacific: "Asia Pacific",
     marketRegionEurope: "Europe",
     marketRegionAmericas: "Americas",
     marketRegionAfrica: "Africa",
     marketMismatchWarning:
       "Note: Bing returned wallpapers for {actualMkt} instead of your selected {requestedMkt}. This usually happens when Bing in your region does not support the selected market.",
+    marketDegradedWarning:
+      "Fetching wallpapers for the {mkt} market has failed repeatedly, so automatic retries are reduced until it recovers",
+    marketSuggestion:
+      "Based on your region, the {mkt} market is suggested for wallpapers",
+    marketSuggestionAccept: "Switch",
+    archiveBackfill: "Historical Archive",
+    archiveBackfillHint:
+      "Allow backfilling wallpapers older than Bing's 8-day window from a third-party archive mirror",
+    downloadResolution: "Download Resolution",
+    downloadResolutionAuto: "Auto (match screen)",
+    downloadResolutionHint:
+      "In auto mode, 1920x1080 is downloaded when the largest screen is 1080p or smaller, otherwise UHD",
+    localFolder: "Custom Image Folder",
+    localFolderSelect: "Select Custom Image Folder",
+    localFolderSchedule: "Usage",
+    localFolderScheduleOff: "Off",
+    localFolderScheduleAlternate: "Alternate daily with Bing",
+    localFolderScheduleMix: "Mix into the Bing rotation",
+    localFolderOrder: "Rotation Order",
+    localFolderOrderMtime: "By modified time (newest first)",
+    localFolderOrderName: "By file name",
+    localFolderClear: "Remove Folder",
+    customCa: "Custom Root Certificate",
+    customCaChoose: "Choose Certificate",
+    customCaSelect: "Select Root Certificate File (PEM)",
+    customCaOnly: "Trust Only This Certificate",
+    customCaClear: "Remove Certificate",
+    customCaHint:
+      "If an SSL-inspecting corporate proxy blocks connections to Bing, choose the proxy's root certificate. The system certificate store stays trusted by default",
+    httpKeepAlive: "Keep Idle Connections",
+    httpKeepAliveOff: "Don't keep",
+    httpKeepAlive30s: "30 seconds",
+    httpKeepAlive90s: "90 seconds",
+    httpKeepAlive5m: "5 minutes",
+    resetNetwork: "Reset Network",
+    resetNetworkDone: "Reset done",
+    resetNetworkFailed: "Reset failed",
+    httpKeepAliveHint:
+      "Connections are rebuilt automatically after network changes such as VPN toggles. If requests still fail, reset manually or shorten the keep time",
+    bingOnlyNetwork: "Only Connect to Bing",
+    bingOnlyNetworkHint:
+      "Block update checks, IP geolocation and the historical archive. Only Bing and your own backup destinations are contacted",
+    localFolderHint:
+      "When wallpapers are applied automatically, JPG / PNG images from this folder are rotated in as selected (read-only, never copied or modified)",
+    spotlight: "Windows Spotlight Images",
+    spotlightImport: "Import Now",
+    spotlightImported: "Imported {count} new images",
+    spotlightImportFailed: "Import failed",
+    spotlightHint:
+      "Windows only. Landscape images from the lock screen Spotlight cache are collected (deduplicated by content and copied to the spotlight folder in the wallpaper directory) and rotated with Bing wallpapers as selected",
+    idlePrefetch: "Prefetch Images When Idle",
+    idlePrefetchHint:
+      "While the computer is idle and not on a metered network, download images for wallpapers that only have metadata so they can be browsed offline",
+    deferDownloadsOnBattery: "Defer Downloads on Battery",
+    deferDownloadsOnBatteryHint:
+      "While running on battery, only fetch wallpaper info and download images once plugged in to save power",
+    enablePortraitVariant: "Portrait Wallpapers",
+    enablePortraitVariantHint:
+      "Download and set the portrait version of wallpapers on rotated displays; when off, portrait displays use the landscape wallpaper",
+    lowDiskSpaceThreshold: "Pause Downloads When Disk Is Low",
+    lowDiskSpaceThresholdHint:
+      "Skip background downloads while free space on the wallpaper folder's disk is below this value; downloads resume at the next update once space is freed",
+    lowDiskSpaceThresholdOff: "Off",
+    indexBackupInterval: "Back Up Index",
+    indexBackupIntervalOff: "Off",
+    indexBackupIntervalDaily: "Daily",
+    indexBackupIntervalWeekly: "Weekly",
+    indexBackupIntervalMonthly: "Monthly",
+    indexBackupIntervalHint:
+      "Periodically copy index.json into the backups folder of the wallpaper directory (keeps the latest 8) so a corrupted index can be restored",
+    lowDiskSpace: "Low Disk Space",
+    lowDiskSpaceMessage:
+      "Only {available} MB is free on the disk holding {directory} (below {threshold} MB); wallpaper downloads are paused",
+    profiles: "Profiles",
+    profilesHint:
+      "Save the current folder, market and download resolution as a profile (e.g. work/home) and switch between them here or from the tray menu",
+    profileName: "Profile name",
+    profileSave: "Save Current",
+    profileSwitch: "Switch",
+    profileDelete: "Delete",
+    profileActive: "active",
+    profileInvalidName: "Profile names must be 1-32 characters",
+    profileSwitchBusy: "Wallpapers are updating; try switching again shortly",
+    settingsManagedByPolicy:
+      "Some settings are managed by your administrator and cannot be changed",
+    directoryPermissionError: "Wallpaper Folder Not Writable",
+    directoryPermissionErrorMessage:
+      "No permission to write to {directory}. New wallpapers cannot be downloaded.",
+    directoryPermissionFallback: "Use the default folder {directory} instead?",
+    directoryPermissionFallbackFailed: "Failed to switch to the default folder",
+    moveToTrash: "Move Deleted Images to Trash",
+    moveToTrashHint:
+      "When wallpaper images are deleted (e.g. by resetting the app), move them to the system Trash / Recycle Bin so they can be restored; turn off to delete permanently",
+    backup: "Remote Backup",
+    backupHint:
+      "Upload wallpaper images and index.json to WebDAV or S3-compatible storage; only new or changed files are uploaded. Passwords are kept in the system keychain",
+    backupTargetNone: "Off",
+    backupTargetWebDav: "WebDAV",
+    backupTargetS3: "S3-compatible",
+    backupUrl: "WebDAV URL",
+    backupUsername: "Username",
+    backupPassword: "Password",
+    backupEndpoint: "Endpoint",
+    backupBucket: "Bucket",
+    backupRegion: "Region",
+    backupAccessKeyId: "Access Key ID",
+    backupSecretKey: "Secret Key",
+    backupPrefix: "Key Prefix",
+    backupPathStyle: "Use Path-Style URLs",
+    backupSecretPlaceholder: "Leave empty to keep the saved credential",
+    backupAuto: "Back Up After Each Update",
+    backupSave: "Save",
+    backupSaved: "Backup settings saved",
+    backupSaveError: "Failed to save backup settings",
+    backupNow: "Back Up Now",
+    backupInProgress: "Backing up...",
+    backupResult: "Uploaded {uploaded} files, skipped {skipped} unchanged files",
+    backupResultFailed: "{count} files failed to upload: {files}",
+    backupBusy: "A backup is already running, please try again shortly",
+    backupNotConfigured: "Choose and save a backup target first",
+    backupError: "Backup failed",
+    updateChannel: "Update Channel",
+    updateChannelStable: "Stable",
+    updateChannelBeta: "Beta",
+    updateChannelHint:
+      "The beta channel receives pre-release versions early and may be less stable",
+    wallpaperFade: "Fade Between Wallpapers",
+    wallpaperFadeHint:
+      "Play a short cross-fade when the desktop wallpaper changes (macOS only)",
+    lockScreenWallpaper: "Sync Lock Screen",
+    lockScreenWallpaperHint:
+      "Also use the wallpaper as the KDE lock screen background (Linux only)",
+    greeterBackground: "Login Screen Background",
+    greeterBackgroundOff: "Don't Change",
+    greeterBackgroundHint:
+      "Also use the wallpaper as the SDDM login screen background (Linux only; GDM is not supported). An administrator must grant write access to /var/lib/bing-wallpaper-now and the theme's theme.conf.user",
+    sddmThemeDir: "SDDM Theme Folder",
+    sddmThemeDirAuto: "Detect automatically",
+    wallpaperOsd: "Show Wallpaper Info",
+    wallpaperOsdDuration: "Show for {seconds}s",
+    wallpaperOsdPosition: "Position",
+    osdTopLeft: "Top left",
+    osdTopRight: "Top right",
+    osdBottomLeft: "Bottom left",
+    osdBottomRight: "Bottom right",
+    wallpaperOsdHint:
+      "Briefly show the title and location in a screen corner after the wallpaper changes automatically",
+    placeholderWallpaper: "Placeholder When Offline",
+    placeholderWallpaperHint:
+      "If Bing can't be reached on first launch, set a generated placeholder as the desktop wallpaper. It's replaced automatically once wallpapers arrive",
+    virtualDesktopMode: "Virtual Desktops",
+    virtualDesktopModeAll: "All virtual desktops",
+    virtualDesktopModeCurrent: "Current virtual desktop only",
+    virtualDesktopModeHint:
+      "With all virtual desktops, switching to another desktop applies the same wallpaper automatically (Windows 11 only)",
+    trayLeftClick: "Tray Icon Click",
+    trayLeftClickToggleWindow: "Show/hide window",
+    trayLeftClickShowMenu: "Open menu",
+    trayLeftClickNextWallpaper: "Next wallpaper",
+    trayDoubleClick: "Tray Icon Double-Click",
+    trayDoubleClickNone: "Do nothing",
+    trayDoubleClickOpenWindow: "Open window",
+    trayDoubleClickRefresh: "Update now",
+    trayDoubleClickNextWallpaper: "Next wallpaper",
+    trayDoubleClickHint:
+      "Windows only. With a double-click action, single clicks wait briefly so the two can be told apart",
+    jpegRecompress: "Compress Downloads",
+    jpegQuality: "Quality {quality}%",
+    jpegRecompressHint:
+      "Re-encode downloaded landscape images at the chosen quality to save disk space. The original is replaced only when the file gets smaller",
+    solarSchedule: "Sunrise & Sunset",
+    solarScheduleOff: "Off",
+    solarScheduleSunrise: "Apply new wallpaper at sunrise",
+    solarScheduleDayNight: "Switch day/night wallpaper",
+    solarScheduleHint:
+      "Schedule wallpapers by the sunrise and sunset times at your location",
+    solarLatitude: "Latitude",
+    solarLongitude: "Longitude",
+    solarLocationHint: "Leave empty to locate by IP address",
+    solarDayWallpaper: "Day wallpaper",
+    solarNightWallpaper: "Night wallpaper",
+    solarWallpaperHint:
+      "Date of a local wallpaper (YYYYMMDD). The latest wallpaper is not applied automatically while switching",
+    quitBehavior: "When Quitting",
+    quitBehaviorAsk: "Ask",
+    quitBehaviorQuit: "Quit",
+    quitBehaviorHide: "Hide to tray only",
+    quitBehaviorHint:
+      "Quitting from the tray or with Cmd+Q stops automatic wallpaper updates; hiding to the tray keeps the app running in the background",
+    extensionEvents: "Publish Events to Other Tools",
+    extensionEventsHint:
+      "Publish JSON events over a local socket / named pipe when the wallpaper changes, so tools like conky, Rainmeter or OBS scripts can react",
+    extensionEventsEndpoint: "Endpoint: {endpoint}",
+    applyHook: "Run Program on Change",
+    applyHookSelect: "Choose Program",
+    applyHookClear: "Remove Program",
+    applyHookConfirm:
+      "{path} will run every time the wallpaper changes, with the image path and title as arguments. Only choose programs you trust. Continue?",
+    applyHookHint:
+      "Use it to sync terminal colors, update stream overlays and more; programs still running after 30 seconds are stopped",
     saveDirectory: "Save Directory",
     dataActions: "Data Management",
     dataStatsSummary: "{count} wallpapers · {range}",
@@ -223,6 +644,14 @@ export const translations = {
     dataStatsError: "Failed to read wallpaper data",
     settingsLoading: "Loading settings...",
     settingsSaveError: "Failed to save settings",
+    settingsFieldNotAbsolute: "Use a full absolute path",
+    settingsFieldNotDirectory: "This path is not an available folder",
+    settingsFieldNotFile: "This path is not a file",
+    settingsFieldInvalidCertificate:
+      "No valid PEM certificate found in this file",
+    settingsFieldInvalidMkt: "Invalid market code",
+    settingsFieldInvalidValue: "Invalid option",
+    settingsFieldInvalidEndDate: "Invalid date, expected YYYYMMDD",
     settingsFolderSelectError: "Failed to select folder",
     testWallpaperNotification: "Preview",
     testingWallpaperNotification: "Previewing...",
@@ -241,6 +670,8 @@ export const translations = {
     importImagesFailed: "{count} images failed to copy",
     warningSeparator: ", ",
     transferNotDirectory: "The selected path is not a valid directory",
+    transferBusy: "An import or export is already running, please try again shortly",
+    transferReadOnly: "The wallpaper folder is a read-only archive, nothing can be imported",
     importNoData: "No importable data found in the selected directory",
     importAlreadyUpToDate: "All data is already up to date, nothing to import",
     importError: "Import failed",
@@ -298,6 +729,8 @@ export const translations = {
     noUpdateAvailable: "Already up to date",
     updateCheckError: "Update Check Failed",
     updateCheckFailed: "Unable to check for updates, please try again later",
+    updateCheckRestricted:
+      "Update checks are disabled while only Bing access is allowed",
 
     // 托盘菜单
     showWindow: "Show Window",
@@ -120,6 +120,7 @@ vi.mock("@tauri-apps/plugin-opener", () => ({
 vi.mock("@tauri-apps/plugin-dialog", () => ({
   open: vi.fn(),
   message: vi.fn(),
+  ask: vi.fn(),
 }));
 
 // Extend expect matchers
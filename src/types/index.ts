@@ -7,6 +7,28 @@ export interface LocalWallpaperRaw {
   l: string; // copyright_link
   d: string; // end_date
   u?: string; // urlbase (可选)
+  r?: string; // resolution (可选，实际下载的分辨率)
+  p?: boolean; // portrait_available (可选，当日是否提供竖屏版本)
+  w?: boolean; // watermark_free (可选，磁盘上的图片是否为无水印版本)
+  z?: RecompressionRaw; // recompression (可选，下载后重新压缩的记录)
+}
+
+/**
+ * 横屏图片重新压缩的记录（后端格式：使用短字段名）
+ */
+export interface RecompressionRaw {
+  q: number; // quality
+  o: number; // original_size
+  s: number; // stored_size
+}
+
+/**
+ * 横屏图片重新压缩的记录
+ */
+export interface Recompression {
+  quality: number;
+  original_size: number; // 原图大小（字节）
+  stored_size: number; // 压缩后实际存储的大小（字节）
 }
 
 /**
@@ -18,6 +40,20 @@ export interface LocalWallpaper {
   copyright_link: string;
   end_date: string;
   urlbase?: string;
+  resolution?: string;
+  portrait_available?: boolean;
+  watermark_free?: boolean;
+  recompression?: Recompression;
+}
+
+/**
+ * 分页获取的本地壁纸（get_local_wallpapers_page 返回，按日期降序）
+ */
+export interface LocalWallpaperPageRaw {
+  wallpapers: LocalWallpaperRaw[];
+  total: number; // 该 mkt 下的壁纸总数
+  offset: number;
+  mkt: string; // 实际读取的 mkt
 }
 
 /**
@@ -32,6 +68,14 @@ export interface WallpaperDataStats {
 /**
  * 将后端返回的短字段名格式转换为前端使用的完整字段名格式
  */
+/**
+ * 单张壁纸元数据已重新获取（wallpaper-metadata-refreshed 事件载荷）
+ */
+export interface WallpaperMetadataRefreshed {
+  mkt: string; // 元数据所属的 mkt
+  wallpaper: LocalWallpaperRaw;
+}
+
 export function normalizeWallpaper(raw: LocalWallpaperRaw): LocalWallpaper {
   return {
     title: raw.t,
@@ -39,6 +83,14 @@ export function normalizeWallpaper(raw: LocalWallpaperRaw): LocalWallpaper {
     copyright_link: raw.l,
     end_date: raw.d,
     urlbase: raw.u,
+    resolution: raw.r,
+    portrait_available: raw.p,
+    watermark_free: raw.w,
+    recompression: raw.z && {
+      quality: raw.z.q,
+      original_size: raw.z.o,
+      stored_size: raw.z.s,
+    },
   };
 }
 
@@ -114,8 +166,318 @@ export interface MarketStatus {
   effective_mkt: string;
   /** 是否存在 mismatch */
   is_mismatch: boolean;
+  /** 请求的 mkt 近期持续请求失败（后端已减少重试次数） */
+  is_degraded: boolean;
+}
+
+/**
+ * 市场可用性探测结果（切换 mkt 时提前检测是否被 Bing 重定向）
+ */
+export interface MarketProbeResult {
+  /** 探测的 mkt */
+  requested_mkt: string;
+  /** Bing 实际返回的 mkt（null 表示无法检测） */
+  served_mkt: string | null;
+  /** 发生重定向时实际响应的主机 */
+  served_host: string | null;
+  /** 所选市场是否真正可用 */
+  is_available: boolean;
+}
+
+/**
+ * 首次启动的市场建议（需用户确认后才应用）
+ */
+export interface MktSuggestion {
+  /** 建议的 mkt */
+  mkt: string;
+  /** 推算出的国家/地区代码 */
+  region: string;
+  source: "geo" | "locale";
+  status: "pending" | "accepted" | "dismissed";
+}
+
+/**
+ * 单张壁纸详情（详情面板一次性获取）
+ */
+export interface WallpaperDetails {
+  end_date: string;
+  title: string;
+  copyright: string;
+  /** 壁纸所在的 mkt */
+  mkt: string;
+  /** 本地文件路径 */
+  file_path: string;
+  /** 文件大小（字节），未下载时为 null */
+  file_size: number | null;
+  /** 重新压缩前的原图大小（字节），未重新压缩时为 null */
+  original_file_size: number | null;
+  /** 从 JPEG 文件头解析的宽度 */
+  width: number | null;
+  /** 从 JPEG 文件头解析的高度 */
+  height: number | null;
+  /** 下载分辨率档位（如 "UHD"） */
+  resolution: string | null;
+  /** 下载时间（RFC 3339） */
+  downloaded_at: string | null;
+  /** 图片来源地址 */
+  source_url: string | null;
+  /** 是否已有竖屏版本 */
+  has_portrait: boolean;
+  /** 用户添加的标签（按名称排序） */
+  tags: string[];
+  /** 从版权文本解析出的地点和署名 */
+  copyright_info: CopyrightInfo;
+}
+
+/**
+ * 从 Bing 版权文本（`地点 (© 摄影师/机构)`）解析出的结构化信息
+ */
+export interface CopyrightInfo {
+  location: string;
+  photographer: string | null;
+  /** 图片机构（如 Getty Images） */
+  agency: string | null;
+}
+
+/**
+ * 标签及其使用次数（get_all_tags）
+ */
+export interface TagCount {
+  tag: string;
+  count: number;
+}
+
+/**
+ * 某一日期壁纸的本地状态（get_wallpaper_status）
+ */
+export interface WallpaperStatus {
+  /** 是否有任一 mkt 的索引记录 */
+  in_index: boolean;
+  /** 横屏图片文件是否存在 */
+  file_exists: boolean;
+  /** 竖屏图片文件是否存在 */
+  portrait_exists: boolean;
+  /** 横屏文件大小（字节），文件不存在时为 null */
+  file_size: number | null;
+  /** 索引中包含该日期的 mkt（按名称排序） */
+  mkt_list: string[];
+}
+
+/**
+ * 备份目标（凭据单独保存在系统钥匙串中）
+ */
+export type BackupTarget =
+  | { kind: "none" }
+  | { kind: "web_dav"; url: string; username: string }
+  | {
+      kind: "s3";
+      endpoint: string;
+      bucket: string;
+      region: string;
+      access_key_id: string;
+      prefix: string;
+      path_style: boolean;
+    };
+
+/**
+ * 备份配置
+ */
+export interface BackupConfig {
+  /** 每次更新后自动备份 */
+  enabled: boolean;
+  target: BackupTarget;
+}
+
+/**
+ * 一次备份的结果统计
+ */
+export interface BackupReport {
+  uploaded: number;
+  skipped: number;
+  failed: string[];
+}
+
+/**
+ * 非正常退出后的状态修复报告（诊断用）
+ */
+export interface RecoveryReport {
+  detected_at: string;
+  previous_session_started_at: string | null;
+  removed_temp_files: string[];
+  removed_empty_files: string[];
+  missing_wallpapers: string[];
+  cleared_flags: string[];
+}
+
+/**
+ * 导入预览（preview_import 返回，不写入任何文件）
+ */
+export interface ImportPreview {
+  metadata_new: number;
+  metadata_updated: number;
+  metadata_conflicting: number;
+  metadata_unchanged: number;
+  metadata_skipped: number;
+  images_to_copy: number;
+  images_skipped: number;
+  mkt_count: number;
+}
+
+/**
+ * 返回时间戳的命令可选的输出格式
+ * - display: 按界面语言格式化的展示文本（默认）
+ * - rfc3339: 可解析的原始时间
+ */
+export type TimestampFormat = "display" | "rfc3339";
+
+/**
+ * 当前已应用的壁纸（重启后仍可获取）
+ */
+export interface CurrentWallpaper {
+  mkt: string;
+  end_date: string;
+  file_path: string;
+}
+
+/**
+ * 某个显示器上实际显示的壁纸（get_applied_wallpapers 返回）
+ */
+export interface AppliedWallpaper {
+  screen_index: number;
+  file_path: string | null; // 系统报告的壁纸路径，无法获取时为 null
+  end_date: string | null; // 存档壁纸键，不是壁纸目录中的图片时为 null
+  matches_current: boolean; // 是否与本应用最近设置的壁纸一致
 }
 
+/**
+ * 正在进行的图片下载（get_active_downloads 返回，
+ * 也是 download-started / download-finished 事件的负载）
+ */
+export interface ActiveDownload {
+  end_date: string;
+  portrait: boolean;
+  url: string;
+  downloaded_bytes: number;
+  /** 服务器未返回大小时为 null */
+  total_bytes: number | null;
+  /** 0 ~ 1，总大小未知时为 null */
+  progress: number | null;
+  /** 当前尝试次数（从 1 开始） */
+  attempt: number;
+  max_attempts: number;
+}
+
+/**
+ * download-failed 事件负载
+ */
+export interface DownloadFailure extends ActiveDownload {
+  error: string;
+  /** 是否还会自动重试 */
+  will_retry: boolean;
+}
+
+/**
+ * 图片下载流量（包含失败的尝试）
+ */
+export interface BandwidthUsage {
+  bytes: number;
+  /** 下载尝试次数 */
+  downloads: number;
+  duration_ms: number;
+}
+
+/**
+ * get_bandwidth_stats 的时间范围（均包含今天）
+ */
+export type BandwidthRange = "today" | "week" | "month" | "year" | "all";
+
+/**
+ * 下载流量统计（get_bandwidth_stats 返回）
+ */
+export interface BandwidthStats {
+  total: BandwidthUsage;
+  /** 按日期升序，只包含有下载的日期 */
+  days: Array<BandwidthUsage & { date: string }>;
+}
+
+/**
+ * 更新循环阶段
+ */
+export type UpdatePhase =
+  | "idle"
+  | "checking_cache"
+  | "fetching"
+  | "saving_metadata"
+  | "downloading"
+  | "applying"
+  | "done"
+  | "failed"
+  | "cancelled"
+  | "resetting";
+
+/**
+ * 更新循环状态（get_update_state 返回，也是 update-state-changed 事件的负载）
+ */
+export interface UpdateState {
+  phase: UpdatePhase;
+  /** 是否为强制更新 */
+  force: boolean;
+  started_at: string | null;
+  /** 失败时的错误码 */
+  error: string | null;
+  failed_phase: UpdatePhase | null;
+  /** 本轮经过的阶段 */
+  transitions: Array<{ phase: UpdatePhase; at: string }>;
+}
+
+/**
+ * 壁纸历史统计（get_archive_statistics 返回）
+ */
+export interface ArchiveStatistics {
+  /** 统计的年份，null 表示全部 */
+  year: number | null;
+  total: number;
+  /** 出现最多的地点（版权文本中地点的最后一段） */
+  top_locations: { location: string; count: number }[];
+  /** 作品最多的摄影师 */
+  top_photographers: { photographer: string; count: number }[];
+  /** 按月份升序，month 为 YYYY-MM */
+  monthly_counts: { month: string; count: number }[];
+  /** 已下载图片的平均色（#rrggbb），按日期升序 */
+  average_colors: { end_date: string; color: string }[];
+}
+
+/**
+ * update_settings 字段校验错误码
+ */
+export type SettingsFieldError =
+  | "NOT_ABSOLUTE"
+  | "NOT_DIRECTORY"
+  | "NOT_FILE"
+  | "INVALID_CERTIFICATE"
+  | "INVALID_MKT"
+  | "INVALID_VALUE"
+  | "INVALID_END_DATE";
+
+export type SettingsFieldErrors = Partial<
+  Record<keyof AppSettings, SettingsFieldError>
+>;
+
+/**
+ * update_settings 返回的错误
+ */
+export type UpdateSettingsError =
+  | { kind: "validation"; fields: SettingsFieldErrors }
+  | { kind: "failed"; message: string };
+
+/**
+ * update_settings 返回的自启动应用结果（失败时其余设置仍已保存）
+ */
+export type AutostartStatus =
+  | { status: "ok" }
+  | { status: "unsupported"; reason: string }
+  | { status: "failed"; reason: string };
+
 /**
  * 应用设置
  */
@@ -124,8 +486,131 @@ export interface AppSettings {
   new_wallpaper_notification: boolean;
   save_directory: string | null;
   launch_at_startup: boolean;
-  theme: string; // "light" | "dark" | "system" - 必需字段，与 Rust 端保持一致
+  theme: string; // "light" | "dark" | "system" | "wallpaper" - 必需字段，与 Rust 端保持一致
   language: string; // "auto" | "zh-CN" | "en-US" - 用户的语言偏好（可以是 "auto"）
   resolved_language: string; // "zh-CN" | "en-US" - 后端解析后的实际语言，前端 i18n 应使用此字段
   mkt: string; // Bing API 市场代码（如 "zh-CN", "en-US", "ja-JP"），与 UI 语言独立
+  archive_backfill_enabled: boolean; // 是否允许通过第三方归档回填历史壁纸
+  download_resolution: string; // 下载分辨率: "auto" | "UHD" | "1920x1080"
+  local_folder: string | null; // 自定义图片文件夹
+  local_folder_order: string; // 自定义图片轮换顺序: "mtime" | "name"
+  local_folder_schedule: string; // 与 Bing 壁纸的排期: "off" | "alternate" | "mix"
+  wallpaper_fade: boolean; // 切换壁纸时淡入淡出（仅 macOS）
+  tray_left_click: string; // 左键单击托盘图标: "toggle_window" | "show_menu" | "next_wallpaper"
+  idle_prefetch: boolean; // 系统空闲时预取只有元数据的壁纸图片
+  move_to_trash: boolean; // 删除壁纸图片时移到回收站而非永久删除
+  update_channel: string; // 更新通道: "stable" | "beta"
+  defer_downloads_on_battery: boolean; // 电池供电时推迟后台图片下载
+  enable_portrait_variant: boolean; // 竖屏显示器使用竖屏壁纸
+  low_disk_space_threshold_mb: number; // 可用空间低于该值（MB）时跳过后台下载，0 表示不检查
+  virtual_desktop_mode: string; // Windows 11 虚拟桌面: "all" | "current"
+  index_backup_interval_days: number; // 每隔多少天备份 index.json 到 backups/，0 表示不备份
+  custom_ca_path: string | null; // 额外信任的根证书（PEM 文件），用于 SSL 检查代理
+  custom_ca_only: boolean; // 只信任 custom_ca_path 中的证书，不使用系统证书库
+  quit_behavior: string; // 从托盘或 Cmd+Q 退出时: "ask" | "quit" | "hide"
+  extension_events: boolean; // 在本地 IPC 端点上向第三方工具发布壁纸变化事件
+  tray_double_click: string; // 双击托盘图标（仅 Windows）: "none" | "open_window" | "refresh" | "next_wallpaper"
+  solar_schedule: string; // 按日出日落安排壁纸: "off" | "sunrise" | "day_night"
+  solar_latitude: number | null; // 日出日落计算使用的纬度，与经度均未设置时按 IP 定位
+  solar_longitude: number | null; // 日出日落计算使用的经度
+  solar_day_wallpaper: string | null; // day_night 模式白天使用的壁纸日期（YYYYMMDD）
+  solar_night_wallpaper: string | null; // day_night 模式夜间使用的壁纸日期（YYYYMMDD）
+  jpeg_quality: number; // 下载后重新压缩 JPEG 的质量（50-95），0 表示不压缩
+  spotlight_schedule: string; // 与 Windows 聚焦图片轮换: "off" | "alternate" | "mix"
+  osd_duration_secs: number; // 壁纸信息浮层显示秒数，0 表示关闭
+  osd_position: string; // "top_left" | "top_right" | "bottom_left" | "bottom_right"
+  http_keep_alive_secs: number; // 空闲连接保持秒数，0 表示不复用连接
+  placeholder_wallpaper: boolean; // 离线时将占位壁纸设为桌面壁纸
+  bing_only_network: boolean; // 仅允许访问 Bing
+  lock_screen_wallpaper: boolean; // Linux KDE 锁屏同步壁纸
+  greeter_background: "off" | "sddm"; // 登录界面背景
+  sddm_theme_dir: string | null; // SDDM 主题目录
+  apply_hook: string | null; // 壁纸变化后运行的程序
+}
+
+/**
+ * 单个设置项的变化（settings-changed 事件载荷的元素）
+ */
+export interface SettingChange {
+  /** 设置字段名 */
+  key: keyof AppSettings;
+  old_value: unknown;
+  new_value: unknown;
+}
+
+/**
+ * 壁纸配置方案：独立的壁纸目录、市场和下载分辨率
+ */
+export interface WallpaperProfile {
+  name: string;
+  save_directory: string | null;
+  mkt: string;
+  download_resolution: string;
+}
+
+/**
+ * 全部配置方案与当前使用的方案
+ */
+export interface ProfilesConfig {
+  profiles: WallpaperProfile[];
+  active: string | null;
+}
+
+/**
+ * 远程备份目标（凭据不在此结构中，保存在系统钥匙串）
+ */
+export type BackupTarget =
+  | { kind: "none" }
+  | { kind: "web_dav"; url: string; username: string }
+  | {
+      kind: "s3";
+      endpoint: string;
+      bucket: string;
+      region: string;
+      access_key_id: string;
+      prefix: string;
+      path_style: boolean;
+    };
+
+/**
+ * 远程备份配置
+ */
+export interface BackupConfig {
+  enabled: boolean; // 每次更新后自动备份
+  target: BackupTarget;
+}
+
+/**
+ * 一次备份的结果统计
+ */
+export interface BackupReport {
+  uploaded: number;
+  skipped: number;
+  failed: string[];
+}
+
+/**
+ * 管理员设置策略（企业部署）：被锁定的设置在界面中只读
+ */
+export interface SettingsPolicyStatus {
+  path: string | null; // 策略文件路径，没有策略时为 null
+  locked: (keyof AppSettings)[];
+}
+
+/**
+ * 壁纸目录无写入权限（directory-permission-error 事件）
+ */
+export interface DirectoryPermissionError {
+  directory: string;
+  default_directory: string | null; // 可回退的默认目录，无法回退时为 null
+  message: string;
+}
+
+/**
+ * 壁纸目录磁盘空间不足，已暂停下载（low-disk-space 事件）
+ */
+export interface LowDiskSpace {
+  directory: string;
+  available_bytes: number;
+  threshold_bytes: number;
 }
@@ -15,6 +15,8 @@ const translations: TransferTranslations = {
   notDirectory: "Not a directory",
   sameDirectory: "Same directory",
   noData: "No data",
+  busy: "Busy",
+  readOnly: "Read-only archive",
   error: "Error",
 };
 
@@ -137,6 +139,16 @@ describe("transferHelpers", () => {
       expect(msg.text).toBe("Same directory");
     });
 
+    it("should map READ_ONLY_DIRECTORY to readOnly translation", () => {
+      const msg = buildTransferErrorMessage(
+        "READ_ONLY_DIRECTORY",
+        translations,
+      );
+
+      expect(msg.type).toBe("error");
+      expect(msg.text).toBe("Read-only archive");
+    });
+
     it("should map NO_DATA to noData translation", () => {
       const msg = buildTransferErrorMessage("NO_DATA", translations);
 
@@ -144,6 +156,13 @@ describe("transferHelpers", () => {
       expect(msg.text).toBe("No data");
     });
 
+    it("should map BUSY to busy translation", () => {
+      const msg = buildTransferErrorMessage("BUSY", translations);
+
+      expect(msg.type).toBe("error");
+      expect(msg.text).toBe("Busy");
+    });
+
     it("should format unknown errors with error prefix", () => {
       const msg = buildTransferErrorMessage("Some unknown error", translations);
 
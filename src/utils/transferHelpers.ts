@@ -19,6 +19,8 @@ export type TransferTranslations = {
   notDirectory: string;
   sameDirectory: string;
   noData: string;
+  busy: string;
+  readOnly: string;
   error: string;
 };
 
@@ -71,6 +73,8 @@ const TRANSFER_ERROR_MAP: Record<string, keyof TransferTranslations> = {
   NOT_DIRECTORY: "notDirectory",
   SAME_DIRECTORY: "sameDirectory",
   NO_DATA: "noData",
+  BUSY: "busy",
+  READ_ONLY_DIRECTORY: "readOnly",
 };
 
 export function buildTransferErrorMessage(
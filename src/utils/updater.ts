@@ -0,0 +1,26 @@
+import { invoke } from "@tauri-apps/api/core";
+import { Update } from "@tauri-apps/plugin-updater";
+
+/** 与 updater 插件 `check` 返回结构一致的更新元数据 */
+interface UpdateMetadata {
+  rid: number;
+  currentVersion: string;
+  version: string;
+  date?: string;
+  body?: string;
+  rawJson: Record<string, unknown>;
+}
+
+/**
+ * 按设置中的更新通道（stable / beta）检查应用更新
+ * 发布选择由 Rust 后端完成，插件仍负责下载与签名校验。
+ * @param options.timeout 单次请求超时（毫秒）
+ */
+export async function checkForUpdates(
+  options: { timeout?: number } = {},
+): Promise<Update | null> {
+  const metadata = await invoke<UpdateMetadata | null>("check_for_updates", {
+    timeout: options.timeout,
+  });
+  return metadata ? new Update(metadata) : null;
+}